@@ -1,4 +1,4 @@
-use csml_engine::{validate_bot, CsmlResult};
+use csml_engine::{validate_bot, CsmlResult, ErrorCode};
 use csml_interpreter::data::csml_bot::CsmlBot;
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +27,7 @@ struct ValidationError {
     end_line: Option<u32>,
     end_column: Option<u32>,
     message: String,
+    code: ErrorCode,
 }
 
 pub fn handler(body: CsmlBot) -> Result<serde_json::Value, Error> {
@@ -53,6 +54,7 @@ pub fn handler(body: CsmlBot) -> Result<serde_json::Value, Error> {
                     end_line: error_info.position.interval.end_line,
                     end_column: error_info.position.interval.end_column,
                     message: error_info.message.clone(),
+                    code: error_info.code,
                 })
             }
             ValidateBotResponse {
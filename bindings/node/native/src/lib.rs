@@ -274,6 +274,9 @@ fn format_errors<'a, C: Context<'a>>(
             object.set(cx, "end_column", end_column).unwrap();
         }
         object.set(cx, "message", message).unwrap();
+        let code = serde_json::json!(err.code);
+        let code = cx.string(code.as_str().unwrap_or_default());
+        object.set(cx, "code", code).unwrap();
 
         array.set(cx, index as u32, object).unwrap();
     }
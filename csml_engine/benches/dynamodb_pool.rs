@@ -0,0 +1,138 @@
+//! Compare the throughput of dynamodb writes and batch reads with a single connection and with a
+//! pool of connections.
+//! Needs a reachable dynamodb table, for example with dynamodb-local:
+//! `AWS_REGION=local AWS_DYNAMODB_ENDPOINT=http://localhost:8000 AWS_DYNAMODB_TABLE=csml-engine-db-local
+//! ENCRYPTION_SECRET=secret cargo bench --features dynamo --bench dynamodb_pool`
+
+use csml_engine::{
+    create_client_memory,
+    data::{BotOpt, CsmlRequest},
+    delete_client, get_client_memories, get_client_messages_page, start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const RUNS: usize = 10;
+
+// memories are read by batches of 25 keys, messages by batches of 100
+const MEMORIES: usize = 100;
+
+// every run writes 200 messages, split in batches of 25 items
+const FLOW: &str = r#"
+start:
+    do index = 0
+    while (index < 200) {
+        say "message {{index}}"
+        do index = index + 1
+    }
+    goto end
+"#;
+
+fn init_bot() -> CsmlBot {
+    CsmlBot {
+        id: "dynamodb_pool_bench".to_owned(),
+        name: "dynamodb_pool_bench".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow {
+            id: "Default".to_owned(),
+            name: "Default".to_owned(),
+            commands: vec![],
+            content: FLOW.to_owned(),
+        }],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+    }
+}
+
+fn init_request(client: &Client) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "bench".to_owned(),
+        client: client.to_owned(),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "start"},
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        conversation_id: None,
+    }
+}
+
+/// Total time spent writing the conversations, reading their messages and reading their memories
+#[derive(Default)]
+struct Timings {
+    write: Duration,
+    messages: Duration,
+    memories: Duration,
+}
+
+fn run(pool_size: Option<usize>) -> Timings {
+    match pool_size {
+        Some(pool_size) => std::env::set_var("AWS_DYNAMODB_POOL_SIZE", pool_size.to_string()),
+        None => std::env::remove_var("AWS_DYNAMODB_POOL_SIZE"),
+    }
+
+    let bot = init_bot();
+    let mut timings = Timings::default();
+
+    for _ in 0..RUNS {
+        let client = Client {
+            user_id: "bench".to_owned(),
+            bot_id: Uuid::new_v4().to_string(),
+            channel_id: Uuid::new_v4().to_string(),
+        };
+
+        let now = Instant::now();
+        start_conversation(init_request(&client), BotOpt::CsmlBot(bot.to_owned())).unwrap();
+        timings.write += now.elapsed();
+
+        let now = Instant::now();
+        get_client_messages_page(&client, 200, None).unwrap();
+        timings.messages += now.elapsed();
+
+        for index in 0..MEMORIES {
+            create_client_memory(&client, format!("memory_{}", index), json!(index)).unwrap();
+        }
+
+        let now = Instant::now();
+        get_client_memories(&client).unwrap();
+        timings.memories += now.elapsed();
+
+        delete_client(&client).unwrap();
+    }
+
+    timings
+}
+
+fn print_throughput(name: &str, operation: &str, total: Duration) {
+    println!(
+        "{:<20} {:<10} {:>8.2?} per conversation, {:>8.2} conversations/s",
+        name,
+        operation,
+        total / RUNS as u32,
+        RUNS as f64 / total.as_secs_f64()
+    );
+}
+
+fn main() {
+    std::env::set_var("ENGINE_DB_TYPE", "dynamodb");
+
+    for (name, pool_size) in [("single connection", Some(1)), ("default pool", None)].iter() {
+        let timings = run(*pool_size);
+
+        print_throughput(name, "write", timings.write);
+        print_throughput(name, "messages", timings.messages);
+        print_throughput(name, "memories", timings.memories);
+    }
+}
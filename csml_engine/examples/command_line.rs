@@ -37,6 +37,7 @@ fn init_request(string: &str) -> CsmlRequest {
         ttl_duration: None,
         step_limit: None,
         low_data_mode: None,
+        conversation_id: None,
     }
 }
 
@@ -0,0 +1,4 @@
+-- summaries of the conversations of a client: get_conversation_summaries groups the messages
+-- of the client's conversations by conversation, reading min and max created_at from the index
+CREATE INDEX conversation_client ON csml_conversations (bot_id, channel_id, user_id);
+CREATE INDEX message_conversation_created_at ON csml_messages (conversation_id, created_at);
@@ -0,0 +1,12 @@
+-- request counter of each client for its current rate limit window, incremented atomically
+-- by an upsert that resets it when a new window starts
+CREATE TABLE csml_rate_limits (
+  bot_id VARCHAR NOT NULL,
+  channel_id VARCHAR NOT NULL,
+  user_id VARCHAR NOT NULL,
+
+  window_start BIGINT NOT NULL,
+  count BIGINT NOT NULL,
+
+  PRIMARY KEY (bot_id, channel_id, user_id)
+);
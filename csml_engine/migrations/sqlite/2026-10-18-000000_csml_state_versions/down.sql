@@ -0,0 +1,2 @@
+DROP INDEX state_client_key;
+ALTER TABLE csml_states DROP COLUMN version;
@@ -0,0 +1,10 @@
+-- a state is saved once per client, type and key: the duplicates left by the former inserts
+-- are dropped, keeping the last saved one. The version of a state is checked and incremented
+-- by the conditional saves of the hold position, the states saved without condition keep 0
+DELETE FROM csml_states WHERE rowid NOT IN (
+  SELECT MAX(rowid) FROM csml_states GROUP BY bot_id, channel_id, user_id, type, key
+);
+
+ALTER TABLE csml_states ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+
+CREATE UNIQUE INDEX state_client_key ON csml_states (bot_id, channel_id, user_id, type, key);
@@ -0,0 +1,50 @@
+/**
+ * Current date of the engine, configured with `set_clock`.
+ *
+ * The dates saved by the engine (the `created_at` of the messages, the `expires_at` computed
+ * from the ttl, ...) are read from the clock, so the tests can run with a fixed date.
+ * The date of the clock at the start of a turn is given to the flows in the context.
+ * The SystemClock is used until a clock is set.
+ */
+use chrono::{DateTime, Utc};
+use crate::lock::{read_or_recover, write_or_recover};
+
+use std::sync::RwLock;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/**
+ * Clock reading the system time
+ */
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+static CLOCK: RwLock<Option<Box<dyn Clock>>> = RwLock::new(None);
+
+/**
+ * Replace the clock giving the current date
+ */
+pub fn set_clock(clock: Box<dyn Clock>) {
+    let mut current = write_or_recover(&CLOCK);
+
+    *current = Some(clock);
+}
+
+/**
+ * Current date of the clock
+ */
+pub fn now() -> DateTime<Utc> {
+    let clock = read_or_recover(&CLOCK);
+
+    match clock.as_deref() {
+        Some(clock) => clock.now(),
+        None => SystemClock.now(),
+    }
+}
@@ -0,0 +1,63 @@
+/**
+ * Ids of the conversations minted by the engine when the request does not give one.
+ *
+ * The ids are UUIDv7 (RFC 9562): the 48 first bits are the milliseconds of the clock
+ * of the engine, so the ids sort like the dates of the conversations. The ids of the
+ * same millisecond are ordered by a 12 bits counter, the ids minted by the engine
+ * are always increasing, even if the clock goes back.
+ */
+use crate::clock;
+use crate::lock::lock_or_recover;
+
+use rand::Rng;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+const MAX_COUNTER: u16 = 0xfff;
+
+struct LastId {
+    millis: u64,
+    counter: u16,
+}
+
+static LAST_ID: Mutex<LastId> = Mutex::new(LastId {
+    millis: 0,
+    counter: 0,
+});
+
+/**
+ * Timestamp and counter of the next id, after the ones of the last id
+ */
+fn next_timestamp() -> (u64, u16) {
+    let now = clock::now().timestamp_millis().max(0) as u64;
+    let mut last = lock_or_recover(&LAST_ID);
+
+    if now > last.millis {
+        // the counter starts in its lower half, to keep room for the ids of the same millisecond
+        last.millis = now;
+        last.counter = rand::thread_rng().gen_range(0..=MAX_COUNTER / 2);
+    } else if last.counter < MAX_COUNTER {
+        last.counter += 1;
+    } else {
+        // all the ids of the millisecond are used, the id is minted in the next one
+        last.millis += 1;
+        last.counter = 0;
+    }
+
+    (last.millis, last.counter)
+}
+
+pub fn new_conversation_id() -> Uuid {
+    let (millis, counter) = next_timestamp();
+    let mut bytes = [0u8; 16];
+
+    rand::thread_rng().fill(&mut bytes[8..]);
+    bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+    // version 7 and the counter
+    bytes[6] = 0x70 | (counter >> 8) as u8;
+    bytes[7] = counter as u8;
+    // variant of RFC 9562
+    bytes[8] = 0x80 | (bytes[8] & 0x3f);
+
+    Uuid::from_bytes(bytes)
+}
@@ -1,11 +1,16 @@
 use crate::{
-    db_connectors,
+    db_connectors::{self, connector::Connector},
     encrypt::{decrypt_data, encrypt_data},
     Client, Context,
 };
 use csml_interpreter::data::{CsmlBot, CsmlFlow, Message, Module, MultiBot};
+#[cfg(feature = "dynamo")]
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+#[cfg(feature = "dynamo")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
 
 pub const DEBUG: &str = "DEBUG";
 pub const DISABLE_SSL_VERIFY: &str = "DISABLE_SSL_VERIFY";
@@ -346,6 +351,9 @@ pub struct CsmlRequest {
     pub step_limit: Option<usize>,
     pub ttl_duration: Option<serde_json::Value>,
     pub low_data_mode: Option<serde_json::Value>,
+    // id of the conversation opened by the request, minted by the engine when not given
+    #[serde(default)]
+    pub conversation_id: Option<String>,
 }
 
 pub enum Database {
@@ -357,18 +365,45 @@ pub enum Database {
     Postgresql(PostgresqlClient),
     #[cfg(feature = "sqlite")]
     SqLite(SqliteClient),
+    Connector(Box<dyn Connector>),
     None,
 }
 
 #[cfg(feature = "sqlite")]
 pub struct SqliteClient {
-    pub client: diesel::prelude::SqliteConnection,
+    connection: &'static std::sync::Mutex<Option<diesel::prelude::SqliteConnection>>,
 }
 
 #[cfg(feature = "sqlite")]
 impl SqliteClient {
-    pub fn new(client: diesel::prelude::SqliteConnection) -> Self {
-        Self { client }
+    pub fn new(
+        connection: &'static std::sync::Mutex<Option<diesel::prelude::SqliteConnection>>,
+    ) -> Self {
+        Self { connection }
+    }
+
+    /**
+     * Lock the shared connection for a single query: the guard is dropped at the end
+     * of the statement using it, so the other requests are not blocked between queries.
+     */
+    pub fn client(&self) -> SqliteConnectionGuard {
+        SqliteConnectionGuard(crate::lock::lock_or_recover(self.connection))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub struct SqliteConnectionGuard(
+    std::sync::MutexGuard<'static, Option<diesel::prelude::SqliteConnection>>,
+);
+
+#[cfg(feature = "sqlite")]
+impl std::ops::Deref for SqliteConnectionGuard {
+    type Target = diesel::prelude::SqliteConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+            .as_ref()
+            .expect("SqLite connection is not initialized")
     }
 }
 
@@ -387,14 +422,31 @@ impl PostgresqlClient {
 #[cfg(feature = "mongo")]
 pub struct MongoDbClient {
     pub client: mongodb::sync::Database,
+    pub connection: mongodb::sync::Client,
 }
 
 #[cfg(feature = "mongo")]
 impl MongoDbClient {
-    pub fn new(client: mongodb::sync::Database) -> Self {
+    pub fn new(connection: mongodb::sync::Client, dbname: &str) -> Self {
+        Self {
+            client: connection.database(dbname),
+            connection,
+        }
+    }
+}
+
+#[cfg(feature = "redis")]
+pub struct RedisClient {
+    pub client: redis::Connection,
+}
+
+#[cfg(feature = "redis")]
+impl RedisClient {
+    pub fn new(client: redis::Connection) -> Self {
         Self { client }
     }
 }
+
 /**
  * Dynamodb runs in async by default and returns futures, that need to be awaited on.
  * The proper way to do it is by using tokio's runtime::block_on(). It is however quite costly
@@ -403,17 +455,250 @@ impl MongoDbClient {
 #[cfg(feature = "dynamo")]
 pub struct DynamoDbClient {
     pub client: rusoto_dynamodb::DynamoDbClient,
+    // optional read replica, only used by the reads made with ReadFrom::Replica
+    pub read_client: Option<rusoto_dynamodb::DynamoDbClient>,
     pub s3_client: rusoto_s3::S3Client,
     pub runtime: tokio::runtime::Runtime,
+    // maximum number of requests sent in parallel to dynamodb
+    pub pool_size: usize,
+    // backoff of the requests retried when the throughput is exceeded
+    pub retry_config: RetryConfig,
+    // reads of optional data (memories) return nothing instead of a connectivity error
+    pub fail_open: bool,
+    // counters of the queries, retries and throttles, DYNAMODB_METRICS by default
+    pub metrics: &'static DynamoDbMetrics,
+}
+
+/**
+ * Counters of the dynamodb queries, readable by the host to export them (to Prometheus
+ * for example). A query counts as one call however many times it is sent, each new send
+ * is a retry. A throttle is a request refused because the throughput is exceeded, the
+ * last one is not retried when the query gives up. Recording an event is a single
+ * relaxed atomic increment.
+ */
+#[cfg(feature = "dynamo")]
+#[derive(Debug, Default)]
+pub struct DynamoDbMetrics {
+    calls: AtomicU64,
+    retries: AtomicU64,
+    throttles: AtomicU64,
+}
+
+/**
+ * Counters shared by all the dynamodb clients that were not given their own
+ */
+#[cfg(feature = "dynamo")]
+pub static DYNAMODB_METRICS: DynamoDbMetrics = DynamoDbMetrics::new();
+
+#[cfg(feature = "dynamo")]
+impl DynamoDbMetrics {
+    pub const fn new() -> Self {
+        Self {
+            calls: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            throttles: AtomicU64::new(0),
+        }
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn throttles(&self) -> u64 {
+        self.throttles.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_call(&self) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_throttle(&self) {
+        self.throttles.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/**
+ * How the backoff interval of a retry is randomized: full jitter waits between 0 and
+ * the interval, equal jitter waits between half of the interval and the interval.
+ */
+#[cfg(feature = "dynamo")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Jitter {
+    Full,
+    Equal,
+}
+
+/**
+ * Backoff of the dynamodb requests retried when the throughput is exceeded. The interval
+ * grows with each retry from `base_millis` up to `max_interval_millis`, a request gives up
+ * after `max_elapsed_millis` or after `max_retries` retries when it is set.
+ */
+#[cfg(feature = "dynamo")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryConfig {
+    pub base_millis: u64,
+    pub max_interval_millis: u64,
+    pub max_elapsed_millis: u64,
+    pub max_retries: Option<u64>,
+    pub jitter: Jitter,
+}
+
+#[cfg(feature = "dynamo")]
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            // 0.5 seconds
+            base_millis: 500,
+            // 1 minute
+            max_interval_millis: 60_000,
+            // 10 minutes
+            max_elapsed_millis: 600_000,
+            max_retries: None,
+            jitter: Jitter::Full,
+        }
+    }
+}
+
+#[cfg(feature = "dynamo")]
+impl RetryConfig {
+    /**
+     * Time to wait before the retry number `retry_times` (starting at 1),
+     * None if the request has been retried `max_retries` times already.
+     */
+    pub fn get_delay(&self, retry_times: u64) -> Option<std::time::Duration> {
+        if let Some(max_retries) = self.max_retries {
+            if retry_times > max_retries {
+                return None;
+            }
+        }
+
+        let interval = std::cmp::min(
+            self.max_interval_millis,
+            self.base_millis.saturating_mul(2 * retry_times),
+        );
+        let mut rng = rand::thread_rng();
+
+        let delay = match (self.jitter, interval) {
+            (_, 0) => 0,
+            (Jitter::Full, _) => rng.gen_range(0..interval),
+            (Jitter::Equal, _) => interval / 2 + rng.gen_range(0..=interval / 2),
+        };
+
+        Some(std::time::Duration::from_millis(delay))
+    }
+
+    /**
+     * The request must give up if it was first sent more than `max_elapsed_millis` ago
+     */
+    pub fn is_elapsed(&self, start: std::time::Instant) -> bool {
+        start.elapsed() >= std::time::Duration::from_millis(self.max_elapsed_millis)
+    }
+}
+
+/**
+ * Where a dynamodb read is sent. Reads that must see the writes of the current turn
+ * use the primary, the others can go to the read replica when one is configured.
+ */
+#[cfg(feature = "dynamo")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReadFrom {
+    Primary,
+    Replica,
 }
 
 #[cfg(feature = "dynamo")]
 impl DynamoDbClient {
     pub fn new(dynamo_region: rusoto_core::Region, s3_region: rusoto_core::Region) -> Self {
+        let pool_size = match std::env::var("AWS_DYNAMODB_POOL_SIZE") {
+            Ok(val) => val.parse::<usize>().ok().filter(|size| *size > 0),
+            Err(_) => None,
+        };
+
+        let pool_size = match pool_size {
+            Some(pool_size) => pool_size,
+            None => std::thread::available_parallelism()
+                .map(|size| size.get())
+                .unwrap_or(1),
+        };
+
+        let fail_open = match std::env::var("CSML_DB_FAIL_OPEN") {
+            Ok(val) => val == "true" || val == "1",
+            Err(_) => false,
+        };
+
+        Self::with_pool_size(dynamo_region, s3_region, pool_size).with_fail_open(fail_open)
+    }
+
+    /**
+     * Each connection of the pool is served by its own worker thread,
+     * so batches of requests can be executed in parallel instead of one after the other.
+     */
+    pub fn with_pool_size(
+        dynamo_region: rusoto_core::Region,
+        s3_region: rusoto_core::Region,
+        pool_size: usize,
+    ) -> Self {
+        let pool_size = std::cmp::max(pool_size, 1);
+
         Self {
             client: rusoto_dynamodb::DynamoDbClient::new(dynamo_region),
+            read_client: None,
             s3_client: rusoto_s3::S3Client::new(s3_region),
-            runtime: tokio::runtime::Runtime::new().unwrap(),
+            runtime: tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(pool_size)
+                .enable_all()
+                .build()
+                .unwrap(),
+            pool_size,
+            retry_config: RetryConfig::default(),
+            fail_open: false,
+            metrics: &DYNAMODB_METRICS,
+        }
+    }
+
+    pub fn with_read_replica(mut self, read_region: rusoto_core::Region) -> Self {
+        self.read_client = Some(rusoto_dynamodb::DynamoDbClient::new(read_region));
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /**
+     * Writes always fail on an error, only the reads of optional data can degrade
+     * to empty when dynamodb is unreachable.
+     */
+    pub fn with_fail_open(mut self, fail_open: bool) -> Self {
+        self.fail_open = fail_open;
+        self
+    }
+
+    /**
+     * Record the queries of this client in `metrics` instead of DYNAMODB_METRICS
+     */
+    pub fn with_metrics(mut self, metrics: &'static DynamoDbMetrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /**
+     * Client serving the reads of `read_from`, the primary is used
+     * when no read replica is configured.
+     */
+    pub fn reader(&self, read_from: ReadFrom) -> &rusoto_dynamodb::DynamoDbClient {
+        match (read_from, &self.read_client) {
+            (ReadFrom::Replica, Some(read_client)) => read_client,
+            _ => &self.client,
         }
     }
 }
@@ -429,6 +714,12 @@ pub struct ConversationInfo {
     pub ttl: Option<chrono::Duration>,
     pub low_data: bool,
     pub db: Database,
+    // listener of the messages of the turn, as they are produced
+    pub stream: Option<mpsc::Sender<Value>>,
+    // memories of `remember_conversation`, they are forgotten when the conversation changes
+    pub conversation_memories: serde_json::Map<String, Value>,
+    // version of the hold state of the client, the hold is saved only if it is unchanged
+    pub hold_version: i64,
 }
 
 #[derive(Debug)]
@@ -452,6 +743,10 @@ pub enum EngineError {
     Time(std::time::SystemTimeError),
     Openssl(openssl::error::ErrorStack),
     Base64(base64::DecodeError),
+    // a versioned state was saved by another request since it was read
+    StateConflict(String),
+    // the engine is shutting down, the request was not started
+    ShuttingDown(String),
 
     #[cfg(any(feature = "mongo"))]
     BsonDecoder(bson::de::Error),
@@ -471,6 +766,9 @@ pub enum EngineError {
     SqlErrorCode(String),
     #[cfg(any(feature = "postgresql", feature = "sqlite"))]
     SqlMigrationsError(String),
+
+    #[cfg(feature = "redis")]
+    Redis(String),
 }
 
 impl From<serde_json::Error> for EngineError {
@@ -557,3 +855,10 @@ impl From<diesel_migrations::RunMigrationsError> for EngineError {
         EngineError::SqlMigrationsError(e.to_string())
     }
 }
+
+#[cfg(feature = "redis")]
+impl From<redis::RedisError> for EngineError {
+    fn from(e: redis::RedisError) -> Self {
+        EngineError::Redis(e.to_string())
+    }
+}
@@ -4,6 +4,8 @@ use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
 #[cfg(feature = "postgresql")]
 use crate::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "redis")]
+use crate::db_connectors::{is_redis, redis_connector};
 #[cfg(feature = "sqlite")]
 use crate::db_connectors::{is_sqlite, sqlite_connector};
 
@@ -364,6 +366,12 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut Database) -> Result<(), Engine
         LogLvl::Debug,
     );
 
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut redis_db = redis_connector::init()?;
+        redis_connector::memories::delete_all_bot_data(bot_id, &mut redis_db)?;
+    }
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         delete_bot_versions(bot_id, db)?;
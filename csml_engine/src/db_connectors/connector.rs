@@ -0,0 +1,584 @@
+/**
+ * Interface of the database connectors for the messages, memories, conversations and
+ * states of the clients, implemented by every built-in connector and by the connectors
+ * registered by the host. The generic functions only go through this trait, apart from
+ * the memories kept in redis.
+ *
+ * Implementors only store and read data: the message payloads, memory values and state
+ * values they receive are already encrypted, and they return them as saved. The provided
+ * methods encrypt and decrypt them with the configured `Encryptor`.
+ *
+ * A connector is used when ENGINE_DB_TYPE matches the name it was registered with
+ * in `register_connector`.
+ */
+use crate::db_connectors::{DbConversation, MessageCursor};
+use crate::encrypt::{decrypt_data, encrypt_data};
+use crate::error_messages::{ERROR_BOT_MESSAGES_EXPORT, ERROR_RATE_LIMIT_COUNTER};
+use crate::{Client, Database, EngineError, Memory};
+use crate::lock::{read_or_recover, write_or_recover};
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+
+/**
+ * Position in the conversation of the messages of an interaction
+ */
+pub struct Interaction<'a> {
+    pub conversation_id: &'a str,
+    pub flow_id: &'a str,
+    pub step_id: &'a str,
+    pub interaction_order: i32,
+    pub direction: &'a str,
+    pub ttl: Option<chrono::Duration>,
+}
+
+/**
+ * Message to save, message_order is its position in the interaction
+ */
+pub struct EncryptedMessage {
+    pub message_order: i32,
+    pub content_type: String,
+    pub payload: String,
+}
+
+pub trait Connector {
+    /**
+     * Save the messages of an interaction
+     */
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Get the client's messages from the most recent one, formatted as
+     * {"messages": [...], "pagination_key": ...} with their payloads as saved
+     */
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError>;
+
+    /**
+     * Get up to `limit` messages after the cursor (see MessageCursor), from the most
+     * recent one and with their payloads as saved
+     */
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError>;
+
+    /**
+     * Get a page of all the messages of the bot across its clients, with their payloads
+     * as saved, and the pagination key of the next page, None after the last page
+     */
+    fn query_bot_messages(
+        &mut self,
+        _bot_id: &str,
+        _limit: i64,
+        _pagination_key: Option<String>,
+    ) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+        Err(EngineError::Manager(ERROR_BOT_MESSAGES_EXPORT.to_owned()))
+    }
+
+    /**
+     * Save (key, encrypted value) memories, replacing the memories with the same key
+     */
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Get the client's memories formatted as {"key": ..., "value": ..., "created_at": ...},
+     * with their values as saved
+     */
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError>;
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError>;
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    /**
+     * Open a new conversation with the given id, given by the host or minted by the engine
+     */
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError>;
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError>;
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Get the client's conversations formatted as {"conversations": [...], "pagination_key": ...}
+     */
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError>;
+
+    /**
+     * Get one summary per conversation of the client, from the most recent last message,
+     * formatted as {"conversations": [...], "pagination_key": ...}.
+     * A summary is {"conversation_id", "started_at", "last_message_at", "message_count"},
+     * the dates being the ones of the first and the last message of the conversation.
+     * A conversation without messages starts at its creation, which also sorts it, and
+     * its last_message_at is null.
+     */
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError>;
+
+    /**
+     * Save (key, encrypted value) states of the given type
+     */
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Save a (key, encrypted value) state only if its version is still `version`, the version
+     * of a missing state being 0, and give it the version `version + 1`. The check and the
+     * write must be atomic: a state saved in the meantime fails with EngineError::StateConflict.
+     * The mongodb and postgresql connectors keep no versions and save the state without check.
+     */
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        version: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Get a state formatted as
+     * {"client": ..., "type": ..., "value": ..., "version": ..., "created_at": ...},
+     * with its value as saved. The states saved by `save_state_items` have the version 0.
+     */
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError>;
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Save the messages and the memories of an interaction, connectors with transactions
+     * save them together
+     */
+    fn save_messages_and_memories(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+        memories: Vec<(String, String)>,
+    ) -> Result<(), EngineError> {
+        if !messages.is_empty() {
+            self.save_messages(client, interaction, messages)?;
+        }
+        if !memories.is_empty() {
+            self.save_memories(client, memories, interaction.ttl)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_messages_bulk(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: &[serde_json::Value],
+    ) -> Result<(), EngineError> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        self.save_messages(client, interaction, encrypt_messages(messages)?)
+    }
+
+    fn add_messages_and_memories(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: &[serde_json::Value],
+        memories: &HashMap<String, Memory>,
+    ) -> Result<(), EngineError> {
+        if messages.is_empty() && memories.is_empty() {
+            return Ok(());
+        }
+
+        let messages = encrypt_messages(messages)?;
+        let memories = encrypt_memories(memories)?;
+
+        self.save_messages_and_memories(client, interaction, messages, memories)
+    }
+
+    fn get_client_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let mut value = self.query_messages(client, limit, pagination_key, from_date, to_date)?;
+
+        if let Some(messages) = value["messages"].as_array_mut() {
+            for message in messages.iter_mut() {
+                decrypt_field(message, "payload")?;
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn get_client_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        let mut messages = self.query_messages_page(client, limit, cursor)?;
+
+        for message in messages.iter_mut() {
+            decrypt_field(message, "payload")?;
+        }
+
+        Ok(messages)
+    }
+
+    fn get_bot_messages(
+        &mut self,
+        bot_id: &str,
+        limit: i64,
+        pagination_key: Option<String>,
+    ) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+        let (mut messages, pagination_key) =
+            self.query_bot_messages(bot_id, limit, pagination_key)?;
+
+        for message in messages.iter_mut() {
+            decrypt_field(message, "payload")?;
+        }
+
+        Ok((messages, pagination_key))
+    }
+
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        if memories.is_empty() {
+            return Ok(());
+        }
+
+        self.save_memories(client, encrypt_memories(memories)?, ttl)
+    }
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: String,
+        value: serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let encrypted = encrypt_data(&value)?;
+
+        self.save_memories(client, vec![(key, encrypted)], ttl)
+    }
+
+    /**
+     * Get the client's memories as a {key: value} map
+     */
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError> {
+        let mut map = serde_json::Map::new();
+
+        for mut memory in self.query_memories(client)? {
+            let key = match memory["key"].as_str() {
+                Some(key) if !map.contains_key(key) => key.to_owned(),
+                _ => continue,
+            };
+
+            decrypt_field(&mut memory, "value")?;
+            map.insert(key, memory["value"].take());
+        }
+
+        Ok(serde_json::Value::Object(map))
+    }
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError> {
+        let mut keys = HashSet::new();
+        let mut memories = vec![];
+
+        for mut memory in self.query_memories(client)? {
+            if !keys.insert(memory["key"].to_string()) {
+                continue;
+            }
+
+            decrypt_field(&mut memory, "value")?;
+            memories.push(memory);
+        }
+
+        Ok(serde_json::Value::Array(memories))
+    }
+
+    /**
+     * Get one of the client's memories, null if it does not exist
+     */
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError> {
+        for mut memory in self.query_memories(client)? {
+            if memory["key"].as_str() == Some(key) {
+                decrypt_field(&mut memory, "value")?;
+
+                return Ok(memory);
+            }
+        }
+
+        Ok(serde_json::Value::Null)
+    }
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let mut encrypted = vec![];
+        for (key, value) in keys_values {
+            encrypted.push((key.to_owned(), encrypt_data(value)?));
+        }
+
+        self.save_state_items(client, _type, encrypted, ttl)
+    }
+
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        let (value, _) = self.get_versioned_state_key(client, _type, key)?;
+
+        Ok(value)
+    }
+
+    /**
+     * Get a state with its version, a missing or cleared state has no value
+     */
+    fn get_versioned_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(Option<serde_json::Value>, i64), EngineError> {
+        match self.query_state_key(client, _type, key)? {
+            Some(mut state) => {
+                decrypt_field(&mut state, "value")?;
+
+                let version = state["version"].as_i64().unwrap_or(0);
+                match state["value"].take() {
+                    serde_json::Value::Null => Ok((None, version)),
+                    value => Ok((Some(value), version)),
+                }
+            }
+            None => Ok((None, 0)),
+        }
+    }
+
+    /**
+     * Save a state if its version is still `version` and return its new version.
+     * Clearing a state (None) keeps its version, unlike deleting it, so that a request
+     * holding an outdated version can not save it afterwards.
+     */
+    fn set_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: Option<&serde_json::Value>,
+        version: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        let encrypted = encrypt_data(value.unwrap_or(&serde_json::Value::Null))?;
+
+        self.save_state_item_if_version(client, _type, key, encrypted, version, ttl)?;
+
+        Ok(version + 1)
+    }
+
+    /**
+     * Count a request of the client in the rate limit window starting at `window_start`
+     * and lasting `window` seconds, and return the number of requests of the window.
+     * The increment must be atomic so that concurrent requests are all counted: without
+     * an atomic counter, the requests can only be limited with REDIS_URL set.
+     */
+    fn increment_request_count(
+        &mut self,
+        _client: &Client,
+        _window_start: i64,
+        _window: i64,
+    ) -> Result<i64, EngineError> {
+        Err(EngineError::Manager(ERROR_RATE_LIMIT_COUNTER.to_owned()))
+    }
+
+    /**
+     * Get the hold position of the client
+     */
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        match self.query_state_key(client, "hold", "position")? {
+            Some(mut state) => {
+                decrypt_field(&mut state, "value")?;
+                if state["value"].is_null() {
+                    return Ok(None);
+                }
+
+                Ok(Some(serde_json::json!({
+                    "client": state["client"],
+                    "type": state["type"],
+                    "value": state["value"],
+                    "created_at": state["created_at"],
+                })))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+type ConnectorInit = fn() -> Result<Box<dyn Connector>, EngineError>;
+
+// connectors registered with register_connector, by ENGINE_DB_TYPE
+static CONNECTORS: RwLock<Vec<(String, ConnectorInit)>> = RwLock::new(Vec::new());
+
+fn encrypt_messages(messages: &[serde_json::Value]) -> Result<Vec<EncryptedMessage>, EngineError> {
+    let mut encrypted = vec![];
+    for (i, message) in messages.iter().enumerate() {
+        encrypted.push(EncryptedMessage {
+            message_order: i as i32,
+            content_type: message["content_type"]
+                .as_str()
+                .unwrap_or("text")
+                .to_owned(),
+            payload: encrypt_data(message)?,
+        });
+    }
+
+    Ok(encrypted)
+}
+
+fn encrypt_memories(
+    memories: &HashMap<String, Memory>,
+) -> Result<Vec<(String, String)>, EngineError> {
+    let mut encrypted = vec![];
+    for memory in memories.values() {
+        encrypted.push((memory.key.to_owned(), encrypt_data(&memory.value)?));
+    }
+
+    Ok(encrypted)
+}
+
+fn decrypt_field(value: &mut serde_json::Value, field: &str) -> Result<(), EngineError> {
+    if let Some(encrypted) = value[field].as_str() {
+        value[field] = decrypt_data(encrypted.to_owned())?;
+    }
+
+    Ok(())
+}
+
+/**
+ * Use the connector created by `init` when ENGINE_DB_TYPE is set to `db_type`.
+ * The connector is created for each request, like the database clients of the other connectors.
+ */
+pub fn register_connector(db_type: &str, init: ConnectorInit) {
+    let mut connectors = write_or_recover(&CONNECTORS);
+
+    connectors.retain(|(name, _)| name != db_type);
+    connectors.push((db_type.to_owned(), init));
+}
+
+/**
+ * Create the registered connector matching ENGINE_DB_TYPE, if any
+ */
+pub fn init_registered_connector() -> Option<Result<Database, EngineError>> {
+    let db_type = std::env::var("ENGINE_DB_TYPE").ok()?;
+    let connectors = read_or_recover(&CONNECTORS);
+
+    let (_, init) = connectors.iter().find(|(name, _)| *name == db_type)?;
+
+    Some(init().map(Database::Connector))
+}
+
+impl Database {
+    /**
+     * Connector of the database, None when no database is set up
+     */
+    pub fn connector(&mut self) -> Option<&mut dyn Connector> {
+        match self {
+            Database::Connector(connector) => Some(connector.as_mut()),
+            #[cfg(feature = "mongo")]
+            Database::Mongo(db) => Some(db),
+            #[cfg(feature = "dynamo")]
+            Database::Dynamodb(db) => Some(db),
+            #[cfg(feature = "postgresql")]
+            Database::Postgresql(db) => Some(db),
+            #[cfg(feature = "sqlite")]
+            Database::SqLite(db) => Some(db),
+            Database::None => None,
+        }
+    }
+}
@@ -1,23 +1,20 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
-
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
-use crate::db_connectors::{state, utils::*};
+use crate::conversation_id::new_conversation_id;
+use crate::db_connectors::state;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, ConversationInfo, Database, DbConversation, EngineError};
 
+/**
+ * Create a conversation with the id given by the host, or with an id minted by the engine
+ * (see conversation_id). MongoDB keeps its ObjectId, already ordered by time
+ */
 pub fn create_conversation(
     flow_id: &str,
     step_id: &str,
     client: &Client,
     ttl: Option<chrono::Duration>,
+    conversation_id: Option<&str>,
     db: &mut Database,
 ) -> Result<String, EngineError> {
     csml_logger(
@@ -45,41 +42,14 @@ pub fn create_conversation(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-
-        let expires_at = get_expires_at_for_mongodb(ttl);
-        return mongodb_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_dynamodb(ttl);
-        return dynamodb_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_postgresql(ttl);
-        return postgresql_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
+    let id = match conversation_id {
+        Some(id) => id.to_owned(),
+        None => new_conversation_id().to_string(),
+    };
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_sqlite(ttl);
-        return sqlite_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
+    if let Some(connector) = db.connector() {
+        connector.create_conversation(&id, flow_id, step_id, client, ttl)?;
+        return Ok(id);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -108,28 +78,8 @@ pub fn close_conversation(id: &str, client: &Client, db: &mut Database) -> Resul
     // delete previous bot info at the end of the conversation
     state::delete_state_key(&client, "bot", "previous", db)?;
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::close_conversation(id, client, "CLOSED", db);
+    if let Some(connector) = db.connector() {
+        return connector.close_conversation(id, client, "CLOSED");
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -150,28 +100,8 @@ pub fn close_all_conversations(client: &Client, db: &mut Database) -> Result<(),
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::close_all_conversations(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.close_all_conversations(client);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -200,28 +130,8 @@ pub fn get_latest_open(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::get_latest_open(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.get_latest_open(client);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -257,50 +167,8 @@ pub fn update_conversation(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(&data.db)?;
-        return mongodb_connector::conversations::update_conversation(
-            &data.conversation_id,
-            &data.client,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(&mut data.db)?;
-        return dynamodb_connector::conversations::update_conversation(
-            &data.conversation_id,
-            &data.client,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(&mut data.db)?;
-        return postgresql_connector::conversations::update_conversation(
-            &data.conversation_id,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(&mut data.db)?;
-        return sqlite_connector::conversations::update_conversation(
-            &data.conversation_id,
-            flow_id,
-            step_id,
-            db,
-        );
+    if let Some(connector) = data.db.connector() {
+        return connector.update_conversation(&data.conversation_id, &data.client, flow_id, step_id);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -334,52 +202,34 @@ pub fn get_client_conversations(
         LogLvl::Info,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
-
-        return mongodb_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
+    if let Some(connector) = db.connector() {
+        return connector.get_client_conversations(client, limit, pagination_key);
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
-
-        return dynamodb_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
 
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
+pub fn get_conversation_summaries(
+    client: &Client,
+    db: &mut Database,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    csml_logger(
+        CsmlLog::new(
+            Some(&client),
+            None,
+            None,
+            format!(
+                "db call get conversation summaries limit: {:?}, pagination_key: {:?}",
+                limit, pagination_key
+            ),
+        ),
+        LogLvl::Info,
+    );
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
+    if let Some(connector) = db.connector() {
+        return connector.get_conversation_summaries(client, limit, pagination_key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use csml_interpreter::data::{context::ContextStepInfo, CsmlFlow, Message};
+    use csml_interpreter::data::{context::ContextStepInfo, CsmlFlow, Memory, Message};
     use std::collections::HashMap;
 
     use crate::{db_connectors::*, init_db, make_migrations, Client, Context, ConversationInfo};
@@ -16,12 +16,14 @@ mod tests {
     fn get_context() -> Context {
         Context {
             current: HashMap::new(),
+            conversation: HashMap::new(),
             metadata: HashMap::new(),
             api_info: None,
             hold: None,
             step: ContextStepInfo::Normal("start".to_owned()),
             flow: "Default".to_owned(),
             previous_bot: None,
+            now: None,
         }
     }
 
@@ -63,6 +65,9 @@ mod tests {
             ttl: None,
             low_data: false,
             db,
+            stream: None,
+            conversation_memories: serde_json::Map::new(),
+            hold_version: 0,
         }
     }
 
@@ -108,8 +113,10 @@ mod tests {
         let mut db = init_db().unwrap();
         user::delete_client(&client, &mut db).unwrap();
 
-        let c_id =
-            conversations::create_conversation("Default", "start", &client, None, &mut db).unwrap();
+        let c_id = conversations::create_conversation(
+            "Default", "start", &client, None, None, &mut db,
+        )
+        .unwrap();
 
         let msgs = vec![
             gen_message("1"),
@@ -149,6 +156,250 @@ mod tests {
         assert_eq!(0, received_msgs.len());
     }
 
+    fn get_page_texts(page: &serde_json::Value) -> Vec<&str> {
+        page["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|message| message["payload"]["content"]["text"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn ok_messages_page() {
+        make_migrations().unwrap_or({});
+
+        // other tests delete the messages of the default client while running
+        let client = Client {
+            user_id: "paginated-user".to_owned(),
+            ..get_client()
+        };
+        let mut db = init_db().unwrap();
+        user::delete_client(&client, &mut db).unwrap();
+
+        let c_id = conversations::create_conversation(
+            "Default", "start", &client, None, None, &mut db,
+        )
+        .unwrap();
+
+        let mut data = get_conversation_info(vec![], c_id, db);
+        data.client = client.clone();
+
+        let msgs = vec![gen_message("1"), gen_message("2"), gen_message("3")];
+        messages::add_messages_bulk(&mut data, msgs, 0, "RECEIVE").unwrap();
+        let msgs = vec![gen_message("4"), gen_message("5"), gen_message("6")];
+        messages::add_messages_bulk(&mut data, msgs, 1, "SEND").unwrap();
+
+        let page = messages::get_client_messages_page(&client, &mut data.db, 4, None).unwrap();
+        assert_eq!(vec!["6", "5", "4", "3"], get_page_texts(&page));
+
+        let cursor = page["next_cursor"].as_str().unwrap().to_owned();
+        let page =
+            messages::get_client_messages_page(&client, &mut data.db, 4, Some(cursor)).unwrap();
+        assert_eq!(vec!["2", "1"], get_page_texts(&page));
+        assert!(page["next_cursor"].is_null());
+
+        // a page ending on the last message has no next page
+        let page = messages::get_client_messages_page(&client, &mut data.db, 3, None).unwrap();
+        assert_eq!(vec!["6", "5", "4"], get_page_texts(&page));
+
+        let cursor = page["next_cursor"].as_str().unwrap().to_owned();
+        let page =
+            messages::get_client_messages_page(&client, &mut data.db, 3, Some(cursor)).unwrap();
+        assert_eq!(vec!["3", "2", "1"], get_page_texts(&page));
+        assert!(page["next_cursor"].is_null());
+
+        // a cursor past the end returns an empty page
+        let cursor = MessageCursor {
+            created_at: "1970-01-01T00:00:00.000Z".to_owned(),
+            interaction_order: 0,
+            message_order: 0,
+        };
+        let page = messages::get_client_messages_page(
+            &client,
+            &mut data.db,
+            4,
+            Some(cursor.encode()),
+        )
+        .unwrap();
+        assert!(get_page_texts(&page).is_empty());
+        assert!(page["next_cursor"].is_null());
+
+        assert!(
+            messages::get_client_messages_page(&client, &mut data.db, 4, Some("?".to_owned()))
+                .is_err()
+        );
+
+        user::delete_client(&client, &mut data.db).unwrap();
+    }
+
+    fn get_memories_map(memories: Vec<(&str, serde_json::Value)>) -> HashMap<String, Memory> {
+        memories
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    key.to_owned(),
+                    Memory {
+                        key: key.to_owned(),
+                        value,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ok_messages_and_memories() {
+        make_migrations().unwrap_or({});
+
+        let client = Client {
+            user_id: "interaction-user".to_owned(),
+            ..get_client()
+        };
+        let mut db = init_db().unwrap();
+        user::delete_client(&client, &mut db).unwrap();
+
+        let c_id = conversations::create_conversation(
+            "Default", "start", &client, None, None, &mut db,
+        )
+        .unwrap();
+
+        let mut data = get_conversation_info(vec![], c_id, db);
+        data.client = client.clone();
+
+        let msgs = vec![gen_message("1"), gen_message("2")];
+        let mems = get_memories_map(vec![("key", serde_json::json!("value"))]);
+        messages::add_messages_and_memories(&mut data, msgs, 0, "SEND", &mems).unwrap();
+
+        let page = messages::get_client_messages_page(&client, &mut data.db, 10, None).unwrap();
+        assert_eq!(vec!["2", "1"], get_page_texts(&page));
+
+        let response = memories::internal_use_get_memories(&client, &mut data.db).unwrap();
+        assert_eq!(response["key"], serde_json::json!("value"));
+
+        user::delete_client(&client, &mut data.db).unwrap();
+    }
+
+    #[cfg(feature = "mongo")]
+    #[test]
+    fn ok_mongodb_transaction_failure() {
+        use crate::db_connectors::connector::{EncryptedMessage, Interaction};
+        use crate::db_connectors::mongodb as mongodb_connector;
+
+        if !is_mongodb() {
+            return;
+        }
+
+        let client = Client {
+            user_id: "transaction-user".to_owned(),
+            ..get_client()
+        };
+        let mut db = init_db().unwrap();
+        user::delete_client(&client, &mut db).unwrap();
+
+        let c_id = conversations::create_conversation(
+            "Default", "start", &client, None, None, &mut db,
+        )
+        .unwrap();
+
+        let mut data = get_conversation_info(vec![], c_id, db);
+        data.client = client.clone();
+
+        let msgs: Vec<EncryptedMessage> = vec![gen_message("1"), gen_message("2")]
+            .iter()
+            .enumerate()
+            .map(|(i, message)| EncryptedMessage {
+                message_order: i as i32,
+                content_type: "text".to_owned(),
+                payload: crate::encrypt::encrypt_data(message).unwrap(),
+            })
+            .collect();
+        let mems = vec![(
+            "key".to_owned(),
+            crate::encrypt::encrypt_data(&serde_json::json!("value")).unwrap(),
+        )];
+        let interaction = Interaction {
+            conversation_id: &data.conversation_id,
+            flow_id: "Default",
+            step_id: "start",
+            interaction_order: 0,
+            direction: "SEND",
+            ttl: None,
+        };
+
+        // the memories can't be saved after the messages, nothing must be kept
+        let mongo_db = mongodb_connector::get_db(&data.db).unwrap();
+        let result = mongodb_connector::with_transaction(mongo_db, |mut session| {
+            mongodb_connector::messages::add_messages_bulk(
+                &client,
+                &interaction,
+                &msgs,
+                None,
+                session.as_deref_mut(),
+                mongo_db,
+            )?;
+            mongodb_connector::memories::add_memories(&client, &mems, None, session, mongo_db)?;
+
+            Err(crate::EngineError::Manager("simulated failure".to_owned()))
+        });
+        assert!(result.is_err());
+
+        // without a replica set the writes are done sequentially and can't be rolled back
+        if mongodb_connector::transactions_available() {
+            let page = messages::get_client_messages_page(&client, &mut data.db, 10, None).unwrap();
+            assert!(get_page_texts(&page).is_empty());
+
+            let response = memories::internal_use_get_memories(&client, &mut data.db).unwrap();
+            assert_eq!(response.as_object().unwrap().len(), 0);
+        }
+
+        user::delete_client(&client, &mut data.db).unwrap();
+    }
+
+    #[cfg(feature = "mongo")]
+    #[test]
+    fn ok_mongodb_conversation_ids() {
+        if !is_mongodb() {
+            return;
+        }
+
+        let client = Client {
+            user_id: "conversation-id-user".to_owned(),
+            ..get_client()
+        };
+        let mut db = init_db().unwrap();
+        user::delete_client(&client, &mut db).unwrap();
+
+        // the id of the host is not an ObjectId, it is kept as is
+        let host_id = conversations::create_conversation(
+            "Default",
+            "start",
+            &client,
+            None,
+            Some("host-conversation"),
+            &mut db,
+        )
+        .unwrap();
+        assert_eq!(host_id, "host-conversation");
+
+        let open = conversations::get_latest_open(&client, &mut db).unwrap().unwrap();
+        assert_eq!(open.id, host_id);
+
+        conversations::close_conversation(&host_id, &client, &mut db).unwrap();
+        assert!(conversations::get_latest_open(&client, &mut db).unwrap().is_none());
+
+        // the id minted by the engine is recorded
+        let minted_id =
+            conversations::create_conversation("Default", "start", &client, None, None, &mut db)
+                .unwrap();
+        assert_eq!(uuid::Uuid::parse_str(&minted_id).unwrap().get_version_num(), 7);
+
+        let open = conversations::get_latest_open(&client, &mut db).unwrap().unwrap();
+        assert_eq!(open.id, minted_id);
+
+        user::delete_client(&client, &mut db).unwrap();
+    }
+
     #[test]
     fn ok_conversation() {
         make_migrations().unwrap_or({});
@@ -158,9 +409,12 @@ mod tests {
 
         user::delete_client(&client, &mut db).unwrap();
 
-        conversations::create_conversation("Default", "start", &client, None, &mut db).unwrap();
-        conversations::create_conversation("Default", "start", &client, None, &mut db).unwrap();
-        conversations::create_conversation("Default", "start", &client, None, &mut db).unwrap();
+        conversations::create_conversation("Default", "start", &client, None, None, &mut db)
+            .unwrap();
+        conversations::create_conversation("Default", "start", &client, None, None, &mut db)
+            .unwrap();
+        conversations::create_conversation("Default", "start", &client, None, None, &mut db)
+            .unwrap();
 
         let response =
             conversations::get_client_conversations(&client, &mut db, Some(6), None).unwrap();
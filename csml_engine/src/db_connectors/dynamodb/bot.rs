@@ -1,4 +1,4 @@
-use crate::data::{DynamoBot, DynamoBotBincode, DynamoDbClient};
+use crate::data::{DynamoBot, DynamoBotBincode, DynamoDbClient, ReadFrom};
 use crate::db_connectors::dynamodb::utils::*;
 use crate::db_connectors::{
     dynamodb::{aws_s3, Bot, BotKeys, Class, DynamoDbKey},
@@ -177,7 +177,7 @@ pub fn get_bot_versions(
         ..Default::default()
     };
 
-    let bots = execute_bot_version_batch_get_query(db, input)?;
+    let bots = execute_bot_version_batch_get_queries(db, vec![input], ReadFrom::Primary)?;
 
     match data.last_evaluated_key {
         Some(pagination_key) => {
@@ -438,14 +438,15 @@ pub fn delete_bot_versions(bot_id: &str, db: &mut DynamoDbClient) -> Result<(),
     }
 }
 
-fn query_bot_info(
+pub fn query_bot_info(
     bot_id: &str,
     class: &str,
     limit: i64,
     db: &mut DynamoDbClient,
     pagination_key: Option<HashMap<String, AttributeValue>>,
+    read_from: ReadFrom,
 ) -> Result<QueryOutput, EngineError> {
-    let hash = format!("bot_id:{}#", bot_id);
+    let hash = format!("{}bot_id:{}#", get_hash_prefix(), bot_id);
 
     let expr_attr_names = [
         (String::from("#classKey"), String::from("class")),
@@ -490,7 +491,7 @@ fn query_bot_info(
         ..Default::default()
     };
 
-    let future = db.client.query(input);
+    let future = db.reader(read_from).query(input);
     let data = match db.runtime.block_on(future) {
         Ok(data) => data,
         Err(e) => return Err(EngineError::Manager(format!("query_bot_info {:?}", e))),
@@ -508,7 +509,7 @@ pub fn delete_all_bot_data(
 
     loop {
         // 25 is the Maximum operations in a single request for BatchWriteItemInput
-        let data = query_bot_info(bot_id, class, 25, db, pagination_key)?;
+        let data = query_bot_info(bot_id, class, 25, db, pagination_key, ReadFrom::Primary)?;
 
         // The query returns an array of items (max 10, based on the limit param above).
         // If 0 item is returned it means that there is no open conversation, so simply return None
@@ -0,0 +1,202 @@
+use crate::data::DynamoDbClient;
+use crate::db_connectors::{
+    connector::{Connector, EncryptedMessage, Interaction},
+    dynamodb::{
+        conversations, get_pagination_key, memories, messages, rate_limit, state,
+        utils::get_date_time_from_timestamp,
+    },
+    utils::{get_conversation_ttl_for_dynamodb, get_expires_at_for_dynamodb},
+    DbConversation, MessageCursor,
+};
+use crate::{Client, EngineError};
+
+impl Connector for DynamoDbClient {
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError> {
+        // messages are kept as long as their conversation
+        let expires_at =
+            get_expires_at_for_dynamodb(get_conversation_ttl_for_dynamodb(interaction.ttl));
+
+        messages::add_messages_bulk(client, interaction, messages, expires_at, self)
+    }
+
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+        let from = from_date.map(get_date_time_from_timestamp);
+        let to = to_date.map(get_date_time_from_timestamp);
+
+        messages::get_client_messages_between(
+            client,
+            self,
+            limit,
+            pagination_key,
+            from.as_deref(),
+            to.as_deref(),
+        )
+    }
+
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        messages::get_client_messages_page(client, self, limit, cursor)
+    }
+
+    fn query_bot_messages(
+        &mut self,
+        bot_id: &str,
+        limit: i64,
+        pagination_key: Option<String>,
+    ) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        messages::get_bot_messages(bot_id, self, limit, pagination_key)
+    }
+
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        memories::add_memories(client, memories, get_expires_at_for_dynamodb(ttl), self)
+    }
+
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        memories::get_all_memories(client, self)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        memories::delete_client_memory(client, key, self)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        memories::delete_client_memories(client, self)
+    }
+
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(get_conversation_ttl_for_dynamodb(ttl));
+
+        conversations::create_conversation(id, flow_id, step_id, client, expires_at, self)
+    }
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError> {
+        conversations::close_conversation(id, client, status, self)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::close_all_conversations(client, self)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError> {
+        conversations::get_latest_open(client, self)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        conversations::update_conversation(conversation_id, client, flow_id, step_id, self)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        conversations::get_client_conversations(client, self, limit, pagination_key)
+    }
+
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        conversations::get_conversation_summaries(client, self, limit, pagination_key)
+    }
+
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        state::set_state_items(client, _type, items, get_expires_at_for_dynamodb(ttl), self)
+    }
+
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        version: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+
+        state::set_state_item_if_version(client, _type, key, &value, version, expires_at, self)
+    }
+
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_state_key(client, _type, key, self)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        state::delete_state_key(client, _type, key, self)
+    }
+
+    fn increment_request_count(
+        &mut self,
+        client: &Client,
+        window_start: i64,
+        window: i64,
+    ) -> Result<i64, EngineError> {
+        rate_limit::increment_request_count(client, window_start, window, self)
+    }
+}
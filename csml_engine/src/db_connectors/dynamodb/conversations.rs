@@ -1,20 +1,24 @@
-use crate::data::DynamoDbClient;
-use crate::db_connectors::dynamodb::{Conversation, ConversationKeys, DynamoDbKey};
+use crate::data::{DynamoDbClient, ReadFrom};
+use crate::db_connectors::dynamodb::{
+    Conversation, ConversationKeys, ConversationSummary, DynamoDbKey,
+};
 use crate::db_connectors::DbConversation;
 use crate::{Client, EngineError};
+use rusoto_core::RusotoError;
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
 use crate::db_connectors::dynamodb::utils::*;
 
 pub fn create_conversation(
+    id: &str,
     flow_id: &str,
     step_id: &str,
     client: &Client,
     expires_at: Option<i64>,
     db: &mut DynamoDbClient,
-) -> Result<String, EngineError> {
-    let data = Conversation::new(client, flow_id, step_id, expires_at);
+) -> Result<(), EngineError> {
+    let data = Conversation::new(id, client, flow_id, step_id, expires_at);
     let input = PutItemInput {
         item: serde_dynamodb::to_hashmap(&data)?,
         table_name: get_table_name()?,
@@ -26,7 +30,7 @@ pub fn create_conversation(
 
     db.runtime.block_on(future)?;
 
-    Ok(data.id.to_owned())
+    Ok(())
 }
 
 /**
@@ -46,58 +50,82 @@ pub fn close_conversation(
     let range = Conversation::get_range("OPEN", id);
     let old_key = DynamoDbKey::new(&hash, &range);
 
-    // get conv with ID
-    let get_input = GetItemInput {
-        table_name: get_table_name()?,
-        key: serde_dynamodb::to_hashmap(&old_key)?,
-        ..Default::default()
-    };
-
-    let future = db.client.get_item(get_input);
-
-    let res = db.runtime.block_on(future)?;
+    // the conversation is read again when messages were written since it was read
+    loop {
+        // get conv with ID
+        let get_input = GetItemInput {
+            table_name: get_table_name()?,
+            key: serde_dynamodb::to_hashmap(&old_key)?,
+            ..Default::default()
+        };
 
-    // If no conversation matches the request, we assume it's already closed and move on
-    let item = match res.item {
-        None => return Ok(()),
-        Some(data) => data,
-    };
+        let future = db.client.get_item(get_input);
 
-    // Update the conversation with the new status and closed state
-    let mut new_conv: Conversation = serde_dynamodb::from_hashmap(item)?;
+        let res = db.runtime.block_on(future)?;
 
-    let now = get_date_time();
-    new_conv.status = status.to_owned();
-    new_conv.last_interaction_at = now.to_owned();
-    new_conv.updated_at = now.to_owned();
-    new_conv.range_time = make_range(&["interaction", "CLOSED", &now, &id]);
-    new_conv.range = Conversation::get_range("CLOSED", &id);
+        // If no conversation matches the request, we assume it's already closed and move on
+        let item = match res.item {
+            None => return Ok(()),
+            Some(data) => data,
+        };
 
-    let new_item = serde_dynamodb::to_hashmap(&new_conv)?;
+        // Update the conversation with the new status and closed state
+        let mut new_conv: Conversation = serde_dynamodb::from_hashmap(item)?;
 
-    replace_conversation(&old_key, new_item, db)?;
+        let now = get_date_time();
+        new_conv.status = status.to_owned();
+        new_conv.last_interaction_at = now.to_owned();
+        new_conv.updated_at = now.to_owned();
+        new_conv.range_time = make_range(&["interaction", "CLOSED", &now, &id]);
+        new_conv.range = Conversation::get_range("CLOSED", &id);
 
-    Ok(())
+        if replace_conversation(&old_key, &new_conv, db)? {
+            return Ok(());
+        }
+    }
 }
 
 /**
  * To close a conversation, we must replace the given conversation,
  * ideally in a transaction to make sure that we don't lose a conversation in the process.
+ * The conversation is only replaced if no messages were counted in it since it was read,
+ * otherwise nothing is written and false is returned.
  */
 fn replace_conversation(
     old_key: &DynamoDbKey,
-    new_item: HashMap<String, AttributeValue>,
+    new_conv: &Conversation,
     db: &mut DynamoDbClient,
-) -> Result<(), EngineError> {
+) -> Result<bool, EngineError> {
     let put = Put {
         table_name: get_table_name()?,
-        item: new_item,
+        item: serde_dynamodb::to_hashmap(new_conv)?,
         ..Default::default()
     };
 
     let del = Delete {
         table_name: get_table_name()?,
         key: serde_dynamodb::to_hashmap(old_key.to_owned())?,
+        condition_expression: Some(
+            "attribute_not_exists(#messageCount) OR #messageCount = :messageCount".to_owned(),
+        ),
+        expression_attribute_names: Some(
+            [("#messageCount".to_owned(), "message_count".to_owned())]
+                .iter()
+                .cloned()
+                .collect(),
+        ),
+        expression_attribute_values: Some(
+            [(
+                ":messageCount".to_owned(),
+                AttributeValue {
+                    n: Some(new_conv.message_count.to_string()),
+                    ..Default::default()
+                },
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        ),
         ..Default::default()
     };
 
@@ -116,7 +144,91 @@ fn replace_conversation(
     };
 
     let future = db.client.transact_write_items(input);
-    db.runtime.block_on(future)?;
+    match db.runtime.block_on(future) {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(TransactWriteItemsError::TransactionCanceled(_))) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/**
+ * Count the messages written in the conversation and move it in the LastMessageIndex.
+ * The status is part of the key of the conversation, so the open conversation is updated
+ * first, then the closed one. The messages of an unknown conversation are not counted.
+ */
+pub fn add_conversation_messages(
+    client: &Client,
+    id: &str,
+    count: usize,
+    first_message_at: &str,
+    last_message_at: &str,
+    db: &mut DynamoDbClient,
+) -> Result<(), EngineError> {
+    let expr_attr_names: HashMap<String, String> = [
+        ("#hashKey".to_owned(), "hash".to_owned()),
+        ("#firstMessageAt".to_owned(), "first_message_at".to_owned()),
+        ("#lastMessageAt".to_owned(), "last_message_at".to_owned()),
+        ("#messageCount".to_owned(), "message_count".to_owned()),
+        (
+            "#rangeLastMessage".to_owned(),
+            "range_last_message".to_owned(),
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let string_value = |value: String| AttributeValue {
+        s: Some(value),
+        ..Default::default()
+    };
+    let expr_attr_values: HashMap<String, AttributeValue> = [
+        (
+            ":firstMessageAt".to_owned(),
+            string_value(first_message_at.to_owned()),
+        ),
+        (
+            ":lastMessageAt".to_owned(),
+            string_value(last_message_at.to_owned()),
+        ),
+        (
+            ":rangeLastMessage".to_owned(),
+            string_value(make_range(&["conversation", last_message_at, id])),
+        ),
+        (
+            ":count".to_owned(),
+            AttributeValue {
+                n: Some(count.to_string()),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    for status in ["OPEN", "CLOSED"].iter() {
+        let input = UpdateItemInput {
+            table_name: get_table_name()?,
+            key: serde_dynamodb::to_hashmap(&Conversation::get_key(client, status, id))?,
+            condition_expression: Some("attribute_exists(#hashKey)".to_owned()),
+            update_expression: Some(
+                "SET #firstMessageAt = if_not_exists(#firstMessageAt, :firstMessageAt), \
+                #lastMessageAt = :lastMessageAt, #rangeLastMessage = :rangeLastMessage \
+                ADD #messageCount :count"
+                    .to_owned(),
+            ),
+            expression_attribute_names: Some(expr_attr_names.clone()),
+            expression_attribute_values: Some(expr_attr_values.clone()),
+            ..Default::default()
+        };
+
+        match db.runtime.block_on(db.client.update_item(input)) {
+            Ok(_) => return Ok(()),
+            Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
 
     Ok(())
 }
@@ -227,7 +339,7 @@ fn get_all_open_conversations(
         ..Default::default()
     };
 
-    execute_conversations_batch_get_query(db, input)
+    execute_conversations_batch_get_queries(db, vec![input], ReadFrom::Primary)
 }
 
 pub fn close_all_conversations(
@@ -246,9 +358,11 @@ pub fn close_all_conversations(
         new_conv.range = Conversation::get_range(status, &new_conv.id);
 
         let old_key = Conversation::get_key(&client, "OPEN", &new_conv.id);
-        let new_item = serde_dynamodb::to_hashmap(&new_conv)?;
 
-        replace_conversation(&old_key, new_item, db)?;
+        // messages were written since the conversation was read
+        if !replace_conversation(&old_key, &new_conv, db)? {
+            close_conversation(&new_conv.id, client, status, db)?;
+        }
     }
     Ok(())
 }
@@ -326,7 +440,7 @@ pub fn get_latest_open(
         ..Default::default()
     };
 
-    let conv = execute_conversation_get_query(db, input)?;
+    let conv = execute_conversation_get_query(db, input, ReadFrom::Primary)?;
 
     Ok(Some(DbConversation {
         id: conv.id.to_string(),
@@ -435,6 +549,7 @@ pub fn update_conversation(
 fn query_conversation(
     client: &Client,
     db: &mut DynamoDbClient,
+    index_name: &str,
     limit: i64,
     pagination_key: Option<HashMap<String, AttributeValue>>,
     projection_expression: Option<String>,
@@ -465,7 +580,7 @@ fn query_conversation(
 
     let input = QueryInput {
         table_name: get_table_name()?,
-        index_name: Some("TimeIndex".to_owned()),
+        index_name: Some(index_name.to_owned()),
         key_condition_expression,
         expression_attribute_names,
         expression_attribute_values: Some(expr_attr_values),
@@ -509,6 +624,7 @@ pub fn delete_user_conversations(
         let data = query_conversation(
             client,
             db,
+            "TimeIndex",
             25,
             pagination_key,
             Some("#hashKey, #rangeKey".to_owned()),
@@ -588,6 +704,7 @@ pub fn get_client_conversations(
     let data = query_conversation(
         client,
         db,
+        "TimeIndex",
         limit,
         pagination_key,
         Some("#hashKey, #rangeKey".to_owned()),
@@ -634,7 +751,8 @@ pub fn get_client_conversations(
         ..Default::default()
     };
 
-    let get_conversations = execute_conversations_batch_get_query(db, input)?;
+    let get_conversations =
+        execute_conversations_batch_get_queries(db, vec![input], ReadFrom::Primary)?;
 
     for conversation in get_conversations {
         conversations.push(DbConversation {
@@ -660,3 +778,77 @@ pub fn get_client_conversations(
         None => Ok(serde_json::json!({ "conversations": conversations })),
     }
 }
+
+/**
+ * The conversations are read from the LastMessageIndex, sorted from the most recent last
+ * message, with the dates and the number of messages counted when they are written.
+ * The conversations without messages are sorted by their creation date.
+ */
+pub fn get_conversation_summaries(
+    client: &Client,
+    db: &mut DynamoDbClient,
+    limit: Option<i64>,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+) -> Result<serde_json::Value, EngineError> {
+    let limit = match limit {
+        Some(limit) if limit >= 1 => limit,
+        Some(_limit) => 20,
+        None => 20,
+    };
+
+    let key_condition_expression =
+        "#hashKey = :hashVal AND begins_with(#rangeLastMessage, :rangePrefix)".to_owned();
+
+    let expr_attr_names: HashMap<String, String> = [
+        ("#hashKey".to_string(), "hash".to_string()),
+        (
+            "#rangeLastMessage".to_string(),
+            "range_last_message".to_string(),
+        ),
+        ("#id".to_string(), "id".to_string()),
+        (
+            "#firstMessageAt".to_string(),
+            "first_message_at".to_string(),
+        ),
+        ("#lastMessageAt".to_string(), "last_message_at".to_string()),
+        ("#messageCount".to_string(), "message_count".to_string()),
+        ("#createdAt".to_string(), "created_at".to_string()),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let data = query_conversation(
+        client,
+        db,
+        "LastMessageIndex",
+        limit,
+        pagination_key,
+        Some("#id, #firstMessageAt, #lastMessageAt, #messageCount, #createdAt".to_owned()),
+        Some(key_condition_expression),
+        Some(expr_attr_names),
+    )?;
+
+    let mut conversations = vec![];
+    for item in data.items.unwrap_or_default() {
+        let summary: ConversationSummary = serde_dynamodb::from_hashmap(item)?;
+
+        conversations.push(serde_json::json!({
+            "conversation_id": summary.id,
+            "started_at": summary.first_message_at.unwrap_or(summary.created_at),
+            "last_message_at": summary.last_message_at,
+            "message_count": summary.message_count,
+        }));
+    }
+
+    match data.last_evaluated_key {
+        Some(pagination_key) => {
+            let pagination_key = base64::encode(serde_json::json!(pagination_key).to_string());
+
+            Ok(
+                serde_json::json!({"conversations": conversations, "pagination_key": pagination_key}),
+            )
+        }
+        None => Ok(serde_json::json!({ "conversations": conversations })),
+    }
+}
@@ -1,94 +1,50 @@
-use crate::data::DynamoDbClient;
-use crate::db_connectors::dynamodb::{get_db, DynamoDbKey, Memory, MemoryDeleteInfo, MemoryKeys};
-use crate::{encrypt::encrypt_data, Client, ConversationInfo, EngineError};
-use csml_interpreter::data::Memory as InterpreterMemory;
+use crate::data::{DynamoDbClient, ReadFrom};
+use crate::db_connectors::dynamodb::{DynamoDbKey, Memory, MemoryDeleteInfo, MemoryKeys};
+use crate::{Client, EngineError};
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
 use crate::db_connectors::dynamodb::utils::*;
 
-fn format_memories(
-    data: &ConversationInfo,
-    memories: &HashMap<String, InterpreterMemory>,
-    expires_at: Option<i64>,
-) -> Result<Vec<Memory>, EngineError> {
-    let mut res = vec![];
-
-    for (_, mem) in memories.iter() {
-        res.push(Memory::new(
-            &data.client,
-            &mem.key,
-            Some(encrypt_data(&mem.value)?),
-            expires_at,
-        ));
-    }
-
-    Ok(res)
-}
-
+/**
+ * Save (key, encrypted value) memories, all the memories of a turn in a single batch write.
+ * `execute_batch_write_query` splits it in batches of BATCH_WRITE_LIMIT items.
+ */
 pub fn add_memories(
-    data: &mut ConversationInfo,
-    memories: &HashMap<String, InterpreterMemory>,
+    client: &Client,
+    memories: Vec<(String, String)>,
     expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
     if memories.len() == 0 {
         return Ok(());
     }
 
-    let memories = format_memories(data, memories, expires_at)?;
-
-    // We can only use BatchWriteItem on up to 25 items at once,
-    // so we need to split the memories to write into chunks of max
-    // 25 items.
-    for chunk in memories.chunks(25) {
-        let mut request_items = HashMap::new();
-
-        let mut items_to_write = vec![];
-        for data in chunk {
-            items_to_write.push(WriteRequest {
-                put_request: Some(PutRequest {
-                    item: serde_dynamodb::to_hashmap(&data)?,
-                    ..Default::default()
-                }),
-                ..Default::default()
-            });
-        }
+    let memories: Vec<Memory> = memories
+        .into_iter()
+        .map(|(key, value)| Memory::new(client, &key, Some(value), expires_at))
+        .collect();
 
-        request_items.insert(get_table_name()?, items_to_write);
-
-        let input = BatchWriteItemInput {
-            request_items,
+    let mut items_to_write = vec![];
+    for data in memories.iter() {
+        items_to_write.push(WriteRequest {
+            put_request: Some(PutRequest {
+                item: serde_dynamodb::to_hashmap(&data)?,
+                ..Default::default()
+            }),
             ..Default::default()
-        };
-
-        let db = get_db(&mut data.db)?;
-        let future = db.client.batch_write_item(input);
-
-        db.runtime.block_on(future)?;
+        });
     }
 
-    Ok(())
-}
+    let mut request_items = HashMap::new();
+    request_items.insert(get_table_name()?, items_to_write);
 
-pub fn create_client_memory(
-    client: &Client,
-    key: String,
-    value: serde_json::Value,
-    expires_at: Option<i64>,
-    db: &mut DynamoDbClient,
-) -> Result<(), EngineError> {
-    let memories = Memory::new(client, &key, Some(encrypt_data(&value)?), expires_at);
-
-    let input = PutItemInput {
-        item: serde_dynamodb::to_hashmap(&memories)?,
-        table_name: get_table_name()?,
+    let input = BatchWriteItemInput {
+        request_items,
         ..Default::default()
     };
 
-    let client = db.client.to_owned();
-    let future = client.put_item(input);
-
-    db.runtime.block_on(future)?;
+    execute_batch_write_query(db, input)?;
 
     Ok(())
 }
@@ -122,17 +78,21 @@ fn query_memories(
     let future = db.client.query(input);
     let data = match db.runtime.block_on(future) {
         Ok(data) => data,
-        Err(e) => return Err(EngineError::Manager(format!("query_memories {:?}", e))),
+        Err(e) => fail_open_read(db, "query_memories", e)
+            .map_err(|e| EngineError::Manager(format!("query_memories {:?}", e)))?,
     };
 
     Ok(data)
 }
 
-fn get_all_memories(
+/**
+ * Get all the memories of the client, with their values as saved
+ */
+pub fn get_all_memories(
     client: &Client,
     db: &mut DynamoDbClient,
 ) -> Result<Vec<serde_json::Value>, EngineError> {
-    let mut memories = vec![];
+    let mut inputs = vec![];
     let mut last_evaluated_key = None;
 
     let expr_attr_names: HashMap<String, String> = [
@@ -163,7 +123,7 @@ fn get_all_memories(
     .cloned()
     .collect();
 
-    // retrieve all memories from dynamodb
+    // retrieve the keys of all memories from dynamodb, the memories are read together after
     loop {
         let data = query_memories(
             None,
@@ -207,13 +167,10 @@ fn get_all_memories(
             })
             .collect();
 
-        let input = BatchGetItemInput {
+        inputs.push(BatchGetItemInput {
             request_items,
             ..Default::default()
-        };
-
-        let mut new_memory_batch = execute_memory_batch_get_query(db, input)?;
-        memories.append(&mut new_memory_batch);
+        });
 
         if let None = &data.last_evaluated_key {
             break;
@@ -222,69 +179,7 @@ fn get_all_memories(
         last_evaluated_key = data.last_evaluated_key;
     }
 
-    Ok(memories)
-}
-
-pub fn internal_use_get_memories(
-    client: &Client,
-    db: &mut DynamoDbClient,
-) -> Result<serde_json::Value, EngineError> {
-    let memories = get_all_memories(client, db)?;
-
-    // format memories output
-    let mut map = serde_json::Map::new();
-    for mem in memories {
-        let key = mem["key"].as_str().unwrap();
-
-        if !map.contains_key(key) {
-            map.insert(key.to_string(), mem["value"].clone());
-        }
-    }
-
-    Ok(serde_json::json!(map))
-}
-
-pub fn get_memories(
-    client: &Client,
-    db: &mut DynamoDbClient,
-) -> Result<serde_json::Value, EngineError> {
-    let memories = get_all_memories(client, db)?;
-
-    // format memories output
-    let mut map = serde_json::Map::new();
-    let mut vec = vec![];
-    for mem in memories {
-        let key = mem["key"].as_str().unwrap();
-
-        if !map.contains_key(key) {
-            map.insert(key.to_string(), mem["value"].clone());
-
-            vec.push(mem);
-        }
-    }
-
-    Ok(serde_json::json!(vec))
-}
-
-pub fn get_memory(
-    client: &Client,
-    key: &str,
-    db: &mut DynamoDbClient,
-) -> Result<serde_json::Value, EngineError> {
-    let memories = get_all_memories(client, db)?;
-
-    // format memories output
-    let mut return_value = serde_json::Value::Null;
-    for mem in memories {
-        let val = mem["key"].as_str().unwrap();
-
-        if key == val {
-            return_value = mem;
-            break;
-        }
-    }
-
-    Ok(return_value)
+    execute_memory_batch_get_queries(db, inputs, ReadFrom::Primary)
 }
 
 fn get_memory_batches_to_delete(
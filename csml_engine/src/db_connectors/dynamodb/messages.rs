@@ -1,44 +1,49 @@
-use crate::db_connectors::dynamodb::{
-    get_db, DynamoDbClient, DynamoDbKey, Message, MessageFromDateInfo, MessageKeys,
+use crate::data::{EngineError, ReadFrom};
+use crate::db_connectors::{
+    connector::{EncryptedMessage, Interaction},
+    dynamodb::{
+        bot::query_bot_info, conversations::add_conversation_messages, Class, DynamoDbClient,
+        DynamoDbKey, Message, MessageKeys, MessageTimeKeys,
+    },
+    MessageCursor,
 };
-use crate::{data::EngineError, encrypt::encrypt_data, Client, ConversationInfo};
-use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
+use crate::Client;
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
 use crate::db_connectors::dynamodb::utils::*;
 
 fn format_messages(
-    data: &ConversationInfo,
-    messages: &[serde_json::Value],
-    interaction_order: i32,
-    direction: &str,
+    client: &Client,
+    interaction: &Interaction,
+    messages: Vec<EncryptedMessage>,
     expires_at: Option<i64>,
-) -> Result<Vec<Message>, EngineError> {
-    let mut res = vec![];
-
-    for (i, message) in messages.iter().enumerate() {
-        res.push(Message::new(
-            &data.client,
-            &data.conversation_id,
-            &data.context.flow,
-            &data.context.step.get_step(),
-            direction,
-            interaction_order,
-            i as i32,
-            &encrypt_data(&message)?,
-            &message["content_type"].to_string(),
-            expires_at,
-        ));
-    }
-
-    Ok(res)
+) -> Vec<Message> {
+    messages
+        .into_iter()
+        .map(|message| {
+            Message::new(
+                client,
+                interaction.conversation_id,
+                interaction.flow_id,
+                interaction.step_id,
+                interaction.direction,
+                interaction.interaction_order,
+                message.message_order,
+                &message.payload,
+                &message.content_type,
+                expires_at,
+            )
+        })
+        .collect()
 }
 
 pub fn write_messages_batch(
     messages: &[Message],
     db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
+    let mut inputs = vec![];
+
     // We can only use BatchWriteItem on up to 25 items at once,
     // so we need to split the messages to write into chunks of max
     // 25 items.
@@ -62,63 +67,109 @@ pub fn write_messages_batch(
             ..Default::default()
         };
 
-        let future = db.client.batch_write_item(input);
-
-        db.runtime.block_on(future)?;
+        inputs.push(input);
     }
 
+    execute_batch_write_queries(db, inputs)?;
+
     Ok(())
 }
 
 pub fn add_messages_bulk(
-    data: &mut ConversationInfo,
-    messages: &[serde_json::Value],
-    interaction_order: i32,
-    direction: &str,
+    client: &Client,
+    interaction: &Interaction,
+    messages: Vec<EncryptedMessage>,
     expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
     if messages.len() == 0 {
         return Ok(());
     }
 
-    let messages = format_messages(data, messages, interaction_order, direction, expires_at)?;
-    let db = get_db(&mut data.db)?;
+    let mut messages = format_messages(client, interaction, messages, expires_at);
+
+    let first = reserve_message_sequence(
+        client,
+        interaction.conversation_id,
+        messages.len(),
+        expires_at,
+        db,
+    )?;
+    set_message_sequence(&mut messages, first);
+
+    write_messages_batch(&messages, db)?;
+
+    let dates = messages.iter().map(|message| message.created_at.as_str());
+    add_conversation_messages(
+        client,
+        interaction.conversation_id,
+        messages.len(),
+        dates.clone().min().unwrap_or_default(),
+        dates.max().unwrap_or_default(),
+        db,
+    )
+}
+
+/**
+ * Reserve `count` numbers of the message sequence of the conversation and return the
+ * first one. The counter is incremented atomically, so messages written concurrently
+ * in the same conversation never share a number.
+ */
+fn reserve_message_sequence(
+    client: &Client,
+    conversation_id: &str,
+    count: usize,
+    expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
+) -> Result<i64, EngineError> {
+    // the counter is stored with the messages of the conversation and deleted with them
+    let key = serde_dynamodb::to_hashmap(&DynamoDbKey::new(
+        &Message::get_hash(client),
+        &Message::get_range(conversation_id, "sequence"),
+    ))?;
+
+    let last = execute_sequence_update_query(db, key, count as i64, expires_at)?;
+
+    Ok(last - count as i64 + 1)
+}
 
-    write_messages_batch(&messages, db)
+fn set_message_sequence(messages: &mut [Message], first: i64) {
+    for (message, sequence) in messages.iter_mut().zip(first..) {
+        message.sequence = sequence;
+    }
 }
 
 fn query_messages(
     client: &Client,
     db: &mut DynamoDbClient,
-    range: String,
+    range_values: &[(&str, String)],
     index_name: Option<String>,
     limit: i64,
     pagination_key: Option<HashMap<String, AttributeValue>>,
     expression_attribute_names: Option<HashMap<String, String>>,
     key_condition_expression: Option<String>,
     projection_expression: Option<String>,
+    read_from: ReadFrom,
 ) -> Result<QueryOutput, EngineError> {
     let hash = Message::get_hash(client);
 
-    let expr_attr_values: HashMap<String, AttributeValue> = [
-        (
-            String::from(":hashVal"),
-            AttributeValue {
-                s: Some(hash),
-                ..Default::default()
-            },
-        ),
-        (
-            String::from(":rangePrefix"),
+    let mut expr_attr_values: HashMap<String, AttributeValue> = HashMap::new();
+    expr_attr_values.insert(
+        String::from(":hashVal"),
+        AttributeValue {
+            s: Some(hash),
+            ..Default::default()
+        },
+    );
+    for (name, value) in range_values {
+        expr_attr_values.insert(
+            name.to_string(),
             AttributeValue {
-                s: Some(format!("{}", range)),
+                s: Some(value.to_owned()),
                 ..Default::default()
             },
-        ),
-    ]
-    .iter()
-    .cloned()
-    .collect();
+        );
+    }
 
     let input = QueryInput {
         table_name: get_table_name()?,
@@ -134,7 +185,7 @@ fn query_messages(
         ..Default::default()
     };
 
-    let future = db.client.query(input);
+    let future = db.reader(read_from).query(input);
     let data = match db.runtime.block_on(future) {
         Ok(data) => data,
         Err(e) => return Err(EngineError::Manager(format!("query_messages {:?}", e))),
@@ -143,74 +194,43 @@ fn query_messages(
     Ok(data)
 }
 
-fn query_messages_from_date(
-    db: &mut DynamoDbClient,
-    range: String,
-    index_name: Option<String>,
-    limit: i64,
-    pagination_key: Option<HashMap<String, AttributeValue>>,
-    projection_expression: Option<String>,
-    expression_attribute_names: Option<HashMap<String, String>>,
-    from_date: i64,
-    _to_date: Option<i64>,
-) -> Result<QueryOutput, EngineError> {
-    let from_date = DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(from_date, 0), Utc);
-    // let to_date = match to_date {
-    //     Some(to_date) => {
-    //         DateTime::<Utc>::from_utc(NaiveDateTime::from_timestamp(to_date, 0), Utc)
-    //     }
-    //     None => chrono::Utc::now(),
-    // };
-
-    let expr_attr_values: HashMap<String, AttributeValue> = [
-        (
-            String::from(":hashVal"),
-            AttributeValue {
-                s: Some(range),
-                ..Default::default()
-            },
-        ),
-        (
-            String::from(":fromDateTime"),
-            AttributeValue {
-                s: Some(from_date.to_rfc3339_opts(SecondsFormat::Millis, true)),
-                ..Default::default()
-            },
-        ),
-    ]
-    .iter()
-    .cloned()
-    .collect();
-
-    let key_condition_expression = "#hashKey = :hashVal and #rangeKey >= :fromDateTime".to_owned();
+/**
+ * Bounds of the range_time keys of the messages created between `from` and `to`, both
+ * formatted like `get_date_time` and included. None if `from` is after `to`.
+ */
+fn get_time_range_bounds(from: Option<&str>, to: Option<&str>) -> Option<(String, String)> {
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return None;
+        }
+    }
 
-    let input = QueryInput {
-        table_name: get_table_name()?,
-        index_name,
-        key_condition_expression: Some(key_condition_expression),
-        expression_attribute_names,
-        expression_attribute_values: Some(expr_attr_values),
-        limit: Some(limit),
-        exclusive_start_key: pagination_key,
-        scan_index_forward: Some(false),
-        projection_expression,
-        ..Default::default()
+    // range_time = message#timestamp#interaction_order#message_order#id, '~' sorts after
+    // the keys of all the messages created at `to`
+    let lower_bound = match from {
+        Some(from) => make_range(&["message", from]),
+        None => make_range(&["message", ""]),
     };
-
-    let future = db.client.query(input);
-    let data = match db.runtime.block_on(future) {
-        Ok(data) => data,
-        Err(e) => return Err(EngineError::Manager(format!("query_messages {:?}", e))),
+    let upper_bound = match to {
+        Some(to) => make_range(&["message", to, "~"]),
+        None => make_range(&["message", "~"]),
     };
 
-    Ok(data)
+    Some((lower_bound, upper_bound))
 }
 
-pub fn get_client_messages(
+/**
+ * Get the client's messages created between `from` and `to` (formatted like `get_date_time`),
+ * both optional and included. The range is a condition of the query, the messages out of it
+ * are not read.
+ */
+pub fn get_client_messages_between(
     client: &Client,
     db: &mut DynamoDbClient,
     limit: Option<i64>,
     pagination_key: Option<HashMap<String, AttributeValue>>,
+    from: Option<&str>,
+    to: Option<&str>,
 ) -> Result<serde_json::Value, EngineError> {
     let limit = match limit {
         Some(limit) if limit >= 1 => limit,
@@ -218,8 +238,14 @@ pub fn get_client_messages(
         None => 20,
     };
 
+    // dynamodb rejects a 'between' condition with its lower bound above the upper one
+    let (lower_bound, upper_bound) = match get_time_range_bounds(from, to) {
+        Some(bounds) => bounds,
+        None => return Ok(serde_json::json!({"messages": []})),
+    };
+
     let key_condition_expression =
-        "#hashKey = :hashVal and begins_with(#rangeTimeKey, :rangePrefix)".to_owned();
+        "#hashKey = :hashVal and #rangeTimeKey between :rangeFrom and :rangeTo".to_owned();
 
     let expr_attr_names: HashMap<String, String> = [
         (String::from("#hashKey"), String::from("hash")),
@@ -233,13 +259,14 @@ pub fn get_client_messages(
     let data = query_messages(
         client,
         db,
-        String::from("message#"),
+        &[(":rangeFrom", lower_bound), (":rangeTo", upper_bound)],
         Some(String::from("TimeIndex")),
         limit,
         pagination_key,
         Some(expr_attr_names),
         Some(key_condition_expression),
         Some(String::from("#rangeKey, #hashKey")),
+        ReadFrom::Replica,
     )?;
 
     // The query returns an array of items (max 10, based on the limit param above).
@@ -281,7 +308,10 @@ pub fn get_client_messages(
         ..Default::default()
     };
 
-    let messages = execute_messages_batch_get_query(db, input)?;
+    let mut messages = execute_messages_batch_get_queries(db, vec![input], ReadFrom::Replica)?;
+
+    // batch get items are not returned in order
+    messages.sort_by(|a, b| message_key(b).cmp(&message_key(a)));
 
     match data.last_evaluated_key {
         Some(pagination_key) => {
@@ -293,60 +323,209 @@ pub fn get_client_messages(
     }
 }
 
-pub fn get_client_messages_from_date(
+/**
+ * Read the position of a message from its range_time key
+ * (message#timestamp#interaction_order#message_order#id)
+ */
+fn get_message_position(range_time: &str) -> Option<MessageCursor> {
+    let mut parts = range_time.split('#');
+
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("message"), Some(created_at), Some(interaction_order), Some(message_order)) => {
+            Some(MessageCursor {
+                created_at: created_at.to_owned(),
+                interaction_order: interaction_order.parse().ok()?,
+                message_order: message_order.parse().ok()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn position_key(position: &MessageCursor) -> (&str, i32, i32) {
+    (
+        &position.created_at,
+        position.interaction_order,
+        position.message_order,
+    )
+}
+
+// the sequence orders the messages of a conversation created in the same millisecond
+fn message_key(
+    message: &serde_json::Value,
+) -> (Option<&str>, Option<i64>, Option<i64>, Option<i64>) {
+    (
+        message["created_at"].as_str(),
+        message["sequence"].as_i64(),
+        message["interaction_order"].as_i64(),
+        message["message_order"].as_i64(),
+    )
+}
+
+pub fn get_client_messages_page(
+    client: &Client,
     db: &mut DynamoDbClient,
-    limit: Option<i64>,
-    pagination_key: Option<HashMap<String, AttributeValue>>,
-    from_date: i64,
-    to_date: Option<i64>,
-) -> Result<serde_json::Value, EngineError> {
-    let mut messages = vec![];
-    let limit = match limit {
-        Some(limit) if limit >= 1 => limit,
-        Some(_limit) => 20,
-        None => 20,
+    limit: i64,
+    cursor: Option<MessageCursor>,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    // range_time keys sort messages by timestamp, the query starts with the messages created
+    // at the same time as the cursor and the ones already returned are skipped below
+    let upper_bound = match &cursor {
+        Some(cursor) => make_range(&["message", &cursor.created_at, "~"]),
+        None => make_range(&["message", "~"]),
     };
 
+    let key_condition_expression =
+        "#hashKey = :hashVal and #rangeTimeKey < :rangePrefix".to_owned();
+
     let expr_attr_names: HashMap<String, String> = [
-        (String::from("#hashKey"), String::from("class")),
-        (String::from("#rangeKey"), String::from("created_at")), // time index
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#rangeKey"), String::from("range")),
+        (String::from("#rangeTimeKey"), String::from("range_time")),
     ]
     .iter()
     .cloned()
     .collect();
 
-    let data = query_messages_from_date(
-        db,
-        String::from("message"),
-        Some(String::from("CreatedIndex")),
+    let mut positions: Vec<(MessageCursor, DynamoDbKey)> = vec![];
+    let mut pagination_key = None;
+
+    loop {
+        let data = query_messages(
+            client,
+            db,
+            &[(":rangePrefix", upper_bound.to_owned())],
+            Some(String::from("TimeIndex")),
+            limit,
+            pagination_key,
+            Some(expr_attr_names.clone()),
+            Some(key_condition_expression.clone()),
+            Some(String::from("#rangeKey, #hashKey, #rangeTimeKey")),
+            ReadFrom::Replica,
+        )?;
+
+        let mut is_last_query = data.last_evaluated_key.is_none();
+        let mut last_created_at = None;
+
+        for item in data.items.unwrap_or_default() {
+            let keys: MessageTimeKeys = serde_dynamodb::from_hashmap(item)?;
+
+            // other item classes are sorted below the messages
+            let position = match get_message_position(&keys.range_time) {
+                Some(position) => position,
+                None => {
+                    is_last_query = true;
+                    break;
+                }
+            };
+
+            last_created_at = Some(position.created_at.to_owned());
+
+            let is_after_cursor = match &cursor {
+                Some(cursor) => position_key(&position) < position_key(cursor),
+                None => true,
+            };
+
+            if is_after_cursor {
+                positions.push((
+                    position,
+                    DynamoDbKey {
+                        hash: keys.hash,
+                        range: keys.range,
+                    },
+                ));
+            }
+        }
+
+        positions.sort_by(|(a, _), (b, _)| position_key(b).cmp(&position_key(a)));
+
+        // the page is complete once every message sharing the timestamp of its last
+        // message has been read, message_order alone decides their order
+        let is_page_complete = match (positions.get(limit as usize - 1), last_created_at) {
+            (Some((last, _)), Some(last_created_at)) => last_created_at < last.created_at,
+            _ => false,
+        };
+
+        if is_last_query || is_page_complete {
+            break;
+        }
+
+        pagination_key = data.last_evaluated_key;
+    }
+
+    positions.truncate(limit as usize);
+
+    let mut inputs = vec![];
+
+    // a batch get reads at most 100 items
+    for chunk in positions.chunks(100) {
+        let mut get_requests = vec![];
+
+        for (_, key) in chunk {
+            get_requests.push(serde_dynamodb::to_hashmap(key)?);
+        }
+
+        let request_items = [(get_table_name()?, get_requests)]
+            .iter()
+            .cloned()
+            .map(|(name, keys)| {
+                let mut attval = KeysAndAttributes::default();
+
+                attval.keys = keys;
+
+                (name, attval)
+            })
+            .collect();
+
+        inputs.push(BatchGetItemInput {
+            request_items,
+            ..Default::default()
+        });
+    }
+
+    let mut messages = execute_messages_batch_get_queries(db, inputs, ReadFrom::Replica)?;
+
+    // batch get items are not returned in order
+    messages.sort_by(|a, b| message_key(b).cmp(&message_key(a)));
+
+    Ok(messages)
+}
+
+/**
+ * Get a page of all the messages of the bot across its clients, in the order of the
+ * ClassByClientIndex. The returned pagination key is the encoded last evaluated key,
+ * None after the last page.
+ */
+pub fn get_bot_messages(
+    bot_id: &str,
+    db: &mut DynamoDbClient,
+    limit: i64,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+    // a batch get reads at most 100 items
+    let limit = std::cmp::min(limit, 100);
+    let data = query_bot_info(
+        bot_id,
+        "message",
         limit,
+        db,
         pagination_key,
-        None,
-        Some(expr_attr_names.clone()),
-        from_date,
-        to_date,
+        ReadFrom::Replica,
     )?;
 
-    // The query returns an array of items (max 10, based on the limit param above).
-    // If 0 item is returned it means that there is no open conversation, so simply return None
-    // , "last_key": :
     let items = match data.items {
-        None => return Ok(serde_json::json!({"messages": []})),
-        Some(items) if items.len() == 0 => return Ok(serde_json::json!({"messages": []})),
-        Some(items) => items.clone(),
+        Some(items) if items.len() > 0 => items,
+        _ => return Ok((vec![], None)),
     };
 
     let mut get_requests = vec![];
-
     for item in items {
-        let message: MessageFromDateInfo = serde_dynamodb::from_hashmap(item)?;
+        let class: Class = serde_dynamodb::from_hashmap(item)?;
 
-        let key = serde_dynamodb::to_hashmap(&DynamoDbKey {
-            hash: message.hash,
-            range: message.range,
-        })?;
-
-        get_requests.push(key);
+        get_requests.push(serde_dynamodb::to_hashmap(&DynamoDbKey {
+            hash: class.hash,
+            range: class.range,
+        })?);
     }
 
     let request_items = [(get_table_name()?, get_requests)]
@@ -366,17 +545,17 @@ pub fn get_client_messages_from_date(
         ..Default::default()
     };
 
-    let mut get_messages = execute_messages_batch_get_query(db, input)?;
-    messages.append(&mut get_messages);
+    let mut messages = execute_messages_batch_get_queries(db, vec![input], ReadFrom::Replica)?;
 
-    match data.last_evaluated_key {
-        Some(pagination_key) => {
-            let pagination_key = base64::encode(serde_json::json!(pagination_key).to_string());
+    // batch get items are not returned in order
+    messages.sort_by(|a, b| message_key(a).cmp(&message_key(b)));
 
-            return Ok(serde_json::json!({"messages": messages, "pagination_key": pagination_key}));
-        }
-        None => return Ok(serde_json::json!({ "messages": messages })),
-    }
+    let pagination_key = match data.last_evaluated_key {
+        Some(key) => Some(base64::encode(serde_json::json!(key).to_string())),
+        None => None,
+    };
+
+    Ok((messages, pagination_key))
 }
 
 pub fn delete_user_messages(client: &Client, db: &mut DynamoDbClient) -> Result<(), EngineError> {
@@ -398,13 +577,14 @@ pub fn delete_user_messages(client: &Client, db: &mut DynamoDbClient) -> Result<
         let data = query_messages(
             client,
             db,
-            String::from("message#"),
+            &[(":rangePrefix", String::from("message#"))],
             None,
             25,
             pagination_key,
             Some(expr_attr_names.clone()),
             Some(key_condition_expression.clone()),
             Some("#hashKey, #rangeKey".to_owned()),
+            ReadFrom::Primary,
         )?;
 
         // The query returns an array of items (max 10, based on the limit param above).
@@ -450,3 +630,149 @@ pub fn delete_user_messages(client: &Client, db: &mut DynamoDbClient) -> Result<
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_client() -> Client {
+        Client {
+            bot_id: "bot_id".to_owned(),
+            channel_id: "channel_id".to_owned(),
+            user_id: "user_id".to_owned(),
+        }
+    }
+
+    fn get_message(direction: &str, created_at: &str) -> Message {
+        let mut message = Message::new(
+            &get_client(),
+            "conversation",
+            "flow",
+            "step",
+            direction,
+            0,
+            0,
+            "payload",
+            "text",
+            None,
+        );
+        message.created_at = created_at.to_owned();
+
+        message
+    }
+
+    fn to_json(message: &Message) -> serde_json::Value {
+        serde_json::json!({
+            "id": message.id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "sequence": message.sequence,
+            "created_at": message.created_at,
+        })
+    }
+
+    #[test]
+    fn sequence_orders_messages_with_identical_timestamps() {
+        let created_at = "2022-04-08T13:52:29.841Z";
+
+        // the received message and the first answer share their orders and timestamp
+        let mut messages = vec![get_message("RECEIVE", created_at)];
+        set_message_sequence(&mut messages, 1);
+        let mut answers = vec![
+            get_message("SEND", created_at),
+            get_message("SEND", created_at),
+            get_message("SEND", created_at),
+        ];
+        answers[1].message_order = 1;
+        answers[2].message_order = 2;
+        set_message_sequence(&mut answers, 2);
+        messages.append(&mut answers);
+
+        let sequences: Vec<i64> = messages.iter().map(|message| message.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 3, 4]);
+
+        let written: Vec<serde_json::Value> = messages.iter().map(to_json).collect();
+        let expected: Vec<serde_json::Value> = written.iter().rev().cloned().collect();
+
+        // batch get items come back in any order, the read back order is always the same
+        for rotation in 0..written.len() {
+            let mut read = written.clone();
+            read.rotate_left(rotation);
+            read.swap(0, rotation);
+            read.sort_by(|a, b| message_key(b).cmp(&message_key(a)));
+
+            assert_eq!(read, expected);
+        }
+    }
+
+    fn is_in_range(created_at: &str, bounds: &Option<(String, String)>) -> bool {
+        let range_time = make_range(&["message", created_at, "0", "1", "id"]);
+
+        match bounds {
+            Some((lower_bound, upper_bound)) => {
+                lower_bound <= &range_time && &range_time <= upper_bound
+            }
+            None => false,
+        }
+    }
+
+    const BEFORE: &str = "2022-04-08T13:52:29.840Z";
+    const FROM: &str = "2022-04-08T13:52:29.841Z";
+    const BETWEEN: &str = "2022-04-08T14:00:00.000Z";
+    const TO: &str = "2022-04-09T08:00:00.000Z";
+    const AFTER: &str = "2022-04-09T08:00:00.001Z";
+
+    #[test]
+    fn time_range_only_from() {
+        let bounds = get_time_range_bounds(Some(FROM), None);
+
+        assert!(!is_in_range(BEFORE, &bounds));
+        assert!(is_in_range(FROM, &bounds));
+        assert!(is_in_range(AFTER, &bounds));
+    }
+
+    #[test]
+    fn time_range_only_to() {
+        let bounds = get_time_range_bounds(None, Some(TO));
+
+        assert!(is_in_range(BEFORE, &bounds));
+        assert!(is_in_range(TO, &bounds));
+        assert!(!is_in_range(AFTER, &bounds));
+    }
+
+    #[test]
+    fn time_range_both_bounds() {
+        let bounds = get_time_range_bounds(Some(FROM), Some(TO));
+
+        assert!(!is_in_range(BEFORE, &bounds));
+        assert!(is_in_range(FROM, &bounds));
+        assert!(is_in_range(BETWEEN, &bounds));
+        assert!(is_in_range(TO, &bounds));
+        assert!(!is_in_range(AFTER, &bounds));
+
+        // a single instant
+        let bounds = get_time_range_bounds(Some(FROM), Some(FROM));
+        assert!(is_in_range(FROM, &bounds));
+        assert!(!is_in_range(BETWEEN, &bounds));
+    }
+
+    #[test]
+    fn time_range_inverted_is_empty() {
+        assert_eq!(get_time_range_bounds(Some(TO), Some(FROM)), None);
+    }
+
+    #[test]
+    fn messages_without_sequence() {
+        let created_at = "2022-04-08T13:52:29.841Z";
+        let mut first = to_json(&get_message("SEND", created_at));
+        let mut second = to_json(&get_message("SEND", created_at));
+        first.as_object_mut().unwrap().remove("sequence");
+        second.as_object_mut().unwrap().remove("sequence");
+        second["message_order"] = serde_json::json!(1);
+
+        // messages written before the sequence fall back on their orders
+        let mut read = vec![first.clone(), second.clone()];
+        read.sort_by(|a, b| message_key(b).cmp(&message_key(a)));
+        assert_eq!(read, vec![second, first]);
+    }
+}
@@ -7,9 +7,11 @@ use uuid::Uuid;
 
 pub mod aws_s3;
 pub mod bot;
+mod connector;
 pub mod conversations;
 pub mod memories;
 pub mod messages;
+pub mod rate_limit;
 pub mod state;
 pub mod utils;
 
@@ -31,7 +33,7 @@ pub fn init() -> Result<Database, EngineError> {
     }
 
     let mut s3_region = Region::default();
-    if let (Some(region_name), Some(s3_endpoint)) = (region_name, s3_endpoint) {
+    if let (Some(region_name), Some(s3_endpoint)) = (region_name.clone(), s3_endpoint) {
         s3_region = Region::Custom {
             name: region_name,
             endpoint: s3_endpoint,
@@ -43,7 +45,36 @@ pub fn init() -> Result<Database, EngineError> {
 
     let client = DynamoDbClient::new(dynamodb_region, s3_region);
 
-    Ok(Database::Dynamodb(client))
+    match get_read_region(region_name)? {
+        Some(read_region) => Ok(Database::Dynamodb(client.with_read_replica(read_region))),
+        None => Ok(Database::Dynamodb(client)),
+    }
+}
+
+/**
+ * Region of the optional read replica, set with AWS_DYNAMODB_READ_REGION and/or
+ * AWS_DYNAMODB_READ_ENDPOINT. A custom endpoint defaults to the AWS_REGION name.
+ */
+fn get_read_region(region_name: Option<String>) -> Result<Option<Region>, EngineError> {
+    let read_region_name = std::env::var("AWS_DYNAMODB_READ_REGION").ok();
+    let read_endpoint = std::env::var("AWS_DYNAMODB_READ_ENDPOINT").ok();
+
+    match (read_region_name, read_endpoint) {
+        (name, Some(endpoint)) => Ok(Some(Region::Custom {
+            name: name
+                .or(region_name)
+                .unwrap_or_else(|| Region::default().name().to_owned()),
+            endpoint,
+        })),
+        (Some(name), None) => match name.parse::<Region>() {
+            Ok(region) => Ok(Some(region)),
+            Err(_) => Err(EngineError::Manager(format!(
+                "Invalid AWS_DYNAMODB_READ_REGION {}",
+                name
+            ))),
+        },
+        (None, None) => Ok(None),
+    }
 }
 
 pub fn get_db<'a>(db: &'a mut Database) -> Result<&'a mut DynamoDbClient, EngineError> {
@@ -110,7 +141,7 @@ pub struct BotKeys {
 
 impl Bot {
     pub fn get_hash(id: &str) -> String {
-        format!("bot#{}", id)
+        format!("{}bot#{}", get_hash_prefix(), id)
     }
 
     pub fn get_range(version_id: &str) -> String {
@@ -143,11 +174,23 @@ pub struct ConversationKeys {
     pub range: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConversationSummary {
+    pub id: String,
+    pub first_message_at: Option<String>,
+    pub last_message_at: Option<String>,
+    #[serde(default)]
+    pub message_count: i64,
+    pub created_at: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Conversation {
     pub hash: String,
     pub range: String,
     pub range_time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_last_message: Option<String>,
     pub class: String,
     pub id: String,
     pub client: Option<Client>,
@@ -158,6 +201,14 @@ pub struct Conversation {
     pub step_id: String,
     pub status: String,
     pub last_interaction_at: String,
+    // dates and number of the messages, updated when they are written. The conversations
+    // written before they were introduced are not in the LastMessageIndex
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_message_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message_at: Option<String>,
+    #[serde(default)]
+    pub message_count: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<i64>,
     pub updated_at: String,
@@ -183,9 +234,18 @@ impl Conversation {
      * hash = bot_id:xxxx#channel_id:xxxx#user_id:xxxx
      * range = conversation#OPEN|CLOSED#id
      * range_time = conversation#OPEN|CLOSED#timestamp#id
+     * range_last_message = conversation#last_message_at#id, the creation date stands for
+     * the last message until the first one is written. It is the sort key of the
+     * LastMessageIndex (hash, range_last_message), projecting all the attributes
      */
-    pub fn new(client: &Client, flow_id: &str, step_id: &str, expires_at: Option<i64>) -> Self {
-        let id = Uuid::new_v4().to_string();
+    pub fn new(
+        id: &str,
+        client: &Client,
+        flow_id: &str,
+        step_id: &str,
+        expires_at: Option<i64>,
+    ) -> Self {
+        let id = id.to_owned();
         let now = get_date_time();
         let status = "OPEN";
         let class_name = "conversation";
@@ -193,6 +253,7 @@ impl Conversation {
             hash: Self::get_hash(client),
             range: Self::get_range("OPEN", &id),
             range_time: make_range(&[class_name, status, &now, &id]),
+            range_last_message: Some(make_range(&[class_name, &now, &id])),
             id,
             client: Some(client.to_owned()),
             bot_id: Some(client.bot_id.to_owned()),
@@ -203,6 +264,9 @@ impl Conversation {
             step_id: step_id.to_owned(),
             status: status.to_owned(),
             last_interaction_at: now.to_owned(),
+            first_message_at: None,
+            last_message_at: None,
+            message_count: 0,
             expires_at,
             updated_at: now.to_owned(),
             created_at: now.to_owned(),
@@ -287,11 +351,10 @@ struct MessageKeys {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct MessageFromDateInfo {
-    class: String,
-    range: String,
-    created_at: String,
+struct MessageTimeKeys {
     hash: String,
+    range: String,
+    range_time: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -310,6 +373,9 @@ pub struct Message {
     pub step_id: String,
     pub message_order: i32,
     pub interaction_order: i32,
+    // position in the conversation, messages written before it was introduced have 0
+    #[serde(default)]
+    pub sequence: i64,
     pub direction: String,
     pub payload: String,
     pub content_type: String,
@@ -415,6 +481,7 @@ impl Message {
             step_id: step_id.to_owned(),
             message_order: message_order,
             interaction_order: interaction_order,
+            sequence: 0,
             direction: direction.to_owned(),
             payload: payload.to_owned(),
             content_type: content_type.to_owned(),
@@ -445,6 +512,9 @@ pub struct State {
     pub _type: String,
     pub key: String,
     pub value: String,
+    // incremented by the conditional writes, 0 for the states saved without condition
+    #[serde(default)]
+    pub version: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<i64>,
     pub created_at: String,
@@ -485,6 +555,7 @@ impl State {
             _type: _type.to_string(),
             key: key.to_owned(),
             value: encrypted_value.to_owned(),
+            version: 0,
             expires_at,
             created_at: now.to_string(),
         }
@@ -0,0 +1,23 @@
+use crate::data::DynamoDbClient;
+use crate::db_connectors::dynamodb::DynamoDbKey;
+use crate::{Client, EngineError};
+
+use crate::db_connectors::dynamodb::utils::*;
+
+/**
+ * Each window of each client has its own counter, stored next to the data of the client
+ * and incremented atomically with an ADD update. It expires with its window.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    window: i64,
+    db: &mut DynamoDbClient,
+) -> Result<i64, EngineError> {
+    let key = serde_dynamodb::to_hashmap(&DynamoDbKey::new(
+        &make_hash(client),
+        &make_range(&["rate_limit", &window_start.to_string()]),
+    ))?;
+
+    execute_sequence_update_query(db, key, 1, Some(window_start + window))
+}
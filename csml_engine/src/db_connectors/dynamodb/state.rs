@@ -1,9 +1,7 @@
 use crate::data::DynamoDbClient;
 use crate::db_connectors::dynamodb::{DynamoDbKey, State, StatDeleteInfo};
-use crate::{
-    encrypt::{decrypt_data, encrypt_data},
-    Client, EngineError,
-};
+use crate::{Client, EngineError};
+use rusoto_core::RusotoError;
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
@@ -32,6 +30,9 @@ pub fn delete_state_key(
     Ok(())
 }
 
+/**
+ * Get a state with its value as saved
+ */
 pub fn get_state_key(
     client: &Client,
     _type: &str,
@@ -56,74 +57,28 @@ pub fn get_state_key(
         Some(val) => {
             let state: State = serde_dynamodb::from_hashmap(val)?;
 
-            let val = serde_json::json!(state);
-            let value = decrypt_data(val["value"].as_str().unwrap().to_string())?;
-            Ok(Some(value))
+            Ok(Some(serde_json::json!(state)))
         }
         _ => Ok(None),
     }
 }
 
-pub fn get_current_state(
-    client: &Client,
-    db: &mut DynamoDbClient,
-) -> Result<Option<serde_json::Value>, EngineError> {
-    let item_key = DynamoDbKey {
-        hash: State::get_hash(client),
-        range: State::get_range("hold", "position"),
-    };
-
-    let input = GetItemInput {
-        table_name: get_table_name()?,
-        key: serde_dynamodb::to_hashmap(&item_key)?,
-        ..Default::default()
-    };
-
-    let future = db.client.get_item(input);
-    let res = db.runtime.block_on(future)?;
-
-    match res.item {
-        Some(val) => {
-            let dynamo_state: State = serde_dynamodb::from_hashmap(val)?;
-
-            let mut state = serde_json::json!(dynamo_state);
-            state["value"] = decrypt_data(state["value"].as_str().unwrap().to_string())?;
-
-            let current_state = serde_json::json!({
-                "client": state["client"],
-                "type": state["type"],
-                "value": state["value"],
-                "created_at": state["created_at"],
-            });
-
-            Ok(Some(current_state))
-        }
-        _ => Ok(None),
-    }
-}
-
-fn format_state_data(
-    client: &Client,
-    _type: &str,
-    keys_values: Vec<(&str, &serde_json::Value)>,
-    expires_at: Option<i64>,
-) -> Result<Vec<State>, EngineError> {
-    let mut vec = vec![];
-    for (key, value) in keys_values.iter() {
-        let encrypted_value = encrypt_data(value)?;
-        vec.push(State::new(client, _type, *key, &encrypted_value, expires_at));
-    }
-    Ok(vec)
-}
-
+/**
+ * Save (key, encrypted value) states of the given type
+ */
 pub fn set_state_items(
     client: &Client,
     _type: &str,
-    keys_values: Vec<(&str, &serde_json::Value)>,
+    items: Vec<(String, String)>,
     expires_at: Option<i64>,
     db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
-    let states = format_state_data(&client, _type, keys_values, expires_at)?;
+    let states: Vec<State> = items
+        .iter()
+        .map(|(key, value)| State::new(client, _type, key, value, expires_at))
+        .collect();
+
+    let mut inputs = vec![];
 
     // We can only use BatchWriteItem on up to 25 items at once,
     // so we need to split the memories to write into chunks of max
@@ -148,14 +103,74 @@ pub fn set_state_items(
             ..Default::default()
         };
 
-        let future = db.client.batch_write_item(input);
-
-        db.runtime.block_on(future)?;
+        inputs.push(input);
     }
 
+    execute_batch_write_queries(db, inputs)?;
+
     Ok(())
 }
 
+/**
+ * Save a state only if its version is still `version`, with a conditional put: a state that
+ * was saved since it was read fails with EngineError::StateConflict
+ */
+pub fn set_state_item_if_version(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    value: &str,
+    version: i64,
+    expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
+) -> Result<(), EngineError> {
+    let mut state = State::new(client, _type, key, value, expires_at);
+    state.version = version + 1;
+
+    // the states saved without condition and the missing ones have the version 0
+    let condition_expr = match version {
+        0 => "attribute_not_exists(#version) OR #version = :version",
+        _ => "#version = :version",
+    };
+
+    let expr_attr_names = [(String::from("#version"), String::from("version"))]
+        .iter()
+        .cloned()
+        .collect();
+
+    let expr_attr_values = [(
+        String::from(":version"),
+        AttributeValue {
+            n: Some(version.to_string()),
+            ..Default::default()
+        },
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = PutItemInput {
+        item: serde_dynamodb::to_hashmap(&state)?,
+        table_name: get_table_name()?,
+        condition_expression: Some(condition_expr.to_owned()),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    match db.runtime.block_on(future) {
+        Ok(_) => Ok(()),
+        Err(RusotoError::Service(PutItemError::ConditionalCheckFailed(_))) => {
+            Err(EngineError::StateConflict(format!(
+                "state {} {} is no longer at the version {}",
+                _type, key, version
+            )))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn query_states(
     client: &Client,
     db: &mut DynamoDbClient,
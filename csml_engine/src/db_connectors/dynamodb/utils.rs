@@ -1,36 +1,42 @@
 use crate::db_connectors::dynamodb::{Bot, Conversation, Memory, Message};
+pub use crate::db_connectors::utils::{get_hash_prefix, make_hash};
 use crate::{
-    data::{DynamoBot, DynamoBotBincode, DynamoDbClient},
-    encrypt::decrypt_data,
-    Client, EngineError,
+    data::{DynamoBot, DynamoBotBincode, DynamoDbClient, DynamoDbMetrics, ReadFrom, RetryConfig},
+    EngineError,
 };
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
 use rusoto_core::RusotoError;
 use rusoto_dynamodb::{
-    BatchGetItemError, BatchGetItemInput, BatchWriteItemError, BatchWriteItemInput, DynamoDb,
-    GetItemError, GetItemInput,
+    AttributeValue, BatchGetItemError, BatchGetItemInput, BatchWriteItemError, BatchWriteItemInput,
+    DynamoDb, GetItemError, GetItemInput, UpdateItemError, UpdateItemInput, WriteRequest,
 };
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashMap;
 use std::{thread, time};
 
-use rand::Rng;
-
-// The maximum back off time in milliseconds (0.5 seconds).
-const RETRY_BASE: u64 = 500;
-// The maximum back off time in milliseconds (1 minute).
-const MAX_INTERVAL_LIMIT: u64 = 60_000;
-// The default maximum elapsed time in milliseconds (10 minutes).
-const MAX_ELAPSED_TIME_MILLIS: u64 = 600_000;
+// The maximum number of write requests of a batch write.
+const BATCH_WRITE_LIMIT: usize = 25;
 
 /**
  * Return the current datetime formatted as YYYY-MM-DDTHH:mm:ss.SSS[Z].
  * For example: 2020-03-12T12:33:42.123Z
  */
 pub fn get_date_time() -> String {
-    return chrono::Utc::now()
+    return crate::clock::now()
         .format("%Y-%m-%dT%H:%M:%S.%3fZ")
         .to_string();
 }
 
+/**
+ * Format a timestamp in seconds like `get_date_time`
+ */
+pub fn get_date_time_from_timestamp(timestamp: i64) -> String {
+    chrono::NaiveDateTime::from_timestamp(timestamp, 0)
+        .format("%Y-%m-%dT%H:%M:%S.%3fZ")
+        .to_string()
+}
+
 /**
  * Return the table's name
  */
@@ -45,16 +51,6 @@ pub fn get_table_name() -> Result<String, EngineError> {
     }
 }
 
-/**
- * Create a hash key from the client info
- */
-pub fn make_hash(client: &Client) -> String {
-    format!(
-        "bot_id:{}#channel_id:{}#user_id:{}",
-        client.bot_id, client.channel_id, client.user_id
-    )
-}
-
 /**
  * Create a serialized range key from given arguments
  */
@@ -70,112 +66,301 @@ pub fn make_range(args: &[&str]) -> String {
 }
 
 /**
- * Batch write query wrapper with exponential backoff in case of exceeded throughput
+ * Span of a query, with the number of retries after exceeded throughput and the elapsed
+ * time recorded when the query ends. Without a subscriber the span is disabled and the
+ * records do nothing. The call, its retries and throttles are also counted in `metrics`.
  */
-pub fn execute_batch_write_query(
-    db: &mut DynamoDbClient,
-    input: BatchWriteItemInput,
+struct QueryTrace {
+    span: tracing::Span,
+    start: time::Instant,
+    retries: u64,
+    metrics: &'static DynamoDbMetrics,
+}
+
+impl QueryTrace {
+    fn new(query: &'static str, metrics: &'static DynamoDbMetrics) -> Self {
+        metrics.record_call();
+
+        Self {
+            span: tracing::debug_span!(
+                "dynamodb_query",
+                query,
+                retries = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty,
+            ),
+            start: time::Instant::now(),
+            retries: 0,
+            metrics,
+        }
+    }
+
+    fn throttle(&self) {
+        self.metrics.record_throttle();
+    }
+
+    fn retry(&mut self) {
+        self.retries += 1;
+        self.metrics.record_retry();
+    }
+}
+
+impl Drop for QueryTrace {
+    fn drop(&mut self) {
+        let elapsed_ms = self.start.elapsed().as_millis() as u64;
+
+        self.span.record("retries", &self.retries);
+        self.span.record("elapsed_ms", &elapsed_ms);
+    }
+}
+
+fn count_write_requests(request_items: &HashMap<String, Vec<WriteRequest>>) -> usize {
+    request_items.values().map(|requests| requests.len()).sum()
+}
+
+/**
+ * Split a batch write in batches of at most BATCH_WRITE_LIMIT write requests, the limit of DynamoDB
+ */
+fn split_batch_write_input(mut input: BatchWriteItemInput) -> Vec<BatchWriteItemInput> {
+    if count_write_requests(&input.request_items) <= BATCH_WRITE_LIMIT {
+        return vec![input];
+    }
+
+    let requests: Vec<(String, WriteRequest)> = std::mem::take(&mut input.request_items)
+        .into_iter()
+        .flat_map(|(table, requests)| {
+            requests
+                .into_iter()
+                .map(move |request| (table.clone(), request))
+        })
+        .collect();
+
+    requests
+        .chunks(BATCH_WRITE_LIMIT)
+        .map(|chunk| {
+            let mut request_items: HashMap<String, Vec<WriteRequest>> = HashMap::new();
+            for (table, request) in chunk {
+                request_items
+                    .entry(table.to_owned())
+                    .or_default()
+                    .push(request.to_owned());
+            }
+
+            BatchWriteItemInput {
+                request_items,
+                ..input.clone()
+            }
+        })
+        .collect()
+}
+
+/**
+ * Send a batch write request and retry it with exponential backoff in case of exceeded throughput.
+ * The items left unprocessed by DynamoDB are sent again with the same backoff.
+ * The total retry time is bounded by the max_elapsed_millis of `retry_config` from `start`.
+ */
+async fn batch_write_with_backoff(
+    client: &rusoto_dynamodb::DynamoDbClient,
+    mut input: BatchWriteItemInput,
+    retry_config: RetryConfig,
+    metrics: &'static DynamoDbMetrics,
+    start: time::Instant,
 ) -> Result<(), RusotoError<BatchWriteItemError>> {
     let mut retry_times = 1;
+    let mut trace = QueryTrace::new("execute_batch_write_query", metrics);
 
-    let mut rng = rand::thread_rng();
-    let now = time::Instant::now();
     loop {
-        match db
-            .runtime
-            .block_on(db.client.batch_write_item(input.clone()))
-        {
-            Ok(_) => return Ok(()),
-            // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
-            Err(RusotoError::Service(BatchWriteItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
-
-                thread::sleep(duration);
+        let err = match client.batch_write_item(input.clone()).await {
+            Ok(output) => match output.unprocessed_items {
+                Some(items) if !items.is_empty() => {
+                    let err = format!("{} unprocessed items", count_write_requests(&items));
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
-                    return Err(RusotoError::Service(
-                        BatchWriteItemError::ProvisionedThroughputExceeded(err),
-                    ));
+                    input.request_items = items;
+                    err
                 }
+                _ => return Ok(()),
+            },
+            // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
+            Err(RusotoError::Service(BatchWriteItemError::ProvisionedThroughputExceeded(err))) => {
+                trace.throttle();
+                err
             }
             Err(err) => return Err(err),
+        };
+
+        let delay = retry_config.get_delay(retry_times);
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        if delay.is_none() || retry_config.is_elapsed(start) {
+            // give up after max_retries retries or max_elapsed_millis
+            return Err(RusotoError::Service(
+                BatchWriteItemError::ProvisionedThroughputExceeded(err),
+            ));
         }
+        trace.retry();
         retry_times += 1;
     }
 }
 
 /**
- * Batch get query wrapper with exponential backoff in case of exceeded throughput
+ * Connectivity errors: the request could not be sent or dynamodb answered with a server error.
+ * They may succeed later, unlike the errors caused by the request itself.
+ */
+pub fn is_transient_error<E>(err: &RusotoError<E>) -> bool {
+    match err {
+        RusotoError::HttpDispatch(_) => true,
+        RusotoError::Unknown(response) => response.status.is_server_error(),
+        _ => false,
+    }
+}
+
+/**
+ * Error handling of the reads of optional data: when `db.fail_open` is set, a transient error
+ * is logged and the read returns an empty result. The other errors are always returned.
+ * Writes never go through this function, they fail closed.
  */
-pub fn execute_bot_version_batch_get_query(
+pub fn fail_open_read<T: Default, E: std::error::Error + 'static>(
+    db: &DynamoDbClient,
+    query: &str,
+    err: RusotoError<E>,
+) -> Result<T, RusotoError<E>> {
+    if !db.fail_open || !is_transient_error(&err) {
+        return Err(err);
+    }
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("{}: dynamodb is unreachable, read as empty: {}", query, err),
+        ),
+        LogLvl::Warn,
+    );
+
+    Ok(T::default())
+}
+
+/**
+ * Batch write query wrapper with exponential backoff in case of exceeded throughput.
+ * Inputs larger than BATCH_WRITE_LIMIT items are split in several batches.
+ */
+pub fn execute_batch_write_query(
     db: &mut DynamoDbClient,
-    input: BatchGetItemInput,
-) -> Result<Vec<serde_json::Value>, EngineError> {
+    input: BatchWriteItemInput,
+) -> Result<(), RusotoError<BatchWriteItemError>> {
+    execute_batch_write_queries(db, vec![input])
+}
+
+/**
+ * Execute several batch write queries in parallel, at most `db.pool_size` at the same time.
+ * Inputs larger than BATCH_WRITE_LIMIT items are split in several batches first.
+ * Each query keeps its own exponential backoff, the whole operation is bounded by the
+ * max_elapsed_millis of `db.retry_config`.
+ */
+pub fn execute_batch_write_queries(
+    db: &mut DynamoDbClient,
+    inputs: Vec<BatchWriteItemInput>,
+) -> Result<(), RusotoError<BatchWriteItemError>> {
+    let now = time::Instant::now();
+    let client = &db.client;
+    let retry_config = db.retry_config;
+    let metrics = db.metrics;
+
+    let queries = stream::iter(inputs.into_iter().flat_map(split_batch_write_input))
+        .map(|input| batch_write_with_backoff(client, input, retry_config, metrics, now))
+        .buffer_unordered(db.pool_size)
+        .try_collect::<Vec<()>>();
+
+    db.runtime.block_on(queries)?;
+
+    Ok(())
+}
+
+/**
+ * Atomically add `count` to the `sequence` counter of the item at `key` (created at 0 if it
+ * does not exist) and return the new value, concurrent calls never get the same numbers.
+ * Exponential backoff in case of exceeded throughput, the counter is always on the primary.
+ */
+pub fn execute_sequence_update_query(
+    db: &mut DynamoDbClient,
+    key: HashMap<String, AttributeValue>,
+    count: i64,
+    expires_at: Option<i64>,
+) -> Result<i64, EngineError> {
+    let mut update_expr = "ADD #sequence :count".to_owned();
+    let mut expr_attr_values = HashMap::new();
+    expr_attr_values.insert(
+        ":count".to_owned(),
+        AttributeValue {
+            n: Some(count.to_string()),
+            ..Default::default()
+        },
+    );
+
+    // the counter expires with the messages it numbers
+    if let Some(expires_at) = expires_at {
+        update_expr = format!("{} SET expires_at = :expiresAt", update_expr);
+        expr_attr_values.insert(
+            ":expiresAt".to_owned(),
+            AttributeValue {
+                n: Some(expires_at.to_string()),
+                ..Default::default()
+            },
+        );
+    }
+
+    let input = UpdateItemInput {
+        table_name: get_table_name()?,
+        key,
+        update_expression: Some(update_expr),
+        expression_attribute_names: Some(
+            [("#sequence".to_owned(), "sequence".to_owned())]
+                .iter()
+                .cloned()
+                .collect(),
+        ),
+        expression_attribute_values: Some(expr_attr_values),
+        return_values: Some("UPDATED_NEW".to_owned()),
+        ..Default::default()
+    };
+
     let mut retry_times = 1;
+    let mut trace = QueryTrace::new("execute_sequence_update_query", db.metrics);
 
-    let mut rng = rand::thread_rng();
     let now = time::Instant::now();
     loop {
-        match db.runtime.block_on(db.client.batch_get_item(input.clone())) {
+        match db.runtime.block_on(db.client.update_item(input.clone())) {
             Ok(output) => {
-                let items = match output.responses {
-                    None => return Ok(vec![]),
-                    Some(items) if items.len() == 0 => return Ok(vec![]),
-                    Some(items) => items.clone(),
-                };
-                let mut bots = vec![];
-
-                for (_, item) in items {
-                    for item in item {
-                        let data: Bot = serde_dynamodb::from_hashmap(item.to_owned())?;
-
-                        let csml_bot: DynamoBot = match base64::decode(&data.bot) {
-                            Ok(base64decoded) => {
-                                match bincode::deserialize::<DynamoBotBincode>(&base64decoded[..]) {
-                                    Ok(bot) => bot.to_bot(),
-                                    Err(_) => serde_json::from_str(&data.bot).unwrap(),
-                                }
-                            }
-                            Err(_) => serde_json::from_str(&data.bot).unwrap(),
-                        };
-
-                        let mut json = serde_json::json!({
-                            "version_id": data.version_id,
-                            "id": data.id,
-                            "name": csml_bot.name,
-                            "default_flow": csml_bot.default_flow,
-                            "engine_version": data.engine_version,
-                            "created_at": data.created_at
-                        });
-
-                        if let Some(custom_components) = csml_bot.custom_components {
-                            json["custom_components"] = serde_json::json!(custom_components);
-                        }
-
-                        bots.push(json);
-                    }
-                }
+                let sequence = output
+                    .attributes
+                    .and_then(|attributes| attributes.get("sequence")?.n.to_owned())
+                    .and_then(|sequence| sequence.parse::<i64>().ok());
 
-                return Ok(bots);
+                return match sequence {
+                    Some(sequence) => Ok(sequence),
+                    None => Err(EngineError::Manager(
+                        "execute_sequence_update_query: sequence not returned".to_owned(),
+                    )),
+                };
             }
             // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
-            Err(RusotoError::Service(BatchGetItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
-
-                thread::sleep(duration);
+            Err(RusotoError::Service(UpdateItemError::ProvisionedThroughputExceeded(err))) => {
+                trace.throttle();
+                let delay = db.retry_config.get_delay(retry_times);
+                if let Some(delay) = delay {
+                    thread::sleep(delay);
+                }
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
+                if delay.is_none() || db.retry_config.is_elapsed(now) {
+                    // give up after max_retries retries or max_elapsed_millis
                     return Err(RusotoError::Service(
-                        BatchGetItemError::ProvisionedThroughputExceeded(err),
+                        UpdateItemError::ProvisionedThroughputExceeded(err),
                     )
                     .into());
                 }
+                trace.retry();
             }
             Err(err) => return Err(err.into()),
         }
@@ -184,195 +369,235 @@ pub fn execute_bot_version_batch_get_query(
 }
 
 /**
- * Batch get query wrapper with exponential backoff in case of exceeded throughput
+ * Send a batch get request and retry it with exponential backoff in case of exceeded throughput.
+ * The total retry time is bounded by the max_elapsed_millis of `retry_config` from `start`.
  */
-pub fn execute_messages_batch_get_query(
-    db: &mut DynamoDbClient,
+async fn batch_get_with_backoff(
+    client: &rusoto_dynamodb::DynamoDbClient,
     input: BatchGetItemInput,
-) -> Result<Vec<serde_json::Value>, EngineError> {
+    retry_config: RetryConfig,
+    metrics: &'static DynamoDbMetrics,
+    query: &'static str,
+    start: time::Instant,
+) -> Result<Vec<HashMap<String, AttributeValue>>, RusotoError<BatchGetItemError>> {
     let mut retry_times = 1;
+    let mut trace = QueryTrace::new(query, metrics);
 
-    let mut rng = rand::thread_rng();
-    let now = time::Instant::now();
     loop {
-        match db.runtime.block_on(db.client.batch_get_item(input.clone())) {
+        let err = match client.batch_get_item(input.clone()).await {
             Ok(output) => {
-                let items = match output.responses {
-                    None => return Ok(vec![]),
-                    Some(items) if items.len() == 0 => return Ok(vec![]),
-                    Some(items) => items.clone(),
-                };
-                let mut messages = vec![];
-
-                for (_, item) in items {
-                    for item in item {
-                        let message: Message = serde_dynamodb::from_hashmap(item)?;
-
-                        let json = serde_json::json!({
-                            "client": message.client,
-                            "conversation_id": message.conversation_id,
-                            "flow_id": message.flow_id,
-                            "step_id": message.step_id,
-                            "message_order": message.message_order,
-                            "interaction_order": message.interaction_order,
-                            "direction": message.direction,
-                            "payload": decrypt_data(message.payload)?,
-                            "created_at": message.created_at
-                        });
-
-                        messages.push(json)
-                    }
-                }
-
-                return Ok(messages);
+                return Ok(output
+                    .responses
+                    .into_iter()
+                    .flat_map(|responses| responses.into_values())
+                    .flatten()
+                    .collect())
             }
             // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
             Err(RusotoError::Service(BatchGetItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
+                trace.throttle();
+                err
+            }
+            Err(err) => return Err(err),
+        };
 
-                thread::sleep(duration);
+        let delay = retry_config.get_delay(retry_times);
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
-                    return Err(RusotoError::Service(
-                        BatchGetItemError::ProvisionedThroughputExceeded(err),
-                    )
-                    .into());
-                }
-            }
-            Err(err) => return Err(err.into()),
+        if delay.is_none() || retry_config.is_elapsed(start) {
+            // give up after max_retries retries or max_elapsed_millis
+            return Err(RusotoError::Service(
+                BatchGetItemError::ProvisionedThroughputExceeded(err),
+            ));
         }
+        trace.retry();
         retry_times += 1;
     }
 }
 
 /**
- * Batch get query wrapper with exponential backoff in case of exceeded throughput
+ * Execute several batch get queries in parallel, at most `db.pool_size` at the same time,
+ * sent to the database selected by `read_from`. The items of all the queries are returned
+ * together, in no particular order.
  */
-pub fn execute_memory_batch_get_query(
+fn execute_batch_get_queries(
     db: &mut DynamoDbClient,
-    input: BatchGetItemInput,
-) -> Result<Vec<serde_json::Value>, EngineError> {
-    let mut retry_times = 1;
-
-    let mut rng = rand::thread_rng();
+    inputs: Vec<BatchGetItemInput>,
+    read_from: ReadFrom,
+    query: &'static str,
+) -> Result<Vec<HashMap<String, AttributeValue>>, RusotoError<BatchGetItemError>> {
     let now = time::Instant::now();
-    loop {
-        match db.runtime.block_on(db.client.batch_get_item(input.clone())) {
-            Ok(output) => {
-                let items = match output.responses {
-                    None => return Ok(vec![]),
-                    Some(items) if items.len() == 0 => return Ok(vec![]),
-                    Some(items) => items.clone(),
-                };
-                let mut memories = vec![];
+    let client = db.reader(read_from);
+    let retry_config = db.retry_config;
+    let metrics = db.metrics;
 
-                for (_, item) in items {
-                    for item in item {
-                        let memory: Memory = serde_dynamodb::from_hashmap(item)?;
+    let queries = stream::iter(inputs)
+        .map(|input| batch_get_with_backoff(client, input, retry_config, metrics, query, now))
+        .buffer_unordered(db.pool_size)
+        .try_concat();
 
-                        let json = serde_json::json!({
-                            "key": memory.key,
-                            "value": decrypt_data(memory.value.unwrap())?,
-                            "created_at": memory.created_at,
-                        });
+    db.runtime.block_on(queries)
+}
 
-                        memories.push(json)
-                    }
-                }
+/**
+ * Batch get queries wrapper with exponential backoff in case of exceeded throughput,
+ * sent in parallel to the database selected by `read_from`
+ */
+pub fn execute_bot_version_batch_get_queries(
+    db: &mut DynamoDbClient,
+    inputs: Vec<BatchGetItemInput>,
+    read_from: ReadFrom,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    let items = execute_batch_get_queries(
+        db,
+        inputs,
+        read_from,
+        "execute_bot_version_batch_get_queries",
+    )?;
+    let mut bots = vec![];
 
-                return Ok(memories);
-            }
-            // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
-            Err(RusotoError::Service(BatchGetItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
+    for item in items {
+        let data: Bot = serde_dynamodb::from_hashmap(item)?;
 
-                thread::sleep(duration);
+        let csml_bot: DynamoBot = match base64::decode(&data.bot) {
+            Ok(base64decoded) => match bincode::deserialize::<DynamoBotBincode>(&base64decoded[..]) {
+                Ok(bot) => bot.to_bot(),
+                Err(_) => serde_json::from_str(&data.bot).unwrap(),
+            },
+            Err(_) => serde_json::from_str(&data.bot).unwrap(),
+        };
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
-                    return Err(RusotoError::Service(
-                        BatchGetItemError::ProvisionedThroughputExceeded(err),
-                    )
-                    .into());
-                }
-            }
-            Err(err) => return Err(err.into()),
+        let mut json = serde_json::json!({
+            "version_id": data.version_id,
+            "id": data.id,
+            "name": csml_bot.name,
+            "default_flow": csml_bot.default_flow,
+            "engine_version": data.engine_version,
+            "created_at": data.created_at
+        });
+
+        if let Some(custom_components) = csml_bot.custom_components {
+            json["custom_components"] = serde_json::json!(custom_components);
         }
-        retry_times += 1;
+
+        bots.push(json);
     }
+
+    Ok(bots)
 }
 
 /**
- * Batch get query wrapper with exponential backoff in case of exceeded throughput
+ * Batch get queries wrapper with exponential backoff in case of exceeded throughput,
+ * sent in parallel to the database selected by `read_from`
  */
-pub fn execute_conversations_batch_get_query(
+pub fn execute_messages_batch_get_queries(
     db: &mut DynamoDbClient,
-    input: BatchGetItemInput,
-) -> Result<Vec<Conversation>, EngineError> {
-    let mut retry_times = 1;
+    inputs: Vec<BatchGetItemInput>,
+    read_from: ReadFrom,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    let items =
+        execute_batch_get_queries(db, inputs, read_from, "execute_messages_batch_get_queries")?;
+    let mut messages = vec![];
 
-    let mut rng = rand::thread_rng();
-    let now = time::Instant::now();
-    loop {
-        match db.runtime.block_on(db.client.batch_get_item(input.clone())) {
-            Ok(output) => {
-                let items = match output.responses {
-                    None => return Ok(vec![]),
-                    Some(items) if items.len() == 0 => return Ok(vec![]),
-                    Some(items) => items.clone(),
-                };
-                let mut conversations = vec![];
+    for item in items {
+        let message: Message = serde_dynamodb::from_hashmap(item)?;
 
-                for (_, item) in items {
-                    for item in item {
-                        let conversation: Conversation = serde_dynamodb::from_hashmap(item)?;
+        let json = serde_json::json!({
+            "client": message.client,
+            "conversation_id": message.conversation_id,
+            "flow_id": message.flow_id,
+            "step_id": message.step_id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "sequence": message.sequence,
+            "direction": message.direction,
+            "payload": message.payload,
+            "created_at": message.created_at
+        });
 
-                        conversations.push(conversation)
-                    }
-                }
+        messages.push(json)
+    }
 
-                return Ok(conversations);
-            }
-            // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
-            Err(RusotoError::Service(BatchGetItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
+    Ok(messages)
+}
 
-                thread::sleep(duration);
+/**
+ * Batch get queries wrapper with exponential backoff in case of exceeded throughput,
+ * sent in parallel to the database selected by `read_from`
+ */
+pub fn execute_memory_batch_get_queries(
+    db: &mut DynamoDbClient,
+    inputs: Vec<BatchGetItemInput>,
+    read_from: ReadFrom,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    let query = "execute_memory_batch_get_queries";
+    let items = match execute_batch_get_queries(db, inputs, read_from, query) {
+        Ok(items) => items,
+        Err(err) => return Ok(fail_open_read(db, query, err)?),
+    };
+    let mut memories = vec![];
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
-                    return Err(RusotoError::Service(
-                        BatchGetItemError::ProvisionedThroughputExceeded(err),
-                    )
-                    .into());
-                }
-            }
-            Err(err) => return Err(err.into()),
-        }
-        retry_times += 1;
+    for item in items {
+        let memory: Memory = serde_dynamodb::from_hashmap(item)?;
+
+        let json = serde_json::json!({
+            "key": memory.key,
+            "value": memory.value,
+            "created_at": memory.created_at,
+        });
+
+        memories.push(json)
     }
+
+    Ok(memories)
 }
 
 /**
- * Batch get query wrapper with exponential backoff in case of exceeded throughput
+ * Batch get queries wrapper with exponential backoff in case of exceeded throughput,
+ * sent in parallel to the database selected by `read_from`
+ */
+pub fn execute_conversations_batch_get_queries(
+    db: &mut DynamoDbClient,
+    inputs: Vec<BatchGetItemInput>,
+    read_from: ReadFrom,
+) -> Result<Vec<Conversation>, EngineError> {
+    let items = execute_batch_get_queries(
+        db,
+        inputs,
+        read_from,
+        "execute_conversations_batch_get_queries",
+    )?;
+    let mut conversations = vec![];
+
+    for item in items {
+        let conversation: Conversation = serde_dynamodb::from_hashmap(item)?;
+
+        conversations.push(conversation)
+    }
+
+    Ok(conversations)
+}
+
+/**
+ * Batch get query wrapper with exponential backoff in case of exceeded throughput,
+ * sent to the database selected by `read_from`
  */
 pub fn execute_conversation_get_query(
     db: &mut DynamoDbClient,
     input: GetItemInput,
+    read_from: ReadFrom,
 ) -> Result<Conversation, EngineError> {
     let mut retry_times = 1;
+    let mut trace = QueryTrace::new("execute_conversation_get_query", db.metrics);
 
-    let mut rng = rand::thread_rng();
     let now = time::Instant::now();
     loop {
-        match db.runtime.block_on(db.client.get_item(input.clone())) {
+        match db
+            .runtime
+            .block_on(db.reader(read_from).get_item(input.clone()))
+        {
             Ok(item) => {
                 let conversation: Conversation = serde_dynamodb::from_hashmap(item.item.unwrap())?;
 
@@ -380,22 +605,527 @@ pub fn execute_conversation_get_query(
             }
             // request rate is too high, reduce the frequency of requests and use exponential backoff. "https://docs.aws.amazon.com/amazondynamodb/latest/developerguide/Programming.Errors.html#Programming.Errors.RetryAndBackoff"
             Err(RusotoError::Service(GetItemError::ProvisionedThroughputExceeded(err))) => {
-                let interval = std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
-                let interval_jitter = rng.gen_range(0..interval);
-                let duration = time::Duration::from_millis(interval_jitter);
-
-                thread::sleep(duration);
+                trace.throttle();
+                let delay = db.retry_config.get_delay(retry_times);
+                if let Some(delay) = delay {
+                    thread::sleep(delay);
+                }
 
-                if now.elapsed() >= time::Duration::from_millis(MAX_ELAPSED_TIME_MILLIS) {
-                    // if time elapsed reach the MAX_ELAPSED_TIME_MILLIS return error
+                if delay.is_none() || db.retry_config.is_elapsed(now) {
+                    // give up after max_retries retries or max_elapsed_millis
                     return Err(RusotoError::Service(
                         BatchGetItemError::ProvisionedThroughputExceeded(err),
                     )
                     .into());
                 }
+                trace.retry();
             }
             Err(err) => return Err(err.into()),
         }
         retry_times += 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{set_clock, Clock, SystemClock};
+    use crate::data::Jitter;
+    use rusoto_core::{credential::StaticProvider, HttpClient, Region};
+    use rusoto_dynamodb::{KeysAndAttributes, PutRequest, WriteRequest};
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use std::fmt;
+    use std::sync::Mutex;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+
+    const THROUGHPUT_EXCEEDED: &str = r#"{"__type":"com.amazonaws.dynamodb.v20120810#ProvisionedThroughputExceededException","message":"rate exceeded"}"#;
+
+    /// Mock dynamodb endpoint answering each request with the next (status, body) of
+    /// `responses`, returns its url and the number of requests received
+    fn mock_endpoint(responses: Vec<(u16, &'static str)>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        thread::spawn(move || {
+            for ((status, body), stream) in responses.into_iter().zip(listener.incoming()) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // read the request headers and body before answering
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/x-amz-json-1.0\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    type Fields = HashMap<String, String>;
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_owned(), value.to_owned());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0
+                .insert(field.name().to_owned(), format!("{:?}", value));
+        }
+    }
+
+    /// Subscriber keeping the name and fields of every span
+    #[derive(Clone, Default)]
+    struct SpanRecorder {
+        spans: Arc<Mutex<Vec<(String, Fields)>>>,
+    }
+
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields::new();
+            span.record(&mut FieldVisitor(&mut fields));
+
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name().to_owned(), fields));
+
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+
+            values.record(&mut FieldVisitor(fields));
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &tracing::Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    fn mock_client(endpoint: String) -> rusoto_dynamodb::DynamoDbClient {
+        rusoto_dynamodb::DynamoDbClient::new_with(
+            HttpClient::new().unwrap(),
+            StaticProvider::new_minimal("key".to_owned(), "secret".to_owned()),
+            Region::Custom {
+                name: "local".to_owned(),
+                endpoint,
+            },
+        )
+    }
+
+    fn init_db(primary: String, replica: Option<String>) -> DynamoDbClient {
+        let mut db = DynamoDbClient::with_pool_size(Region::default(), Region::default(), 1);
+        db.client = mock_client(primary);
+        db.read_client = replica.map(mock_client);
+
+        db
+    }
+
+    fn batch_get_input() -> BatchGetItemInput {
+        let mut request_items = HashMap::new();
+        request_items.insert("table".to_owned(), KeysAndAttributes::default());
+
+        BatchGetItemInput {
+            request_items,
+            ..Default::default()
+        }
+    }
+
+    fn batch_write_input() -> BatchWriteItemInput {
+        batch_write_input_of(1)
+    }
+
+    fn batch_write_input_of(count: usize) -> BatchWriteItemInput {
+        let requests = (0..count)
+            .map(|_| WriteRequest {
+                put_request: Some(PutRequest::default()),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut request_items = HashMap::new();
+        request_items.insert("table".to_owned(), requests);
+
+        BatchWriteItemInput {
+            request_items,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reads_hit_the_replica_and_writes_the_primary() {
+        let (primary, primary_requests) = mock_endpoint(vec![(200, "{}"), (200, "{}")]);
+        let (replica, replica_requests) = mock_endpoint(vec![(200, r#"{"Responses":{}}"#)]);
+        let mut db = init_db(primary, Some(replica));
+
+        let messages =
+            execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Replica)
+                .unwrap();
+        assert!(messages.is_empty());
+        assert_eq!(replica_requests.load(Ordering::SeqCst), 1);
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 0);
+
+        execute_batch_write_query(&mut db, batch_write_input()).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 1);
+
+        // consistency sensitive reads opt into the primary
+        execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 2);
+        assert_eq!(replica_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn replica_reads_fall_back_on_the_primary() {
+        let (primary, primary_requests) = mock_endpoint(vec![(200, "{}")]);
+        let mut db = init_db(primary, None);
+
+        execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Replica).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn replica_reads_back_off() {
+        let (primary, primary_requests) = mock_endpoint(vec![]);
+        let (replica, replica_requests) = mock_endpoint(vec![
+            (400, THROUGHPUT_EXCEEDED),
+            (400, THROUGHPUT_EXCEEDED),
+            (200, r#"{"Responses":{}}"#),
+        ]);
+        let mut db = init_db(primary, Some(replica));
+
+        execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Replica).unwrap();
+        assert_eq!(replica_requests.load(Ordering::SeqCst), 3);
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn batch_gets_are_all_sent() {
+        let (primary, primary_requests) = mock_endpoint(vec![(200, r#"{"Responses":{}}"#); 3]);
+        let mut db = init_db(primary, None);
+
+        let inputs = vec![batch_get_input(), batch_get_input(), batch_get_input()];
+        execute_messages_batch_get_queries(&mut db, inputs, ReadFrom::Primary).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 3);
+
+        // nothing is sent without keys to read
+        execute_memory_batch_get_queries(&mut db, vec![], ReadFrom::Primary).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn sequence_update_returns_the_counter() {
+        std::env::set_var("AWS_DYNAMODB_TABLE", "table");
+        let (primary, primary_requests) = mock_endpoint(vec![
+            (400, THROUGHPUT_EXCEEDED),
+            (200, r#"{"Attributes":{"sequence":{"N":"5"}}}"#),
+            (200, "{}"),
+        ]);
+        let (replica, replica_requests) = mock_endpoint(vec![]);
+        let mut db = init_db(primary, Some(replica));
+
+        let sequence = execute_sequence_update_query(&mut db, HashMap::new(), 3, None).unwrap();
+        assert_eq!(sequence, 5);
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 2);
+        assert_eq!(replica_requests.load(Ordering::SeqCst), 0);
+
+        // the counter must be returned to know the reserved numbers
+        assert!(execute_sequence_update_query(&mut db, HashMap::new(), 3, None).is_err());
+    }
+
+    #[test]
+    fn query_span_records_the_retries() {
+        let (primary, _) = mock_endpoint(vec![]);
+        let (replica, _) = mock_endpoint(vec![
+            (400, THROUGHPUT_EXCEEDED),
+            (400, THROUGHPUT_EXCEEDED),
+            (200, r#"{"Responses":{}}"#),
+        ]);
+        let mut db = init_db(primary, Some(replica));
+        let recorder = SpanRecorder::default();
+
+        tracing::subscriber::with_default(recorder.clone(), || {
+            execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Replica)
+                .unwrap();
+        });
+
+        let spans = recorder.spans.lock().unwrap();
+        let (_, fields) = spans
+            .iter()
+            .find(|(name, _)| name == "dynamodb_query")
+            .unwrap();
+        assert_eq!(fields["query"], "execute_messages_batch_get_queries");
+        assert_eq!(fields["retries"], "2");
+        assert!(fields.contains_key("elapsed_ms"));
+    }
+
+    #[test]
+    fn large_batch_writes_are_split() {
+        let batches = split_batch_write_input(batch_write_input_of(60));
+        let sizes: Vec<usize> = batches
+            .iter()
+            .map(|input| count_write_requests(&input.request_items))
+            .collect();
+        assert_eq!(sizes, vec![25, 25, 10]);
+
+        let (primary, primary_requests) =
+            mock_endpoint(vec![(200, "{}"), (200, "{}"), (200, "{}")]);
+        let mut db = init_db(primary, None);
+
+        execute_batch_write_query(&mut db, batch_write_input_of(60)).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn unprocessed_items_are_written_again() {
+        let (primary, primary_requests) = mock_endpoint(vec![
+            (
+                200,
+                r#"{"UnprocessedItems":{"table":[{"PutRequest":{"Item":{}}}]}}"#,
+            ),
+            (200, r#"{"UnprocessedItems":{}}"#),
+            (200, "{}"),
+            (200, "{}"),
+        ]);
+        let mut db = init_db(primary, None);
+
+        // the unprocessed item of the first batch is sent again before the next batches
+        execute_batch_write_query(&mut db, batch_write_input_of(60)).unwrap();
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 4);
+    }
+
+    fn fast_retry_config(max_retries: Option<u64>, max_elapsed_millis: u64) -> RetryConfig {
+        RetryConfig {
+            base_millis: 1,
+            max_interval_millis: 10,
+            max_elapsed_millis,
+            max_retries,
+            jitter: Jitter::Full,
+        }
+    }
+
+    #[test]
+    fn retries_give_up_after_max_retries() {
+        let (primary, primary_requests) = mock_endpoint(vec![(400, THROUGHPUT_EXCEEDED); 6]);
+        let mut db = init_db(primary, None).with_retry_config(fast_retry_config(Some(2), 60_000));
+
+        let err = execute_batch_write_query(&mut db, batch_write_input()).unwrap_err();
+        assert!(matches!(
+            err,
+            RusotoError::Service(BatchWriteItemError::ProvisionedThroughputExceeded(_))
+        ));
+        // the first request and 2 retries
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 3);
+
+        let (primary, primary_requests) = mock_endpoint(vec![(400, THROUGHPUT_EXCEEDED); 6]);
+        let mut db = init_db(primary, None).with_retry_config(fast_retry_config(Some(1), 60_000));
+
+        assert!(
+            execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary)
+                .is_err()
+        );
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn retries_give_up_after_max_elapsed_time() {
+        let (primary, primary_requests) = mock_endpoint(vec![(400, THROUGHPUT_EXCEEDED); 6]);
+        let mut db = init_db(primary, None).with_retry_config(fast_retry_config(None, 0));
+
+        assert!(execute_batch_write_query(&mut db, batch_write_input()).is_err());
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 1);
+
+        let (primary, primary_requests) = mock_endpoint(vec![(400, THROUGHPUT_EXCEEDED); 6]);
+        let mut db = init_db(primary, None).with_retry_config(fast_retry_config(None, 0));
+
+        assert!(
+            execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).is_err()
+        );
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn metrics_count_the_retries_and_throttles() {
+        let metrics: &'static DynamoDbMetrics = Box::leak(Box::new(DynamoDbMetrics::new()));
+        let counters = || (metrics.calls(), metrics.retries(), metrics.throttles());
+
+        let (primary, primary_requests) = mock_endpoint(vec![
+            (400, THROUGHPUT_EXCEEDED),
+            (400, THROUGHPUT_EXCEEDED),
+            (400, THROUGHPUT_EXCEEDED),
+            (200, "{}"),
+            (
+                200,
+                r#"{"UnprocessedItems":{"table":[{"PutRequest":{"Item":{}}}]}}"#,
+            ),
+            (200, "{}"),
+        ]);
+        let mut db = init_db(primary, None)
+            .with_retry_config(fast_retry_config(None, 60_000))
+            .with_metrics(metrics);
+
+        execute_batch_write_query(&mut db, batch_write_input()).unwrap();
+        assert_eq!(counters(), (1, 3, 3));
+
+        // the unprocessed items are sent again without being throttled
+        execute_batch_write_query(&mut db, batch_write_input()).unwrap();
+        assert_eq!(counters(), (2, 4, 3));
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 6);
+
+        // the last throttle is not retried when the query gives up
+        let (primary, _) = mock_endpoint(vec![(400, THROUGHPUT_EXCEEDED); 3]);
+        let mut db = init_db(primary, None)
+            .with_retry_config(fast_retry_config(Some(2), 60_000))
+            .with_metrics(metrics);
+
+        assert!(
+            execute_messages_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary)
+                .is_err()
+        );
+        assert_eq!(counters(), (3, 6, 6));
+    }
+
+    /// Url of a port nothing listens on, the requests fail to connect
+    fn closed_endpoint() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+
+        format!("http://{}", listener.local_addr().unwrap())
+    }
+
+    #[test]
+    fn fail_open_reads_are_empty() {
+        let mut db = init_db(closed_endpoint(), None).with_fail_open(true);
+
+        let memories =
+            execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).unwrap();
+        assert!(memories.is_empty());
+
+        // server errors are transient too
+        let (primary, primary_requests) = mock_endpoint(vec![(500, "")]);
+        let mut db = init_db(primary, None).with_fail_open(true);
+
+        let memories =
+            execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).unwrap();
+        assert!(memories.is_empty());
+        assert_eq!(primary_requests.load(Ordering::SeqCst), 1);
+
+        // the errors of the request itself are not hidden
+        let (primary, _) = mock_endpoint(vec![(
+            400,
+            r#"{"__type":"com.amazon.coral.validate#ValidationException","message":"invalid"}"#,
+        )]);
+        let mut db = init_db(primary, None).with_fail_open(true);
+
+        assert!(
+            execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).is_err()
+        );
+    }
+
+    #[test]
+    fn reads_fail_closed_by_default() {
+        let mut db = init_db(closed_endpoint(), None);
+
+        assert!(
+            execute_memory_batch_get_queries(&mut db, vec![batch_get_input()], ReadFrom::Primary).is_err()
+        );
+    }
+
+    #[test]
+    fn writes_always_fail_closed() {
+        let mut db = init_db(closed_endpoint(), None).with_fail_open(true);
+
+        let err = execute_batch_write_query(&mut db, batch_write_input()).unwrap_err();
+        assert!(is_transient_error(&err));
+        assert!(matches!(err, RusotoError::HttpDispatch(_)));
+    }
+
+    #[test]
+    fn retry_delays_follow_the_jitter() {
+        let mut config = RetryConfig {
+            max_retries: Some(3),
+            ..RetryConfig::default()
+        };
+
+        for retry_times in 1..=3 {
+            let interval = std::cmp::min(60_000, 500 * 2 * retry_times);
+
+            let delay = config.get_delay(retry_times).unwrap().as_millis() as u64;
+            assert!(delay < interval);
+
+            config.jitter = Jitter::Equal;
+            let delay = config.get_delay(retry_times).unwrap().as_millis() as u64;
+            assert!(delay >= interval / 2 && delay <= interval);
+            config.jitter = Jitter::Full;
+        }
+        assert_eq!(config.get_delay(4), None);
+    }
+
+    #[test]
+    fn timestamps_are_formatted_like_date_times() {
+        assert_eq!(get_date_time_from_timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            get_date_time_from_timestamp(1649425949),
+            "2022-04-08T13:52:29.000Z"
+        );
+        assert_eq!(get_date_time().len(), get_date_time_from_timestamp(0).len());
+    }
+
+    /// Clock always giving the same date
+    struct FixedClock(chrono::DateTime<chrono::Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> chrono::DateTime<chrono::Utc> {
+            self.0
+        }
+    }
+
+    #[test]
+    fn date_times_are_read_from_the_clock() {
+        let date = chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+        set_clock(Box::new(FixedClock(date.with_timezone(&chrono::Utc))));
+
+        let date_time = get_date_time();
+        set_clock(Box::new(SystemClock));
+
+        assert_eq!(date_time, "2024-01-02T03:04:05.678Z");
+    }
+}
@@ -0,0 +1,715 @@
+/**
+ * Connector keeping the data in memory, meant for the tests of the bots using the engine.
+ *
+ * It is registered with `InMemoryConnector::register` and used when ENGINE_DB_TYPE is set
+ * to `InMemoryConnector::DB_TYPE`. All its instances share the same store, in which the
+ * data of each client is kept until `clear` is called: the ttl of the bots is ignored.
+ *
+ * The data is saved encrypted like with the other databases. The memories and messages
+ * can be seeded before a request, and the ones saved by the requests are returned
+ * decrypted by `take_writes`.
+ */
+use crate::db_connectors::{
+    connector::{Connector, EncryptedMessage, Interaction},
+    DbConversation, MessageCursor,
+};
+use crate::encrypt::decrypt_data;
+use crate::{Client, EngineError};
+use crate::lock::lock_or_recover;
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/**
+ * Messages and memories saved for a client since the last call to `take_writes`.
+ * The messages are in their saving order, with their payloads decrypted.
+ */
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InMemoryWrites {
+    pub messages: Vec<serde_json::Value>,
+    pub memories: serde_json::Map<String, serde_json::Value>,
+    pub deleted_memories: Vec<String>,
+    // number of calls saving memories, the memories of a turn are saved in a single call
+    pub memory_batches: usize,
+}
+
+pub struct InMemoryConnector {
+    // the data seeded by the tests is not part of the writes
+    record_writes: bool,
+}
+
+#[derive(Default)]
+struct ClientData {
+    // in their saving order, with their payloads as saved
+    messages: Vec<serde_json::Value>,
+    memories: HashMap<String, serde_json::Value>,
+    conversations: Vec<DbConversation>,
+    states: HashMap<(String, String), serde_json::Value>,
+    // (window_start, count) of the current rate limit window
+    request_count: (i64, i64),
+    written_messages: Vec<serde_json::Value>,
+    written_memories: Vec<(String, String)>,
+    deleted_memories: Vec<String>,
+    memory_batches: usize,
+}
+
+// data of the clients by (bot_id, channel_id, user_id)
+type Store = HashMap<(String, String, String), ClientData>;
+
+static STORE: Mutex<Option<Store>> = Mutex::new(None);
+
+const PAGE_SIZE: i64 = 25;
+
+fn store() -> MutexGuard<'static, Option<Store>> {
+    lock_or_recover(&STORE)
+}
+
+fn with_client_data<T>(client: &Client, f: impl FnOnce(&mut ClientData) -> T) -> T {
+    let mut store = store();
+    let key = (
+        client.bot_id.to_owned(),
+        client.channel_id.to_owned(),
+        client.user_id.to_owned(),
+    );
+
+    f(store
+        .get_or_insert_with(HashMap::new)
+        .entry(key)
+        .or_default())
+}
+
+// fixed width, the dates are compared as strings
+fn now() -> String {
+    crate::clock::now()
+        .format("%Y-%m-%dT%H:%M:%S%.6fZ")
+        .to_string()
+}
+
+fn message_position(message: &serde_json::Value) -> Option<(String, i64, i64)> {
+    Some((
+        message["created_at"].as_str()?.to_owned(),
+        message["interaction_order"].as_i64()?,
+        message["message_order"].as_i64()?,
+    ))
+}
+
+fn decrypt_payload(mut message: serde_json::Value) -> Result<serde_json::Value, EngineError> {
+    if let Some(payload) = message["payload"].as_str() {
+        message["payload"] = decrypt_data(payload.to_owned())?;
+    }
+
+    Ok(message)
+}
+
+/**
+ * Split the values in pages of `limit` (at most 25) values, the pagination key is the
+ * number of the next page
+ */
+fn paginate(
+    values: Vec<serde_json::Value>,
+    field: &str,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> serde_json::Value {
+    let page = match pagination_key {
+        Some(paginate) => paginate.parse::<i64>().unwrap_or(1).max(1),
+        None => 1,
+    };
+    let limit = match limit {
+        Some(limit) => std::cmp::min(limit, PAGE_SIZE).max(1),
+        None => PAGE_SIZE,
+    };
+
+    let total = values.len();
+    let start = ((page - 1) * limit) as usize;
+    let page_values: Vec<serde_json::Value> = values
+        .into_iter()
+        .skip(start)
+        .take(limit as usize)
+        .collect();
+
+    match start + page_values.len() < total {
+        true => serde_json::json!({field: page_values, "pagination_key": (page + 1).to_string()}),
+        false => serde_json::json!({ field: page_values }),
+    }
+}
+
+impl Default for InMemoryConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemoryConnector {
+    pub const DB_TYPE: &'static str = "in_memory";
+
+    /**
+     * Use the in-memory connector when ENGINE_DB_TYPE is set to `InMemoryConnector::DB_TYPE`
+     */
+    pub fn register() {
+        crate::db_connectors::connector::register_connector(Self::DB_TYPE, || {
+            Ok(Box::new(InMemoryConnector::new()))
+        });
+    }
+
+    /**
+     * Connector recording the writes of the requests, as created when it is registered
+     */
+    pub fn new() -> Self {
+        InMemoryConnector {
+            record_writes: true,
+        }
+    }
+
+    /**
+     * Save memories of the client, as if they were remembered by a previous request
+     */
+    pub fn seed_memories(
+        client: &Client,
+        memories: &[(&str, serde_json::Value)],
+    ) -> Result<(), EngineError> {
+        let mut connector = InMemoryConnector {
+            record_writes: false,
+        };
+
+        for (key, value) in memories {
+            connector.create_client_memory(client, key.to_string(), value.to_owned(), None)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Save messages of the client in the given interaction, as if they were sent or
+     * received by a previous request
+     */
+    pub fn seed_messages(
+        client: &Client,
+        interaction: &Interaction,
+        messages: &[serde_json::Value],
+    ) -> Result<(), EngineError> {
+        let mut connector = InMemoryConnector {
+            record_writes: false,
+        };
+
+        connector.add_messages_bulk(client, interaction, messages)
+    }
+
+    /**
+     * Get the messages and memories saved for the client since the last call
+     */
+    pub fn take_writes(client: &Client) -> Result<InMemoryWrites, EngineError> {
+        let (messages, memories, deleted_memories, memory_batches) =
+            with_client_data(client, |data| {
+                (
+                    std::mem::take(&mut data.written_messages),
+                    std::mem::take(&mut data.written_memories),
+                    std::mem::take(&mut data.deleted_memories),
+                    std::mem::take(&mut data.memory_batches),
+                )
+            });
+
+        let mut writes = InMemoryWrites {
+            deleted_memories,
+            memory_batches,
+            ..Default::default()
+        };
+        for message in messages {
+            writes.messages.push(decrypt_payload(message)?);
+        }
+        for (key, value) in memories {
+            writes.memories.insert(key, decrypt_data(value)?);
+        }
+
+        Ok(writes)
+    }
+
+    /**
+     * Get all the messages of the client, in their saving order and with their
+     * payloads decrypted
+     */
+    pub fn messages(client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        let messages = with_client_data(client, |data| data.messages.to_owned());
+
+        messages.into_iter().map(decrypt_payload).collect()
+    }
+
+    /**
+     * Get the memories of the client as a {key: value} map
+     */
+    pub fn memories(client: &Client) -> Result<serde_json::Value, EngineError> {
+        InMemoryConnector {
+            record_writes: false,
+        }
+        .internal_use_get_memories(client)
+    }
+
+    /**
+     * Remove all the data of the client
+     */
+    pub fn clear(client: &Client) {
+        with_client_data(client, |data| *data = ClientData::default());
+    }
+}
+
+impl Connector for InMemoryConnector {
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError> {
+        let created_at = now();
+
+        with_client_data(client, |data| {
+            for message in messages {
+                let message = serde_json::json!({
+                    "client": client,
+                    "conversation_id": interaction.conversation_id,
+                    "flow_id": interaction.flow_id,
+                    "step_id": interaction.step_id,
+                    "message_order": message.message_order,
+                    "interaction_order": interaction.interaction_order,
+                    "direction": interaction.direction,
+                    "content_type": message.content_type,
+                    "payload": message.payload,
+                    "created_at": created_at,
+                });
+
+                if self.record_writes {
+                    data.written_messages.push(message.to_owned());
+                }
+                data.messages.push(message);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let messages = with_client_data(client, |data| {
+            data.messages
+                .iter()
+                .rev()
+                .filter(|message| {
+                    let created_at = message["created_at"].as_str().unwrap_or_default();
+                    let timestamp = match chrono::DateTime::parse_from_rfc3339(created_at) {
+                        Ok(date) => date.timestamp(),
+                        Err(_) => return false,
+                    };
+
+                    !matches!(from_date, Some(from) if timestamp < from)
+                        && !matches!(to_date, Some(to) if timestamp > to)
+                })
+                .cloned()
+                .collect()
+        });
+
+        Ok(paginate(messages, "messages", limit, pagination_key))
+    }
+
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        let cursor = cursor.map(|cursor| {
+            (
+                cursor.created_at,
+                cursor.interaction_order as i64,
+                cursor.message_order as i64,
+            )
+        });
+
+        Ok(with_client_data(client, |data| {
+            data.messages
+                .iter()
+                .rev()
+                .filter(|message| match &cursor {
+                    Some(cursor) => message_position(message).as_ref() < Some(cursor),
+                    None => true,
+                })
+                .take(limit as usize)
+                .cloned()
+                .collect()
+        }))
+    }
+
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let created_at = now();
+
+        with_client_data(client, |data| {
+            if self.record_writes {
+                data.memory_batches += 1;
+            }
+
+            for (key, value) in memories {
+                if self.record_writes {
+                    data.written_memories
+                        .push((key.to_owned(), value.to_owned()));
+                }
+
+                data.memories.insert(
+                    key.to_owned(),
+                    serde_json::json!({"key": key, "value": value, "created_at": created_at}),
+                );
+            }
+        });
+
+        Ok(())
+    }
+
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        let mut memories: Vec<serde_json::Value> =
+            with_client_data(client, |data| data.memories.values().cloned().collect());
+
+        // from the most recent one, like the other databases
+        memories.sort_by(|a, b| {
+            let a = (a["created_at"].as_str(), a["key"].as_str());
+            let b = (b["created_at"].as_str(), b["key"].as_str());
+
+            b.cmp(&a)
+        });
+
+        Ok(memories)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        with_client_data(client, |data| {
+            if data.memories.remove(key).is_some() && self.record_writes {
+                data.deleted_memories.push(key.to_owned());
+            }
+        });
+
+        Ok(())
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        with_client_data(client, |data| {
+            let mut keys: Vec<String> = data.memories.drain().map(|(key, _)| key).collect();
+
+            if self.record_writes {
+                keys.sort();
+                data.deleted_memories.append(&mut keys);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let now = now();
+
+        with_client_data(client, |data| {
+            data.conversations.push(DbConversation {
+                id: id.to_owned(),
+                client: client.to_owned(),
+                flow_id: flow_id.to_owned(),
+                step_id: step_id.to_owned(),
+                status: "OPEN".to_owned(),
+                last_interaction_at: now.to_owned(),
+                updated_at: now.to_owned(),
+                created_at: now,
+            })
+        });
+
+        Ok(())
+    }
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError> {
+        let now = now();
+
+        with_client_data(client, |data| {
+            for conversation in data.conversations.iter_mut() {
+                if conversation.id == id {
+                    conversation.status = status.to_owned();
+                    conversation.updated_at = now.to_owned();
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        let now = now();
+
+        with_client_data(client, |data| {
+            for conversation in data.conversations.iter_mut() {
+                if conversation.status == "OPEN" {
+                    conversation.status = "CLOSED".to_owned();
+                    conversation.updated_at = now.to_owned();
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError> {
+        Ok(with_client_data(client, |data| {
+            data.conversations
+                .iter()
+                .rev()
+                .find(|conversation| conversation.status == "OPEN")
+                .cloned()
+        }))
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        let now = now();
+
+        with_client_data(client, |data| {
+            for conversation in data.conversations.iter_mut() {
+                if conversation.id != conversation_id {
+                    continue;
+                }
+
+                if let Some(flow_id) = &flow_id {
+                    conversation.flow_id = flow_id.to_owned();
+                }
+                if let Some(step_id) = &step_id {
+                    conversation.step_id = step_id.to_owned();
+                }
+                conversation.last_interaction_at = now.to_owned();
+                conversation.updated_at = now.to_owned();
+            }
+        });
+
+        Ok(())
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let mut conversations = with_client_data(client, |data| data.conversations.to_owned());
+
+        // from the most recently updated one
+        conversations.reverse();
+        conversations.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+
+        let conversations = conversations
+            .iter()
+            .map(|conversation| serde_json::json!(conversation))
+            .collect();
+
+        Ok(paginate(
+            conversations,
+            "conversations",
+            limit,
+            pagination_key,
+        ))
+    }
+
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        // (conversation_id, started_at, last_message_at, message_count) of the saved
+        // conversations, then of the conversations only known by their messages.
+        // A conversation without messages starts at its creation
+        let mut summaries: Vec<(String, String, Option<String>, i64)> = vec![];
+
+        with_client_data(client, |data| {
+            for conversation in data.conversations.iter() {
+                summaries.push((
+                    conversation.id.to_owned(),
+                    conversation.created_at.to_owned(),
+                    None,
+                    0,
+                ));
+            }
+
+            for message in data.messages.iter() {
+                let conversation_id = message["conversation_id"].as_str().unwrap_or_default();
+                let created_at = message["created_at"].as_str().unwrap_or_default();
+
+                match summaries.iter_mut().find(|(id, ..)| id == conversation_id) {
+                    Some((_, started_at, last_message_at, message_count)) => {
+                        if *message_count == 0 || created_at < started_at.as_str() {
+                            *started_at = created_at.to_owned();
+                        }
+                        if last_message_at.as_deref() < Some(created_at) {
+                            *last_message_at = Some(created_at.to_owned());
+                        }
+                        *message_count += 1;
+                    }
+                    None => summaries.push((
+                        conversation_id.to_owned(),
+                        created_at.to_owned(),
+                        Some(created_at.to_owned()),
+                        1,
+                    )),
+                }
+            }
+        });
+
+        // from the most recent last message, or creation of the conversations without messages
+        summaries.reverse();
+        summaries.sort_by(|a, b| {
+            b.2.as_ref()
+                .unwrap_or(&b.1)
+                .cmp(a.2.as_ref().unwrap_or(&a.1))
+        });
+
+        let summaries = summaries
+            .into_iter()
+            .map(
+                |(conversation_id, started_at, last_message_at, message_count)| {
+                    serde_json::json!({
+                        "conversation_id": conversation_id,
+                        "started_at": started_at,
+                        "last_message_at": last_message_at,
+                        "message_count": message_count,
+                    })
+                },
+            )
+            .collect();
+
+        Ok(paginate(summaries, "conversations", limit, pagination_key))
+    }
+
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let created_at = now();
+
+        with_client_data(client, |data| {
+            for (key, value) in items {
+                let state = serde_json::json!({
+                    "client": client,
+                    "type": _type,
+                    "value": value,
+                    "version": 0,
+                    "created_at": created_at,
+                });
+
+                data.states.insert((_type.to_owned(), key), state);
+            }
+        });
+
+        Ok(())
+    }
+
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        version: i64,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let created_at = now();
+
+        // the version is checked and the state saved under the lock of the store
+        with_client_data(client, |data| {
+            let state_key = (_type.to_owned(), key.to_owned());
+            let current = match data.states.get(&state_key) {
+                Some(state) => state["version"].as_i64().unwrap_or(0),
+                None => 0,
+            };
+            if current != version {
+                return Err(EngineError::StateConflict(format!(
+                    "state {} {} has the version {}, expected {}",
+                    _type, key, current, version
+                )));
+            }
+
+            let state = serde_json::json!({
+                "client": client,
+                "type": _type,
+                "value": value,
+                "version": version + 1,
+                "created_at": created_at,
+            });
+            data.states.insert(state_key, state);
+
+            Ok(())
+        })
+    }
+
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        Ok(with_client_data(client, |data| {
+            data.states
+                .get(&(_type.to_owned(), key.to_owned()))
+                .cloned()
+        }))
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        with_client_data(client, |data| {
+            data.states.remove(&(_type.to_owned(), key.to_owned()));
+        });
+
+        Ok(())
+    }
+
+    fn increment_request_count(
+        &mut self,
+        client: &Client,
+        window_start: i64,
+        _window: i64,
+    ) -> Result<i64, EngineError> {
+        let count = with_client_data(client, |data| {
+            data.request_count = match data.request_count {
+                (start, count) if start == window_start => (start, count + 1),
+                _ => (window_start, 1),
+            };
+
+            data.request_count.1
+        });
+
+        Ok(count)
+    }
+}
@@ -1,20 +1,19 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
+#[cfg(feature = "redis")]
+use crate::db_connectors::{is_redis, redis_connector};
 
 
 use csml_interpreter::data::csml_logs::{LogLvl, CsmlLog, csml_logger};
 
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, ConversationInfo, Database, EngineError, Memory};
+use crate::db_connectors::state;
+#[cfg(feature = "redis")]
 use crate::db_connectors::utils::*;
 use std::collections::HashMap;
 
+// state type of the memories of the conversations
+const CONVERSATION_MEMORY: &str = "conversation_memory";
+
 pub fn add_memories(
     data: &mut ConversationInfo,
     memories: &HashMap<String, Memory>,
@@ -38,28 +37,15 @@ pub fn add_memories(
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let expires_at = get_expires_at_for_mongodb(data.ttl);
-        return mongodb_connector::memories::add_memories(data, &memories, expires_at);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let expires_at = get_expires_at_for_dynamodb(data.ttl);
-        return dynamodb_connector::memories::add_memories(data, &memories, expires_at);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let expires_at = get_expires_at_for_postgresql(data.ttl);
-        return postgresql_connector::memories::add_memories(data, &memories, expires_at);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        let ttl = get_ttl_for_redis(data.ttl);
+        return redis_connector::memories::add_memories(&data.client, memories, ttl, &mut db);
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let expires_at = get_expires_at_for_sqlite(data.ttl);
-        return sqlite_connector::memories::add_memories(data, &memories, expires_at);
+    if let Some(connector) = data.db.connector() {
+        return connector.add_memories(&data.client, memories, data.ttl);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -87,38 +73,20 @@ pub fn create_client_memory(
             None,
             None,
             None,
-            format!("db call save memory {:?} with value {:?}", key, value)
+            format!("db call save memory {:?} with value {:?}", key, Memory::redact_value(&value))
         ),
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_mongodb(ttl);
-        return mongodb_connector::memories::create_client_memory(client, key, value, expires_at, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_dynamodb(ttl);
-
-        return dynamodb_connector::memories::create_client_memory(client, key, value, expires_at, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_postgresql(ttl);
-        return postgresql_connector::memories::create_client_memory(client, &key, &value, expires_at,db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        let ttl = get_ttl_for_redis(ttl);
+        return redis_connector::memories::create_client_memory(client, &key, &value, ttl, &mut db);
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_sqlite(ttl);
-        return sqlite_connector::memories::create_client_memory(client, &key, &value, expires_at,db);
+    if let Some(connector) = db.connector() {
+        return connector.create_client_memory(client, key, value, ttl);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -144,28 +112,14 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::memories::internal_use_get_memories(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::memories::internal_use_get_memories(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::memories::internal_use_get_memories(client, db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        return redis_connector::memories::internal_use_get_memories(client, &mut db);
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::memories::internal_use_get_memories(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.internal_use_get_memories(client);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -194,28 +148,14 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::memories::get_memories(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::memories::get_memories(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::memories::get_memories(client, db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        return redis_connector::memories::get_memories(client, &mut db);
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::memories::get_memories(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.get_memories(client);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -244,28 +184,14 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::memories::get_memory(client, key, db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        return redis_connector::memories::get_memory(client, key, &mut db);
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::memories::get_memory(client, key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::memories::get_memory(client, key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::memories::get_memory(client, key, db);
+    if let Some(connector) = db.connector() {
+        return connector.get_memory(client, key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -292,28 +218,14 @@ pub fn delete_client_memory(client: &Client, key: &str, db: &mut Database) -> Re
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::memories::delete_client_memory(client, key, db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        return redis_connector::memories::delete_client_memory(client, key, &mut db);
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::memories::delete_client_memory(client, key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::memories::delete_client_memory(client, key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::memories::delete_client_memory(client, key, db);
+    if let Some(connector) = db.connector() {
+        return connector.delete_client_memory(client, key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -339,29 +251,45 @@ pub fn delete_client_memories(client: &Client, db: &mut Database) -> Result<(),
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::memories::delete_client_memories(client, db);
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut db = redis_connector::init()?;
+        return redis_connector::memories::delete_client_memories(client, &mut db);
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::memories::delete_client_memories(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.delete_client_memories(client);
     }
 
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::memories::delete_client_memories(client, db);
-    }
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::memories::delete_client_memories(client, db);
+/**
+ * Get the memories of the conversation, they are saved in the state of the client
+ * under the id of the conversation, apart from the memories of the user
+ */
+pub fn get_conversation_memories(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    match state::get_state_key(client, CONVERSATION_MEMORY, conversation_id, db)? {
+        Some(serde_json::Value::Object(memories)) => Ok(memories),
+        _ => Ok(serde_json::Map::new()),
     }
+}
 
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+/**
+ * Save all the memories of the current conversation
+ */
+pub fn save_conversation_memories(data: &mut ConversationInfo) -> Result<(), EngineError> {
+    let memories = serde_json::Value::Object(data.conversation_memories.clone());
+
+    state::set_state_items(
+        &data.client,
+        CONVERSATION_MEMORY,
+        vec![(data.conversation_id.as_str(), &memories)],
+        data.ttl,
+        &mut data.db,
+    )
 }
@@ -1,16 +1,27 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
-
-use crate::db_connectors::utils::*;
+#[cfg(feature = "redis")]
+use crate::db_connectors::is_redis;
+
+use crate::db_connectors::connector::Interaction;
+use crate::db_connectors::memories::add_memories;
+use crate::db_connectors::MessageCursor;
 use crate::error_messages::ERROR_DB_SETUP;
-use crate::{Client, ConversationInfo, Database, EngineError};
+use crate::{Client, ConversationInfo, Database, EngineError, Memory};
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use std::collections::HashMap;
+use std::io::Write;
+
+// maximum number of messages returned in a single page
+const MAX_MESSAGES_PAGE_SIZE: i64 = 100;
+// number of messages read at once when exporting all the messages of a bot
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+fn memories_in_redis() -> bool {
+    #[cfg(feature = "redis")]
+    return is_redis();
+
+    #[cfg(not(feature = "redis"))]
+    false
+}
 
 pub fn add_messages_bulk(
     data: &mut ConversationInfo,
@@ -37,56 +48,71 @@ pub fn add_messages_bulk(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let expires_at = get_expires_at_for_mongodb(data.ttl);
-
-        return mongodb_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
+    if let Some(connector) = data.db.connector() {
+        let interaction = Interaction {
+            conversation_id: &data.conversation_id,
+            flow_id: &data.context.flow,
+            step_id: &data.context.step.get_step(),
             interaction_order,
             direction,
-            expires_at,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let expires_at = get_expires_at_for_dynamodb(data.ttl);
+            ttl: data.ttl,
+        };
 
-        return dynamodb_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction,
-            expires_at,
-        );
+        return connector.add_messages_bulk(&data.client, &interaction, &msgs);
     }
 
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let expires_at = get_expires_at_for_postgresql(data.ttl);
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Save the messages and memories of an interaction. With MongoDB both writes are done
+ * in a single transaction, so that a crash can't save the messages without the memories.
+ */
+pub fn add_messages_and_memories(
+    data: &mut ConversationInfo,
+    msgs: Vec<serde_json::Value>,
+    interaction_order: i32,
+    direction: &str,
+    memories: &HashMap<String, Memory>,
+) -> Result<(), EngineError> {
+    if memories_in_redis() {
+        if !data.low_data {
+            add_messages_bulk(data, msgs, interaction_order, direction)?;
+        }
 
-        return postgresql_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction,
-            expires_at,
-        );
+        return add_memories(data, memories);
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let expires_at = get_expires_at_for_sqlite(data.ttl);
+    let msgs = match data.low_data {
+        true => vec![],
+        false => msgs,
+    };
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&data.client),
+            None,
+            None,
+            format!(
+                "db call save messages {:?} and memories {:?}",
+                msgs,
+                memories.keys()
+            ),
+        ),
+        LogLvl::Debug,
+    );
 
-        return sqlite_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
+    if let Some(connector) = data.db.connector() {
+        let interaction = Interaction {
+            conversation_id: &data.conversation_id,
+            flow_id: &data.context.flow,
+            step_id: &data.context.step.get_step(),
             interaction_order,
             direction,
-            expires_at,
-        );
+            ttl: data.ttl,
+        };
+
+        return connector.add_messages_and_memories(&data.client, &interaction, &msgs, memories);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -109,73 +135,186 @@ pub fn get_client_messages(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
-
-        return mongodb_connector::messages::get_client_messages(
-            client,
-            db,
-            limit,
-            pagination_key,
-            from_date,
-            to_date,
+    if let Some(connector) = db.connector() {
+        return connector.get_client_messages(client, limit, pagination_key, from_date, to_date);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Get the client's messages like get_client_messages, grouped by turn in "turns" instead
+ * of "messages". A turn is a list of consecutive messages of the same conversation and
+ * interaction_order, sorted by message_order. The turns keep the order of the messages.
+ */
+pub fn get_client_messages_by_turn(
+    client: &Client,
+    db: &mut Database,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    from_date: Option<i64>,
+    to_date: Option<i64>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut value = get_client_messages(client, db, limit, pagination_key, from_date, to_date)?;
+
+    if let Some(map) = value.as_object_mut() {
+        let messages = match map.remove("messages") {
+            Some(serde_json::Value::Array(messages)) => messages,
+            _ => vec![],
+        };
+
+        map.insert(
+            "turns".to_owned(),
+            serde_json::json!(group_messages_by_turn(messages)),
         );
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
-
-        match from_date {
-            Some(from_date) => {
-                return dynamodb_connector::messages::get_client_messages_from_date(
-                    db,
-                    limit,
-                    pagination_key,
-                    from_date,
-                    to_date,
-                );
-            }
-            None => {
-                return dynamodb_connector::messages::get_client_messages(
-                    client,
-                    db,
-                    limit,
-                    pagination_key,
-                )
+    Ok(value)
+}
+
+fn group_messages_by_turn(messages: Vec<serde_json::Value>) -> Vec<Vec<serde_json::Value>> {
+    let mut turns: Vec<Vec<serde_json::Value>> = vec![];
+
+    for message in messages {
+        match turns.last_mut() {
+            Some(turn)
+                if turn[0]["conversation_id"] == message["conversation_id"]
+                    && turn[0]["interaction_order"] == message["interaction_order"] =>
+            {
+                turn.push(message)
             }
+            _ => turns.push(vec![message]),
         }
     }
 
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-
-        return postgresql_connector::messages::get_client_messages(
-            client,
-            db,
-            limit,
-            pagination_key,
-            from_date,
-            to_date,
-        );
+    for turn in turns.iter_mut() {
+        turn.sort_by_key(|message| message["message_order"].as_i64());
     }
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-
-        return sqlite_connector::messages::get_client_messages(
-            client,
-            db,
-            limit,
-            pagination_key,
-            from_date,
-            to_date,
-        );
+    turns
+}
+
+/**
+ * Get a page of the client's messages, from the most recent one. The returned next_cursor
+ * points after the last message of the page and is null when there are no more messages.
+ */
+pub fn get_client_messages_page(
+    client: &Client,
+    db: &mut Database,
+    page_size: i64,
+    cursor: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    csml_logger(
+        CsmlLog::new(None, None, None, format!("db call get messages page")),
+        LogLvl::Info,
+    );
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call get messages page"),
+        ),
+        LogLvl::Debug,
+    );
+
+    let page_size = match page_size {
+        page_size if page_size >= 1 => std::cmp::min(page_size, MAX_MESSAGES_PAGE_SIZE),
+        _ => 20,
+    };
+    let cursor = match cursor {
+        Some(cursor) => Some(MessageCursor::decode(&cursor)?),
+        None => None,
+    };
+
+    // one more message than the page size is requested to know if there is a next page
+    let messages = get_messages_after_cursor(client, db, page_size + 1, cursor)?;
+
+    Ok(format_messages_page(messages, page_size))
+}
+
+fn format_messages_page(mut messages: Vec<serde_json::Value>, page_size: i64) -> serde_json::Value {
+    let has_next_page = messages.len() > page_size as usize;
+    messages.truncate(page_size as usize);
+
+    let next_cursor = match messages.last() {
+        Some(last) if has_next_page => {
+            MessageCursor::from_message(last).map(|cursor| cursor.encode())
+        }
+        _ => None,
+    };
+
+    serde_json::json!({"messages": messages, "next_cursor": next_cursor})
+}
+
+fn get_messages_after_cursor(
+    client: &Client,
+    db: &mut Database,
+    limit: i64,
+    cursor: Option<MessageCursor>,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    if let Some(connector) = db.connector() {
+        return connector.get_client_messages_page(client, limit, cursor);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Write all the messages of the bot to the writer as newline-delimited JSON, one page
+ * at a time so that the memory used does not depend on the number of messages.
+ * Returns the number of written messages.
+ */
+pub fn export_bot_messages<W: Write>(
+    bot_id: &str,
+    db: &mut Database,
+    writer: &mut W,
+) -> Result<usize, EngineError> {
+    csml_logger(
+        CsmlLog::new(None, None, None, format!("db call export bot messages")),
+        LogLvl::Info,
+    );
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call export bot messages, bot_id: {:?}", bot_id),
+        ),
+        LogLvl::Debug,
+    );
+
+    let mut count = 0;
+    let mut pagination_key = None;
+
+    loop {
+        let (messages, next_key) = get_bot_messages(bot_id, db, EXPORT_PAGE_SIZE, pagination_key)?;
+
+        for message in messages.iter() {
+            serde_json::to_writer(&mut *writer, message)?;
+            writer.write_all(b"\n")?;
+        }
+        count += messages.len();
+
+        match next_key {
+            Some(key) => pagination_key = Some(key),
+            None => break,
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(count)
+}
+
+fn get_bot_messages(
+    bot_id: &str,
+    db: &mut Database,
+    limit: i64,
+    pagination_key: Option<String>,
+) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+    if let Some(connector) = db.connector() {
+        return connector.get_bot_messages(bot_id, limit, pagination_key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
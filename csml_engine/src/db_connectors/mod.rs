@@ -12,6 +12,8 @@
  *   - MONGODB_DATABASE
  *   - MONGODB_USERNAME
  *   - MONGODB_PASSWORD
+ * The messages and memories of an interaction are saved in a single transaction, which
+ * requires a replica set. On a standalone server they are saved one after the other.
  *
  * - `dynamodb`: requires a DynamoDB-compatible database (on AWS, or dynamodb-local
  * for dev purposes). A S3-compatible storage is also needed for storing bots in the engine.
@@ -24,11 +26,54 @@
  *   - AWS_S3_BUCKET
  *   - AWS_S3_ENDPOINT optional, defaults to the S3 endpoint for the given region
  * Both AWS_REGION AND AWS_DYNAMODB_ENDPOINT must be set to use a custom dynamodb-compatible DB.
+ * The message history reads can be sent to a read replica set with AWS_DYNAMODB_READ_REGION
+ * and/or AWS_DYNAMODB_READ_ENDPOINT, the writes and the reads of the current turn use the primary.
+ * Conversations and messages expire after CSML_CONVERSATION_TTL_DAYS days when set and the
+ * bot has no ttl, the table's TTL attribute must be `expires_at`.
+ * The messages of a conversation are numbered by an atomic counter stored next to them,
+ * which keeps their order when several are created in the same millisecond.
+ * The hold position is saved with a conditional write on its `version` attribute: of two
+ * requests resuming the same hold, the second one fails with EngineError::StateConflict
+ * and can be retried or dropped by the host. The mongodb and postgresql connectors do not check it.
+ *
+ * - `sqlite`: meant for local development and tests, the database file is set with
+ * SQLITE_PATH (formerly SQLITE_URL) and defaults to an in-memory database. The tables are
+ * created on first use. A single connection is shared behind a mutex as SQLite only
+ * allows one writer at a time, each query locks it so the queries wait for each other.
+ * Like with dynamodb, the hold position is saved with a conditional statement on its `version`.
  *
  * If the ENGINE_DB_TYPE env var is not set, mongodb is used by default.
  *
- * To add a new DB type, please use one of the existing templates implementations.
- * Each method of each module must be fully reimplemented in order to extend the "generic"
+ * With the `redis` feature, memories can be kept in a redis instance instead of
+ * the main database by setting REDIS_URL. Memories expire after the bot ttl, or
+ * REDIS_MEMORY_TTL seconds (defaults to one day) if the bot has no ttl.
+ *
+ * The number of requests of each client can be limited to CSML_RATE_LIMIT_MAX_REQUESTS
+ * per window of CSML_RATE_LIMIT_WINDOW seconds (defaults to 60). The counters are kept
+ * in redis when REDIS_URL is set, otherwise in the configured database, and incremented
+ * atomically. A registered connector limits the requests only if it implements
+ * `Connector::increment_request_count`.
+ *
+ * The dynamodb and redis keys can be isolated per tenant with the optional
+ * CSML_HASH_PREFIX env var, which prepends `tenant:{prefix}#` to every hash.
+ * With CSML_HASH_MODE set to `sha256` (defaults to `plain`), the channel and user ids of
+ * the hashes are replaced by a SHA-256 digest to keep them short with long user ids.
+ * The keys saved in the other mode are not found anymore after changing it.
+ *
+ * Other databases can be used by implementing the `Connector` trait and registering
+ * the connector with `register_connector`: ENGINE_DB_TYPE is then set to the name it
+ * was registered with. The messages, memories, conversations and states are read and
+ * written through the trait, which also handles their encryption. Every built-in
+ * connector implements it, the mongodb and postgresql ones without a version check on
+ * their states.
+ *
+ * With the `test-utils` feature, the `InMemoryConnector` keeps the data in memory for
+ * the tests of the bots: it is registered with `InMemoryConnector::register` and used
+ * when ENGINE_DB_TYPE is set to `in_memory`.
+ *
+ * To add a new built-in DB type, please use one of the existing templates implementations.
+ * The new connector implements the `Connector` trait, the methods of the bot, user
+ * and clean_db modules must still be fully reimplemented in order to extend the "generic"
  * implementation at the root of db_connectors directory.
  */
 use crate::data::{Database, EngineError};
@@ -42,13 +87,17 @@ use self::dynamodb as dynamodb_connector;
 use self::mongodb as mongodb_connector;
 #[cfg(feature = "postgresql")]
 use self::postgresql as postgresql_connector;
+#[cfg(feature = "redis")]
+use self::redis as redis_connector;
 #[cfg(feature = "sqlite")]
 use self::sqlite as sqlite_connector;
 
 pub mod bot;
+pub mod connector;
 pub mod conversations;
 pub mod memories;
 pub mod messages;
+pub mod rate_limit;
 pub mod state;
 
 pub mod user;
@@ -57,6 +106,9 @@ pub mod utils;
 
 pub mod db_test;
 
+#[cfg(feature = "test-utils")]
+pub mod in_memory;
+
 use crate::Client;
 
 #[cfg(feature = "dynamo")]
@@ -65,12 +117,14 @@ mod dynamodb;
 mod mongodb;
 #[cfg(feature = "postgresql")]
 mod postgresql;
+#[cfg(feature = "redis")]
+mod redis;
 
 #[cfg(feature = "sqlite")]
 mod sqlite;
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DbConversation {
     pub id: String,
     pub client: Client,
@@ -146,6 +200,44 @@ pub struct BotVersionCreated {
     pub engine_version: String,
 }
 
+/**
+ * Position of a message in the client's message history, used as an opaque
+ * cursor to page through the messages. Messages are ordered from the most recent,
+ * messages created at the same time are ordered by interaction_order then message_order.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessageCursor {
+    pub created_at: String,
+    pub interaction_order: i32,
+    pub message_order: i32,
+}
+
+impl MessageCursor {
+    pub fn from_message(message: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            created_at: message["created_at"].as_str()?.to_owned(),
+            interaction_order: message["interaction_order"].as_i64()? as i32,
+            message_order: message["message_order"].as_i64()? as i32,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        base64::encode(serde_json::json!(self).to_string())
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, EngineError> {
+        let base64decoded = match base64::decode(cursor) {
+            Ok(base64decoded) => base64decoded,
+            Err(_) => return Err(EngineError::Manager(format!("Invalid cursor"))),
+        };
+
+        match serde_json::from_slice(&base64decoded) {
+            Ok(cursor) => Ok(cursor),
+            Err(_) => Err(EngineError::Manager(format!("Invalid cursor"))),
+        }
+    }
+}
+
 impl BotVersion {
     pub fn flatten(&self) -> serde_json::Value {
         let mut value = self.bot.to_json();
@@ -190,7 +282,19 @@ pub fn is_sqlite() -> bool {
     }
 }
 
+#[cfg(feature = "redis")]
+pub fn is_redis() -> bool {
+    match std::env::var("REDIS_URL") {
+        Ok(val) => !val.is_empty(),
+        Err(_) => false,
+    }
+}
+
 pub fn init_db() -> Result<Database, EngineError> {
+    if let Some(db) = connector::init_registered_connector() {
+        return db;
+    }
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         return mongodb_connector::init();
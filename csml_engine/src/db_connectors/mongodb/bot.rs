@@ -26,7 +26,7 @@ pub fn create_bot_version(
     db: &MongoDbClient,
 ) -> Result<String, EngineError> {
     let collection = db.client.collection::<Document>("bot");
-    let time = bson::DateTime::from_chrono(chrono::Utc::now());
+    let time = bson::DateTime::from_chrono(crate::clock::now());
 
     let bot = doc! {
         "bot_id": bot_id,
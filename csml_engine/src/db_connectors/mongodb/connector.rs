@@ -0,0 +1,221 @@
+use crate::db_connectors::{
+    connector::{Connector, EncryptedMessage, Interaction},
+    mongodb::{
+        conversations, get_pagination_key, memories, messages, rate_limit, state,
+        with_transaction,
+    },
+    utils::get_expires_at_for_mongodb,
+    DbConversation, MessageCursor,
+};
+use crate::{Client, EngineError, MongoDbClient};
+
+impl Connector for MongoDbClient {
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_mongodb(interaction.ttl);
+
+        messages::add_messages_bulk(client, interaction, &messages, expires_at, None, self)
+    }
+
+    /**
+     * The messages and memories are saved in a single transaction, so that a crash
+     * can't save the messages without the memories
+     */
+    fn save_messages_and_memories(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+        memories: Vec<(String, String)>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_mongodb(interaction.ttl);
+        let db: &MongoDbClient = self;
+
+        with_transaction(db, |mut session| {
+            messages::add_messages_bulk(
+                client,
+                interaction,
+                &messages,
+                expires_at,
+                session.as_deref_mut(),
+                db,
+            )?;
+
+            memories::add_memories(client, &memories, expires_at, session, db)
+        })
+    }
+
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        messages::get_client_messages(client, self, limit, pagination_key, from_date, to_date)
+    }
+
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        messages::get_client_messages_page(client, self, limit, cursor)
+    }
+
+    fn query_bot_messages(
+        &mut self,
+        bot_id: &str,
+        limit: i64,
+        pagination_key: Option<String>,
+    ) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+        messages::get_bot_messages(bot_id, self, limit, pagination_key)
+    }
+
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_mongodb(ttl);
+
+        memories::add_memories(client, &memories, expires_at, None, self)
+    }
+
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        memories::get_all_memories(client, self)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        memories::delete_client_memory(client, key, self)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        memories::delete_client_memories(client, self)
+    }
+
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_mongodb(ttl);
+
+        conversations::create_conversation(id, flow_id, step_id, client, expires_at, self)?;
+
+        Ok(())
+    }
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError> {
+        conversations::close_conversation(id, client, status, self)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::close_all_conversations(client, self)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError> {
+        conversations::get_latest_open(client, self)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        conversations::update_conversation(conversation_id, client, flow_id, step_id, self)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        conversations::get_client_conversations(client, self, limit, pagination_key)
+    }
+
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+
+        conversations::get_conversation_summaries(client, self, limit, pagination_key)
+    }
+
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        state::set_state_items(client, _type, items, get_expires_at_for_mongodb(ttl), self)
+    }
+
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        _version: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_mongodb(ttl);
+
+        // the states have no version, the state is saved without checking it
+        state::set_state_items(client, _type, vec![(key.to_owned(), value)], expires_at, self)
+    }
+
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_state_key(client, _type, key, self)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        state::delete_state_key(client, _type, key, self)
+    }
+
+    fn increment_request_count(
+        &mut self,
+        client: &Client,
+        window_start: i64,
+        window: i64,
+    ) -> Result<i64, EngineError> {
+        rate_limit::increment_request_count(client, window_start, window, self)
+    }
+}
@@ -2,11 +2,37 @@ use crate::{db_connectors::DbConversation, Client, EngineError, MongoDbClient};
 use bson::{doc, Document};
 use chrono::SecondsFormat;
 
+/**
+ * The conversations are identified by their id field, the ones created before it by the
+ * hex of their ObjectId
+ */
+fn get_conversation_id(conversation: &Document) -> String {
+    match conversation.get_str("id") {
+        Ok(id) => id.to_owned(),
+        Err(_) => conversation.get_object_id("_id").unwrap().to_hex(),
+    }
+}
+
+fn conversation_filter(id: &str, client: &Client) -> Document {
+    let mut filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+
+    match bson::oid::ObjectId::parse_str(id) {
+        Ok(object_id) => filter.insert("$or", vec![doc! { "id": id }, doc! { "_id": object_id }]),
+        Err(_) => filter.insert("id", id),
+    };
+
+    filter
+}
+
 fn format_conversation_struct(
     conversation: bson::document::Document,
 ) -> Result<DbConversation, EngineError> {
     Ok(DbConversation {
-        id: conversation.get_object_id("_id").unwrap().to_hex(), // to_hex bson::oid::ObjectId
+        id: get_conversation_id(&conversation),
         client: bson::from_bson(conversation.get("client").unwrap().to_owned())?,
         flow_id: conversation.get_str("flow_id").unwrap().to_owned(), // to_hex
         step_id: conversation.get_str("step_id").unwrap().to_owned(), // to_hex
@@ -30,6 +56,7 @@ fn format_conversation_struct(
 }
 
 pub fn create_conversation(
+    id: &str,
     flow_id: &str,
     step_id: &str,
     client: &Client,
@@ -37,9 +64,11 @@ pub fn create_conversation(
     db: &MongoDbClient,
 ) -> Result<String, EngineError> {
     let collection = db.client.collection::<Document>("conversation");
-    let time = bson::DateTime::from_chrono(chrono::Utc::now());
+    let time = bson::DateTime::from_chrono(crate::clock::now());
 
+    // the id minted by the engine or given by the host is kept as is, MongoDB mints the _id
     let conversation = doc! {
+        "id": id,
         "client": bson::to_bson(&client)?,
         "flow_id": flow_id,
         "step_id": step_id,
@@ -50,11 +79,9 @@ pub fn create_conversation(
         "created_at": &time
     };
 
-    let inserted = collection.insert_one(conversation.clone(), None)?;
+    collection.insert_one(conversation, None)?;
 
-    let id = inserted.inserted_id.as_object_id().unwrap();
-
-    Ok(id.to_hex())
+    Ok(id.to_owned())
 }
 
 pub fn close_conversation(
@@ -65,12 +92,7 @@ pub fn close_conversation(
 ) -> Result<(), EngineError> {
     let collection = db.client.collection::<Document>("conversation");
 
-    let filter = doc! {
-        "_id": bson::oid::ObjectId::parse_str(id).unwrap(),
-        "client.bot_id": client.bot_id.to_owned(),
-        "client.user_id": client.user_id.to_owned(),
-        "client.channel_id": client.channel_id.to_owned(),
-    };
+    let filter = conversation_filter(id, client);
 
     collection.update_one(
         filter,
@@ -139,12 +161,7 @@ pub fn update_conversation(
 ) -> Result<(), EngineError> {
     let collection = db.client.collection::<Document>("conversation");
 
-    let filter = doc! {
-        "_id": bson::oid::ObjectId::parse_str(conversation_id).unwrap(),
-        "client.bot_id": client.bot_id.to_owned(),
-        "client.user_id": client.user_id.to_owned(),
-        "client.channel_id": client.channel_id.to_owned(),
-    };
+    let filter = conversation_filter(conversation_id, client);
 
     let doc = match (flow_id, step_id) {
         (Some(flow_id), Some(step_id)) => doc! {
@@ -258,3 +275,102 @@ pub fn get_client_conversations(
         false => Ok(serde_json::json!({ "conversations": conversations })),
     }
 }
+
+pub fn get_conversation_summaries(
+    client: &Client,
+    db: &MongoDbClient,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    let collection = db.client.collection::<Document>("message");
+
+    let page = match pagination_key {
+        Some(key) => key.parse::<i64>().unwrap_or(1).max(1),
+        None => 1,
+    };
+    let limit = match limit {
+        Some(limit) => std::cmp::min(limit, 25).max(1),
+        None => 25,
+    };
+
+    // the messages are grouped with the index on (client, conversation_id, created_at), then
+    // the conversations of the client are added to keep the ones without messages, sorted by
+    // their creation ($unionWith needs MongoDB 4.4). One more summary than the page is read
+    // to know if there is a next page
+    let pipeline = vec![
+        doc! { "$match": {
+            "client.bot_id": client.bot_id.to_owned(),
+            "client.channel_id": client.channel_id.to_owned(),
+            "client.user_id": client.user_id.to_owned(),
+        }},
+        doc! { "$group": {
+            "_id": "$conversation_id",
+            "first_message_at": { "$min": "$created_at" },
+            "last_message_at": { "$max": "$created_at" },
+            "message_count": { "$sum": 1 },
+        }},
+        doc! { "$unionWith": {
+            "coll": "conversation",
+            "pipeline": [
+                { "$match": {
+                    "client.bot_id": client.bot_id.to_owned(),
+                    "client.channel_id": client.channel_id.to_owned(),
+                    "client.user_id": client.user_id.to_owned(),
+                }},
+                { "$project": {
+                    "_id": { "$ifNull": ["$id", { "$toString": "$_id" }] },
+                    "created_at": 1,
+                    "message_count": { "$literal": 0 },
+                }},
+            ],
+        }},
+        doc! { "$group": {
+            "_id": "$_id",
+            "first_message_at": { "$min": "$first_message_at" },
+            "last_message_at": { "$max": "$last_message_at" },
+            "message_count": { "$sum": "$message_count" },
+            "created_at": { "$min": "$created_at" },
+        }},
+        doc! { "$addFields": {
+            "started_at": { "$ifNull": ["$first_message_at", "$created_at"] },
+            "sorted_at": { "$ifNull": ["$last_message_at", "$created_at"] },
+        }},
+        doc! { "$sort": { "sorted_at": -1, "_id": -1 } },
+        doc! { "$skip": (page - 1) * limit },
+        doc! { "$limit": limit + 1 },
+    ];
+    let cursor = collection.aggregate(pipeline, None)?;
+
+    let mut conversations = vec![];
+    for doc in cursor {
+        let summary = doc?;
+
+        let format_date = |field: &str| match summary.get_datetime(field) {
+            Ok(date) => Some(
+                date.to_chrono()
+                    .to_rfc3339_opts(SecondsFormat::Millis, true),
+            ),
+            Err(_) => None,
+        };
+
+        conversations.push(serde_json::json!({
+            "conversation_id": summary.get_str("_id").unwrap_or_default(),
+            "started_at": format_date("started_at").unwrap_or_default(),
+            "last_message_at": format_date("last_message_at"),
+            "message_count": summary.get_i32("message_count").unwrap_or_default(),
+        }));
+    }
+
+    match conversations.len() as i64 > limit {
+        true => {
+            conversations.pop();
+            let pagination_key =
+                base64::encode(serde_json::json!((page + 1).to_string()).to_string());
+
+            Ok(
+                serde_json::json!({"conversations": conversations, "pagination_key": pagination_key}),
+            )
+        }
+        false => Ok(serde_json::json!({ "conversations": conversations })),
+    }
+}
@@ -1,111 +1,65 @@
-use crate::{
-    db_connectors::mongodb::get_db,
-    encrypt::{decrypt_data, encrypt_data},
-    Client, ConversationInfo, EngineError, Memory, MongoDbClient,
-};
-use bson::{doc, Bson, Document};
-use std::collections::HashMap;
+use crate::{Client, EngineError, MongoDbClient};
+use bson::{doc, Document};
+use mongodb::sync::ClientSession;
 
 fn format_memories(
-    data: &mut ConversationInfo,
-    memories: &HashMap<String, Memory>,
+    client: &Client,
+    memories: &[(String, String)],
     expires_at: Option<bson::DateTime>,
 ) -> Result<Vec<bson::Document>, EngineError> {
-    let client = bson::to_bson(&data.client)?;
-
-    memories.iter().fold(Ok(vec![]), |vec, (_, mem)| {
-        let time = bson::DateTime::from_chrono(chrono::Utc::now());
-        let value = encrypt_data(&mem.value)?;
-
-        let mut vec = vec?;
-
-        vec.push(doc! {
-            "client": client.clone(),
-            "key": &mem.key,
-            "value": value, // encrypted
-            "expires_at": Bson::Null,
-            "expires_at": expires_at,
-            "created_at": time.clone(),
-            "updated_at": time
-        });
-        Ok(vec)
-    })
+    let client = bson::to_bson(client)?;
+    let time = bson::DateTime::from_chrono(crate::clock::now());
+
+    let docs = memories
+        .iter()
+        .map(|(key, value)| {
+            doc! {
+                "client": client.clone(),
+                "key": key,
+                "value": value, // encrypted
+                "expires_at": expires_at,
+                "created_at": time,
+                "updated_at": time
+            }
+        })
+        .collect();
+
+    Ok(docs)
 }
 
+/**
+ * Save the (key, encrypted value) memories. The memories are never updated: the most
+ * recent memory of a key replaces the previous ones when they are read.
+ */
 pub fn add_memories(
-    data: &mut ConversationInfo,
-    memories: &HashMap<String, Memory>,
+    client: &Client,
+    memories: &[(String, String)],
     expires_at: Option<bson::DateTime>,
+    session: Option<&mut ClientSession>,
+    db: &MongoDbClient,
 ) -> Result<(), EngineError> {
     if memories.is_empty() {
         return Ok(());
     }
 
-    let mem = format_memories(data, memories, expires_at)?;
-    let db = get_db(&data.db)?;
+    let mem = format_memories(client, memories, expires_at)?;
 
     let collection = db.client.collection::<Document>("memory");
-    collection.insert_many(mem, None)?;
-
-    Ok(())
-}
-
-pub fn create_client_memory(
-    client: &Client,
-    key: String,
-    value: serde_json::Value,
-    expires_at: Option<bson::DateTime>,
-    db: &MongoDbClient,
-) -> Result<(), EngineError> {
-    let time = bson::DateTime::from_chrono(chrono::Utc::now());
-    let memory = doc! {
-        "client": bson::to_bson(&client)?,
-        "key": key,
-        "value": encrypt_data(&value)?, // encrypted
-        "expires_at": expires_at,
-        "created_at": &time,
-        "updated_at": time
+    match session {
+        Some(session) => collection.insert_many_with_session(mem, None, session)?,
+        None => collection.insert_many(mem, None)?,
     };
 
-    let collection = db.client.collection::<Document>("memory");
-    collection.insert_one(memory, None)?;
-
     Ok(())
 }
 
-pub fn internal_use_get_memories(
+/**
+ * Get the client's memories from the most recent one, with their values as saved
+ */
+pub fn get_all_memories(
     client: &Client,
     db: &MongoDbClient,
-) -> Result<serde_json::Value, EngineError> {
-    let collection = db.client.collection::<Document>("memory");
-
-    let filter = doc! {
-        "client.bot_id": client.bot_id.to_owned(),
-        "client.user_id": client.user_id.to_owned(),
-        "client.channel_id": client.channel_id.to_owned(),
-    };
-    let find_options = mongodb::options::FindOptions::builder()
-        .sort(doc! { "$natural": -1 })
-        .build();
-
-    let cursor = collection.find(filter, find_options)?;
-    let mut map = serde_json::Map::new();
-
-    for elem in cursor {
-        if let Ok(doc) = elem {
-            let mem: serde_json::Value = bson::from_bson(bson::Bson::Document(doc))?;
-            let value: serde_json::Value = decrypt_data(mem["value"].as_str().unwrap().to_owned())?;
-
-            if !map.contains_key(mem["key"].as_str().unwrap()) {
-                map.insert(mem["key"].as_str().unwrap().to_owned(), value);
-            }
-        }
-    }
-
-    Ok(serde_json::json!(map))
-}
-
-pub fn get_memories(client: &Client, db: &MongoDbClient) -> Result<serde_json::Value, EngineError> {
+) -> Result<Vec<serde_json::Value>, EngineError> {
     let collection = db.client.collection::<Document>("memory");
 
     let filter = doc! {
@@ -120,57 +74,17 @@ pub fn get_memories(client: &Client, db: &MongoDbClient) -> Result<serde_json::V
     let cursor = collection.find(filter, find_options)?;
 
     let mut vec = vec![];
-    for elem in cursor {
-        if let Ok(doc) = elem {
-            let mem: serde_json::Value = bson::from_bson(bson::Bson::Document(doc))?;
-            let value: serde_json::Value = decrypt_data(mem["value"].as_str().unwrap().to_owned())?;
-            let mut memory = serde_json::Map::new();
-
-            memory.insert("key".to_owned(), mem["key"].clone());
-            memory.insert("value".to_owned(), value);
-            memory.insert("created_at".to_owned(), mem["created_at"]["$date"].clone());
-
-            vec.push(memory);
-        }
-    }
-
-    Ok(serde_json::json!(vec))
-}
-
-pub fn get_memory(
-    client: &Client,
-    key: &str,
-    db: &MongoDbClient,
-) -> Result<serde_json::Value, EngineError> {
-    let collection = db.client.collection::<Document>("memory");
-
-    let filter = doc! {
-        "client.bot_id": client.bot_id.to_owned(),
-        "client.user_id": client.user_id.to_owned(),
-        "client.channel_id": client.channel_id.to_owned(),
-        "key": key,
-    };
-    let find_options = mongodb::options::FindOneOptions::builder()
-        .sort(doc! { "$natural": -1 })
-        .build();
-
-    let result = collection.find_one(filter, find_options)?;
-
-    if let Some(doc) = result {
+    for doc in cursor.flatten() {
         let mem: serde_json::Value = bson::from_bson(bson::Bson::Document(doc))?;
-        let mut memory = serde_json::Map::new();
-
-        memory.insert("key".to_owned(), mem["key"].clone());
-        memory.insert(
-            "value".to_owned(),
-            decrypt_data(mem["value"].as_str().unwrap().to_owned())?,
-        );
-        memory.insert("created_at".to_owned(), mem["created_at"]["$date"].clone());
-
-        return Ok(serde_json::json!(memory));
-    } else {
-        return Ok(serde_json::Value::Null);
+
+        vec.push(serde_json::json!({
+            "key": mem["key"],
+            "value": mem["value"],
+            "created_at": mem["created_at"]["$date"],
+        }));
     }
+
+    Ok(vec)
 }
 
 pub fn delete_client_memory(
@@ -1,62 +1,46 @@
 use crate::{
-    db_connectors::{mongodb::get_db, DbMessage},
-    encrypt::{decrypt_data, encrypt_data},
-    Client, ConversationInfo, EngineError, MongoDbClient,
+    db_connectors::{
+        connector::{EncryptedMessage, Interaction},
+        DbMessage, MessageCursor,
+    },
+    Client, EngineError, MongoDbClient,
 };
 use bson::{doc, Document};
 use chrono::SecondsFormat;
+use mongodb::sync::ClientSession;
 
 fn format_messages(
-    data: &ConversationInfo,
-    messages: &[serde_json::Value],
-    interaction_order: i32,
-    direction: &str,
+    client: &Client,
+    interaction: &Interaction,
+    messages: &[EncryptedMessage],
     expires_at: Option<bson::DateTime>,
 ) -> Result<Vec<Document>, EngineError> {
-    messages
+    let client = bson::to_bson(client)?;
+    let time = bson::DateTime::from_chrono(crate::clock::now());
+
+    let docs = messages
         .iter()
-        .enumerate()
-        .map(|(i, var)| {
-            format_message(
-                data,
-                var.clone(),
-                i as i32,
-                interaction_order,
-                direction,
-                expires_at,
-            )
+        .map(|message| {
+            doc! {
+                "client": client.clone(),
+                "conversation_id": interaction.conversation_id,
+                "flow_id": interaction.flow_id,
+                "step_id": interaction.step_id,
+                "message_order": message.message_order,
+                "interaction_order": interaction.interaction_order,
+                "direction": interaction.direction,
+                "payload": &message.payload, // encrypted
+                "expires_at": expires_at,
+                "created_at": time
+            }
         })
-        .collect::<Result<Vec<Document>, EngineError>>()
-}
+        .collect();
 
-fn format_message(
-    data: &ConversationInfo,
-    message: serde_json::Value,
-    msg_order: i32,
-    interaction_order: i32,
-    direction: &str,
-    expires_at: Option<bson::DateTime>,
-) -> Result<Document, EngineError> {
-    let time = bson::DateTime::from_chrono(chrono::Utc::now());
-    let doc = doc! {
-        "client": bson::to_bson(&data.client)?,
-        "conversation_id": &data.conversation_id,
-        "flow_id": &data.context.flow,
-        "step_id": &data.context.step.get_step(),
-        "message_order": msg_order,
-        "interaction_order": interaction_order,
-        "direction": direction,
-        "payload": encrypt_data(&message)?, // encrypted
-        "expires_at": expires_at,
-        "created_at": time
-    };
-
-    Ok(doc)
+    Ok(docs)
 }
 
 fn format_message_struct(message: bson::document::Document) -> Result<DbMessage, EngineError> {
-    let encrypted_payload: String = message.get_str("payload").unwrap().to_owned();
-    let payload = decrypt_data(encrypted_payload)?;
+    let payload = serde_json::json!(message.get_str("payload").unwrap());
 
     Ok(DbMessage {
         id: message.get_object_id("_id").unwrap().to_hex(), // to_hex bson::oid::ObjectId
@@ -77,21 +61,24 @@ fn format_message_struct(message: bson::document::Document) -> Result<DbMessage,
 }
 
 pub fn add_messages_bulk(
-    data: &ConversationInfo,
-    msgs: &[serde_json::Value],
-    interaction_order: i32,
-    direction: &str,
+    client: &Client,
+    interaction: &Interaction,
+    messages: &[EncryptedMessage],
     expires_at: Option<bson::DateTime>,
+    session: Option<&mut ClientSession>,
+    db: &MongoDbClient,
 ) -> Result<(), EngineError> {
-    if msgs.len() == 0 {
+    if messages.is_empty() {
         return Ok(());
     }
-    let docs = format_messages(data, msgs, interaction_order, direction, expires_at)?;
-    let db = get_db(&data.db)?;
+    let docs = format_messages(client, interaction, messages, expires_at)?;
 
     let message = db.client.collection::<Document>("message");
 
-    message.insert_many(docs, None)?;
+    match session {
+        Some(session) => message.insert_many_with_session(docs, None, session)?,
+        None => message.insert_many(docs, None)?,
+    };
 
     Ok(())
 }
@@ -130,7 +117,7 @@ pub fn get_client_messages(
             let from_date = bson::DateTime::from_millis(from_date * 1000);
             let to_date = match to_date {
                 Some(to_date) => bson::DateTime::from_millis(to_date * 1000),
-                None => bson::DateTime::from_chrono(chrono::Utc::now()),
+                None => bson::DateTime::from_chrono(crate::clock::now()),
             };
 
             doc! {
@@ -145,7 +132,7 @@ pub fn get_client_messages(
             let from_date = bson::DateTime::from_millis(from_date * 1000);
             let to_date = match to_date {
                 Some(to_date) => bson::DateTime::from_millis(to_date * 1000),
-                None => bson::DateTime::from_chrono(chrono::Utc::now()),
+                None => bson::DateTime::from_chrono(crate::clock::now()),
             };
 
             doc! {
@@ -215,3 +202,139 @@ pub fn get_client_messages(
         false => Ok(serde_json::json!({ "messages": messages })),
     }
 }
+
+/**
+ * Get a page of all the messages of the bot across its clients, from the oldest one.
+ * The returned pagination key is the id of the last message, None after the last page.
+ */
+pub fn get_bot_messages(
+    bot_id: &str,
+    db: &MongoDbClient,
+    limit: i64,
+    pagination_key: Option<String>,
+) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+    let collection = db.client.collection::<Document>("message");
+
+    let filter = match pagination_key {
+        Some(key) => {
+            let id = match bson::oid::ObjectId::parse_str(&key) {
+                Ok(id) => id,
+                Err(_) => return Err(EngineError::Manager(format!("Invalid pagination_key"))),
+            };
+
+            doc! {
+                "client.bot_id": bot_id,
+                "_id": {"$gt": id},
+            }
+        }
+        None => doc! {
+            "client.bot_id": bot_id,
+        },
+    };
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(30)
+        .limit(limit)
+        .build();
+
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut messages = vec![];
+    let mut last_id = None;
+    for doc in cursor {
+        let message = format_message_struct(doc?)?;
+
+        let json = serde_json::json!({
+            "client": message.client,
+            "conversation_id": message.conversation_id,
+            "flow_id": message.flow_id,
+            "step_id": message.step_id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "direction": message.direction,
+            "payload": message.payload,
+            "created_at": message.created_at,
+        });
+
+        messages.push(json);
+        last_id = Some(message.id);
+    }
+
+    match messages.len() == limit as usize {
+        true => Ok((messages, last_id)),
+        false => Ok((messages, None)),
+    }
+}
+
+pub fn get_client_messages_page(
+    client: &Client,
+    db: &MongoDbClient,
+    limit: i64,
+    cursor: Option<MessageCursor>,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    let collection = db.client.collection::<Document>("message");
+
+    let filter = match cursor {
+        Some(cursor) => {
+            let created_at = match chrono::DateTime::parse_from_rfc3339(&cursor.created_at) {
+                Ok(created_at) => {
+                    bson::DateTime::from_chrono(created_at.with_timezone(&chrono::Utc))
+                }
+                Err(_) => return Err(EngineError::Manager(format!("Invalid cursor"))),
+            };
+
+            doc! {
+                "client.bot_id": client.bot_id.to_owned(),
+                "client.user_id": client.user_id.to_owned(),
+                "client.channel_id": client.channel_id.to_owned(),
+                "$or": [
+                    { "created_at": {"$lt": created_at} },
+                    {
+                        "created_at": created_at,
+                        "interaction_order": {"$lt": cursor.interaction_order}
+                    },
+                    {
+                        "created_at": created_at,
+                        "interaction_order": cursor.interaction_order,
+                        "message_order": {"$lt": cursor.message_order}
+                    },
+                ]
+            }
+        }
+        None => doc! {
+            "client.bot_id": client.bot_id.to_owned(),
+            "client.user_id": client.user_id.to_owned(),
+            "client.channel_id": client.channel_id.to_owned(),
+        },
+    };
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": -1, "interaction_order": -1, "message_order": -1 })
+        .batch_size(30)
+        .limit(limit)
+        .build();
+
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut messages = vec![];
+    for doc in cursor {
+        let message = format_message_struct(doc?)?;
+
+        let json = serde_json::json!({
+            "client": message.client,
+            "conversation_id": message.conversation_id,
+            "flow_id": message.flow_id,
+            "step_id": message.step_id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "direction": message.direction,
+            "payload": message.payload,
+            "created_at": message.created_at,
+        });
+
+        messages.push(json);
+    }
+
+    Ok(messages)
+}
@@ -1,13 +1,36 @@
 pub mod bot;
+mod connector;
 pub mod conversations;
 pub mod memories;
 pub mod messages;
+pub mod rate_limit;
 pub mod state;
 
 use crate::{Database, EngineError, MongoDbClient};
 use bson::{doc, Document};
 use core::time::Duration as CoreDuration;
-use mongodb::{IndexModel, options::IndexOptions};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use mongodb::{
+    error::{ErrorKind, TRANSIENT_TRANSACTION_ERROR, UNKNOWN_TRANSACTION_COMMIT_RESULT},
+    options::IndexOptions,
+    sync::ClientSession,
+    IndexModel,
+};
+use rand::Rng;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{thread, time};
+
+// The base back off time in milliseconds (0.1 seconds).
+const TRANSACTION_RETRY_BASE: u64 = 100;
+// The maximum back off time in milliseconds (5 seconds).
+const TRANSACTION_MAX_INTERVAL_LIMIT: u64 = 5_000;
+// The maximum elapsed time in milliseconds (1 minute), matching the default transaction lifetime.
+const TRANSACTION_MAX_ELAPSED_TIME_MILLIS: u64 = 60_000;
+// MongoDB error code returned by standalone servers for operations using transaction numbers.
+const ILLEGAL_OPERATION_CODE: i32 = 20;
+
+// Set once the deployment is known not to support transactions (i.e. not a replica set)
+static TRANSACTIONS_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
 
 fn create_mongodb_uri() -> Result<String, EngineError> {
     let mut uri = "mongodb://".to_owned();
@@ -50,7 +73,7 @@ pub fn init() -> Result<Database, EngineError> {
     };
 
     let client = mongodb::sync::Client::with_uri_str(&uri)?;
-    let mongodb_client = MongoDbClient::new(client.database(&dbname));
+    let mongodb_client = MongoDbClient::new(client, &dbname);
     create_ttl_indexes(&mongodb_client);
     create_client_indexes(&mongodb_client);
 
@@ -87,6 +110,124 @@ pub fn get_pagination_key(pagination_key: Option<String>) -> Result<Option<Strin
     }
 }
 
+fn has_error_label(err: &EngineError, label: &str) -> bool {
+    match err {
+        EngineError::MongoDB(err) => err.contains_label(label),
+        _ => false,
+    }
+}
+
+fn is_transaction_unsupported(err: &EngineError) -> bool {
+    match err {
+        EngineError::MongoDB(err) => match err.kind.as_ref() {
+            // the driver refuses to start a transaction on a standalone server
+            ErrorKind::SessionsNotSupported | ErrorKind::Transaction { .. } => true,
+            ErrorKind::Command(command_error) => command_error.code == ILLEGAL_OPERATION_CODE,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn set_transactions_unavailable(err: &EngineError) {
+    // warn only the first time, every following write is done sequentially
+    if !TRANSACTIONS_UNAVAILABLE.swap(true, Ordering::Relaxed) {
+        csml_logger(
+            CsmlLog::new(
+                None,
+                None,
+                None,
+                format!(
+                    "MongoDB transactions are not available on this deployment, writes will not be atomic: {:?}",
+                    err
+                ),
+            ),
+            LogLvl::Warn,
+        );
+    }
+}
+
+fn start_transaction(db: &MongoDbClient) -> Result<ClientSession, EngineError> {
+    let mut session = db.connection.start_session(None)?;
+    session.start_transaction(None)?;
+
+    Ok(session)
+}
+
+fn commit_transaction(session: &mut ClientSession) -> Result<(), EngineError> {
+    loop {
+        match session.commit_transaction() {
+            Ok(_) => return Ok(()),
+            Err(err) if err.contains_label(UNKNOWN_TRANSACTION_COMMIT_RESULT) => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+fn transaction_backoff(retry_times: u64, start: time::Instant) -> bool {
+    if start.elapsed() >= time::Duration::from_millis(TRANSACTION_MAX_ELAPSED_TIME_MILLIS) {
+        return false;
+    }
+
+    let interval = std::cmp::min(
+        TRANSACTION_MAX_INTERVAL_LIMIT,
+        TRANSACTION_RETRY_BASE * 2 * retry_times,
+    );
+    let interval_jitter = rand::thread_rng().gen_range(0..interval);
+    thread::sleep(time::Duration::from_millis(interval_jitter));
+
+    true
+}
+
+/**
+ * Run the given writes in a single transaction, the whole transaction is retried with
+ * exponential backoff in case of transient transaction errors.
+ * Deployments without transactions (standalone servers) run the writes sequentially instead.
+ */
+pub fn with_transaction<F>(db: &MongoDbClient, mut writes: F) -> Result<(), EngineError>
+where
+    F: FnMut(Option<&mut ClientSession>) -> Result<(), EngineError>,
+{
+    if !transactions_available() {
+        return writes(None);
+    }
+
+    let mut retry_times = 1;
+    let now = time::Instant::now();
+
+    loop {
+        let result = start_transaction(db).and_then(|mut session| {
+            match writes(Some(&mut session)).and_then(|_| commit_transaction(&mut session)) {
+                Ok(_) => Ok(()),
+                Err(err) => {
+                    session.abort_transaction().ok();
+                    Err(err)
+                }
+            }
+        });
+
+        match result {
+            Ok(_) => return Ok(()),
+            Err(err) if is_transaction_unsupported(&err) => {
+                // nothing was written, the transaction has been aborted
+                set_transactions_unavailable(&err);
+                return writes(None);
+            }
+            Err(err) if has_error_label(&err, TRANSIENT_TRANSACTION_ERROR) => {
+                if !transaction_backoff(retry_times, now) {
+                    return Err(err);
+                }
+            }
+            Err(err) => return Err(err),
+        }
+        retry_times += 1;
+    }
+}
+
+pub fn transactions_available() -> bool {
+    !TRANSACTIONS_UNAVAILABLE.load(Ordering::Relaxed)
+}
+
 fn create_ttl_indexes(
     db: &MongoDbClient,
 ) {
@@ -139,6 +280,18 @@ fn create_ttl_indexes(
     .options(Some(IndexOptions::builder().expire_after(CoreDuration::new(0, 0)).build()))
     .build();
     state.create_index(index,None).ok();
+
+    // create index expires_at for rate_limit
+    let rate_limit = db.client.collection::<Document>("rate_limit");
+    let index: IndexModel = IndexModel::builder()
+    .keys(
+        doc! {
+            "expires_at": 1
+        }
+    )
+    .options(Some(IndexOptions::builder().expire_after(CoreDuration::new(0, 0)).build()))
+    .build();
+    rate_limit.create_index(index,None).ok();
 }
 
 fn create_client_indexes(
@@ -157,6 +310,17 @@ fn create_client_indexes(
     .build();
     conversation.create_index(index, None).ok();
 
+    // create index of the conversation ids, sparse for the conversations created before them
+    let index: IndexModel = IndexModel::builder()
+    .keys(
+        doc! {
+            "id": 1
+        }
+    )
+    .options(Some(IndexOptions::builder().unique(true).sparse(true).build()))
+    .build();
+    conversation.create_index(index, None).ok();
+
     // create compound client index for memory
     let memory = db.client.collection::<Document>("memory");
     let index: IndexModel = IndexModel::builder()
@@ -183,6 +347,20 @@ fn create_client_indexes(
     .build();
     message.create_index(index,None).ok();
 
+    // create index of the conversation summaries of a client
+    let index: IndexModel = IndexModel::builder()
+    .keys(
+        doc! {
+            "client.bot_id": 1,
+            "client.channel_id": 1,
+            "client.user_id": 1,
+            "conversation_id": 1,
+            "created_at": 1
+        }
+    )
+    .build();
+    message.create_index(index,None).ok();
+
     // create compound client index for state
     let state = db.client.collection::<Document>("state");
     let index: IndexModel = IndexModel::builder()
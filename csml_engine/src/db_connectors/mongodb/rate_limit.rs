@@ -0,0 +1,74 @@
+use crate::{EngineError, MongoDbClient};
+use bson::{doc, Document};
+use csml_interpreter::data::Client;
+use mongodb::{
+    error::ErrorKind,
+    options::{FindOneAndUpdateOptions, ReturnDocument},
+};
+
+// MongoDB error code of a write conflicting with a unique index
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    match err.kind.as_ref() {
+        ErrorKind::Command(command_error) => command_error.code == DUPLICATE_KEY_CODE,
+        _ => false,
+    }
+}
+
+/**
+ * Each window of each client has its own counter, identified by its _id and incremented
+ * atomically. Two requests creating the same counter conflict on its _id, the one failing
+ * increments the counter created by the other one.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    window: i64,
+    db: &MongoDbClient,
+) -> Result<i64, EngineError> {
+    let collection = db.client.collection::<Document>("rate_limit");
+
+    let filter = doc! {
+        "_id": {
+            "client": bson::to_bson(client)?,
+            "window_start": window_start,
+        }
+    };
+    // the counter expires with its window
+    let expires_at = bson::DateTime::from_millis((window_start + window) * 1000);
+    let update = doc! {
+        "$inc": { "count": 1_i64 },
+        "$setOnInsert": { "expires_at": expires_at },
+    };
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+
+    let counter =
+        match collection.find_one_and_update(filter.clone(), update.clone(), options.clone()) {
+            Err(err) if is_duplicate_key(&err) => {
+                collection.find_one_and_update(filter, update, options)?
+            }
+            result => result?,
+        };
+
+    match counter.and_then(|counter| counter.get_i64("count").ok()) {
+        Some(count) => Ok(count),
+        None => Err(EngineError::Manager(
+            "increment_request_count: count not returned".to_owned(),
+        )),
+    }
+}
+
+pub fn delete_user_rate_limits(client: &Client, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection::<Document>("rate_limit");
+
+    let filter = doc! {
+        "_id.client": bson::to_bson(client)?,
+    };
+    collection.delete_many(filter, None)?;
+
+    Ok(())
+}
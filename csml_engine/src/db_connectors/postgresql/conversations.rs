@@ -1,9 +1,10 @@
-use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl};
+use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl, sql_query, sql_types};
 
 use crate::{
     EngineError, PostgresqlClient,
     Client, DbConversation
 };
+use crate::error_messages::ERROR_CONVERSATION_ID;
 use chrono::{NaiveDateTime};
 
 use super::{
@@ -13,14 +14,18 @@ use super::{
 };
 
 pub fn create_conversation(
+    id: &str,
     flow_id: &str,
     step_id: &str,
     client: &Client,
     expires_at: Option<NaiveDateTime>,
     db: &PostgresqlClient,
 ) -> Result<String, EngineError> {
+    let id = uuid::Uuid::parse_str(id)
+        .map_err(|_| EngineError::Format(ERROR_CONVERSATION_ID.to_owned()))?;
+
     let new_conversation = models::NewConversation {
-        id: uuid::Uuid::new_v4(),
+        id,
         bot_id: &client.bot_id,
         channel_id: &client.channel_id,
         user_id: &client.user_id,
@@ -216,6 +221,73 @@ pub fn get_client_conversations(
     }
 }
 
+pub fn get_conversation_summaries(
+    client: &Client,
+    db: &PostgresqlClient,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+
+    let pagination_key = match pagination_key {
+        Some(paginate) => paginate.parse::<i64>().unwrap_or(1).max(1),
+        None => 1
+    };
+
+    let limit_per_page = match limit {
+        Some(limit) => std::cmp::min(limit, 25).max(1),
+        None => 25,
+    };
+
+    // the messages are grouped with the index on (conversation_id, created_at), the
+    // conversations without messages are kept by the left join and sorted by their creation.
+    // One more row than the page is read to know if there is a next page
+    let mut summaries = sql_query("
+        SELECT c.id AS conversation_id,
+            COALESCE(MIN(m.created_at), c.created_at) AS started_at,
+            MAX(m.created_at) AS last_message_at,
+            COUNT(m.id) AS message_count
+        FROM csml_conversations c
+        LEFT JOIN csml_messages m ON m.conversation_id = c.id
+        WHERE c.bot_id = $1 AND c.channel_id = $2 AND c.user_id = $3
+        GROUP BY c.id, c.created_at
+        ORDER BY COALESCE(MAX(m.created_at), c.created_at) DESC
+        LIMIT $4 OFFSET $5
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .bind::<sql_types::BigInt, _>(limit_per_page + 1)
+    .bind::<sql_types::BigInt, _>((pagination_key - 1) * limit_per_page)
+    .load::<models::ConversationSummary>(&db.client)?;
+
+    let has_next_page = summaries.len() as i64 > limit_per_page;
+    summaries.truncate(limit_per_page as usize);
+
+    let mut convs = vec![];
+    for summary in summaries {
+        let json = serde_json::json!({
+            "conversation_id": summary.conversation_id,
+            "started_at": summary.started_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            "last_message_at": summary
+                .last_message_at
+                .map(|date| date.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()),
+            "message_count": summary.message_count
+        });
+
+        convs.push(json);
+    }
+
+    match has_next_page {
+        true => {
+            let pagination_key = (pagination_key + 1).to_string();
+            Ok(
+                serde_json::json!({"conversations": convs, "pagination_key": pagination_key}),
+            )
+        }
+        false => Ok(serde_json::json!({ "conversations": convs })),
+    }
+}
+
 pub fn delete_all_bot_data(
     bot_id: &str,
     db: &PostgresqlClient,
@@ -14,7 +14,7 @@ use super::{
 pub fn delete_expired_data(
     db: &PostgresqlClient,
 ) -> Result<(), EngineError> {
-    let date_now = chrono::Utc::now().naive_utc();
+    let date_now = crate::clock::now().naive_utc();
 
     diesel::delete(
         csml_conversations::table
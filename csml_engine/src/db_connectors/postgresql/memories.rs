@@ -1,10 +1,8 @@
 use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl};
 
 use crate::{
-    db_connectors::postgresql::get_db,
-    encrypt::{decrypt_data, encrypt_data},
     EngineError, PostgresqlClient,
-    ConversationInfo, Memory, Client
+    Client,
 };
 
 use super::{
@@ -13,81 +11,45 @@ use super::{
 };
 
 use chrono::{NaiveDateTime};
-use std::collections::HashMap;
 
+/**
+ * Save the (key, encrypted value) memories, replacing the ones with the same key
+ */
 pub fn add_memories(
-    data: &mut ConversationInfo,
-    memories: &HashMap<String, Memory>,
-    expires_at: Option<NaiveDateTime>,
-) -> Result<(), EngineError> {
-    if memories.is_empty() {
-        return Ok(());
-    }
-
-    let db = get_db(&data.db)?;
-
-    for (key, mem) in memories.iter() {
-        create_client_memory(&data.client, key, &mem.value, expires_at, db)?;
-    }
-
-    Ok(())
-}
-
-pub fn create_client_memory(
     client: &Client,
-    key: &str,
-    value: &serde_json::Value,
+    memories: Vec<(String, String)>,
     expires_at: Option<NaiveDateTime>,
     db: &PostgresqlClient,
 ) -> Result<(), EngineError> {
-
-    let value = encrypt_data(&value)?;
-
-    let new_memories = models::NewMemory {
-        id: uuid::Uuid::new_v4(),
-        bot_id: &client.bot_id,
-        channel_id: &client.channel_id,
-        user_id: &client.user_id,
-        key,
-        value: value.clone(),
-        expires_at,
-    };
-
-    diesel::insert_into(csml_memories::table)
-    .values(&new_memories)
-    .on_conflict((csml_memories::bot_id, csml_memories::channel_id, csml_memories::user_id, csml_memories::key))
-    .do_update()
-    .set(csml_memories::value.eq(value))
-    .execute(&db.client)?;
-
-    Ok(())
-}
-
-pub fn internal_use_get_memories(
-    client: &Client,
-    db: &PostgresqlClient
-) -> Result<serde_json::Value, EngineError> {
-    let memories: Vec<models::Memory> = csml_memories::table
-    .filter(csml_memories::bot_id.eq(&client.bot_id))
-    .filter(csml_memories::channel_id.eq(&client.channel_id))
-    .filter(csml_memories::user_id.eq(&client.user_id))
-    .load(&db.client)?;
-
-    let mut map = serde_json::Map::new();
-    for mem in memories {
-        if !map.contains_key(&mem.key) {
-            let value: serde_json::Value = decrypt_data(mem.value)?;
-            map.insert(mem.key, value);
-        }
+    for (key, value) in memories {
+        let new_memories = models::NewMemory {
+            id: uuid::Uuid::new_v4(),
+            bot_id: &client.bot_id,
+            channel_id: &client.channel_id,
+            user_id: &client.user_id,
+            key: &key,
+            value: value.clone(),
+            expires_at,
+        };
+
+        diesel::insert_into(csml_memories::table)
+        .values(&new_memories)
+        .on_conflict((csml_memories::bot_id, csml_memories::channel_id, csml_memories::user_id, csml_memories::key))
+        .do_update()
+        .set(csml_memories::value.eq(value))
+        .execute(&db.client)?;
     }
 
-    Ok(serde_json::json!(map))
+    Ok(())
 }
 
-pub fn get_memories(
+/**
+ * Get the client's memories with their values as saved
+ */
+pub fn get_all_memories(
     client: &Client,
     db: &PostgresqlClient
-) -> Result<serde_json::Value, EngineError> {
+) -> Result<Vec<serde_json::Value>, EngineError> {
     let memories: Vec<models::Memory> = csml_memories::table
     .filter(csml_memories::bot_id.eq(&client.bot_id))
     .filter(csml_memories::channel_id.eq(&client.channel_id))
@@ -96,40 +58,14 @@ pub fn get_memories(
 
     let mut vec = vec![];
     for mem in memories {
-        let value: serde_json::Value = decrypt_data(mem.value)?;
-        let mut memory = serde_json::Map::new();
-
-        memory.insert("key".to_owned(), serde_json::json!(mem.key));
-        memory.insert("value".to_owned(), value);
-        memory.insert("created_at".to_owned(), serde_json::json!(mem.created_at.to_string()));
-
-        vec.push(memory);
+        vec.push(serde_json::json!({
+            "key": mem.key,
+            "value": mem.value,
+            "created_at": mem.created_at.to_string(),
+        }));
     }
 
-    Ok(serde_json::json!(vec))
-}
-
-pub fn get_memory(
-    client: &Client,
-    key: &str,
-    db: &PostgresqlClient,
-) -> Result<serde_json::Value, EngineError> {
-
-    let mem: models::Memory = csml_memories::table
-        .filter(csml_memories::key.eq(&key))
-        .filter(csml_memories::bot_id.eq(&client.bot_id))
-        .filter(csml_memories::channel_id.eq(&client.channel_id))
-        .filter(csml_memories::user_id.eq(&client.user_id))
-        .get_result(&db.client)?;
-
-    let mut memory = serde_json::Map::new();
-    let value: serde_json::Value = decrypt_data(mem.value)?;
-
-    memory.insert("key".to_owned(), serde_json::json!(mem.key));
-    memory.insert("value".to_owned(), value);
-    memory.insert("created_at".to_owned(), serde_json::json!(mem.created_at.to_string()));
-
-    Ok(serde_json::json!(memory))
+    Ok(vec)
 }
 
 pub fn delete_client_memory(
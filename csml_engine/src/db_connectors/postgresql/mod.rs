@@ -1,7 +1,9 @@
 pub mod bot;
+mod connector;
 pub mod conversations;
 pub mod memories;
 pub mod messages;
+pub mod rate_limit;
 pub mod state;
 
 pub mod pagination;
@@ -17,15 +19,25 @@ use diesel::prelude::{Connection, PgConnection};
 
 embed_migrations!("migrations/postgresql");
 
-pub fn init() -> Result<Database, EngineError> {
+pub fn get_postgresql_url() -> Result<String, EngineError> {
+    match std::env::var("POSTGRESQL_URL") {
+        Ok(val) => Ok(val),
+        _ => Err(EngineError::Manager(
+            "Missing POSTGRESQL_URL env var".to_owned(),
+        )),
+    }
+}
 
-    let uri = match std::env::var("POSTGRESQL_URL") {
-        Ok(var) => var,
-        _ => "".to_owned(),
-    };
+fn establish_connection() -> Result<PgConnection, EngineError> {
+    let uri = get_postgresql_url()?;
 
-    let pg_connection = PgConnection::establish(&uri)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", uri));
+    PgConnection::establish(&uri).map_err(|err| {
+        EngineError::Manager(format!("Error connecting to postgresql: {}", err))
+    })
+}
+
+pub fn init() -> Result<Database, EngineError> {
+    let pg_connection = establish_connection()?;
 
     let db = Database::Postgresql(
         PostgresqlClient::new(pg_connection)
@@ -34,13 +46,7 @@ pub fn init() -> Result<Database, EngineError> {
 }
 
 pub fn make_migrations() -> Result<(), EngineError> {
-    let uri = match std::env::var("POSTGRESQL_URL") {
-        Ok(var) => var,
-        _ => "".to_owned(),
-    };
-
-    let pg_connection = PgConnection::establish(&uri)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", uri));
+    let pg_connection = establish_connection()?;
 
     embedded_migrations::run_with_output(&pg_connection, &mut std::io::stdout())?;
 
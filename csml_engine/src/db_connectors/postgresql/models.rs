@@ -150,6 +150,24 @@ pub struct State {
     pub created_at: NaiveDateTime,
 }
 
+#[derive(QueryableByName, PartialEq, Debug)]
+pub struct ConversationSummary {
+    #[sql_type = "diesel::sql_types::Uuid"]
+    pub conversation_id: Uuid,
+    #[sql_type = "diesel::sql_types::Timestamp"]
+    pub started_at: NaiveDateTime,
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Timestamp>"]
+    pub last_message_at: Option<NaiveDateTime>,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub message_count: i64,
+}
+
+#[derive(QueryableByName, PartialEq, Debug)]
+pub struct RequestCount {
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub count: i64,
+}
+
 #[derive(Insertable, Queryable, Associations, PartialEq, Debug)]
 #[table_name = "csml_states"]
 pub struct NewState<'a> {
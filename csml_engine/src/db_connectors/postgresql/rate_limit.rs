@@ -0,0 +1,49 @@
+use diesel::{sql_query, sql_types, RunQueryDsl};
+
+use crate::{Client, EngineError, PostgresqlClient};
+
+use super::models;
+
+/**
+ * The counter of the client is incremented, or reset when a new window starts, by a
+ * single upsert returning its new value, so that concurrent requests are all counted.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    db: &PostgresqlClient,
+) -> Result<i64, EngineError> {
+    let request_count: models::RequestCount = sql_query("
+        INSERT INTO csml_rate_limits (bot_id, channel_id, user_id, window_start, count)
+            VALUES($1, $2, $3, $4, 1)
+            ON CONFLICT(bot_id, channel_id, user_id)
+            DO UPDATE SET
+                count = CASE
+                    WHEN csml_rate_limits.window_start = excluded.window_start
+                    THEN csml_rate_limits.count + 1
+                    ELSE 1
+                END,
+                window_start = excluded.window_start
+            RETURNING count
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .bind::<sql_types::BigInt, _>(window_start)
+    .get_result(&db.client)?;
+
+    Ok(request_count.count)
+}
+
+pub fn delete_user_rate_limits(client: &Client, db: &PostgresqlClient) -> Result<(), EngineError> {
+    sql_query("
+        DELETE FROM csml_rate_limits
+            WHERE bot_id = $1 AND channel_id = $2 AND user_id = $3
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .execute(&db.client)?;
+
+    Ok(())
+}
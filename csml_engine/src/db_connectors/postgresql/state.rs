@@ -1,7 +1,6 @@
 use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl};
 
 use crate::{
-    encrypt::{decrypt_data, encrypt_data},
     EngineError, PostgresqlClient,
     Client
 };
@@ -47,8 +46,16 @@ pub fn get_state_key(
 
     match state {
         Ok(state) => {
-            let value = decrypt_data(state.value)?;
-            Ok(Some(value))
+            Ok(Some(serde_json::json!({
+                "client": {
+                    "bot_id": state.bot_id,
+                    "channel_id": state.channel_id,
+                    "user_id": state.user_id
+                },
+                "type": state.type_,
+                "value": state.value,
+                "created_at": state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            })))
         },
         Err(_err) => {
             Ok(None)
@@ -56,50 +63,23 @@ pub fn get_state_key(
     }
 }
 
-pub fn get_current_state(
-    client: &Client,
-    db: &PostgresqlClient,
-) -> Result<Option<serde_json::Value>, EngineError> {
-
-    let current_state: models::State = csml_states::table
-        .filter(csml_states::bot_id.eq(&client.bot_id))
-        .filter(csml_states::channel_id.eq(&client.channel_id))
-        .filter(csml_states::user_id.eq(&client.user_id))
-
-        .filter(csml_states::type_.eq("hold"))
-        .filter(csml_states::key.eq("position"))
-
-        .get_result(&db.client)?;
-
-    let current_state = serde_json::json!({
-        "client": {
-            "bot_id": current_state.bot_id,
-            "channel_id": current_state.channel_id,
-            "user_id": current_state.user_id
-        },
-        "type": current_state.type_,
-        "value": decrypt_data(current_state.value)?,
-        "created_at": current_state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
-    });
-
-    Ok(Some(current_state))
-}
-
+/**
+ * Save the states, replacing the ones with the same key
+ */
 pub fn set_state_items(
     client: &Client,
     type_: &str,
-    keys_values: Vec<(&str, &serde_json::Value)>,
+    keys_values: Vec<(String, String)>,
     expires_at: Option<NaiveDateTime>,
     db: &PostgresqlClient,
 ) -> Result<(), EngineError> {
-    if keys_values.len() == 0 {
+    if keys_values.is_empty() {
         return Ok(());
     }
 
     let mut new_states = vec!();
     for (key, value) in keys_values.iter() {
-
-        let value = encrypt_data(value)?;
+        delete_state_key(client, type_, key, db)?;
 
         let mem = models::NewState {
             id: uuid::Uuid::new_v4(),
@@ -109,7 +89,7 @@ pub fn set_state_items(
             user_id: &client.user_id,
             type_,
             key,
-            value,
+            value: value.to_owned(),
             expires_at,
         };
 
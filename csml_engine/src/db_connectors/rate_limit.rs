@@ -0,0 +1,47 @@
+#[cfg(feature = "redis")]
+use crate::db_connectors::{is_redis, redis_connector};
+
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Client, Database, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+
+/**
+ * Count a new request of the client in the window starting at `window_start` and
+ * return the number of requests of the window. The counter is kept in redis when
+ * REDIS_URL is set, otherwise in the configured database. It is incremented atomically,
+ * so concurrent requests are all counted.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    window: i64,
+    db: &mut Database,
+) -> Result<i64, EngineError> {
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            "db call increment request count".to_owned(),
+        ),
+        LogLvl::Debug,
+    );
+
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        return redis_connector::with_connection(|db| {
+            redis_connector::rate_limit::increment_request_count(
+                client,
+                window_start,
+                window as usize,
+                db,
+            )
+        });
+    }
+
+    if let Some(connector) = db.connector() {
+        return connector.increment_request_count(client, window_start, window);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
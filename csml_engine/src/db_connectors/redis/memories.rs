@@ -0,0 +1,264 @@
+use crate::{
+    db_connectors::utils::{get_hash_prefix, make_hash},
+    encrypt::{decrypt_data, encrypt_data},
+    Client, EngineError, Memory, RedisClient,
+};
+use redis::Commands;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/**
+ * The memories of a client are stored in a single redis hash named after the client
+ * (see make_hash), each field being the key of a memory. The whole hash expires
+ * after `ttl` seconds without any new memory.
+ */
+#[derive(Serialize, Deserialize, Debug)]
+struct RedisMemory {
+    value: String,
+    created_at: String,
+}
+
+fn format_memory(key: &str, memory: RedisMemory) -> Result<serde_json::Value, EngineError> {
+    let mut map = serde_json::Map::new();
+
+    map.insert("key".to_owned(), serde_json::json!(key));
+    map.insert("value".to_owned(), decrypt_data(memory.value)?);
+    map.insert(
+        "created_at".to_owned(),
+        serde_json::json!(memory.created_at),
+    );
+
+    Ok(serde_json::json!(map))
+}
+
+fn get_all_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<HashMap<String, RedisMemory>, EngineError> {
+    let fields: HashMap<String, String> = db.client.hgetall(make_hash(client))?;
+
+    let mut memories = HashMap::new();
+    for (key, field) in fields {
+        memories.insert(key, serde_json::from_str(&field)?);
+    }
+
+    Ok(memories)
+}
+
+pub fn add_memories(
+    client: &Client,
+    memories: &HashMap<String, Memory>,
+    ttl: usize,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    for (key, mem) in memories.iter() {
+        create_client_memory(client, key, &mem.value, ttl, db)?;
+    }
+
+    Ok(())
+}
+
+pub fn create_client_memory(
+    client: &Client,
+    key: &str,
+    value: &serde_json::Value,
+    ttl: usize,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    let hash = make_hash(client);
+    let memory = RedisMemory {
+        value: encrypt_data(value)?,
+        created_at: crate::clock::now().naive_utc().to_string(),
+    };
+
+    redis::pipe()
+        .atomic()
+        .hset(&hash, key, serde_json::to_string(&memory)?)
+        .ignore()
+        .expire(&hash, ttl)
+        .ignore()
+        .query::<()>(&mut db.client)?;
+
+    Ok(())
+}
+
+pub fn internal_use_get_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let mut map = serde_json::Map::new();
+
+    for (key, memory) in get_all_memories(client, db)? {
+        map.insert(key, decrypt_data(memory.value)?);
+    }
+
+    Ok(serde_json::json!(map))
+}
+
+pub fn get_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let mut vec = vec![];
+
+    for (key, memory) in get_all_memories(client, db)? {
+        vec.push(format_memory(&key, memory)?);
+    }
+
+    Ok(serde_json::json!(vec))
+}
+
+/**
+ * A missing or expired memory is not an error, an empty object is returned instead
+ */
+pub fn get_memory(
+    client: &Client,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let field: Option<String> = db.client.hget(make_hash(client), key)?;
+
+    match field {
+        Some(field) => format_memory(key, serde_json::from_str(&field)?),
+        None => Ok(serde_json::json!({})),
+    }
+}
+
+pub fn delete_client_memory(
+    client: &Client,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    let _: () = db.client.hdel(make_hash(client), key)?;
+
+    Ok(())
+}
+
+pub fn delete_client_memories(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let _: () = db.client.del(make_hash(client))?;
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut RedisClient) -> Result<(), EngineError> {
+    let pattern = format!("{}bot_id:{}#*", get_hash_prefix(), bot_id);
+    let hashes: Vec<String> = db.client.scan_match(pattern)?.collect();
+
+    for hash in hashes {
+        let _: () = db.client.del(hash)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    //! They need a running redis instance: `REDIS_URL=... cargo test --features redis redis`
+    use super::*;
+    use crate::db_connectors::{redis::init, utils::get_ttl_for_redis};
+
+    fn get_client() -> Client {
+        Client {
+            user_id: "test".to_owned(),
+            bot_id: uuid::Uuid::new_v4().to_string(),
+            channel_id: "redis-channel".to_owned(),
+        }
+    }
+
+    #[test]
+    fn redis_memory_roundtrip() {
+        std::env::set_var("ENCRYPTION_SECRET", "secret");
+        crate::encrypt::reset_encryption_keys();
+        let client = get_client();
+        let mut db = init().unwrap();
+
+        let value = serde_json::json!({"first_name": "Ada"});
+        create_client_memory(&client, "user", &value, 60, &mut db).unwrap();
+
+        // values are stored encrypted
+        let raw: String = db.client.hget(make_hash(&client), "user").unwrap();
+        assert!(!raw.contains("Ada"));
+
+        let memory = get_memory(&client, "user", &mut db).unwrap();
+        assert_eq!(memory["key"], "user");
+        assert_eq!(memory["value"], value);
+        assert!(memory["created_at"].is_string());
+
+        let memories = internal_use_get_memories(&client, &mut db).unwrap();
+        assert_eq!(memories, serde_json::json!({ "user": value }));
+
+        delete_client_memories(&client, &mut db).unwrap();
+    }
+
+    #[test]
+    fn redis_memory_ttl() {
+        let client = get_client();
+        let mut db = init().unwrap();
+
+        create_client_memory(&client, "name", &serde_json::json!("csml"), 60, &mut db).unwrap();
+
+        let ttl: i64 = db.client.ttl(make_hash(&client)).unwrap();
+        assert!(ttl > 0 && ttl <= 60);
+
+        delete_client_memories(&client, &mut db).unwrap();
+    }
+
+    #[test]
+    fn redis_memory_missing_key() {
+        let client = get_client();
+        let mut db = init().unwrap();
+
+        assert_eq!(
+            get_memory(&client, "unknown", &mut db).unwrap(),
+            serde_json::json!({})
+        );
+        assert_eq!(
+            internal_use_get_memories(&client, &mut db).unwrap(),
+            serde_json::json!({})
+        );
+        assert_eq!(
+            get_memories(&client, &mut db).unwrap(),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn redis_memory_delete() {
+        let client = get_client();
+        let mut db = init().unwrap();
+
+        create_client_memory(&client, "a", &serde_json::json!(1), 60, &mut db).unwrap();
+        create_client_memory(&client, "b", &serde_json::json!(2), 60, &mut db).unwrap();
+
+        delete_client_memory(&client, "a", &mut db).unwrap();
+        let memories = get_memories(&client, &mut db).unwrap();
+        assert_eq!(memories.as_array().unwrap().len(), 1);
+        assert_eq!(memories[0]["key"], "b");
+
+        delete_client_memories(&client, &mut db).unwrap();
+        assert_eq!(
+            get_memories(&client, &mut db).unwrap(),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn redis_delete_all_bot_data() {
+        let client = get_client();
+        let mut db = init().unwrap();
+
+        create_client_memory(&client, "a", &serde_json::json!(1), 60, &mut db).unwrap();
+
+        delete_all_bot_data(&client.bot_id, &mut db).unwrap();
+        assert_eq!(
+            get_memories(&client, &mut db).unwrap(),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    fn redis_ttl_default() {
+        assert_eq!(get_ttl_for_redis(Some(chrono::Duration::seconds(30))), 30);
+        assert_eq!(get_ttl_for_redis(None), 86400);
+    }
+}
@@ -0,0 +1,49 @@
+pub mod memories;
+pub mod rate_limit;
+
+use crate::lock::lock_or_recover;
+use crate::{EngineError, RedisClient};
+use std::sync::Mutex;
+
+// connection shared by the requests, opened on first use
+static CONNECTION: Mutex<Option<RedisClient>> = Mutex::new(None);
+
+pub fn get_redis_url() -> Result<String, EngineError> {
+    match std::env::var("REDIS_URL") {
+        Ok(val) => Ok(val),
+        _ => Err(EngineError::Manager("Missing REDIS_URL env var".to_owned())),
+    }
+}
+
+pub fn init() -> Result<RedisClient, EngineError> {
+    let uri = get_redis_url()?;
+
+    let client = redis::Client::open(uri)?;
+    let connection = client
+        .get_connection()
+        .map_err(|err| EngineError::Manager(format!("Error connecting to redis: {}", err)))?;
+
+    Ok(RedisClient::new(connection))
+}
+
+/**
+ * Run `f` with the connection kept by the connector instead of connecting for each call.
+ * The connection is dropped when `f` fails, the next call connects again.
+ */
+pub fn with_connection<T>(
+    f: impl FnOnce(&mut RedisClient) -> Result<T, EngineError>,
+) -> Result<T, EngineError> {
+    let mut connection = lock_or_recover(&CONNECTION);
+
+    let db = match connection.as_mut() {
+        Some(db) => db,
+        None => connection.insert(init()?),
+    };
+
+    let result = f(db);
+    if result.is_err() {
+        *connection = None;
+    }
+
+    result
+}
@@ -0,0 +1,23 @@
+use crate::{db_connectors::utils::make_hash, Client, EngineError, RedisClient};
+
+/**
+ * Each window of each client has its own counter, incremented atomically and
+ * expiring with the window.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    window: usize,
+    db: &mut RedisClient,
+) -> Result<i64, EngineError> {
+    let key = format!("rate_limit#{}#{}", make_hash(client), window_start);
+
+    let (count,): (i64,) = redis::pipe()
+        .atomic()
+        .incr(&key, 1)
+        .expire(&key, window)
+        .ignore()
+        .query(&mut db.client)?;
+
+    Ok(count)
+}
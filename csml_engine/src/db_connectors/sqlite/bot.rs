@@ -29,7 +29,7 @@ pub fn create_bot_version(
 
     diesel::insert_into(cmsl_bot_versions::table)
     .values(&newbot)
-    .execute(&db.client)?;
+    .execute(&*db.client())?;
 
     Ok(id.to_string())
 }
@@ -58,7 +58,7 @@ pub fn get_bot_versions(
     query = query.per_page(limit_per_page);
 
     let (bot_versions, total_pages) =
-    query.load_and_count_pages::<models::Bot>(&db.client)?;
+    query.load_and_count_pages::<models::Bot>(&*db.client())?;
 
     let mut bots = vec![];
     for bot_version in bot_versions {
@@ -99,7 +99,7 @@ pub fn get_bot_by_version_id(
 
     let result: Result<models::Bot, diesel::result::Error> = cmsl_bot_versions::table
     .filter(cmsl_bot_versions::id.eq(&version_id))
-    .first::<models::Bot>(&db.client);
+    .first::<models::Bot>(&*db.client());
 
     match result {
         Ok(bot) => {
@@ -122,7 +122,7 @@ pub fn get_last_bot_version(
     let result: Result<models::Bot, diesel::result::Error> = cmsl_bot_versions::table
     .filter(cmsl_bot_versions::bot_id.eq(&bot_id))
     .order_by(cmsl_bot_versions::created_at.desc())
-    .get_result(&db.client);
+    .get_result(&*db.client());
 
     match result {
         Ok(bot) => {
@@ -150,7 +150,7 @@ pub fn delete_bot_version(
     diesel::delete(
         cmsl_bot_versions::table
         .filter(cmsl_bot_versions::id.eq(id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
 }
@@ -159,7 +159,7 @@ pub fn delete_bot_versions(bot_id: &str, db: &SqliteClient) -> Result<(), Engine
     diesel::delete(
         cmsl_bot_versions::table
         .filter(cmsl_bot_versions::bot_id.eq(bot_id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
 }
@@ -1,9 +1,10 @@
-use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl};
+use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl, sql_query, sql_types};
 
 use crate::{
     EngineError, SqliteClient,
     Client, DbConversation,
 };
+use crate::error_messages::ERROR_CONVERSATION_ID;
 use chrono::{NaiveDateTime};
 
 use super::{
@@ -13,13 +14,15 @@ use super::{
 };
 
 pub fn create_conversation(
+    id: &str,
     flow_id: &str,
     step_id: &str,
     client: &Client,
     expires_at: Option<NaiveDateTime>,
     db: &SqliteClient,
 ) -> Result<String, EngineError> {
-    let id = models::UUID::new_v4();
+    let id = models::UUID::parse_str(id)
+        .map_err(|_| EngineError::Format(ERROR_CONVERSATION_ID.to_owned()))?;
 
     let new_conversation = models::NewConversation {
         id: id.clone(),
@@ -34,7 +37,7 @@ pub fn create_conversation(
 
     diesel::insert_into(csml_conversations::table)
     .values(&new_conversation)
-    .execute(&db.client)?;
+    .execute(&*db.client())?;
 
     Ok(id.to_string())
 }
@@ -52,7 +55,7 @@ pub fn close_conversation(
         .filter(csml_conversations::id.eq(id))
     )
     .set(csml_conversations::status.eq(status))
-    .execute(&db.client)?;
+    .execute(&*db.client())?;
 
     Ok(())
 }
@@ -65,7 +68,7 @@ pub fn close_all_conversations(client: &Client, db: &SqliteClient) -> Result<(),
         .filter(csml_conversations::user_id.eq(&client.user_id))
     )
     .set(csml_conversations::status.eq("CLOSED"))
-    .execute(&db.client)?;
+    .execute(&*db.client())?;
 
     Ok(())
 }
@@ -81,7 +84,7 @@ pub fn get_latest_open(
         .filter(csml_conversations::status.eq("OPEN"))
         .order_by(csml_conversations::updated_at.desc())
         .limit(1)
-        .get_result(&db.client);
+        .get_result(&*db.client());
 
     match result {
         Ok(conv) => {
@@ -126,7 +129,7 @@ pub fn update_conversation(
                 csml_conversations::flow_id.eq(flow_id.as_str()),
                 csml_conversations::step_id.eq(step_id.as_str())
             ))
-            .execute(&db.client)?;
+            .execute(&*db.client())?;
         }
         (Some(flow_id), _) => {
             diesel::update(
@@ -134,7 +137,7 @@ pub fn update_conversation(
                 .filter(csml_conversations::id.eq(&id))
             )
             .set(csml_conversations::flow_id.eq(flow_id.as_str()))
-            .execute(&db.client)?;
+            .execute(&*db.client())?;
         }
         (_, Some(step_id)) => {
             diesel::update(
@@ -142,7 +145,7 @@ pub fn update_conversation(
                 .filter(csml_conversations::id.eq(&id))
             )
             .set(csml_conversations::step_id.eq(step_id.as_str()))
-            .execute(&db.client)?;
+            .execute(&*db.client())?;
         }
         _ => return Ok(())
     };
@@ -155,7 +158,7 @@ pub fn delete_user_conversations(client: &Client, db: &SqliteClient) -> Result<(
         .filter(csml_conversations::bot_id.eq(&client.bot_id))
         .filter(csml_conversations::channel_id.eq(&client.channel_id))
         .filter(csml_conversations::user_id.eq(&client.user_id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
 }
@@ -186,7 +189,7 @@ pub fn get_client_conversations(
     query = query.per_page(limit_per_page);
 
     let (conversations, total_pages) =
-    query.load_and_count_pages::<models::Conversation>(&db.client)?;
+    query.load_and_count_pages::<models::Conversation>(&*db.client())?;
 
     let mut convs = vec![];
     for conversation in conversations {
@@ -218,6 +221,74 @@ pub fn get_client_conversations(
     }
 }
 
+pub fn get_conversation_summaries(
+    client: &Client,
+    db: &SqliteClient,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+
+    let pagination_key = match pagination_key {
+        Some(paginate) => paginate.parse::<i64>().unwrap_or(1).max(1),
+        None => 1
+    };
+
+    let limit_per_page = match limit {
+        Some(limit) => std::cmp::min(limit, 25).max(1),
+        None => 25,
+    };
+
+    // the messages are grouped with the index on (conversation_id, created_at), the
+    // conversations without messages are kept by the left join and sorted by their creation.
+    // One more row than the page is read to know if there is a next page.
+    // created_at is saved to the second, the last inserted message breaks the ties
+    let mut summaries = sql_query("
+        SELECT c.id AS conversation_id,
+            COALESCE(MIN(m.created_at), c.created_at) AS started_at,
+            MAX(m.created_at) AS last_message_at,
+            COUNT(m.id) AS message_count
+        FROM csml_conversations c
+        LEFT JOIN csml_messages m ON m.conversation_id = c.id
+        WHERE c.bot_id = ? AND c.channel_id = ? AND c.user_id = ?
+        GROUP BY c.id, c.created_at
+        ORDER BY COALESCE(MAX(m.created_at), c.created_at) DESC, MAX(m.rowid) DESC, c.rowid DESC
+        LIMIT ? OFFSET ?
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .bind::<sql_types::BigInt, _>(limit_per_page + 1)
+    .bind::<sql_types::BigInt, _>((pagination_key - 1) * limit_per_page)
+    .load::<models::ConversationSummary>(&*db.client())?;
+
+    let has_next_page = summaries.len() as i64 > limit_per_page;
+    summaries.truncate(limit_per_page as usize);
+
+    let mut convs = vec![];
+    for summary in summaries {
+        let json = serde_json::json!({
+            "conversation_id": summary.conversation_id.get_uuid(),
+            "started_at": summary.started_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            "last_message_at": summary
+                .last_message_at
+                .map(|date| date.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()),
+            "message_count": summary.message_count
+        });
+
+        convs.push(json);
+    }
+
+    match has_next_page {
+        true => {
+            let pagination_key = (pagination_key + 1).to_string();
+            Ok(
+                serde_json::json!({"conversations": convs, "pagination_key": pagination_key}),
+            )
+        }
+        false => Ok(serde_json::json!({ "conversations": convs })),
+    }
+}
+
 pub fn delete_all_bot_data(
     bot_id: &str,
     db: &SqliteClient,
@@ -225,7 +296,7 @@ pub fn delete_all_bot_data(
     diesel::delete(
         csml_conversations::table
         .filter(csml_conversations::bot_id.eq(bot_id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
 }
@@ -1,9 +1,11 @@
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
 
 use crate::{
-    db_connectors::sqlite::get_db,
-    encrypt::{decrypt_data, encrypt_data},
-    Client, ConversationInfo, EngineError, SqliteClient,
+    db_connectors::{
+        connector::{EncryptedMessage, Interaction},
+        MessageCursor,
+    },
+    Client, EngineError, SqliteClient,
 };
 
 use super::{
@@ -13,35 +15,34 @@ use super::{
 };
 use chrono::NaiveDateTime;
 
+const DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.fZ";
+
 pub fn add_messages_bulk(
-    data: &ConversationInfo,
-    msgs: &[serde_json::Value],
-    interaction_order: i32,
-    direction: &str,
+    interaction: &Interaction,
+    messages: Vec<EncryptedMessage>,
     expires_at: Option<NaiveDateTime>,
+    db: &SqliteClient,
 ) -> Result<(), EngineError> {
-    if msgs.len() == 0 {
+    if messages.is_empty() {
         return Ok(());
     }
 
-    let db = get_db(&data.db)?;
+    let conversation_id = models::UUID::parse_str(interaction.conversation_id).unwrap();
 
     let mut new_messages = vec![];
-    for (message_order, message) in msgs.iter().enumerate() {
-        let conversation_id = models::UUID::parse_str(&data.conversation_id).unwrap();
-
+    for message in messages.iter() {
         let msg = models::NewMessages {
             id: models::UUID::new_v4(),
             conversation_id,
 
-            flow_id: &data.context.flow,
-            step_id: data.context.step.get_step_ref(),
-            direction,
-            payload: encrypt_data(&message)?,
-            content_type: &message["content_type"].as_str().unwrap_or("text"),
+            flow_id: interaction.flow_id,
+            step_id: interaction.step_id,
+            direction: interaction.direction,
+            payload: message.payload.clone(),
+            content_type: &message.content_type,
 
-            message_order: message_order as i32,
-            interaction_order,
+            message_order: message.message_order,
+            interaction_order: interaction.interaction_order,
             expires_at,
         };
 
@@ -50,7 +51,7 @@ pub fn add_messages_bulk(
 
     diesel::insert_into(csml_messages::table)
         .values(&new_messages)
-        .execute(&db.client)?;
+        .execute(&*db.client())?;
 
     Ok(())
 }
@@ -60,13 +61,13 @@ pub fn delete_user_messages(client: &Client, db: &SqliteClient) -> Result<(), En
         .filter(csml_conversations::bot_id.eq(&client.bot_id))
         .filter(csml_conversations::channel_id.eq(&client.channel_id))
         .filter(csml_conversations::user_id.eq(&client.user_id))
-        .load(&db.client)?;
+        .load(&*db.client())?;
 
     for conversation in conversations {
         diesel::delete(
             csml_messages::table.filter(csml_messages::conversation_id.eq(&conversation.id)),
         )
-        .execute(&db.client)
+        .execute(&*db.client())
         .ok();
     }
 
@@ -91,7 +92,7 @@ pub fn get_client_messages(
             let from_date = NaiveDateTime::from_timestamp(from_date, 0);
             let to_date = match to_date {
                 Some(to_date) => NaiveDateTime::from_timestamp(to_date, 0),
-                None => chrono::Utc::now().naive_utc(),
+                None => crate::clock::now().naive_utc(),
             };
 
             let mut query = csml_conversations::table
@@ -112,7 +113,7 @@ pub fn get_client_messages(
             };
             query = query.per_page(limit_per_page);
 
-            query.load_and_count_pages::<(models::Conversation, models::Message)>(&db.client)?
+            query.load_and_count_pages::<(models::Conversation, models::Message)>(&*db.client())?
         }
         None => {
             let mut query = csml_conversations::table
@@ -131,7 +132,7 @@ pub fn get_client_messages(
             };
             query = query.per_page(limit_per_page);
 
-            query.load_and_count_pages::<(models::Conversation, models::Message)>(&db.client)?
+            query.load_and_count_pages::<(models::Conversation, models::Message)>(&*db.client())?
         }
     };
 
@@ -149,7 +150,7 @@ pub fn get_client_messages(
             "flow_id": message.flow_id,
             "step_id": message.step_id,
             "direction": message.direction,
-            "payload": decrypt_data(message.payload)?,
+            "payload": message.payload,
 
             "updated_at": message.updated_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
             "created_at": message.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()
@@ -166,3 +167,123 @@ pub fn get_client_messages(
         false => Ok(serde_json::json!({ "messages": msgs })),
     }
 }
+
+/**
+ * Get a page of all the messages of the bot across its clients, from the oldest one.
+ * The returned pagination key is the next page number, None after the last page.
+ */
+pub fn get_bot_messages(
+    bot_id: &str,
+    db: &SqliteClient,
+    limit: i64,
+    pagination_key: Option<String>,
+) -> Result<(Vec<serde_json::Value>, Option<String>), EngineError> {
+    let pagination_key = match pagination_key {
+        Some(paginate) => paginate.parse::<i64>().unwrap_or(1),
+        None => 1,
+    };
+
+    let query = csml_conversations::table
+        .filter(csml_conversations::bot_id.eq(bot_id))
+        .inner_join(csml_messages::table)
+        .select((csml_conversations::all_columns, csml_messages::all_columns))
+        .order_by(csml_messages::created_at.asc())
+        .then_order_by(csml_messages::id.asc())
+        .paginate(pagination_key)
+        .per_page(limit);
+
+    let (conversation_with_messages, total_pages) =
+        query.load_and_count_pages::<(models::Conversation, models::Message)>(&*db.client())?;
+
+    let mut msgs = vec![];
+    for (conversation, message) in conversation_with_messages {
+        let json = serde_json::json!({
+            "client": {
+                "bot_id": conversation.bot_id,
+                "channel_id": conversation.channel_id,
+                "user_id": conversation.user_id
+            },
+            "conversation_id": message.conversation_id.get_uuid(),
+            "flow_id": message.flow_id,
+            "step_id": message.step_id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "direction": message.direction,
+            "payload": message.payload,
+
+            "updated_at": message.updated_at.format(DATE_FORMAT).to_string(),
+            "created_at": message.created_at.format(DATE_FORMAT).to_string()
+        });
+
+        msgs.push(json);
+    }
+
+    match pagination_key < total_pages {
+        true => Ok((msgs, Some((pagination_key + 1).to_string()))),
+        false => Ok((msgs, None)),
+    }
+}
+
+pub fn get_client_messages_page(
+    client: &Client,
+    db: &SqliteClient,
+    limit: i64,
+    cursor: Option<MessageCursor>,
+) -> Result<Vec<serde_json::Value>, EngineError> {
+    let mut query = csml_conversations::table
+        .filter(csml_conversations::bot_id.eq(&client.bot_id))
+        .filter(csml_conversations::channel_id.eq(&client.channel_id))
+        .filter(csml_conversations::user_id.eq(&client.user_id))
+        .inner_join(csml_messages::table)
+        .select((csml_conversations::all_columns, csml_messages::all_columns))
+        .order_by(csml_messages::created_at.desc())
+        .then_order_by(csml_messages::interaction_order.desc())
+        .then_order_by(csml_messages::message_order.desc())
+        .limit(limit)
+        .into_boxed();
+
+    if let Some(cursor) = cursor {
+        let created_at = match NaiveDateTime::parse_from_str(&cursor.created_at, DATE_FORMAT) {
+            Ok(created_at) => created_at,
+            Err(_) => return Err(EngineError::Manager(format!("Invalid cursor"))),
+        };
+
+        query = query.filter(
+            csml_messages::created_at
+                .lt(created_at)
+                .or(csml_messages::created_at.eq(created_at).and(
+                    csml_messages::interaction_order
+                        .lt(cursor.interaction_order)
+                        .or(csml_messages::interaction_order
+                            .eq(cursor.interaction_order)
+                            .and(csml_messages::message_order.lt(cursor.message_order))),
+                )),
+        );
+    }
+
+    let conversation_with_messages: Vec<(models::Conversation, models::Message)> =
+        query.load(&*db.client())?;
+
+    let mut msgs = vec![];
+    for (_, message) in conversation_with_messages {
+        let json = serde_json::json!({
+            "client": {
+                "bot_id": &client.bot_id,
+                "channel_id": &client.channel_id,
+                "user_id": &client.user_id
+            },
+            "conversation_id": message.conversation_id.get_uuid(),
+            "flow_id": message.flow_id,
+            "step_id": message.step_id,
+            "message_order": message.message_order,
+            "interaction_order": message.interaction_order,
+            "direction": message.direction,
+            "payload": message.payload,
+            "created_at": message.created_at.format(DATE_FORMAT).to_string()
+        });
+
+        msgs.push(json);
+    }
+
+    Ok(msgs)
+}
@@ -1,7 +1,9 @@
 pub mod bot;
+mod connector;
 pub mod conversations;
 pub mod memories;
 pub mod messages;
+pub mod rate_limit;
 pub mod state;
 
 pub mod pagination;
@@ -13,37 +15,64 @@ pub mod schema;
 pub mod expired_data;
 
 use crate::{Database, EngineError, SqliteClient};
+use crate::lock::lock_or_recover;
 
 use diesel::prelude::*;
+use std::sync::Mutex;
 
 embed_migrations!("migrations/sqlite");
 
-pub fn init() -> Result<Database, EngineError> {
+/**
+ * SQLite only allows one writer at a time and an in-memory database only lives as long
+ * as its connection, so a single connection is shared by the whole process behind a mutex.
+ * Queries wait for each other: this connector is meant for local development and tests,
+ * not for production deployments.
+ */
+static CONNECTION: Mutex<Option<SqliteConnection>> = Mutex::new(None);
+
+fn get_path() -> String {
+    // SQLITE_URL is kept for backward compatibility
+    match std::env::var("SQLITE_PATH").or_else(|_| std::env::var("SQLITE_URL")) {
+        Ok(var) if !var.is_empty() => var,
+        _ => ":memory:".to_owned(),
+    }
+}
 
-    let uri = match std::env::var("SQLITE_URL") {
-        Ok(var) => var,
-        _ => "".to_owned(),
+fn establish_connection() -> Result<SqliteConnection, EngineError> {
+    let path = get_path();
+
+    let connection = match SqliteConnection::establish(&path) {
+        Ok(connection) => connection,
+        Err(err) => {
+            return Err(EngineError::Manager(format!(
+                "Error connecting to {}: {}",
+                path, err
+            )))
+        }
     };
 
-    let sqlite_connection = SqliteConnection::establish(&uri)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", uri));
+    // the tables are created when the database is first used
+    embedded_migrations::run(&connection)?;
 
-    let db = Database::SqLite(
-        SqliteClient::new(sqlite_connection)
-    );
-    Ok(db)
+    Ok(connection)
 }
 
-pub fn make_migrations() -> Result<(), EngineError> {
-    let uri = match std::env::var("SQLITE_URL") {
-        Ok(var) => var,
-        _ => "".to_owned(),
-    };
+pub fn init() -> Result<Database, EngineError> {
+    let mut connection = lock_or_recover(&CONNECTION);
 
-    let sqlite_connection = SqliteConnection::establish(&uri)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", uri));
+    if connection.is_none() {
+        *connection = Some(establish_connection()?);
+    }
+
+    // the connection is locked by each query, not for the lifetime of the client
+    Ok(Database::SqLite(SqliteClient::new(&CONNECTION)))
+}
+
+pub fn make_migrations() -> Result<(), EngineError> {
+    let db = init()?;
+    let db = get_db(&db)?;
 
-    embedded_migrations::run_with_output(&sqlite_connection, &mut std::io::stdout())?;
+    embedded_migrations::run_with_output(&*db.client(), &mut std::io::stdout())?;
 
     Ok(())
 }
@@ -156,6 +156,25 @@ pub struct State {
     pub expires_at: Option<NaiveDateTime>,
     pub updated_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    pub version: i64,
+}
+
+#[derive(QueryableByName, PartialEq, Debug)]
+pub struct ConversationSummary {
+    #[sql_type = "diesel::sql_types::Binary"]
+    pub conversation_id: UUID,
+    #[sql_type = "diesel::sql_types::Timestamp"]
+    pub started_at: NaiveDateTime,
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Timestamp>"]
+    pub last_message_at: Option<NaiveDateTime>,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub message_count: i64,
+}
+
+#[derive(QueryableByName, PartialEq, Debug)]
+pub struct RequestCount {
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub count: i64,
 }
 
 #[derive(Insertable, Queryable, Associations, PartialEq, Debug)]
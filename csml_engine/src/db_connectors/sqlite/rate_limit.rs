@@ -0,0 +1,60 @@
+use diesel::{sql_query, sql_types, RunQueryDsl};
+
+use crate::{Client, EngineError, SqliteClient};
+
+use super::models;
+
+/**
+ * The counter of the client is incremented, or reset when a new window starts, by a
+ * single upsert so that concurrent requests are all counted.
+ */
+pub fn increment_request_count(
+    client: &Client,
+    window_start: i64,
+    db: &SqliteClient,
+) -> Result<i64, EngineError> {
+    // the connection is kept locked between the upsert and the read of the count
+    let connection = db.client();
+
+    sql_query("
+        INSERT INTO csml_rate_limits (bot_id, channel_id, user_id, window_start, count)
+            VALUES(?, ?, ?, ?, 1)
+            ON CONFLICT(bot_id, channel_id, user_id)
+            DO UPDATE SET
+                count = CASE
+                    WHEN csml_rate_limits.window_start = excluded.window_start
+                    THEN csml_rate_limits.count + 1
+                    ELSE 1
+                END,
+                window_start = excluded.window_start;
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .bind::<sql_types::BigInt, _>(window_start)
+    .execute(&*connection)?;
+
+    let request_count: models::RequestCount = sql_query("
+        SELECT count FROM csml_rate_limits
+            WHERE bot_id = ? AND channel_id = ? AND user_id = ?
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .get_result(&*connection)?;
+
+    Ok(request_count.count)
+}
+
+pub fn delete_user_rate_limits(client: &Client, db: &SqliteClient) -> Result<(), EngineError> {
+    sql_query("
+        DELETE FROM csml_rate_limits
+            WHERE bot_id = ? AND channel_id = ? AND user_id = ?
+    ")
+    .bind::<sql_types::VarChar, _>(&client.bot_id)
+    .bind::<sql_types::VarChar, _>(&client.channel_id)
+    .bind::<sql_types::VarChar, _>(&client.user_id)
+    .execute(&*db.client())?;
+
+    Ok(())
+}
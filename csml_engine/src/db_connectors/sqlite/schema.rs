@@ -69,6 +69,7 @@ table! {
         expires_at -> Nullable<Timestamp>,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        version -> BigInt,
     }
 }
 
@@ -1,7 +1,8 @@
 use diesel::{RunQueryDsl, ExpressionMethods, QueryDsl};
+use diesel::sql_query;
+use diesel::sql_types;
 
 use crate::{
-    encrypt::{decrypt_data, encrypt_data},
     EngineError, SqliteClient,
     Client
 };
@@ -24,7 +25,7 @@ pub fn delete_state_key(
         .filter(csml_states::user_id.eq(&client.user_id))
         .filter(csml_states::type_.eq(type_))
         .filter(csml_states::key.eq(key))
-    ).execute(&db.client)?;
+    ).execute(&*db.client())?;
 
     Ok(())
 }
@@ -43,12 +44,21 @@ pub fn get_state_key(
     .filter(csml_states::type_.eq(type_))
     .filter(csml_states::key.eq(key))
 
-    .get_result(&db.client);
+    .get_result(&*db.client());
 
     match state {
         Ok(state) => {
-            let value = decrypt_data(state.value)?;
-            Ok(Some(value))
+            Ok(Some(serde_json::json!({
+                "client": {
+                    "bot_id": state.bot_id,
+                    "channel_id": state.channel_id,
+                    "user_id": state.user_id
+                },
+                "type": state.type_,
+                "value": state.value,
+                "version": state.version,
+                "created_at": state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+            })))
         },
         Err(_err) => {
             Ok(None)
@@ -56,71 +66,104 @@ pub fn get_state_key(
     }
 }
 
-pub fn get_current_state(
+/**
+ * Save the states, replacing the ones with the same key. They are given the version 0.
+ */
+pub fn set_state_items(
     client: &Client,
+    type_: &str,
+    keys_values: Vec<(String, String)>,
+    expires_at: Option<NaiveDateTime>,
     db: &SqliteClient,
-) -> Result<Option<serde_json::Value>, EngineError> {
-
-    let current_state: models::State = csml_states::table
-        .filter(csml_states::bot_id.eq(&client.bot_id))
-        .filter(csml_states::channel_id.eq(&client.channel_id))
-        .filter(csml_states::user_id.eq(&client.user_id))
-
-        .filter(csml_states::type_.eq("hold"))
-        .filter(csml_states::key.eq("position"))
-
-        .get_result(&db.client)?;
-
-    let current_state = serde_json::json!({
-        "client": {
-            "bot_id": current_state.bot_id,
-            "channel_id": current_state.channel_id,
-            "user_id": current_state.user_id
-        },
-        "type": current_state.type_,
-        "value": decrypt_data(current_state.value)?,
-        "created_at": current_state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
-    });
+) -> Result<(), EngineError> {
+    for (key, value) in keys_values {
+        sql_query("
+            INSERT INTO csml_states (id, bot_id, channel_id, user_id, type, key, value, expires_at)
+                VALUES(?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(bot_id, channel_id, user_id, type, key)
+                DO UPDATE SET
+                    value = excluded.value,
+                    expires_at = excluded.expires_at,
+                    version = 0,
+                    updated_at = CURRENT_TIMESTAMP;
+        ")
+        .bind::<sql_types::Binary, _>(models::UUID::new_v4())
+        .bind::<sql_types::VarChar, _>(&client.bot_id)
+        .bind::<sql_types::VarChar, _>(&client.channel_id)
+        .bind::<sql_types::VarChar, _>(&client.user_id)
+        .bind::<sql_types::VarChar, _>(type_)
+        .bind::<sql_types::VarChar, _>(&key)
+        .bind::<sql_types::VarChar, _>(&value)
+        .bind::<sql_types::Nullable<sql_types::Timestamp>, _>(expires_at)
+        .execute(&*db.client())?;
+    }
 
-    Ok(Some(current_state))
+    Ok(())
 }
 
-pub fn set_state_items(
+/**
+ * Save a state only if its version is still `version`, with a single conditional statement:
+ * a state that was saved since it was read fails with EngineError::StateConflict
+ */
+pub fn set_state_item_if_version(
     client: &Client,
     type_: &str,
-    keys_values: Vec<(&str, &serde_json::Value)>,
+    key: &str,
+    value: &str,
+    version: i64,
     expires_at: Option<NaiveDateTime>,
     db: &SqliteClient,
 ) -> Result<(), EngineError> {
-    if keys_values.len() == 0 {
-        return Ok(());
-    }
-
-    let mut new_states = vec!();
-    for (key, value) in keys_values.iter() {
-
-        let value = encrypt_data(value)?;
-
-        let mem = models::NewState {
-            id: models::UUID::new_v4(),
-
-            bot_id: &client.bot_id,
-            channel_id: &client.channel_id,
-            user_id: &client.user_id,
-            type_,
-            key,
-            value,
-            expires_at,
-        };
-
-        new_states.push(mem);
+    // the states saved without condition and the missing ones have the version 0
+    let saved = match version {
+        0 => {
+            sql_query("
+                INSERT INTO csml_states (id, bot_id, channel_id, user_id, type, key, value, expires_at, version)
+                    VALUES(?, ?, ?, ?, ?, ?, ?, ?, 1)
+                    ON CONFLICT(bot_id, channel_id, user_id, type, key)
+                    DO UPDATE SET
+                        value = excluded.value,
+                        expires_at = excluded.expires_at,
+                        version = 1,
+                        updated_at = CURRENT_TIMESTAMP
+                    WHERE csml_states.version = 0;
+            ")
+            .bind::<sql_types::Binary, _>(models::UUID::new_v4())
+            .bind::<sql_types::VarChar, _>(&client.bot_id)
+            .bind::<sql_types::VarChar, _>(&client.channel_id)
+            .bind::<sql_types::VarChar, _>(&client.user_id)
+            .bind::<sql_types::VarChar, _>(type_)
+            .bind::<sql_types::VarChar, _>(key)
+            .bind::<sql_types::VarChar, _>(value)
+            .bind::<sql_types::Nullable<sql_types::Timestamp>, _>(expires_at)
+            .execute(&*db.client())?
+        }
+        _ => {
+            sql_query("
+                UPDATE csml_states
+                    SET value = ?, expires_at = ?, version = version + 1, updated_at = CURRENT_TIMESTAMP
+                    WHERE bot_id = ? AND channel_id = ? AND user_id = ? AND type = ? AND key = ?
+                        AND version = ?;
+            ")
+            .bind::<sql_types::VarChar, _>(value)
+            .bind::<sql_types::Nullable<sql_types::Timestamp>, _>(expires_at)
+            .bind::<sql_types::VarChar, _>(&client.bot_id)
+            .bind::<sql_types::VarChar, _>(&client.channel_id)
+            .bind::<sql_types::VarChar, _>(&client.user_id)
+            .bind::<sql_types::VarChar, _>(type_)
+            .bind::<sql_types::VarChar, _>(key)
+            .bind::<sql_types::BigInt, _>(version)
+            .execute(&*db.client())?
+        }
+    };
+
+    match saved {
+        0 => Err(EngineError::StateConflict(format!(
+            "state {} {} is no longer at the version {}",
+            type_, key, version
+        ))),
+        _ => Ok(()),
     }
-
-    diesel::insert_into(csml_states::table)
-    .values(&new_states)
-    .execute(&db.client)?;
-
-    Ok(())
 }
 
 pub fn delete_user_state(
@@ -131,7 +174,7 @@ pub fn delete_user_state(
         .filter(csml_states::bot_id.eq(&client.bot_id))
         .filter(csml_states::channel_id.eq(&client.channel_id))
         .filter(csml_states::user_id.eq(&client.user_id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
 }
@@ -143,7 +186,57 @@ pub fn delete_all_bot_data(
     diesel::delete(
         csml_states::table
         .filter(csml_states::bot_id.eq(bot_id))
-    ).execute(&db.client).ok();
+    ).execute(&*db.client()).ok();
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_connectors::sqlite::{get_db, init};
+
+    #[test]
+    fn set_state_item_if_version_conflict() {
+        let client = Client {
+            bot_id: models::UUID::new_v4().to_string(),
+            channel_id: "channel_id".to_owned(),
+            user_id: "test".to_owned(),
+        };
+        let db = init().unwrap();
+        let db = get_db(&db).unwrap();
+
+        // a missing state has the version 0
+        set_state_item_if_version(&client, "hold", "position", "first", 0, None, db).unwrap();
+        match set_state_item_if_version(&client, "hold", "position", "second", 0, None, db) {
+            Err(EngineError::StateConflict(_)) => {}
+            other => panic!("expected a state conflict, got {:?}", other),
+        }
+
+        set_state_item_if_version(&client, "hold", "position", "second", 1, None, db).unwrap();
+        let state = get_state_key(&client, "hold", "position", db).unwrap().unwrap();
+        assert_eq!(state["value"], "second");
+        assert_eq!(state["version"], 2);
+
+        // the states saved without condition are replaced and go back to the version 0
+        set_state_items(
+            &client,
+            "hold",
+            vec![("position".to_owned(), "third".to_owned())],
+            None,
+            db,
+        )
+        .unwrap();
+        let state = get_state_key(&client, "hold", "position", db).unwrap().unwrap();
+        assert_eq!(state["value"], "third");
+        assert_eq!(state["version"], 0);
+
+        // a deleted state can not be saved with its former version
+        delete_state_key(&client, "hold", "position", db).unwrap();
+        assert!(
+            set_state_item_if_version(&client, "hold", "position", "fourth", 2, None, db).is_err()
+        );
+
+        delete_all_bot_data(&client.bot_id, db).unwrap();
+    }
+}
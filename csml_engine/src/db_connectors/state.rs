@@ -1,17 +1,6 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
-
-
 use csml_interpreter::data::csml_logs::{LogLvl, CsmlLog, csml_logger};
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Database, EngineError};
-use crate::db_connectors::utils::*;
 use csml_interpreter::data::Client;
 
 pub fn delete_state_key(
@@ -39,28 +28,8 @@ pub fn delete_state_key(
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::delete_state_key(client, _type, key, db);
+    if let Some(connector) = db.connector() {
+        return connector.delete_state_key(client, _type, key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -91,28 +60,8 @@ pub fn get_state_key(
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::get_state_key(client, _type, _key, db);
+    if let Some(connector) = db.connector() {
+        return connector.get_state_key(client, _type, _key);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -141,28 +90,8 @@ pub fn get_current_state(
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::get_current_state(client, db); // "hold", "position"
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::get_current_state(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::get_current_state(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::get_current_state(client, db);
+    if let Some(connector) = db.connector() {
+        return connector.get_current_state(client);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -194,36 +123,67 @@ pub fn set_state_items(
         LogLvl::Debug
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_mongodb(ttl);
-
-        return mongodb_connector::state::set_state_items(_client, _type, _keys_values, expires_at, &db);
+    if let Some(connector) = _db.connector() {
+        return connector.set_state_items(_client, _type, _keys_values, ttl);
     }
 
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_dynamodb(ttl);
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
 
-        return dynamodb_connector::state::set_state_items(_client, _type, _keys_values, expires_at, db);
+/**
+ * Get a state with its version. The mongodb and postgresql connectors keep no versions,
+ * their states always have the version 0.
+ */
+pub fn get_versioned_state_key(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    db: &mut Database,
+) -> Result<(Option<serde_json::Value>, i64), EngineError> {
+    if let Some(connector) = db.connector() {
+        return connector.get_versioned_state_key(client, _type, key);
     }
 
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_postgresql(ttl);
-
-        return postgresql_connector::state::set_state_items(_client, _type, _keys_values, expires_at, db);
-    }
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_sqlite(ttl);
+/**
+ * Save or clear (None) a state only if its version is still `version`, return its new version.
+ * A state saved by another request since it was read fails with EngineError::StateConflict,
+ * the request can then be retried or dropped.
+ *
+ * The mongodb and postgresql connectors do not check the version and always save the state.
+ */
+pub fn set_state_item_if_version(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    value: Option<&serde_json::Value>,
+    version: i64,
+    ttl: Option<chrono::Duration>,
+    db: &mut Database,
+) -> Result<i64, EngineError> {
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call set state key: {:?}, type: {:?}, version {}", key, _type, version)
+        ),
+        LogLvl::Info
+    );
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call set state key: {:?}, type: {:?}, version {}", key, _type, version)
+        ),
+        LogLvl::Debug
+    );
 
-        return sqlite_connector::state::set_state_items(_client, _type, _keys_values, expires_at, db);
+    if let Some(connector) = db.connector() {
+        return connector.set_state_item_if_version(client, _type, key, value, version, ttl);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
@@ -255,7 +215,9 @@ mod tests {
             step_name: "step_name".to_owned(),
             flow_name: "flow_name".to_owned(),
             previous: None,
-            secure: false
+            secure: false,
+            wake_at: None,
+            retries: 0,
         };
 
         let state_hold: serde_json::Value = serde_json::json!({
@@ -4,6 +4,8 @@ use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
 #[cfg(feature = "postgresql")]
 use crate::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "redis")]
+use crate::db_connectors::{is_redis, redis_connector};
 #[cfg(feature = "sqlite")]
 use crate::db_connectors::{is_sqlite, sqlite_connector};
 
@@ -21,6 +23,13 @@ pub fn delete_client(client: &Client, db: &mut Database) -> Result<(), EngineErr
         LogLvl::Debug,
     );
 
+    // memories kept in redis are removed first, the main database is cleaned below
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        let mut redis_db = redis_connector::init()?;
+        redis_connector::memories::delete_client_memories(client, &mut redis_db)?;
+    }
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -29,6 +38,7 @@ pub fn delete_client(client: &Client, db: &mut Database) -> Result<(), EngineErr
         mongodb_connector::memories::delete_client_memories(client, db)?;
         mongodb_connector::messages::delete_user_messages(client, db)?;
         mongodb_connector::state::delete_user_state(client, db)?;
+        mongodb_connector::rate_limit::delete_user_rate_limits(client, db)?;
 
         return Ok(());
     }
@@ -53,6 +63,7 @@ pub fn delete_client(client: &Client, db: &mut Database) -> Result<(), EngineErr
         postgresql_connector::memories::delete_client_memories(client, db)?;
         postgresql_connector::messages::delete_user_messages(client, db)?;
         postgresql_connector::state::delete_user_state(client, db)?;
+        postgresql_connector::rate_limit::delete_user_rate_limits(client, db)?;
 
         return Ok(());
     }
@@ -65,6 +76,7 @@ pub fn delete_client(client: &Client, db: &mut Database) -> Result<(), EngineErr
         sqlite_connector::memories::delete_client_memories(client, db)?;
         sqlite_connector::messages::delete_user_messages(client, db)?;
         sqlite_connector::state::delete_user_state(client, db)?;
+        sqlite_connector::rate_limit::delete_user_rate_limits(client, db)?;
 
         return Ok(());
     }
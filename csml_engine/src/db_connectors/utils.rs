@@ -1,10 +1,91 @@
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+use crate::Client;
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+use sha2::{Digest, Sha256};
+
+// Memories stored in redis without a bot ttl expire after one day
+#[cfg(feature = "redis")]
+const REDIS_MEMORY_TTL: usize = 86400;
+
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+fn format_hash_prefix(prefix: Option<String>) -> String {
+    match prefix {
+        Some(prefix) if !prefix.is_empty() => format!("tenant:{}#", prefix),
+        _ => String::new(),
+    }
+}
+
+/**
+ * Get the tenant prefix of the hash keys, set with the CSML_HASH_PREFIX env var.
+ * Unset or empty, no prefix is added and keys keep the single-tenant format.
+ */
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+pub fn get_hash_prefix() -> String {
+    format_hash_prefix(std::env::var("CSML_HASH_PREFIX").ok())
+}
+
+/**
+ * Format of the hash keys, set with the CSML_HASH_MODE env var
+ */
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashMode {
+    // bot_id:{bot_id}#channel_id:{channel_id}#user_id:{user_id}
+    Plain,
+    // bot_id:{bot_id}#{sha256 of the plain key}, for the clients with long ids
+    Sha256,
+}
+
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+fn format_hash_mode(mode: Option<String>) -> HashMode {
+    match mode.as_deref() {
+        Some("sha256") => HashMode::Sha256,
+        _ => HashMode::Plain,
+    }
+}
+
+/**
+ * Get the format of the hash keys, `plain` unless CSML_HASH_MODE is set to `sha256`
+ */
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+pub fn get_hash_mode() -> HashMode {
+    format_hash_mode(std::env::var("CSML_HASH_MODE").ok())
+}
+
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+fn format_hash(prefix: &str, mode: HashMode, client: &Client) -> String {
+    let hash = format!(
+        "bot_id:{}#channel_id:{}#user_id:{}",
+        client.bot_id, client.channel_id, client.user_id
+    );
+
+    match mode {
+        HashMode::Plain => format!("{}{}", prefix, hash),
+        // the bot_id is kept in clear, the data of a bot is queried with the `bot_id:{bot_id}#` prefix
+        HashMode::Sha256 => format!(
+            "{}bot_id:{}#{}",
+            prefix,
+            client.bot_id,
+            hex::encode(Sha256::digest(hash.as_bytes()))
+        ),
+    }
+}
+
+/**
+ * Create a hash key from the client info
+ */
+#[cfg(any(feature = "dynamo", feature = "redis"))]
+pub fn make_hash(client: &Client) -> String {
+    format_hash(&get_hash_prefix(), get_hash_mode(), client)
+}
+
 
 #[cfg(feature = "mongo")]
 pub fn get_expires_at_for_mongodb(ttl: Option<chrono::Duration>) -> Option<bson::DateTime> {
 
     match ttl {
         Some(ttl) => {
-            let expires_at = chrono::Utc::now() + ttl;
+            let expires_at = crate::clock::now() + ttl;
 
             Some(bson::DateTime::from_chrono(expires_at))
         },
@@ -16,7 +97,7 @@ pub fn get_expires_at_for_mongodb(ttl: Option<chrono::Duration>) -> Option<bson:
 pub fn get_expires_at_for_dynamodb(ttl: Option<chrono::Duration>) -> Option<i64> {
     match ttl {
         Some(ttl) => {
-            let expires_at = chrono::Utc::now() + ttl;
+            let expires_at = crate::clock::now() + ttl;
 
             Some(expires_at.timestamp())
         },
@@ -24,11 +105,34 @@ pub fn get_expires_at_for_dynamodb(ttl: Option<chrono::Duration>) -> Option<i64>
     }
 }
 
+#[cfg(feature = "dynamo")]
+fn format_conversation_ttl(days: Option<String>) -> Option<chrono::Duration> {
+    match days.and_then(|days| days.parse::<i64>().ok()) {
+        Some(days) if days > 0 => Some(chrono::Duration::days(days)),
+        _ => None,
+    }
+}
+
+/**
+ * Conversations and messages saved in dynamodb without a bot ttl expire after
+ * CSML_CONVERSATION_TTL_DAYS days if the env var is set. The `expires_at` attribute
+ * must be enabled as the table's TTL attribute for dynamodb to remove them.
+ */
+#[cfg(feature = "dynamo")]
+pub fn get_conversation_ttl_for_dynamodb(
+    ttl: Option<chrono::Duration>,
+) -> Option<chrono::Duration> {
+    match ttl {
+        Some(ttl) => Some(ttl),
+        None => format_conversation_ttl(std::env::var("CSML_CONVERSATION_TTL_DAYS").ok()),
+    }
+}
+
 #[cfg(feature = "postgresql")]
 pub fn get_expires_at_for_postgresql(ttl: Option<chrono::Duration>) -> Option<chrono::NaiveDateTime> {
     match ttl {
         Some(ttl) => {
-            let expires_at = chrono::Utc::now().naive_utc() + ttl;
+            let expires_at = crate::clock::now().naive_utc() + ttl;
 
             Some(expires_at)
         },
@@ -40,10 +144,185 @@ pub fn get_expires_at_for_postgresql(ttl: Option<chrono::Duration>) -> Option<ch
 pub fn get_expires_at_for_sqlite(ttl: Option<chrono::Duration>) -> Option<chrono::NaiveDateTime> {
     match ttl {
         Some(ttl) => {
-            let expires_at = chrono::Utc::now().naive_utc() + ttl;
+            let expires_at = crate::clock::now().naive_utc() + ttl;
 
             Some(expires_at)
         },
         None => None
     }
-}
\ No newline at end of file
+}
+
+/**
+ * Get the number of seconds before memories stored in redis expire.
+ * The bot ttl is used first, then the REDIS_MEMORY_TTL env var and finally
+ * a default of one day.
+ */
+#[cfg(feature = "redis")]
+pub fn get_ttl_for_redis(ttl: Option<chrono::Duration>) -> usize {
+    match ttl {
+        Some(ttl) if ttl.num_seconds() > 0 => ttl.num_seconds() as usize,
+        _ => match std::env::var("REDIS_MEMORY_TTL") {
+            Ok(val) => val.parse::<usize>().unwrap_or(REDIS_MEMORY_TTL),
+            Err(_) => REDIS_MEMORY_TTL,
+        },
+    }
+}
+
+#[cfg(all(test, any(feature = "dynamo", feature = "redis")))]
+mod tests {
+    use super::*;
+
+    fn get_client() -> Client {
+        Client {
+            bot_id: "bot".to_owned(),
+            channel_id: "channel".to_owned(),
+            user_id: "user".to_owned(),
+        }
+    }
+
+    #[test]
+    fn hash_without_prefix() {
+        let prefix = format_hash_prefix(None);
+
+        assert_eq!(
+            format_hash(&prefix, HashMode::Plain, &get_client()),
+            "bot_id:bot#channel_id:channel#user_id:user"
+        );
+    }
+
+    #[test]
+    fn hash_with_prefix() {
+        let prefix = format_hash_prefix(Some("acme".to_owned()));
+
+        assert_eq!(
+            format_hash(&prefix, HashMode::Plain, &get_client()),
+            "tenant:acme#bot_id:bot#channel_id:channel#user_id:user"
+        );
+    }
+
+    #[test]
+    fn hash_mode() {
+        assert_eq!(format_hash_mode(None), HashMode::Plain);
+        assert_eq!(format_hash_mode(Some("plain".to_owned())), HashMode::Plain);
+        assert_eq!(
+            format_hash_mode(Some("sha256".to_owned())),
+            HashMode::Sha256
+        );
+        assert_eq!(format_hash_mode(Some("md5".to_owned())), HashMode::Plain);
+    }
+
+    #[test]
+    fn hash_sha256() {
+        let prefix = format_hash_prefix(Some("acme".to_owned()));
+        let hash = format_hash(&prefix, HashMode::Sha256, &get_client());
+
+        assert_eq!(
+            hash,
+            "tenant:acme#bot_id:bot#a0a45ca362b7723876d03b02177b2342e714f03ecfe21333d306541d6da0cf4b"
+        );
+        assert_eq!(hash, format_hash(&prefix, HashMode::Sha256, &get_client()));
+    }
+
+    #[test]
+    fn hash_sha256_fixed_length() {
+        let mut client = get_client();
+        client.user_id = format!(
+            "{}@example.com#{}",
+            "user".repeat(100),
+            uuid::Uuid::new_v4()
+        );
+        let hash = format_hash("", HashMode::Sha256, &client);
+
+        assert_eq!(hash.len(), "bot_id:bot#".len() + 64);
+    }
+
+    #[test]
+    fn hash_sha256_distinct_clients() {
+        let mut hashes = std::collections::HashSet::new();
+
+        for (channel_id, user_id) in [("a", "b"), ("b", "a"), ("a", "a"), ("ab", ""), ("", "ab")] {
+            let client = Client {
+                bot_id: "bot".to_owned(),
+                channel_id: channel_id.to_owned(),
+                user_id: user_id.to_owned(),
+            };
+
+            assert!(hashes.insert(format_hash("", HashMode::Sha256, &client)));
+            assert!(hashes.insert(format_hash("", HashMode::Plain, &client)));
+        }
+    }
+
+    #[test]
+    fn hash_with_empty_prefix() {
+        assert_eq!(
+            format_hash_prefix(Some("".to_owned())),
+            format_hash_prefix(None)
+        );
+    }
+
+    #[cfg(feature = "dynamo")]
+    fn get_message_item(
+        expires_at: Option<i64>,
+    ) -> (
+        crate::db_connectors::dynamodb::Message,
+        std::collections::HashMap<String, rusoto_dynamodb::AttributeValue>,
+    ) {
+        let message = crate::db_connectors::dynamodb::Message::new(
+            &get_client(),
+            "conversation",
+            "flow",
+            "step",
+            "SEND",
+            0,
+            0,
+            "payload",
+            "text",
+            expires_at,
+        );
+        let item = serde_dynamodb::to_hashmap(&message).unwrap();
+
+        (message, item)
+    }
+
+    #[cfg(feature = "dynamo")]
+    #[test]
+    fn conversation_ttl_days() {
+        assert_eq!(
+            format_conversation_ttl(Some("90".to_owned())),
+            Some(chrono::Duration::days(90))
+        );
+        assert_eq!(format_conversation_ttl(None), None);
+        assert_eq!(format_conversation_ttl(Some("0".to_owned())), None);
+        assert_eq!(format_conversation_ttl(Some("ninety".to_owned())), None);
+    }
+
+    #[cfg(feature = "dynamo")]
+    #[test]
+    fn conversation_ttl_attribute() {
+        let ttl = format_conversation_ttl(Some("90".to_owned()));
+        let (message, item) = get_message_item(get_expires_at_for_dynamodb(ttl));
+
+        let expires_at = item["expires_at"]
+            .n
+            .as_ref()
+            .unwrap()
+            .parse::<i64>()
+            .unwrap();
+        let created_at = chrono::DateTime::parse_from_rfc3339(&message.created_at)
+            .unwrap()
+            .timestamp();
+
+        // epoch seconds, 90 days after the message was created
+        let delta = expires_at - created_at - chrono::Duration::days(90).num_seconds();
+        assert!(delta.abs() <= 1);
+    }
+
+    #[cfg(feature = "dynamo")]
+    #[test]
+    fn conversation_ttl_attribute_absent() {
+        let ttl = format_conversation_ttl(None);
+        let (_message, item) = get_message_item(get_expires_at_for_dynamodb(ttl));
+
+        assert!(!item.contains_key("expires_at"));
+    }
+}
@@ -10,22 +10,134 @@
  * Decrypt: Data is decrypted from an encrypted string and is returned as a JSON Value.
  *
  * The encryption algorithm used is AES-256-GCM.
+ *
+ * Key rotation: encrypted data is prefixed with the id of the key used to encrypt it
+ * ("{key_id}:{data}"). ENCRYPTION_KEY_ID sets the id of the current ENCRYPTION_SECRET
+ * (defaults to 0), and retired secrets are kept as ENCRYPTION_SECRET_{key_id} to decrypt
+ * older data, with numeric key ids only. The keys are read from the env vars once. New data is always encrypted with the current secret, `reencrypt_data`
+ * can be used to migrate existing data. Data encrypted before key ids were introduced
+ * is decrypted with whichever of the configured secrets matches.
+ *
+ * The algorithm above is the `BuiltinEncryptor`, used by default. Another implementation
+ * of the `Encryptor` trait (for instance backed by a KMS) can be configured with
+ * `set_encryptor`, every DB connector then encrypts and decrypts data with it.
  */
 use crate::EngineError;
+use crate::lock::{read_or_recover, write_or_recover};
 
 use openssl::{
     pkcs5::pbkdf2_hmac,
     rand::rand_bytes,
     symm::{decrypt_aead, encrypt_aead, Cipher},
 };
+use std::collections::HashMap;
 use std::env;
+use std::sync::{OnceLock, RwLock};
+
+const DEFAULT_KEY_ID: &str = "0";
+const KEY_ID_SEPARATOR: char = ':';
+const RETIRED_SECRET_PREFIX: &str = "ENCRYPTION_SECRET_";
+
+/**
+ * Encryption of the data stored by the engine (messages, memories and states).
+ * The trait is object-safe so that any implementation can be set with `set_encryptor`.
+ */
+pub trait Encryptor: Send + Sync {
+    /**
+     * Encrypt a JSON value into the string saved in the database.
+     */
+    fn encrypt(&self, value: &serde_json::Value) -> Result<String, EngineError>;
+
+    /**
+     * Decrypt a string read from the database back into its JSON value.
+     */
+    fn decrypt(&self, value: &str) -> Result<serde_json::Value, EngineError>;
+
+    /**
+     * Encrypt again data encrypted with a previous key. By default the data is
+     * decrypted and encrypted again.
+     */
+    fn reencrypt(&self, value: String) -> Result<String, EngineError> {
+        self.encrypt(&self.decrypt(&value)?)
+    }
+}
+
+/**
+ * AES-256-GCM encryption with the ENCRYPTION_SECRET env var, data is stored
+ * as plain JSON if the secret is not set.
+ */
+pub struct BuiltinEncryptor;
+
+// None means the BuiltinEncryptor is used
+static ENCRYPTOR: RwLock<Option<Box<dyn Encryptor>>> = RwLock::new(None);
+
+// read from the env vars on first use, None when ENCRYPTION_SECRET is not set
+static ENCRYPTION_KEYS: RwLock<OnceLock<Option<EncryptionKeys>>> = RwLock::new(OnceLock::new());
+
+struct EncryptionKeys {
+    current_id: String,
+    current_secret: String,
+    retired_secrets: HashMap<String, String>,
+}
+
+impl EncryptionKeys {
+    fn from_env() -> Option<Self> {
+        let current_secret = env::var("ENCRYPTION_SECRET").ok()?;
+        let current_id = match env::var("ENCRYPTION_KEY_ID") {
+            Ok(key_id) if !key_id.is_empty() => key_id,
+            _ => DEFAULT_KEY_ID.to_owned(),
+        };
+
+        Some(Self {
+            current_id,
+            current_secret,
+            retired_secrets: get_retired_secrets(env::vars()),
+        })
+    }
+
+    fn get_secret(&self, key_id: &str) -> Result<&str, EngineError> {
+        if key_id == self.current_id {
+            return Ok(&self.current_secret);
+        }
+
+        match self.retired_secrets.get(key_id) {
+            Some(secret) => Ok(secret),
+            None => Err(EngineError::Manager(format!(
+                "Unknown encryption key id: {}",
+                key_id
+            ))),
+        }
+    }
+}
+
+/**
+ * Secrets of the ENCRYPTION_SECRET_{key_id} env vars, other vars sharing the prefix
+ * (ENCRYPTION_SECRET_FILE for instance) are not keys.
+ */
+fn get_retired_secrets(vars: impl Iterator<Item = (String, String)>) -> HashMap<String, String> {
+    vars.filter_map(|(name, secret)| {
+        name.strip_prefix(RETIRED_SECRET_PREFIX)
+            .filter(|key_id| !key_id.is_empty() && key_id.bytes().all(|c| c.is_ascii_digit()))
+            .map(|key_id| (key_id.to_owned(), secret))
+    })
+    .collect()
+}
 
-fn get_key(salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
-    let pass = match env::var("ENCRYPTION_SECRET") {
-        Ok(var) => var,
-        _ => panic!("No ENCRYPTION_SECRET value in env"),
-    };
+fn with_keys<T>(f: impl FnOnce(Option<&EncryptionKeys>) -> T) -> T {
+    let keys = read_or_recover(&ENCRYPTION_KEYS);
+
+    f(keys.get_or_init(EncryptionKeys::from_env).as_ref())
+}
+
+/**
+ * Forget the encryption keys, they are read again from the env vars on next use.
+ */
+#[cfg(any(test, feature = "test-utils"))]
+pub fn reset_encryption_keys() {
+    *write_or_recover(&ENCRYPTION_KEYS) = OnceLock::new();
+}
 
+fn get_key(pass: &str, salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
     pbkdf2_hmac(
         pass.as_bytes(),
         &salt,
@@ -54,7 +166,7 @@ fn decode(text: &str) -> Result<Vec<u8>, EngineError> {
     }
 }
 
-fn encrypt(text: &[u8]) -> Result<String, EngineError> {
+fn encrypt(keys: &EncryptionKeys, text: &[u8]) -> Result<String, EngineError> {
     let cipher = Cipher::aes_256_gcm();
 
     let mut tag = vec![0; 16];
@@ -63,22 +175,20 @@ fn encrypt(text: &[u8]) -> Result<String, EngineError> {
     let mut salt = vec![0; 64];
     rand_bytes(&mut salt)?;
     let mut key = [0; 32];
-    get_key(&salt, &mut key)?;
+    get_key(&keys.current_secret, &salt, &mut key)?;
 
     let encrypted = encrypt_aead(cipher, &key, Some(&iv), &[], text, &mut tag)?;
 
-    Ok(base64::encode(&[salt, iv, tag, encrypted].concat()))
+    Ok(format!(
+        "{}{}{}",
+        keys.current_id,
+        KEY_ID_SEPARATOR,
+        base64::encode(&[salt, iv, tag, encrypted].concat())
+    ))
 }
 
-pub fn encrypt_data(value: &serde_json::Value) -> Result<String, EngineError> {
-    match env::var("ENCRYPTION_SECRET") {
-        Ok(..) => encrypt(&value.to_string().as_bytes()),
-        _ => Ok(value.to_string()),
-    }
-}
-
-fn decrypt(text: String) -> Result<String, EngineError> {
-    let ciphertext = decode(&text)?;
+fn decrypt_with_secret(secret: &str, text: &str) -> Result<String, EngineError> {
+    let ciphertext = decode(text)?;
     let cipher = Cipher::aes_256_gcm();
 
     let iv_length = 16;
@@ -93,22 +203,181 @@ fn decrypt(text: String) -> Result<String, EngineError> {
     let encrypted: &[u8] = &ciphertext[encrypted_position..];
 
     let mut key = [0; 32];
-    get_key(&salt, &mut key)?;
+    get_key(secret, &salt, &mut key)?;
 
     let value = decrypt_aead(cipher, &key, Some(&iv), &[], &encrypted, &tag)?;
 
     Ok(String::from_utf8_lossy(&value).to_string())
 }
 
+fn decrypt(keys: &EncryptionKeys, text: &str) -> Result<String, EngineError> {
+    if let Some((key_id, text)) = text.split_once(KEY_ID_SEPARATOR) {
+        return decrypt_with_secret(keys.get_secret(key_id)?, text);
+    }
+
+    // data encrypted without key id, the AES-GCM tag only matches with the right secret
+    let result = decrypt_with_secret(&keys.current_secret, text);
+    if result.is_ok() {
+        return result;
+    }
+
+    keys.retired_secrets
+        .values()
+        .find_map(|secret| decrypt_with_secret(secret, text).ok())
+        .map_or(result, Ok)
+}
+
+fn reencrypt(keys: &EncryptionKeys, value: String) -> Result<String, EngineError> {
+    match value.split_once(KEY_ID_SEPARATOR) {
+        Some((key_id, _)) if key_id == keys.current_id => Ok(value),
+        _ => encrypt(keys, decrypt(keys, &value)?.as_bytes()),
+    }
+}
+
+impl Encryptor for BuiltinEncryptor {
+    fn encrypt(&self, value: &serde_json::Value) -> Result<String, EngineError> {
+        with_keys(|keys| match keys {
+            Some(keys) => encrypt(keys, &value.to_string().as_bytes()),
+            None => Ok(value.to_string()),
+        })
+    }
+
+    fn decrypt(&self, value: &str) -> Result<serde_json::Value, EngineError> {
+        with_keys(|keys| match keys {
+            Some(keys) => {
+                let value: serde_json::Value = serde_json::from_str(&decrypt(keys, value)?)?;
+                Ok(value)
+            }
+            None => {
+                let value: serde_json::Value = serde_json::from_str(value)?;
+                Ok(value)
+            }
+        })
+    }
+
+    /**
+     * Data already encrypted with the current key is returned as is.
+     */
+    fn reencrypt(&self, value: String) -> Result<String, EngineError> {
+        with_keys(|keys| match keys {
+            Some(keys) => reencrypt(keys, value),
+            None => Ok(value),
+        })
+    }
+}
+
+fn with_encryptor<T>(f: impl FnOnce(&dyn Encryptor) -> T) -> T {
+    let encryptor = read_or_recover(&ENCRYPTOR);
+
+    match encryptor.as_deref() {
+        Some(encryptor) => f(encryptor),
+        None => f(&BuiltinEncryptor),
+    }
+}
+
+/**
+ * Replace the encryptor used to store data, the BuiltinEncryptor is used until it is set.
+ */
+pub fn set_encryptor(encryptor: Box<dyn Encryptor>) {
+    let mut current = write_or_recover(&ENCRYPTOR);
+
+    *current = Some(encryptor);
+}
+
+pub fn encrypt_data(value: &serde_json::Value) -> Result<String, EngineError> {
+    with_encryptor(|encryptor| encryptor.encrypt(value))
+}
+
 pub fn decrypt_data(value: String) -> Result<serde_json::Value, EngineError> {
-    match env::var("ENCRYPTION_SECRET") {
-        Ok(..) => {
-            let value: serde_json::Value = serde_json::from_str(&decrypt(value)?)?;
-            Ok(value)
-        }
-        _ => {
-            let value: serde_json::Value = serde_json::from_str(&value)?;
-            Ok(value)
+    with_encryptor(|encryptor| encryptor.decrypt(&value))
+}
+
+/**
+ * Encrypt again data that was encrypted with a retired key, using the current key.
+ */
+pub fn reencrypt_data(value: String) -> Result<String, EngineError> {
+    with_encryptor(|encryptor| encryptor.reencrypt(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_keys(
+        current_id: &str,
+        current_secret: &str,
+        retired: &[(&str, &str)],
+    ) -> EncryptionKeys {
+        EncryptionKeys {
+            current_id: current_id.to_owned(),
+            current_secret: current_secret.to_owned(),
+            retired_secrets: retired
+                .iter()
+                .map(|(key_id, secret)| (key_id.to_string(), secret.to_string()))
+                .collect(),
         }
     }
+
+    #[test]
+    fn ok_encrypt_with_key_id() {
+        let keys = get_keys("2", "new-secret", &[]);
+
+        let encrypted = encrypt(&keys, b"hello").unwrap();
+
+        assert!(encrypted.starts_with("2:"));
+        assert_eq!(decrypt(&keys, &encrypted).unwrap(), "hello");
+    }
+
+    #[test]
+    fn ok_decrypt_with_retired_key() {
+        let old_keys = get_keys("1", "old-secret", &[]);
+        let encrypted = encrypt(&old_keys, b"hello").unwrap();
+
+        let keys = get_keys("2", "new-secret", &[("1", "old-secret")]);
+        assert_eq!(decrypt(&keys, &encrypted).unwrap(), "hello");
+
+        let reencrypted = reencrypt(&keys, encrypted).unwrap();
+        assert!(reencrypted.starts_with("2:"));
+
+        let new_keys = get_keys("2", "new-secret", &[]);
+        assert_eq!(decrypt(&new_keys, &reencrypted).unwrap(), "hello");
+    }
+
+    #[test]
+    fn ok_decrypt_without_key_id() {
+        let old_keys = get_keys("1", "old-secret", &[]);
+        let encrypted = encrypt(&old_keys, b"hello").unwrap();
+        let (_, legacy) = encrypted.split_once(KEY_ID_SEPARATOR).unwrap();
+
+        let keys = get_keys("2", "new-secret", &[("1", "old-secret")]);
+        assert_eq!(decrypt(&keys, legacy).unwrap(), "hello");
+    }
+
+    #[test]
+    fn ok_retired_secrets_with_numeric_key_id() {
+        let vars = vec![
+            ("ENCRYPTION_SECRET_1", "old-secret"),
+            ("ENCRYPTION_SECRET_FILE", "/run/secrets/encryption"),
+            ("ENCRYPTION_SECRET_", "empty-id"),
+            ("ENCRYPTION_SECRET", "new-secret"),
+        ];
+
+        let secrets = get_retired_secrets(
+            vars.into_iter()
+                .map(|(name, secret)| (name.to_owned(), secret.to_owned())),
+        );
+
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets["1"], "old-secret");
+    }
+
+    #[test]
+    fn err_decrypt_unknown_key_id() {
+        let old_keys = get_keys("1", "old-secret", &[]);
+        let encrypted = encrypt(&old_keys, b"hello").unwrap();
+
+        let keys = get_keys("2", "new-secret", &[("3", "other-secret")]);
+        assert!(decrypt(&keys, &encrypted).is_err());
+        assert!(reencrypt(&keys, encrypted).is_err());
+    }
 }
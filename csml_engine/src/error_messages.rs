@@ -1 +1,9 @@
 pub const ERROR_DB_SETUP: &'static str = "Database connector is not setup correctly";
+pub const ERROR_SHUTTING_DOWN: &'static str =
+    "The engine is shutting down and does not accept new requests";
+pub const ERROR_CONVERSATION_ID: &'static str =
+    "The conversation_id of the request is not a valid id for the database";
+pub const ERROR_RATE_LIMIT_COUNTER: &'static str =
+    "The database connector has no atomic request counter, set REDIS_URL to limit the requests";
+pub const ERROR_BOT_MESSAGES_EXPORT: &'static str =
+    "The database connector can not export the messages of a bot";
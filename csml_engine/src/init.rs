@@ -4,7 +4,7 @@ use crate::{
     data::{ConversationInfo, CsmlRequest, Database, EngineError},
     utils::{
         get_default_flow, get_flow_by_id, get_low_data_mode_value, get_ttl_duration_value,
-        search_flow, send_msg_to_callback_url,
+        push_message, search_flow,
     },
     BotOpt, Context, CsmlBot, CsmlFlow, CsmlResult,
 };
@@ -64,6 +64,7 @@ pub fn init_conversation_info<'a>(
         flow_found,
         &request.client,
         ttl,
+        request.conversation_id.as_deref(),
         &mut db,
     )?;
 
@@ -73,6 +74,14 @@ pub fn init_conversation_info<'a>(
         &context.flow,
     );
 
+    // the memories of the conversation are kept apart, only coalesce_memory reads them first
+    let conversation_memories =
+        get_conversation_memories(&request.client, &conversation_id, &mut db)?;
+    context.conversation = get_hashmap_from_mem(
+        &serde_json::Value::Object(conversation_memories.clone()),
+        &context.flow,
+    );
+
     let mut data = ConversationInfo {
         conversation_id,
         context,
@@ -84,6 +93,9 @@ pub fn init_conversation_info<'a>(
         ttl,
         low_data,
         db,
+        stream: None,
+        conversation_memories,
+        hold_version: 0,
     };
 
     let flow = data.context.flow.to_owned();
@@ -176,12 +188,14 @@ pub fn init_context(
 
     Context {
         current: HashMap::new(),
+        conversation: HashMap::new(),
         metadata: HashMap::new(),
         api_info,
         hold: None,
         step: ContextStepInfo::Normal("start".to_owned()),
         flow,
         previous_bot,
+        now: Some(crate::clock::now()),
     }
 }
 
@@ -194,6 +208,7 @@ fn get_previous_bot(client: &Client, db: &mut Database) -> Option<PreviousBot> {
 
 /**
  * Retrieve the current conversation, or create one if none exists.
+ * The new conversation has the id given by the host, if any.
  */
 fn get_or_create_conversation<'a>(
     context: &mut Context,
@@ -201,6 +216,7 @@ fn get_or_create_conversation<'a>(
     flow_found: Option<(&'a CsmlFlow, String)>,
     client: &Client,
     ttl: Option<chrono::Duration>,
+    conversation_id: Option<&str>,
     db: &mut Database,
 ) -> Result<String, EngineError> {
     match get_latest_open(client, db)? {
@@ -218,7 +234,13 @@ fn get_or_create_conversation<'a>(
                             close_conversation(&conversation.id, &client, db)?;
                             // start new conversation at default flow
                             return create_new_conversation(
-                                context, bot, flow_found, client, ttl, db,
+                                context,
+                                bot,
+                                flow_found,
+                                client,
+                                ttl,
+                                conversation_id,
+                                db,
                             );
                         }
                     };
@@ -230,7 +252,7 @@ fn get_or_create_conversation<'a>(
 
             Ok(conversation.id)
         }
-        None => create_new_conversation(context, bot, flow_found, client, ttl, db),
+        None => create_new_conversation(context, bot, flow_found, client, ttl, conversation_id, db),
     }
 }
 
@@ -243,6 +265,7 @@ fn create_new_conversation<'a>(
     flow_found: Option<(&'a CsmlFlow, String)>,
     client: &Client,
     ttl: Option<chrono::Duration>,
+    conversation_id: Option<&str>,
     db: &mut Database,
 ) -> Result<String, EngineError> {
     let (flow, step) = match flow_found {
@@ -250,7 +273,7 @@ fn create_new_conversation<'a>(
         None => (get_default_flow(bot)?, "start".to_owned()),
     };
 
-    let conversation_id = create_conversation(&flow.id, &step, client, ttl, db)?;
+    let conversation_id = create_conversation(&flow.id, &step, client, ttl, conversation_id, db)?;
 
     context.step = ContextStepInfo::UnknownFlow(step);
     context.flow = flow.name.to_owned();
@@ -300,6 +323,11 @@ pub fn switch_bot(
     // update client with the new bot id
     data.client.bot_id = bot.id.to_owned();
 
+    // the hold of the new client is saved only if it is unchanged since now
+    let (_, hold_version) =
+        state::get_versioned_state_key(&data.client, "hold", "position", &mut data.db)?;
+    data.hold_version = hold_version;
+
     let (flow, step) = match get_flow_by_id(&data.context.flow, &bot.flows) {
         Ok(flow) => (flow, data.context.step.clone()),
         Err(_) => {
@@ -313,10 +341,8 @@ pub fn switch_bot(
                 content: serde_json::json!({"error": error_message.clone()}),
             };
 
-            // save message
-            data.messages.push(message.clone());
-            // send message
-            send_msg_to_callback_url(data, vec![message], 0, false);
+            // save and send message
+            push_message(data, message, 0, false);
 
             // setting default step && flow
             data.context.step = ContextStepInfo::Normal("start".to_owned());
@@ -343,6 +369,7 @@ pub fn switch_bot(
         &step.get_step(),
         &data.client,
         data.ttl.clone(),
+        None,
         &mut data.db,
     )?;
 
@@ -352,6 +379,8 @@ pub fn switch_bot(
         &internal_use_get_memories(&data.client, &mut data.db)?,
         &data.context.flow,
     );
+    data.conversation_memories = serde_json::Map::new();
+    data.context.conversation.clear();
 
     Ok(())
 }
@@ -1,6 +1,12 @@
-use crate::db_connectors::{conversations::*, memories::*, messages::*, state::*};
+use crate::db_connectors::{
+    conversations::*,
+    memories::{delete_client_memories, delete_client_memory, save_conversation_memories},
+    messages::*,
+    state::*,
+};
 use crate::utils::*;
-use crate::{data::*, delete_client_memories};
+use crate::data::*;
+use crate::step_handler::{step_enter, step_exit};
 
 use csml_interpreter::data::context::ContextStepInfo;
 use csml_interpreter::{
@@ -67,31 +73,50 @@ pub fn interpret_step(
         ),
         LogLvl::Debug,
     );
+    // entered before the interpreter runs, the step is left on a hold, a goto or the end
+    step_enter(&data.client, &data.context)?;
+    let mut in_step = true;
+
     let new_bot = bot.clone();
     thread::spawn(move || {
         interpret(new_bot, context, event, Some(sender));
     });
 
     let mut memories = HashMap::new();
+    let mut conversation_memories_changed = false;
+    // set when the conversation is held by a delay, the host resumes it at this date
+    let mut delay_wake_at = None;
 
     for received in receiver {
         match received {
             MSG::Remember(mem) => {
                 memories.insert(mem.key.clone(), mem);
             }
+            MSG::RememberConversation(mem) => {
+                data.conversation_memories.insert(mem.key, mem.value);
+                conversation_memories_changed = true;
+            }
             MSG::Forget(mem) => match mem {
                 ForgetMemory::ALL => {
                     memories.clear();
-                    delete_client_memories(&data.client)?;
+                    data.conversation_memories.clear();
+                    conversation_memories_changed = true;
+                    delete_client_memories(&data.client, &mut data.db)?;
                 }
                 ForgetMemory::SINGLE(memory) => {
                     memories.remove(&memory.ident);
-                    crate::delete_client_memory(&data.client, &memory.ident)?;
+                    if data.conversation_memories.remove(&memory.ident).is_some() {
+                        conversation_memories_changed = true;
+                    }
+                    delete_client_memory(&data.client, &memory.ident, &mut data.db)?;
                 }
                 ForgetMemory::LIST(mem_list) => {
                     for mem in mem_list.iter() {
                         memories.remove(&mem.ident);
-                        crate::delete_client_memory(&data.client, &mem.ident)?;
+                        if data.conversation_memories.remove(&mem.ident).is_some() {
+                            conversation_memories_changed = true;
+                        }
+                        delete_client_memory(&data.client, &mem.ident, &mut data.db)?;
                     }
                 }
             },
@@ -115,8 +140,7 @@ pub fn interpret_step(
                     LogLvl::Debug,
                 );
 
-                send_msg_to_callback_url(data, vec![msg.clone()], interaction_order, false);
-                data.messages.push(msg);
+                push_message(data, msg, interaction_order, false);
             }
             MSG::Log {
                 flow,
@@ -136,6 +160,8 @@ pub fn interpret_step(
                 flow_name,
                 previous,
                 secure,
+                wake_at,
+                retries,
             }) => {
                 let hash = get_current_step_hash(&data.context, bot)?;
                 let state_hold: Value = serde_json::json!({
@@ -143,8 +169,11 @@ pub fn interpret_step(
                     "step_vars": step_vars,
                     "hash": hash,
                     "previous": previous,
-                    "secure": secure
+                    "secure": secure,
+                    "wake_at": wake_at,
+                    "retries": retries
                 });
+                delay_wake_at = wake_at.clone();
 
                 csml_logger(
                     CsmlLog::new(
@@ -165,13 +194,19 @@ pub fn interpret_step(
                     LogLvl::Debug,
                 );
 
-                set_state_items(
+                data.hold_version = set_state_item_if_version(
                     &data.client,
                     "hold",
-                    vec![("position", &state_hold)],
+                    "position",
+                    Some(&state_hold),
+                    data.hold_version,
                     data.ttl,
                     &mut data.db,
                 )?;
+
+                in_step = false;
+                step_exit(&data.client, &data.context)?;
+
                 data.context.hold = Some(Hold {
                     index,
                     step_vars,
@@ -179,6 +214,8 @@ pub fn interpret_step(
                     flow_name,
                     previous,
                     secure,
+                    wake_at,
+                    retries,
                 });
             }
             MSG::Next {
@@ -186,6 +223,9 @@ pub fn interpret_step(
                 step,
                 bot: None,
             } => {
+                in_step = false;
+                step_exit(&data.client, &data.context)?;
+
                 if let Ok(InterpreterReturn::End) = manage_internal_goto(
                     data,
                     &mut conversation_end,
@@ -198,6 +238,9 @@ pub fn interpret_step(
                 ) {
                     break;
                 }
+
+                in_step = true;
+                step_enter(&data.client, &data.context)?;
             }
 
             MSG::Next {
@@ -205,6 +248,9 @@ pub fn interpret_step(
                 step,
                 bot: Some(target_bot),
             } => {
+                in_step = false;
+                step_exit(&data.client, &data.context)?;
+
                 if let Ok(InterpreterReturn::SwitchBot(s_bot)) =
                     manage_switch_bot(data, &mut interaction_order, &bot, flow, step, target_bot)
                 {
@@ -225,13 +271,16 @@ pub fn interpret_step(
                     LogLvl::Error,
                 );
 
-                send_msg_to_callback_url(data, vec![err_msg.clone()], interaction_order, true);
-                data.messages.push(err_msg);
+                push_message(data, err_msg, interaction_order, true);
                 close_conversation(&data.conversation_id, &data.client, &mut data.db)?;
             }
         }
     }
 
+    if in_step {
+        step_exit(&data.client, &data.context)?;
+    }
+
     // save in db
     let msgs: Vec<serde_json::Value> = data
         .messages
@@ -239,21 +288,22 @@ pub fn interpret_step(
         .map(|var| var.clone().message_to_json())
         .collect();
 
-    if !data.low_data {
-        add_messages_bulk(data, msgs, interaction_order, "SEND")?;
+    add_messages_and_memories(data, msgs, interaction_order, "SEND", &memories)?;
+    if conversation_memories_changed {
+        save_conversation_memories(data)?;
     }
 
-    add_memories(data, &memories)?;
+    let mut response = messages_formatter(
+        data,
+        data.messages.clone(),
+        interaction_order,
+        conversation_end,
+    );
+    if let Some(wake_at) = delay_wake_at {
+        response.insert("wake_at".to_owned(), serde_json::json!(wake_at));
+    }
 
-    Ok((
-        messages_formatter(
-            data,
-            data.messages.clone(),
-            interaction_order,
-            conversation_end,
-        ),
-        switch_bot,
-    ))
+    Ok((response, switch_bot))
 }
 
 fn manage_switch_bot<'a>(
@@ -383,10 +433,8 @@ fn manage_switch_bot<'a>(
     };
 
     let message = Message::switch_bot_message(&next_bot.id, &data.client);
-    // save message
-    data.messages.push(message.clone());
-    // send message switch bot
-    send_msg_to_callback_url(data, vec![message], *interaction_order, true);
+    // save and send message switch bot
+    push_message(data, message, *interaction_order, true);
 
     csml_logger(
         CsmlLog::new(
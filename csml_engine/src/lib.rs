@@ -1,23 +1,40 @@
 pub mod data;
 
+mod clock;
+mod conversation_id;
 mod db_connectors;
 mod encrypt;
 mod error_messages;
 mod init;
 mod interpreter_actions;
+mod lock;
 mod send;
+mod shutdown;
+mod step_handler;
 mod utils;
 
+pub use db_connectors::{
+    connector::{Connector, EncryptedMessage, Interaction},
+    DbConversation, MessageCursor,
+};
+#[cfg(feature = "test-utils")]
+pub use db_connectors::in_memory::{InMemoryConnector, InMemoryWrites};
+#[cfg(feature = "test-utils")]
+pub use encrypt::reset_encryption_keys;
+pub use clock::{Clock, SystemClock};
+pub use encrypt::{BuiltinEncryptor, Encryptor};
+pub use step_handler::StepHandler;
+
 pub use csml_interpreter::{
     data::{
         ast::{Expr, Flow, InstructionScope},
         csml_logs::*,
-        error_info::ErrorInfo,
+        error_info::{ErrorCode, ErrorInfo},
         position::Position,
         warnings::Warnings,
-        Client, CsmlResult, Event,
+        Client, CsmlResult, Event, NativeFunction,
     },
-    load_components, search_for_modules,
+    load_components, register_native_function, search_for_modules,
 };
 
 #[cfg(any(feature = "postgresql", feature = "sqlite"))]
@@ -32,17 +49,16 @@ use data::*;
 use db_connectors::{
     bot, clean_db, conversations, init_db, memories, messages, state,
     state::{delete_state_key, set_state_items},
-    user, BotVersion, BotVersionCreated, DbConversation,
+    user, BotVersion, BotVersionCreated,
 };
 use init::*;
 use interpreter_actions::{interpret_step, SwitchBot};
 use utils::*;
 
-use chrono::prelude::*;
 use csml_interpreter::data::{
     csml_bot::CsmlBot, csml_flow::CsmlFlow, Context, Hold, IndexInfo, Memory,
 };
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, sync::mpsc};
 
 /**
  * Initiate a CSML chat request.
@@ -58,14 +74,46 @@ use std::{collections::HashMap, env};
  * - user_id: differentiate users on the same communication channel
  */
 pub fn start_conversation(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    // nobody listens to the stream, the messages are returned at the end of the turn
+    let (sender, _) = mpsc::channel();
+
+    start_conversation_stream(request, bot_opt, sender)
+}
+
+/**
+ * Same as start_conversation, but each message is also sent to `stream` as soon
+ * as it is produced, formatted as in the `messages` array returned at the end of the turn.
+ * Nothing is sent once the flow reaches a `hold` or the end of the conversation.
+ */
+pub fn start_conversation_stream(
     request: CsmlRequest,
     mut bot_opt: BotOpt,
+    stream: mpsc::Sender<serde_json::Value>,
 ) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
     init_logger();
+    // the turn is counted until its writes end, `shutdown` waits for it
+    let _turn = shutdown::start_turn()?;
+
+    // the turn span only carries identifiers, never the payload
+    let turn = tracing::info_span!(
+        "csml_turn",
+        bot_id = %request.client.bot_id,
+        channel_id = %request.client.channel_id,
+        flow = tracing::field::Empty,
+        step = tracing::field::Empty,
+    );
+    let _enter = turn.enter();
 
     let mut formatted_event = format_event(&request)?;
     let mut db = init_db()?;
 
+    if let Some(rate_limited) = check_rate_limit(&request, &mut db)? {
+        return Ok(rate_limited);
+    }
+
     let mut bot = bot_opt.search_bot(&mut db)?;
     init_bot(&mut bot)?;
 
@@ -76,14 +124,17 @@ pub fn start_conversation(
         &bot,
         db,
     )?;
+    data.stream = Some(stream);
 
     check_for_hold(&mut data, &bot, &mut formatted_event)?;
+    turn.record("flow", &data.context.flow.as_str());
+    turn.record("step", &data.context.step.get_step_ref());
 
     /////////// block user event if delay variable si on and delay_time is bigger than current time
     if let Some(delay) = bot.no_interruption_delay {
         if let Some(delay) = state::get_state_key(&data.client, "delay", "content", &mut data.db)? {
             match (delay["delay_value"].as_i64(), delay["timestamp"].as_i64()) {
-                (Some(delay), Some(timestamp)) if timestamp + delay >= Utc::now().timestamp() => {
+                (Some(delay), Some(timestamp)) if timestamp + delay >= clock::now().timestamp() => {
                     return Ok(serde_json::Map::new())
                 }
                 _ => {}
@@ -92,7 +143,7 @@ pub fn start_conversation(
 
         let delay: serde_json::Value = serde_json::json!({
             "delay_value": delay,
-            "timestamp": Utc::now().timestamp()
+            "timestamp": clock::now().timestamp()
         });
 
         set_state_items(
@@ -219,6 +270,59 @@ pub fn get_client_messages(
     messages::get_client_messages(client, &mut db, limit, pagination_key, from_date, to_date)
 }
 
+/**
+ * Get the client's messages grouped by turn: {"turns": [[message, ...], ...]}, each turn
+ * being the messages of one interaction sorted by message_order
+ */
+pub fn get_client_messages_by_turn(
+    client: &Client,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    from_date: Option<i64>,
+    to_date: Option<i64>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    messages::get_client_messages_by_turn(
+        client,
+        &mut db,
+        limit,
+        pagination_key,
+        from_date,
+        to_date,
+    )
+}
+
+/**
+ * Get a page of the client's messages, from the most recent one. The next page is
+ * requested with the next_cursor of the previous one, which is null on the last page.
+ */
+pub fn get_client_messages_page(
+    client: &Client,
+    page_size: i64,
+    cursor: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    messages::get_client_messages_page(client, &mut db, page_size, cursor)
+}
+
+/**
+ * Write every message of a bot, across all its users and channels, to the writer as
+ * newline-delimited JSON with decrypted payloads. Returns the number of written messages.
+ */
+pub fn export_bot_messages<W: std::io::Write>(
+    bot_id: &str,
+    writer: &mut W,
+) -> Result<usize, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    messages::export_bot_messages(bot_id, &mut db, writer)
+}
+
 pub fn get_client_conversations(
     client: &Client,
     limit: Option<i64>,
@@ -230,6 +334,24 @@ pub fn get_client_conversations(
     conversations::get_client_conversations(client, &mut db, limit, pagination_key)
 }
 
+/**
+ * Get the conversations of the client, from the most recent last message, with the dates
+ * of their first and last messages and their number of messages:
+ * {"conversations": [{"conversation_id", "started_at", "last_message_at", "message_count"}],
+ * "pagination_key": ...}. The pagination_key is only set when there is a next page.
+ * The conversations without messages start at their creation and have no last_message_at.
+ */
+pub fn get_conversation_summaries(
+    client: &Client,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    conversations::get_conversation_summaries(client, &mut db, limit, pagination_key)
+}
+
 /**
  * Get current State ether Hold or NULL
  */
@@ -466,7 +588,8 @@ pub fn user_close_all_conversations(client: Client) -> Result<(), EngineError> {
  *
  * If a hold is found, make sure that the flow has not been updated since last conversation.
  * If that's the case, we can not be sure that the hold is in the same position,
- * so we need to clear the hold's position and restart the conversation.
+ * so we need to clear the hold's position and restart the step, or start the flow set
+ * in CSML_FLOW_CHANGED_HANDLER.
  *
  * If the hold is valid, we also need to load the local step memory
  * (context.hold.step_vars) into the conversation context.
@@ -476,15 +599,27 @@ fn check_for_hold(
     bot: &CsmlBot,
     event: &mut Event,
 ) -> Result<(), EngineError> {
-    match state::get_state_key(&data.client, "hold", "position", &mut data.db) {
+    // the hold is cleared as soon as it is resumed, with the version read here: of two requests
+    // resuming the same hold, the second one fails with EngineError::StateConflict
+    let hold = match state::get_versioned_state_key(&data.client, "hold", "position", &mut data.db)
+    {
+        Ok((hold, version)) => {
+            data.hold_version = version;
+            hold
+        }
+        Err(_) => None,
+    };
+
+    match hold {
         // user is currently on hold
-        Ok(Some(hold)) => {
+        Some(hold) => {
             match hold.get("hash") {
                 Some(hash_value) => {
                     let flow_hash = get_current_step_hash(&data.context, bot)?;
-                    // cleanup the current hold and restart flow
+                    // the step changed since the hold: cleanup the current hold and restart
+                    // the step, or go to the flow changed handler
                     if flow_hash != *hash_value {
-                        return clean_hold_and_restart(data);
+                        return clean_hold_and_restart(data, bot);
                     }
                     flow_hash
                 }
@@ -493,10 +628,7 @@ fn check_for_hold(
 
             let index = match serde_json::from_value::<IndexInfo>(hold["index"].clone()) {
                 Ok(index) => index,
-                Err(_) => {
-                    state::delete_state_key(&data.client, "hold", "position", &mut data.db)?;
-                    return Ok(());
-                }
+                Err(_) => return clear_hold(data),
             };
 
             let secure_hold = hold["secure"].as_bool().unwrap_or(false);
@@ -513,13 +645,14 @@ fn check_for_hold(
                 flow_name: data.context.flow.to_owned(),
                 previous: serde_json::from_value(hold["previous"].clone()).unwrap_or(None),
                 secure: secure_hold,
+                wake_at: serde_json::from_value(hold["wake_at"].clone()).unwrap_or(None),
+                retries: serde_json::from_value(hold["retries"].clone()).unwrap_or(0),
             });
 
-            state::delete_state_key(&data.client, "hold", "position", &mut data.db)?;
+            clear_hold(data)?;
         }
         // user is not on hold
-        Ok(None) => (),
-        Err(_) => (),
+        None => (),
     };
     Ok(())
 }
@@ -611,3 +744,58 @@ pub fn delete_expired_data() -> Result<(), EngineError> {
 
     clean_db::delete_expired_data(&mut db)
 }
+
+/**
+ * Encrypt again a value encrypted with a retired ENCRYPTION_SECRET_{key_id} using the
+ * current ENCRYPTION_SECRET, in order to migrate stored data before removing old keys.
+ */
+pub fn reencrypt_data(value: String) -> Result<String, EngineError> {
+    encrypt::reencrypt_data(value)
+}
+
+/**
+ * Encrypt and decrypt the stored messages, memories and states with the given encryptor
+ * instead of the BuiltinEncryptor.
+ */
+pub fn set_encryptor(encryptor: Box<dyn Encryptor>) {
+    encrypt::set_encryptor(encryptor)
+}
+
+/**
+ * Store the data with the connector created by `init` when ENGINE_DB_TYPE is set to `db_type`.
+ */
+pub fn register_connector(db_type: &str, init: fn() -> Result<Box<dyn Connector>, EngineError>) {
+    db_connectors::connector::register_connector(db_type, init)
+}
+
+/**
+ * Call the given handler when the conversations enter and exit their steps.
+ */
+pub fn set_step_handler(handler: Box<dyn StepHandler>) {
+    step_handler::set_step_handler(handler)
+}
+
+/**
+ * Read the dates saved by the engine from the given clock instead of the SystemClock.
+ * The flows read the date of the clock at the start of the turn (`Time()`, `delay`).
+ */
+pub fn set_clock(clock: Box<dyn Clock>) {
+    clock::set_clock(clock)
+}
+
+/**
+ * Stop accepting new requests, they fail with EngineError::ShuttingDown, and wait up to
+ * `timeout` for the running requests to save their data.
+ * Returns the number of requests still running at the timeout.
+ */
+pub fn shutdown(timeout: std::time::Duration) -> usize {
+    shutdown::shutdown(timeout)
+}
+
+/**
+ * Mint a time ordered id of conversation (UUIDv7), like the ids of the conversations opened
+ * without the conversation_id of the request. The ids minted by the engine are increasing.
+ */
+pub fn new_conversation_id() -> String {
+    conversation_id::new_conversation_id().to_string()
+}
@@ -0,0 +1,20 @@
+/**
+ * Locks of the engine globals (clock, encryptor, registries, hooks, sqlite connection).
+ *
+ * These values are swapped or updated in one step and stay consistent when a request
+ * panics with the lock held, so a poisoned lock is taken back instead of failing every
+ * following request.
+ */
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+pub(crate) fn lock_or_recover<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+pub(crate) fn read_or_recover<T: ?Sized>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|err| err.into_inner())
+}
+
+pub(crate) fn write_or_recover<T: ?Sized>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|err| err.into_inner())
+}
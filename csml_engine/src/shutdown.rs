@@ -0,0 +1,94 @@
+/**
+ * Drain of the turns before the engine is stopped, started with `shutdown`.
+ *
+ * A turn is counted from the start of the request to the end of its writes. Once the
+ * engine is shutting down, the new turns fail with EngineError::ShuttingDown and
+ * `shutdown` waits for the running turns to end.
+ */
+use crate::data::EngineError;
+use crate::error_messages::ERROR_SHUTTING_DOWN;
+
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use crate::lock::lock_or_recover;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+struct Turns {
+    shutting_down: bool,
+    running: usize,
+}
+
+static TURNS: Mutex<Turns> = Mutex::new(Turns {
+    shutting_down: false,
+    running: 0,
+});
+static TURN_END: Condvar = Condvar::new();
+
+fn lock_turns() -> MutexGuard<'static, Turns> {
+    lock_or_recover(&TURNS)
+}
+
+/**
+ * Running turn, it is counted until it is dropped
+ */
+pub struct Turn;
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        let mut turns = lock_turns();
+
+        turns.running -= 1;
+        TURN_END.notify_all();
+    }
+}
+
+/**
+ * Count a new turn, unless the engine is shutting down
+ */
+pub fn start_turn() -> Result<Turn, EngineError> {
+    let mut turns = lock_turns();
+
+    if turns.shutting_down {
+        return Err(EngineError::ShuttingDown(ERROR_SHUTTING_DOWN.to_owned()));
+    }
+
+    turns.running += 1;
+    Ok(Turn)
+}
+
+/**
+ * Refuse the new turns and wait up to `timeout` for the running turns to end.
+ * Returns the number of turns still running at the timeout.
+ */
+pub fn shutdown(timeout: Duration) -> usize {
+    let deadline = Instant::now() + timeout;
+    let mut turns = lock_turns();
+
+    turns.shutting_down = true;
+
+    while turns.running > 0 {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+
+        turns = match TURN_END.wait_timeout(turns, deadline - now) {
+            Ok((turns, _)) => turns,
+            Err(err) => err.into_inner().0,
+        };
+    }
+
+    if turns.running > 0 {
+        csml_logger(
+            CsmlLog::new(
+                None,
+                None,
+                None,
+                format!("shutdown timeout, {} turns still running", turns.running),
+            ),
+            LogLvl::Warn,
+        );
+    }
+
+    turns.running
+}
@@ -0,0 +1,66 @@
+/**
+ * Host code run around the steps of the conversations, configured with `set_step_handler`.
+ *
+ * `on_step_enter` is called when the interpreter starts a step: at the start of a request
+ * (the held step when the conversation resumes) and after each goto.
+ * `on_step_exit` is called when it leaves the step: on a goto, when the step holds
+ * (waiting for the next request) and when the request ends.
+ *
+ * An error returned by a handler stops the request with this error.
+ */
+use crate::{Client, EngineError};
+use csml_interpreter::data::Context;
+use crate::lock::{read_or_recover, write_or_recover};
+
+use std::sync::RwLock;
+
+pub trait StepHandler: Send + Sync {
+    fn on_step_enter(&self, _client: &Client, _flow: &str, _step: &str) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_step_exit(&self, _client: &Client, _flow: &str, _step: &str) -> Result<(), EngineError> {
+        Ok(())
+    }
+}
+
+// no handler is called until one is set
+static STEP_HANDLER: RwLock<Option<Box<dyn StepHandler>>> = RwLock::new(None);
+
+fn with_step_handler(
+    f: impl FnOnce(&dyn StepHandler) -> Result<(), EngineError>,
+) -> Result<(), EngineError> {
+    let handler = read_or_recover(&STEP_HANDLER);
+
+    match handler.as_deref() {
+        Some(handler) => f(handler),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Replace the handler called around the steps
+ */
+pub fn set_step_handler(handler: Box<dyn StepHandler>) {
+    let mut current = write_or_recover(&STEP_HANDLER);
+
+    *current = Some(handler);
+}
+
+/**
+ * Call on_step_enter with the current step of the context
+ */
+pub fn step_enter(client: &Client, context: &Context) -> Result<(), EngineError> {
+    let step = context.step.get_step();
+
+    with_step_handler(|handler| handler.on_step_enter(client, &context.flow, &step))
+}
+
+/**
+ * Call on_step_exit with the current step of the context
+ */
+pub fn step_exit(client: &Client, context: &Context) -> Result<(), EngineError> {
+    let step = context.step.get_step();
+
+    with_step_handler(|handler| handler.on_step_exit(client, &context.flow, &step))
+}
@@ -1,11 +1,16 @@
 use crate::{
+    clock,
     data::{ConversationInfo, CsmlRequest, Database, EngineError, FlowTrigger},
-    db_connectors::state::delete_state_key,
+    db_connectors::{
+        conversations::update_conversation,
+        rate_limit::increment_request_count,
+        state::{delete_state_key, set_state_item_if_version},
+    },
     send::send_to_callback_url,
     CsmlBot, CsmlFlow,
 };
 
-use chrono::{prelude::Utc, SecondsFormat};
+use chrono::SecondsFormat;
 use csml_interpreter::{
     data::{
         ast::{Flow, InsertStep, InstructionScope},
@@ -37,6 +42,12 @@ pub fn update_current_context(data: &mut ConversationInfo, memories: &HashMap<St
 
         data.context.current.insert(mem.key.to_owned(), lit);
     }
+
+    for (key, value) in data.conversation_memories.iter() {
+        let lit = json_to_literal(value, Interval::default(), &data.context.flow).unwrap();
+
+        data.context.conversation.insert(key.to_owned(), lit);
+    }
 }
 
 /**
@@ -145,6 +156,11 @@ pub fn format_event(request: &CsmlRequest) -> Result<Event, EngineError> {
         ttl_duration: json_event["ttl_duration"].as_i64(),
         low_data_mode: json_event["low_data_mode"].as_bool(),
         step_limit,
+        goto_loop_limit: None,
+        instruction_limit: None,
+        time_limit: None,
+        seed: None,
+        hold_confidence_default: None,
         secure: json_event["payload"]["secure"].as_bool().unwrap_or(false),
     })
 }
@@ -174,6 +190,20 @@ pub fn send_msg_to_callback_url(
     send_to_callback_url(data, serde_json::json!(messages))
 }
 
+/**
+ * Save a message of the current turn. It is streamed to the listener of the
+ * conversation as soon as it is produced, and sent to the callback_url if any.
+ */
+pub fn push_message(data: &mut ConversationInfo, msg: Message, interaction_order: i32, end: bool) {
+    if let Some(stream) = &data.stream {
+        // the listener may be gone, the messages are still returned at the end of the turn
+        let _ = stream.send(add_info_to_message(data, msg.clone(), interaction_order));
+    }
+
+    send_msg_to_callback_url(data, vec![msg.clone()], interaction_order, end);
+    data.messages.push(msg);
+}
+
 /**
  * Update ConversationInfo data with current information about the request.
  */
@@ -212,7 +242,7 @@ pub fn messages_formatter(
 
     map.insert(
         "received_at".to_owned(),
-        json!(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        json!(clock::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
     );
 
     let mut map_client: Map<String, Value> = Map::new();
@@ -449,10 +479,55 @@ pub fn get_current_step_hash(context: &Context, bot: &CsmlBot) -> Result<String,
     Ok(format!("{:x}", hash.finalize()))
 }
 
-pub fn clean_hold_and_restart(data: &mut ConversationInfo) -> Result<(), EngineError> {
-    delete_state_key(&data.client, "hold", "position", &mut data.db)?;
+/**
+ * Clear the hold position of the client, only if it was not saved by another request since
+ * this request read it: two requests resuming the same hold can not both go on.
+ */
+pub fn clear_hold(data: &mut ConversationInfo) -> Result<(), EngineError> {
+    data.hold_version = set_state_item_if_version(
+        &data.client,
+        "hold",
+        "position",
+        None,
+        data.hold_version,
+        data.ttl,
+        &mut data.db,
+    )?;
+
+    Ok(())
+}
+
+/**
+ * Flow started instead of a held step that was modified since the hold was saved, set with
+ * the CSML_FLOW_CHANGED_HANDLER env var (name or id of the flow). None if it is not set or
+ * if the bot has no such flow.
+ */
+pub fn get_flow_changed_handler(bot: &CsmlBot) -> Option<&CsmlFlow> {
+    let handler = env::var("CSML_FLOW_CHANGED_HANDLER").ok()?;
+
+    get_flow_by_id(&handler, &bot.flows).ok()
+}
+
+/**
+ * The held step was modified since the hold was saved, its index can not be trusted:
+ * the hold is cleared and the step restarts from its beginning, or the conversation goes
+ * to the start of the flow changed handler when one is configured.
+ */
+pub fn clean_hold_and_restart(
+    data: &mut ConversationInfo,
+    bot: &CsmlBot,
+) -> Result<(), EngineError> {
+    clear_hold(data)?;
     data.context.hold = None;
-    return Ok(());
+
+    if let Some(handler) = get_flow_changed_handler(bot) {
+        data.context.flow = handler.name.to_owned();
+        data.context.step = ContextStepInfo::Normal("start".to_owned());
+
+        update_conversation(data, Some(handler.id.to_owned()), Some("start".to_owned()))?;
+    }
+
+    Ok(())
 }
 
 pub fn get_ttl_duration_value(event: Option<&Event>) -> Option<chrono::Duration> {
@@ -484,3 +559,73 @@ pub fn get_low_data_mode_value(event: &Event) -> bool {
 
     return false;
 }
+
+// the rate limit window defaults to one minute
+const RATE_LIMIT_WINDOW: i64 = 60;
+
+/**
+ * Get the maximum number of requests per client and the window in seconds, set with
+ * CSML_RATE_LIMIT_MAX_REQUESTS and CSML_RATE_LIMIT_WINDOW. Without a positive maximum,
+ * the requests are not limited.
+ */
+fn get_rate_limit() -> Option<(i64, i64)> {
+    let max_requests = match env::var("CSML_RATE_LIMIT_MAX_REQUESTS") {
+        Ok(max) => max.parse::<i64>().ok().filter(|max| *max > 0)?,
+        Err(_) => return None,
+    };
+
+    let window = match env::var("CSML_RATE_LIMIT_WINDOW") {
+        Ok(window) => window
+            .parse::<i64>()
+            .ok()
+            .filter(|window| *window > 0)
+            .unwrap_or(RATE_LIMIT_WINDOW),
+        Err(_) => RATE_LIMIT_WINDOW,
+    };
+
+    Some((max_requests, window))
+}
+
+/**
+ * Count the request in the client's current window. Once the client has exceeded the
+ * limit, the request is not interpreted and the returned result holds the number of
+ * seconds before the next window in retry_after.
+ */
+pub fn check_rate_limit(
+    request: &CsmlRequest,
+    db: &mut Database,
+) -> Result<Option<Map<String, Value>>, EngineError> {
+    let (max_requests, window) = match get_rate_limit() {
+        Some(rate_limit) => rate_limit,
+        None => return Ok(None),
+    };
+
+    let now = clock::now().timestamp();
+    let window_start = now - now % window;
+
+    let count = increment_request_count(&request.client, window_start, window, db)?;
+    if count <= max_requests {
+        return Ok(None);
+    }
+
+    let retry_after = window_start + window - now;
+    csml_logger(
+        CsmlLog::new(
+            Some(&request.client),
+            None,
+            None,
+            format!("rate limited, retry after {} seconds", retry_after),
+        ),
+        LogLvl::Info,
+    );
+
+    let mut map: Map<String, Value> = Map::new();
+
+    map.insert("messages".to_owned(), json!([]));
+    map.insert("request_id".to_owned(), json!(request.request_id));
+    map.insert("client".to_owned(), json!(request.client));
+    map.insert("rate_limited".to_owned(), json!(true));
+    map.insert("retry_after".to_owned(), json!(retry_after));
+
+    Ok(Some(map))
+}
@@ -0,0 +1,53 @@
+//! The dates saved by the engine are read from the clock set with `set_clock`:
+//! `cargo test --features test-utils --test clock`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_bot, init_in_memory_client, init_request};
+use chrono::{DateTime, Utc};
+use csml_engine::{data::BotOpt, set_clock, start_conversation, Clock, InMemoryConnector};
+
+/// Clock always giving the same date
+struct FixedClock(DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+#[test]
+fn messages_are_dated_by_the_clock() {
+    let date = DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+    set_clock(Box::new(FixedClock(date.with_timezone(&Utc))));
+    let client = init_in_memory_client();
+
+    let bot = init_bot("clock_test", "start:\n    say \"hello\"\n    goto end");
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(bot)).unwrap();
+
+    let messages = InMemoryConnector::messages(&client).unwrap();
+    assert_eq!(messages.len(), 2);
+    for message in messages {
+        assert_eq!(message["created_at"], "2024-01-02T03:04:05.678000Z");
+    }
+}
+
+#[test]
+fn flows_read_the_date_of_the_clock() {
+    let date = DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+    set_clock(Box::new(FixedClock(date.with_timezone(&Utc))));
+    let client = init_in_memory_client();
+
+    let bot = init_bot(
+        "clock_test",
+        "start:\n    say Time().format()\n    goto end",
+    );
+    let response =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(bot)).unwrap();
+
+    assert_eq!(
+        response["messages"][0]["payload"]["content"]["text"],
+        "2024-01-02T03:04:05.678Z"
+    );
+}
@@ -0,0 +1,469 @@
+//! The engine runs against an in-memory connector registered with `register_connector`,
+//! no database feature is needed: `cargo test --test connector`
+
+mod support;
+
+use crate::support::init_request;
+use csml_engine::{
+    data::{BotOpt, EngineError},
+    get_client_memories, get_client_messages, get_client_messages_page, get_open_conversation,
+    register_connector, start_conversation, Connector, DbConversation, EncryptedMessage,
+    Interaction, MessageCursor,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use serde_json::json;
+use std::sync::{Mutex, MutexGuard};
+
+struct Store {
+    messages: Vec<serde_json::Value>,
+    memories: Vec<(Client, serde_json::Value)>,
+    conversations: Vec<DbConversation>,
+    states: Vec<(Client, serde_json::Value)>,
+}
+
+static STORE: Mutex<Store> = Mutex::new(Store {
+    messages: Vec::new(),
+    memories: Vec::new(),
+    conversations: Vec::new(),
+    states: Vec::new(),
+});
+
+/// Connector keeping the data in STORE, shared by all its instances
+struct InMemoryConnector;
+
+fn store() -> MutexGuard<'static, Store> {
+    STORE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+fn message_position(message: &serde_json::Value) -> MessageCursor {
+    MessageCursor::from_message(message).unwrap()
+}
+
+fn position_key(position: &MessageCursor) -> (&str, i32, i32) {
+    (
+        &position.created_at,
+        position.interaction_order,
+        position.message_order,
+    )
+}
+
+fn client_messages(client: &Client) -> Vec<serde_json::Value> {
+    let mut messages: Vec<serde_json::Value> = store()
+        .messages
+        .iter()
+        .filter(|message| message["client"] == json!(client))
+        .cloned()
+        .collect();
+
+    messages.sort_by(|a, b| {
+        let (a, b) = (message_position(a), message_position(b));
+        position_key(&b).cmp(&position_key(&a))
+    });
+
+    messages
+}
+
+impl Connector for InMemoryConnector {
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError> {
+        let created_at = now();
+
+        for message in messages {
+            store().messages.push(json!({
+                "client": client,
+                "conversation_id": interaction.conversation_id,
+                "flow_id": interaction.flow_id,
+                "step_id": interaction.step_id,
+                "message_order": message.message_order,
+                "interaction_order": interaction.interaction_order,
+                "direction": interaction.direction,
+                "payload": message.payload,
+                "created_at": created_at,
+            }));
+        }
+
+        Ok(())
+    }
+
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        _pagination_key: Option<String>,
+        _from_date: Option<i64>,
+        _to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let mut messages = client_messages(client);
+        messages.truncate(limit.unwrap_or(20) as usize);
+
+        Ok(json!({ "messages": messages }))
+    }
+
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        let mut messages: Vec<serde_json::Value> = client_messages(client)
+            .into_iter()
+            .filter(|message| match &cursor {
+                Some(cursor) => position_key(&message_position(message)) < position_key(cursor),
+                None => true,
+            })
+            .collect();
+        messages.truncate(limit as usize);
+
+        Ok(messages)
+    }
+
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let mut store = store();
+
+        for (key, value) in memories {
+            store
+                .memories
+                .retain(|(owner, memory)| !(owner == client && memory["key"] == key));
+            store.memories.push((
+                client.to_owned(),
+                json!({"key": key, "value": value, "created_at": now()}),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        Ok(store()
+            .memories
+            .iter()
+            .filter(|(owner, _)| owner == client)
+            .map(|(_, memory)| memory.to_owned())
+            .collect())
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        store()
+            .memories
+            .retain(|(owner, memory)| !(owner == client && memory["key"] == key));
+
+        Ok(())
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        store().memories.retain(|(owner, _)| owner != client);
+
+        Ok(())
+    }
+
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let now = now();
+
+        store().conversations.push(DbConversation {
+            id: id.to_owned(),
+            client: client.to_owned(),
+            flow_id: flow_id.to_owned(),
+            step_id: step_id.to_owned(),
+            status: "OPEN".to_owned(),
+            last_interaction_at: now.to_owned(),
+            updated_at: now.to_owned(),
+            created_at: now,
+        });
+
+        Ok(())
+    }
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError> {
+        for conversation in store().conversations.iter_mut() {
+            if conversation.id == id && conversation.client == *client {
+                conversation.status = status.to_owned();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        for conversation in store().conversations.iter_mut() {
+            if conversation.client == *client {
+                conversation.status = "CLOSED".to_owned();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError> {
+        let store = store();
+        let latest =
+            store.conversations.iter().rev().find(|conversation| {
+                conversation.client == *client && conversation.status == "OPEN"
+            });
+
+        Ok(latest.map(|conversation| DbConversation {
+            id: conversation.id.to_owned(),
+            client: conversation.client.to_owned(),
+            flow_id: conversation.flow_id.to_owned(),
+            step_id: conversation.step_id.to_owned(),
+            status: conversation.status.to_owned(),
+            last_interaction_at: conversation.last_interaction_at.to_owned(),
+            updated_at: conversation.updated_at.to_owned(),
+            created_at: conversation.created_at.to_owned(),
+        }))
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        for conversation in store().conversations.iter_mut() {
+            if conversation.id == conversation_id && conversation.client == *client {
+                if let Some(flow_id) = &flow_id {
+                    conversation.flow_id = flow_id.to_owned();
+                }
+                if let Some(step_id) = &step_id {
+                    conversation.step_id = step_id.to_owned();
+                }
+                conversation.updated_at = now();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        _limit: Option<i64>,
+        _pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let store = store();
+        let conversations: Vec<&DbConversation> = store
+            .conversations
+            .iter()
+            .filter(|conversation| conversation.client == *client)
+            .collect();
+
+        Ok(json!({ "conversations": conversations }))
+    }
+
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        _limit: Option<i64>,
+        _pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let mut summaries: Vec<serde_json::Value> = vec![];
+
+        // client_messages is sorted from the most recent message
+        for message in client_messages(client) {
+            let created_at = message["created_at"].to_owned();
+
+            match summaries
+                .iter_mut()
+                .find(|summary| summary["conversation_id"] == message["conversation_id"])
+            {
+                Some(summary) => {
+                    summary["started_at"] = created_at;
+                    summary["message_count"] =
+                        json!(summary["message_count"].as_i64().unwrap() + 1);
+                }
+                None => summaries.push(json!({
+                    "conversation_id": message["conversation_id"],
+                    "started_at": created_at,
+                    "last_message_at": created_at,
+                    "message_count": 1,
+                })),
+            }
+        }
+
+        Ok(json!({ "conversations": summaries }))
+    }
+
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        for (key, value) in items {
+            self.delete_state_key(client, _type, &key)?;
+            store().states.push((
+                client.to_owned(),
+                json!({
+                    "client": client,
+                    "type": _type,
+                    "key": key,
+                    "value": value,
+                    "created_at": now(),
+                }),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        version: i64,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let mut store = store();
+        let is_state = |owner: &Client, state: &serde_json::Value| {
+            owner == client && state["type"] == _type && state["key"] == key
+        };
+
+        let current = store
+            .states
+            .iter()
+            .find(|(owner, state)| is_state(owner, state))
+            .map_or(0, |(_, state)| state["version"].as_i64().unwrap_or(0));
+        if current != version {
+            return Err(EngineError::StateConflict(format!("{} {}", _type, key)));
+        }
+
+        store
+            .states
+            .retain(|(owner, state)| !is_state(owner, state));
+        store.states.push((
+            client.to_owned(),
+            json!({
+                "client": client,
+                "type": _type,
+                "key": key,
+                "value": value,
+                "version": version + 1,
+                "created_at": now(),
+            }),
+        ));
+
+        Ok(())
+    }
+
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        Ok(store()
+            .states
+            .iter()
+            .find(|(owner, state)| owner == client && state["type"] == _type && state["key"] == key)
+            .map(|(_, state)| state.to_owned()))
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        store().states.retain(|(owner, state)| {
+            !(owner == client && state["type"] == _type && state["key"] == key)
+        });
+
+        Ok(())
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"hello\"\n    remember name = \"csml\"\n    hold\n    say \"{{event}} {{name}}\"\n    goto end";
+
+    support::init_bot("connector_test", content)
+}
+
+fn init_client() -> Client {
+    register_connector("memory", || Ok(Box::new(InMemoryConnector)));
+
+    support::init_client("memory")
+}
+
+#[test]
+fn in_memory_conversation_hold() {
+    let client = init_client();
+
+    let result =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hello");
+    assert_eq!(result["conversation_end"], false);
+
+    let conversation = get_open_conversation(&client).unwrap().unwrap();
+    assert_eq!(conversation.flow_id, "Default");
+    assert_eq!(conversation.step_id, "start");
+
+    let result =
+        start_conversation(init_request("hi", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(
+        result["messages"][0]["payload"]["content"]["text"],
+        "hi csml"
+    );
+    assert_eq!(result["conversation_end"], true);
+    assert!(get_open_conversation(&client).unwrap().is_none());
+
+    let memories = get_client_memories(&client).unwrap();
+    assert_eq!(memories[0]["key"], "name");
+    assert_eq!(memories[0]["value"], "csml");
+}
+
+#[test]
+fn in_memory_messages_are_encrypted() {
+    let client = init_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    // the connector only sees the payloads encrypted by the default methods
+    for message in store().messages.iter() {
+        assert!(message["payload"].is_string());
+    }
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    let payloads: Vec<&serde_json::Value> = value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| &message["payload"]["content"]["text"])
+        .collect();
+    assert_eq!(payloads, vec!["hello", "start"]);
+
+    let page = get_client_messages_page(&client, 1, None).unwrap();
+    assert_eq!(page["messages"][0]["payload"]["content"]["text"], "hello");
+
+    let cursor = page["next_cursor"].as_str().unwrap().to_owned();
+    let page = get_client_messages_page(&client, 1, Some(cursor)).unwrap();
+    assert_eq!(page["messages"][0]["payload"]["content"]["text"], "start");
+    assert_eq!(page["next_cursor"], serde_json::Value::Null);
+}
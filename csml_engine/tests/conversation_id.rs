@@ -0,0 +1,100 @@
+//! The conversations opened without the conversation_id of the request get a time ordered
+//! id minted by the engine: `cargo test --features test-utils --test conversation_id`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_in_memory_client, init_request};
+use chrono::{DateTime, Utc};
+use csml_engine::{
+    data::{BotOpt, CsmlRequest},
+    new_conversation_id, set_clock, start_conversation, Clock, InMemoryConnector,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use uuid::Uuid;
+
+/// Clock always giving the same date
+struct FixedClock(DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    support::init_bot(
+        "conversation_id_test",
+        "start:\n    say \"hello\"\n    goto end",
+    )
+}
+
+fn conversation_ids(client: &Client) -> Vec<String> {
+    InMemoryConnector::messages(client)
+        .unwrap()
+        .iter()
+        .map(|message| message["conversation_id"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+fn assert_increasing(ids: &[String]) {
+    for pair in ids.windows(2) {
+        assert!(pair[0] < pair[1], "{} is not before {}", pair[0], pair[1]);
+    }
+}
+
+fn assert_version_7(id: &str) {
+    let uuid = Uuid::parse_str(id).unwrap();
+
+    assert_eq!(uuid.get_version_num(), 7);
+    assert_eq!(uuid.get_variant(), Some(uuid::Variant::RFC4122));
+}
+
+#[test]
+fn generated_ids_are_increasing() {
+    let ids: Vec<String> = (0..10_000).map(|_| new_conversation_id()).collect();
+
+    assert_increasing(&ids);
+    for id in ids.iter() {
+        assert_version_7(id);
+    }
+}
+
+#[test]
+fn generated_ids_are_increasing_on_the_same_millisecond() {
+    // more ids than the counter of a single millisecond
+    let date = DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+    set_clock(Box::new(FixedClock(date.with_timezone(&Utc))));
+
+    let ids: Vec<String> = (0..5_000).map(|_| new_conversation_id()).collect();
+
+    assert_increasing(&ids);
+}
+
+#[test]
+fn conversation_gets_a_generated_id() {
+    let client = init_in_memory_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let ids = conversation_ids(&client);
+    assert_eq!(ids.len(), 2);
+    assert_eq!(ids[0], ids[1]);
+    assert_version_7(&ids[0]);
+}
+
+#[test]
+fn conversation_keeps_the_host_id() {
+    let client = init_in_memory_client();
+    let request = CsmlRequest {
+        conversation_id: Some("host-conversation".to_owned()),
+        ..init_request("start", &client)
+    };
+
+    start_conversation(request, BotOpt::CsmlBot(init_bot())).unwrap();
+
+    assert_eq!(
+        conversation_ids(&client),
+        vec!["host-conversation", "host-conversation"]
+    );
+}
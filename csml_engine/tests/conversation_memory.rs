@@ -0,0 +1,155 @@
+//! Memories of `remember_conversation` are kept until the end of the conversation,
+//! the memories of `remember` are kept for the user.
+//! They run against an in-memory sqlite database: `cargo test --features sqlite --test conversation_memory`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::{init_bot, init_client, init_request};
+use csml_engine::{
+    create_client_memory, data::BotOpt, delete_client, get_client_memories, start_conversation,
+};
+use csml_interpreter::data::Client;
+use serde_json::{json, Value};
+
+fn get_texts(client: &Client, content: &str, text: &str) -> Vec<String> {
+    let result = start_conversation(
+        init_request(text, client),
+        BotOpt::CsmlBot(init_bot("conversation_memory_test", content)),
+    )
+    .unwrap();
+
+    result["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| {
+            message["payload"]["content"]["text"]
+                .as_str()
+                .unwrap()
+                .to_owned()
+        })
+        .collect()
+}
+
+fn get_memory_keys(client: &Client) -> Vec<String> {
+    let memories = get_client_memories(client).unwrap();
+
+    let mut keys: Vec<String> = memories
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|memory: &Value| memory["key"].as_str().unwrap().to_owned())
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[test]
+fn conversation_memory_is_not_kept_in_a_new_conversation() {
+    let client = init_client("sqlite");
+    let content = r#"start:
+    do has_cart = Exists("cart")
+    do has_name = Exists("name")
+    say "{{has_cart}} {{has_name}}"
+    remember_conversation cart = "apple"
+    remember name = "Ada"
+    hold
+    say "{{cart}} {{name}}"
+    goto end"#;
+
+    assert_eq!(get_texts(&client, content, "start"), vec!["false false"]);
+    assert_eq!(get_memory_keys(&client), vec!["name"]);
+
+    // the same conversation sees both memories
+    assert_eq!(get_texts(&client, content, "next"), vec!["apple Ada"]);
+
+    // the conversation ended, only the memory of the user is left
+    assert_eq!(get_texts(&client, content, "start"), vec!["false true"]);
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn conversation_memory_updates_stay_in_the_conversation() {
+    let client = init_client("sqlite");
+    let content = r#"start:
+    do has_items = Exists("items")
+    say "{{has_items}}"
+    remember_conversation items = []
+    do items.push("pear")
+    hold
+    say "{{items.length()}}"
+    goto end"#;
+
+    assert_eq!(get_texts(&client, content, "start"), vec!["false"]);
+    assert!(get_memory_keys(&client).is_empty());
+
+    assert_eq!(get_texts(&client, content, "next"), vec!["1"]);
+    assert_eq!(get_texts(&client, content, "start"), vec!["false"]);
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn remember_saves_the_memory_of_the_user() {
+    let client = init_client("sqlite");
+    let content = r#"start:
+    if (Exists("choice")) {
+        say "{{choice}}"
+        goto end
+    }
+    remember_conversation choice = "conversation choice"
+    remember choice = "user choice"
+    say "saved"
+    goto end"#;
+
+    assert_eq!(get_texts(&client, content, "start"), vec!["saved"]);
+    assert_eq!(get_memory_keys(&client), vec!["choice"]);
+
+    // the conversation ended, the memory of the user is read in a new conversation
+    assert_eq!(get_texts(&client, content, "start"), vec!["user choice"]);
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn memory_of_the_user_is_read_before_the_conversation() {
+    let client = init_client("sqlite");
+    let content = r#"start:
+    remember_conversation cart = "conversation cart"
+    hold
+    say cart
+    goto end"#;
+
+    create_client_memory(&client, "cart".to_owned(), json!("user cart")).unwrap();
+
+    assert!(get_texts(&client, content, "start").is_empty());
+    assert_eq!(get_texts(&client, content, "next"), vec!["user cart"]);
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn coalesce_memory_reads_the_conversation_first() {
+    let client = init_client("sqlite");
+    let content = r#"start:
+    say coalesce_memory("cart")
+    remember_conversation cart = "conversation cart"
+    hold
+    say coalesce_memory("cart")
+    goto end"#;
+
+    create_client_memory(&client, "cart".to_owned(), json!("user cart")).unwrap();
+
+    assert_eq!(get_texts(&client, content, "start"), vec!["user cart"]);
+    assert_eq!(
+        get_texts(&client, content, "next"),
+        vec!["conversation cart"]
+    );
+
+    // the memory of the user is left in a new conversation
+    assert_eq!(get_texts(&client, content, "start"), vec!["user cart"]);
+
+    delete_client(&client).unwrap();
+}
@@ -0,0 +1,42 @@
+//! A conversation held by a delay reports its wake date and stays on hold until then:
+//! `cargo test --features test-utils --test delay`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::init_request;
+use csml_engine::{data::BotOpt, register_connector, start_conversation, InMemoryConnector};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use serde_json::json;
+
+const DB_TYPE: &str = "delay_in_memory";
+
+fn init_bot() -> CsmlBot {
+    support::init_bot(
+        "delay_test",
+        "start:\n    say \"later\"\n    delay 1day\n    say \"woke up\"\n    goto end",
+    )
+}
+
+fn init_client() -> Client {
+    register_connector(DB_TYPE, || Ok(Box::new(InMemoryConnector::new())));
+
+    support::init_client(DB_TYPE)
+}
+
+#[test]
+fn delay_keeps_the_hold_until_wake_at() {
+    let client = init_client();
+
+    let first =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    let wake_at = first["wake_at"].as_str().unwrap().to_owned();
+    assert!(chrono::DateTime::parse_from_rfc3339(&wake_at).unwrap() > chrono::Utc::now());
+    assert_eq!(first["conversation_end"], false);
+
+    // resumed too early: nothing is sent and the wake date is unchanged
+    let second =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(second["messages"], json!([]));
+    assert_eq!(second["wake_at"], json!(wake_at));
+}
@@ -0,0 +1,82 @@
+//! The messages are stored with a custom encryptor set with `set_encryptor`.
+//! They run against an in-memory sqlite database: `cargo test --features sqlite --test encryptor`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::{init_client, init_request};
+use csml_engine::{
+    data::{BotOpt, EngineError},
+    delete_client, get_client_messages, set_encryptor, start_conversation, Encryptor,
+};
+use csml_interpreter::data::csml_bot::CsmlBot;
+use serde_json::json;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+const XOR_KEY: &[u8] = b"csml";
+
+/// Stub encryptor XORing the data with a fixed key, counting its calls
+struct XorEncryptor {
+    calls: Arc<AtomicUsize>,
+}
+
+fn xor(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .zip(XOR_KEY.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect()
+}
+
+impl Encryptor for XorEncryptor {
+    fn encrypt(&self, value: &serde_json::Value) -> Result<String, EngineError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+
+        Ok(base64::encode(xor(value.to_string().as_bytes())))
+    }
+
+    fn decrypt(&self, value: &str) -> Result<serde_json::Value, EngineError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+
+        let bytes = base64::decode(value)?;
+        Ok(serde_json::from_slice(&xor(&bytes))?)
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"hello\"\n    say {\"nested\": [1, 2]}\n    goto end";
+
+    support::init_bot("encryptor_test", content)
+}
+
+#[test]
+fn xor_encryptor_messages() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    set_encryptor(Box::new(XorEncryptor {
+        calls: calls.clone(),
+    }));
+
+    let client = init_client("sqlite");
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    let encrypted = calls.load(Ordering::SeqCst);
+    assert!(encrypted >= 3);
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    let messages = value["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 3);
+    assert!(calls.load(Ordering::SeqCst) >= encrypted + 3);
+
+    let payloads: Vec<&serde_json::Value> =
+        messages.iter().map(|message| &message["payload"]).collect();
+    assert!(payloads.contains(&&json!({"content_type": "text", "content": {"text": "start"}})));
+    assert!(payloads.contains(&&json!({"content_type": "text", "content": {"text": "hello"}})));
+    assert!(payloads
+        .iter()
+        .any(|payload| payload["content"] == json!({"nested": [1, 2]})));
+
+    delete_client(&client).unwrap();
+}
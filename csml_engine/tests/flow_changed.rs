@@ -0,0 +1,104 @@
+//! A hold resumed after its step was modified, with the restart and the handler strategies:
+//! `cargo test --features test-utils --test flow_changed`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_flow, init_request};
+use csml_engine::{data::BotOpt, register_connector, start_conversation, InMemoryConnector};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use std::sync::Mutex;
+
+const DB_TYPE: &str = "flow_changed_in_memory";
+
+// the strategy is read from the env, the tests changing it run one after the other
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+const FIRST_VERSION: &str =
+    "start:\n    say \"before\"\n    hold\n    say \"resumed by {{event}}\"\n    goto end";
+
+const SECOND_VERSION: &str = "start:\n    say \"new first message\"\n    say \"before\"\n    hold\n    say \"resumed by {{event}}\"\n    goto end";
+
+fn init_bot(content: &str) -> CsmlBot {
+    let mut bot = support::init_bot("flow_changed_test", content);
+    bot.flows.push(init_flow(
+        "FlowChanged",
+        "start:\n    say \"the flow changed, got {{event}}\"\n    goto end",
+    ));
+
+    bot
+}
+
+fn init_client() -> Client {
+    register_connector(DB_TYPE, || Ok(Box::new(InMemoryConnector::new())));
+
+    support::init_client(DB_TYPE)
+}
+
+fn sent_texts(client: &Client) -> Vec<serde_json::Value> {
+    InMemoryConnector::messages(client)
+        .unwrap()
+        .iter()
+        .filter(|message| message["direction"] == "SEND")
+        .map(|message| message["payload"]["content"]["text"].clone())
+        .collect()
+}
+
+#[test]
+fn unchanged_step_resumes_the_hold() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    std::env::remove_var("CSML_FLOW_CHANGED_HANDLER");
+    let client = init_client();
+
+    let bot = init_bot(FIRST_VERSION);
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(bot)).unwrap();
+    let bot = init_bot(FIRST_VERSION);
+    start_conversation(init_request("answer", &client), BotOpt::CsmlBot(bot)).unwrap();
+
+    assert_eq!(sent_texts(&client), vec!["before", "resumed by answer"]);
+}
+
+#[test]
+fn changed_step_restarts() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    std::env::remove_var("CSML_FLOW_CHANGED_HANDLER");
+    let client = init_client();
+
+    let bot = init_bot(FIRST_VERSION);
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(bot)).unwrap();
+
+    // the step was redeployed with a new instruction before the hold: its index is not resumed
+    let bot = init_bot(SECOND_VERSION);
+    start_conversation(init_request("answer", &client), BotOpt::CsmlBot(bot)).unwrap();
+    assert_eq!(
+        sent_texts(&client),
+        vec!["before", "new first message", "before"]
+    );
+
+    // the new hold is resumed as usual
+    let bot = init_bot(SECOND_VERSION);
+    start_conversation(init_request("answer", &client), BotOpt::CsmlBot(bot)).unwrap();
+    assert_eq!(
+        sent_texts(&client),
+        vec!["before", "new first message", "before", "resumed by answer"]
+    );
+}
+
+#[test]
+fn changed_step_goes_to_the_handler() {
+    let _lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    std::env::set_var("CSML_FLOW_CHANGED_HANDLER", "FlowChanged");
+    let client = init_client();
+
+    let bot = init_bot(FIRST_VERSION);
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(bot)).unwrap();
+    let bot = init_bot(SECOND_VERSION);
+    let result = start_conversation(init_request("answer", &client), BotOpt::CsmlBot(bot));
+    std::env::remove_var("CSML_FLOW_CHANGED_HANDLER");
+    result.unwrap();
+
+    assert_eq!(
+        sent_texts(&client),
+        vec!["before", "the flow changed, got answer"]
+    );
+}
@@ -0,0 +1,52 @@
+//! A `goto end` ends the conversation, the next event starts a new one:
+//! `cargo test --features test-utils --test goto_end`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_in_memory_client, init_request};
+use csml_engine::{data::BotOpt, get_current_state, get_open_conversation, start_conversation};
+use csml_interpreter::data::csml_bot::CsmlBot;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"start\"\n    hold\n    foreach (item) in [1, 2] {\n        say \"item {{item}}\"\n        goto end\n    }\n    say \"never\"";
+
+    support::init_bot("goto_end_test", content)
+}
+
+fn texts(result: &serde_json::Map<String, serde_json::Value>) -> Vec<&serde_json::Value> {
+    result["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| &message["payload"]["content"]["text"])
+        .collect()
+}
+
+#[test]
+fn goto_end_in_foreach() {
+    let client = init_in_memory_client();
+
+    let result =
+        start_conversation(init_request("a", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(texts(&result), vec!["start"]);
+    assert_eq!(result["conversation_end"], false);
+    assert!(get_current_state(&client).unwrap().is_some());
+
+    // the end inside the loop ends the whole turn, the hold is cleared
+    let result =
+        start_conversation(init_request("b", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(texts(&result), vec!["item 1"]);
+    assert_eq!(result["conversation_end"], true);
+    assert!(get_open_conversation(&client).unwrap().is_none());
+    assert!(get_current_state(&client).unwrap().is_none());
+
+    let result =
+        start_conversation(init_request("c", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(texts(&result), vec!["start"]);
+    assert_eq!(result["conversation_end"], false);
+    assert_eq!(
+        get_open_conversation(&client).unwrap().unwrap().step_id,
+        "start"
+    );
+}
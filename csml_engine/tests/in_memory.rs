@@ -0,0 +1,306 @@
+//! The engine runs against the `InMemoryConnector` of the `test-utils` feature:
+//! `cargo test --features test-utils --test in_memory`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_bot, init_in_memory_client, init_request};
+use csml_engine::{
+    data::BotOpt, get_client_messages, get_client_messages_by_turn, get_conversation_summaries,
+    start_conversation, InMemoryConnector, Interaction,
+};
+use csml_interpreter::data::Client;
+use serde_json::json;
+
+#[test]
+fn in_memory_seeded_memories() {
+    let client = init_in_memory_client();
+    let content = "start:\n    say \"hello {{name}}\"\n    remember count = count + 1\n    forget name\n    goto end";
+
+    InMemoryConnector::seed_memories(&client, &[("name", json!("csml")), ("count", json!(1))])
+        .unwrap();
+
+    let result = start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", content)),
+    )
+    .unwrap();
+    assert_eq!(
+        result["messages"][0]["payload"]["content"]["text"],
+        "hello csml"
+    );
+
+    // only the memories changed by the request are written
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    assert_eq!(json!(writes.memories), json!({"count": 2}));
+    assert_eq!(writes.deleted_memories, vec!["name"]);
+    assert_eq!(
+        InMemoryConnector::memories(&client).unwrap(),
+        json!({"count": 2})
+    );
+
+    InMemoryConnector::clear(&client);
+    assert_eq!(InMemoryConnector::memories(&client).unwrap(), json!({}));
+}
+
+#[test]
+fn in_memory_memories_in_one_batch() {
+    let client = init_in_memory_client();
+    let remembers: String = (0..30)
+        .map(|index| format!("    remember mem_{} = {}\n", index, index))
+        .collect();
+    let content = format!(
+        "start:\n    remember count = 1\n    say \"{{{{count}}}}\"\n    remember count = count + 1\n\
+         {}    say \"{{{{count}}}} {{{{mem_29}}}}\"\n    goto end",
+        remembers
+    );
+
+    InMemoryConnector::seed_memories(&client, &[("count", json!(0))]).unwrap();
+
+    let result = start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", &content)),
+    )
+    .unwrap();
+
+    // the memories remembered in the turn are read before they are saved
+    assert_eq!(result["messages"][0]["payload"]["content"]["text"], "1");
+    assert_eq!(result["messages"][1]["payload"]["content"]["text"], "2 29");
+
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    assert_eq!(writes.memory_batches, 1);
+    assert_eq!(writes.memories.len(), 31);
+    assert_eq!(writes.memories["count"], json!(2));
+}
+
+#[test]
+fn in_memory_sensitive_memories() {
+    let client = init_in_memory_client();
+    let content = "start:\n    remember secret ssn = \"123-45-6789\"\n    hold\n    remember ssn = \"987-65-4321\"\n    say \"{{ssn}}\"\n    goto end";
+    let sensitive = |value: &str| json!({"_additional_info": {"sensitive": true}, "value": value});
+
+    start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", content)),
+    )
+    .unwrap();
+
+    // the real value is saved, tagged as sensitive
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    assert_eq!(writes.memories["ssn"], sensitive("123-45-6789"));
+
+    let result = start_conversation(
+        init_request("next", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", content)),
+    )
+    .unwrap();
+    assert_eq!(
+        result["messages"][0]["payload"]["content"]["text"],
+        "987-65-4321"
+    );
+
+    // the memory read in the next turn is still sensitive
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    assert_eq!(writes.memories["ssn"], sensitive("987-65-4321"));
+}
+
+#[test]
+fn in_memory_written_messages() {
+    let client = init_in_memory_client();
+    let content = "start:\n    say \"hello\"\n    hold\n    say \"{{event}}\"\n    goto end";
+
+    start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", content)),
+    )
+    .unwrap();
+
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    let messages: Vec<(&serde_json::Value, &serde_json::Value)> = writes
+        .messages
+        .iter()
+        .map(|message| {
+            (
+                &message["direction"],
+                &message["payload"]["content"]["text"],
+            )
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (&json!("RECEIVE"), &json!("start")),
+            (&json!("SEND"), &json!("hello"))
+        ]
+    );
+
+    start_conversation(
+        init_request("hi", &client),
+        BotOpt::CsmlBot(init_bot("in_memory_test", content)),
+    )
+    .unwrap();
+
+    // the writes of the first request were already taken
+    let writes = InMemoryConnector::take_writes(&client).unwrap();
+    assert_eq!(writes.messages.len(), 2);
+    assert_eq!(writes.messages[1]["payload"]["content"]["text"], "hi");
+    assert_eq!(writes.messages[1]["step_id"], "start");
+    assert_eq!(InMemoryConnector::messages(&client).unwrap().len(), 4);
+}
+
+#[test]
+fn in_memory_seeded_messages() {
+    let client = init_in_memory_client();
+    let interaction = Interaction {
+        conversation_id: "seeded",
+        flow_id: "Default",
+        step_id: "start",
+        interaction_order: 0,
+        direction: "SEND",
+        ttl: None,
+    };
+
+    InMemoryConnector::seed_messages(
+        &client,
+        &interaction,
+        &[json!({"content_type": "text", "content": {"text": "seeded"}})],
+    )
+    .unwrap();
+
+    // the seeded messages are saved encrypted and are not part of the writes
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    assert_eq!(value["messages"][0]["payload"]["content"]["text"], "seeded");
+    assert_eq!(
+        InMemoryConnector::take_writes(&client).unwrap(),
+        Default::default()
+    );
+}
+
+fn texts(turn: &serde_json::Value) -> Vec<&str> {
+    turn.as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["payload"]["content"]["text"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn in_memory_messages_by_turn() {
+    let client = init_in_memory_client();
+    let turns = [
+        (0, "RECEIVE", vec!["hi"]),
+        (1, "SEND", vec!["hello", "how are you?"]),
+        (0, "RECEIVE", vec!["fine"]),
+        (1, "SEND", vec!["great", "bye", "see you"]),
+    ];
+
+    for (interaction_order, direction, messages) in turns.iter() {
+        let interaction = Interaction {
+            conversation_id: "conversation",
+            flow_id: "Default",
+            step_id: "start",
+            interaction_order: *interaction_order,
+            direction,
+            ttl: None,
+        };
+        let messages: Vec<serde_json::Value> = messages
+            .iter()
+            .map(|text| json!({"content_type": "text", "content": {"text": text}}))
+            .collect();
+
+        InMemoryConnector::seed_messages(&client, &interaction, &messages).unwrap();
+    }
+
+    // from the most recent turn, each one in the order of its messages
+    let value = get_client_messages_by_turn(&client, None, None, None, None).unwrap();
+    let turns = value["turns"].as_array().unwrap();
+
+    assert!(value.get("messages").is_none());
+    assert_eq!(turns.len(), 4);
+    assert_eq!(texts(&turns[0]), vec!["great", "bye", "see you"]);
+    assert_eq!(texts(&turns[1]), vec!["fine"]);
+    assert_eq!(texts(&turns[2]), vec!["hello", "how are you?"]);
+    assert_eq!(texts(&turns[3]), vec!["hi"]);
+    assert_eq!(turns[0][2]["message_order"], 2);
+}
+
+#[test]
+fn in_memory_messages_by_turn_empty() {
+    let client = init_in_memory_client();
+
+    let value = get_client_messages_by_turn(&client, None, None, None, None).unwrap();
+    assert_eq!(value["turns"], json!([]));
+}
+
+fn seed_conversation_message(client: &Client, conversation_id: &str, text: &str) {
+    let interaction = Interaction {
+        conversation_id,
+        flow_id: "Default",
+        step_id: "start",
+        interaction_order: 0,
+        direction: "SEND",
+        ttl: None,
+    };
+
+    InMemoryConnector::seed_messages(
+        client,
+        &interaction,
+        &[json!({"content_type": "text", "content": {"text": text}})],
+    )
+    .unwrap();
+    // the messages of each call have a different date
+    std::thread::sleep(std::time::Duration::from_millis(2));
+}
+
+#[test]
+fn in_memory_conversation_summaries() {
+    let client = init_in_memory_client();
+
+    seed_conversation_message(&client, "first", "hi");
+    seed_conversation_message(&client, "first", "hello");
+    seed_conversation_message(&client, "second", "hey");
+    seed_conversation_message(&client, "first", "bye");
+
+    let dates: Vec<serde_json::Value> = InMemoryConnector::messages(&client)
+        .unwrap()
+        .iter()
+        .map(|message| message["created_at"].to_owned())
+        .collect();
+
+    // from the most recent last message
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    assert!(value.get("pagination_key").is_none());
+    assert_eq!(
+        value["conversations"],
+        json!([
+            {
+                "conversation_id": "first",
+                "started_at": dates[0],
+                "last_message_at": dates[3],
+                "message_count": 3,
+            },
+            {
+                "conversation_id": "second",
+                "started_at": dates[2],
+                "last_message_at": dates[2],
+                "message_count": 1,
+            },
+        ])
+    );
+
+    let value = get_conversation_summaries(&client, Some(1), None).unwrap();
+    assert_eq!(value["conversations"][0]["conversation_id"], "first");
+    assert_eq!(value["pagination_key"], "2");
+
+    let value = get_conversation_summaries(&client, Some(1), Some("2".to_owned())).unwrap();
+    assert_eq!(value["conversations"][0]["conversation_id"], "second");
+    assert!(value.get("pagination_key").is_none());
+}
+
+#[test]
+fn in_memory_conversation_summaries_empty() {
+    let client = init_in_memory_client();
+
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    assert_eq!(value, json!({ "conversations": [] }));
+}
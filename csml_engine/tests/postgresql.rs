@@ -0,0 +1,79 @@
+//! Integration tests for the postgresql connector.
+//! They need a running database: `POSTGRESQL_URL=... cargo test --features postgresql --test postgresql`
+#![cfg(feature = "postgresql")]
+
+mod support;
+
+use crate::support::init_request;
+use csml_engine::{
+    data::BotOpt,
+    delete_client, get_client_memories, get_client_messages, make_migrations, start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"hello\"\n    remember name = \"csml\"\n    say \"world\"\n    goto end";
+
+    support::init_bot("postgresql_test", content)
+}
+
+fn init_client() -> Client {
+    let client = support::init_client("postgresql");
+    make_migrations().unwrap();
+
+    client
+}
+
+#[test]
+fn postgresql_messages() {
+    let client = init_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    let messages = value["messages"].as_array().unwrap();
+
+    // one received message and two sent messages, newest first
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0]["payload"]["content"]["text"], "world");
+    assert_eq!(messages[0]["direction"], "SEND");
+    assert_eq!(messages[0]["client"]["bot_id"], client.bot_id.as_str());
+    assert_eq!(messages[0]["flow_id"], "Default");
+    assert!(messages[0]["created_at"].is_string());
+    assert!(value.get("pagination_key").is_none());
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn postgresql_messages_pagination() {
+    let client = init_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let value = get_client_messages(&client, Some(2), None, None, None).unwrap();
+    assert_eq!(value["messages"].as_array().unwrap().len(), 2);
+    assert_eq!(value["pagination_key"], "2");
+
+    let value = get_client_messages(&client, Some(2), Some("2".to_owned()), None, None).unwrap();
+    assert_eq!(value["messages"].as_array().unwrap().len(), 1);
+    assert!(value.get("pagination_key").is_none());
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn postgresql_memories() {
+    let client = init_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let memories = get_client_memories(&client).unwrap();
+    let memories = memories.as_array().unwrap();
+
+    assert_eq!(memories.len(), 1);
+    assert_eq!(memories[0]["key"], "name");
+    assert_eq!(memories[0]["value"], "csml");
+
+    delete_client(&client).unwrap();
+}
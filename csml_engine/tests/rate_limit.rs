@@ -0,0 +1,108 @@
+//! The requests of a client are limited with CSML_RATE_LIMIT_MAX_REQUESTS.
+//! They run against an in-memory sqlite database: `cargo test --features sqlite --test rate_limit`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::init_request;
+use csml_engine::{
+    data::BotOpt, delete_client, delete_client_memories, get_client_memories, get_client_messages,
+    start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    remember visited = true\n    say \"hello\"\n    goto end";
+
+    support::init_bot("rate_limit_test", content)
+}
+
+fn init_client() -> Client {
+    std::env::set_var("CSML_RATE_LIMIT_MAX_REQUESTS", "2");
+    std::env::set_var("CSML_RATE_LIMIT_WINDOW", "3600");
+
+    support::init_client("sqlite")
+}
+
+#[test]
+fn rate_limit_exceeded() {
+    let client = init_client();
+
+    for _ in 0..2 {
+        let result =
+            start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot()))
+                .unwrap();
+        assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hello");
+        assert!(result.get("rate_limited").is_none());
+    }
+    delete_client_memories(&client).unwrap();
+
+    let result =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(result["rate_limited"], true);
+    assert_eq!(result["client"]["user_id"], "test");
+    assert!(result["messages"].as_array().unwrap().is_empty());
+
+    let retry_after = result["retry_after"].as_i64().unwrap();
+    assert!(retry_after > 0 && retry_after <= 3600);
+
+    // the flow was not executed and the request was not saved
+    assert!(get_client_memories(&client)
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .is_empty());
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    assert_eq!(value["messages"].as_array().unwrap().len(), 4);
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn rate_limit_per_client() {
+    let client = init_client();
+    let other_client = Client {
+        user_id: "other".to_owned(),
+        ..client.clone()
+    };
+
+    for _ in 0..3 {
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    }
+
+    let result = start_conversation(
+        init_request("start", &other_client),
+        BotOpt::CsmlBot(init_bot()),
+    )
+    .unwrap();
+    assert!(result.get("rate_limited").is_none());
+    assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hello");
+
+    delete_client(&client).unwrap();
+    delete_client(&other_client).unwrap();
+}
+
+#[test]
+fn rate_limit_concurrent_requests() {
+    let client = init_client();
+
+    // the requests are counted atomically, no request of the window is lost
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            std::thread::spawn(move || {
+                start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot()))
+                    .unwrap()
+            })
+        })
+        .collect();
+
+    let rate_limited = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .filter(|result| result.get("rate_limited").is_some())
+        .count();
+    assert_eq!(rate_limited, 2);
+
+    delete_client(&client).unwrap();
+}
@@ -0,0 +1,69 @@
+//! `shutdown` refuses the new turns and waits for the running turns to save their data:
+//! `cargo test --features test-utils --test shutdown`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::{init_in_memory_client, init_request};
+use csml_engine::{
+    data::{BotOpt, EngineError},
+    set_step_handler, shutdown, start_conversation, InMemoryConnector, StepHandler,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// told when a turn enters its first step
+static TURN_STARTED: Mutex<Option<mpsc::Sender<()>>> = Mutex::new(None);
+
+/// Handler making the turns slow, once they are started
+struct SlowHandler;
+
+impl StepHandler for SlowHandler {
+    fn on_step_enter(&self, _client: &Client, _flow: &str, _step: &str) -> Result<(), EngineError> {
+        if let Some(sender) = TURN_STARTED.lock().unwrap().as_ref() {
+            sender.send(()).unwrap();
+        }
+
+        thread::sleep(Duration::from_millis(300));
+        Ok(())
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    support::init_bot("shutdown_test", "start:\n    say \"hello\"\n    goto end")
+}
+
+#[test]
+fn shutdown_waits_for_the_running_turns() {
+    let client = init_in_memory_client();
+    let (sender, receiver) = mpsc::channel();
+    *TURN_STARTED.lock().unwrap() = Some(sender);
+    set_step_handler(Box::new(SlowHandler));
+
+    let request = init_request("start", &client);
+    let turn = thread::spawn(move || start_conversation(request, BotOpt::CsmlBot(init_bot())));
+    receiver.recv().unwrap();
+
+    // the turn is still running at the timeout
+    assert_eq!(shutdown(Duration::from_millis(10)), 1);
+
+    // the new turns are refused while the running turn ends
+    match start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())) {
+        Err(EngineError::ShuttingDown(_)) => {}
+        other => panic!("expected the engine to be shutting down, got {:?}", other),
+    }
+
+    assert_eq!(shutdown(Duration::from_secs(10)), 0);
+
+    // the turn saved its messages before shutdown returned
+    let messages = InMemoryConnector::messages(&client).unwrap();
+    let directions: Vec<&str> = messages
+        .iter()
+        .map(|message| message["direction"].as_str().unwrap())
+        .collect();
+    assert_eq!(directions, vec!["RECEIVE", "SEND"]);
+
+    turn.join().unwrap().unwrap();
+}
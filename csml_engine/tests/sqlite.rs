@@ -0,0 +1,294 @@
+//! Integration tests for the sqlite connector.
+//! They run against an in-memory database: `cargo test --features sqlite --test sqlite`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::{init_client, init_request};
+use csml_engine::{
+    data::{BotOpt, EngineError},
+    delete_client, export_bot_messages, get_client_memories, get_client_messages,
+    get_conversation_summaries, get_open_conversation, start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use serde_json::json;
+use uuid::Uuid;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"hello\"\n    remember name = \"csml\"\n    hold\n    say \"{{event}} {{name}}\"\n    goto forget_step\n\nforget_step:\n    forget name\n    say \"bye\"\n    goto end";
+
+    support::init_bot("sqlite_test", content)
+}
+
+fn init_export_bot(bot_id: &str) -> CsmlBot {
+    let content = "start:\n    do i = 0\n    while (i < 150) {\n        say \"{{i}}\"\n        do i = i + 1\n    }\n    goto end";
+
+    let mut bot = init_bot();
+    bot.id = bot_id.to_owned();
+    bot.flows[0].content = content.to_owned();
+
+    bot
+}
+
+#[test]
+fn sqlite_conversation_hold() {
+    let client = init_client("sqlite");
+
+    let result =
+        start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hello");
+
+    // the conversation waits for the next event on the hold
+    let conversation = get_open_conversation(&client).unwrap().unwrap();
+    assert_eq!(conversation.flow_id, "Default");
+    assert_eq!(conversation.step_id, "start");
+
+    let memories = get_client_memories(&client).unwrap();
+    let memories = memories.as_array().unwrap();
+    assert_eq!(memories.len(), 1);
+    assert_eq!(memories[0]["key"], "name");
+    assert_eq!(memories[0]["value"], "csml");
+
+    let result =
+        start_conversation(init_request("world", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(
+        result["messages"][0]["payload"]["content"]["text"],
+        "world csml"
+    );
+    assert_eq!(result["messages"][1]["payload"]["content"]["text"], "bye");
+
+    assert!(get_open_conversation(&client).unwrap().is_none());
+    assert!(get_client_memories(&client)
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn sqlite_concurrent_conversations() {
+    // the connection is locked by each query, the conversations run side by side
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            std::thread::spawn(|| {
+                let client = init_client("sqlite");
+                let result =
+                    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot()))
+                        .unwrap();
+                assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hello");
+
+                delete_client(&client).unwrap();
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn sqlite_conversation_id() {
+    let client = init_client("sqlite");
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    let conversation = get_open_conversation(&client).unwrap().unwrap();
+    let generated = Uuid::parse_str(&conversation.id).unwrap();
+    assert_eq!(generated.get_version_num(), 7);
+    delete_client(&client).unwrap();
+
+    // the id given by the host is kept, it must be a UUID to be saved
+    let host_id = Uuid::new_v4().to_string();
+    let mut request = init_request("start", &client);
+    request.conversation_id = Some(host_id.to_owned());
+    start_conversation(request, BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let conversation = get_open_conversation(&client).unwrap().unwrap();
+    assert_eq!(conversation.id, host_id);
+    delete_client(&client).unwrap();
+
+    let mut request = init_request("start", &client);
+    request.conversation_id = Some("host-conversation".to_owned());
+    match start_conversation(request, BotOpt::CsmlBot(init_bot())) {
+        Err(EngineError::Format(_)) => {}
+        other => panic!(
+            "expected the conversation_id to be refused, got {:?}",
+            other
+        ),
+    }
+}
+
+#[test]
+fn sqlite_messages() {
+    let client = init_client("sqlite");
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    start_conversation(init_request("world", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    let messages = value["messages"].as_array().unwrap();
+
+    // two received messages and three sent messages
+    assert_eq!(messages.len(), 5);
+    assert_eq!(messages[0]["client"]["bot_id"], client.bot_id.as_str());
+    assert_eq!(messages[0]["flow_id"], "Default");
+    assert!(messages[0]["created_at"].is_string());
+
+    let received: Vec<&str> = messages
+        .iter()
+        .filter(|message| message["direction"] == "RECEIVE")
+        .map(|message| message["payload"]["content"]["text"].as_str().unwrap())
+        .collect();
+    assert_eq!(received.len(), 2);
+    assert!(received.contains(&"start") && received.contains(&"world"));
+
+    delete_client(&client).unwrap();
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    assert!(value["messages"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn sqlite_conversation_summaries() {
+    let client = init_client("sqlite");
+
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    assert_eq!(value, json!({ "conversations": [] }));
+
+    // the first conversation ends after the hold, the next request starts a second one
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    start_conversation(init_request("world", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    start_conversation(init_request("again", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let value = get_client_messages(&client, None, None, None, None).unwrap();
+    let messages = value["messages"].as_array().unwrap();
+
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    let summaries = value["conversations"].as_array().unwrap();
+
+    assert!(value.get("pagination_key").is_none());
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0]["message_count"], 2);
+    assert_eq!(summaries[1]["message_count"], 5);
+
+    for summary in summaries {
+        let mut dates: Vec<&str> = messages
+            .iter()
+            .filter(|message| message["conversation_id"] == summary["conversation_id"])
+            .map(|message| message["created_at"].as_str().unwrap())
+            .collect();
+        dates.sort();
+
+        assert_eq!(summary["started_at"], dates[0]);
+        assert_eq!(summary["last_message_at"], dates[dates.len() - 1]);
+    }
+    assert!(summaries[0]["last_message_at"].as_str() >= summaries[1]["last_message_at"].as_str());
+
+    let value = get_conversation_summaries(&client, Some(1), None).unwrap();
+    assert_eq!(value["conversations"][0], summaries[0]);
+    assert_eq!(value["pagination_key"], "2");
+
+    let value = get_conversation_summaries(&client, Some(1), Some("2".to_owned())).unwrap();
+    assert_eq!(value["conversations"][0], summaries[1]);
+    assert!(value.get("pagination_key").is_none());
+}
+
+#[test]
+fn sqlite_conversation_summaries_without_messages() {
+    let client = init_client("sqlite");
+
+    // the messages of the low data mode are not saved
+    let mut request = init_request("start", &client);
+    request.low_data_mode = Some(json!(true));
+    start_conversation(request, BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let conversation = get_open_conversation(&client).unwrap().unwrap();
+
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "conversations": [{
+                "conversation_id": conversation.id,
+                "started_at": conversation.created_at,
+                "last_message_at": null,
+                "message_count": 0,
+            }]
+        })
+    );
+
+    // the messages are counted once saved
+    start_conversation(init_request("world", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let value = get_conversation_summaries(&client, None, None).unwrap();
+    assert_eq!(value["conversations"][0]["conversation_id"], conversation.id);
+    assert_eq!(value["conversations"][0]["message_count"], 3);
+    assert!(value["conversations"][0]["last_message_at"].is_string());
+}
+
+#[test]
+fn sqlite_export_bot_messages() {
+    let client = init_client("sqlite");
+    let other_client = Client {
+        channel_id: Uuid::new_v4().to_string(),
+        ..client.clone()
+    };
+
+    for client in [&client, &other_client].iter() {
+        let bot = init_export_bot(&client.bot_id);
+        start_conversation(init_request("start", client), BotOpt::CsmlBot(bot)).unwrap();
+    }
+
+    let mut buffer = vec![];
+    let count = export_bot_messages(&client.bot_id, &mut buffer).unwrap();
+
+    // one received message and 150 sent messages per client, read over several pages
+    let lines: Vec<&str> = std::str::from_utf8(&buffer).unwrap().lines().collect();
+    assert_eq!(count, 302);
+    assert_eq!(lines.len(), 302);
+
+    let messages: Vec<serde_json::Value> = lines
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    for message in messages.iter() {
+        assert_eq!(message["client"]["bot_id"], client.bot_id.as_str());
+        assert!(message["conversation_id"].is_string());
+        assert!(message["created_at"].is_string());
+    }
+
+    let channels: Vec<&str> = messages
+        .iter()
+        .map(|message| message["client"]["channel_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        channels
+            .iter()
+            .filter(|channel| **channel == client.channel_id)
+            .count(),
+        151
+    );
+
+    // the payloads are decrypted
+    let texts: Vec<&str> = messages
+        .iter()
+        .filter_map(|message| message["payload"]["content"]["text"].as_str())
+        .collect();
+    assert!(texts.contains(&"start") && texts.contains(&"149"));
+
+    delete_client(&client).unwrap();
+    delete_client(&other_client).unwrap();
+}
+
+#[test]
+fn sqlite_export_empty_bot() {
+    init_client("sqlite");
+
+    let mut buffer = vec![];
+    let count = export_bot_messages(&Uuid::new_v4().to_string(), &mut buffer).unwrap();
+
+    assert_eq!(count, 0);
+    assert!(buffer.is_empty());
+}
@@ -0,0 +1,285 @@
+//! Two requests resuming the same hold, the versioned state of the hold lets only one go on:
+//! `cargo test --features test-utils --test state_conflict`
+#![cfg(feature = "test-utils")]
+
+mod support;
+
+use crate::support::init_request;
+use csml_engine::{
+    data::{BotOpt, CsmlRequest, EngineError},
+    register_connector, start_conversation, Connector, DbConversation, EncryptedMessage,
+    InMemoryConnector, Interaction, MessageCursor,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use serde_json::json;
+use std::sync::Mutex;
+
+const DB_TYPE: &str = "racing_in_memory";
+
+// request run by the connector when the next request reads the hold, before it goes on
+static CONCURRENT_REQUEST: Mutex<Option<CsmlRequest>> = Mutex::new(None);
+
+/// In-memory connector running CONCURRENT_REQUEST between the read of the hold and its use
+struct RacingConnector(InMemoryConnector);
+
+impl Connector for RacingConnector {
+    fn save_messages(
+        &mut self,
+        client: &Client,
+        interaction: &Interaction,
+        messages: Vec<EncryptedMessage>,
+    ) -> Result<(), EngineError> {
+        self.0.save_messages(client, interaction, messages)
+    }
+
+    fn query_messages(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+        from_date: Option<i64>,
+        to_date: Option<i64>,
+    ) -> Result<serde_json::Value, EngineError> {
+        self.0
+            .query_messages(client, limit, pagination_key, from_date, to_date)
+    }
+
+    fn query_messages_page(
+        &mut self,
+        client: &Client,
+        limit: i64,
+        cursor: Option<MessageCursor>,
+    ) -> Result<Vec<serde_json::Value>, EngineError> {
+        self.0.query_messages_page(client, limit, cursor)
+    }
+
+    fn save_memories(
+        &mut self,
+        client: &Client,
+        memories: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.0.save_memories(client, memories, ttl)
+    }
+
+    fn query_memories(&mut self, client: &Client) -> Result<Vec<serde_json::Value>, EngineError> {
+        self.0.query_memories(client)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        self.0.delete_client_memory(client, key)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.0.delete_client_memories(client)
+    }
+
+    fn create_conversation(
+        &mut self,
+        id: &str,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.0
+            .create_conversation(id, flow_id, step_id, client, ttl)
+    }
+
+    fn close_conversation(
+        &mut self,
+        id: &str,
+        client: &Client,
+        status: &str,
+    ) -> Result<(), EngineError> {
+        self.0.close_conversation(id, client, status)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.0.close_all_conversations(client)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<DbConversation>, EngineError> {
+        self.0.get_latest_open(client)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: &str,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        self.0
+            .update_conversation(conversation_id, client, flow_id, step_id)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        self.0
+            .get_client_conversations(client, limit, pagination_key)
+    }
+
+    fn get_conversation_summaries(
+        &mut self,
+        client: &Client,
+        limit: Option<i64>,
+        pagination_key: Option<String>,
+    ) -> Result<serde_json::Value, EngineError> {
+        self.0
+            .get_conversation_summaries(client, limit, pagination_key)
+    }
+
+    fn save_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        items: Vec<(String, String)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.0.save_state_items(client, _type, items, ttl)
+    }
+
+    fn save_state_item_if_version(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+        value: String,
+        version: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.0
+            .save_state_item_if_version(client, _type, key, value, version, ttl)
+    }
+
+    fn query_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        let state = self.0.query_state_key(client, _type, key)?;
+
+        if (_type, key) == ("hold", "position") {
+            let concurrent_request = CONCURRENT_REQUEST.lock().unwrap().take();
+            if let Some(request) = concurrent_request {
+                let bot = init_bot();
+                start_conversation(request, BotOpt::CsmlBot(bot))?;
+            }
+        }
+
+        Ok(state)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        self.0.delete_state_key(client, _type, key)
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    let content =
+        "start:\n    say \"hello\"\n    hold\n    say \"resumed by {{event}}\"\n    goto end";
+
+    support::init_bot("state_conflict_test", content)
+}
+
+fn init_client() -> Client {
+    register_connector(DB_TYPE, || {
+        Ok(Box::new(RacingConnector(InMemoryConnector::new())))
+    });
+
+    support::init_client(DB_TYPE)
+}
+
+fn sent_texts(client: &Client) -> Vec<serde_json::Value> {
+    InMemoryConnector::messages(client)
+        .unwrap()
+        .iter()
+        .filter(|message| message["direction"] == "SEND")
+        .map(|message| message["payload"]["content"]["text"].clone())
+        .collect()
+}
+
+#[test]
+fn concurrent_turns_resume_the_hold_once() {
+    let client = init_client();
+
+    start_conversation(init_request("start", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    // the second request resumes the hold while the first one has already read it
+    *CONCURRENT_REQUEST.lock().unwrap() = Some(init_request("second", &client));
+    let result = start_conversation(init_request("first", &client), BotOpt::CsmlBot(init_bot()));
+
+    match result {
+        Err(EngineError::StateConflict(_)) => {}
+        Err(err) => panic!("expected a state conflict, got {:?}", err),
+        Ok(messages) => panic!("expected a state conflict, got {:?}", messages),
+    }
+    assert_eq!(sent_texts(&client), vec!["hello", "resumed by second"]);
+
+    // the hold was consumed by the second request, the next request starts over
+    start_conversation(init_request("third", &client), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(
+        sent_texts(&client),
+        vec!["hello", "resumed by second", "hello"]
+    );
+}
+
+#[test]
+fn stale_state_version_is_rejected() {
+    let client = init_client();
+    let mut connector = InMemoryConnector::new();
+    let hold = json!({"hash": "hash", "index": {"command_index": 1, "loop_index": []}});
+
+    let version = connector
+        .set_state_item_if_version(&client, "hold", "position", Some(&hold), 0, None)
+        .unwrap();
+    assert_eq!(version, 1);
+
+    // both turns read the hold at the same version
+    let (first, first_version) = connector
+        .get_versioned_state_key(&client, "hold", "position")
+        .unwrap();
+    let (second, second_version) = connector
+        .get_versioned_state_key(&client, "hold", "position")
+        .unwrap();
+    assert_eq!(first, Some(hold.clone()));
+    assert_eq!(second, Some(hold));
+    assert_eq!((first_version, second_version), (1, 1));
+
+    let version = connector
+        .set_state_item_if_version(&client, "hold", "position", None, first_version, None)
+        .unwrap();
+    assert_eq!(version, 2);
+
+    match connector.set_state_item_if_version(
+        &client,
+        "hold",
+        "position",
+        None,
+        second_version,
+        None,
+    ) {
+        Err(EngineError::StateConflict(_)) => {}
+        other => panic!("expected a state conflict, got {:?}", other),
+    }
+
+    // the cleared hold keeps its version
+    assert_eq!(
+        connector
+            .get_versioned_state_key(&client, "hold", "position")
+            .unwrap(),
+        (None, 2)
+    );
+    assert_eq!(connector.get_current_state(&client).unwrap(), None);
+}
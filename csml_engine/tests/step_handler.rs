@@ -0,0 +1,114 @@
+//! The steps are wrapped with the handler set with `set_step_handler`.
+//! They run against an in-memory sqlite database: `cargo test --features sqlite --test step_handler`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::{init_bot, init_request};
+use csml_engine::{
+    data::{BotOpt, EngineError},
+    delete_client, set_step_handler, start_conversation, StepHandler,
+};
+use csml_interpreter::data::Client;
+use std::sync::Mutex;
+
+// (bot_id, event) of the calls of the handler, the tests of this file run in parallel
+static CALLS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Handler recording its calls, entering the step "broken" fails
+struct RecordHandler;
+
+impl StepHandler for RecordHandler {
+    fn on_step_enter(&self, client: &Client, flow: &str, step: &str) -> Result<(), EngineError> {
+        if step == "broken" {
+            return Err(EngineError::Manager("can not enter broken".to_owned()));
+        }
+
+        record(client, format!("enter {}.{}", flow, step));
+        Ok(())
+    }
+
+    fn on_step_exit(&self, client: &Client, flow: &str, step: &str) -> Result<(), EngineError> {
+        record(client, format!("exit {}.{}", flow, step));
+        Ok(())
+    }
+}
+
+fn record(client: &Client, event: String) {
+    let mut calls = CALLS.lock().unwrap_or_else(|err| err.into_inner());
+
+    calls.push((client.bot_id.to_owned(), event));
+}
+
+fn get_calls(client: &Client) -> Vec<String> {
+    let calls = CALLS.lock().unwrap_or_else(|err| err.into_inner());
+
+    calls
+        .iter()
+        .filter(|(bot_id, _)| *bot_id == client.bot_id)
+        .map(|(_, event)| event.to_owned())
+        .collect()
+}
+
+fn init_client() -> Client {
+    set_step_handler(Box::new(RecordHandler));
+
+    support::init_client("sqlite")
+}
+
+#[test]
+fn step_handler_goto_and_hold() {
+    let client = init_client();
+    let content = "start:\n    say \"hello\"\n    goto wait\n\nwait:\n    hold\n    say \"{{event}}\"\n    goto end";
+
+    start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("step_handler_test", content)),
+    )
+    .unwrap();
+    assert_eq!(
+        get_calls(&client),
+        vec![
+            "enter Default.start",
+            "exit Default.start",
+            "enter Default.wait",
+            "exit Default.wait",
+        ]
+    );
+
+    // the held step is entered again when the conversation resumes
+    let result = start_conversation(
+        init_request("hi", &client),
+        BotOpt::CsmlBot(init_bot("step_handler_test", content)),
+    )
+    .unwrap();
+    assert_eq!(result["messages"][0]["payload"]["content"]["text"], "hi");
+    assert_eq!(
+        get_calls(&client)[4..],
+        ["enter Default.wait", "exit Default.wait"]
+    );
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn step_handler_error() {
+    let client = init_client();
+    let content = "start:\n    goto broken\n\nbroken:\n    say \"never\"\n    goto end";
+
+    let result = start_conversation(
+        init_request("start", &client),
+        BotOpt::CsmlBot(init_bot("step_handler_test", content)),
+    );
+
+    match result {
+        Err(EngineError::Manager(message)) => assert_eq!(message, "can not enter broken"),
+        _ => panic!("entering the broken step must fail"),
+    }
+    assert_eq!(
+        get_calls(&client),
+        vec!["enter Default.start", "exit Default.start"]
+    );
+
+    delete_client(&client).unwrap();
+}
@@ -0,0 +1,84 @@
+//! The messages of a turn are streamed as they are produced with `start_conversation_stream`.
+//! They run against an in-memory sqlite database: `cargo test --features sqlite --test stream`
+#![cfg(feature = "sqlite")]
+
+mod support;
+
+use crate::support::{init_client, init_request};
+use csml_engine::{data::BotOpt, delete_client, start_conversation, start_conversation_stream};
+use csml_interpreter::data::{csml_bot::CsmlBot, Client};
+use serde_json::{json, Value};
+use std::sync::mpsc;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n    say \"hello\"\n    say {\"nested\": [1, 2]}\n    hold\n    say \"after hold\"\n    goto end";
+
+    support::init_bot("stream_test", content)
+}
+
+fn payloads(messages: &[Value]) -> Vec<Value> {
+    messages
+        .iter()
+        .map(|message| message["payload"].to_owned())
+        .collect()
+}
+
+fn stream_turn(string: &str, client: &Client) -> (Vec<Value>, Vec<Value>) {
+    let (sender, receiver) = mpsc::channel();
+
+    let result = start_conversation_stream(
+        init_request(string, client),
+        BotOpt::CsmlBot(init_bot()),
+        sender,
+    )
+    .unwrap();
+    let streamed: Vec<Value> = receiver.try_iter().collect();
+
+    (streamed, result["messages"].as_array().unwrap().to_owned())
+}
+
+#[test]
+fn stream_messages_order() {
+    let client = init_client("sqlite");
+
+    let (streamed, messages) = stream_turn("start", &client);
+    assert_eq!(payloads(&streamed), payloads(&messages));
+    assert_eq!(
+        payloads(&streamed),
+        vec![
+            json!({"content_type": "text", "content": {"text": "hello"}}),
+            json!({"content_type": "object", "content": {"nested": [1, 2]}}),
+        ]
+    );
+
+    // the flow resumes after the hold on the next turn
+    let (streamed, messages) = stream_turn("next", &client);
+    assert_eq!(payloads(&streamed), payloads(&messages));
+    assert_eq!(
+        payloads(&streamed),
+        vec![json!({"content_type": "text", "content": {"text": "after hold"}})]
+    );
+
+    delete_client(&client).unwrap();
+}
+
+#[test]
+fn stream_same_as_batch() {
+    let stream_client = init_client("sqlite");
+    let batch_client = init_client("sqlite");
+
+    let (streamed, _) = stream_turn("start", &stream_client);
+    let result = start_conversation(
+        init_request("start", &batch_client),
+        BotOpt::CsmlBot(init_bot()),
+    )
+    .unwrap();
+
+    assert_eq!(
+        payloads(&streamed),
+        payloads(result["messages"].as_array().unwrap())
+    );
+
+    delete_client(&stream_client).unwrap();
+    delete_client(&batch_client).unwrap();
+}
@@ -0,0 +1,81 @@
+//! Bots, clients and requests shared by the integration tests.
+#![allow(dead_code)]
+
+use csml_engine::data::CsmlRequest;
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+use uuid::Uuid;
+
+/**
+ * Bot with a single `Default` flow
+ */
+pub fn init_bot(name: &str, content: &str) -> CsmlBot {
+    CsmlBot {
+        id: name.to_owned(),
+        name: name.to_owned(),
+        apps_endpoint: None,
+        flows: vec![init_flow("Default", content)],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+    }
+}
+
+pub fn init_flow(name: &str, content: &str) -> CsmlFlow {
+    CsmlFlow {
+        id: name.to_owned(),
+        name: name.to_owned(),
+        commands: vec![],
+        content: content.to_owned(),
+    }
+}
+
+/**
+ * New client of the database selected with ENGINE_DB_TYPE, each test gets its own bot and channel
+ */
+pub fn init_client(db_type: &str) -> Client {
+    std::env::set_var("ENGINE_DB_TYPE", db_type);
+
+    Client {
+        user_id: "test".to_owned(),
+        bot_id: Uuid::new_v4().to_string(),
+        channel_id: Uuid::new_v4().to_string(),
+    }
+}
+
+/**
+ * New client of the `InMemoryConnector` of the `test-utils` feature
+ */
+#[cfg(feature = "test-utils")]
+pub fn init_in_memory_client() -> Client {
+    use csml_engine::InMemoryConnector;
+
+    InMemoryConnector::register();
+
+    init_client(InMemoryConnector::DB_TYPE)
+}
+
+/**
+ * Text event sent by the client
+ */
+pub fn init_request(string: &str, client: &Client) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "tmp".to_owned(),
+        client: client.to_owned(),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": string},
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        conversation_id: None,
+    }
+}
@@ -111,6 +111,7 @@ fn init_request(string: &str, bot_id: String, channel_id: String) -> CsmlRequest
         ttl_duration: None,
         step_limit: None,
         low_data_mode: None,
+        conversation_id: None,
     }
 }
 
@@ -52,6 +52,11 @@ fn main() {
         ttl_duration: None,
         low_data_mode: None,
         step_limit: None,
+        goto_loop_limit: None,
+        instruction_limit: None,
+        time_limit: None,
+        seed: None,
+        hold_confidence_default: None,
         secure: false,
     };
 
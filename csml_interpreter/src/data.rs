@@ -1,5 +1,7 @@
 pub mod ast;
+pub mod budget;
 pub mod client;
+pub mod compiled_flow;
 pub mod context;
 pub mod csml_bot;
 pub mod csml_flow;
@@ -15,13 +17,19 @@ pub mod memories;
 pub mod message;
 pub mod message_data;
 pub mod msg;
+pub mod native_function;
+pub mod observer;
 pub mod position;
 pub mod primitive;
+pub mod random;
+pub mod run_options;
 pub mod tokens;
 pub mod warnings;
 
 pub use ast::Interval;
+pub use budget::ExecutionBudget;
 pub use client::Client;
+pub use compiled_flow::CompiledFlow;
 pub use context::{ApiInfo, Context, PreviousBot};
 pub use csml_bot::{CsmlBot, Module, MultiBot};
 pub use csml_flow::CsmlFlow;
@@ -31,12 +39,22 @@ pub use event::Event;
 pub use fn_args_type::ArgsType;
 pub use hold::{Hold, IndexInfo};
 pub use literal::Literal;
-pub use memories::{Memory, MemoryType};
+pub use memories::{Memory, MemoryChange, MemoryDiff, MemoryType};
 pub use message::Message;
 pub use message_data::MessageData;
+pub use native_function::NativeFunction;
+pub use observer::MessageObserver;
 pub use position::Position;
+pub use random::RandomSource;
+pub use run_options::RunOptions;
 
 pub use msg::MSG;
 
 // limit of steps in a single execution
 pub static STEP_LIMIT: usize = 100;
+
+// limit of entries of a single step in a single execution, above it the gotos are looping
+pub static GOTO_LOOP_LIMIT: usize = 100;
+
+// limit of iterations of a single while loop in a single execution
+pub static WHILE_LIMIT: usize = 10_000;
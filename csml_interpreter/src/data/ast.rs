@@ -9,11 +9,19 @@ use std::hash::{Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
+// the ast is cached by the hosts in compiled flows, bump AST_VERSION of compiled_flow.rs
+// when the types of this file change
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Flow {
     pub flow_instructions: HashMap<InstructionScope, Expr>,
     pub flow_type: FlowType,
     pub constants: HashMap<String, Literal>,
+    // content of the @metadata block, for tooling only
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    // comments of the flow, only kept by `parse_flow_with_comments` for the formatters
+    #[serde(default)]
+    pub comments: Vec<AttachedComment>,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -21,11 +29,47 @@ pub enum FlowType {
     Normal,
 }
 
+// comment of the source, its text holds the delimiters ('//', '/*' and '*/')
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub text: String,
+    pub interval: Interval,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum CommentPlacement {
+    // the comment is before the node
+    Leading,
+    // the comment is after the start of the node, on the same line or at the end of the flow
+    Trailing,
+}
+
+// comment attached to the node of the AST the nearest to it: a step, a function, an import,
+// a constant or a command. The node is given by its interval (see interval_from_expr)
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct AttachedComment {
+    pub comment: Comment,
+    pub placement: CommentPlacement,
+    pub node: Interval,
+}
+
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub enum FromFlow {
     Normal(String),
     Extern(String),
     None,
+    // chosen between two flows with the env of the bot when the bot is loaded
+    Conditional(ImportCondition),
+}
+
+// import fn from then_flow if _env.key == "value" else else_flow,
+// without a value the env key must hold a boolean
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCondition {
+    pub key: String,
+    pub value: Option<String>,
+    pub then_flow: String,
+    pub else_flow: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -75,7 +119,11 @@ impl Eq for ImportScope {}
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum InstructionScope {
     StepScope(String),
-    FunctionScope { name: String, args: Vec<String> },
+    FunctionScope {
+        name: String,
+        args: Vec<String>,
+        defaults: HashMap<String, Expr>,
+    },
     ImportScope(ImportScope),
     InsertStep(InsertStep),
     Constant(String),
@@ -244,6 +292,20 @@ pub enum ObjectType {
     Previous(PreviousType, Interval),
     Hold(Interval),
     HoldSecure(Interval),
+    // hold waiting for an event matching a JSON schema, set to true for hold_secure
+    HoldSchema(Box<Expr>, bool, Interval),
+    // hold waiting for an event that can be coerced to the expected type
+    HoldExpect(ExpectedType, bool, Interval),
+    // hold waiting for an event with a confidence reaching the threshold, the less
+    // confident events go to the optional else goto instead of staying on hold
+    HoldConfidence(Box<Expr>, Option<Block>, bool, Interval),
+    // hold until the duration is elapsed, resumed by the host scheduler
+    Delay(Box<Expr>, Interval),
+    // hold again on the hold resumed in the step, at most the optional number of times,
+    // the step goes on once they are used up
+    Retry(Option<usize>, Interval),
+    // condition and optional message, only checked when CSML_ASSERTIONS is enabled
+    Assert(Box<Expr>, Option<Box<Expr>>, Interval),
     Say(Box<Expr>),
     Debug(Box<Expr>, Interval),
     Log {
@@ -256,14 +318,27 @@ pub enum ObjectType {
     Use(Box<Expr>),
 
     Remember(Identifier, Box<Expr>),
+    // memory kept until the end of the conversation
+    RememberConversation(Identifier, Box<Expr>),
+    // memory masked in the debugging outputs, its value is still saved
+    RememberSecret(Identifier, Box<Expr>),
     Assign(AssignType, Box<Expr>, Box<Expr>),
     Forget(ForgetMemory, Interval),
 
     As(Identifier, Box<Expr>),
 
     BuiltIn(Function),
-    Break(Interval),
-    Continue(Interval),
+    Break(Option<Identifier>, Interval), // optional loop label
+    Continue(Option<Identifier>, Interval),
+}
+
+// type of the event expected by 'hold expect'
+#[derive(PartialEq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ExpectedType {
+    Number,
+    String,
+    Boolean,
+    Email,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -304,14 +379,10 @@ pub enum BlockType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum IfStatement {
-    IfStmt {
-        cond: Box<Expr>,
-        consequence: Block,
-        then_branch: Option<Box<IfStatement>>,
-        last_action_index: usize,
-    },
-    ElseStmt(Block, Interval),
+pub struct IfBranch {
+    pub cond: Box<Expr>,
+    pub consequence: Block,
+    pub last_action_index: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -321,8 +392,20 @@ pub enum Expr {
         scope: Block,
         range: Interval,
     },
-    ForEachExpr(Identifier, Option<Identifier>, Box<Expr>, Block, Interval),
+    ForEachExpr(
+        Identifier,
+        Option<Identifier>,
+        Box<Expr>,
+        Block,
+        Option<Identifier>, // optional loop label
+        Interval,
+    ),
+    RangeExpr(Box<Expr>, Box<Expr>, bool, Interval), // bool is true for inclusive ranges
     WhileExpr(Box<Expr>, Block, Interval),
+    MatchExpr(Box<Expr>, Vec<(Expr, Block)>, Interval),
+    // /pattern/flags in a match arm, the flags are stored as an inline group like (?i)
+    RegexExpr(String, Interval),
+    TryCatchExpr(Block, Identifier, Block, Interval),
     ComplexLiteral(Vec<Expr>, Interval),
     MapExpr {
         object: HashMap<String, Expr>,
@@ -330,10 +413,22 @@ pub enum Expr {
         interval: Interval,
     },
     VecExpr(Vec<Expr>, Interval),
+    // ...expr inside an array or object literal
+    SpreadExpr(Box<Expr>, Interval),
+    // object literal with spread elements, its MapExpr and SpreadExpr parts are merged
+    // in order so that later keys override earlier ones
+    SpreadMapExpr(Vec<Expr>, Interval),
     InfixExpr(Infix, Box<Expr>, Box<Expr>),
     PostfixExpr(Vec<Pretfix>, Box<Expr>),
     ObjectExpr(ObjectType),
-    IfExpr(IfStatement),
+    // cond ? then : else, only the branch matching the condition is evaluated
+    TernaryExpr(Box<Expr>, Box<Expr>, Box<Expr>, Interval),
+    // if, else if and else of a conditional, the first branch with a true condition is executed
+    IfExpr {
+        branches: Vec<IfBranch>,
+        else_body: Option<Block>,
+        range: Interval,
+    },
 
     PathExpr {
         literal: Box<Expr>,
@@ -345,6 +440,30 @@ pub enum Expr {
         literal: Literal,
         in_in_substring: bool, // this value is use to determine if this literal was declare inside a string or not
     },
+
+    Destructure(Pattern, Box<Expr>, Interval),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Pattern {
+    Ident(Identifier),
+    // each key is bound to a sub pattern, {name} is short for {name: name}
+    Object(Vec<(Identifier, Pattern)>, Interval),
+    Array(Vec<Pattern>, Interval),
+}
+
+impl Pattern {
+    // variables bound by the pattern
+    pub fn idents(&self) -> Vec<&Identifier> {
+        match self {
+            Pattern::Ident(ident) => vec![ident],
+            Pattern::Object(fields, _) => fields
+                .iter()
+                .flat_map(|(_key, pattern)| pattern.idents())
+                .collect(),
+            Pattern::Array(items, _) => items.iter().flat_map(|item| item.idents()).collect(),
+        }
+    }
 }
 
 impl Expr {
@@ -359,7 +478,16 @@ pub enum Infix {
     Subtraction,
     Divide,
     Multiply,
-    Remainder,
+    // the operator interval locates division by zero errors
+    Remainder(Interval),
+    IntegerDivision(Interval),
+
+    // only defined for int operands, the interval locates the type and shift count errors
+    BitwiseAnd(Interval),
+    BitwiseOr(Interval),
+    BitwiseXor(Interval),
+    ShiftLeft(Interval),
+    ShiftRight(Interval),
 
     Match,
     NotMatch,
@@ -373,6 +501,8 @@ pub enum Infix {
 
     And,
     Or,
+
+    NullCoalescing,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
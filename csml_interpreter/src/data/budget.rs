@@ -0,0 +1,77 @@
+use crate::error_format::{ERROR_INSTRUCTION_LIMIT, ERROR_TIME_LIMIT};
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Limits the work done by the interpreter in a single run, the budget is shared
+/// by every scope of the run (steps, loops, functions and closures).
+#[derive(Debug)]
+pub struct ExecutionBudget {
+    instruction_limit: Option<usize>,
+    time_limit: Option<u64>,
+    deadline: Option<Instant>,
+    instructions: Cell<usize>,
+    exceeded: Cell<bool>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// STATIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl ExecutionBudget {
+    /// time_limit is in milliseconds and starts running when the budget is created
+    pub fn new(instruction_limit: Option<usize>, time_limit: Option<u64>) -> Self {
+        Self {
+            instruction_limit,
+            time_limit,
+            deadline: time_limit.map(|limit| Instant::now() + Duration::from_millis(limit)),
+            instructions: Cell::new(0),
+            exceeded: Cell::new(false),
+        }
+    }
+}
+
+impl Default for ExecutionBudget {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl ExecutionBudget {
+    /// count a newly executed instruction, returns the error message once the budget is exceeded
+    pub fn consume(&self) -> Result<(), String> {
+        let instructions = self.instructions.get() + 1;
+        self.instructions.set(instructions);
+
+        if let Some(limit) = self.instruction_limit {
+            if instructions > limit {
+                self.exceeded.set(true);
+                return Err(format!(
+                    "{} {} instructions",
+                    ERROR_INSTRUCTION_LIMIT, limit
+                ));
+            }
+        }
+
+        if let (Some(deadline), Some(limit)) = (self.deadline, self.time_limit) {
+            if Instant::now() > deadline {
+                self.exceeded.set(true);
+                return Err(format!("{} {}ms", ERROR_TIME_LIMIT, limit));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded.get()
+    }
+}
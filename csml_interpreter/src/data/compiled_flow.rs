@@ -0,0 +1,87 @@
+use crate::data::ast::{Flow, Interval};
+use crate::data::error_info::ErrorInfo;
+use crate::data::position::Position;
+use crate::error_format::{gen_error_info, ERROR_COMPILED_FLOW, ERROR_COMPILED_FLOW_VERSION};
+
+use serde::{Deserialize, Serialize};
+
+// version of the format of the ast, a compiled flow only runs with the format that compiled it.
+// Bump AST_VERSION with every change of the types of ast.rs
+pub const AST_VERSION: u32 = 3;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Parsed flow that can be cached by the host and run without parsing its source again.
+/// The ast has maps with non string keys: use a binary serde format like bincode,
+/// `to_bytes` and `from_bytes` do it and check the version.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompiledFlow {
+    // first field, so it can be read before the ast
+    pub version: u32,
+    pub flow: Flow,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// STATIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl CompiledFlow {
+    pub fn new(flow: &Flow) -> Self {
+        Self {
+            version: AST_VERSION,
+            flow: flow.to_owned(),
+        }
+    }
+
+    /// The version is checked before the ast is read, the ast of another version
+    /// may not be readable
+    pub fn from_bytes(bytes: &[u8], flow_name: &str) -> Result<Self, ErrorInfo> {
+        let version: u32 = bincode::deserialize(bytes).map_err(|err| {
+            gen_error_info(
+                Position::new(Interval::default(), flow_name),
+                format!("{}: {}", ERROR_COMPILED_FLOW, err),
+            )
+        })?;
+        check_version(version, flow_name)?;
+
+        bincode::deserialize(bytes).map_err(|err| {
+            gen_error_info(
+                Position::new(Interval::default(), flow_name),
+                format!("{}: {}", ERROR_COMPILED_FLOW, err),
+            )
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl CompiledFlow {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).unwrap()
+    }
+
+    pub fn validate(&self, flow_name: &str) -> Result<(), ErrorInfo> {
+        check_version(self.version, flow_name)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn check_version(version: u32, flow_name: &str) -> Result<(), ErrorInfo> {
+    match version == AST_VERSION {
+        true => Ok(()),
+        false => Err(gen_error_info(
+            Position::new(Interval::default(), flow_name),
+            format!(
+                "{}: compiled with {}, expected {}",
+                ERROR_COMPILED_FLOW_VERSION, version, AST_VERSION
+            ),
+        )),
+    }
+}
@@ -1,10 +1,12 @@
 use crate::data::{
     primitive::{PrimitiveObject, PrimitiveType},
-    Client, Hold, Interval, Literal,
+    Client, Hold, Interval, Literal, Memory,
 };
+use crate::error_format::{ERROR_CONTEXT_FORMAT, ERROR_CONTEXT_VERSION};
 
 use crate::interpreter::{json_to_literal, memory_to_literal};
 
+use chrono::{DateTime, Utc};
 use nom::lib::std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
@@ -12,13 +14,13 @@ use serde::{Deserialize, Serialize};
 // DATA STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ApiInfo {
     pub client: Client,
     pub apps_endpoint: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ContextStepInfo {
     Normal(String),
     UnknownFlow(String),
@@ -51,22 +53,60 @@ impl ContextStepInfo {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct PreviousBot {
     pub bot: String,
     pub flow: String,
     pub step: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Context {
     pub current: HashMap<String, Literal>,
+    // memories of the conversation scope, read first by `coalesce_memory`
+    pub conversation: HashMap<String, Literal>,
     pub metadata: HashMap<String, Literal>,
     pub api_info: Option<ApiInfo>,
     pub hold: Option<Hold>,
     pub step: ContextStepInfo,
     pub flow: String,
     pub previous_bot: Option<PreviousBot>,
+    // date of the turn given by the clock of the host, the system time is read when it is None
+    pub now: Option<DateTime<Utc>>,
+}
+
+// serialized form of a Context, the literals are kept in the memory format.
+// Bump CONTEXT_VERSION when it changes and migrate the older versions in Context::from_bytes
+#[derive(Debug, Deserialize, Serialize)]
+struct SerializedContext {
+    version: u32,
+    current: serde_json::Value,
+    // missing from the version 1
+    #[serde(default)]
+    conversation: serde_json::Value,
+    metadata: serde_json::Value,
+    hold: Option<Hold>,
+    step: ContextStepInfo,
+    flow: String,
+    previous_bot: Option<PreviousBot>,
+}
+
+pub const CONTEXT_VERSION: u32 = 2;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn literals_to_mem(map: &HashMap<String, Literal>) -> serde_json::Value {
+    let memories: serde_json::Map<String, serde_json::Value> = map
+        .iter()
+        .map(|(key, literal)| {
+            let memory = Memory::new(key.to_owned(), literal.to_owned());
+            (memory.key, memory.value)
+        })
+        .collect();
+
+    serde_json::Value::Object(memories)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -129,14 +169,76 @@ impl Context {
     ) -> Self {
         Self {
             current,
+            conversation: HashMap::new(),
             metadata,
             api_info,
             hold,
             step: ContextStepInfo::Normal(step.to_owned()),
             flow: flow.to_owned(),
             previous_bot,
+            now: None,
         }
     }
+
+    /// Restore a context serialized with `to_bytes`.
+    /// The api info and the date of the turn are not serialized and must be set again by the caller.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let value: serde_json::Value = match serde_json::from_slice(bytes) {
+            Ok(value) => value,
+            Err(err) => return Err(format!("{}: {}", ERROR_CONTEXT_FORMAT, err)),
+        };
+
+        let context: SerializedContext = match value["version"].as_u64() {
+            // the version 1 has no memories of the conversation
+            Some(version) if version == 1 || version == CONTEXT_VERSION as u64 => {
+                match serde_json::from_value(value) {
+                    Ok(context) => context,
+                    Err(err) => return Err(format!("{}: {}", ERROR_CONTEXT_FORMAT, err)),
+                }
+            }
+            _ => return Err(format!("{}: {}", ERROR_CONTEXT_VERSION, value["version"])),
+        };
+
+        Ok(Self {
+            current: get_hashmap_from_mem(&context.current, &context.flow),
+            conversation: get_hashmap_from_mem(&context.conversation, &context.flow),
+            metadata: get_hashmap_from_mem(&context.metadata, &context.flow),
+            api_info: None,
+            hold: context.hold,
+            step: context.step,
+            flow: context.flow,
+            previous_bot: context.previous_bot,
+            now: None,
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl Context {
+    /// Date of the turn, used for the dates computed by the flow (`Time()`, `delay`)
+    pub fn get_now(&self) -> DateTime<Utc> {
+        self.now.unwrap_or_else(Utc::now)
+    }
+
+    /// Versioned serialization of the memories, metadata, position and hold of the context.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let context = SerializedContext {
+            version: CONTEXT_VERSION,
+            current: literals_to_mem(&self.current),
+            conversation: literals_to_mem(&self.conversation),
+            metadata: literals_to_mem(&self.metadata),
+            hold: self.hold.clone(),
+            step: self.step.clone(),
+            flow: self.flow.clone(),
+            previous_bot: self.previous_bot.clone(),
+        };
+
+        // the serialized context only holds json values and strings
+        serde_json::to_vec(&context).unwrap()
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
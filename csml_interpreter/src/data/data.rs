@@ -1,5 +1,5 @@
 use crate::data::context::Context;
-use crate::data::Event;
+use crate::data::{Event, ExecutionBudget, Hold, MessageObserver, RandomSource};
 use crate::data::{ast::*, Literal};
 
 use crate::data::context::ContextStepInfo;
@@ -10,7 +10,7 @@ use std::collections::HashMap;
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
 ////////////////////////////////////////////////////////////////////////////////
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PreviousInfo {
     pub flow: String,
     pub step_at_flow: (ContextStepInfo, String), // step / flow
@@ -25,13 +25,22 @@ pub struct Data<'a> {
     pub default_flow: String,
     pub context: &'a mut Context,
     pub event: &'a Event,
+    // event coerced to the type expected by the hold that resumed the conversation
+    pub event_value: Option<Literal>,
     pub env: &'a Literal,
 
     pub loop_indexes: Vec<usize>,
     pub loop_index: usize,
+    // runtime errors stop the current block when interpreting a try block
+    pub in_try_block: bool,
+    // hold that resumed the step, where retry holds the conversation again
+    pub resumed_hold: Option<Hold>,
 
     pub step_count: &'a mut usize,
     pub step_limit: usize,
+    pub budget: &'a ExecutionBudget,
+    pub random: &'a RandomSource,
+    pub message_observer: Option<&'a dyn MessageObserver>,
 
     pub step_vars: HashMap<String, Literal>,
     pub previous_info: Option<PreviousInfo>,
@@ -68,11 +77,15 @@ impl<'a> Data<'a> {
         default_flow: String,
         context: &'a mut Context,
         event: &'a Event,
+        event_value: Option<Literal>,
         env: &'a Literal,
         loop_indexes: Vec<usize>,
         loop_index: usize,
         step_count: &'a mut usize,
         step_limit: usize,
+        budget: &'a ExecutionBudget,
+        random: &'a RandomSource,
+        message_observer: Option<&'a dyn MessageObserver>,
         step_vars: HashMap<String, Literal>,
         previous_info: Option<PreviousInfo>,
         custom_component: &'a serde_json::Map<String, serde_json::Value>,
@@ -88,11 +101,17 @@ impl<'a> Data<'a> {
             default_flow,
             context,
             event,
+            event_value,
             env,
             loop_indexes,
             loop_index,
+            in_try_block: false,
+            resumed_hold: None,
             step_count,
             step_limit,
+            budget,
+            random,
+            message_observer,
             step_vars,
             previous_info,
             custom_component,
@@ -128,22 +147,29 @@ impl<'a> Data<'a> {
 
     // get permanent and temporary memories in a single hashmap
     pub fn get_all_memories(&self) -> HashMap<String, Literal> {
+        let conversation_memory = self.context.conversation.clone();
         let remember_memory = self.context.current.clone();
         let step_memory = self.step_vars.clone();
 
-        remember_memory.into_iter().chain(step_memory).collect()
+        conversation_memory
+            .into_iter()
+            .chain(remember_memory)
+            .chain(step_memory)
+            .collect()
     }
 }
 
 pub fn init_child_context(data: &Data) -> Context {
     Context {
         current: HashMap::new(),
+        conversation: HashMap::new(),
         metadata: data.context.metadata.clone(),
         api_info: data.context.api_info.clone(),
         hold: None,
         step: data.context.step.clone(),
         flow: data.context.flow.clone(),
         previous_bot: data.context.previous_bot.clone(),
+        now: data.context.now,
     }
 }
 
@@ -159,11 +185,15 @@ pub fn init_child_scope<'a>(
         data.default_flow.clone(),
         context,
         &data.event,
+        data.event_value.clone(),
         &data.env,
         data.loop_indexes.clone(),
         data.loop_index,
         step_count,
         data.step_limit,
+        data.budget,
+        data.random,
+        data.message_observer,
         HashMap::new(),
         data.previous_info.clone(),
         &data.custom_component,
@@ -10,10 +10,30 @@ use std::collections::HashMap;
 // DATA STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    UnexpectedToken,
+    IncompleteInput,
+    DuplicateInstruction,
+    ExecutionBudgetExceeded,
+    GotoLoop,
+    AssertionFailed,
+    Other,
+}
+
+impl Default for ErrorCode {
+    fn default() -> Self {
+        ErrorCode::Other
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorInfo {
     pub position: Position,
     pub message: String,
+    #[serde(default)]
+    pub code: ErrorCode,
     pub additional_info: Option<HashMap<String, Literal>>,
 }
 
@@ -28,10 +48,16 @@ impl ErrorInfo {
         Self {
             position,
             message,
+            code: ErrorCode::Other,
             additional_info: Some(error_info),
         }
     }
 
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
     pub fn add_info(&mut self, key: &str, value: Literal) {
         match self.additional_info {
             Some(ref mut map) => {
@@ -81,6 +107,7 @@ impl From<std::io::Error> for ErrorInfo {
         Self {
             position: Position::default(),
             message: e.to_string(),
+            code: ErrorCode::Other,
             additional_info: None,
         }
     }
@@ -91,6 +118,7 @@ impl From<serde_json::Error> for ErrorInfo {
         Self {
             position: Position::default(),
             message: e.to_string(),
+            code: ErrorCode::Other,
             additional_info: None,
         }
     }
@@ -101,6 +129,7 @@ impl From<uuid::Error> for ErrorInfo {
         Self {
             position: Position::default(),
             message: e.to_string(),
+            code: ErrorCode::Other,
             additional_info: None,
         }
     }
@@ -111,6 +140,7 @@ impl From<std::time::SystemTimeError> for ErrorInfo {
         Self {
             position: Position::default(),
             message: e.to_string(),
+            code: ErrorCode::Other,
             additional_info: None,
         }
     }
@@ -10,6 +10,15 @@ pub struct Event {
     pub ttl_duration: Option<i64>,
     pub low_data_mode: Option<bool>,
     pub step_limit: Option<usize>,
+    // max number of times a single step is entered in a run
+    pub goto_loop_limit: Option<usize>,
+    // execution budget of a run: max number of instructions and max duration in milliseconds
+    pub instruction_limit: Option<usize>,
+    pub time_limit: Option<u64>,
+    // seed of the random builtins, their values are the same in each run when it is set
+    pub seed: Option<u64>,
+    // confidence of the events without one in their content, read by 'hold confidence'
+    pub hold_confidence_default: Option<f64>,
     pub secure: bool,
 }
 
@@ -26,6 +35,11 @@ impl Default for Event {
             ttl_duration: None,
             low_data_mode: None,
             step_limit: None,
+            goto_loop_limit: None,
+            instruction_limit: None,
+            time_limit: None,
+            seed: None,
+            hold_confidence_default: None,
             secure: false,
         }
     }
@@ -44,6 +58,11 @@ impl Event {
             ttl_duration: None,
             low_data_mode: None,
             step_limit: None,
+            goto_loop_limit: None,
+            instruction_limit: None,
+            time_limit: None,
+            seed: None,
+            hold_confidence_default: None,
             secure: false,
         }
     }
@@ -75,11 +75,10 @@ impl ArgsType {
     pub fn get<'a>(&'a self, key: &str, index: usize) -> Option<&'a Literal> {
         match self {
             Self::Named(var) => {
-                match (var.get(key), index) {
-                    (Some(val), _) => Some(val),
-                    // tmp ?
-                    (None, 0) => var.get(&format!("arg{}", index)),
-                    (None, _) => None,
+                // positional arguments come before the named ones
+                match var.get(key) {
+                    Some(val) => Some(val),
+                    None => var.get(&format!("arg{}", index)),
                 }
             }
             Self::Normal(var) => var.get(&format!("arg{}", index)),
@@ -1,4 +1,9 @@
-use crate::data::{Data, Literal};
+use crate::data::{
+    ast::{Expr, Flow, InstructionScope},
+    position::Position,
+    Data, Interval, Literal,
+};
+use crate::error_format::{gen_error_info, ErrorInfo, ERROR_HOLD_INDEX, ERROR_STEP_EXIST};
 use serde::{Deserialize, Serialize};
 
 use super::data::PreviousInfo;
@@ -7,13 +12,17 @@ use super::data::PreviousInfo;
 // DATA STRUCTURE
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Position of a hold inside a step.
+/// `command_index` is the index of the held instruction, counted in order through all the
+/// nested blocks of the step; the execution resumes right after it.
+/// `loop_index` holds the current iteration of each enclosing loop, from the outermost one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexInfo {
     pub command_index: usize,
     pub loop_index: Vec<usize>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hold {
     pub index: IndexInfo,
     pub step_vars: serde_json::Value,
@@ -21,12 +30,28 @@ pub struct Hold {
     pub flow_name: String,
     pub previous: Option<PreviousInfo>,
     pub secure: bool,
+    // set by delay: the conversation does not resume before this date, "%Y-%m-%dT%H:%M:%S.%3fZ"
+    #[serde(default)]
+    pub wake_at: Option<String>,
+    // number of times the conversation was held again on this hold by retry
+    #[serde(default)]
+    pub retries: usize,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 // STATIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
+impl IndexInfo {
+    /// Index of the first instruction of a step, outside of any loop.
+    pub fn root() -> Self {
+        Self {
+            command_index: 0,
+            loop_index: vec![],
+        }
+    }
+}
+
 impl Hold {
     pub fn new(
         index: IndexInfo,
@@ -43,20 +68,67 @@ impl Hold {
             flow_name,
             previous,
             secure,
+            wake_at: None,
+            retries: 0,
         }
     }
 
+    /// Build a hold that resumes `step_name` of `flow` right after the instruction `command_index`.
+    /// Fails if the step does not exist or if `command_index` is not an instruction of the step,
+    /// instead of silently skipping the whole step at execution.
+    pub fn resume_at(
+        flow: &Flow,
+        flow_name: &str,
+        step_name: &str,
+        command_index: usize,
+        loop_index: Vec<usize>,
+    ) -> Result<Self, ErrorInfo> {
+        let commands_count = match flow
+            .flow_instructions
+            .get(&InstructionScope::StepScope(step_name.to_owned()))
+        {
+            Some(Expr::Scope { scope, .. }) => scope.commands_count,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(Interval::default(), flow_name),
+                    format!("[{}] {}", step_name, ERROR_STEP_EXIST),
+                ))
+            }
+        };
+
+        if command_index >= commands_count {
+            return Err(gen_error_info(
+                Position::new(Interval::default(), flow_name),
+                format!(
+                    "{}: {} is not lower than the {} instructions of step [{}]",
+                    ERROR_HOLD_INDEX, command_index, commands_count, step_name
+                ),
+            ));
+        }
+
+        Ok(Self::new(
+            IndexInfo {
+                command_index,
+                loop_index,
+            },
+            serde_json::json!({}),
+            step_name.to_owned(),
+            flow_name.to_owned(),
+            None,
+            false,
+        ))
+    }
+
     pub fn default() -> Self {
         Self {
-            index: IndexInfo {
-                command_index: 0,
-                loop_index: vec![],
-            },
+            index: IndexInfo::root(),
             step_vars: serde_json::json!({}),
             step_name: "".to_owned(),
             flow_name: "".to_owned(),
             previous: None,
             secure: false,
+            wake_at: None,
+            retries: 0,
         }
     }
 }
@@ -84,6 +156,34 @@ pub fn hold_index_start_loop<'a>(
     array
 }
 
+pub fn hold_index_start_range(data: &mut Data) -> usize {
+    // add the new loop index in stack
+    data.loop_indexes.push(0);
+
+    // range values are generated on the fly, the saved index is the number of
+    // values to skip from the start of the range
+    match &data.context.hold {
+        Some(hold) if data.loop_index < hold.index.loop_index.len() => {
+            hold.index.loop_index[data.loop_index]
+        }
+        _ => 0,
+    }
+}
+
+pub fn hold_index_start_while(data: &mut Data) -> usize {
+    // add the new loop index in stack
+    data.loop_indexes.push(0);
+
+    // while loops can't skip values like foreach, the saved index is only used
+    // to continue counting the iterations from where the hold was made
+    match &data.context.hold {
+        Some(hold) if data.loop_index < hold.index.loop_index.len() => {
+            hold.index.loop_index[data.loop_index]
+        }
+        _ => 0,
+    }
+}
+
 // remove the loop index of the stack
 pub fn hold_index_end_loop(data: &mut Data) {
     data.loop_indexes.pop();
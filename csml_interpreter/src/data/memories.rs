@@ -1,5 +1,14 @@
-use crate::data::primitive::PrimitiveObject;
-use crate::data::Literal;
+use crate::data::primitive::{PrimitiveBoolean, PrimitiveObject};
+use crate::data::{ast::Interval, Literal};
+
+use std::collections::HashMap;
+use std::fmt;
+
+// key of the additional info marking a sensitive memory, saved with the memory so
+// it is still sensitive in the next turns
+pub const SENSITIVE_INFO: &str = "sensitive";
+// value displayed instead of a sensitive memory
+pub const REDACTED_VALUE: &str = "***";
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum MemoryType {
@@ -10,12 +19,30 @@ pub enum MemoryType {
     Constant,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Memory {
     pub key: String,
     pub value: serde_json::Value,
 }
 
+// a literal is sensitive when it is marked in its additional info
+pub fn is_sensitive_literal(literal: &Literal) -> bool {
+    match &literal.additional_info {
+        Some(info) => match info.get(SENSITIVE_INFO) {
+            Some(sensitive) => sensitive.primitive.as_bool(),
+            None => false,
+        },
+        None => false,
+    }
+}
+
+pub fn set_sensitive_literal(literal: &mut Literal, interval: Interval) {
+    literal.add_info(
+        SENSITIVE_INFO,
+        PrimitiveBoolean::get_literal(true, interval),
+    );
+}
+
 impl Memory {
     pub fn new(key: String, value: Literal) -> Self {
         let content_type = &value.content_type;
@@ -31,4 +58,125 @@ impl Memory {
 
         Self { key, value }
     }
+
+    /// A sensitive memory is saved as `{"_additional_info": {"sensitive": true}, "value": ...}`
+    pub fn is_sensitive_value(value: &serde_json::Value) -> bool {
+        value["_additional_info"][SENSITIVE_INFO] == serde_json::Value::Bool(true)
+    }
+
+    /// Memory value to display in the logs and the debugging outputs
+    pub fn redact_value(value: &serde_json::Value) -> serde_json::Value {
+        match Self::is_sensitive_value(value) {
+            true => serde_json::json!(REDACTED_VALUE),
+            false => value.to_owned(),
+        }
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        Self::is_sensitive_value(&self.value)
+    }
+}
+
+// the value of a sensitive memory is never displayed
+impl fmt::Debug for Memory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memory")
+            .field("key", &self.key)
+            .field("value", &Self::redact_value(&self.value))
+            .finish()
+    }
+}
+
+// memory changed during a turn, before is None for an added memory and after is None
+// for a removed one
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryChange {
+    pub key: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryDiff {
+    pub added: Vec<MemoryChange>,
+    pub modified: Vec<MemoryChange>,
+    pub removed: Vec<MemoryChange>,
+}
+
+impl MemoryDiff {
+    // compare the memories at the start and at the end of a turn, the changes are sorted by key.
+    // The values of a memory sensitive before or after the turn are masked
+    pub fn new(before: &HashMap<String, Literal>, after: &HashMap<String, Literal>) -> Self {
+        let mut diff = Self::default();
+
+        let value_of = |key: &str, literal: &Literal, sensitive: bool| match sensitive {
+            true => serde_json::json!(REDACTED_VALUE),
+            false => Memory::new(key.to_owned(), literal.to_owned()).value,
+        };
+
+        for (key, literal) in after.iter() {
+            match before.get(key) {
+                Some(old) => {
+                    let old_value = Memory::new(key.to_owned(), old.to_owned()).value;
+                    let value = Memory::new(key.to_owned(), literal.to_owned()).value;
+
+                    if old_value != value {
+                        let sensitive = is_sensitive_literal(old) || is_sensitive_literal(literal);
+
+                        diff.modified.push(MemoryChange {
+                            key: key.to_owned(),
+                            before: Some(value_of(key, old, sensitive)),
+                            after: Some(value_of(key, literal, sensitive)),
+                        });
+                    }
+                }
+                None => diff.added.push(MemoryChange {
+                    key: key.to_owned(),
+                    before: None,
+                    after: Some(value_of(key, literal, is_sensitive_literal(literal))),
+                }),
+            }
+        }
+
+        for (key, literal) in before.iter() {
+            if !after.contains_key(key) {
+                diff.removed.push(MemoryChange {
+                    key: key.to_owned(),
+                    before: Some(value_of(key, literal, is_sensitive_literal(literal))),
+                    after: None,
+                });
+            }
+        }
+
+        diff.added.sort_by(|a, b| a.key.cmp(&b.key));
+        diff.modified.sort_by(|a, b| a.key.cmp(&b.key));
+        diff.removed.sort_by(|a, b| a.key.cmp(&b.key));
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let changes_to_json = |changes: &Vec<MemoryChange>| -> serde_json::Value {
+            changes
+                .iter()
+                .map(|change| {
+                    serde_json::json!({
+                        "key": change.key,
+                        "before": change.before,
+                        "after": change.after,
+                    })
+                })
+                .collect()
+        };
+
+        serde_json::json!({
+            "added": changes_to_json(&self.added),
+            "modified": changes_to_json(&self.modified),
+            "removed": changes_to_json(&self.removed),
+        })
+    }
 }
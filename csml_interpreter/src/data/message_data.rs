@@ -1,5 +1,5 @@
 use crate::data::error_info::ErrorInfo;
-use crate::data::{Hold, Literal, Memory, Message, MSG};
+use crate::data::{Hold, Literal, Memory, MemoryDiff, Message, MSG};
 use crate::parser::ExitCondition;
 
 use core::ops::Add;
@@ -15,6 +15,10 @@ pub struct MessageData {
     pub messages: Vec<Message>,
     pub hold: Option<Hold>,
     pub exit_condition: Option<ExitCondition>,
+    // first runtime error sent while interpreting, used by try/catch blocks
+    pub error: Option<ErrorInfo>,
+    // memories added, modified and removed during the turn, set at the end of interpret
+    pub memory_diff: MemoryDiff,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -28,6 +32,8 @@ impl Default for MessageData {
             messages: Vec::new(),
             hold: None,
             exit_condition: None,
+            error: None,
+            memory_diff: MemoryDiff::default(),
         }
     }
 }
@@ -44,13 +50,15 @@ impl Add<MessageData> for MessageData {
                 _ => None,
             },
             messages: [&self.messages[..], &other.messages[..]].concat(),
-            hold: self.hold,
+            hold: self.hold.or(other.hold),
             exit_condition: match (&self.exit_condition, &other.exit_condition) {
                 (Some(exit_condition), None) => Some(exit_condition.to_owned()),
                 (None, Some(exit_condition)) => Some(exit_condition.to_owned()),
                 (Some(exit_condition), Some(_)) => Some(exit_condition.to_owned()),
                 _ => None,
             },
+            error: self.error.or(other.error),
+            memory_diff: self.memory_diff,
         }
     }
 }
@@ -85,6 +93,8 @@ impl MessageData {
                     }],
                     hold: None,
                     exit_condition: Some(ExitCondition::Error),
+                    error: Some(err),
+                    memory_diff: MemoryDiff::default(),
                 }
             }
         }
@@ -96,24 +106,47 @@ impl MessageData {
 ////////////////////////////////////////////////////////////////////////////////
 
 impl MessageData {
+    /// The run reached `goto end`, or the end of a step without goto:
+    /// the conversation is over and the next event starts it again
+    pub fn ended(&self) -> bool {
+        self.exit_condition == Some(ExitCondition::End)
+    }
+
+    /// The memories and messages of the run, formatted as
+    /// {"memories": [{"key": ..., "value": ...}], "messages": [...]}
+    pub fn to_json_value(&self) -> serde_json::Value {
+        let memories: Vec<serde_json::Value> = match &self.memories {
+            Some(memories) => memories
+                .iter()
+                .map(|memory| serde_json::json!({"key": memory.key, "value": memory.value}))
+                .collect(),
+            None => vec![],
+        };
+        let messages: Vec<serde_json::Value> = self
+            .messages
+            .iter()
+            .map(|message| message.to_owned().message_to_json())
+            .collect();
+
+        serde_json::json!({
+            "memories": memories,
+            "messages": messages,
+        })
+    }
+
     pub fn add_message(mut self, message: Message) -> Self {
         self.messages.push(message);
         self
     }
 
+    // the memory is kept as it is saved, with its additional info
     pub fn add_to_memory(&mut self, key: &str, value: Literal) {
-        let content_type = &value.content_type;
+        let memory = Memory::new(key.to_owned(), value);
 
         if let Some(ref mut vec) = self.memories {
-            vec.push(Memory {
-                key: key.to_owned(),
-                value: value.primitive.format_mem(content_type, true),
-            });
+            vec.push(memory);
         } else {
-            self.memories = Some(vec![Memory {
-                key: key.to_owned(),
-                value: value.primitive.format_mem(content_type, true),
-            }])
+            self.memories = Some(vec![memory])
         };
     }
 }
@@ -12,6 +12,7 @@ use std::sync::mpsc;
 #[derive(Debug)]
 pub enum MSG {
     Remember(Memory),
+    RememberConversation(Memory),
     Forget(ForgetMemory),
     Message(Message),
     Log {
@@ -59,7 +60,11 @@ impl MSG {
                 }
 
                 let mut error_lit = PrimitiveNull::get_literal(err.position.interval);
-                error_lit.additional_info = err.additional_info;
+                error_lit.additional_info = err.additional_info.clone();
+
+                if msg_data.error.is_none() {
+                    msg_data.error = Some(err);
+                }
 
                 error_lit
             }
@@ -0,0 +1,42 @@
+use crate::lock::{read_or_recover, write_or_recover};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Function of the host called by its name from the flows, registered with
+/// `register_native_function`. It is resolved after the functions of the flow and
+/// its imports, a function of a flow with the same name is a load time error.
+///
+/// The arguments are given by position, an error returned by the function is a runtime
+/// error of the flow and can be caught with try/catch.
+pub trait NativeFunction: Send + Sync {
+    fn call(&self, args: Vec<serde_json::Value>) -> Result<serde_json::Value, String>;
+}
+
+// the functions are shared by all the bots of the host
+static NATIVE_FUNCTIONS: RwLock<BTreeMap<String, Arc<dyn NativeFunction>>> =
+    RwLock::new(BTreeMap::new());
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn register_native_function(name: &str, function: Box<dyn NativeFunction>) {
+    let mut functions = write_or_recover(&NATIVE_FUNCTIONS);
+
+    functions.insert(name.to_owned(), Arc::from(function));
+}
+
+// the function is cloned out of the registry, it can register other functions when called
+pub fn get_native_function(name: &str) -> Option<Arc<dyn NativeFunction>> {
+    let functions = read_or_recover(&NATIVE_FUNCTIONS);
+
+    functions.get(name).cloned()
+}
+
+pub fn is_native_function(name: &str) -> bool {
+    get_native_function(name).is_some()
+}
@@ -0,0 +1,30 @@
+use crate::data::{Client, Interval, Message};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Hook called synchronously for each message emitted by `say` or `debug`, before the
+/// message is sent or queued. Secure variables can not be displayed, so their values
+/// never reach the observer.
+///
+/// The client is the one of the context's api_info, when it is set.
+/// An error returned by the observer is logged and does not stop the run.
+pub trait MessageObserver {
+    fn on_message(
+        &self,
+        client: Option<&Client>,
+        interval: &Interval,
+        message: &Message,
+    ) -> Result<(), String>;
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TRAIT FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl std::fmt::Debug for dyn MessageObserver + '_ {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "MessageObserver")
+    }
+}
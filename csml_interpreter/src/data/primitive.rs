@@ -23,6 +23,7 @@ pub use null::PrimitiveNull;
 pub use object::PrimitiveObject;
 pub use string::PrimitiveString;
 
+use crate::data::ast::Infix;
 use crate::data::primitive::tools::*;
 use crate::data::{Data, Interval, Literal, MemoryType, Message, MessageData, MSG};
 use crate::error_format::*;
@@ -41,7 +42,7 @@ pub enum Right {
     Write,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum PrimitiveType {
     PrimitiveArray,
     PrimitiveBoolean,
@@ -140,6 +141,84 @@ impl dyn Primitive {
 
         Ok(res)
     }
+
+    // 'lhs div rhs', rounded towards negative infinity like '%' so that
+    // (lhs div rhs) * rhs + lhs % rhs == lhs
+    pub fn int_div(&self, other: &dyn Primitive) -> Result<Box<dyn Primitive>, String> {
+        match (get_number(self), get_number(other)) {
+            (Some(Integer::Int(lhs)), Some(Integer::Int(rhs))) => {
+                check_division_by_zero_i64(lhs, rhs)?;
+
+                match lhs.checked_div(rhs) {
+                    Some(value) if lhs % rhs != 0 && (lhs < 0) != (rhs < 0) => {
+                        Ok(Box::new(PrimitiveInt::new(value - 1)))
+                    }
+                    Some(value) => Ok(Box::new(PrimitiveInt::new(value))),
+                    None => Err(format!(
+                        "{} {:?} div {:?}",
+                        OVERFLOWING_OPERATION,
+                        self.get_type(),
+                        other.get_type()
+                    )),
+                }
+            }
+            (Some(lhs), Some(rhs)) => {
+                let to_float = |number| match number {
+                    Integer::Int(int) => int as f64,
+                    Integer::Float(float) => float,
+                };
+                let (lhs, rhs) = (to_float(lhs), to_float(rhs));
+
+                check_division_by_zero_f64(lhs, rhs)?;
+
+                Ok(Box::new(PrimitiveFloat::new((lhs / rhs).floor())))
+            }
+            _ => Err(format!(
+                "{} {:?} div {:?}",
+                ERROR_ILLEGAL_OPERATION,
+                self.get_type(),
+                other.get_type()
+            )),
+        }
+    }
+
+    // '&', '|', '^', '<<' and '>>' on ints, '>>' keeps the sign of lhs
+    pub fn bitwise(
+        &self,
+        infix: &Infix,
+        other: &dyn Primitive,
+    ) -> Result<Box<dyn Primitive>, String> {
+        let (lhs, rhs) = match (
+            self.as_any().downcast_ref::<PrimitiveInt>(),
+            other.as_any().downcast_ref::<PrimitiveInt>(),
+        ) {
+            (Some(lhs), Some(rhs)) => (lhs.value, rhs.value),
+            _ => {
+                return Err(format!(
+                    "{}, got {} and {}",
+                    ERROR_OPS_BITWISE,
+                    self.get_type().to_string(),
+                    other.get_type().to_string()
+                ))
+            }
+        };
+
+        let shift_count = || match rhs {
+            0..=63 => Ok(rhs as u32),
+            _ => Err(format!("{}, got {}", ERROR_OPS_SHIFT_COUNT, rhs)),
+        };
+
+        let value = match infix {
+            Infix::BitwiseAnd(_) => lhs & rhs,
+            Infix::BitwiseOr(_) => lhs | rhs,
+            Infix::BitwiseXor(_) => lhs ^ rhs,
+            Infix::ShiftLeft(_) => lhs << shift_count()?,
+            Infix::ShiftRight(_) => lhs >> shift_count()?,
+            _ => return Err(ERROR_OPS.to_owned()),
+        };
+
+        Ok(Box::new(PrimitiveInt::new(value)))
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
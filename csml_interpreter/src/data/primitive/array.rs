@@ -16,7 +16,6 @@ use crate::interpreter::variable_handler::resolve_csml_object::{
 };
 use phf::phf_map;
 use rand::seq::SliceRandom;
-use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::cmp::Ordering;
@@ -47,6 +46,7 @@ const FUNCTIONS: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
     "to_string" => (PrimitiveArray::to_string as PrimitiveMethod, Right::Read),
 
     "init" => (PrimitiveArray::init as PrimitiveMethod, Right::Read),
+    "contains" => (PrimitiveArray::contains as PrimitiveMethod, Right::Read),
     "find" => (PrimitiveArray::find as PrimitiveMethod, Right::Read),
     "is_empty" => (PrimitiveArray::is_empty as PrimitiveMethod, Right::Read),
     "insert_at" => (PrimitiveArray::insert_at as PrimitiveMethod, Right::Write),
@@ -99,6 +99,31 @@ fn check_index(
     Ok(())
 }
 
+// negative indexes count from the end of the array, indexes out of the array are clamped to it
+fn get_slice_index(literal: &Literal, length: usize, flow_name: &str) -> Result<usize, ErrorInfo> {
+    let index = *Literal::get_value::<i64>(
+        &literal.primitive,
+        flow_name,
+        literal.interval,
+        ERROR_SLICE_ARG_INT.to_owned(),
+    )?;
+    let length = length as i64;
+
+    let index = match index.is_negative() {
+        true => length.saturating_add(index),
+        false => index,
+    };
+
+    Ok(index.clamp(0, length) as usize)
+}
+
+// errors of map, filter and reduce callbacks keep the index of the element they failed on
+fn add_element_index(mut err: ErrorInfo, index: usize, interval: Interval) -> ErrorInfo {
+    err.add_info("index", PrimitiveInt::get_literal(index as i64, interval));
+
+    err
+}
+
 impl PrimitiveArray {
     fn is_number(
         _array: &mut PrimitiveArray,
@@ -279,6 +304,33 @@ impl PrimitiveArray {
         Ok(PrimitiveArray::get_literal(&vec, interval))
     }
 
+    fn contains(
+        array: &mut PrimitiveArray,
+        args: &HashMap<String, Literal>,
+        _additional_info: &Option<HashMap<String, Literal>>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "contains(value: primitive) => boolean";
+
+        let value = match args.get("arg0") {
+            Some(value) if args.len() == 1 => value,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    format!("usage: {}", usage),
+                ));
+            }
+        };
+
+        // arrays and objects are equal when all their values are, at any depth
+        let contains = array.value.iter().any(|literal| literal == value);
+
+        Ok(PrimitiveBoolean::get_literal(contains, interval))
+    }
+
     fn find(
         array: &mut PrimitiveArray,
         args: &HashMap<String, Literal>,
@@ -529,10 +581,7 @@ impl PrimitiveArray {
             ));
         }
 
-        if let Some(res) = array
-            .value
-            .get(rand::thread_rng().gen_range(0..array.value.len()))
-        {
+        if let Some(res) = data.random.with(|rng| array.value.choose(rng)) {
             return Ok(res.to_owned());
         }
 
@@ -671,7 +720,7 @@ impl PrimitiveArray {
 
         let mut vector = array.value.to_owned();
 
-        vector.shuffle(&mut rand::thread_rng());
+        data.random.with(|rng| vector.shuffle(rng));
 
         Ok(PrimitiveArray::get_literal(&vector, interval))
     }
@@ -687,108 +736,29 @@ impl PrimitiveArray {
     ) -> Result<Literal, ErrorInfo> {
         let usage = "slice(start: Integer, end: Optional<Integer>) => [Literal]";
         let len = array.value.len();
+        let flow_name = &data.context.flow;
+
+        let (start, end) = match (args.len(), args.get("arg0"), args.get("arg1")) {
+            (1, Some(start), None) => (get_slice_index(start, len, flow_name)?, len),
+            (2, Some(start), Some(end)) => (
+                get_slice_index(start, len, flow_name)?,
+                get_slice_index(end, len, flow_name)?,
+            ),
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, flow_name),
+                    format!("usage: {}", usage),
+                ))
+            }
+        };
 
-        match args.len() {
-            1 => match args.get("arg0") {
-                Some(literal) => {
-                    let mut int_start = Literal::get_value::<i64>(
-                        &literal.primitive,
-                        &data.context.flow,
-                        literal.interval,
-                        ERROR_SLICE_ARG_INT.to_owned(),
-                    )?
-                    .to_owned();
-
-                    if int_start < 0 {
-                        int_start = len as i64 + int_start;
-                    }
-
-                    let start = match int_start {
-                        value if value >= 0 && (value as usize) < len => value as usize,
-                        _ => {
-                            return Err(gen_error_info(
-                                Position::new(interval, &data.context.flow),
-                                ERROR_SLICE_ARG_LEN.to_owned(),
-                            ))
-                        }
-                    };
-
-                    let value = array.value[start..]
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<Literal>>();
-
-                    Ok(PrimitiveArray::get_literal(&value, interval))
-                }
-                _ => Err(gen_error_info(
-                    Position::new(interval, &data.context.flow),
-                    ERROR_SLICE_ARG_INT.to_owned(),
-                )),
-            },
-            2 => match (args.get("arg0"), args.get("arg1")) {
-                (Some(literal_start), Some(literal_end)) => {
-                    let mut int_start = Literal::get_value::<i64>(
-                        &literal_start.primitive,
-                        &data.context.flow,
-                        literal_start.interval,
-                        ERROR_SLICE_ARG_INT.to_owned(),
-                    )?
-                    .to_owned();
-                    let mut int_end = Literal::get_value::<i64>(
-                        &literal_end.primitive,
-                        &data.context.flow,
-                        literal_end.interval,
-                        ERROR_SLICE_ARG_INT.to_owned(),
-                    )?
-                    .to_owned();
-
-                    if int_start < 0 {
-                        int_start = len as i64 + int_start;
-                    }
-
-                    if int_end.is_negative() {
-                        int_end = len as i64 + int_end;
-                    }
-                    if int_end < int_start {
-                        return Err(gen_error_info(
-                            Position::new(interval, &data.context.flow),
-                            ERROR_SLICE_ARG2.to_owned(),
-                        ));
-                    }
+        // an end before the start gives an empty array
+        let value = match start < end {
+            true => array.value[start..end].to_vec(),
+            false => vec![],
+        };
 
-                    let (start, end) = match (int_start, int_end) {
-                        (start, end)
-                            if int_start >= 0
-                                && end >= 0
-                                && (start as usize) < len
-                                && (end as usize) <= len =>
-                        {
-                            (start as usize, end as usize)
-                        }
-                        _ => {
-                            return Err(gen_error_info(
-                                Position::new(interval, &data.context.flow),
-                                ERROR_SLICE_ARG_LEN.to_owned(),
-                            ))
-                        }
-                    };
-                    let value = array.value[start..end]
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<Literal>>();
-
-                    Ok(PrimitiveArray::get_literal(&value, interval))
-                }
-                _ => Err(gen_error_info(
-                    Position::new(interval, &data.context.flow),
-                    ERROR_SLICE_ARG_INT.to_owned(),
-                )),
-            },
-            _ => Err(gen_error_info(
-                Position::new(interval, &data.context.flow),
-                format!("usage: {}", usage),
-            )),
-        }
+        Ok(PrimitiveArray::get_literal(&value, interval))
     }
 
     fn reverse(
@@ -961,7 +931,8 @@ impl PrimitiveArray {
                         &mut new_scope_data,
                         msg_data,
                         sender,
-                    )?;
+                    )
+                    .map_err(|err| add_element_index(err, index, interval))?;
                     vec.push(result);
                 }
 
@@ -1037,7 +1008,8 @@ impl PrimitiveArray {
                         &mut new_scope_data,
                         msg_data,
                         sender,
-                    )?;
+                    )
+                    .map_err(|err| add_element_index(err, index, interval))?;
 
                     if result.primitive.as_bool() {
                         vec.push(value.clone());
@@ -1062,63 +1034,74 @@ impl PrimitiveArray {
         msg_data: &mut MessageData,
         sender: &Option<mpsc::Sender<MSG>>,
     ) -> Result<Literal, ErrorInfo> {
-        let usage = "reduce(acc, fn) expect tow arguments an initial value and 'Closure' with two arguments: an 'accumulator', and an element";
-
-        match (args.get("arg0"), args.get("arg1")) {
-            (Some(acc), Some(closure)) => {
-                let mut accumulator = acc.clone();
-
-                let closure: &PrimitiveClosure = Literal::get_value::<PrimitiveClosure>(
-                    &closure.primitive,
-                    &data.context.flow,
-                    interval,
+        let usage = "reduce([initial,] fn) expect an optional initial value and a 'Closure' with two arguments: an 'accumulator' and an element";
+
+        // without an initial value, the first element is the initial accumulator
+        let (mut accumulator, closure, first_index) = match (args.get("arg0"), args.get("arg1")) {
+            (Some(acc), Some(closure)) => (acc.clone(), closure, 0),
+            (Some(closure), None) => match array.value.first() {
+                Some(first) => (first.clone(), closure, 1),
+                None => {
+                    return Err(gen_error_info(
+                        Position::new(interval, &data.context.flow),
+                        ERROR_ARRAY_REDUCE_EMPTY.to_owned(),
+                    ))
+                }
+            },
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
                     format!("usage: {}", usage),
-                )?;
-
-                let mut context = init_child_context(&data);
-                let mut step_count = data.step_count.clone();
-                let mut new_scope_data = init_child_scope(data, &mut context, &mut step_count);
-
-                for (index, value) in array.value.iter().enumerate() {
-                    let mut map = HashMap::new();
-                    map.insert("arg0".to_owned(), accumulator);
-                    map.insert("arg1".to_owned(), value.to_owned());
-
-                    if closure.args.len() >= 2 {
-                        map.insert(
-                            "arg2".to_owned(),
-                            PrimitiveInt::get_literal(index as i64, interval),
-                        );
-                    }
+                ))
+            }
+        };
 
-                    let args = ArgsType::Normal(map);
+        let closure: &PrimitiveClosure = Literal::get_value::<PrimitiveClosure>(
+            &closure.primitive,
+            &data.context.flow,
+            interval,
+            format!("usage: {}", usage),
+        )?;
 
-                    insert_args_in_scope_memory(
-                        &mut new_scope_data,
-                        &closure.args,
-                        &args,
-                        msg_data,
-                        sender,
-                    );
+        let mut context = init_child_context(&data);
+        let mut step_count = data.step_count.clone();
+        let mut new_scope_data = init_child_scope(data, &mut context, &mut step_count);
 
-                    accumulator = exec_closure(
-                        &closure.func,
-                        &closure.args,
-                        args,
-                        interval,
-                        &mut new_scope_data,
-                        msg_data,
-                        sender,
-                    )?;
-                }
+        for (index, value) in array.value.iter().enumerate().skip(first_index) {
+            let mut map = HashMap::new();
+            map.insert("arg0".to_owned(), accumulator);
+            map.insert("arg1".to_owned(), value.to_owned());
 
-                Ok(accumulator)
+            if closure.args.len() >= 2 {
+                map.insert(
+                    "arg2".to_owned(),
+                    PrimitiveInt::get_literal(index as i64, interval),
+                );
             }
-            _ => Err(gen_error_info(
-                Position::new(interval, &data.context.flow),
-                format!("usage: {}", usage),
-            )),
+
+            let args = ArgsType::Normal(map);
+
+            insert_args_in_scope_memory(
+                &mut new_scope_data,
+                &closure.args,
+                &args,
+                msg_data,
+                sender,
+            );
+
+            accumulator = exec_closure(
+                &closure.func,
+                &closure.args,
+                args,
+                interval,
+                &mut new_scope_data,
+                msg_data,
+                sender,
+            )
+            .map_err(|err| add_element_index(err, index, interval))?;
         }
+
+        Ok(accumulator)
     }
 }
 
@@ -1133,6 +1116,10 @@ impl PrimitiveArray {
         }
     }
 
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(vector: &[Literal], interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveArray::new(vector));
 
@@ -1152,9 +1139,15 @@ impl PrimitiveArray {
 
 #[typetag::serde]
 impl Primitive for PrimitiveArray {
+    // elements are compared in order with the same rules as `==`
     fn is_eq(&self, other: &dyn Primitive) -> bool {
         if let Some(other) = other.as_any().downcast_ref::<Self>() {
-            return self.value == other.value;
+            return self.value.len() == other.value.len()
+                && self
+                    .value
+                    .iter()
+                    .zip(other.value.iter())
+                    .all(|(lhs, rhs)| *lhs.primitive == *rhs.primitive);
         }
 
         false
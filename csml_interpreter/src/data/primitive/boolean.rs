@@ -174,6 +174,10 @@ impl PrimitiveBoolean {
         Self { value }
     }
 
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(boolean: bool, interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveBoolean::new(boolean));
 
@@ -497,6 +497,10 @@ impl PrimitiveFloat {
         Self { value }
     }
 
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(float: f64, interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveFloat::new(float));
 
@@ -623,22 +627,22 @@ impl Primitive for PrimitiveFloat {
     }
 
     fn do_rem(&self, other: &dyn Primitive) -> Result<Box<dyn Primitive>, String> {
-        let mut error_msg = ERROR_ILLEGAL_OPERATION;
-
         if let Some(other) = other.as_any().downcast_ref::<Self>() {
-            let lhs = self.value as i64;
-            let rhs = other.value as i64;
+            check_division_by_zero_f64(self.value, other.value)?;
 
-            if lhs.checked_rem(rhs).is_some() {
-                return Ok(Box::new(PrimitiveFloat::new(self.value % other.value)));
-            }
+            let value = self.value % other.value;
+            // the result has the sign of the divisor, -7.5 % 2 is 0.5
+            let value = match value != 0.0 && (value < 0.0) != (other.value < 0.0) {
+                true => value + other.value,
+                false => value,
+            };
 
-            error_msg = OVERFLOWING_OPERATION;
+            return Ok(Box::new(PrimitiveFloat::new(value)));
         }
 
         Err(format!(
             "{} {:?} % {:?}",
-            error_msg,
+            ERROR_ILLEGAL_OPERATION,
             self.get_type(),
             other.get_type()
         ))
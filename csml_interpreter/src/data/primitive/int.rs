@@ -508,6 +508,10 @@ impl PrimitiveInt {
         Self { value }
     }
 
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(int: i64, interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveInt::new(int));
 
@@ -633,7 +637,15 @@ impl Primitive for PrimitiveInt {
         let mut error_msg = ERROR_ILLEGAL_OPERATION;
 
         if let Some(other) = other.as_any().downcast_ref::<Self>() {
+            check_division_by_zero_i64(self.value, other.value)?;
+
             if let Some(value) = self.value.checked_rem(other.value) {
+                // the result has the sign of the divisor, -7 % 3 is 2
+                let value = match value != 0 && (value < 0) != (other.value < 0) {
+                    true => value + other.value,
+                    false => value,
+                };
+
                 return Ok(Box::new(PrimitiveInt::new(value)));
             }
 
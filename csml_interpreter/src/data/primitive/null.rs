@@ -178,6 +178,10 @@ impl Default for PrimitiveNull {
 }
 
 impl PrimitiveNull {
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveNull::default());
 
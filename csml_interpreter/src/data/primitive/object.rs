@@ -43,7 +43,10 @@ const FUNCTIONS_HTTP: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map
     "put" => (PrimitiveObject::put as PrimitiveMethod, Right::Read),
     "delete" => (PrimitiveObject::delete as PrimitiveMethod, Right::Read),
     "patch" => (PrimitiveObject::patch as PrimitiveMethod, Right::Read),
+    "timeout" => (PrimitiveObject::timeout as PrimitiveMethod, Right::Read),
+    "retry" => (PrimitiveObject::retry as PrimitiveMethod, Right::Read),
     "send" => (PrimitiveObject::send as PrimitiveMethod, Right::Read),
+    "fetch" => (PrimitiveObject::fetch as PrimitiveMethod, Right::Read),
 };
 
 const FUNCTIONS_SMTP: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
@@ -461,6 +464,131 @@ impl PrimitiveObject {
         Ok(result)
     }
 
+    fn timeout(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        _additional_info: &Option<HashMap<String, Literal>>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "timeout(milliseconds: int) => http object";
+
+        let milliseconds = match args.get("arg0") {
+            Some(lit) if args.len() == 1 => Literal::get_value::<i64>(
+                &lit.primitive,
+                &data.context.flow,
+                lit.interval,
+                format!("usage: {}", usage),
+            )?,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    format!("usage: {}", usage),
+                ));
+            }
+        };
+
+        if *milliseconds <= 0 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let mut object = object.to_owned();
+
+        object.value.insert(
+            "timeout".to_owned(),
+            PrimitiveInt::get_literal(*milliseconds, interval),
+        );
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn retry(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        _additional_info: &Option<HashMap<String, Literal>>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "retry(max_retries: int) => http object";
+
+        let max_retries = match args.get("arg0") {
+            Some(lit) if args.len() == 1 => Literal::get_value::<i64>(
+                &lit.primitive,
+                &data.context.flow,
+                lit.interval,
+                format!("usage: {}", usage),
+            )?,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    format!("usage: {}", usage),
+                ));
+            }
+        };
+
+        if *max_retries < 0 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let mut object = object.to_owned();
+
+        object.value.insert(
+            "retry".to_owned(),
+            PrimitiveInt::get_literal(*max_retries, interval),
+        );
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn get_http_method(
+        object: &PrimitiveObject,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<&'static str, ErrorInfo> {
+        let literal = match object.value.get("method") {
+            Some(literal) => literal,
+            None => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    ERROR_HTTP_SEND.to_owned(),
+                ))
+            }
+        };
+
+        match Literal::get_value::<String>(
+            &literal.primitive,
+            &data.context.flow,
+            interval,
+            ERROR_HTTP_UNKNOWN_METHOD.to_string(),
+        ) {
+            Ok(delete) if delete == "delete" => Ok("delete"),
+            Ok(put) if put == "put" => Ok("put"),
+            Ok(patch) if patch == "patch" => Ok("patch"),
+            Ok(post) if post == "post" => Ok("post"),
+            Ok(get) if get == "get" => Ok("get"),
+            _ => Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_HTTP_UNKNOWN_METHOD.to_string(),
+            )),
+        }
+    }
+
     fn send(
         object: &mut PrimitiveObject,
         args: &HashMap<String, Literal>,
@@ -478,39 +606,44 @@ impl PrimitiveObject {
             ));
         }
 
-        if let Some(literal) = object.value.get("method") {
-            let method = match Literal::get_value::<String>(
-                &literal.primitive,
-                &data.context.flow,
-                interval,
-                ERROR_HTTP_UNKNOWN_METHOD.to_string(),
-            ) {
-                Ok(delete) if delete == "delete" => "delete",
-                Ok(put) if put == "put" => "put",
-                Ok(patch) if patch == "patch" => "patch",
-                Ok(post) if post == "post" => "post",
-                Ok(get) if get == "get" => "get",
-                _ => {
-                    return Err(gen_error_info(
-                        Position::new(interval, &data.context.flow),
-                        ERROR_HTTP_UNKNOWN_METHOD.to_string(),
-                    ))
-                }
-            };
+        let method = PrimitiveObject::get_http_method(object, data, interval)?;
+
+        let (value, response_info) =
+            http_request(&object.value, method, &data.context.flow, interval, false)?;
+        let mut literal = json_to_literal(&value, interval, &data.context.flow)?;
+        // add additional information about the http request response: status and headers
+        literal.add_info_block(response_info);
+
+        Ok(literal)
+    }
 
-            let (value, response_info) =
-                http_request(&object.value, method, &data.context.flow, interval, false)?;
-            let mut literal = json_to_literal(&value, interval, &data.context.flow)?;
-            // add additional information about the http request response: status and headers
-            literal.add_info_block(response_info);
+    fn fetch(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        _additional_info: &Option<HashMap<String, Literal>>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "fetch() => object { status, headers, body }";
 
-            return Ok(literal);
+        if !args.is_empty() {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                format!("usage: {}", usage),
+            ));
         }
 
-        Err(gen_error_info(
-            Position::new(interval, &data.context.flow),
-            ERROR_HTTP_SEND.to_owned(),
-        ))
+        let method = PrimitiveObject::get_http_method(object, data, interval)?;
+
+        let (value, mut response) =
+            http_request(&object.value, method, &data.context.flow, interval, false)?;
+        response.insert(
+            "body".to_owned(),
+            json_to_literal(&value, interval, &data.context.flow)?,
+        );
+
+        Ok(PrimitiveObject::get_literal(&response, interval))
     }
 }
 
@@ -2570,9 +2703,18 @@ impl PrimitiveObject {
 
 #[typetag::serde]
 impl Primitive for PrimitiveObject {
+    // the values of the same keys are compared with the same rules as `==`,
+    // whatever the order of the keys
     fn is_eq(&self, other: &dyn Primitive) -> bool {
         if let Some(other) = other.as_any().downcast_ref::<Self>() {
-            return self.value == other.value;
+            return self.value.len() == other.value.len()
+                && self
+                    .value
+                    .iter()
+                    .all(|(key, lhs)| match other.value.get(key) {
+                        Some(rhs) => *lhs.primitive == *rhs.primitive,
+                        None => false,
+                    });
         }
 
         false
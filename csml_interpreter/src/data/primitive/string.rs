@@ -58,6 +58,7 @@ const FUNCTIONS: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
     "append" => (PrimitiveString::append as PrimitiveMethod, Right::Read),
     "contains" => (PrimitiveString::contains as PrimitiveMethod, Right::Read),
     "contains_regex" => (PrimitiveString::contains_regex as PrimitiveMethod, Right::Read),
+    "eq_ignore_case" => (PrimitiveString::eq_ignore_case as PrimitiveMethod, Right::Read),
     "replace_regex" => (PrimitiveString::replace_regex as PrimitiveMethod, Right::Read),
     "replace_all" => (PrimitiveString::replace_all as PrimitiveMethod, Right::Read),
     "replace" => (PrimitiveString::replace as PrimitiveMethod, Right::Read),
@@ -73,6 +74,8 @@ const FUNCTIONS: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
     "starts_with_regex" => (PrimitiveString::starts_with_regex as PrimitiveMethod, Right::Read),
     "to_lowercase" => (PrimitiveString::to_lowercase as PrimitiveMethod, Right::Read),
     "to_uppercase" => (PrimitiveString::to_uppercase as PrimitiveMethod, Right::Read),
+    "to_lower" => (PrimitiveString::to_lowercase as PrimitiveMethod, Right::Read),
+    "to_upper" => (PrimitiveString::to_uppercase as PrimitiveMethod, Right::Read),
     "capitalize" => (PrimitiveString::capitalize as PrimitiveMethod, Right::Read),
     "slice" => (PrimitiveString::slice as PrimitiveMethod, Right::Read),
     "split" => (PrimitiveString::split as PrimitiveMethod, Right::Read),
@@ -687,6 +690,47 @@ impl PrimitiveString {
         Ok(PrimitiveBoolean::get_literal(result, interval))
     }
 
+    fn eq_ignore_case(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        _additional_info: &Option<HashMap<String, Literal>>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "eq_ignore_case(value: string) => boolean";
+
+        if args.len() != 1 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let value = match args.get("arg0") {
+            Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveString => {
+                Literal::get_value::<String>(
+                    &res.primitive,
+                    &data.context.flow,
+                    interval,
+                    ERROR_STRING_EQ_IGNORE_CASE.to_owned(),
+                )?
+            }
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    ERROR_STRING_EQ_IGNORE_CASE.to_owned(),
+                ));
+            }
+        };
+
+        // unicode aware, unlike str::eq_ignore_ascii_case
+        let result = string.value.to_lowercase() == value.to_lowercase();
+
+        Ok(PrimitiveBoolean::get_literal(result, interval))
+    }
+
     fn contains_regex(
         string: &mut PrimitiveString,
         args: &HashMap<String, Literal>,
@@ -1066,7 +1110,8 @@ impl PrimitiveString {
             ));
         }
 
-        let result = string.value.len();
+        // characters, not bytes
+        let result = string.value.chars().count();
 
         Ok(PrimitiveInt::get_literal(result as i64, interval))
     }
@@ -1468,7 +1513,7 @@ impl PrimitiveString {
         _msg_data: &mut MessageData,
         _sender: &Option<mpsc::Sender<MSG>>,
     ) -> Result<Literal, ErrorInfo> {
-        let usage = "string(separator: string) => array";
+        let usage = "split(separator: string) => array";
 
         if args.len() != 1 {
             return Err(gen_error_info(
@@ -1489,15 +1534,22 @@ impl PrimitiveString {
             _ => {
                 return Err(gen_error_info(
                     Position::new(interval, &data.context.flow),
-                    ERROR_ARRAY_JOIN.to_owned(),
+                    ERROR_STRING_SPLIT.to_owned(),
                 ));
             }
         };
 
         let mut vector: Vec<Literal> = Vec::new();
 
-        for result in string.value.split(separator) {
-            vector.push(PrimitiveString::get_literal(result, interval));
+        // an empty separator splits the string into its characters
+        if separator.is_empty() {
+            for c in string.value.chars() {
+                vector.push(PrimitiveString::get_literal(&c.to_string(), interval));
+            }
+        } else {
+            for result in string.value.split(separator) {
+                vector.push(PrimitiveString::get_literal(result, interval));
+            }
         }
 
         Ok(PrimitiveArray::get_literal(&vector, interval))
@@ -2135,6 +2187,10 @@ impl PrimitiveString {
         }
     }
 
+    pub fn has_method(name: &str) -> bool {
+        FUNCTIONS.contains_key(name)
+    }
+
     pub fn get_literal(string: &str, interval: Interval) -> Literal {
         let primitive = Box::new(PrimitiveString::new(string));
 
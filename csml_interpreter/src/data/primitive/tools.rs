@@ -1,6 +1,12 @@
-use crate::data::primitive::{PrimitiveString, PrimitiveType};
+use crate::data::primitive::{
+    Primitive, PrimitiveFloat, PrimitiveInt, PrimitiveString, PrimitiveType,
+};
 use crate::data::{Literal, Position};
 use crate::error_format::*;
+use crate::lock::lock_or_recover;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURE
@@ -15,6 +21,22 @@ pub enum Integer {
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
+// regexes are compiled the first time they are used and shared by every flow afterwards
+pub fn get_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    static REGEX_CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+
+    let mut cache = lock_or_recover(REGEX_CACHE.get_or_init(Default::default));
+
+    if let Some(regex) = cache.get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = Regex::new(pattern)?;
+    cache.insert(pattern.to_owned(), regex.clone());
+
+    Ok(regex)
+}
+
 pub fn get_integer(text: &str) -> Result<Integer, String> {
     match (text.parse::<i64>(), text.parse::<f64>()) {
         (Ok(int), _) => Ok(Integer::Int(int)),
@@ -23,6 +45,25 @@ pub fn get_integer(text: &str) -> Result<Integer, String> {
     }
 }
 
+// ints, floats and numeric strings, following the promotion rules of the operators
+pub fn get_number(primitive: &dyn Primitive) -> Option<Integer> {
+    match primitive.get_type() {
+        PrimitiveType::PrimitiveInt => {
+            let int = primitive.as_any().downcast_ref::<PrimitiveInt>()?;
+            Some(Integer::Int(int.value))
+        }
+        PrimitiveType::PrimitiveFloat => {
+            let float = primitive.as_any().downcast_ref::<PrimitiveFloat>()?;
+            Some(Integer::Float(float.value))
+        }
+        PrimitiveType::PrimitiveString => {
+            let string = primitive.as_any().downcast_ref::<PrimitiveString>()?;
+            get_integer(&string.value).ok()
+        }
+        _ => None,
+    }
+}
+
 pub fn get_array(
     literal: Literal,
     flow_name: &str,
@@ -197,3 +197,30 @@ where
         }
     }
 }
+
+// milliseconds in one unit of a duration literal like 30m or 3days
+pub fn get_duration_unit(unit: &str) -> Option<i64> {
+    match unit {
+        "ms" => Some(1),
+        "s" | "second" | "seconds" => Some(1_000),
+        "m" | "minute" | "minutes" => Some(60_000),
+        "h" | "hour" | "hours" => Some(3_600_000),
+        "d" | "day" | "days" => Some(86_400_000),
+        "w" | "week" | "weeks" => Some(604_800_000),
+        _ => None,
+    }
+}
+
+// durations are objects holding a number of milliseconds, like the time objects
+pub fn get_duration_literal(milliseconds: i64, interval: Interval) -> Literal {
+    let mut duration = HashMap::new();
+    duration.insert(
+        "milliseconds".to_owned(),
+        PrimitiveInt::get_literal(milliseconds, interval),
+    );
+
+    let mut literal = PrimitiveObject::get_literal(&duration, interval);
+    literal.set_content_type("duration");
+
+    literal
+}
@@ -0,0 +1,47 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::RefCell;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Random number generator of a run, shared by all its scopes. With a seed the random
+/// builtins and methods return the same values in each run
+#[derive(Debug)]
+pub struct RandomSource {
+    rng: RefCell<StdRng>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// STATIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl RandomSource {
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Self {
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+impl Default for RandomSource {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl RandomSource {
+    pub fn with<T>(&self, func: impl FnOnce(&mut StdRng) -> T) -> T {
+        func(&mut self.rng.borrow_mut())
+    }
+}
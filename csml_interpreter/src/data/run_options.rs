@@ -0,0 +1,94 @@
+use crate::data::ast::{Flow, InstructionScope, Interval};
+use crate::data::context::ContextStepInfo;
+use crate::data::position::Position;
+use crate::data::{Context, Event};
+use crate::error_format::{gen_error_info, ErrorInfo, ERROR_ENTRY_STEP};
+use chrono::{DateTime, Utc};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/// Options of a `run_flow` call. The options left to None keep the behavior of
+/// `interpret`: the limits are read from the event, then from the env vars
+/// (STEP_LIMIT, GOTO_LOOP_LIMIT, INSTRUCTION_LIMIT and TIME_LIMIT) or their defaults.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    // max number of steps in the run
+    pub step_limit: Option<usize>,
+    // max number of times a single step is entered in the run
+    pub goto_loop_limit: Option<usize>,
+    // execution budget of the run: max number of instructions and max duration in milliseconds
+    pub instruction_limit: Option<usize>,
+    pub time_limit: Option<u64>,
+    // seed of the random builtins, the same seed gives the same values in each run
+    pub seed: Option<u64>,
+    // confidence of the events without one in their content, 0 when it is not set
+    pub hold_confidence_default: Option<f64>,
+    // step run instead of 'start' when the context starts a new conversation on 'start'
+    pub entry_step: Option<String>,
+    // value of `_env` in the flow
+    pub env: Option<serde_json::Value>,
+    // date of the turn read by `Time()` and `delay`, the system time when it is not set
+    pub now: Option<DateTime<Utc>>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl RunOptions {
+    /// The limits set in the options take precedence over the ones of the event
+    pub fn apply_limits(&self, event: &mut Event) {
+        if let Some(step_limit) = self.step_limit {
+            event.step_limit = Some(step_limit);
+        }
+        if let Some(goto_loop_limit) = self.goto_loop_limit {
+            event.goto_loop_limit = Some(goto_loop_limit);
+        }
+        if let Some(instruction_limit) = self.instruction_limit {
+            event.instruction_limit = Some(instruction_limit);
+        }
+        if let Some(time_limit) = self.time_limit {
+            event.time_limit = Some(time_limit);
+        }
+        if let Some(seed) = self.seed {
+            event.seed = Some(seed);
+        }
+        if let Some(confidence) = self.hold_confidence_default {
+            event.hold_confidence_default = Some(confidence);
+        }
+    }
+
+    /// The date set in the options is the date of the turn of the context
+    pub fn apply_now(&self, context: &mut Context) {
+        if let Some(now) = self.now {
+            context.now = Some(now);
+        }
+    }
+
+    /// The entry step must be a step of the flow, even when the context does not start on it.
+    /// A context resuming a hold or targeting another step than 'start' is left as it is
+    pub fn apply_entry_step(&self, flow: &Flow, context: &mut Context) -> Result<(), ErrorInfo> {
+        let entry_step = match &self.entry_step {
+            Some(entry_step) => entry_step,
+            None => return Ok(()),
+        };
+
+        if !flow
+            .flow_instructions
+            .contains_key(&InstructionScope::StepScope(entry_step.to_owned()))
+        {
+            return Err(gen_error_info(
+                Position::new(Interval::default(), &context.flow),
+                format!("[{}] {}", entry_step, ERROR_ENTRY_STEP),
+            ));
+        }
+
+        if context.hold.is_none() && context.step.is_step("start") {
+            context.step = ContextStepInfo::Normal(entry_step.to_owned());
+        }
+
+        Ok(())
+    }
+}
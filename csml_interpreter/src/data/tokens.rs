@@ -17,6 +17,8 @@ pub const SUBTRACTION: &str = "-";
 pub const DIVIDE: &str = "/";
 pub const MULTIPLY: &str = "*";
 pub const REMAINDER: &str = "%";
+// "//" starts a comment, integer division is written 'a div b'
+pub const INTEGER_DIVISION: &str = "div";
 pub const NOT: &str = "!";
 
 pub const EQUAL: &str = "==";
@@ -25,6 +27,14 @@ pub const ASSIGN: &str = "=";
 
 pub const OR: &str = "||";
 pub const AND: &str = "&&";
+pub const NULL_COALESCING: &str = "??";
+pub const TERNARY: &str = "?";
+
+pub const BITWISE_AND: &str = "&";
+pub const BITWISE_OR: &str = "|";
+pub const BITWISE_XOR: &str = "^";
+pub const SHIFT_LEFT: &str = "<<";
+pub const SHIFT_RIGHT: &str = ">>";
 
 pub const SUBTRACTION_ASSIGNMENT: &str = "-=";
 pub const ADDITION_ASSIGNMENT: &str = "+=";
@@ -39,9 +49,15 @@ pub const LESS_THAN: &str = "<";
 
 pub const COMMA: &str = ",";
 pub const DOT: &str = ".";
+pub const RANGE: &str = "..";
+pub const RANGE_INCLUSIVE: &str = "..=";
+pub const SPREAD: &str = "...";
 pub const SEMICOLON: &str = ";";
+pub const FATARROW: &str = "=>";
 pub const COLON: &str = ":";
 pub const DOUBLE_QUOTE: &str = "\"";
+pub const TRIPLE_DOUBLE_QUOTE: &str = "\"\"\"";
+pub const SINGLE_QUOTE: &str = "'";
 pub const BACKSLASH_DOUBLE_QUOTE: &str = "\\\"";
 
 pub const UNDERSCORE: char = '_';
@@ -59,6 +75,8 @@ pub const FOREACH: &str = "foreach";
 pub const WHILE: &str = "while";
 pub const IF: &str = "if";
 pub const ELSE: &str = "else";
+pub const TRY: &str = "try";
+pub const CATCH: &str = "catch";
 
 pub const IMPORT: &str = "import";
 pub const CONST: &str = "const";
@@ -75,17 +93,26 @@ pub const STEP: &str = "step";
 pub const SAY: &str = "say";
 pub const DEBUG_ACTION: &str = "debug";
 pub const LOG_ACTION: &str = "log";
+pub const ASSERT: &str = "assert";
 pub const USE: &str = "use";
 pub const HOLD: &str = "hold";
 pub const HOLD_SECURE: &str = "hold_secure";
+pub const VALIDATE: &str = "validate";
+pub const EXPECT: &str = "expect";
+pub const CONFIDENCE: &str = "confidence";
+pub const DELAY: &str = "delay";
+pub const RETRY: &str = "retry";
 pub const GOTO: &str = "goto";
 pub const PREVIOUS: &str = "previous";
 pub const MATCH: &str = "match";
 pub const NOT_MATCH: &str = "!match";
 pub const DEFAULT: &str = "default";
 pub const REMEMBER: &str = "remember";
+pub const REMEMBER_CONVERSATION: &str = "remember_conversation";
+pub const SECRET: &str = "secret";
 pub const FORGET: &str = "forget";
 pub const _METADATA: &str = "_metadata";
+pub const METADATA: &str = "@metadata";
 pub const _MEMORY: &str = "_memory";
 pub const _ENV: &str = "_env";
 pub const BREAK: &str = "break";
@@ -168,11 +195,27 @@ pub const UUID: &str = "UUID";
 pub const TIME: &str = "Time";
 pub const EXISTS: &str = "Exists";
 
+pub const TYPE_OF: &str = "typeof";
+pub const IS_NUMBER: &str = "is_number";
+pub const IS_STRING: &str = "is_string";
+pub const IS_BOOLEAN: &str = "is_boolean";
+pub const IS_ARRAY: &str = "is_array";
+pub const IS_OBJECT: &str = "is_object";
+pub const IS_NULL: &str = "is_null";
+
+pub const GET_PATH: &str = "get_path";
+pub const COALESCE_MEMORY: &str = "coalesce_memory";
+
+pub const RANDOM_FN: &str = "random";
+pub const SHUFFLE_FN: &str = "shuffle";
+pub const SAMPLE: &str = "sample";
+
 pub const OBJECT: &str = "Object";
 
 pub const BUILT_IN: &[&str] = &[
     ONE_OF, SHUFFLE, LENGTH, FIND, RANDOM, FLOOR, FN, APP, HTTP, OBJECT, DEBUG, UUID, BASE64, HEX,
-    JWT, CRYPTO, TIME, SMTP, EXISTS,
+    JWT, CRYPTO, TIME, SMTP, EXISTS, TYPE_OF, IS_NUMBER, IS_STRING, IS_BOOLEAN, IS_ARRAY,
+    IS_OBJECT, IS_NULL, GET_PATH, COALESCE_MEMORY, RANDOM_FN, SHUFFLE_FN, SAMPLE,
 ];
 
 pub const OR_BUILT_IN: &str = "Or";
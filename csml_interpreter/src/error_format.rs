@@ -7,8 +7,9 @@ use nom::{
     *,
 };
 
-pub use crate::data::error_info::ErrorInfo;
+pub use crate::data::error_info::{ErrorCode, ErrorInfo};
 pub use data::CustomError;
+use data::KEEP_POSITION_ERROR_KIND;
 
 // TODO: add link to docs
 
@@ -19,10 +20,26 @@ pub const ERROR_PARENTHESES_END: &str =
 pub const ERROR_NUMBER_AS_IDENT: &str = "Int/Float can't be used as identifier";
 pub const ERROR_FLOW_STEP: &str = "syntax error.";
 pub const ERROR_RESERVED: &str = "reserved keyword can't be used as identifier";
+pub const ERROR_ENV_READ_ONLY: &str =
+    "_env is read-only, its values are set by the bot configuration";
 pub const ERROR_PARSING: &str =
     "Invalid argument. One of the action keywords [say, do, if, ...] is missing";
 pub const ERROR_REMEMBER: &str =
     "'remember' must be assigning to a variable via '='. Example: 'remember key = value'";
+pub const ERROR_DESTRUCTURE_OBJECT: &str =
+    "object destructuring like 'do {name, email} = user' only accepts objects";
+pub const ERROR_DESTRUCTURE_ARRAY: &str =
+    "array destructuring like 'do [first, second] = list' only accepts arrays";
+pub const ERROR_SPREAD_ARRAY: &str =
+    "spread like '[...list]' only accepts arrays in array literals";
+pub const ERROR_SPREAD_OBJECT: &str =
+    "spread like '{...base}' only accepts objects in object literals";
+pub const ERROR_TERNARY: &str =
+    "ternary expressions expect ':' between the two values. Example: 'cond ? a : b'";
+pub const ERROR_METADATA: &str =
+    "@metadata expects an object of constant values. Example: '@metadata { version: \"1.2\", tags: [\"sales\"] }'";
+pub const ERROR_METADATA_DUPLICATE: &str =
+    "a flow accepts a single @metadata block, placed before its steps";
 pub const ERROR_USE: &str =
     "'use' must be assigning a variable with keyword 'as'. Example: 'use value as key'";
 pub const ERROR_ACTION_ARGUMENT: &str =
@@ -32,15 +49,39 @@ pub const ERROR_IMPORT_ARGUMENT: &str =
 pub const ERROR_INSERT_ARGUMENT: &str =
     "'insert' expecting valid step name. Example: 'insert step from flow'";
 pub const ERROR_BREAK: &str = "break can only be used inside loops";
+pub const ERROR_CONTINUE: &str = "continue can only be used inside loops";
+pub const ERROR_LOOP_LABEL: &str =
+    "undeclared loop label, labels are declared on loops as 'label: foreach";
+pub const ERROR_LOOP_LABEL_NAME: &str = "expecting a label name after '";
 pub const ERROR_RETURN: &str = "return expects a value to return";
+pub const ERROR_RETURN_GUARD: &str =
+    "'if' after return expects a condition. Example: return error if name == null";
+pub const ERROR_UNTERMINATED_COMMENT: &str = "expecting '*/' to end the comment";
 pub const ERROR_LEFT_BRACE: &str = "expecting '{'";
 pub const ERROR_RIGHT_BRACE: &str = "expecting '}'";
 pub const ERROR_RIGHT_BRACKET: &str = "expecting ']'";
+pub const ERROR_MATCH_ARM: &str =
+    "match arms expect a string, a number, a regex or '_' followed by '=>'. Example: \"hello\" => say \"hi\"";
+pub const ERROR_MATCH_REGEX: &str =
+    "invalid regex in match arm, the flags can be i, m, s, x or U. Example: /hello.*world/i => say \"hi\"";
+pub const ERROR_TRY_CATCH: &str =
+    "try blocks expect a catch block with the name of the error. Example: try { ... } catch (err) { ... }";
 pub const ERROR_GOTO_STEP: &str = "missing step name after goto";
 pub const ERROR_IMPORT_STEP: &str = "missing step name after import";
+pub const ERROR_IMPORT_CONDITION: &str =
+    "conditional import expects a condition on the env and an else flow. Example: 'import function from flow_b if _env.variant == \"b\" else flow_a'";
+pub const ERROR_IMPORT_COLLISION: &str = "import collides with the local function";
+pub const ERROR_SINGLE_STEP: &str =
+    "expecting a single step: the range must start with a step name and hold no other step, constant, import or function";
+pub const ERROR_STEP_RANGE: &str = "the step range is not a valid byte range of the flow";
 pub const ERROR_DOUBLE_QUOTE: &str = "expecting '\"' to end string";
+pub const ERROR_UNTERMINATED_HEREDOC: &str = "expecting '\"\"\"' to end heredoc string";
 pub const ERROR_DOUBLE_OPEN_BRACE: &str = "expecting '{{' to begin expandable string";
 pub const ERROR_DOUBLE_CLOSE_BRACE: &str = "expecting '}}' to end expandable string";
+pub const ERROR_STRING_ESCAPE: &str =
+    "invalid escape sequence, expecting one of \\n \\t \\r \\0 \\\\ \\\" \\' \\{ \\} \\uXXXX or \\u{X}";
+pub const ERROR_STRING_UNICODE_ESCAPE: &str =
+    "invalid unicode escape, expecting \\uXXXX or \\u{X} with 1 to 6 hex digits of a valid code point. Example: \\u00e9 or \\u{1F600}";
 pub const ERROR_UNREACHABLE: &str = "unreachable";
 pub const ERROR_WRONG_ARGUMENT_EXPANDABLE_STRING: &str =
     "wrong argument(s) given to expandable string";
@@ -53,11 +94,37 @@ pub const ERROR_NO_FLOW: &str = "bot must have at least one flow";
 // ##Interpreter Errors
 // ### Validation
 pub const ERROR_STEP_EXIST: &str = "step does not exist";
+pub const ERROR_ENTRY_STEP: &str = "entry step of the run options does not exist in the flow";
+pub const ERROR_CONTEXT_FORMAT: &str = "invalid serialized context";
+pub const ERROR_CONTEXT_VERSION: &str = "unsupported serialized context version";
+pub const ERROR_HOLD_INDEX: &str = "hold command_index is out of the step instructions range";
+pub const ERROR_HOLD_SCHEMA: &str =
+    "hold validate expects a JSON schema object. Example: hold validate {\"type\": \"object\"}";
+pub const ERROR_HOLD_SCHEMA_MISMATCH: &str = "the event does not match the hold schema";
+pub const ERROR_HOLD_EXPECT: &str = "hold expect expects one of the types number, string, boolean or email. Example: hold expect number";
+pub const ERROR_ASSERT: &str = "assertion failed";
+pub const ERROR_DELAY: &str = "delay expects a positive duration. Example: delay 30m";
+pub const ERROR_RETRY: &str =
+    "retry expects an optional maximum number of retries. Example: retry 2";
+pub const ERROR_HOLD_EXPECT_MISMATCH: &str = "the event does not match the hold expected type";
+pub const ERROR_HOLD_CONFIDENCE: &str =
+    "hold confidence expects a threshold between 0 and 1. Example: hold confidence 0.8";
+pub const ERROR_HOLD_CONFIDENCE_ELSE: &str =
+    "hold confidence expects a goto after else. Example: hold confidence 0.8 else goto clarify";
+pub const ERROR_HOLD_CONFIDENCE_TOO_LOW: &str =
+    "the confidence of the event is below the hold threshold";
+pub const ERROR_HOLD_CONFIDENCE_INVALID: &str =
+    "the confidence of the event must be a number between 0 and 1";
 pub const ERROR_INVALID_FLOW: &str = "invalid flow: ";
+pub const ERROR_COMPILED_FLOW: &str = "invalid compiled flow";
+pub const ERROR_COMPILED_FLOW_VERSION: &str =
+    "the compiled flow was compiled with another version of the ast";
 pub const ERROR_START_INSTRUCTIONS: &str =
     "to start an action one of the following instructions is expected: [say, do, if, foreach, goto]";
 pub const ERROR_FOREACH: &str =
-    "foreach only accepts iterable elements like arrays and strings. Example: foreach(elem) in [1, 2, 3]";
+    "foreach only accepts iterable elements like arrays, strings and objects. Example: foreach(elem) in [1, 2, 3]";
+pub const ERROR_FOREACH_RANGE: &str =
+    "range bounds must be of type int. Example: foreach(i) in 0..10";
 pub const ERROR_FIND_BY_INDEX: &str =
     "index must be of type int or string. Example var.[42] or var.[\"key\"]";
 pub const ERROR_ASSIGN_IDENT: &str = "key must be of type identifier";
@@ -70,6 +137,13 @@ pub const ERROR_PAYLOAD_EXCEED_MAX_SIZE: &str = "payload exceeds max payload siz
 
 pub const ERROR_STEP_LIMIT: &str =
     "[Infinite loop] Step limit reached: 100 steps where executed in a single run";
+pub const ERROR_GOTO_LOOP: &str = "[Infinite loop] Goto loop detected";
+pub const ERROR_WHILE_LIMIT: &str =
+    "[Infinite loop] While limit reached: 10000 iterations where executed in a single run";
+pub const ERROR_INSTRUCTION_LIMIT: &str =
+    "[Execution budget exceeded] Instruction limit reached, the run is limited to";
+pub const ERROR_TIME_LIMIT: &str =
+    "[Execution budget exceeded] Time limit reached, the run is limited to";
 
 // Event
 pub const ERROR_EVENT_CONTENT_TYPE: &str = "event can only be of ContentType::Event";
@@ -100,6 +174,12 @@ pub const ERROR_FIND_MEMORY: &str = "is used before it was saved in memory";
 
 // ### Functions
 pub const ERROR_FN_ARGS: &str = "function arguments are not valid";
+pub const ERROR_FN_DEFAULT_ARGS: &str =
+    "function arguments with a default value must come after the required arguments. Example: 'fn greet(name, greeting = \"Hello\"):'";
+pub const ERROR_FN_NAMED_ARGS: &str =
+    "named arguments must come after the positional arguments. Example: 'greet(\"Bob\", greeting = \"Hi\")'";
+pub const ERROR_FN_DUPLICATE_ARG: &str = "function argument is given more than once";
+pub const ERROR_FN_UNKNOWN_ARG: &str = "function has no argument named";
 pub const ERROR_FN_COLON: &str =
     "Expecting ':' at the end of function prototype. Example: 'fn name():' ";
 
@@ -121,10 +201,26 @@ pub const ERROR_ONE_OF: &str =
     "OneOf builtin expects one value of type Array. Example: OneOf( [1, 2, 3] )";
 pub const ERROR_VAR_EXISTS: &str =
     "Exists builtin expects one value of type String. Example: Exists( \"var_name\" )";
+pub const ERROR_NATIVE_FUNCTION: &str = "native function failed";
+pub const ERROR_NATIVE_FUNCTION_ARGS: &str =
+    "native functions expect arguments given by position. Example: reverse(\"text\")";
+pub const ERROR_NATIVE_FUNCTION_COLLISION: &str = "function collides with the native function";
+pub const ERROR_COALESCE_MEMORY: &str =
+    "coalesce_memory expects one value of type String. Example: coalesce_memory(\"var_name\")";
 pub const ERROR_SHUFFLE: &str =
     "Shuffle builtin expects one value of type Array. Example: Shuffle( [1, 2, 3] )";
 pub const ERROR_LENGTH: &str =
-    "Length builtin expects one value of type Array or String. Example: Length( value )";
+    "Length builtin expects one value of type Array, String, Object or Null. Example: Length( value )";
+pub const ERROR_GET_PATH: &str = "get_path builtin expects a value and a path of type String. Example: get_path(response, \"data.items[0].id\")";
+pub const ERROR_GET_PATH_SYNTAX: &str = "get_path builtin got a malformed path";
+pub const ERROR_RANDOM_RANGE: &str =
+    "random builtin expects two values of type Int or Float, min and max. Example: random(1, 6)";
+pub const ERROR_RANDOM_RANGE_ORDER: &str =
+    "random builtin expects min to be lower than or equal to max";
+pub const ERROR_SHUFFLE_ARRAY: &str =
+    "shuffle builtin expects one value of type Array. Example: shuffle( [1, 2, 3] )";
+pub const ERROR_SAMPLE: &str =
+    "sample builtin expects one value of type Array. Example: sample( [1, 2, 3] )";
 pub const ERROR_FIND: &str = "Find builtin expects 'in' param to be of type String. Example: Find(value, in = \"hola\", case_sensitive = true)";
 pub const ERROR_FLOOR: &str =
     "Floor builtin expects one argument of type float. Example: Floor(4.2)";
@@ -192,6 +288,7 @@ pub const ERROR_INT_UNKNOWN_METHOD: &str = "is not a method of Int";
 
 // #### Null
 pub const ERROR_NULL_UNKNOWN_METHOD: &str = "is not a method of Null";
+pub const ERROR_NULL_MEMBER: &str = "can't be accessed on a Null value";
 
 // #### String
 pub const ERROR_STRING_DO_MATCH: &str =
@@ -200,6 +297,8 @@ pub const ERROR_STRING_APPEND: &str =
     "[append] takes one parameter of type String. Usage: string.append(\"text to append\")";
 pub const ERROR_STRING_CONTAINS: &str =
     "[contains] takes one parameter of type String. Usage: string.contains(\"word\")";
+pub const ERROR_STRING_EQ_IGNORE_CASE: &str =
+    "[eq_ignore_case] takes one parameter of type String. Usage: string.eq_ignore_case(\"Hello\")";
 pub const ERROR_STRING_REPLACE: &str =
     "[replace] takes tow parameter of type String. Usage: \"this is old\".replace(\"old\", \"new\")";
 pub const ERROR_STRING_REPLACE_ALL: &str =
@@ -254,6 +353,8 @@ pub const ERROR_ARRAY_JOIN: &str =
     "[join] takes one parameter of type String. Usage: array.join(\"elem\") ";
 pub const ERROR_ARRAY_INDEX_OF: &str =
     "[index_of] takes one parameter. Usage: array.index_of(elem)";
+pub const ERROR_ARRAY_REDUCE_EMPTY: &str =
+    "[reduce] Cannot reduce an empty array without an initial value. Usage: array.reduce(initial, fn)";
 pub const ERROR_ARRAY_FIND: &str = "[find] takes one parameter. Usage: array.find(elem)";
 pub const ERROR_ARRAY_UNKNOWN_METHOD: &str = "is not a method of Array";
 
@@ -304,6 +405,7 @@ pub const ERROR_HTTP_UNKNOWN_METHOD: &str = "is not a method of HTTP";
 // #### OBJECT
 pub const ERROR_OBJECT_TYPE: &str = "value must be of type Object";
 pub const ERROR_OBJECT_GET: &str = "key does not exist";
+pub const ERROR_OBJECT_SET: &str = "can't be set on a value that is not an Object";
 pub const ERROR_OBJECT_CONTAINS: &str =
     "[contains] takes one argument of type String. Usage: object.contains(\"key\")";
 pub const ERROR_OBJECT_GET_GENERICS: &str =
@@ -326,6 +428,8 @@ pub const ERROR_OPS_DIV_FLOAT: &str = "[!] Float: Division by zero";
 
 pub const ERROR_ILLEGAL_OPERATION: &str = "illegal operation:";
 pub const OVERFLOWING_OPERATION: &str = "overflowing operation:";
+pub const ERROR_OPS_BITWISE: &str = "[!] Ops: bitwise operators only take int operands";
+pub const ERROR_OPS_SHIFT_COUNT: &str = "[!] Ops: shift count must be between 0 and 63";
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRiVTE FUNCTION
@@ -412,6 +516,21 @@ where
     ))
 }
 
+/**
+ * Failure reported on `span` even when the parsers around it fail as well, instead of
+ * the start of the enclosing item (see `CustomError::keep_position`)
+ */
+pub fn gen_nom_failure_in_place<'a, E>(span: Span<'a>, error: &'static str) -> Err<E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    Err::Failure(E::add_context(
+        span,
+        error,
+        E::from_error_kind(span, KEEP_POSITION_ERROR_KIND),
+    ))
+}
+
 pub fn convert_error_from_span<'a>(flow_slice: Span<'a>, e: CustomError<Span<'a>>) -> String {
     let message = e.error.to_owned();
     let offset = e.input.location_offset();
@@ -1,27 +1,41 @@
 use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
 
+// kind of the errors created by gen_nom_failure_in_place, no parser fails with it otherwise
+pub const KEEP_POSITION_ERROR_KIND: ErrorKind = ErrorKind::Fail;
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct CustomError<I> {
     pub input: I,
     pub end: Option<I>,
     pub error: String,
+    // the error is not moved to the input of the parsers failing around it
+    pub keep_position: bool,
 }
 
 impl<I: std::fmt::Display> ParseError<I> for CustomError<I> {
     //TODO: update this in nom 6
-    fn from_error_kind(input: I, _kind: ErrorKind) -> Self {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
         CustomError {
             input,
             end: None,
             error: "".to_owned(),
+            keep_position: kind == KEEP_POSITION_ERROR_KIND,
         }
     }
 
     fn append(input: I, _kind: ErrorKind, other: Self) -> Self {
+        // for instance an unterminated comment hides the rest of the flow, the error stays
+        // on the opening '/*', unbalanced braces and invalid escapes are reported on the
+        // braces and the backslash instead of the string start
+        if other.keep_position {
+            return other;
+        }
+
         Self {
             input: input,
             end: Some(other.input),
             error: other.error,
+            keep_position: false,
         }
     }
 }
@@ -46,6 +60,7 @@ impl<I, E> FromExternalError<I, E> for CustomError<I> {
             input,
             end: None,
             error: "".to_owned(),
+            keep_position: false,
         }
     }
 }
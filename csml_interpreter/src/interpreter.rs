@@ -1,7 +1,9 @@
 pub mod ast_interpreter;
 pub mod builtins;
 pub mod components;
+pub mod event_coercion;
 pub mod function_scope;
+pub mod json_schema;
 pub mod json_to_rust;
 pub mod variable_handler;
 
@@ -14,7 +16,12 @@ use crate::data::{
 };
 use crate::error_format::*;
 use crate::interpreter::{
-    ast_interpreter::{for_loop, match_actions, solve_if_statement, while_loop},
+    ast_interpreter::{
+        for_loop, match_actions, solve_if_statement, solve_match_statement, solve_try_catch,
+        while_loop,
+    },
+    event_coercion::coerce_event,
+    json_schema::validate_json_schema,
     variable_handler::{expr_to_literal, interval::interval_from_expr},
 };
 use crate::parser::ExitCondition;
@@ -37,10 +44,268 @@ fn step_vars_to_json(map: HashMap<String, Literal>) -> serde_json::Value {
     serde_json::json!(json_map)
 }
 
+fn hold_conversation(
+    secure: bool,
+    wake_at: Option<String>,
+    instruction_info: &InstructionInfo,
+    data: &mut Data,
+    message_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) {
+    let index = instruction_info.index;
+    let map = data.step_vars.to_owned();
+
+    let mut hold = Hold::new(
+        IndexInfo {
+            command_index: index,
+            loop_index: data.loop_indexes.clone(),
+        },
+        step_vars_to_json(map),
+        data.context.step.get_step(),
+        data.context.flow.clone(),
+        data.previous_info.clone(),
+        secure,
+    );
+    hold.wake_at = wake_at;
+
+    message_data.hold = Some(hold.to_owned());
+
+    MSG::send(&sender, MSG::Hold(hold));
+    message_data.exit_condition = Some(ExitCondition::Hold);
+}
+
+// hold the conversation again on the hold that resumed the step with the current step
+// variables, return false once the retries are used up so that the step goes on
+fn retry_conversation(
+    max: &Option<usize>,
+    data: &mut Data,
+    message_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> bool {
+    let resumed = match &data.resumed_hold {
+        Some(hold) if !matches!(max, Some(max) if hold.retries >= *max) => hold,
+        _ => return false,
+    };
+
+    let mut hold = Hold::new(
+        resumed.index.clone(),
+        step_vars_to_json(data.step_vars.to_owned()),
+        data.context.step.get_step(),
+        data.context.flow.clone(),
+        data.previous_info.clone(),
+        resumed.secure,
+    );
+    hold.retries = resumed.retries + 1;
+
+    message_data.hold = Some(hold.to_owned());
+
+    MSG::send(&sender, MSG::Hold(hold));
+    message_data.exit_condition = Some(ExitCondition::Hold);
+    true
+}
+
+// date at which a delay of the duration ends, in the same format as the engine dates
+fn get_wake_at(duration: &Literal, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    if duration.content_type != "duration" {
+        return None;
+    }
+
+    let milliseconds = duration
+        .primitive
+        .get_value()
+        .downcast_ref::<HashMap<String, Literal>>()?
+        .get("milliseconds")?
+        .primitive
+        .get_value()
+        .downcast_ref::<i64>()
+        .copied()
+        .filter(|milliseconds| *milliseconds >= 0)?;
+    let wake_at = now.checked_add_signed(chrono::Duration::milliseconds(milliseconds))?;
+
+    Some(wake_at.format("%Y-%m-%dT%H:%M:%S.%3fZ").to_string())
+}
+
+// a delay without a readable wake date does not keep the conversation on hold
+fn is_delay_over(wake_at: &Option<String>, now: chrono::DateTime<chrono::Utc>) -> bool {
+    match wake_at
+        .as_ref()
+        .and_then(|wake_at| chrono::DateTime::parse_from_rfc3339(wake_at).ok())
+    {
+        Some(wake_at) => now >= wake_at,
+        None => true,
+    }
+}
+
+fn delay_conversation(
+    duration: &Expr,
+    interval: Interval,
+    instruction_info: &InstructionInfo,
+    data: &mut Data,
+    message_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<(), ErrorInfo> {
+    let duration = expr_to_literal(
+        duration,
+        &DisplayWarnings::On,
+        None,
+        data,
+        message_data,
+        sender,
+    )?;
+
+    match get_wake_at(&duration, data.context.get_now()) {
+        Some(wake_at) => {
+            hold_conversation(
+                false,
+                Some(wake_at),
+                instruction_info,
+                data,
+                message_data,
+                sender,
+            );
+        }
+        None => {
+            let err = gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_DELAY.to_owned(),
+            );
+            MSG::send_error_msg(sender, message_data, Err(err));
+        }
+    }
+
+    Ok(())
+}
+
+// the schema is evaluated when the conversation resumes, so it can be built from the step variables
+fn validate_event(
+    schema: &Expr,
+    interval: Interval,
+    data: &mut Data,
+    message_data: &mut MessageData,
+) -> Result<Option<ErrorInfo>, ErrorInfo> {
+    let schema = expr_to_literal(
+        schema,
+        &DisplayWarnings::On,
+        None,
+        data,
+        message_data,
+        &None,
+    )?;
+    let errors = validate_json_schema(&data.event.content, &schema.primitive.to_json());
+
+    if errors.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(gen_error_info(
+        Position::new(interval, &data.context.flow),
+        format!("{}: {}", ERROR_HOLD_SCHEMA_MISMATCH, errors.join(", ")),
+    )))
+}
+
+// the coerced event is the value of 'event' in the rest of the run
+fn coerce_hold_event(
+    expected: ExpectedType,
+    interval: Interval,
+    data: &mut Data,
+) -> Option<ErrorInfo> {
+    match coerce_event(expected, data.event, interval) {
+        Ok(literal) => {
+            data.event_value = Some(literal);
+            None
+        }
+        Err(reason) => Some(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!("{}: {}", ERROR_HOLD_EXPECT_MISMATCH, reason),
+        )),
+    }
+}
+
+// confidence of the event given by the NLU in its content, the events without one have
+// the hold_confidence_default of the event: 0 when it is not set, 1 lets them through
+fn get_event_confidence(interval: Interval, data: &Data) -> Result<f64, ErrorInfo> {
+    let confidence = match data.event.content.get("confidence") {
+        Some(confidence) => confidence.as_f64(),
+        None => Some(data.event.hold_confidence_default.unwrap_or(0.0)),
+    };
+
+    match confidence {
+        Some(confidence) if (0.0..=1.0).contains(&confidence) => Ok(confidence),
+        _ => Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            ERROR_HOLD_CONFIDENCE_INVALID.to_owned(),
+        )),
+    }
+}
+
+// the confidence of the event, given by the NLU in its content, reaches the threshold
+fn check_event_confidence(
+    threshold: &Expr,
+    interval: Interval,
+    data: &mut Data,
+    message_data: &mut MessageData,
+) -> Result<Option<ErrorInfo>, ErrorInfo> {
+    let threshold = expr_to_literal(
+        threshold,
+        &DisplayWarnings::On,
+        None,
+        data,
+        message_data,
+        &None,
+    )?;
+    let threshold = match threshold.primitive.to_json().as_f64() {
+        Some(threshold) if (0.0..=1.0).contains(&threshold) => threshold,
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_HOLD_CONFIDENCE.to_owned(),
+            ))
+        }
+    };
+
+    let confidence = get_event_confidence(interval, data)?;
+
+    if confidence >= threshold {
+        return Ok(None);
+    }
+
+    Ok(Some(gen_error_info(
+        Position::new(interval, &data.context.flow),
+        format!(
+            "{}: {} < {}",
+            ERROR_HOLD_CONFIDENCE_TOO_LOW, confidence, threshold
+        ),
+    )))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+// count the instruction in the execution budget of the run, once the budget is
+// exceeded the error is sent and the current scope stops with an error exit condition
+pub fn consume_budget(
+    action: &Expr,
+    data: &Data,
+    message_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> bool {
+    match data.budget.consume() {
+        Ok(()) => false,
+        Err(message) => {
+            let err = gen_error_info(
+                Position::new(interval_from_expr(action), &data.context.flow),
+                message,
+            )
+            .with_code(ErrorCode::ExecutionBudgetExceeded);
+
+            MSG::send_error_msg(sender, message_data, Err(err));
+            message_data.exit_condition = Some(ExitCondition::Error);
+            true
+        }
+    }
+}
+
 pub fn interpret_scope(
     actions: &Block,
     data: &mut Data,
@@ -55,8 +320,80 @@ pub fn interpret_scope(
             if hold.index.command_index > instruction_total {
                 continue;
             } else if hold.index.command_index == instruction_info.index {
-                data.context.hold = None;
-                continue; // this command is the hold, we need to skip it in order to continue the conversation
+                // loops and if statements share their index with their first command,
+                // in that case the hold is inside the block and will be skipped there
+                if let Expr::ObjectExpr(..) = action {
+                    let wake_at = hold.wake_at.take();
+                    let resumed_hold = data.context.hold.take();
+
+                    // resumed before the end of the delay, the conversation stays on hold
+                    if let Expr::ObjectExpr(ObjectType::Delay(..)) = action {
+                        if !is_delay_over(&wake_at, data.context.get_now()) {
+                            hold_conversation(
+                                false,
+                                wake_at,
+                                instruction_info,
+                                data,
+                                &mut message_data,
+                                sender,
+                            );
+                            return Ok(message_data);
+                        }
+                    }
+
+                    // the conversation stays on hold until the event matches the schema,
+                    // can be coerced to the expected type or is confident enough
+                    let (error, secure) = match action {
+                        Expr::ObjectExpr(ObjectType::HoldSchema(schema, secure, interval)) => (
+                            validate_event(schema, *interval, data, &mut message_data)?,
+                            *secure,
+                        ),
+                        Expr::ObjectExpr(ObjectType::HoldExpect(expected, secure, interval)) => {
+                            (coerce_hold_event(*expected, *interval, data), *secure)
+                        }
+                        Expr::ObjectExpr(ObjectType::HoldConfidence(
+                            threshold,
+                            else_goto,
+                            secure,
+                            interval,
+                        )) => {
+                            let error = check_event_confidence(
+                                threshold,
+                                *interval,
+                                data,
+                                &mut message_data,
+                            )?;
+
+                            // the less confident events go to the else goto instead
+                            if let (Some(_), Some(else_goto)) = (&error, else_goto) {
+                                return Ok(message_data + interpret_scope(else_goto, data, sender)?);
+                            }
+
+                            (error, *secure)
+                        }
+                        _ => (None, false),
+                    };
+
+                    if let Some(err) = error {
+                        MSG::send_error_msg(sender, &mut message_data, Err(err));
+                        hold_conversation(
+                            secure,
+                            None,
+                            instruction_info,
+                            data,
+                            &mut message_data,
+                            sender,
+                        );
+                        return Ok(message_data);
+                    }
+
+                    // a delay is over once resumed, only the holds can be retried
+                    if !matches!(action, Expr::ObjectExpr(ObjectType::Delay(..))) {
+                        data.resumed_hold = resumed_hold;
+                    }
+
+                    continue; // this command is the hold, we need to skip it in order to continue the conversation
+                }
             }
         }
 
@@ -64,6 +401,12 @@ pub fn interpret_scope(
             return Ok(message_data);
         }
 
+        // commands replayed to reach the hold are not counted, only the new ones
+        if data.context.hold.is_none() && consume_budget(action, data, &mut message_data, sender)
+        {
+            return Ok(message_data);
+        }
+
         match action {
             Expr::ObjectExpr(ObjectType::Return(var)) => {
                 let lit = expr_to_literal(
@@ -78,78 +421,113 @@ pub fn interpret_scope(
 
                 return Ok(message_data);
             }
-            Expr::ObjectExpr(ObjectType::Break(..)) => {
-                message_data.exit_condition = Some(ExitCondition::Break);
+            Expr::ObjectExpr(ObjectType::Break(label, interval)) => {
+                // every loop push its index in the stack, an empty stack means we are not in a loop
+                if data.loop_indexes.is_empty() {
+                    let err = gen_error_info(
+                        Position::new(*interval, &data.context.flow),
+                        ERROR_BREAK.to_owned(),
+                    );
+
+                    MSG::send_error_msg(&sender, &mut message_data, Err(err));
+                    message_data.exit_condition = Some(ExitCondition::Error);
+                    return Ok(message_data);
+                }
+
+                message_data.exit_condition =
+                    Some(ExitCondition::Break(label.as_ref().map(|l| l.ident.to_owned())));
 
                 return Ok(message_data);
             }
-            Expr::ObjectExpr(ObjectType::Continue(..)) => {
-                message_data.exit_condition = Some(ExitCondition::Continue);
+            Expr::ObjectExpr(ObjectType::Continue(label, interval)) => {
+                if data.loop_indexes.is_empty() {
+                    let err = gen_error_info(
+                        Position::new(*interval, &data.context.flow),
+                        ERROR_CONTINUE.to_owned(),
+                    );
+
+                    MSG::send_error_msg(&sender, &mut message_data, Err(err));
+                    message_data.exit_condition = Some(ExitCondition::Error);
+                    return Ok(message_data);
+                }
+
+                message_data.exit_condition = Some(ExitCondition::Continue(
+                    label.as_ref().map(|l| l.ident.to_owned()),
+                ));
 
                 return Ok(message_data);
             }
             Expr::ObjectExpr(ObjectType::Hold(..)) => {
-                let index = instruction_info.index;
-                let map = data.step_vars.to_owned();
-
-                let hold = Hold::new(
-                    IndexInfo {
-                        command_index: index,
-                        loop_index: data.loop_indexes.clone(),
-                    },
-                    step_vars_to_json(map),
-                    data.context.step.get_step(),
-                    data.context.flow.clone(),
-                    data.previous_info.clone(),
+                hold_conversation(
                     false,
+                    None,
+                    instruction_info,
+                    data,
+                    &mut message_data,
+                    sender,
                 );
-
-                message_data.hold = Some(hold.to_owned());
-
-                MSG::send(&sender, MSG::Hold(hold));
-                message_data.exit_condition = Some(ExitCondition::Hold);
                 return Ok(message_data);
             }
             Expr::ObjectExpr(ObjectType::HoldSecure(..)) => {
-                let index = instruction_info.index;
-                let map = data.step_vars.to_owned();
-
-                let hold = Hold::new(
-                    IndexInfo {
-                        command_index: index,
-                        loop_index: data.loop_indexes.clone(),
-                    },
-                    step_vars_to_json(map),
-                    data.context.step.get_step(),
-                    data.context.flow.clone(),
-                    data.previous_info.clone(),
+                hold_conversation(
                     true,
+                    None,
+                    instruction_info,
+                    data,
+                    &mut message_data,
+                    sender,
                 );
-
-                message_data.hold = Some(hold.to_owned());
-
-                MSG::send(&sender, MSG::Hold(hold));
-                message_data.exit_condition = Some(ExitCondition::Hold);
                 return Ok(message_data);
             }
-            Expr::ObjectExpr(fun) => {
-                message_data = match_actions(fun, message_data, data, &sender)?
-            }
-            Expr::IfExpr(ref if_statement) => {
-                message_data = solve_if_statement(
-                    if_statement,
-                    message_data,
+            Expr::ObjectExpr(ObjectType::HoldSchema(_, secure, _))
+            | Expr::ObjectExpr(ObjectType::HoldExpect(_, secure, _))
+            | Expr::ObjectExpr(ObjectType::HoldConfidence(_, _, secure, _)) => {
+                hold_conversation(
+                    *secure,
+                    None,
+                    instruction_info,
                     data,
+                    &mut message_data,
+                    sender,
+                );
+                return Ok(message_data);
+            }
+            Expr::ObjectExpr(ObjectType::Retry(max, _)) => {
+                if retry_conversation(max, data, &mut message_data, sender) {
+                    return Ok(message_data);
+                }
+            }
+            Expr::ObjectExpr(ObjectType::Delay(duration, interval)) => {
+                delay_conversation(
+                    duration,
+                    *interval,
                     instruction_info,
-                    &sender,
+                    data,
+                    &mut message_data,
+                    sender,
                 )?;
+                if message_data.exit_condition.is_some() {
+                    return Ok(message_data);
+                }
+            }
+            Expr::ObjectExpr(fun) => {
+                message_data = match_actions(fun, message_data, data, &sender)?
             }
-            Expr::ForEachExpr(ident, index, expr, block, range) => {
+            Expr::IfExpr {
+                branches,
+                else_body,
+                ..
+            } => {
+                message_data =
+                    solve_if_statement(branches, else_body, message_data, data, &sender)?;
+            }
+            Expr::ForEachExpr(ident, index, expr, block, label, range) => {
                 message_data = for_loop(
                     ident,
                     index,
                     expr,
                     block,
+                    label,
                     range,
                     message_data,
                     data,
@@ -159,6 +537,13 @@ pub fn interpret_scope(
             Expr::WhileExpr(expr, block, range) => {
                 message_data = while_loop(expr, block, range, message_data, data, &sender)?
             }
+            Expr::MatchExpr(subject, arms, _range) => {
+                message_data = solve_match_statement(subject, arms, message_data, data, &sender)?
+            }
+            Expr::TryCatchExpr(try_block, ident, catch_block, _range) => {
+                message_data =
+                    solve_try_catch(try_block, ident, catch_block, message_data, data, &sender)?
+            }
             e => {
                 return Err(gen_error_info(
                     Position::new(interval_from_expr(e), &data.context.flow),
@@ -166,6 +551,12 @@ pub fn interpret_scope(
                 ));
             }
         };
+
+        // inside a try block the first runtime error stops the block, the catch block handles it
+        if data.in_try_block && message_data.error.is_some() {
+            message_data.exit_condition = Some(ExitCondition::Error);
+            return Ok(message_data);
+        }
     }
 
     Ok(message_data)
@@ -1,9 +1,15 @@
 mod actions;
+mod destructure;
 mod for_loop;
 mod if_statement;
+mod match_statement;
+mod try_catch;
 mod while_loop;
 
 pub use actions::match_actions;
+pub use destructure::destructure;
 pub use for_loop::for_loop;
-pub use if_statement::{evaluate_condition, solve_if_statement};
+pub use if_statement::{evaluate_condition, solve_if_statement, valid_condition};
+pub use match_statement::solve_match_statement;
+pub use try_catch::solve_try_catch;
 pub use while_loop::while_loop;
@@ -1,3 +1,4 @@
+use crate::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 use crate::data::data::PreviousInfo;
 use crate::data::position::Position;
 use crate::data::warnings::DisplayWarnings;
@@ -6,11 +7,13 @@ use crate::data::{
     context::ContextStepInfo,
     data::Data,
     literal::ContentType,
+    memories::{is_sensitive_literal, set_sensitive_literal},
     message::*,
     primitive::{closure::capture_variables, PrimitiveNull, PrimitiveString},
     Literal, Memory, MemoryType, MessageData, MSG,
 };
 use crate::error_format::*;
+use crate::interpreter::ast_interpreter::destructure;
 use crate::interpreter::variable_handler::{
     exec_path_actions, expr_to_literal,
     forget_memories::{forget_scope_memories, remove_message_data_memories},
@@ -21,6 +24,7 @@ use crate::interpreter::variable_handler::{
 };
 use crate::parser::ExitCondition;
 use std::collections::HashMap;
+use std::env;
 use std::sync::mpsc;
 
 fn get_var_info<'a>(
@@ -91,6 +95,33 @@ fn check_if_inserted_step<'a>(name: &str, interval: &Interval, data: &'a Data) -
     }
 }
 
+// assertions are checked in test environments only, set CSML_ASSERTIONS=true to enable them
+fn assertions_enabled() -> bool {
+    match env::var("CSML_ASSERTIONS") {
+        Ok(value) => value.parse::<bool>().unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+// the observer runs before the message is sent, its errors are only logged
+fn observe_message(message: &Message, interval: &Interval, data: &Data) {
+    if let Some(observer) = data.message_observer {
+        let client = data.context.api_info.as_ref().map(|info| &info.client);
+
+        if let Err(err) = observer.on_message(client, interval, message) {
+            csml_logger(
+                CsmlLog::new(
+                    client,
+                    Some(data.context.flow.to_owned()),
+                    Some(interval.start_line),
+                    format!("message observer error: {}", err),
+                ),
+                LogLvl::Error,
+            );
+        }
+    }
+}
+
 pub fn match_actions(
     function: &ObjectType,
     mut msg_data: MessageData,
@@ -112,7 +143,9 @@ pub fn match_actions(
                 MSG::send_error_msg(&sender, &mut msg_data, Err(err));
                 Ok(msg_data)
             } else {
+                let interval = interval_from_expr(arg);
                 let msg = Message::new(lit, &data.context.flow)?;
+                observe_message(&msg, &interval, data);
                 MSG::send(&sender, MSG::Message(msg.clone()));
                 Ok(Message::add_to_message(msg_data, MessageType::Msg(msg)))
             }
@@ -133,6 +166,7 @@ pub fn match_actions(
                 Ok(msg_data)
             } else {
                 let msg = Message::new(lit, &data.context.flow)?;
+                observe_message(&msg, interval, data);
                 MSG::send(&sender, MSG::Message(msg.clone()));
                 Ok(Message::add_to_message(msg_data, MessageType::Msg(msg)))
             }
@@ -157,6 +191,47 @@ pub fn match_actions(
 
             Ok(msg_data)
         }
+        ObjectType::Assert(condition, message, interval) => {
+            // disabled assertions are not evaluated
+            if !assertions_enabled() {
+                return Ok(msg_data);
+            }
+
+            let lit = expr_to_literal(
+                condition,
+                &DisplayWarnings::On,
+                None,
+                data,
+                &mut msg_data,
+                sender,
+            )?;
+            if lit.primitive.as_bool() {
+                return Ok(msg_data);
+            }
+
+            let message = match message {
+                Some(message) => {
+                    let message = expr_to_literal(
+                        message,
+                        &DisplayWarnings::On,
+                        None,
+                        data,
+                        &mut msg_data,
+                        sender,
+                    )?;
+                    format!("{}: {}", ERROR_ASSERT, message.primitive.to_string())
+                }
+                None => ERROR_ASSERT.to_owned(),
+            };
+
+            // the step stops on the error, unless it is caught by a try block
+            let err = gen_error_info(Position::new(*interval, &data.context.flow), message)
+                .with_code(ErrorCode::AssertionFailed);
+            MSG::send_error_msg(sender, &mut msg_data, Err(err));
+            msg_data.exit_condition = Some(ExitCondition::Error);
+
+            Ok(msg_data)
+        }
         ObjectType::Use(arg) => {
             expr_to_literal(arg, &DisplayWarnings::On, None, data, &mut msg_data, sender)?;
             Ok(msg_data)
@@ -183,11 +258,15 @@ pub fn match_actions(
                 tmp_default_flow,
                 &mut tmp_context,
                 &tmp_event,
+                data.event_value.clone(),
                 &tmp_env,
                 tmp_loop_indexes,
                 tmp_loop_index,
                 &mut tmp_step_count,
                 tmp_step_limit,
+                data.budget,
+                data.random,
+                data.message_observer,
                 tmp_step_vars,
                 data.previous_info.clone(),
                 data.custom_component,
@@ -215,21 +294,41 @@ pub fn match_actions(
 
             let (lit, name, mem_type, path) = get_var_info(old, None, data, &mut msg_data, sender)?;
 
+            // a compound assignment applies to the value at the path, not to the whole variable
+            let current = match (assign_type, &path) {
+                (AssignType::Assignment, _) | (_, None) => None,
+                (_, Some(_)) => Some(
+                    exec_path_actions(
+                        lit,
+                        &DisplayWarnings::On,
+                        &mem_type,
+                        None,
+                        &path,
+                        &ContentType::get(lit),
+                        &mut new_scope_data,
+                        &mut msg_data,
+                        sender,
+                    )?
+                    .0,
+                ),
+            };
+            let current = current.as_ref().unwrap_or(&*lit);
+
             let primitive = match assign_type {
                 AssignType::AdditionAssignment => {
-                    Some(lit.primitive.clone() + new_value.primitive.clone())
+                    Some(current.primitive.clone() + new_value.primitive.clone())
                 }
                 AssignType::SubtractionAssignment => {
-                    Some(lit.primitive.clone() - new_value.primitive.clone())
+                    Some(current.primitive.clone() - new_value.primitive.clone())
                 }
                 AssignType::DivisionAssignment => {
-                    Some(lit.primitive.clone() / new_value.primitive.clone())
+                    Some(current.primitive.clone() / new_value.primitive.clone())
                 }
                 AssignType::MultiplicationAssignment => {
-                    Some(lit.primitive.clone() * new_value.primitive.clone())
+                    Some(current.primitive.clone() * new_value.primitive.clone())
                 }
                 AssignType::RemainderAssignment => {
-                    Some(lit.primitive.clone() % new_value.primitive.clone())
+                    Some(current.primitive.clone() % new_value.primitive.clone())
                 }
                 AssignType::Assignment => None,
             };
@@ -300,6 +399,10 @@ pub fn match_actions(
             Ok(msg_data)
         }
         ObjectType::Do(DoType::Exec(expr)) => {
+            if let Expr::Destructure(pattern, value, _) = &**expr {
+                return destructure(pattern, value, msg_data, data, sender);
+            }
+
             expr_to_literal(
                 expr,
                 &DisplayWarnings::On,
@@ -540,7 +643,9 @@ pub fn match_actions(
 
             Ok(msg_data)
         }
-        ObjectType::Remember(name, variable) => {
+        ObjectType::Remember(name, variable)
+        | ObjectType::RememberConversation(name, variable)
+        | ObjectType::RememberSecret(name, variable) => {
             let mut new_value = expr_to_literal(
                 variable,
                 &DisplayWarnings::On,
@@ -565,16 +670,38 @@ pub fn match_actions(
             let memory: HashMap<String, Literal> = data.get_all_memories();
             capture_variables(&mut &mut new_value, memory, &data.context.flow);
 
-            msg_data.add_to_memory(&name.ident, new_value.clone());
+            let is_conversation = matches!(function, ObjectType::RememberConversation(..));
+            let scope = if is_conversation {
+                &data.context.conversation
+            } else {
+                &data.context.current
+            };
 
-            MSG::send(
-                &sender,
-                MSG::Remember(Memory::new(name.ident.to_owned(), new_value.clone())),
-            );
+            // a sensitive memory stays sensitive when it is remembered again in its scope
+            let sensitive = match scope.get(&name.ident) {
+                Some(current) => is_sensitive_literal(current),
+                None => false,
+            };
+            if sensitive || matches!(function, ObjectType::RememberSecret(..)) {
+                set_sensitive_literal(&mut new_value, name.interval);
+            }
+
+            let memory = Memory::new(name.ident.to_owned(), new_value.clone());
 
-            data.context
-                .current
-                .insert(name.ident.to_owned(), new_value);
+            // the memories of the conversation are not part of the memories of the user,
+            // remember always saves the memory for the user
+            if is_conversation {
+                MSG::send(&sender, MSG::RememberConversation(memory));
+                data.context
+                    .conversation
+                    .insert(name.ident.to_owned(), new_value);
+            } else {
+                msg_data.add_to_memory(&name.ident, new_value.clone());
+                MSG::send(&sender, MSG::Remember(memory));
+                data.context
+                    .current
+                    .insert(name.ident.to_owned(), new_value);
+            }
             Ok(msg_data)
         }
         ObjectType::Forget(memory, _interval) => {
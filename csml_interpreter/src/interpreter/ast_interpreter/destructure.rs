@@ -0,0 +1,161 @@
+use crate::data::position::Position;
+use crate::data::primitive::{PrimitiveNull, PrimitiveType};
+use crate::data::{ast::*, warnings::DisplayWarnings, Data, Literal, MemoryType, MessageData, MSG};
+use crate::error_format::*;
+use crate::interpreter::variable_handler::{
+    expr_to_literal,
+    memory::{save_literal_in_mem, search_in_memory_type},
+};
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn bind_variable(
+    ident: &Identifier,
+    mut value: Literal,
+    data: &mut Data,
+    msg_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) {
+    value.interval = ident.interval;
+
+    let mem_type = match search_in_memory_type(ident, data) {
+        Ok(mem_type) if mem_type == "constant" => {
+            let err = gen_error_info(
+                Position::new(ident.interval, &data.context.flow),
+                format!("const variables are immutable"),
+            );
+
+            MSG::send_error_msg(sender, msg_data, Err(err));
+            return;
+        }
+        Ok(mem_type) if mem_type == "remember" => MemoryType::Remember,
+        _ => MemoryType::Use,
+    };
+
+    save_literal_in_mem(
+        value,
+        ident.ident.to_owned(),
+        &mem_type,
+        true,
+        data,
+        msg_data,
+        sender,
+    );
+}
+
+fn destructure_pattern(
+    pattern: &Pattern,
+    value: Literal,
+    data: &mut Data,
+    msg_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<(), ErrorInfo> {
+    match pattern {
+        Pattern::Ident(ident) => {
+            bind_variable(ident, value, data, msg_data, sender);
+            Ok(())
+        }
+        Pattern::Object(fields, interval) => {
+            if value.primitive.get_type() != PrimitiveType::PrimitiveObject {
+                return Err(gen_error_info(
+                    Position::new(*interval, &data.context.flow),
+                    format!(
+                        "{}, got {}",
+                        ERROR_DESTRUCTURE_OBJECT,
+                        value.primitive.get_type().to_string()
+                    ),
+                ));
+            }
+
+            let object = Literal::get_value::<HashMap<String, Literal>>(
+                &value.primitive,
+                &data.context.flow,
+                value.interval,
+                ERROR_DESTRUCTURE_OBJECT.to_owned(),
+            )?;
+
+            for (key, pattern) in fields.iter() {
+                // missing keys are bound to null
+                let value = match object.get(&key.ident) {
+                    Some(value) => value.to_owned(),
+                    None => PrimitiveNull::get_literal(key.interval),
+                };
+
+                destructure_pattern(pattern, value, data, msg_data, sender)?;
+            }
+
+            Ok(())
+        }
+        Pattern::Array(items, interval) => {
+            if value.primitive.get_type() != PrimitiveType::PrimitiveArray {
+                return Err(gen_error_info(
+                    Position::new(*interval, &data.context.flow),
+                    format!(
+                        "{}, got {}",
+                        ERROR_DESTRUCTURE_ARRAY,
+                        value.primitive.get_type().to_string()
+                    ),
+                ));
+            }
+
+            let array = Literal::get_value::<Vec<Literal>>(
+                &value.primitive,
+                &data.context.flow,
+                value.interval,
+                ERROR_DESTRUCTURE_ARRAY.to_owned(),
+            )?;
+
+            for (index, pattern) in items.iter().enumerate() {
+                // missing values are bound to null
+                let value = match array.get(index) {
+                    Some(value) => value.to_owned(),
+                    None => PrimitiveNull::get_literal(*interval),
+                };
+
+                destructure_pattern(pattern, value, data, msg_data, sender)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn destructure(
+    pattern: &Pattern,
+    expr: &Expr,
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let value = expr_to_literal(
+        expr,
+        &DisplayWarnings::On,
+        None,
+        data,
+        &mut msg_data,
+        sender,
+    )?;
+
+    // check if it is secure variable
+    if value.secure_variable {
+        let err = gen_error_info(
+            Position::new(value.interval, &data.context.flow),
+            "Assignation of secure variable is not allowed".to_owned(),
+        );
+
+        MSG::send_error_msg(&sender, &mut msg_data, Err(err));
+        return Ok(msg_data);
+    }
+
+    destructure_pattern(pattern, value, data, &mut msg_data, sender)?;
+
+    Ok(msg_data)
+}
@@ -1,74 +1,294 @@
-use crate::data::primitive::PrimitiveInt;
+use crate::data::position::Position;
+use crate::data::primitive::{PrimitiveInt, PrimitiveString, PrimitiveType};
 use crate::data::{
     ast::*,
     hold::{
-        hold_index_end_loop, hold_index_start_loop, hold_loop_decrs_index, hold_loop_incrs_index,
+        hold_index_end_loop, hold_index_start_loop, hold_index_start_range,
+        hold_loop_decrs_index, hold_loop_incrs_index,
     },
     primitive::tools::get_array,
     warnings::DisplayWarnings,
-    Data, MessageData, MSG,
+    Data, Literal, MessageData, MSG,
 };
 use crate::error_format::*;
 use crate::interpreter::interpret_scope;
 use crate::interpreter::variable_handler::expr_to_literal::expr_to_literal;
-use crate::parser::ExitCondition;
+use crate::interpreter::variable_handler::interval::interval_from_expr;
+use crate::parser::{state_context::is_loop_target, ExitCondition};
+use std::collections::HashMap;
 use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
-// PUBLIC FUNCTION
+// PRIVATE FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn for_loop(
-    ident: &Identifier,
-    index: &Option<Identifier>,
+fn get_range_bound(
     expr: &Expr,
-    block: &Block,
-    _range_interval: &Interval,
-    mut msg_data: MessageData,
+    msg_data: &mut MessageData,
     data: &mut Data,
     sender: &Option<mpsc::Sender<MSG>>,
-) -> Result<MessageData, ErrorInfo> {
-    let literal = expr_to_literal(
-        expr,
-        &DisplayWarnings::On,
-        None,
-        data,
-        &mut msg_data,
-        sender,
+) -> Result<i64, ErrorInfo> {
+    let literal = expr_to_literal(expr, &DisplayWarnings::On, None, data, msg_data, sender)?;
+
+    let bound = Literal::get_value::<i64>(
+        &literal.primitive,
+        &data.context.flow,
+        literal.interval,
+        ERROR_FOREACH_RANGE.to_owned(),
     )?;
-    let mut array = get_array(literal, &data.context.flow, ERROR_FOREACH.to_owned())?;
 
-    let mut value_skipped = 0;
-    let array = hold_index_start_loop(data, &mut array, &mut value_skipped);
+    Ok(*bound)
+}
+
+// value of the index ident: the position of the element, or its key for the objects
+fn position_literal(position: usize, elem: &Literal) -> Literal {
+    PrimitiveInt::get_literal(position as i64, elem.interval.to_owned())
+}
 
-    for (for_loop_index, elem) in array.iter().enumerate() {
-        data.step_vars
-            .insert(ident.ident.to_owned(), elem.to_owned());
+fn loop_over<I, K>(
+    ident: &Identifier,
+    index: &Option<Identifier>,
+    values: I,
+    index_literal: K,
+    value_skipped: usize,
+    block: &Block,
+    label: &Option<Identifier>,
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo>
+where
+    I: Iterator<Item = Literal>,
+    K: Fn(usize, &Literal) -> Literal,
+{
+    for (for_loop_index, elem) in values.enumerate() {
         if let Some(index) = index {
             data.step_vars.insert(
                 index.ident.to_owned(),
-                PrimitiveInt::get_literal(
-                    (for_loop_index + value_skipped) as i64,
-                    elem.interval.to_owned(),
-                ),
+                index_literal(for_loop_index + value_skipped, &elem),
             );
         };
+        data.step_vars.insert(ident.ident.to_owned(), elem);
 
         hold_loop_incrs_index(data, for_loop_index + value_skipped);
         msg_data = msg_data + interpret_scope(block, data, sender)?;
         hold_loop_decrs_index(data);
 
-        match msg_data.exit_condition {
-            Some(ExitCondition::Break) => {
+        // a break or continue targeting an outer loop stops this one and goes up
+        match &msg_data.exit_condition {
+            Some(ExitCondition::Break(target)) if is_loop_target(target, label) => {
                 msg_data.exit_condition = None;
                 break;
             }
-            Some(ExitCondition::Continue) => msg_data.exit_condition = None,
+            Some(ExitCondition::Continue(target)) if is_loop_target(target, label) => {
+                msg_data.exit_condition = None
+            }
             Some(_) => break,
             None => {}
         }
     }
 
+    Ok(msg_data)
+}
+
+fn range_loop(
+    ident: &Identifier,
+    index: &Option<Identifier>,
+    start: &Expr,
+    end: &Expr,
+    inclusive: bool,
+    range_interval: &Interval,
+    block: &Block,
+    label: &Option<Identifier>,
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let start = get_range_bound(start, &mut msg_data, data, sender)?;
+    let end = get_range_bound(end, &mut msg_data, data, sender)?;
+
+    // values are generated one at a time instead of building an array,
+    // a reversed range is empty
+    let value_skipped = hold_index_start_range(data);
+    let first = start.saturating_add(value_skipped as i64);
+    let to_literal = |value| PrimitiveInt::get_literal(value, *range_interval);
+
+    if inclusive {
+        let values = (first..=end).map(to_literal);
+        loop_over(
+            ident,
+            index,
+            values,
+            position_literal,
+            value_skipped,
+            block,
+            label,
+            msg_data,
+            data,
+            sender,
+        )
+    } else {
+        let values = (first..end).map(to_literal);
+        loop_over(
+            ident,
+            index,
+            values,
+            position_literal,
+            value_skipped,
+            block,
+            label,
+            msg_data,
+            data,
+            sender,
+        )
+    }
+}
+
+fn array_loop(
+    ident: &Identifier,
+    index: &Option<Identifier>,
+    mut array: Vec<Literal>,
+    block: &Block,
+    label: &Option<Identifier>,
+    msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let mut value_skipped = 0;
+    let array = hold_index_start_loop(data, &mut array, &mut value_skipped);
+    let values = array.iter().cloned();
+
+    loop_over(
+        ident,
+        index,
+        values,
+        position_literal,
+        value_skipped,
+        block,
+        label,
+        msg_data,
+        data,
+        sender,
+    )
+}
+
+// the entries are iterated in the order of their keys, which keeps the position
+// of a hold in the loop valid when the step is resumed
+fn object_loop(
+    ident: &Identifier,
+    key: &Option<Identifier>,
+    object: &HashMap<String, Literal>,
+    block: &Block,
+    label: &Option<Identifier>,
+    msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let mut entries: Vec<(&String, &Literal)> = object.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let keys: Vec<&String> = entries.iter().map(|(key, _)| *key).collect();
+    let values: Vec<Literal> = entries
+        .into_iter()
+        .map(|(_, value)| value.to_owned())
+        .collect();
+
+    let mut value_skipped = 0;
+    let values = hold_index_start_loop(data, &values, &mut value_skipped);
+    let key_literal = |position: usize, elem: &Literal| {
+        PrimitiveString::get_literal(keys[position], elem.interval.to_owned())
+    };
+
+    loop_over(
+        ident,
+        key,
+        values.iter().cloned(),
+        key_literal,
+        value_skipped,
+        block,
+        label,
+        msg_data,
+        data,
+        sender,
+    )
+}
+
+fn iterable_loop(
+    ident: &Identifier,
+    index: &Option<Identifier>,
+    expr: &Expr,
+    block: &Block,
+    label: &Option<Identifier>,
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let literal = expr_to_literal(
+        expr,
+        &DisplayWarnings::On,
+        None,
+        data,
+        &mut msg_data,
+        sender,
+    )?;
+
+    match literal.primitive.get_type() {
+        PrimitiveType::PrimitiveObject => {
+            let object = Literal::get_value::<HashMap<String, Literal>>(
+                &literal.primitive,
+                &data.context.flow,
+                literal.interval,
+                ERROR_FOREACH.to_owned(),
+            )?;
+
+            object_loop(ident, index, object, block, label, msg_data, data, sender)
+        }
+        PrimitiveType::PrimitiveArray | PrimitiveType::PrimitiveString => {
+            let array = get_array(literal, &data.context.flow, ERROR_FOREACH.to_owned())?;
+
+            array_loop(ident, index, array, block, label, msg_data, data, sender)
+        }
+        // the value of a variable keeps the interval where it was created,
+        // the error points at the expression after `in`
+        primitive_type => Err(gen_error_info(
+            Position::new(interval_from_expr(expr), &data.context.flow),
+            format!("{} (got {})", ERROR_FOREACH, primitive_type.to_string()),
+        )),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn for_loop(
+    ident: &Identifier,
+    index: &Option<Identifier>,
+    expr: &Expr,
+    block: &Block,
+    label: &Option<Identifier>,
+    _range_interval: &Interval,
+    msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let msg_data = match expr {
+        Expr::RangeExpr(start, end, inclusive, range_interval) => range_loop(
+            ident,
+            index,
+            start,
+            end,
+            *inclusive,
+            range_interval,
+            block,
+            label,
+            msg_data,
+            data,
+            sender,
+        )?,
+        expr => iterable_loop(ident, index, expr, block, label, msg_data, data, sender)?,
+    };
+
     hold_index_end_loop(data);
     data.step_vars.remove(&ident.ident);
     if let Some(index) = index {
@@ -1,5 +1,5 @@
 use crate::data::{
-    ast::{Block, Expr, IfStatement, Infix, InstructionInfo},
+    ast::{Block, Expr, IfBranch, Infix},
     context::ContextStepInfo,
     warnings::DisplayWarnings,
     Data, Literal, MessageData, MSG,
@@ -9,7 +9,8 @@ use crate::interpreter::{
     interpret_scope,
     variable_handler::{
         expr_to_literal, get_var,
-        operations::{evaluate_infix, evaluate_postfix, valid_literal},
+        interval::interval_from_expr,
+        operations::{evaluate_infix, evaluate_null_coalescing, evaluate_postfix, valid_literal},
     },
 };
 use std::sync::mpsc;
@@ -52,24 +53,24 @@ pub fn valid_condition(
     }
 }
 
-fn evaluate_if_condition(
-    cond: &Expr,
-    mut msg_data: MessageData,
+// the operand takes the interval of its expression: the value of a variable keeps the
+// interval where it was created (a loop header, a remember...), the errors of the
+// operation must point at the statement using it
+fn evaluate_operand(
+    expr: &Expr,
     data: &mut Data,
-    consequence: &Block,
-    instruction_info: &InstructionInfo,
+    msg_data: &mut MessageData,
     sender: &Option<mpsc::Sender<MSG>>,
-    then_branch: &Option<Box<IfStatement>>,
-) -> Result<MessageData, ErrorInfo> {
-    if valid_condition(cond, data, &mut msg_data, sender) {
-        msg_data = msg_data + interpret_scope(consequence, data, sender)?;
-        return Ok(msg_data);
-    }
-    if let Some(then) = then_branch {
-        solve_if_statement(then, msg_data, data, instruction_info, sender)
-    } else {
-        Ok(msg_data)
-    }
+) -> Result<Literal, ErrorInfo> {
+    let mut literal = match expr {
+        Expr::InfixExpr(infix, exp_1, exp_2) => {
+            evaluate_condition(infix, exp_1, exp_2, data, msg_data, sender)
+        }
+        exp => expr_to_literal(exp, &DisplayWarnings::Off, None, data, msg_data, sender),
+    }?;
+
+    literal.interval = interval_from_expr(expr);
+    Ok(literal)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -90,79 +91,40 @@ pub fn evaluate_condition(
         data.context.flow.clone()
     };
 
-    match (expr1, expr2) {
-        (Expr::InfixExpr(i1, ex1, ex2), Expr::InfixExpr(i2, exp_1, exp_2)) => evaluate_infix(
-            &flow_name,
-            infix,
-            evaluate_condition(i1, ex1, ex2, data, msg_data, sender),
-            evaluate_condition(i2, exp_1, exp_2, data, msg_data, sender),
-        ),
-        (Expr::InfixExpr(i1, ex1, ex2), exp) => evaluate_infix(
-            &flow_name,
-            infix,
-            evaluate_condition(i1, ex1, ex2, data, msg_data, sender),
-            expr_to_literal(exp, &DisplayWarnings::Off, None, data, msg_data, sender),
-        ),
-        (exp, Expr::InfixExpr(i1, ex1, ex2)) => evaluate_infix(
-            &flow_name,
-            infix,
-            expr_to_literal(exp, &DisplayWarnings::Off, None, data, msg_data, sender),
-            evaluate_condition(i1, ex1, ex2, data, msg_data, sender),
-        ),
-        (exp_1, exp_2) => evaluate_infix(
-            &flow_name,
-            infix,
-            expr_to_literal(exp_1, &DisplayWarnings::Off, None, data, msg_data, sender),
-            expr_to_literal(exp_2, &DisplayWarnings::Off, None, data, msg_data, sender),
-        ),
+    if let Infix::NullCoalescing = infix {
+        return evaluate_null_coalescing(expr1, expr2, data, msg_data, sender);
     }
+
+    let lhs = evaluate_operand(expr1, data, msg_data, sender);
+    let rhs = evaluate_operand(expr2, data, msg_data, sender);
+
+    evaluate_infix(&flow_name, infix, lhs, rhs)
 }
 
 pub fn solve_if_statement(
-    statement: &IfStatement,
+    branches: &[IfBranch],
+    else_body: &Option<Block>,
     mut msg_data: MessageData,
     data: &mut Data,
-    instruction_info: &InstructionInfo,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> Result<MessageData, ErrorInfo> {
-    match statement {
-        IfStatement::IfStmt {
-            cond,
-            consequence: scope,
-            then_branch,
-            last_action_index,
-        } => {
-            match &data.context.hold {
-                Some(hold) => {
-                    if hold.index.command_index <= *last_action_index {
-                        msg_data = msg_data + interpret_scope(scope, data, sender)?;
-                    } else if let Some(then_branch) = then_branch {
-                        return solve_if_statement(
-                            &then_branch,
-                            msg_data,
-                            data,
-                            instruction_info,
-                            sender,
-                        );
-                    }
-                }
-                None => {
-                    return evaluate_if_condition(
-                        cond,
-                        msg_data,
-                        data,
-                        scope,
-                        instruction_info,
-                        sender,
-                        then_branch,
-                    );
-                }
-            }
-            Ok(msg_data)
-        }
-        IfStatement::ElseStmt(consequence, ..) => {
-            msg_data = msg_data + interpret_scope(consequence, data, sender)?;
-            Ok(msg_data)
+    for branch in branches.iter() {
+        let is_executed = match &data.context.hold {
+            // resume in the branch containing the hold, conditions are not evaluated again
+            Some(hold) => hold.index.command_index <= branch.last_action_index,
+            // the conditions are evaluated in order until one of them is true
+            None => valid_condition(&branch.cond, data, &mut msg_data, sender),
+        };
+
+        if is_executed {
+            msg_data = msg_data + interpret_scope(&branch.consequence, data, sender)?;
+            return Ok(msg_data);
         }
     }
+
+    if let Some(else_body) = else_body {
+        msg_data = msg_data + interpret_scope(else_body, data, sender)?;
+    }
+
+    Ok(msg_data)
 }
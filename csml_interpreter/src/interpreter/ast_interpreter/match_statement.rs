@@ -0,0 +1,104 @@
+use crate::data::primitive::{tools::get_regex, PrimitiveType};
+use crate::data::{
+    ast::*, position::Position, warnings::DisplayWarnings, Data, Literal, MessageData, MSG,
+};
+use crate::error_format::*;
+use crate::interpreter::{interpret_scope, variable_handler::expr_to_literal};
+use std::sync::mpsc;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn last_action_index(block: &Block) -> Option<usize> {
+    block
+        .commands
+        .last()
+        .map(|(_, instruction_info)| instruction_info.index + instruction_info.total)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn solve_match_statement(
+    subject: &Expr,
+    arms: &[(Expr, Block)],
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    // the conversation is resuming inside this match, go back in the arm containing the hold
+    // without evaluating the patterns again
+    if let Some(hold) = &data.context.hold {
+        let command_index = hold.index.command_index;
+
+        for (_pattern, block) in arms.iter() {
+            match last_action_index(block) {
+                Some(last_index) if command_index <= last_index => {
+                    return Ok(msg_data + interpret_scope(block, data, sender)?);
+                }
+                _ => continue,
+            }
+        }
+
+        return Ok(msg_data);
+    }
+
+    let subject = expr_to_literal(
+        subject,
+        &DisplayWarnings::On,
+        None,
+        data,
+        &mut msg_data,
+        sender,
+    )?;
+
+    // arms are evaluated from top to bottom, the first matching arm wins
+    for (pattern, block) in arms.iter() {
+        let is_match = match pattern {
+            Expr::IdentExpr(Identifier { ident, .. }) if ident == "_" => true,
+            // regexes only match strings
+            Expr::RegexExpr(regex, interval) => {
+                match subject.primitive.get_type() == PrimitiveType::PrimitiveString {
+                    true => {
+                        let text = Literal::get_value::<String>(
+                            &subject.primitive,
+                            &data.context.flow,
+                            *interval,
+                            ERROR_MATCH_REGEX.to_owned(),
+                        )?;
+                        let regex = get_regex(regex).map_err(|_| {
+                            gen_error_info(
+                                Position::new(*interval, &data.context.flow),
+                                ERROR_MATCH_REGEX.to_owned(),
+                            )
+                        })?;
+
+                        regex.is_match(text)
+                    }
+                    false => false,
+                }
+            }
+            pattern => {
+                let pattern = expr_to_literal(
+                    pattern,
+                    &DisplayWarnings::Off,
+                    None,
+                    data,
+                    &mut msg_data,
+                    sender,
+                )?;
+
+                subject.primitive == pattern.primitive
+            }
+        };
+
+        if is_match {
+            return Ok(msg_data + interpret_scope(block, data, sender)?);
+        }
+    }
+
+    // no arm is matching, the match is ignored
+    Ok(msg_data)
+}
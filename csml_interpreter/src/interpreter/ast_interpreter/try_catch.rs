@@ -0,0 +1,162 @@
+use crate::data::primitive::{PrimitiveInt, PrimitiveObject, PrimitiveString};
+use crate::data::{ast::*, Data, Literal, MessageData, MSG};
+use crate::error_format::*;
+use crate::interpreter::interpret_scope;
+use crate::parser::ExitCondition;
+use std::collections::HashMap;
+use std::{sync::mpsc, thread};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn last_action_index(block: &Block) -> Option<usize> {
+    block
+        .commands
+        .last()
+        .map(|(_, instruction_info)| instruction_info.index + instruction_info.total)
+}
+
+fn is_error_msg(msg: &MSG) -> bool {
+    match msg {
+        MSG::Message(message) => message.content_type == "error",
+        MSG::Error(..) => true,
+        _ => false,
+    }
+}
+
+fn error_to_literal(error: &ErrorInfo) -> Literal {
+    let interval = error.position.interval;
+    let mut object = HashMap::new();
+
+    object.insert(
+        "message".to_owned(),
+        PrimitiveString::get_literal(&error.message, interval),
+    );
+    object.insert(
+        "line".to_owned(),
+        PrimitiveInt::get_literal(interval.start_line as i64, interval),
+    );
+    object.insert(
+        "column".to_owned(),
+        PrimitiveInt::get_literal(interval.start_column as i64, interval),
+    );
+
+    // context added to the error, like the element index of a failing map callback
+    if let Some(info) = &error.additional_info {
+        for (key, value) in info.iter().filter(|(key, _)| *key != "error") {
+            object
+                .entry(key.to_owned())
+                .or_insert_with(|| value.to_owned());
+        }
+    }
+
+    PrimitiveObject::get_literal(&object, interval)
+}
+
+fn interpret_try_block(
+    block: &Block,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let in_try_block = data.in_try_block;
+    data.in_try_block = true;
+
+    let result = match sender {
+        // messages are forwarded as they arrive, the error and everything sent after it
+        // are held back until the end of the block in order to drop them once caught
+        Some(sender) => thread::scope(|scope| {
+            let (try_sender, try_receiver) = mpsc::channel();
+            let forwarder = scope.spawn(move || {
+                let mut held = vec![];
+                for msg in try_receiver {
+                    if held.is_empty() && !is_error_msg(&msg) {
+                        sender.send(msg).unwrap();
+                    } else {
+                        held.push(msg);
+                    }
+                }
+                held
+            });
+
+            let result = interpret_scope(block, data, &Some(try_sender));
+            let held = forwarder.join().unwrap();
+
+            // an exceeded execution budget is not caught, its error is sent as well
+            if data.budget.is_exceeded() {
+                for msg in held {
+                    sender.send(msg).unwrap();
+                }
+            }
+
+            result
+        }),
+        None => interpret_scope(block, data, sender),
+    };
+
+    data.in_try_block = in_try_block;
+
+    result
+}
+
+fn interpret_catch_block(
+    block: &Block,
+    ident: &Identifier,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    let result = interpret_scope(block, data, sender);
+    data.step_vars.remove(&ident.ident);
+
+    result
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn solve_try_catch(
+    try_block: &Block,
+    ident: &Identifier,
+    catch_block: &Block,
+    mut msg_data: MessageData,
+    data: &mut Data,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<MessageData, ErrorInfo> {
+    // the conversation is resuming inside the catch block, the error is still
+    // available in the step variables saved with the hold
+    if let Some(hold) = &data.context.hold {
+        match last_action_index(try_block) {
+            Some(last_index) if hold.index.command_index <= last_index => {}
+            _ => return Ok(msg_data + interpret_catch_block(catch_block, ident, data, sender)?),
+        }
+    }
+
+    let error = match interpret_try_block(try_block, data, sender) {
+        Ok(try_data) if data.budget.is_exceeded() => return Ok(msg_data + try_data),
+        Ok(mut try_data) => match try_data.error.take() {
+            Some(error) => {
+                if let Some(index) = try_data
+                    .messages
+                    .iter()
+                    .position(|message| message.content_type == "error")
+                {
+                    try_data.messages.truncate(index);
+                }
+                if let Some(ExitCondition::Error) = try_data.exit_condition {
+                    try_data.exit_condition = None;
+                }
+
+                msg_data = msg_data + try_data;
+                error
+            }
+            None => return Ok(msg_data + try_data),
+        },
+        Err(error) => error,
+    };
+
+    data.step_vars
+        .insert(ident.ident.to_owned(), error_to_literal(&error));
+
+    Ok(msg_data + interpret_catch_block(catch_block, ident, data, sender)?)
+}
@@ -1,12 +1,10 @@
+use crate::data::position::Position;
 use crate::data::{
     ast::*,
-    // hold::{
-    //     hold_index_end_loop, hold_index_start_loop, hold_loop_decrs_index, hold_loop_incrs_index,
-    // },
-    // primitive::tools::get_array,
-    Data,
-    MessageData,
-    MSG,
+    hold::{
+        hold_index_end_loop, hold_index_start_while, hold_loop_decrs_index, hold_loop_incrs_index,
+    },
+    Data, MessageData, MSG, WHILE_LIMIT,
 };
 use crate::error_format::*;
 use crate::interpreter::{ast_interpreter::if_statement::valid_condition, interpret_scope};
@@ -20,24 +18,48 @@ use std::sync::mpsc;
 pub fn while_loop(
     cond: &Expr,
     block: &Block,
-    _range_interval: &Interval,
+    range_interval: &Interval,
     mut msg_data: MessageData,
     data: &mut Data,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> Result<MessageData, ErrorInfo> {
-    while valid_condition(cond, data, &mut msg_data, sender) {
+    // if the hold is still active the conversation is resuming inside this loop,
+    // the condition was already valid for the held iteration so it is not evaluated again
+    let mut resume_hold = data.context.hold.is_some();
+    let mut while_loop_index = hold_index_start_while(data);
+    let mut iterations = 0;
+
+    while resume_hold || valid_condition(cond, data, &mut msg_data, sender) {
+        resume_hold = false;
+
+        // stop execution if iterations >= WHILE_LIMIT in order to avoid infinite loops
+        if iterations >= WHILE_LIMIT {
+            hold_index_end_loop(data);
+
+            return Err(gen_error_info(
+                Position::new(*range_interval, &data.context.flow),
+                ERROR_WHILE_LIMIT.to_owned(),
+            ));
+        }
+
+        hold_loop_incrs_index(data, while_loop_index);
         msg_data = msg_data + interpret_scope(block, data, sender)?;
+        hold_loop_decrs_index(data);
+
+        while_loop_index += 1;
+        iterations += 1;
 
         match msg_data.exit_condition {
-            Some(ExitCondition::Break) => {
+            Some(ExitCondition::Break(None)) => {
                 msg_data.exit_condition = None;
                 break;
             }
-            Some(ExitCondition::Continue) => msg_data.exit_condition = None,
+            Some(ExitCondition::Continue(None)) => msg_data.exit_condition = None,
             Some(_) => break,
             None => {}
         }
     }
 
+    hold_index_end_loop(data);
     Ok(msg_data)
 }
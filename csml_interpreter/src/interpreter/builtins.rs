@@ -1,9 +1,11 @@
 pub mod api;
+pub mod coalesce_memory;
 pub mod crypto;
 pub mod exists;
 pub mod format;
 pub mod functions;
 pub mod http_builtin;
+pub mod json_path;
 pub mod jwt;
 pub mod smtp;
 pub mod time;
@@ -18,11 +20,13 @@ use crate::interpreter::variable_handler::gen_generic_component::gen_generic_com
 use std::sync::mpsc;
 
 use api::api;
+use coalesce_memory::coalesce_memory;
 use crypto::crypto;
 use exists::exists;
 use format::*;
 use functions::*;
 use http_builtin::http;
+use json_path::get_path;
 use jwt::jwt;
 use smtp::smtp;
 use time::time;
@@ -58,19 +62,31 @@ pub fn match_builtin(
         BASE64 => base64(args, &data.context.flow, interval),
         HEX => hex(args, &data.context.flow, interval),
         FN | APP => api(args, interval, data, msg_data, sender),
-        ONE_OF => one_of(args, &data.context.flow, interval),
+        ONE_OF => one_of(args, &data.context.flow, interval, data.random),
         OR_BUILT_IN => or(args, &data.context.flow, interval),
-        SHUFFLE => shuffle(args, &data.context.flow, interval),
+        SHUFFLE => shuffle(args, &data.context.flow, interval, data.random),
         LENGTH => length(args, &data.context.flow, interval),
         FIND => find(args, &data.context.flow, interval),
-        RANDOM => random(interval),
+        RANDOM => random(interval, data.random),
         DEBUG => debug(args, interval),
         FLOOR => floor(args, &data.context.flow, interval),
         UUID => uuid_command(args, &data.context.flow, interval),
         JWT => jwt(args, &data.context.flow, interval),
         CRYPTO => crypto(args, &data.context.flow, interval),
-        TIME => time(args, &data.context.flow, interval),
+        TIME => time(args, &data.context.flow, interval, data.context.get_now()),
         EXISTS => exists(args, data, interval),
+        COALESCE_MEMORY => coalesce_memory(args, data, interval),
+        TYPE_OF => type_of(args, interval),
+        IS_NUMBER => is_type("number", args, interval),
+        IS_STRING => is_type("string", args, interval),
+        IS_BOOLEAN => is_type("boolean", args, interval),
+        IS_ARRAY => is_type("array", args, interval),
+        IS_OBJECT => is_type("object", args, interval),
+        IS_NULL => is_type("null", args, interval),
+        GET_PATH => get_path(args, &data.context.flow, interval),
+        RANDOM_FN => random_range(args, &data.context.flow, interval, data.random),
+        SHUFFLE_FN => shuffle_array(args, &data.context.flow, interval, data.random),
+        SAMPLE => sample(args, &data.context.flow, interval, data.random),
 
         //old builtin
         _object => object(args, &data.context.flow, interval),
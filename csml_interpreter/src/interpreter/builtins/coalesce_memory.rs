@@ -0,0 +1,41 @@
+use crate::data::error_info::ErrorInfo;
+use crate::data::position::Position;
+use crate::data::primitive::{PrimitiveNull, PrimitiveType};
+use crate::data::{ast::Interval, ArgsType, Data, Literal};
+use crate::error_format::*;
+
+////////////////////////////////////////////////////////////////////////////////
+/// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// value of the memory in the conversation scope, else in the user scope, else null
+pub fn coalesce_memory(
+    args: ArgsType,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    match args.get("string", 0) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            let key = literal.primitive.to_string();
+
+            let memory = match data.context.conversation.get(&key) {
+                Some(memory) => Some(memory),
+                None => data.context.current.get(&key),
+            };
+
+            match memory {
+                Some(memory) => {
+                    let mut memory = memory.to_owned();
+                    memory.interval = interval;
+
+                    Ok(memory)
+                }
+                None => Ok(PrimitiveNull::get_literal(interval)),
+            }
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            ERROR_COALESCE_MEMORY.to_owned(),
+        )),
+    }
+}
@@ -1,10 +1,12 @@
 use crate::data::position::Position;
 use crate::data::primitive::{
-    PrimitiveArray, PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveString,
+    PrimitiveArray, PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveNull, PrimitiveString,
+    PrimitiveType,
 };
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::data::{ast::Interval, ArgsType, Literal, RandomSource};
 use crate::error_format::*;
 use uuid::v1::{Context, Timestamp};
 use uuid::Uuid;
@@ -12,11 +14,39 @@ use uuid::Uuid;
 use rand::seq::SliceRandom;
 use rand::Rng;
 
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_number(literal: &Literal, flow_name: &str, interval: Interval) -> Result<f64, ErrorInfo> {
+    match literal.primitive.get_type() {
+        PrimitiveType::PrimitiveInt => Literal::get_value::<i64>(
+            &literal.primitive,
+            flow_name,
+            interval,
+            ERROR_RANDOM_RANGE.to_owned(),
+        )
+        .map(|value| *value as f64),
+        _ => Literal::get_value::<f64>(
+            &literal.primitive,
+            flow_name,
+            interval,
+            ERROR_RANDOM_RANGE.to_owned(),
+        )
+        .copied(),
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn one_of(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
+pub fn one_of(
+    args: ArgsType,
+    flow_name: &str,
+    interval: Interval,
+    random: &RandomSource,
+) -> Result<Literal, ErrorInfo> {
     match args.get("array", 0) {
         Some(literal) => {
             let res = Literal::get_value::<Vec<Literal>>(
@@ -25,7 +55,7 @@ pub fn one_of(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Lit
                 literal.interval,
                 ERROR_ONE_OF.to_owned(),
             )?;
-            match res.get(rand::thread_rng().gen_range(0..res.len())) {
+            match res.get(random.with(|rng| rng.gen_range(0..res.len()))) {
                 Some(lit) => Ok(lit.to_owned()),
                 None => Err(gen_error_info(
                     Position::new(literal.interval, flow_name),
@@ -53,7 +83,12 @@ pub fn or(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal
     }
 }
 
-pub fn shuffle(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
+pub fn shuffle(
+    args: ArgsType,
+    flow_name: &str,
+    interval: Interval,
+    random: &RandomSource,
+) -> Result<Literal, ErrorInfo> {
     match args.get("array", 0) {
         Some(literal) => {
             let res = Literal::get_value::<Vec<Literal>>(
@@ -63,7 +98,7 @@ pub fn shuffle(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Li
                 ERROR_SHUFFLE.to_owned(),
             )?;
             let mut vec = res.to_owned();
-            vec.shuffle(&mut rand::thread_rng());
+            random.with(|rng| vec.shuffle(rng));
             Ok(PrimitiveArray::get_literal(&vec, literal.interval))
         }
         None => Err(gen_error_info(
@@ -76,33 +111,40 @@ pub fn shuffle(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Li
 pub fn length(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
     match args.get("length", 0) {
         Some(literal) => {
-            if let Ok(res) = Literal::get_value::<Vec<Literal>>(
-                &literal.primitive,
-                flow_name,
-                interval,
-                ERROR_LENGTH.to_owned(),
-            ) {
-                return Ok(PrimitiveInt::get_literal(
-                    res.len() as i64,
-                    literal.interval,
-                ));
-            }
-            if let Ok(res) = Literal::get_value::<String>(
-                &literal.primitive,
-                flow_name,
-                interval,
-                ERROR_LENGTH.to_owned(),
-            ) {
-                return Ok(PrimitiveInt::get_literal(
-                    res.len() as i64,
-                    literal.interval,
-                ));
-            }
+            let length = match literal.primitive.get_type() {
+                PrimitiveType::PrimitiveArray => Literal::get_value::<Vec<Literal>>(
+                    &literal.primitive,
+                    flow_name,
+                    interval,
+                    ERROR_LENGTH.to_owned(),
+                )?
+                .len(),
+                // characters, not bytes
+                PrimitiveType::PrimitiveString => Literal::get_value::<String>(
+                    &literal.primitive,
+                    flow_name,
+                    interval,
+                    ERROR_LENGTH.to_owned(),
+                )?
+                .chars()
+                .count(),
+                PrimitiveType::PrimitiveObject => Literal::get_value::<HashMap<String, Literal>>(
+                    &literal.primitive,
+                    flow_name,
+                    interval,
+                    ERROR_LENGTH.to_owned(),
+                )?
+                .len(),
+                PrimitiveType::PrimitiveNull => 0,
+                primitive_type => {
+                    return Err(gen_error_info(
+                        Position::new(interval, flow_name),
+                        format!("{} (got {})", ERROR_LENGTH, primitive_type.to_string()),
+                    ))
+                }
+            };
 
-            Err(gen_error_info(
-                Position::new(interval, flow_name),
-                ERROR_LENGTH.to_owned(),
-            ))
+            Ok(PrimitiveInt::get_literal(length as i64, literal.interval))
         }
         None => Err(gen_error_info(
             Position::new(interval, flow_name),
@@ -169,14 +211,135 @@ pub fn find(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Liter
     }
 }
 
-pub fn random(interval: Interval) -> Result<Literal, ErrorInfo> {
-    let mut rng = rand::thread_rng();
-
-    let random: f64 = rng.gen();
+pub fn random(interval: Interval, random: &RandomSource) -> Result<Literal, ErrorInfo> {
+    let random: f64 = random.with(|rng| rng.gen());
 
     Ok(PrimitiveFloat::get_literal(random, interval))
 }
 
+// an Int between min and max included when both are Int, a Float between min included
+// and max excluded otherwise
+pub fn random_range(
+    args: ArgsType,
+    flow_name: &str,
+    interval: Interval,
+    random: &RandomSource,
+) -> Result<Literal, ErrorInfo> {
+    let (min, max) = match (args.get("min", 0), args.get("max", 1)) {
+        (Some(min), Some(max)) => (min, max),
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                ERROR_RANDOM_RANGE.to_owned(),
+            ))
+        }
+    };
+
+    match (min.primitive.get_type(), max.primitive.get_type()) {
+        (PrimitiveType::PrimitiveInt, PrimitiveType::PrimitiveInt) => {
+            let min = *Literal::get_value::<i64>(
+                &min.primitive,
+                flow_name,
+                interval,
+                ERROR_RANDOM_RANGE.to_owned(),
+            )?;
+            let max = *Literal::get_value::<i64>(
+                &max.primitive,
+                flow_name,
+                interval,
+                ERROR_RANDOM_RANGE.to_owned(),
+            )?;
+            if min > max {
+                return Err(gen_error_info(
+                    Position::new(interval, flow_name),
+                    format!("{} (got {} and {})", ERROR_RANDOM_RANGE_ORDER, min, max),
+                ));
+            }
+
+            let value = random.with(|rng| rng.gen_range(min..=max));
+            Ok(PrimitiveInt::get_literal(value, interval))
+        }
+        (
+            PrimitiveType::PrimitiveInt | PrimitiveType::PrimitiveFloat,
+            PrimitiveType::PrimitiveInt | PrimitiveType::PrimitiveFloat,
+        ) => {
+            let min = get_number(min, flow_name, interval)?;
+            let max = get_number(max, flow_name, interval)?;
+            if min > max {
+                return Err(gen_error_info(
+                    Position::new(interval, flow_name),
+                    format!("{} (got {} and {})", ERROR_RANDOM_RANGE_ORDER, min, max),
+                ));
+            }
+
+            // gen_range panics on an empty range
+            let value = match min == max {
+                true => min,
+                false => random.with(|rng| rng.gen_range(min..max)),
+            };
+            Ok(PrimitiveFloat::get_literal(value, interval))
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, flow_name),
+            ERROR_RANDOM_RANGE.to_owned(),
+        )),
+    }
+}
+
+pub fn shuffle_array(
+    args: ArgsType,
+    flow_name: &str,
+    interval: Interval,
+    random: &RandomSource,
+) -> Result<Literal, ErrorInfo> {
+    match args.get("array", 0) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveArray => {
+            let mut vec = Literal::get_value::<Vec<Literal>>(
+                &literal.primitive,
+                flow_name,
+                interval,
+                ERROR_SHUFFLE_ARRAY.to_owned(),
+            )?
+            .to_owned();
+            random.with(|rng| vec.shuffle(rng));
+
+            Ok(PrimitiveArray::get_literal(&vec, interval))
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, flow_name),
+            ERROR_SHUFFLE_ARRAY.to_owned(),
+        )),
+    }
+}
+
+// a random element of the array, null when the array is empty
+pub fn sample(
+    args: ArgsType,
+    flow_name: &str,
+    interval: Interval,
+    random: &RandomSource,
+) -> Result<Literal, ErrorInfo> {
+    match args.get("array", 0) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveArray => {
+            let vec = Literal::get_value::<Vec<Literal>>(
+                &literal.primitive,
+                flow_name,
+                interval,
+                ERROR_SAMPLE.to_owned(),
+            )?;
+
+            match random.with(|rng| vec.choose(rng)) {
+                Some(value) => Ok(value.to_owned()),
+                None => Ok(PrimitiveNull::get_literal(interval)),
+            }
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, flow_name),
+            ERROR_SAMPLE.to_owned(),
+        )),
+    }
+}
+
 pub fn floor(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
     match args.get("float", 0) {
         Some(literal) => {
@@ -195,6 +358,35 @@ pub fn floor(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Lite
     }
 }
 
+// type of the first argument, a missing argument is null. ints and floats are both numbers,
+// closures are objects
+fn type_name(args: &ArgsType) -> &'static str {
+    let literal = match args.get("value", 0) {
+        Some(literal) => literal,
+        None => return "null",
+    };
+
+    match literal.primitive.get_type() {
+        PrimitiveType::PrimitiveString => "string",
+        PrimitiveType::PrimitiveInt | PrimitiveType::PrimitiveFloat => "number",
+        PrimitiveType::PrimitiveBoolean => "boolean",
+        PrimitiveType::PrimitiveArray => "array",
+        PrimitiveType::PrimitiveObject | PrimitiveType::PrimitiveClosure => "object",
+        PrimitiveType::PrimitiveNull => "null",
+    }
+}
+
+pub fn type_of(args: ArgsType, interval: Interval) -> Result<Literal, ErrorInfo> {
+    Ok(PrimitiveString::get_literal(type_name(&args), interval))
+}
+
+pub fn is_type(expected: &str, args: ArgsType, interval: Interval) -> Result<Literal, ErrorInfo> {
+    Ok(PrimitiveBoolean::get_literal(
+        type_name(&args) == expected,
+        interval,
+    ))
+}
+
 pub fn uuid_command(
     args: ArgsType,
     flow_name: &str,
@@ -3,8 +3,11 @@ use crate::data::position::Position;
 use crate::data::primitive::{PrimitiveInt, PrimitiveObject, PrimitiveString, PrimitiveType};
 use crate::data::{ast::Interval, csml_logs::*, ArgsType, Literal};
 use crate::error_format::*;
+use rand::Rng;
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::env;
+use std::{thread, time};
 
 use std::sync::Arc;
 use ureq::{Request, Response};
@@ -14,6 +17,11 @@ use rustls::{
     Certificate,
 };
 
+// The base back off time in milliseconds (0.5 seconds).
+const RETRY_BASE: u64 = 500;
+// The maximum back off time in milliseconds (1 minute).
+const MAX_INTERVAL_LIMIT: u64 = 60_000;
+
 ////////////////////////////////////////////////////////////////////////////////
 /// DATA TYPES
 ////////////////////////////////////////////////////////////////////////////////
@@ -97,6 +105,76 @@ fn get_request_info(response: &Response, interval: Interval) -> HashMap<String,
     response_info
 }
 
+fn get_positive_int(key: &str, object: &HashMap<String, Literal>) -> Option<u64> {
+    match object.get(key) {
+        Some(val) if val.primitive.get_type() == PrimitiveType::PrimitiveInt => {
+            let value = val.primitive.as_any().downcast_ref::<PrimitiveInt>()?;
+            u64::try_from(value.value).ok()
+        }
+        _ => None,
+    }
+}
+
+// server errors and timeouts may succeed on a later attempt
+fn is_retryable(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(code, _) => *code >= 500,
+        ureq::Error::Transport(transport) => std::error::Error::source(transport)
+            .and_then(|source| source.downcast_ref::<std::io::Error>())
+            .map_or(false, |err| err.kind() == std::io::ErrorKind::TimedOut),
+    }
+}
+
+/**
+ * Send the request and retry it up to max_retries times with exponential backoff
+ * on server errors and timeouts.
+ */
+fn send_with_backoff(
+    request: Request,
+    body: Option<&Literal>,
+    max_retries: u64,
+    flow_name: &str,
+    interval: Interval,
+) -> Result<Response, ureq::Error> {
+    let mut retry_times = 1;
+
+    loop {
+        let response = match body {
+            Some(body) => request.clone().send_json(body.primitive.to_json()),
+            None => request.clone().call(),
+        };
+
+        match response {
+            Err(err) if retry_times <= max_retries && is_retryable(&err) => {
+                let interval_limit =
+                    std::cmp::min(MAX_INTERVAL_LIMIT, RETRY_BASE * 2 * retry_times);
+                let interval_jitter = rand::thread_rng().gen_range(0..interval_limit);
+
+                csml_logger(
+                    CsmlLog::new(
+                        None,
+                        Some(flow_name.to_string()),
+                        Some(interval.start_line),
+                        format!(
+                            "Http call failed: {:?}, retry {}/{} in {}ms",
+                            err.to_string(),
+                            retry_times,
+                            max_retries,
+                            interval_jitter
+                        ),
+                    ),
+                    LogLvl::Info,
+                );
+
+                thread::sleep(time::Duration::from_millis(interval_jitter));
+            }
+            response => return response,
+        }
+
+        retry_times += 1;
+    }
+}
+
 pub fn get_ssl_state(object: &HashMap<String, Literal>) -> bool {
     match object.get("disable_ssl_verify") {
         Some(val) if val.primitive.get_type() == PrimitiveType::PrimitiveBoolean => {
@@ -242,6 +320,11 @@ pub fn http_request(
 
     let mut request = get_http_request(method, &url, flow_name, interval, is_ssl_disable)?;
 
+    if let Some(timeout) = get_positive_int("timeout", object) {
+        request = request.timeout(time::Duration::from_millis(timeout));
+    }
+    let max_retries = get_positive_int("retry", object).unwrap_or(0);
+
     for key in header.keys() {
         let value = match header.get(key) {
             Some(val) => val.primitive.to_string(),
@@ -275,10 +358,13 @@ pub fn http_request(
         LogLvl::Debug,
     );
 
-    let response = match object.get("body") {
-        Some(body) => request.send_json(body.primitive.to_json()),
-        None => request.call(),
-    };
+    let response = send_with_backoff(
+        request,
+        object.get("body"),
+        max_retries,
+        flow_name,
+        interval,
+    );
 
     match response {
         Ok(response) => {
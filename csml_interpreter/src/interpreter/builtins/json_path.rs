@@ -0,0 +1,121 @@
+use crate::data::error_info::ErrorInfo;
+use crate::data::position::Position;
+use crate::data::primitive::{PrimitiveArray, PrimitiveNull, PrimitiveObject, PrimitiveType};
+use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::error_format::*;
+
+// a key of an object, or an index of an array
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// "a.b[0][1].c": keys separated by dots, each followed by any number of indexes.
+// The path can start with an index, when the value is an array
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut segments = vec![];
+
+    for (position, part) in path.split('.').enumerate() {
+        let (key, mut indexes) = match part.find('[') {
+            Some(start) => part.split_at(start),
+            None => (part, ""),
+        };
+
+        if key.contains(']') {
+            return Err(format!("unexpected ']' in '{}'", part));
+        }
+        match key.is_empty() {
+            true if position == 0 && !indexes.is_empty() => {}
+            true => return Err("empty key".to_owned()),
+            false => segments.push(PathSegment::Key(key.to_owned())),
+        }
+
+        while !indexes.is_empty() {
+            let end = match (indexes.starts_with('['), indexes.find(']')) {
+                (true, Some(end)) => end,
+                (true, None) => return Err(format!("missing ']' in '{}'", part)),
+                (false, _) => return Err(format!("unexpected '{}' in '{}'", indexes, part)),
+            };
+
+            let index = &indexes[1..end];
+            match index.bytes().all(|byte| byte.is_ascii_digit()) {
+                true => match index.parse::<usize>() {
+                    Ok(index) => segments.push(PathSegment::Index(index)),
+                    Err(_) => return Err(format!("invalid index '[{}]'", index)),
+                },
+                false => return Err(format!("invalid index '[{}]'", index)),
+            }
+
+            indexes = &indexes[end + 1..];
+        }
+    }
+
+    Ok(segments)
+}
+
+fn get_segment<'a>(literal: &'a Literal, segment: &PathSegment) -> Option<&'a Literal> {
+    match segment {
+        PathSegment::Key(key) => literal
+            .primitive
+            .as_any()
+            .downcast_ref::<PrimitiveObject>()?
+            .value
+            .get(key),
+        PathSegment::Index(index) => literal
+            .primitive
+            .as_any()
+            .downcast_ref::<PrimitiveArray>()?
+            .value
+            .get(*index),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// value at the path in the first argument, null as soon as a key or an index is missing.
+// Only a malformed path is an error
+pub fn get_path(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
+    let path = match args.get("path", 1) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            literal.primitive.to_string()
+        }
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                ERROR_GET_PATH.to_owned(),
+            ))
+        }
+    };
+
+    let segments = match parse_path(&path) {
+        Ok(segments) => segments,
+        Err(reason) => {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                format!("{} \"{}\": {}", ERROR_GET_PATH_SYNTAX, path, reason),
+            ))
+        }
+    };
+
+    let mut literal = match args.get("value", 0) {
+        Some(literal) => literal,
+        None => return Ok(PrimitiveNull::get_literal(interval)),
+    };
+    for segment in segments.iter() {
+        literal = match get_segment(literal, segment) {
+            Some(value) => value,
+            None => return Ok(PrimitiveNull::get_literal(interval)),
+        };
+    }
+
+    let mut result = literal.to_owned();
+    result.interval = interval;
+
+    Ok(result)
+}
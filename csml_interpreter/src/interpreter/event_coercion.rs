@@ -0,0 +1,163 @@
+use crate::data::primitive::{PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveString};
+use crate::data::{ast::ExpectedType, ast::Interval, Event, Literal};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_type_name(expected: ExpectedType) -> &'static str {
+    match expected {
+        ExpectedType::Number => "a number",
+        ExpectedType::String => "a string",
+        ExpectedType::Boolean => "a boolean",
+        ExpectedType::Email => "an email",
+    }
+}
+
+// one '@' between a local part and a domain with a dot, without whitespaces
+fn is_email(value: &str) -> bool {
+    if value.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !domain.contains("..")
+        }
+        None => false,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// the value of the event as the expected type, or what was expected and what was received
+pub fn coerce_event(
+    expected: ExpectedType,
+    event: &Event,
+    interval: Interval,
+) -> Result<Literal, String> {
+    let value = event.content_value.trim();
+
+    let literal = match expected {
+        ExpectedType::Number => match (value.parse::<i64>(), value.parse::<f64>()) {
+            (Ok(int), _) => Some(PrimitiveInt::get_literal(int, interval)),
+            (_, Ok(float)) if float.is_finite() => {
+                Some(PrimitiveFloat::get_literal(float, interval))
+            }
+            _ => None,
+        },
+        ExpectedType::String if !value.is_empty() => {
+            Some(PrimitiveString::get_literal(&event.content_value, interval))
+        }
+        ExpectedType::Boolean => match value.to_lowercase().as_str() {
+            "true" => Some(PrimitiveBoolean::get_literal(true, interval)),
+            "false" => Some(PrimitiveBoolean::get_literal(false, interval)),
+            _ => None,
+        },
+        ExpectedType::Email if is_email(value) => {
+            Some(PrimitiveString::get_literal(value, interval))
+        }
+        ExpectedType::String | ExpectedType::Email => None,
+    };
+
+    match literal {
+        Some(mut literal) => {
+            literal.secure_variable = event.secure;
+            Ok(literal)
+        }
+        // the value of a secure event is not displayed
+        None if event.secure => Err(format!(
+            "expected {}, got a secure value",
+            get_type_name(expected)
+        )),
+        None => Err(format!(
+            "expected {}, got \"{}\"",
+            get_type_name(expected),
+            event.content_value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::primitive::PrimitiveType;
+
+    fn coerce(expected: ExpectedType, value: &str) -> Result<Literal, String> {
+        let event = Event::new("text", value, serde_json::json!({ "text": value }));
+
+        coerce_event(expected, &event, Interval::default())
+    }
+
+    #[test]
+    fn ok_number() {
+        let int = coerce(ExpectedType::Number, " 42 ").unwrap();
+        let float = coerce(ExpectedType::Number, "4.5").unwrap();
+
+        assert_eq!(int.primitive.get_type(), PrimitiveType::PrimitiveInt);
+        assert_eq!(int.primitive.to_string(), "42");
+        assert_eq!(float.primitive.get_type(), PrimitiveType::PrimitiveFloat);
+        assert_eq!(float.primitive.to_string(), "4.5");
+    }
+
+    #[test]
+    fn ok_boolean() {
+        let boolean = coerce(ExpectedType::Boolean, "TRUE").unwrap();
+
+        assert_eq!(
+            boolean.primitive.get_type(),
+            PrimitiveType::PrimitiveBoolean
+        );
+        assert!(boolean.primitive.as_bool());
+    }
+
+    #[test]
+    fn ok_email() {
+        assert!(coerce(ExpectedType::Email, "jo@example.com").is_ok());
+        assert!(coerce(ExpectedType::Email, "jo.doe+csml@mail.example.co").is_ok());
+    }
+
+    #[test]
+    fn err_email() {
+        for value in [
+            "jo",
+            "jo@",
+            "@example.com",
+            "jo@example",
+            "jo@@example.com",
+            "jo @a.b",
+        ] {
+            assert!(coerce(ExpectedType::Email, value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn err_expected_and_received() {
+        assert_eq!(
+            coerce(ExpectedType::Number, "twelve").unwrap_err(),
+            "expected a number, got \"twelve\""
+        );
+        assert_eq!(
+            coerce(ExpectedType::String, "  ").unwrap_err(),
+            "expected a string, got \"  \""
+        );
+    }
+
+    #[test]
+    fn err_secure_value_is_hidden() {
+        let mut event = Event::new("text", "secret", serde_json::json!({ "text": "secret" }));
+        event.secure = true;
+
+        assert_eq!(
+            coerce_event(ExpectedType::Number, &event, Interval::default()).unwrap_err(),
+            "expected a number, got a secure value"
+        );
+    }
+}
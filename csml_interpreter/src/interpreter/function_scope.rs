@@ -5,7 +5,11 @@ use crate::data::{
 };
 use crate::error_format::*;
 use crate::interpreter::{
-    ast_interpreter::{for_loop, match_actions, solve_if_statement, while_loop},
+    ast_interpreter::{
+        for_loop, match_actions, solve_if_statement, solve_match_statement, solve_try_catch,
+        while_loop,
+    },
+    consume_budget,
     variable_handler::{expr_to_literal, interval::interval_from_expr},
 };
 use crate::parser::ExitCondition;
@@ -22,7 +26,11 @@ fn interpret_function_scope(
 ) -> Result<MessageData, ErrorInfo> {
     let mut message_data = MessageData::default();
 
-    for (action, instruction_info) in actions.commands.iter() {
+    for (action, _) in actions.commands.iter() {
+        if consume_budget(action, data, &mut message_data, sender) {
+            return Ok(message_data);
+        }
+
         match action {
             Expr::ObjectExpr(ObjectType::Return(var)) => {
                 let lit = expr_to_literal(
@@ -38,16 +46,36 @@ fn interpret_function_scope(
                 return Ok(message_data);
             }
             Expr::ObjectExpr(fun) => message_data = match_actions(fun, message_data, data, sender)?,
-            Expr::IfExpr(ref if_statement) => {
-                message_data =
-                    solve_if_statement(if_statement, message_data, data, instruction_info, sender)?;
+            Expr::IfExpr {
+                branches,
+                else_body,
+                ..
+            } => {
+                message_data = solve_if_statement(branches, else_body, message_data, data, sender)?;
             }
-            Expr::ForEachExpr(ident, i, expr, block, range) => {
-                message_data = for_loop(ident, i, expr, block, range, message_data, data, sender)?
+            Expr::ForEachExpr(ident, i, expr, block, label, range) => {
+                message_data = for_loop(
+                    ident,
+                    i,
+                    expr,
+                    block,
+                    label,
+                    range,
+                    message_data,
+                    data,
+                    sender,
+                )?
             }
             Expr::WhileExpr(expr, block, range) => {
                 message_data = while_loop(expr, block, range, message_data, data, sender)?
             }
+            Expr::MatchExpr(subject, arms, _range) => {
+                message_data = solve_match_statement(subject, arms, message_data, data, sender)?
+            }
+            Expr::TryCatchExpr(try_block, ident, catch_block, _range) => {
+                message_data =
+                    solve_try_catch(try_block, ident, catch_block, message_data, data, sender)?
+            }
             e => {
                 return Err(gen_error_info(
                     Position::new(interval_from_expr(e), &data.context.flow),
@@ -56,7 +84,10 @@ fn interpret_function_scope(
             }
         };
 
-        if let Some(ExitCondition::Return(_)) = &message_data.exit_condition {
+        // a failed assertion stops the function and its caller
+        if let Some(ExitCondition::Return(_)) | Some(ExitCondition::Error) =
+            &message_data.exit_condition
+        {
             return Ok(message_data);
         }
     }
@@ -83,8 +114,16 @@ pub fn exec_fn_in_new_scope(
             let fn_msg_data = interpret_function_scope(&scope, new_scope_data, sender)?;
 
             let mut return_value = PrimitiveNull::get_literal(interal.to_owned());
-            if let Some(ExitCondition::Return(lit)) = fn_msg_data.exit_condition {
-                return_value = lit;
+            match fn_msg_data.exit_condition {
+                Some(ExitCondition::Return(lit)) => return_value = lit,
+                // the execution budget was exceeded inside the function, the caller stops too
+                Some(ExitCondition::Error) => {
+                    msg_data.exit_condition = Some(ExitCondition::Error);
+                    if msg_data.error.is_none() {
+                        msg_data.error = fn_msg_data.error;
+                    }
+                }
+                _ => {}
             }
 
             msg_data.messages = [&msg_data.messages[..], &fn_msg_data.messages[..]].concat();
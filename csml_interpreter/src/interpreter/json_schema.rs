@@ -0,0 +1,247 @@
+use serde_json::{Map, Value};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn is_type(value: &Value, schema_type: &str) -> bool {
+    match (schema_type, value) {
+        ("integer", Value::Number(number)) => match number.as_f64() {
+            Some(float) => float.fract() == 0.0,
+            None => false,
+        },
+        ("number", Value::Number(_)) => true,
+        (schema_type, value) => get_type(value) == schema_type,
+    }
+}
+
+fn check_bounds(
+    size: f64,
+    schema: &Map<String, Value>,
+    (min_key, max_key): (&str, &str),
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(min) = schema.get(min_key).and_then(|min| min.as_f64()) {
+        if size < min {
+            errors.push(format!(
+                "{}: {} is lower than {} {}",
+                path, size, min_key, min
+            ));
+        }
+    }
+
+    if let Some(max) = schema.get(max_key).and_then(|max| max.as_f64()) {
+        if size > max {
+            errors.push(format!(
+                "{}: {} is greater than {} {}",
+                path, size, max_key, max
+            ));
+        }
+    }
+}
+
+fn validate_object(
+    map: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(|key| key.as_str()) {
+            if !map.contains_key(key) {
+                errors.push(format!("{}.{}: missing required property", path, key));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(|value| value.as_object());
+
+    for (key, value) in map.iter() {
+        let value_path = format!("{}.{}", path, key);
+
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(value_schema) => validate(value, value_schema, &value_path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    errors.push(format!("{}: unexpected property", value_path))
+                }
+                Some(value_schema) => validate(value, value_schema, &value_path, errors),
+                None => {}
+            },
+        }
+    }
+}
+
+fn validate(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(true) => return,
+        Value::Bool(false) => return errors.push(format!("{}: no value is allowed", path)),
+        _ => return errors.push(format!("{}: invalid schema", path)),
+    };
+
+    if let Some(schema_type) = schema.get("type") {
+        let types: Vec<&str> = match schema_type {
+            Value::String(schema_type) => vec![schema_type],
+            Value::Array(types) => types.iter().filter_map(|value| value.as_str()).collect(),
+            _ => return errors.push(format!("{}: invalid schema type", path)),
+        };
+
+        if !types.iter().any(|schema_type| is_type(value, schema_type)) {
+            // the other keywords are meaningless on a value of the wrong type
+            return errors.push(format!(
+                "{}: expecting {}, got {}",
+                path,
+                types.join(" or "),
+                get_type(value)
+            ));
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            errors.push(format!(
+                "{}: {} is not one of {}",
+                path, value, schema["enum"]
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{}: expecting {}, got {}", path, expected, value));
+        }
+    }
+
+    match value {
+        Value::Object(map) => validate_object(map, schema, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate(item, item_schema, &format!("{}[{}]", path, index), errors);
+                }
+            }
+
+            let size = items.len() as f64;
+            check_bounds(size, schema, ("minItems", "maxItems"), path, errors);
+        }
+        Value::String(string) => {
+            let size = string.chars().count() as f64;
+            check_bounds(size, schema, ("minLength", "maxLength"), path, errors);
+        }
+        Value::Number(number) => {
+            if let Some(number) = number.as_f64() {
+                check_bounds(number, schema, ("minimum", "maximum"), path, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// validate a value against the subset of JSON Schema used by 'hold validate': type, enum,
+// const, properties, required, additionalProperties, items, min/maxItems, min/maxLength
+// and minimum/maximum. Each error starts with the JSON path of the failing value
+pub fn validate_json_schema(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = vec![];
+
+    validate(value, schema, "$", &mut errors);
+
+    errors
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn address_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["name", "address"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "address": {
+                    "type": "object",
+                    "required": ["zip"],
+                    "properties": {
+                        "zip": {"type": "string", "maxLength": 5},
+                        "lines": {"type": "array", "items": {"type": "string"}}
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn ok_valid_value() {
+        let value = json!({"name": "Jo", "address": {"zip": "75001", "lines": ["1 rue"]}});
+
+        assert!(validate_json_schema(&value, &address_schema()).is_empty());
+    }
+
+    #[test]
+    fn ok_integer_number() {
+        assert!(validate_json_schema(&json!(4.0), &json!({"type": "integer"})).is_empty());
+        assert!(validate_json_schema(&json!(4), &json!({"type": "number"})).is_empty());
+    }
+
+    #[test]
+    fn err_nested_paths() {
+        let value = json!({"name": "", "address": {"lines": ["1 rue", 2]}});
+
+        assert_eq!(
+            validate_json_schema(&value, &address_schema()),
+            vec![
+                "$.address.zip: missing required property".to_owned(),
+                "$.address.lines[1]: expecting string, got integer".to_owned(),
+                "$.name: 0 is lower than minLength 1".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn err_enum_and_additional_properties() {
+        let schema = json!({
+            "properties": {"size": {"enum": ["S", "M", "L"]}},
+            "additionalProperties": false
+        });
+        let value = json!({"size": "XL", "color": "red"});
+
+        assert_eq!(
+            validate_json_schema(&value, &schema),
+            vec![
+                "$.color: unexpected property".to_owned(),
+                "$.size: \"XL\" is not one of [\"S\",\"M\",\"L\"]".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn err_invalid_schema() {
+        assert_eq!(
+            validate_json_schema(&json!(1), &json!("string")),
+            vec!["$: invalid schema".to_owned()]
+        );
+    }
+}
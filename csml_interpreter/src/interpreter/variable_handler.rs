@@ -121,6 +121,17 @@ fn loop_path(
             },
             PathLiteral::MapIndex(key) => {
                 if let (Some(ref new), 0) = (&new, path.len()) {
+                    if lit.primitive.get_type() != PrimitiveType::PrimitiveObject {
+                        let err = gen_error_info(
+                            Position::new(*interval, &data.context.flow),
+                            format!("[{}] {}", key, ERROR_OBJECT_SET),
+                        );
+                        return Ok((
+                            MSG::send_error_msg(sender, msg_data, Err(err)),
+                            tmp_update_var,
+                        ));
+                    }
+
                     let mut args = HashMap::new();
 
                     args.insert(
@@ -143,12 +154,17 @@ fn loop_path(
                     )?;
                     return Ok((lit.to_owned(), true));
                 } else {
+                    let message = match lit.primitive.get_type() {
+                        PrimitiveType::PrimitiveNull => ERROR_NULL_MEMBER,
+                        _ => ERROR_OBJECT_GET,
+                    };
+
                     match get_value_from_key(lit, &data.context.flow, key) {
                         Some(new_lit) => lit = new_lit,
                         None => {
                             let err = gen_error_info(
                                 Position::new(*interval, &data.context.flow),
-                                format!("[{}] {}", key, ERROR_OBJECT_GET),
+                                format!("[{}] {}", key, message),
                             );
 
                             let error =
@@ -613,6 +629,18 @@ pub fn get_var(
             Some(path) => {
                 let path = resolve_path(path, dis_warnings, data, msg_data, sender)?;
 
+                // a missing key is null rather than an error to allow optional configuration
+                if let Some((interval, PathLiteral::MapIndex(key))) = path.get(0) {
+                    let is_set = match data.env.primitive.as_any().downcast_ref() {
+                        Some(PrimitiveObject { value, .. }) => value.contains_key(key),
+                        None => false,
+                    };
+
+                    if !is_set {
+                        return Ok(PrimitiveNull::get_literal(*interval));
+                    }
+                }
+
                 let content_type = ContentType::get(&data.env);
                 let (lit, _tmp_mem_update) = exec_path_actions(
                     &mut data.env.clone(),
@@ -694,11 +722,15 @@ pub fn get_var(
                 tmp_default_flow,
                 &mut tmp_context,
                 &tmp_event,
+                data.event_value.clone(),
                 &tmp_env,
                 tmp_loop_indexes,
                 tmp_loop_index,
                 &mut tmp_step_count,
                 tmp_step_limit,
+                data.budget,
+                data.random,
+                data.message_observer,
                 tmp_step_vars,
                 data.previous_info.clone(),
                 data.custom_component,
@@ -1,13 +1,15 @@
 use crate::data::error_info::ErrorInfo;
 use crate::data::literal::ContentType;
-use crate::data::primitive::{closure::capture_variables, PrimitiveArray, PrimitiveObject};
+use crate::data::primitive::{
+    closure::capture_variables, PrimitiveArray, PrimitiveObject, PrimitiveType,
+};
 use crate::data::{
     ast::*, warnings::DisplayWarnings, ArgsType, Data, Literal, MemoryType, MessageData, Position,
     MSG,
 };
 use crate::error_format::*;
 use crate::interpreter::{
-    ast_interpreter::evaluate_condition,
+    ast_interpreter::{evaluate_condition, valid_condition},
     variable_handler::{
         exec_path_actions, get_string_from_complex_string, get_var, interval::interval_from_expr,
         operations::evaluate_postfix, resolve_csml_object::resolve_object, resolve_path,
@@ -47,6 +49,56 @@ fn exec_path_literal(
     }
 }
 
+fn spread_array(
+    literal: Literal,
+    interval: Interval,
+    data: &Data,
+) -> Result<Vec<Literal>, ErrorInfo> {
+    if literal.primitive.get_type() != PrimitiveType::PrimitiveArray {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!(
+                "{}, got {}",
+                ERROR_SPREAD_ARRAY,
+                literal.primitive.get_type().to_string()
+            ),
+        ));
+    }
+
+    Literal::get_value::<Vec<Literal>>(
+        &literal.primitive,
+        &data.context.flow,
+        interval,
+        ERROR_SPREAD_ARRAY.to_owned(),
+    )
+    .map(|array| array.to_owned())
+}
+
+fn spread_object(
+    literal: Literal,
+    interval: Interval,
+    data: &Data,
+) -> Result<HashMap<String, Literal>, ErrorInfo> {
+    if literal.primitive.get_type() != PrimitiveType::PrimitiveObject {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!(
+                "{}, got {}",
+                ERROR_SPREAD_OBJECT,
+                literal.primitive.get_type().to_string()
+            ),
+        ));
+    }
+
+    Literal::get_value::<HashMap<String, Literal>>(
+        &literal.primitive,
+        &data.context.flow,
+        interval,
+        ERROR_SPREAD_OBJECT.to_owned(),
+    )
+    .map(|object| object.to_owned())
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
@@ -99,6 +151,30 @@ pub fn expr_to_literal(
 
             exec_path_literal(&mut literal, dis_warnings, path, data, msg_data, sender)
         }
+        Expr::SpreadMapExpr(parts, range_interval) => {
+            let mut map = HashMap::new();
+            let mut is_secure = false;
+
+            for part in parts.iter() {
+                let (expr, interval) = match part {
+                    Expr::SpreadExpr(expr, interval) => (&**expr, *interval),
+                    part => (part, interval_from_expr(part)),
+                };
+
+                let lit = expr_to_literal(expr, dis_warnings, None, data, msg_data, sender)?;
+                if lit.secure_variable {
+                    is_secure = true;
+                }
+
+                // later keys override earlier ones
+                map.extend(spread_object(lit, interval, data)?);
+            }
+
+            let mut literal = PrimitiveObject::get_literal(&map, range_interval.to_owned());
+            literal.secure_variable = is_secure;
+
+            exec_path_literal(&mut literal, dis_warnings, path, data, msg_data, sender)
+        }
         Expr::ComplexLiteral(vec, range_interval) => {
             let mut string = get_string_from_complex_string(
                 vec,
@@ -114,7 +190,19 @@ pub fn expr_to_literal(
             let mut is_secure = false;
 
             for value in vec.iter() {
-                let lit = expr_to_literal(value, dis_warnings, None, data, msg_data, sender)?;
+                let lit = match value {
+                    Expr::SpreadExpr(expr, interval) => {
+                        let lit =
+                            expr_to_literal(expr, dis_warnings, None, data, msg_data, sender)?;
+                        if lit.secure_variable {
+                            is_secure = true;
+                        }
+
+                        array.append(&mut spread_array(lit, *interval, data)?);
+                        continue;
+                    }
+                    value => expr_to_literal(value, dis_warnings, None, data, msg_data, sender)?,
+                };
                 if lit.secure_variable {
                     is_secure = true;
                 }
@@ -130,6 +218,14 @@ pub fn expr_to_literal(
             let mut literal = evaluate_postfix(pretfix, expr, data, msg_data, sender)?;
             exec_path_literal(&mut literal, dis_warnings, path, data, msg_data, sender)
         }
+        Expr::TernaryExpr(cond, then, or_else, _) => {
+            let branch = match valid_condition(cond, data, msg_data, sender) {
+                true => then,
+                false => or_else,
+            };
+
+            expr_to_literal(branch, dis_warnings, path, data, msg_data, sender)
+        }
         Expr::InfixExpr(infix, exp_1, exp_2) => {
             let mut literal = evaluate_condition(infix, exp_1, exp_2, data, msg_data, sender)?;
             exec_path_literal(&mut literal, dis_warnings, path, data, msg_data, sender)
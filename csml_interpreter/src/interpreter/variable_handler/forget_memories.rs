@@ -5,15 +5,18 @@ pub fn forget_scope_memories(forget_mem: &ForgetMemory, data: &mut Data) {
         ForgetMemory::ALL => {
             data.step_vars.clear();
             data.context.current.clear();
+            data.context.conversation.clear();
         }
         ForgetMemory::SINGLE(memory) => {
             data.step_vars.remove(&memory.ident);
             data.context.current.remove(&memory.ident);
+            data.context.conversation.remove(&memory.ident);
         }
         ForgetMemory::LIST(memories) => {
             for memory in memories.iter() {
                 data.step_vars.remove(&memory.ident);
                 data.context.current.remove(&memory.ident);
+                data.context.conversation.remove(&memory.ident);
             }
         }
     }
@@ -61,14 +61,23 @@ pub fn gen_literal_from_event(
 
             Ok(lit)
         }
-        None => {
-            let mut lit =
-                PrimitiveString::get_literal(&data.event.content_value, interval.to_owned());
+        // the event coerced by a typed hold keeps its type
+        None => match &data.event_value {
+            Some(value) => {
+                let mut lit = value.to_owned();
+                lit.interval = interval;
 
-            lit.secure_variable = data.event.secure;
+                Ok(lit)
+            }
+            None => {
+                let mut lit =
+                    PrimitiveString::get_literal(&data.event.content_value, interval.to_owned());
 
-            Ok(lit)
-        }
+                lit.secure_variable = data.event.secure;
+
+                Ok(lit)
+            }
+        },
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::data::ast::{DoType, Expr, Function, IfStatement, Interval, ObjectType};
+use crate::data::ast::{DoType, Expr, Function, Interval, ObjectType};
 
 pub fn interval_from_expr(expr: &Expr) -> Interval {
     match expr {
@@ -12,22 +12,23 @@ pub fn interval_from_expr(expr: &Expr) -> Interval {
             ..
         } => *range_interval,
         Expr::VecExpr(_e, range_interval) => *range_interval,
+        Expr::SpreadExpr(_e, range_interval) => *range_interval,
+        Expr::SpreadMapExpr(_e, range_interval) => *range_interval,
+        Expr::TernaryExpr(_, _, _, range_interval) => *range_interval,
         Expr::ObjectExpr(fnexpr) => interval_from_reserved_fn(fnexpr),
         Expr::InfixExpr(_i, expr, _e) => interval_from_expr(expr), // RangeInterval ?
         Expr::PostfixExpr(_p, expr) => interval_from_expr(expr),   // RangeInterval ?
         Expr::PathExpr { literal, .. } => interval_from_expr(literal),
-        Expr::ForEachExpr(_, _, _, _, range_interval) => *range_interval,
+        Expr::ForEachExpr(_, _, _, _, _, range_interval) => *range_interval,
         Expr::WhileExpr(_, _, range_interval) => *range_interval,
+        Expr::MatchExpr(_, _, range_interval) => *range_interval,
+        Expr::RegexExpr(_, range_interval) => *range_interval,
+        Expr::TryCatchExpr(_, _, _, range_interval) => *range_interval,
+        Expr::RangeExpr(_, _, _, range_interval) => *range_interval,
         Expr::IdentExpr(ident) => ident.interval.to_owned(),
         Expr::LitExpr { literal, .. } => literal.interval.to_owned(),
-        Expr::IfExpr(ifstmt) => interval_from_if_stmt(ifstmt),
-    }
-}
-
-pub fn interval_from_if_stmt(ifstmt: &IfStatement) -> Interval {
-    match ifstmt {
-        IfStatement::IfStmt { ref cond, .. } => interval_from_expr(cond),
-        IfStatement::ElseStmt(_e, range_interval) => *range_interval,
+        Expr::IfExpr { range, .. } => *range,
+        Expr::Destructure(_, _, range_interval) => *range_interval,
     }
 }
 
@@ -42,14 +43,24 @@ pub fn interval_from_reserved_fn(reserved_fn: &ObjectType) -> Interval {
         ObjectType::Debug(_expr, interval) => interval.to_owned(),
         ObjectType::Log { interval, .. } => interval.to_owned(),
         ObjectType::Return(expr) => interval_from_expr(expr),
-        ObjectType::Remember(ident, ..) => ident.interval.to_owned(),
+        ObjectType::Remember(ident, ..)
+        | ObjectType::RememberConversation(ident, ..)
+        | ObjectType::RememberSecret(ident, ..) => ident.interval.to_owned(),
         ObjectType::Forget(_, interval) => interval.to_owned(),
         ObjectType::Assign(_assign, ident, ..) => interval_from_expr(ident),
         ObjectType::As(ident, ..) => ident.interval.to_owned(),
         ObjectType::BuiltIn(Function { interval, .. }) => interval.to_owned(),
         ObjectType::Hold(interval) => interval.to_owned(),
         ObjectType::HoldSecure(interval) => interval.to_owned(),
-        ObjectType::Break(interval) => interval.to_owned(),
-        ObjectType::Continue(interval) => interval.to_owned(),
+        ObjectType::HoldSchema(_schema, _secure, interval) => interval.to_owned(),
+        ObjectType::HoldExpect(_expected, _secure, interval) => interval.to_owned(),
+        ObjectType::HoldConfidence(_threshold, _else_goto, _secure, interval) => {
+            interval.to_owned()
+        }
+        ObjectType::Delay(_duration, interval) => interval.to_owned(),
+        ObjectType::Retry(_max, interval) => interval.to_owned(),
+        ObjectType::Assert(_condition, _message, interval) => interval.to_owned(),
+        ObjectType::Break(_, interval) => interval.to_owned(),
+        ObjectType::Continue(_, interval) => interval.to_owned(),
     }
 }
@@ -4,9 +4,14 @@ use crate::data::{ast::Identifier, Data, Literal, Memory, MemoryType, MessageDat
 use crate::error_format::*;
 use std::sync::mpsc;
 
+// the memories of the user are read before the memories of the conversation,
+// `coalesce_memory` reads the conversation first
 pub fn search_in_memory_type(name: &Identifier, data: &Data) -> Result<String, ErrorInfo> {
     match (
-        data.context.current.get(&name.ident),
+        data.context
+            .current
+            .get(&name.ident)
+            .or_else(|| data.context.conversation.get(&name.ident)),
         data.step_vars.get(&name.ident),
         data.flow.constants.contains_key(&name.ident),
     ) {
@@ -24,7 +29,13 @@ pub fn search_var_memory<'a>(
     name: Identifier,
     data: &'a mut Data,
 ) -> Result<&'a mut Literal, ErrorInfo> {
-    match data.context.current.get_mut(&name.ident) {
+    let scope = if data.context.current.contains_key(&name.ident) {
+        &mut data.context.current
+    } else {
+        &mut data.context.conversation
+    };
+
+    match scope.get_mut(&name.ident) {
         Some(lit) => {
             lit.interval = name.interval;
             Ok(lit)
@@ -46,6 +57,18 @@ pub fn save_literal_in_mem(
     sender: &Option<mpsc::Sender<MSG>>,
 ) {
     match mem_type {
+        // the updates of a memory of the conversation stay in the conversation
+        MemoryType::Remember
+            if update
+                && !data.context.current.contains_key(&name)
+                && data.context.conversation.contains_key(&name) =>
+        {
+            MSG::send(
+                sender,
+                MSG::RememberConversation(Memory::new(name.clone(), lit.clone())),
+            );
+            data.context.conversation.insert(name, lit);
+        }
         MemoryType::Remember if update => {
             // save new value in current memory
             msg_data.add_to_memory(&name, lit.clone());
@@ -1,16 +1,138 @@
 use crate::data::{
     ast::{Expr, Infix, Pretfix},
     position::Position,
-    primitive::boolean::PrimitiveBoolean,
+    primitive::{
+        boolean::PrimitiveBoolean, tools_time::get_duration_literal, PrimitiveInt, PrimitiveObject,
+        PrimitiveType,
+    },
     warnings::DisplayWarnings,
     Data, Literal, MessageData, MSG,
 };
-use crate::error_format::{gen_error_info, ErrorInfo};
+use crate::error_format::{gen_error_info, ErrorInfo, ERROR_ILLEGAL_OPERATION};
 use crate::interpreter::variable_handler::{
     expr_to_literal, interval::interval_from_expr, match_literals::match_obj,
 };
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::sync::mpsc;
 
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_time_or_duration(literal: &Literal) -> bool {
+    literal.content_type == "time" || literal.content_type == "duration"
+}
+
+// time and duration objects are named after their content type in the errors
+fn get_type_name(literal: &Literal) -> String {
+    match is_time_or_duration(literal) {
+        true => literal.content_type.to_owned(),
+        false => literal.primitive.get_type().to_string(),
+    }
+}
+
+fn get_milliseconds(literal: &Literal) -> Option<i64> {
+    let object = literal
+        .primitive
+        .get_value()
+        .downcast_ref::<HashMap<String, Literal>>()?;
+    let milliseconds = object.get("milliseconds")?;
+
+    milliseconds
+        .primitive
+        .get_value()
+        .downcast_ref::<i64>()
+        .copied()
+}
+
+// the time keeps the timezone of the time operand
+fn set_time_milliseconds(time: &Literal, milliseconds: i64) -> Literal {
+    let mut object = match time
+        .primitive
+        .get_value()
+        .downcast_ref::<HashMap<String, Literal>>()
+    {
+        Some(object) => object.clone(),
+        None => HashMap::new(),
+    };
+    object.insert(
+        "milliseconds".to_owned(),
+        PrimitiveInt::get_literal(milliseconds, time.interval),
+    );
+
+    let mut literal = PrimitiveObject::get_literal(&object, time.interval);
+    literal.set_content_type("time");
+
+    literal
+}
+
+fn compare_milliseconds(infix: &Infix, ordering: Ordering) -> Option<bool> {
+    match infix {
+        Infix::Equal => Some(ordering == Ordering::Equal),
+        Infix::NotEqual => Some(ordering != Ordering::Equal),
+        Infix::GreaterThan => Some(ordering == Ordering::Greater),
+        Infix::GreaterThanEqual => Some(ordering != Ordering::Less),
+        Infix::LessThan => Some(ordering == Ordering::Less),
+        Infix::LessThanEqual => Some(ordering != Ordering::Greater),
+        _ => None,
+    }
+}
+
+// arithmetic and comparisons of time objects and durations, on their milliseconds:
+// time + duration, time - duration and duration +/- duration, time - time is a duration.
+// None for the other operations, which keep the behavior of objects
+fn evaluate_time_infix(
+    flow_name: &str,
+    infix: &Infix,
+    lhs: &Literal,
+    rhs: &Literal,
+) -> Option<Result<Literal, ErrorInfo>> {
+    let (lhs_type, rhs_type) = (get_type_name(lhs), get_type_name(rhs));
+    let milliseconds = (get_milliseconds(lhs), get_milliseconds(rhs));
+
+    let result = match (infix, lhs_type.as_str(), rhs_type.as_str(), milliseconds) {
+        (Infix::Addition, "time", "duration", (Some(lhs_ms), Some(rhs_ms))) => {
+            set_time_milliseconds(lhs, lhs_ms.checked_add(rhs_ms)?)
+        }
+        (Infix::Addition, "duration", "time", (Some(lhs_ms), Some(rhs_ms))) => {
+            set_time_milliseconds(rhs, lhs_ms.checked_add(rhs_ms)?)
+        }
+        (Infix::Subtraction, "time", "duration", (Some(lhs_ms), Some(rhs_ms))) => {
+            set_time_milliseconds(lhs, lhs_ms.checked_sub(rhs_ms)?)
+        }
+        (Infix::Subtraction, "time", "time", (Some(lhs_ms), Some(rhs_ms)))
+        | (Infix::Subtraction, "duration", "duration", (Some(lhs_ms), Some(rhs_ms))) => {
+            get_duration_literal(lhs_ms.checked_sub(rhs_ms)?, lhs.interval)
+        }
+        (Infix::Addition, "duration", "duration", (Some(lhs_ms), Some(rhs_ms))) => {
+            get_duration_literal(lhs_ms.checked_add(rhs_ms)?, lhs.interval)
+        }
+        (Infix::Addition, ..) | (Infix::Subtraction, ..) => {
+            let operator = match infix {
+                Infix::Addition => "+",
+                _ => "-",
+            };
+
+            return Some(Err(gen_error_info(
+                Position::new(lhs.interval, flow_name),
+                format!(
+                    "{} {} {} {}",
+                    ERROR_ILLEGAL_OPERATION, lhs_type, operator, rhs_type
+                ),
+            )));
+        }
+        (_, lhs_type, rhs_type, (Some(lhs_ms), Some(rhs_ms))) if lhs_type == rhs_type => {
+            let result = compare_milliseconds(infix, lhs_ms.cmp(&rhs_ms))?;
+
+            PrimitiveBoolean::get_literal(result, lhs.interval)
+        }
+        _ => return None,
+    };
+
+    Some(Ok(result))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
@@ -21,6 +143,14 @@ pub fn evaluate_infix(
     lhs: Result<Literal, ErrorInfo>,
     rhs: Result<Literal, ErrorInfo>,
 ) -> Result<Literal, ErrorInfo> {
+    if let (Ok(lhs), Ok(rhs)) = (&lhs, &rhs) {
+        if is_time_or_duration(lhs) || is_time_or_duration(rhs) {
+            if let Some(result) = evaluate_time_infix(flow_name, infix, lhs, rhs) {
+                return result;
+            }
+        }
+    }
+
     match (infix, lhs, rhs) {
         (Infix::Equal, Ok(lhs), Ok(rhs)) => Ok(PrimitiveBoolean::get_literal(
             lhs.primitive == rhs.primitive,
@@ -103,7 +233,7 @@ pub fn evaluate_infix(
                 Err(err) => Err(gen_error_info(Position::new(lhs.interval, flow_name), err)),
             }
         }
-        (Infix::Remainder, Ok(lhs), Ok(rhs)) => {
+        (Infix::Remainder(interval), Ok(lhs), Ok(rhs)) => {
             let primitive = lhs.primitive % rhs.primitive;
 
             match primitive {
@@ -114,7 +244,39 @@ pub fn evaluate_infix(
                     secure_variable: false,
                     interval: lhs.interval,
                 }),
-                Err(err) => Err(gen_error_info(Position::new(lhs.interval, flow_name), err)),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
+            }
+        }
+        (Infix::IntegerDivision(interval), Ok(lhs), Ok(rhs)) => {
+            let primitive = lhs.primitive.int_div(&*rhs.primitive);
+
+            match primitive {
+                Ok(primitive) => Ok(Literal {
+                    content_type: primitive.get_type().to_string(),
+                    primitive,
+                    additional_info: None,
+                    secure_variable: false,
+                    interval: lhs.interval,
+                }),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
+            }
+        }
+        (Infix::BitwiseAnd(interval), Ok(lhs), Ok(rhs))
+        | (Infix::BitwiseOr(interval), Ok(lhs), Ok(rhs))
+        | (Infix::BitwiseXor(interval), Ok(lhs), Ok(rhs))
+        | (Infix::ShiftLeft(interval), Ok(lhs), Ok(rhs))
+        | (Infix::ShiftRight(interval), Ok(lhs), Ok(rhs)) => {
+            let primitive = lhs.primitive.bitwise(infix, &*rhs.primitive);
+
+            match primitive {
+                Ok(primitive) => Ok(Literal {
+                    content_type: primitive.get_type().to_string(),
+                    primitive,
+                    additional_info: None,
+                    secure_variable: false,
+                    interval: lhs.interval,
+                }),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
             }
         }
 
@@ -126,6 +288,10 @@ pub fn evaluate_infix(
             lhs.primitive.as_bool() & rhs.primitive.as_bool(),
             lhs.interval,
         )),
+        (Infix::NullCoalescing, Ok(lhs), Ok(rhs)) => match lhs.primitive.get_type() {
+            PrimitiveType::PrimitiveNull => Ok(rhs),
+            _ => Ok(lhs),
+        },
         (Infix::Match, Ok(ref lhs), Ok(ref rhs)) => Ok(PrimitiveBoolean::get_literal(
             match_obj(lhs, rhs),
             lhs.interval,
@@ -138,6 +304,24 @@ pub fn evaluate_infix(
     }
 }
 
+pub fn evaluate_null_coalescing(
+    lhs: &Expr,
+    rhs: &Expr,
+    data: &mut Data,
+    msg_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<Literal, ErrorInfo> {
+    // the right side is only evaluated if the left side is null or not saved in memory
+    let literal = expr_to_literal(lhs, &DisplayWarnings::Off, None, data, msg_data, sender)?;
+
+    match literal.primitive.get_type() {
+        PrimitiveType::PrimitiveNull => {
+            expr_to_literal(rhs, &DisplayWarnings::Off, None, data, msg_data, sender)
+        }
+        _ => Ok(literal),
+    }
+}
+
 pub fn evaluate_postfix(
     postfixes: &[Pretfix],
     expr: &Box<Expr>,
@@ -1,8 +1,9 @@
 use crate::data::{
     ast::*,
     data::{init_child_context, init_child_scope, Data},
-    error_info::ErrorInfo,
+    error_info::{ErrorCode, ErrorInfo},
     literal::create_error_info,
+    native_function::{get_native_function, NativeFunction},
     primitive::PrimitiveClosure,
     tokens::*,
     warnings::DisplayWarnings,
@@ -12,11 +13,16 @@ use crate::error_format::*;
 use crate::interpreter::{
     builtins::{match_builtin, match_native_builtin},
     function_scope::exec_fn_in_new_scope,
+    json_to_rust::json_to_literal,
+    variable_handler::expr_to_literal::expr_to_literal,
     variable_handler::resolve_fn_args,
     variable_handler::save_literal_in_mem,
 };
 
-use std::{collections::HashMap, sync::mpsc};
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc},
+};
 
 ////////////////////////////////////////////////////////////////////////////////
 // Local Struct
@@ -26,9 +32,17 @@ enum ObjType {
     NativeComponent,
     BuiltIn,
     BuiltInWithoutWarnings,
-    Function { fn_args: Vec<String>, scope: Expr },
+    Function {
+        fn_args: Vec<String>,
+        defaults: HashMap<String, Expr>,
+        scope: Expr,
+    },
     Import,
-    Closure { fn_args: Vec<String>, scope: Expr },
+    Closure {
+        fn_args: Vec<String>,
+        scope: Expr,
+    },
+    NativeFunction(Arc<dyn NativeFunction>),
     Error,
 }
 ////////////////////////////////////////////////////////////////////////////////
@@ -42,6 +56,7 @@ fn check_for_function(name: &str, data: &Data) -> Option<(InstructionScope, Expr
         .get_key_value(&InstructionScope::FunctionScope {
             name: name.to_owned(),
             args: Vec::new(),
+            defaults: HashMap::new(),
         }) {
         Some((i, e)) => Some((i.to_owned(), e.to_owned())),
         None => None,
@@ -65,14 +80,19 @@ fn get_type<'a>(name: &'a str, interval: Interval, data: &'a Data) -> ObjType {
         InstructionScope::FunctionScope {
             name: _,
             args: fn_args,
+            defaults,
         },
         scope,
     )) = check_for_function(name, data)
     {
-        return ObjType::Function { fn_args, scope };
+        return ObjType::Function {
+            fn_args,
+            defaults,
+            scope,
+        };
     }
 
-    if let Some((_fn_args, _expr, _new_flow)) = check_for_import(name, interval, data) {
+    if let Some((_fn_args, _defaults, _expr, _new_flow)) = check_for_import(name, interval, data) {
         return ObjType::Import;
     }
 
@@ -80,6 +100,10 @@ fn get_type<'a>(name: &'a str, interval: Interval, data: &'a Data) -> ObjType {
         return ObjType::Closure { fn_args, scope };
     }
 
+    if let Some(function) = get_native_function(name) {
+        return ObjType::NativeFunction(function);
+    }
+
     ObjType::Error
 }
 
@@ -87,20 +111,28 @@ fn get_function<'a>(
     flow: &'a Flow,
     fn_name: &str,
     original_name: &Option<String>,
-) -> Option<(Vec<String>, Expr, &'a Flow)> {
+) -> Option<(Vec<String>, HashMap<String, Expr>, Expr, &'a Flow)> {
     let name = match original_name {
         Some(original_name) => original_name.to_owned(),
         None => fn_name.to_owned(),
     };
 
-    if let (InstructionScope::FunctionScope { name: _, args }, expr) = flow
+    if let (
+        InstructionScope::FunctionScope {
+            name: _,
+            args,
+            defaults,
+        },
+        expr,
+    ) = flow
         .flow_instructions
         .get_key_value(&InstructionScope::FunctionScope {
             name,
             args: Vec::new(),
+            defaults: HashMap::new(),
         })?
     {
-        return Some((args.to_owned(), expr.to_owned(), flow));
+        return Some((args.to_owned(), defaults.to_owned(), expr.to_owned(), flow));
     }
     None
 }
@@ -110,7 +142,7 @@ fn search_function<'a>(
     bot_flows: &'a HashMap<String, Flow>,
     extern_flows: &'a HashMap<String, Flow>,
     import: &ImportScope,
-) -> Result<(Vec<String>, Expr, &'a Flow), ErrorInfo> {
+) -> Result<(Vec<String>, HashMap<String, Expr>, Expr, &'a Flow), ErrorInfo> {
     match &import.from_flow {
         FromFlow::Normal(flow_name) => match bot_flows.get(flow_name) {
             Some(flow) => {
@@ -123,6 +155,7 @@ fn search_function<'a>(
                 get_function(flow, &import.name, &import.original_name).ok_or(ErrorInfo {
                     position: Position::new(import.interval, origin_flow_name),
                     message: error_message,
+                    code: ErrorCode::Other,
                     additional_info: Some(error_info),
                 })
             }
@@ -136,6 +169,7 @@ fn search_function<'a>(
                 Err(ErrorInfo {
                     position: Position::new(import.interval, origin_flow_name),
                     message: error_message,
+                    code: ErrorCode::Other,
                     additional_info: Some(error_info),
                 })
             }
@@ -151,6 +185,7 @@ fn search_function<'a>(
                 get_function(flow, &import.name, &import.original_name).ok_or(ErrorInfo {
                     position: Position::new(import.interval, origin_flow_name),
                     message: error_message,
+                    code: ErrorCode::Other,
                     additional_info: Some(error_info),
                 })
             }
@@ -164,10 +199,26 @@ fn search_function<'a>(
                 Err(ErrorInfo {
                     position: Position::new(import.interval, origin_flow_name),
                     message: error_message,
+                    code: ErrorCode::Other,
                     additional_info: Some(error_info),
                 })
             }
         },
+        // the conditional imports are resolved when the bot is loaded
+        FromFlow::Conditional(condition) => {
+            let error_message = format!(
+                "function '{}' not found, the import condition '{}.{}' was not resolved",
+                import.name, _ENV, condition.key
+            );
+            let error_info = create_error_info(&error_message, Interval::default());
+
+            Err(ErrorInfo {
+                position: Position::new(import.interval, origin_flow_name),
+                message: error_message,
+                code: ErrorCode::Other,
+                additional_info: Some(error_info),
+            })
+        }
         FromFlow::None => {
             for (_name, flow) in bot_flows.iter() {
                 if let Some(values) = get_function(flow, &import.name, &import.original_name) {
@@ -180,6 +231,7 @@ fn search_function<'a>(
             Err(ErrorInfo {
                 position: Position::new(import.interval, origin_flow_name),
                 message: error_message,
+                code: ErrorCode::Other,
                 additional_info: Some(error_info),
             })
         }
@@ -190,7 +242,7 @@ fn check_for_import<'a>(
     name: &str,
     interval: Interval,
     data: &'a Data,
-) -> Option<(Vec<String>, Expr, &'a Flow)> {
+) -> Option<(Vec<String>, HashMap<String, Expr>, Expr, &'a Flow)> {
     match data
         .flow
         .flow_instructions
@@ -202,7 +254,9 @@ fn check_for_import<'a>(
         })) {
         Some((InstructionScope::ImportScope(import), _expr)) => {
             match search_function(&data.context.flow, data.flows, data.extern_flows, import) {
-                Ok((fn_args, expr, new_flow)) => Some((fn_args, expr, new_flow)), // if new_flow == data.flow {
+                Ok((fn_args, defaults, expr, new_flow)) => {
+                    Some((fn_args, defaults, expr, new_flow))
+                } // if new_flow == data.flow {
                 _err => None,
             }
         }
@@ -231,6 +285,78 @@ fn check_for_closure<'a>(
     }
 }
 
+// the arguments are given to the native function by position
+fn call_native_function(
+    name: &str,
+    function: &dyn NativeFunction,
+    args: ArgsType,
+    interval: Interval,
+    flow_name: &str,
+) -> Result<Literal, ErrorInfo> {
+    let map = match args {
+        ArgsType::Normal(map) => map,
+        ArgsType::Named(_) => {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                ERROR_NATIVE_FUNCTION_ARGS.to_owned(),
+            ))
+        }
+    };
+
+    let json_args = (0..map.len())
+        .filter_map(|index| map.get(&format!("arg{}", index)))
+        .map(|arg| arg.primitive.to_json())
+        .collect();
+
+    match function.call(json_args) {
+        Ok(value) => json_to_literal(&value, interval, flow_name),
+        Err(err) => Err(gen_error_info(
+            Position::new(interval, flow_name),
+            format!("{} [{}]: {}", ERROR_NATIVE_FUNCTION, name, err),
+        )),
+    }
+}
+
+fn check_fn_args(
+    fn_args: &[String],
+    defaults: &HashMap<String, Expr>,
+    args: &ArgsType,
+    interval: Interval,
+    flow_name: &str,
+) -> Result<(), ErrorInfo> {
+    // arguments without a default value must be given by the caller
+    for (index, name) in fn_args.iter().enumerate() {
+        if args.get(name, index).is_none() && !defaults.contains_key(name) {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                ERROR_FN_ARGS.to_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// named arguments must match one of the arguments of the function
+fn check_named_args(fn_args: &[String], args: &Expr, flow_name: &str) -> Result<(), ErrorInfo> {
+    if let Expr::VecExpr(vec, ..) = args {
+        for arg in vec.iter() {
+            if let Expr::ObjectExpr(ObjectType::Assign(_, name, _)) = arg {
+                if let Expr::IdentExpr(Identifier { ident, interval }) = &**name {
+                    if !fn_args.contains(ident) {
+                        return Err(gen_error_info(
+                            Position::new(*interval, flow_name),
+                            format!("{} '{}'", ERROR_FN_UNKNOWN_ARG, ident),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
@@ -243,18 +369,58 @@ pub fn insert_args_in_scope_memory(
     sender: &Option<mpsc::Sender<MSG>>,
 ) {
     for (index, name) in fn_args.iter().enumerate() {
-        let value = args.get(name, index).unwrap();
+        if let Some(value) = args.get(name, index) {
+            save_literal_in_mem(
+                value.to_owned(),
+                name.to_owned(),
+                &MemoryType::Use,
+                true,
+                new_scope_data,
+                msg_data,
+                sender,
+            );
+        }
+    }
+}
 
-        save_literal_in_mem(
-            value.to_owned(),
-            name.to_owned(),
-            &MemoryType::Use,
-            true,
-            new_scope_data,
-            msg_data,
-            sender,
-        );
+pub fn insert_default_args_in_scope_memory(
+    new_scope_data: &mut Data,
+    fn_args: &[String],
+    defaults: &HashMap<String, Expr>,
+    args: &ArgsType,
+    msg_data: &mut MessageData,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<(), ErrorInfo> {
+    // default values are evaluated from left to right in the function scope,
+    // after the arguments given by the caller
+    for (index, name) in fn_args.iter().enumerate() {
+        if args.get(name, index).is_some() {
+            continue;
+        }
+
+        if let Some(expr) = defaults.get(name) {
+            let value = expr_to_literal(
+                expr,
+                &DisplayWarnings::On,
+                None,
+                new_scope_data,
+                msg_data,
+                sender,
+            )?;
+
+            save_literal_in_mem(
+                value,
+                name.to_owned(),
+                &MemoryType::Use,
+                true,
+                new_scope_data,
+                msg_data,
+                sender,
+            );
+        }
     }
+
+    Ok(())
 }
 
 pub fn insert_memories_in_scope_memory(
@@ -325,12 +491,18 @@ pub fn resolve_object(
             Ok(MSG::send_error_msg(&sender, msg_data, value))
         }
 
-        ObjType::Function { fn_args, scope } => {
+        ObjType::Function {
+            fn_args,
+            defaults,
+            scope,
+        } => {
+            check_named_args(&fn_args, args, &data.context.flow)?;
             let resolved_args =
                 resolve_fn_args(args, data, msg_data, &DisplayWarnings::On, sender)?;
             exec_fn(
                 &scope,
                 &fn_args,
+                &defaults,
                 resolved_args,
                 None,
                 interval,
@@ -349,12 +521,18 @@ pub fn resolve_object(
                 ERROR_FN_ARGS.to_owned(),
             );
 
-            let (fn_args, expr, new_flow) =
-                check_for_import(name, interval, data).ok_or(error.clone())?;
+            let (fn_args, defaults, expr, new_flow) =
+                check_for_import(name, interval, data).ok_or(error)?;
 
-            if fn_args.len() > resolved_args.len() {
-                return Err(error);
-            }
+            check_named_args(&fn_args, args, &data.context.flow)?;
+
+            check_fn_args(
+                &fn_args,
+                &defaults,
+                &resolved_args,
+                interval,
+                &data.context.flow,
+            )?;
 
             let mut context = init_child_context(&data);
             let mut step_count = data.step_count.clone();
@@ -368,17 +546,27 @@ pub fn resolve_object(
                 msg_data,
                 sender,
             );
+            insert_default_args_in_scope_memory(
+                &mut new_scope_data,
+                &fn_args,
+                &defaults,
+                &resolved_args,
+                msg_data,
+                sender,
+            )?;
 
             exec_fn_in_new_scope(&expr, &mut new_scope_data, msg_data, sender)
         }
 
         ObjType::Closure { fn_args, scope } => {
+            check_named_args(&fn_args, args, &data.context.flow)?;
             let resolved_args =
                 resolve_fn_args(args, data, msg_data, &DisplayWarnings::On, sender)?;
 
             exec_fn(
                 &scope,
                 &fn_args,
+                &HashMap::new(),
                 resolved_args,
                 None,
                 interval,
@@ -388,6 +576,21 @@ pub fn resolve_object(
             )
         }
 
+        ObjType::NativeFunction(function) => {
+            let resolved_args =
+                resolve_fn_args(args, data, msg_data, &DisplayWarnings::On, sender)?;
+
+            let value = call_native_function(
+                name,
+                function.as_ref(),
+                resolved_args,
+                interval,
+                &data.context.flow,
+            );
+
+            Ok(MSG::send_error_msg(&sender, msg_data, value))
+        }
+
         ObjType::Error => {
             let err = gen_error_info(
                 Position::new(interval, &data.context.flow),
@@ -406,6 +609,7 @@ pub fn resolve_object(
 pub fn exec_fn(
     scope: &Expr,
     fn_args: &[String],
+    defaults: &HashMap<String, Expr>,
     args: ArgsType,
     memories_to_insert: Option<HashMap<String, Literal>>,
     interval: Interval,
@@ -413,12 +617,7 @@ pub fn exec_fn(
     msg_data: &mut MessageData,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> Result<Literal, ErrorInfo> {
-    if fn_args.len() > args.len() {
-        return Err(gen_error_info(
-            Position::new(interval, &data.context.flow),
-            ERROR_FN_ARGS.to_owned(),
-        ));
-    }
+    check_fn_args(fn_args, defaults, &args, interval, &data.context.flow)?;
 
     let mut context = init_child_context(&data);
     let mut step_count = data.step_count.clone();
@@ -427,6 +626,14 @@ pub fn exec_fn(
     if let Some(memories) = memories_to_insert {
         insert_memories_in_scope_memory(&mut new_scope_data, memories, msg_data, sender);
     }
+    insert_default_args_in_scope_memory(
+        &mut new_scope_data,
+        fn_args,
+        defaults,
+        &args,
+        msg_data,
+        sender,
+    )?;
 
     let res = exec_fn_in_new_scope(scope, &mut new_scope_data, msg_data, sender);
 
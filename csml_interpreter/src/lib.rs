@@ -3,6 +3,7 @@ pub mod error_format;
 pub mod fold_bot;
 pub mod interpreter;
 pub mod linter;
+mod lock;
 pub mod parser;
 
 pub use data::csml_logs;
@@ -10,18 +11,22 @@ pub use interpreter::components::load_components;
 pub use parser::step_checksum::get_step;
 
 use interpreter::{interpret_scope, json_to_literal};
-use parser::parse_flow;
+use parser::{parse_flow, parse_import::resolve_conditional_imports};
 
 use data::ast::{Expr, Flow, InsertStep, InstructionScope, Interval};
 use data::context::{get_hashmap_from_mem, ContextStepInfo};
-use data::error_info::ErrorInfo;
+use data::error_info::{ErrorCode, ErrorInfo};
 use data::event::Event;
 use data::literal::create_error_info;
 use data::message_data::MessageData;
 use data::msg::MSG;
+use data::primitive::{PrimitiveArray, PrimitiveObject, PrimitiveString};
 use data::CsmlResult;
 use data::{csml_bot::CsmlBot, CsmlFlow};
-use data::{Context, Data, Position, STEP_LIMIT};
+use data::{
+    CompiledFlow, Context, Data, ExecutionBudget, Literal, MemoryDiff, MessageObserver,
+    NativeFunction, Position, RandomSource, RunOptions, GOTO_LOOP_LIMIT, STEP_LIMIT,
+};
 use error_format::*;
 use fold_bot::fold_bot as fold;
 use linter::{linter::lint_bot, FlowToValidate};
@@ -41,6 +46,8 @@ fn execute_step(
     mut data: &mut Data,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> MessageData {
+    let _span = tracing::debug_span!("csml_step", flow = %data.context.flow, step).entered();
+
     // stop execution if step_count >= STEP_LIMIT in order to avoid infinite loops
     if *data.step_count >= data.step_limit {
         let msg_data = Err(gen_error_info(
@@ -67,13 +74,21 @@ fn execute_step(
                 Interval::new_as_u32(0, 0, 0, None, None),
                 &data.context.flow,
             ),
-            format!("[{}] {}", step, ERROR_STEP_EXIST),
+            format!(
+                "[{}] {} in flow: [{}]",
+                step, ERROR_STEP_EXIST, data.context.flow
+            ),
         )),
     };
 
     if let Ok(msg_data) = &mut msg_data {
         match &mut msg_data.exit_condition {
             Some(condition) if *condition == ExitCondition::Goto => {
+                tracing::debug!(
+                    flow = %data.context.flow,
+                    step = %data.context.step.get_step(),
+                    "goto"
+                );
                 msg_data.exit_condition = None;
             }
             Some(_) => (),
@@ -104,16 +119,112 @@ fn get_step_limit(event: &Event) -> usize {
     }
 }
 
+fn get_goto_loop_limit(event: &Event) -> usize {
+    match (event.goto_loop_limit, env::var("GOTO_LOOP_LIMIT").ok()) {
+        (Some(limit), _) => limit,
+        (None, Some(limit)) => limit.parse::<usize>().unwrap_or(GOTO_LOOP_LIMIT),
+        _ => GOTO_LOOP_LIMIT,
+    }
+}
+
+// a step entered more than goto_loop_limit times in a single run is in a goto loop,
+// the steps entered since its previous entry are the cycle of the loop
+fn check_goto_loop(
+    step_history: &mut Vec<(String, String)>,
+    flow: &str,
+    step: &str,
+    goto_loop_limit: usize,
+) -> Result<(), ErrorInfo> {
+    let is_step =
+        |(entry_flow, entry_step): &(String, String)| entry_flow == flow && entry_step == step;
+
+    let entries = step_history.iter().filter(|entry| is_step(entry)).count();
+    let previous_entry = step_history.iter().rposition(|entry| is_step(entry));
+    step_history.push((flow.to_owned(), step.to_owned()));
+
+    let cycle = match previous_entry {
+        Some(index) if entries >= goto_loop_limit => &step_history[index..],
+        _ => return Ok(()),
+    };
+
+    let interval = Interval::new_as_u32(0, 0, 0, None, None);
+    let names: Vec<String> = cycle
+        .iter()
+        .map(|(entry_flow, entry_step)| {
+            if entry_flow == flow {
+                entry_step.to_owned()
+            } else {
+                format!("{}.{}", entry_flow, entry_step)
+            }
+        })
+        .collect();
+
+    let mut err = gen_error_info(
+        Position::new(interval, flow),
+        format!(
+            "{}: step [{}] was entered more than {} times without waiting for an event, cycle: {}",
+            ERROR_GOTO_LOOP,
+            step,
+            goto_loop_limit,
+            names.join(" -> ")
+        ),
+    )
+    .with_code(ErrorCode::GotoLoop);
+
+    let cycle: Vec<Literal> = cycle
+        .iter()
+        .map(|(entry_flow, entry_step)| {
+            let mut object = HashMap::new();
+            object.insert(
+                "flow".to_owned(),
+                PrimitiveString::get_literal(entry_flow, interval),
+            );
+            object.insert(
+                "step".to_owned(),
+                PrimitiveString::get_literal(entry_step, interval),
+            );
+
+            PrimitiveObject::get_literal(&object, interval)
+        })
+        .collect();
+    err.add_info("cycle", PrimitiveArray::get_literal(&cycle, interval));
+
+    Err(err)
+}
+
+// the execution budget is unlimited unless set in the event or with the
+// INSTRUCTION_LIMIT and TIME_LIMIT (in milliseconds) env vars
+fn get_execution_budget(event: &Event) -> ExecutionBudget {
+    let instruction_limit = match event.instruction_limit {
+        Some(limit) => Some(limit),
+        None => env::var("INSTRUCTION_LIMIT")
+            .ok()
+            .and_then(|limit| limit.parse::<usize>().ok()),
+    };
+    let time_limit = match event.time_limit {
+        Some(limit) => Some(limit),
+        None => env::var("TIME_LIMIT")
+            .ok()
+            .and_then(|limit| limit.parse::<u64>().ok()),
+    };
+
+    ExecutionBudget::new(instruction_limit, time_limit)
+}
+
 fn get_flow_ast<'a, 'b>(
     flows: &'a HashMap<String, Flow>,
     flow: &'b str,
+    step: &'b str,
     bot_id: &'b str,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> Result<&'a Flow, MessageData> {
     match flows.get(flow) {
         Some(result) => Ok(result),
         None => {
-            let error_message = format!("flow: [{}] does not exist in bot: [{}]", flow, bot_id);
+            let error_message = format!(
+                "flow: [{}] does not exist in bot: [{}], can not go to step: [{}]",
+                flow, bot_id, step
+            );
             let error_info = create_error_info(&error_message, Interval::default());
 
             Err(MessageData::error_to_message(
@@ -123,6 +234,7 @@ fn get_flow_ast<'a, 'b>(
                         interval: Interval::default(),
                     },
                     message: error_message,
+                    code: ErrorCode::Other,
                     additional_info: Some(error_info),
                 }),
                 &sender,
@@ -177,7 +289,7 @@ fn get_inserted_ast<'a, 'b>(
             }
         }
         ContextStepInfo::InsertedStep { step, flow } => {
-            match get_flow_ast(&flows, &flow, bot_id, &sender) {
+            match get_flow_ast(&flows, &flow, &step, bot_id, &sender) {
                 Ok(inserted_ast) => {
                     let missing_step = inserted_ast
                         .flow_instructions
@@ -223,9 +335,15 @@ pub fn validate_bot(bot: &CsmlBot) -> CsmlResult {
     let mut modules = vec![];
     let mut errors = Vec::new();
     let mut imports = Vec::new();
+    let flow_names: Vec<&str> = bot.flows.iter().map(|flow| flow.name.as_str()).collect();
 
     for flow in bot.flows.iter() {
-        match parse_flow(&flow.content, &flow.name) {
+        let ast_flow = parse_flow(&flow.content, &flow.name).and_then(|mut ast_flow| {
+            resolve_conditional_imports(&mut ast_flow, &flow.name, &bot.env, &flow_names)?;
+            Ok(ast_flow)
+        });
+
+        match ast_flow {
             Ok(ast_flow) => {
                 for (scope, ..) in ast_flow.flow_instructions.iter() {
                     if let InstructionScope::ImportScope(import_scope) = scope {
@@ -441,12 +559,138 @@ pub fn search_for_modules(bot: &mut CsmlBot) -> Result<(), String> {
 
 pub fn interpret(
     bot: CsmlBot,
+    context: Context,
+    event: Event,
+    sender: Option<mpsc::Sender<MSG>>,
+) -> MessageData {
+    let (flows, extern_flows) = get_flows(&bot);
+
+    run_interpreter(bot, flows, extern_flows, context, event, sender, None)
+}
+
+/// Run a single flow from its source, with the native components. The flow is named after
+/// the flow of the context, and the result is the one of `MessageData::to_json_value`:
+/// {"memories": [{"key": ..., "value": ...}], "messages": [...]}
+pub fn run_flow(
+    flow_source: &str,
+    mut event: Event,
+    mut context: Context,
+    options: RunOptions,
+) -> Result<serde_json::Value, ErrorInfo> {
+    let native_components = load_components()?;
+    let flow = CsmlFlow::new(&context.flow, &context.flow, flow_source, Vec::default());
+
+    let bot = CsmlBot::new(
+        "run_flow",
+        "run_flow",
+        None,
+        vec![flow],
+        Some(native_components),
+        None,
+        &context.flow,
+        None,
+        None,
+        options.env.clone(),
+        None,
+        None,
+    );
+    let (flows, extern_flows) = get_flows(&bot);
+
+    // a flow that does not parse is reported by the run
+    if let Some(flow) = flows.get(&context.flow) {
+        options.apply_entry_step(flow, &mut context)?;
+    }
+    options.apply_limits(&mut event);
+    options.apply_now(&mut context);
+
+    Ok(run_interpreter(bot, flows, extern_flows, context, event, None, None).to_json_value())
+}
+
+/// Compile a parsed flow (see `parser::parse_flow`) so the host can cache it
+/// and run it with `run_compiled_flow`, without parsing its source again
+pub fn compile_flow(flow: &Flow) -> CompiledFlow {
+    CompiledFlow::new(flow)
+}
+
+/// Run a compiled flow like `run_flow` runs its source, the result is the same.
+/// A flow compiled with another version of the ast (`AST_VERSION`) is rejected
+pub fn run_compiled_flow(
+    compiled_flow: &CompiledFlow,
+    mut event: Event,
+    mut context: Context,
+    options: RunOptions,
+) -> Result<serde_json::Value, ErrorInfo> {
+    compiled_flow.validate(&context.flow)?;
+    options.apply_entry_step(&compiled_flow.flow, &mut context)?;
+
+    let native_components = load_components()?;
+    let bot = CsmlBot::new(
+        "run_flow",
+        "run_flow",
+        None,
+        vec![],
+        Some(native_components),
+        None,
+        &context.flow,
+        None,
+        None,
+        options.env.clone(),
+        None,
+        None,
+    );
+
+    let mut flows = HashMap::new();
+    flows.insert(context.flow.to_owned(), compiled_flow.flow.to_owned());
+
+    options.apply_limits(&mut event);
+    options.apply_now(&mut context);
+
+    Ok(run_interpreter(bot, flows, HashMap::new(), context, event, None, None).to_json_value())
+}
+
+/// Interpret the bot like `interpret`, `observer` sees every message emitted
+/// by the run in order (see MessageObserver)
+pub fn interpret_with_observer(
+    bot: CsmlBot,
+    context: Context,
+    event: Event,
+    sender: Option<mpsc::Sender<MSG>>,
+    observer: &dyn MessageObserver,
+) -> MessageData {
+    let (flows, extern_flows) = get_flows(&bot);
+
+    run_interpreter(
+        bot,
+        flows,
+        extern_flows,
+        context,
+        event,
+        sender,
+        Some(observer),
+    )
+}
+
+/// Register a function of the host, called by its name from the flows of every bot
+/// (see NativeFunction). A function with the same name replaces it
+pub fn register_native_function(name: &str, function: Box<dyn NativeFunction>) {
+    data::native_function::register_native_function(name, function)
+}
+
+fn run_interpreter(
+    bot: CsmlBot,
+    flows: HashMap<String, Flow>,
+    extern_flows: HashMap<String, Flow>,
     mut context: Context,
     event: Event,
     sender: Option<mpsc::Sender<MSG>>,
+    message_observer: Option<&dyn MessageObserver>,
 ) -> MessageData {
     csml_logs::init_logger();
 
+    // the spans only carry identifiers, never the event or the memories
+    let _span =
+        tracing::info_span!("csml_interpret", bot_id = %bot.id, flow = %context.flow).entered();
+
     let mut msg_data = MessageData::default();
 
     let mut flow = context.flow.to_owned();
@@ -454,6 +698,10 @@ pub fn interpret(
 
     let mut step_count = 0;
     let step_limit = get_step_limit(&event);
+    let goto_loop_limit = get_goto_loop_limit(&event);
+    let mut step_history = vec![];
+    let budget = get_execution_budget(&event);
+    let random = RandomSource::new(event.seed);
 
     let mut step_vars = match &context.hold {
         Some(hold) => get_hashmap_from_mem(&hold.step_vars, &flow),
@@ -470,8 +718,6 @@ pub fn interpret(
         _ => serde_json::Map::new(),
     };
 
-    let (flows, extern_flows) = get_flows(&bot);
-
     let env = match bot.env {
         Some(env) => json_to_literal(&env, Interval::default(), &flow).unwrap(),
         None => data::primitive::PrimitiveNull::get_literal(Interval::default()),
@@ -485,10 +731,18 @@ pub fn interpret(
         None => None,
     };
 
+    // snapshot of the memories at the start of the turn for the memory diff
+    let start_memories = context.current.clone();
+    // the event coerced by a typed hold is kept in the next steps of the run
+    let mut event_value = None;
+
     while msg_data.exit_condition.is_none() {
-        let ast = match get_flow_ast(&flows, &flow, &bot.id, &sender) {
+        let ast = match get_flow_ast(&flows, &flow, step.get_step_ref(), &bot.id, &sender) {
             Ok(ast) => ast,
-            Err(message_data) => return message_data,
+            Err(mut message_data) => {
+                message_data.memory_diff = MemoryDiff::new(&start_memories, &context.current);
+                return message_data;
+            }
         };
 
         let (missing_step, inserted_ast) = get_inserted_ast(&flows, ast, &step, &bot.id, &sender);
@@ -499,6 +753,13 @@ pub fn interpret(
             continue;
         }
 
+        if let Err(err) =
+            check_goto_loop(&mut step_history, &flow, &step.get_step(), goto_loop_limit)
+        {
+            msg_data = msg_data + MessageData::error_to_message(Err(err), &sender);
+            break;
+        }
+
         let mut data = Data::new(
             &flows,
             &extern_flows,
@@ -506,11 +767,15 @@ pub fn interpret(
             bot.default_flow.clone(),
             &mut context,
             &event,
+            event_value.take(),
             &env,
             vec![],
             0,
             &mut step_count,
             step_limit,
+            &budget,
+            &random,
+            message_observer,
             step_vars,
             previous_info.clone(),
             &custom,
@@ -525,6 +790,7 @@ pub fn interpret(
         };
 
         previous_info = data.previous_info.clone();
+        event_value = data.event_value.take();
         flow = data.context.flow.to_string();
         step = data.context.step.clone();
 
@@ -532,5 +798,11 @@ pub fn interpret(
         step_vars = HashMap::new();
     }
 
+    // an ended conversation is not resumed, even from a hold of a previous step
+    if msg_data.ended() {
+        msg_data.hold = None;
+    }
+    msg_data.memory_diff = MemoryDiff::new(&start_memories, &context.current);
+
     msg_data
 }
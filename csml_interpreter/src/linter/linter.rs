@@ -1,5 +1,6 @@
 use crate::data::{
     ast::*,
+    native_function::is_native_function,
     position::Position,
     primitive::{PrimitiveClosure, PrimitiveType},
     tokens::{Span, BUILT_IN, BUILT_IN_WITHOUT_WARNINGS, COMPONENT},
@@ -8,7 +9,7 @@ use crate::data::{
 };
 use crate::error_format::{
     convert_error_from_interval, gen_error_info, gen_infinite_loop_error_msg, gen_warning_info,
-    ErrorInfo,
+    ErrorCode, ErrorInfo, ERROR_NATIVE_FUNCTION_COLLISION,
 };
 use crate::interpreter::variable_handler::interval::interval_from_expr;
 use crate::linter::{
@@ -25,6 +26,7 @@ pub const ERROR_RETURN_IN_FN: &str = "'return' action is not allowed outside fun
 pub const ERROR_BREAK_IN_LOOP: &str = "'break' action is not allowed outside loop";
 pub const ERROR_CONTINUE_IN_LOOP: &str = "'continue' action is not allowed outside loop";
 pub const ERROR_HOLD_IN_LOOP: &str = "'hold' action is not allowed in function scope";
+pub const ERROR_RETRY_WITHOUT_HOLD: &str = "'retry' action is only allowed after a 'hold' of the step";
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
@@ -92,6 +94,7 @@ pub fn lint_bot(
     validate_gotos(&mut linter_info);
     validate_imports(&mut linter_info);
     validate_functions(&mut linter_info);
+    validate_native_functions(&mut linter_info);
     validate_constants(&mut linter_info);
     validate_inserts(&mut linter_info);
 
@@ -321,6 +324,7 @@ pub fn validate_functions(linter_info: &mut LinterInfo) {
             && !BUILT_IN.contains(&info.name.as_str())
             && !BUILT_IN_WITHOUT_WARNINGS.contains(&info.name.as_str())
             && COMPONENT != info.name
+            && !is_native_function(&info.name)
             && !validate_closure(&info, linter_info)
             && !function_exist(&info, linter_info)
         {
@@ -336,6 +340,21 @@ pub fn validate_functions(linter_info: &mut LinterInfo) {
     }
 }
 
+// the functions of the bot would hide the native functions of the host
+pub fn validate_native_functions(linter_info: &mut LinterInfo) {
+    for function in linter_info.function_list.iter() {
+        if !function.extern_module && is_native_function(&function.name) {
+            gen_function_error(
+                linter_info.errors,
+                function.raw_flow,
+                function.in_flow,
+                function.interval.to_owned(),
+                format!("{} '{}'", ERROR_NATIVE_FUNCTION_COLLISION, function.name),
+            );
+        }
+    }
+}
+
 pub fn validate_constants(linter_info: &mut LinterInfo) {
     for (flow, constant_info) in linter_info.bot_constants.iter() {
         for constant in constant_info.constants.iter() {
@@ -450,7 +469,8 @@ pub fn validate_flow_ast(flow: &FlowToValidate, linter_info: &mut LinterInfo, ex
                         format!("duplicate {}", info),
                         interval.to_owned(),
                     ),
-                ));
+                )
+                .with_code(ErrorCode::DuplicateInstruction));
             }
         }
     }
@@ -556,7 +576,8 @@ fn validate_expr_literals(to_be_literal: &Expr, state: &mut State, linter_info:
                 validate_expr_literals(expr, state, linter_info);
             }
         }
-        Expr::VecExpr(vec, ..) | Expr::ComplexLiteral(vec, ..) => {
+        Expr::SpreadExpr(expr, ..) => validate_expr_literals(expr, state, linter_info),
+        Expr::VecExpr(vec, ..) | Expr::ComplexLiteral(vec, ..) | Expr::SpreadMapExpr(vec, ..) => {
             for expr in vec.iter() {
                 validate_expr_literals(expr, state, linter_info);
             }
@@ -565,6 +586,11 @@ fn validate_expr_literals(to_be_literal: &Expr, state: &mut State, linter_info:
             validate_expr_literals(exp_1, state, linter_info);
             validate_expr_literals(exp_2, state, linter_info);
         }
+        Expr::TernaryExpr(cond, then, or_else, _) => {
+            validate_expr_literals(cond, state, linter_info);
+            validate_expr_literals(then, state, linter_info);
+            validate_expr_literals(or_else, state, linter_info);
+        }
         Expr::LitExpr { literal, .. } => {
             if literal.primitive.get_type() == PrimitiveType::PrimitiveClosure {
                 if let Ok(closure) = Literal::get_value::<PrimitiveClosure>(
@@ -591,29 +617,18 @@ fn validate_expr_literals(to_be_literal: &Expr, state: &mut State, linter_info:
 }
 
 fn validate_if_scope(
-    if_statement: &IfStatement,
+    branches: &[IfBranch],
+    else_body: &Option<Block>,
     state: &mut State,
     linter_info: &mut LinterInfo,
     step_breakers: &mut Option<&mut Vec<StepBreakers>>,
 ) {
-    match if_statement {
-        IfStatement::IfStmt {
-            consequence,
-            then_branch,
-            ..
-        } => {
-            validate_scope(consequence, state, linter_info, step_breakers);
-
-            match then_branch {
-                Some(else_scope) => {
-                    validate_if_scope(else_scope, state, linter_info, step_breakers)
-                }
-                None => {}
-            };
-        }
-        IfStatement::ElseStmt(block, ..) => {
-            validate_scope(block, state, linter_info, step_breakers)
-        }
+    for branch in branches.iter() {
+        validate_scope(&branch.consequence, state, linter_info, step_breakers);
+    }
+
+    if let Some(block) = else_body {
+        validate_scope(block, state, linter_info, step_breakers)
     }
 }
 
@@ -761,7 +776,7 @@ fn validate_scope(
                 }
             }
 
-            Expr::ObjectExpr(ObjectType::Break(interval)) => {
+            Expr::ObjectExpr(ObjectType::Break(_, interval)) => {
                 if state.loop_scope == 0 {
                     linter_info.errors.push(gen_error_info(
                         Position::new(interval.to_owned(), linter_info.flow_name),
@@ -773,7 +788,7 @@ fn validate_scope(
                     ));
                 }
             }
-            Expr::ObjectExpr(ObjectType::Continue(interval)) => {
+            Expr::ObjectExpr(ObjectType::Continue(_, interval)) => {
                 if state.loop_scope == 0 {
                     linter_info.errors.push(gen_error_info(
                         Position::new(interval.to_owned(), linter_info.flow_name),
@@ -786,9 +801,18 @@ fn validate_scope(
                 }
             }
 
-            Expr::ObjectExpr(ObjectType::Hold(interval)) => {
+            Expr::ObjectExpr(ObjectType::Hold(interval))
+            | Expr::ObjectExpr(ObjectType::HoldSchema(_, _, interval))
+            | Expr::ObjectExpr(ObjectType::HoldExpect(_, _, interval))
+            | Expr::ObjectExpr(ObjectType::HoldConfidence(_, _, _, interval))
+            | Expr::ObjectExpr(ObjectType::Delay(_, interval)) => {
                 register_flow_breaker(step_breakers, StepBreakers::HOLD(interval.clone()));
 
+                if let Expr::ObjectExpr(ObjectType::HoldConfidence(_, Some(else_goto), ..)) = action
+                {
+                    validate_scope(else_goto, state, linter_info, step_breakers);
+                }
+
                 if state.in_function > 0 {
                     linter_info.errors.push(gen_error_info(
                         Position::new(interval.to_owned(), linter_info.flow_name),
@@ -800,6 +824,26 @@ fn validate_scope(
                     ));
                 }
             }
+            Expr::ObjectExpr(ObjectType::Retry(_, interval)) => {
+                // the holds of the step before the retry are already registered
+                let after_hold = match step_breakers {
+                    Some(step_breakers) => step_breakers
+                        .iter()
+                        .any(|breaker| matches!(breaker, StepBreakers::HOLD(_))),
+                    None => false,
+                };
+
+                if !after_hold {
+                    linter_info.errors.push(gen_error_info(
+                        Position::new(interval.to_owned(), linter_info.flow_name),
+                        convert_error_from_interval(
+                            Span::new(linter_info.raw_flow),
+                            ERROR_RETRY_WITHOUT_HOLD.to_owned(),
+                            interval.to_owned(),
+                        ),
+                    ));
+                }
+            }
             Expr::ObjectExpr(ObjectType::Say(value)) => {
                 if state.in_function > 0 {
                     linter_info.errors.push(gen_error_info(
@@ -823,6 +867,12 @@ fn validate_scope(
                 ));
                 validate_expr_literals(value, state, linter_info);
             }
+            Expr::ObjectExpr(ObjectType::Assert(condition, message, _)) => {
+                validate_expr_literals(condition, state, linter_info);
+                if let Some(message) = message {
+                    validate_expr_literals(message, state, linter_info);
+                }
+            }
 
             Expr::ObjectExpr(ObjectType::Do(DoType::Update(_assign, target, new))) => {
                 if let Expr::IdentExpr(name) = &**target {
@@ -841,10 +891,26 @@ fn validate_scope(
                 validate_expr_literals(new, state, linter_info);
             }
             Expr::ObjectExpr(ObjectType::Do(DoType::Exec(expr))) => {
-                validate_expr_literals(expr, state, linter_info);
+                if let Expr::Destructure(pattern, value, _) = &**expr {
+                    if let Some(flow_constants) =
+                        linter_info.bot_constants.get_mut(linter_info.flow_name)
+                    {
+                        for name in pattern.idents() {
+                            flow_constants
+                                .updated_vars
+                                .insert(name.ident.clone(), name.interval.clone());
+                        }
+                    }
+
+                    validate_expr_literals(value, state, linter_info);
+                } else {
+                    validate_expr_literals(expr, state, linter_info);
+                }
             }
 
-            Expr::ObjectExpr(ObjectType::Remember(ref name, value)) => {
+            Expr::ObjectExpr(ObjectType::Remember(ref name, value))
+            | Expr::ObjectExpr(ObjectType::RememberConversation(ref name, value))
+            | Expr::ObjectExpr(ObjectType::RememberSecret(ref name, value)) => {
                 register_closure(name, true, value, linter_info);
 
                 if state.in_function > 0 {
@@ -860,10 +926,14 @@ fn validate_scope(
                 validate_expr_literals(value, state, linter_info);
             }
 
-            Expr::IfExpr(if_statement) => {
-                validate_if_scope(if_statement, state, linter_info, step_breakers);
+            Expr::IfExpr {
+                branches,
+                else_body,
+                ..
+            } => {
+                validate_if_scope(branches, else_body, state, linter_info, step_breakers);
             }
-            Expr::ForEachExpr(_ident, _index, _expr, block, _range) => {
+            Expr::ForEachExpr(_ident, _index, _expr, block, _label, _range) => {
                 state.enter_loop();
                 validate_scope(block, state, linter_info, step_breakers);
                 state.exit_loop();
@@ -873,6 +943,15 @@ fn validate_scope(
                 validate_scope(block, state, linter_info, step_breakers);
                 state.exit_loop();
             }
+            Expr::MatchExpr(_subject, arms, _range) => {
+                for (_pattern, block) in arms.iter() {
+                    validate_scope(block, state, linter_info, step_breakers);
+                }
+            }
+            Expr::TryCatchExpr(try_block, _ident, catch_block, _range) => {
+                validate_scope(try_block, state, linter_info, step_breakers);
+                validate_scope(catch_block, state, linter_info, step_breakers);
+            }
             _ => {}
         }
     }
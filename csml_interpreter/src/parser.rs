@@ -1,3 +1,4 @@
+pub mod flow_analysis;
 pub mod operator;
 pub mod parse_actions;
 pub mod parse_braces;
@@ -5,6 +6,7 @@ pub mod parse_built_in;
 pub mod parse_closure;
 pub mod parse_comments;
 pub mod parse_constant;
+pub mod parse_destructure;
 pub mod parse_foreach;
 pub mod parse_functions;
 pub mod parse_goto;
@@ -13,36 +15,49 @@ pub mod parse_if;
 pub mod parse_import;
 pub mod parse_insert;
 pub mod parse_literal;
+pub mod parse_loop_label;
+pub mod parse_match;
+pub mod parse_metadata;
 pub mod parse_object;
 pub mod parse_parenthesis;
 pub mod parse_path;
 pub mod parse_previous;
 pub mod parse_scope;
 pub mod parse_string;
+pub mod parse_try_catch;
 pub mod parse_var_types;
 pub mod parse_while_loop;
 pub mod state_context;
 pub mod step_checksum;
 pub mod tools;
+pub mod type_check;
 
 use crate::parser::parse_idents::parse_idents_assignation;
+pub use flow_analysis::{analyze_flow, FlowAnalysis};
 pub use state_context::ExitCondition;
+pub use type_check::type_check_flow;
 
 use crate::data::position::Position;
 use crate::data::{ast::*, tokens::*};
 use crate::error_format::*;
 use crate::interpreter::variable_handler::interval::interval_from_expr;
-use parse_comments::comment;
+use parse_comments::{attach_comments, comment, with_comments};
 use parse_constant::{constant_expr_to_lit, parse_constant};
 use parse_functions::parse_function;
 use parse_import::parse_import;
 use parse_insert::parse_insert;
+use parse_loop_label::check_loop_labels;
+use parse_metadata::{parse_metadata, parse_misplaced_metadata};
 use parse_scope::parse_root;
 use tools::*;
 
 use nom::error::{ContextError, ParseError};
-use nom::{branch::alt, bytes::complete::tag, multi::fold_many0, sequence::preceded, Err, *};
-use std::collections::HashMap;
+use nom::{
+    branch::alt, bytes::complete::tag, combinator::opt, multi::fold_many0, sequence::preceded, Err,
+    *,
+};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
 ////////////////////////////////////////////////////////////////////////////////
 // TOOL FUNCTIONS
@@ -81,77 +96,131 @@ where
 
 pub fn parse_flow<'a>(slice: &'a str, flow_name: &'a str) -> Result<Flow, ErrorInfo> {
     match start_parsing::<CustomError<Span<'a>>>(Span::new(slice)) {
-        Ok((_, (instructions, flow_type))) => {
-            let mut flow_instructions = HashMap::new();
-            let mut constants = HashMap::new();
-            // let mut inserts = vec![];
-
-            for instruction in instructions.into_iter() {
-                match instruction {
-                    Instruction {
-                        instruction_type: InstructionScope::Constant(name),
-                        actions: expr,
-                    } => {
-                        let lit = constant_expr_to_lit(&expr, flow_name)?;
-
-                        constants.insert(name, lit);
-                    }
-                    // Instruction {
-                    //     instruction_type: InstructionScope::InsertStep(insert_step),
-                    //     actions: _,
-                    // } => {
-                    //     inserts.push(insert_step);
-                    // }
-                    _ => {
-                        let instruction_interval = interval_from_expr(&instruction.actions);
-                        let instruction_info = instruction.instruction_type.get_info();
-
-                        if let Some(old_instruction) = flow_instructions
-                            .insert(instruction.instruction_type, instruction.actions)
-                        {
-                            // This is done in order to store all duplicated instruction during parsing
-                            // and use by the linter to display them all as errors
-                            flow_instructions.insert(
-                                InstructionScope::DuplicateInstruction(
-                                    instruction_interval,
-                                    instruction_info,
-                                ),
-                                old_instruction,
-                            );
-                        };
-                    }
-                }
+        Ok((_, (metadata, instructions, flow_type))) => {
+            create_flow(metadata, instructions, flow_type, flow_name)
+        }
+        Err(e) => match e {
+            Err::Error(err) | Err::Failure(err) => Err(gen_parsing_error(slice, flow_name, err)),
+            Err::Incomplete(_err) => unreachable!(),
+        },
+    }
+}
+
+// parse the flow like `parse_flow`, keeping its comments in `Flow::comments` for the
+// formatters. The comments are attached to the nearest step, function or command
+pub fn parse_flow_with_comments<'a>(slice: &'a str, flow_name: &'a str) -> Result<Flow, ErrorInfo> {
+    let (flow, comments) = with_comments(|| parse_flow(slice, flow_name));
+    let mut flow = flow?;
+
+    flow.comments = attach_comments(&flow, comments);
+
+    Ok(flow)
+}
+
+pub fn parse_flow_collect_errors<'a>(
+    slice: &'a str,
+    flow_name: &'a str,
+) -> (Option<Flow>, Vec<ErrorInfo>) {
+    let mut s = Span::new(slice);
+    let mut metadata = None;
+    let mut instructions = vec![];
+    let mut errors = vec![];
+
+    match parse_metadata::<CustomError<Span<'a>>>(s) {
+        Ok((rest, expr)) => {
+            metadata = Some(expr);
+            s = rest;
+        }
+        Err(Err::Failure(err)) => {
+            errors.push(gen_parsing_error(slice, flow_name, err));
+            match skip_to_next_step(s) {
+                Some(next_step) => s = next_step,
+                None => return (None, errors),
+            }
+        }
+        Err(Err::Error(_)) => {}
+        Err(Err::Incomplete(_err)) => unreachable!(),
+    }
+
+    loop {
+        let (item_start, _) = match comment::<CustomError<Span<'a>>>(s) {
+            Ok(value) => value,
+            Err(Err::Error(err)) | Err(Err::Failure(err)) => {
+                errors.push(gen_parsing_error(slice, flow_name, err));
+                break;
             }
+            Err(Err::Incomplete(_err)) => unreachable!(),
+        };
 
-            Ok(Flow {
-                flow_instructions,
-                flow_type,
-                constants,
-            })
+        if item_start.fragment().is_empty() {
+            break;
         }
-        Err(e) => match e {
-            Err::Error(err) | Err::Failure(err) => {
-                let (end_line, end_column) = match err.end {
-                    Some(end) => (Some(end.location_line()), Some(end.get_column() as u32)),
-                    None => (None, None),
+
+        match parse_instruction::<CustomError<Span<'a>>>(item_start) {
+            Ok((rest, mut items)) => {
+                instructions.append(&mut items);
+                s = rest;
+            }
+            Err(Err::Error(err)) | Err(Err::Failure(err)) => {
+                let err = match err.error.is_empty() {
+                    true => CustomError {
+                        input: item_start,
+                        end: None,
+                        error: ERROR_PARSING.to_owned(),
+                        keep_position: false,
+                    },
+                    false => err,
                 };
+                errors.push(gen_parsing_error(slice, flow_name, err));
 
-                Err(gen_error_info(
-                    Position::new(
-                        Interval::new_as_u32(
-                            err.input.location_line(),
-                            err.input.get_column() as u32,
-                            err.input.location_offset(),
-                            end_line,
-                            end_column,
-                        ),
-                        flow_name,
-                    ),
-                    convert_error_from_span(Span::new(slice), err),
-                ))
+                // skip the rest of the instruction in error and resume on the next step
+                match skip_to_next_step(item_start) {
+                    Some(next_step) => s = next_step,
+                    None => break,
+                }
             }
-            Err::Incomplete(_err) => unreachable!(),
-        },
+            Err(Err::Incomplete(_err)) => unreachable!(),
+        }
+    }
+
+    if !errors.is_empty() {
+        return (None, errors);
+    }
+
+    match create_flow(metadata, instructions, FlowType::Normal, flow_name) {
+        Ok(flow) => (Some(flow), errors),
+        Err(err) => (None, vec![err]),
+    }
+}
+
+// parse the step at 'range', a byte range of the flow, without parsing the rest of the flow
+// so an editor can validate only the edited step. The range must hold a single step, the
+// lines, columns and offsets of its AST and errors are those of the whole flow
+pub fn parse_step_in_range<'a>(
+    slice: &'a str,
+    range: Range<usize>,
+    flow_name: &'a str,
+) -> Result<Instruction, ErrorInfo> {
+    let step_slice = match slice.get(range.clone()) {
+        Some(step_slice) => step_slice,
+        None => {
+            return Err(gen_error_info(
+                Position::new(Interval::default(), flow_name),
+                format!("{} {:?}", ERROR_STEP_RANGE, range),
+            ))
+        }
+    };
+    let line = slice[..range.start].matches('\n').count() as u32 + 1;
+
+    // the step slice is a part of the flow slice, where the columns are looked for
+    let span = unsafe { Span::new_from_raw_offset(range.start, line, step_slice, ()) };
+
+    match parse_single_step::<CustomError<Span<'a>>>(span) {
+        Ok((_, instruction)) => Ok(instruction),
+        Err(Err::Error(err)) | Err(Err::Failure(err)) => {
+            Err(gen_parsing_error(slice, flow_name, err))
+        }
+        Err(Err::Incomplete(_err)) => unreachable!(),
     }
 }
 
@@ -159,6 +228,172 @@ pub fn parse_flow<'a>(slice: &'a str, flow_name: &'a str) -> Result<Flow, ErrorI
 // PRIVATE FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+fn get_error_code(err: &CustomError<Span>) -> ErrorCode {
+    // errors on an unclosed construct are moved to its start, the flow is
+    // incomplete if nothing but comments remains after the failing position
+    let position = err.end.unwrap_or(err.input);
+
+    match comment::<CustomError<Span>>(position) {
+        Ok((rest, _)) if rest.fragment().is_empty() => return ErrorCode::IncompleteInput,
+        _ => {}
+    }
+
+    match err.error.as_str() {
+        ERROR_DOUBLE_QUOTE
+        | ERROR_UNTERMINATED_HEREDOC
+        | ERROR_RIGHT_BRACE
+        | ERROR_RIGHT_BRACKET
+        | ERROR_DOUBLE_CLOSE_BRACE
+        | ERROR_UNTERMINATED_COMMENT => ErrorCode::IncompleteInput,
+        _ => ErrorCode::UnexpectedToken,
+    }
+}
+
+fn create_flow(
+    metadata: Option<Expr>,
+    instructions: Vec<Instruction>,
+    flow_type: FlowType,
+    flow_name: &str,
+) -> Result<Flow, ErrorInfo> {
+    let mut flow_instructions = HashMap::new();
+    let mut constants = HashMap::new();
+    // let mut inserts = vec![];
+
+    let functions: HashSet<&String> = instructions
+        .iter()
+        .filter_map(|instruction| match &instruction.instruction_type {
+            InstructionScope::FunctionScope { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect();
+
+    // imported functions can't shadow the functions of the flow
+    for instruction in instructions.iter() {
+        if let InstructionScope::ImportScope(import) = &instruction.instruction_type {
+            if functions.contains(&import.name) {
+                return Err(gen_error_info(
+                    Position::new(import.interval, flow_name),
+                    format!("{} '{}'", ERROR_IMPORT_COLLISION, import.name),
+                ));
+            }
+        }
+    }
+
+    for instruction in instructions.into_iter() {
+        match instruction {
+            Instruction {
+                instruction_type: InstructionScope::Constant(name),
+                actions: expr,
+            } => {
+                let lit = constant_expr_to_lit(&expr, flow_name)?;
+
+                constants.insert(name, lit);
+            }
+            // Instruction {
+            //     instruction_type: InstructionScope::InsertStep(insert_step),
+            //     actions: _,
+            // } => {
+            //     inserts.push(insert_step);
+            // }
+            _ => {
+                let instruction_interval = interval_from_expr(&instruction.actions);
+                let instruction_info = instruction.instruction_type.get_info();
+
+                if let Some(old_instruction) =
+                    flow_instructions.insert(instruction.instruction_type, instruction.actions)
+                {
+                    // This is done in order to store all duplicated instruction during parsing
+                    // and use by the linter to display them all as errors
+                    flow_instructions.insert(
+                        InstructionScope::DuplicateInstruction(
+                            instruction_interval,
+                            instruction_info,
+                        ),
+                        old_instruction,
+                    );
+                };
+            }
+        }
+    }
+
+    let metadata = match metadata {
+        Some(expr) => Some(constant_expr_to_lit(&expr, flow_name)?.primitive.to_json()),
+        None => None,
+    };
+
+    Ok(Flow {
+        flow_instructions,
+        flow_type,
+        constants,
+        metadata,
+        comments: vec![],
+    })
+}
+
+fn gen_parsing_error(slice: &str, flow_name: &str, err: CustomError<Span>) -> ErrorInfo {
+    let (end_line, end_column) = match err.end {
+        Some(end) => (Some(end.location_line()), Some(end.get_column() as u32)),
+        None => (None, None),
+    };
+    let code = get_error_code(&err);
+
+    gen_error_info(
+        Position::new(
+            Interval::new_as_u32(
+                err.input.location_line(),
+                err.input.get_column() as u32,
+                err.input.location_offset(),
+                end_line,
+                end_column,
+            ),
+            flow_name,
+        ),
+        convert_error_from_span(Span::new(slice), err),
+    )
+    .with_code(code)
+}
+
+fn is_step_start(line: &str) -> bool {
+    match line.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    let rest = line.trim_start_matches(|c: char| c.is_alphanumeric() || c == '_');
+    rest.trim_start_matches(|c| c == ' ' || c == '\t')
+        .starts_with(COLON)
+}
+
+fn skip_to_next_step(s: Span) -> Option<Span> {
+    // step names are the only unindented 'ident:' lines of a flow, the search
+    // starts on the line after the instruction in error
+    let fragment = s.fragment();
+    let mut offset = fragment.find('\n')? + 1;
+
+    loop {
+        let line = &fragment[offset..];
+        if is_step_start(line) {
+            return Some(s.slice(offset..));
+        }
+
+        offset += line.find('\n')? + 1;
+    }
+}
+
+fn parse_instruction<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Vec<Instruction>, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    alt((
+        parse_constant,
+        parse_import,
+        parse_insert,
+        parse_function,
+        parse_misplaced_metadata,
+        parse_step,
+    ))(s)
+}
+
 fn parse_step<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Vec<Instruction>, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -166,7 +401,9 @@ where
     let (s, mut interval) = preceded(comment, get_interval)(s)?;
     let (s, ident) = parse_step_name(s)?;
 
-    let (s, actions) = preceded(comment, parse_root)(s)?;
+    let (block_start, _) = comment(s)?;
+    let (s, actions) = parse_root(block_start)?;
+    check_loop_labels(block_start, &actions)?;
     let (s, end) = get_interval(s)?;
     interval.add_end(end);
 
@@ -183,20 +420,41 @@ where
     ))
 }
 
-fn start_parsing<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Vec<Instruction>, FlowType), E>
+fn parse_single_step<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Instruction, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (start, _) = comment(s)?;
+    if !is_step_start(start.fragment()) {
+        return Err(gen_nom_failure(start, ERROR_SINGLE_STEP));
+    }
+
+    let (s, mut instructions) = parse_step(start)?;
+
+    let (last, _) = comment(s)?;
+    if last.fragment().is_empty() {
+        return Ok((last, instructions.remove(0)));
+    }
+
+    // what follows the step is either another instruction of the flow or an error in the step
+    match parse_instruction::<E>(last) {
+        Ok(..) => Err(gen_nom_failure(last, ERROR_SINGLE_STEP)),
+        Err(..) => Err(gen_nom_failure(last, ERROR_PARSING)),
+    }
+}
+
+fn start_parsing<'a, E>(
+    s: Span<'a>,
+) -> IResult<Span<'a>, (Option<Expr>, Vec<Instruction>, FlowType), E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let flow_type = FlowType::Normal;
 
+    let (s, metadata) = opt(parse_metadata)(s)?;
+
     let (s, flow) = fold_many0(
-        alt((
-            parse_constant,
-            parse_import,
-            parse_insert,
-            parse_function,
-            parse_step,
-        )),
+        parse_instruction,
         Vec::new,
         |mut acc, mut item| {
             acc.append(&mut item);
@@ -208,6 +466,6 @@ where
     if !last.fragment().is_empty() {
         Err(gen_nom_failure(last, ERROR_PARSING))
     } else {
-        Ok((s, (flow, flow_type)))
+        Ok((s, (metadata, flow, flow_type)))
     }
 }
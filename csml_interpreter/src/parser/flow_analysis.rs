@@ -0,0 +1,237 @@
+use crate::data::ast::{
+    Block, Expr, Flow, GotoType, GotoValueType, Identifier, InstructionScope, Interval, ObjectType,
+};
+
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FlowAnalysis {
+    // steps that no goto of the flow can reach from start
+    pub unreachable_steps: BTreeSet<String>,
+    // goto targets that are not steps of the flow
+    pub dangling_gotos: Vec<Identifier>,
+    // gotos to a computed step ($var), they may reach any step so no step is
+    // reported unreachable when one of them is reachable
+    pub undecidable_gotos: Vec<Interval>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+struct StepGotos<'a> {
+    targets: Vec<&'a Identifier>,
+    undecidable: Vec<Interval>,
+}
+
+fn add_goto<'a>(step: &'a Option<GotoValueType>, interval: Interval, gotos: &mut StepGotos<'a>) {
+    match step {
+        Some(GotoValueType::Name(ident)) => gotos.targets.push(ident),
+        Some(GotoValueType::Variable(_)) => gotos.undecidable.push(interval),
+        None => {}
+    }
+}
+
+fn get_block_gotos<'a>(block: &'a Block, gotos: &mut StepGotos<'a>) {
+    for (expr, _) in block.commands.iter() {
+        match expr {
+            Expr::ObjectExpr(ObjectType::Goto(goto_type, interval)) => match goto_type {
+                GotoType::Step(GotoValueType::Name(ident)) => gotos.targets.push(ident),
+                GotoType::Step(GotoValueType::Variable(_)) => gotos.undecidable.push(*interval),
+                // steps of other flows are out of the analysis
+                GotoType::StepFlow {
+                    step,
+                    flow: None,
+                    bot: None,
+                } => add_goto(step, *interval, gotos),
+                GotoType::StepFlow { .. } | GotoType::Flow(_) => {}
+            },
+            Expr::ForEachExpr(_ident, _index, _expr, block, _label, _range) => {
+                get_block_gotos(block, gotos)
+            }
+            Expr::WhileExpr(_expr, block, _range) => get_block_gotos(block, gotos),
+            Expr::ObjectExpr(ObjectType::HoldConfidence(_threshold, Some(else_goto), ..)) => {
+                get_block_gotos(else_goto, gotos)
+            }
+            Expr::IfExpr {
+                branches,
+                else_body,
+                ..
+            } => {
+                for branch in branches.iter() {
+                    get_block_gotos(&branch.consequence, gotos);
+                }
+                if let Some(else_body) = else_body {
+                    get_block_gotos(else_body, gotos);
+                }
+            }
+            Expr::MatchExpr(_subject, arms, _range) => {
+                for (_pattern, block) in arms.iter() {
+                    get_block_gotos(block, gotos);
+                }
+            }
+            Expr::TryCatchExpr(try_block, _ident, catch_block, _range) => {
+                get_block_gotos(try_block, gotos);
+                get_block_gotos(catch_block, gotos);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn get_steps_gotos(flow: &Flow) -> HashMap<&str, StepGotos<'_>> {
+    let mut steps = HashMap::new();
+
+    for (instruction_type, expr) in flow.flow_instructions.iter() {
+        match (instruction_type, expr) {
+            (InstructionScope::StepScope(name), Expr::Scope { scope, .. }) => {
+                let mut gotos = StepGotos::default();
+                get_block_gotos(scope, &mut gotos);
+
+                steps.insert(name.as_str(), gotos);
+            }
+            // the gotos of inserted steps target the steps of their own flow
+            (InstructionScope::InsertStep(insert), _) => {
+                steps.insert(insert.name.as_str(), StepGotos::default());
+            }
+            _ => {}
+        }
+    }
+
+    steps
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// static reachability of the steps of a flow from its start step, following the gotos
+// to its own steps. Steps reached from other flows with 'goto step@flow' are not known here
+pub fn analyze_flow(flow: &Flow) -> FlowAnalysis {
+    let steps = get_steps_gotos(flow);
+    let mut analysis = FlowAnalysis::default();
+
+    let mut reachable = BTreeSet::new();
+    let mut to_visit = vec!["start"];
+    let mut is_decidable = true;
+
+    while let Some(name) = to_visit.pop() {
+        let gotos = match steps.get(name) {
+            Some(gotos) if reachable.insert(name) => gotos,
+            _ => continue,
+        };
+
+        if !gotos.undecidable.is_empty() {
+            is_decidable = false;
+        }
+
+        for target in gotos.targets.iter() {
+            to_visit.push(&target.ident);
+        }
+    }
+
+    for (name, gotos) in steps.iter() {
+        if is_decidable && !reachable.contains(name) {
+            analysis.unreachable_steps.insert(name.to_string());
+        }
+
+        for target in gotos.targets.iter() {
+            if target.ident != "end" && !steps.contains_key(target.ident.as_str()) {
+                analysis.dangling_gotos.push((*target).to_owned());
+            }
+        }
+        analysis.undecidable_gotos.extend(gotos.undecidable.iter());
+    }
+
+    analysis
+        .dangling_gotos
+        .sort_by_key(|ident| (ident.interval.start_line, ident.interval.start_column));
+    analysis
+        .undecidable_gotos
+        .sort_by_key(|interval| (interval.start_line, interval.start_column));
+
+    analysis
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_flow;
+
+    fn analyze(content: &str) -> FlowAnalysis {
+        analyze_flow(&parse_flow(content, "flow").unwrap())
+    }
+
+    fn names(steps: &[&str]) -> BTreeSet<String> {
+        steps.iter().map(|step| step.to_string()).collect()
+    }
+
+    #[test]
+    fn ok_all_reachable() {
+        let analysis = analyze(
+            "start:\n    if (event == 1) {\n        goto first\n    }\n    goto second\n\n\
+             first:\n    foreach (item) in [1] {\n        goto second\n    }\n\n\
+             second:\n    goto end\n",
+        );
+
+        assert_eq!(analysis, FlowAnalysis::default());
+    }
+
+    #[test]
+    fn ok_dead_step_and_dangling_goto() {
+        let analysis = analyze(
+            "start:\n    say \"hi\"\n    goto checkout\n\n\
+             checkout:\n    goto paymnt\n\n\
+             payment:\n    goto end\n\n\
+             dead:\n    goto payment\n",
+        );
+
+        assert_eq!(analysis.unreachable_steps, names(&["dead", "payment"]));
+        assert_eq!(analysis.dangling_gotos.len(), 1);
+        assert_eq!(analysis.dangling_gotos[0].ident, "paymnt");
+        assert_eq!(analysis.dangling_gotos[0].interval.start_line, 6);
+        assert!(analysis.undecidable_gotos.is_empty());
+    }
+
+    #[test]
+    fn ok_other_flows_are_ignored() {
+        let analysis = analyze(
+            "start:\n    goto flow sales\n    goto checkout@sales\n    goto @sales\n\n\
+             checkout:\n    goto end\n",
+        );
+
+        // checkout@sales is the checkout step of the sales flow
+        assert_eq!(analysis.unreachable_steps, names(&["checkout"]));
+        assert!(analysis.dangling_gotos.is_empty());
+    }
+
+    #[test]
+    fn ok_undecidable_goto() {
+        let analysis = analyze(
+            "start:\n    do next = \"hidden\"\n    goto $next\n\n\
+             hidden:\n    goto end\n",
+        );
+
+        // the computed goto may reach hidden
+        assert!(analysis.unreachable_steps.is_empty());
+        assert_eq!(analysis.undecidable_gotos.len(), 1);
+        assert_eq!(analysis.undecidable_gotos[0].start_line, 3);
+    }
+
+    #[test]
+    fn ok_unreachable_undecidable_goto() {
+        let analysis = analyze(
+            "start:\n    goto end\n\n\
+             dead:\n    goto step $next\n",
+        );
+
+        // an unreachable computed goto does not hide the dead steps
+        assert_eq!(analysis.unreachable_steps, names(&["dead"]));
+        assert_eq!(analysis.undecidable_gotos.len(), 1);
+    }
+}
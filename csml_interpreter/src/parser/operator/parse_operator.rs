@@ -1,20 +1,47 @@
 use crate::data::{ast::*, tokens::*};
+use crate::error_format::{gen_nom_failure, ERROR_TERNARY};
 use crate::parser::operator::tools::and_operator;
+use crate::parser::operator::tools::bitwise_and_operator;
+use crate::parser::operator::tools::bitwise_or_operator;
+use crate::parser::operator::tools::bitwise_xor_operator;
+use crate::parser::operator::tools::null_coalescing_operator;
 use crate::parser::operator::tools::or_operator;
 use crate::parser::operator::tools::parse_infix_operators;
 use crate::parser::operator::tools::parse_item_operator;
 use crate::parser::operator::tools::parse_not_operator;
+use crate::parser::operator::tools::parse_shift_operator;
 use crate::parser::operator::tools::parse_term_operator;
 use crate::parser::parse_comments::comment;
 use crate::parser::parse_var_types::parse_basic_expr;
+use crate::parser::tools::get_interval;
 use nom::{
     branch::alt,
+    bytes::complete::tag,
+    combinator::{cut, not, opt},
     error::{ContextError, ParseError},
     multi::{many0, many1},
-    sequence::{preceded, tuple},
+    sequence::{preceded, terminated, tuple},
     *,
 };
 
+// Operator precedence, from the tightest to the loosest binding:
+//
+//   !
+//   *  /  %  div
+//   +  -
+//   <<  >>
+//   ==  !=  >  >=  <  <=  match  !match
+//   &
+//   ^
+//   |
+//   &&
+//   ||
+//   ??
+//   ? :
+//
+// As in C, the bitwise operators bind looser than the comparisons:
+// 'flags & 4 == 4' is 'flags & (4 == 4)', write '(flags & 4) == 4'.
+
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
@@ -24,18 +51,72 @@ where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (s, _) = preceded(comment, and_operator)(s)?;
-    parse_infix_expr(s)
+    parse_bitwise_or(s)
+}
+
+fn parse_bitwise_or<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, value) = parse_bitwise_xor(s)?;
+
+    let (s, mut v) = many0(tuple((
+        preceded(comment, bitwise_or_operator),
+        parse_bitwise_xor,
+    )))(s)?;
+
+    let value = v.drain(0..).fold(value, |acc, (infix, expr)| {
+        Expr::InfixExpr(infix, Box::new(acc), Box::new(expr))
+    });
+
+    Ok((s, value))
+}
+
+fn parse_bitwise_xor<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, value) = parse_bitwise_and(s)?;
+
+    let (s, mut v) = many0(tuple((
+        preceded(comment, bitwise_xor_operator),
+        parse_bitwise_and,
+    )))(s)?;
+
+    let value = v.drain(0..).fold(value, |acc, (infix, expr)| {
+        Expr::InfixExpr(infix, Box::new(acc), Box::new(expr))
+    });
+
+    Ok((s, value))
+}
+
+fn parse_bitwise_and<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, value) = parse_infix_expr(s)?;
+
+    let (s, mut v) = many0(tuple((
+        preceded(comment, bitwise_and_operator),
+        parse_infix_expr,
+    )))(s)?;
+
+    let value = v.drain(0..).fold(value, |acc, (infix, expr)| {
+        Expr::InfixExpr(infix, Box::new(acc), Box::new(expr))
+    });
+
+    Ok((s, value))
 }
 
 fn parse_infix_expr<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, expr1) = alt((parse_postfix_operator, parse_item))(s)?;
+    let (s, expr1) = alt((parse_postfix_operator, parse_shift))(s)?;
     let infix: IResult<Span<'a>, Infix, E> = preceded(comment, parse_infix_operators)(s);
     match infix {
         Ok((s, operator)) => {
-            let (s, expr2) = alt((parse_postfix_operator, parse_item))(s)?;
+            let (s, expr2) = alt((parse_postfix_operator, parse_shift))(s)?;
             Ok((
                 s,
                 Expr::InfixExpr(operator, Box::new(expr1), Box::new(expr2)),
@@ -50,7 +131,7 @@ where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (s, vec) = preceded(comment, many1(parse_not_operator))(s)?;
-    let (s, expr) = parse_item(s)?;
+    let (s, expr) = parse_shift(s)?;
 
     Ok((s, Expr::PostfixExpr(vec, Box::new(expr))))
 }
@@ -59,7 +140,7 @@ fn parse_and_condition<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, value) = parse_infix_expr(s)?;
+    let (s, value) = parse_bitwise_or(s)?;
 
     let (s, mut v) = many0(parse_and)(s)?;
 
@@ -70,6 +151,21 @@ where
     Ok((s, value))
 }
 
+fn parse_shift<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, value) = parse_item(s)?;
+
+    let (s, mut v) = many0(tuple((preceded(comment, parse_shift_operator), parse_item)))(s)?;
+
+    let value = v.drain(0..).fold(value, |acc, (infix, expr)| {
+        Expr::InfixExpr(infix, Box::new(acc), Box::new(expr))
+    });
+
+    Ok((s, value))
+}
+
 fn parse_item<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -111,6 +207,47 @@ where
     parse_and_condition(s)
 }
 
+fn parse_or_condition<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, value) = parse_and_condition(s)?;
+
+    let (s, mut v) = many0(parse_or)(s)?;
+
+    let value = v.drain(0..).fold(value, |acc, expr| {
+        Expr::InfixExpr(Infix::Or, Box::new(acc), Box::new(expr))
+    });
+
+    Ok((s, value))
+}
+
+fn parse_null_coalescing<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = preceded(comment, null_coalescing_operator)(s)?;
+    parse_or_condition(s)
+}
+
+// the branches of '? :' are full expressions, so ternaries nest to the right
+// and 'a ? b : c ? d : e' is 'a ? b : (c ? d : e)'
+fn parse_ternary<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Expr, Expr), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = preceded(comment, terminated(tag(TERNARY), not(tag(TERNARY))))(s)?;
+    let (s, then) = cut(parse_operator)(s)?;
+
+    let (s, _) = match preceded(comment, tag(COLON))(s) as IResult<Span<'a>, Span<'a>, E> {
+        Ok(value) => value,
+        Err(_) => return Err(gen_nom_failure(s, ERROR_TERNARY)),
+    };
+    let (s, or_else) = cut(parse_operator)(s)?;
+
+    Ok((s, (then, or_else)))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
@@ -119,13 +256,26 @@ pub fn parse_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, value) = parse_and_condition(s)?;
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, value) = parse_or_condition(s)?;
 
-    let (s, mut v) = many0(parse_or)(s)?;
+    // '??' has the lowest precedence after '? :', 'a ?? b || c' is 'a ?? (b || c)'
+    let (s, mut v) = many0(parse_null_coalescing)(s)?;
 
     let value = v.drain(0..).fold(value, |acc, expr| {
-        Expr::InfixExpr(Infix::Or, Box::new(acc), Box::new(expr))
+        Expr::InfixExpr(Infix::NullCoalescing, Box::new(acc), Box::new(expr))
     });
 
-    Ok((s, value))
+    match opt(parse_ternary)(s)? {
+        (s, Some((then, or_else))) => {
+            let (s, end) = get_interval(s)?;
+            interval.add_end(end);
+
+            Ok((
+                s,
+                Expr::TernaryExpr(Box::new(value), Box::new(then), Box::new(or_else), interval),
+            ))
+        }
+        (s, None) => Ok((s, value)),
+    }
 }
@@ -1,8 +1,12 @@
 use crate::data::{ast::*, tokens::*};
+use crate::parser::{operator::parse_operator, parse_comments::comment, tools::get_interval};
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    error::{ContextError, ParseError},
+    character::complete::satisfy,
+    combinator::not,
+    error::{ContextError, ErrorKind, ParseError},
+    sequence::{terminated, tuple},
     *,
 };
 
@@ -50,6 +54,14 @@ where
     Ok((rest, Infix::Or))
 }
 
+pub fn null_coalescing_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (rest, ..) = tag(NULL_COALESCING)(s)?;
+    Ok((rest, Infix::NullCoalescing))
+}
+
 pub fn divide_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -70,8 +82,92 @@ pub fn remainder_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
+    let (s, mut interval) = get_interval(s)?;
     let (s, _) = tag(REMAINDER)(s)?;
-    Ok((s, Infix::Remainder))
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Infix::Remainder(interval)))
+}
+
+pub fn integer_division_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = get_interval(s)?;
+    // 'div' must not be the start of an identifier like 'divider'
+    let (s, _) = terminated(
+        tag(INTEGER_DIVISION),
+        not(satisfy(|c: char| c.is_alphanumeric() || c == '_')),
+    )(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Infix::IntegerDivision(interval)))
+}
+
+// the operators capture their interval like '%' and 'div', for the type and shift count errors
+fn bitwise_operator<'a, E>(
+    token: &'static str,
+    infix: fn(Interval) -> Infix,
+) -> impl Fn(Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    move |s: Span<'a>| {
+        let (s, mut interval) = get_interval(s)?;
+        // '&' and '|' must not be the start of '&&' and '||'
+        let (s, _) = terminated(tag(token), not(tag(token)))(s)?;
+        let (s, end) = get_interval(s)?;
+        interval.add_end(end);
+
+        Ok((s, infix(interval)))
+    }
+}
+
+pub fn bitwise_and_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    bitwise_operator(BITWISE_AND, Infix::BitwiseAnd)(s)
+}
+
+pub fn bitwise_or_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    bitwise_operator(BITWISE_OR, Infix::BitwiseOr)(s)
+}
+
+pub fn bitwise_xor_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    bitwise_operator(BITWISE_XOR, Infix::BitwiseXor)(s)
+}
+
+pub fn shift_left_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = get_interval(s)?;
+    let (s, _) = tag(SHIFT_LEFT)(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Infix::ShiftLeft(interval)))
+}
+
+pub fn shift_right_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = get_interval(s)?;
+    let (s, _) = tag(SHIFT_RIGHT)(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Infix::ShiftRight(interval)))
 }
 
 pub fn not_equal_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
@@ -103,6 +199,12 @@ where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (rest, ..) = tag(MATCH)(s)?;
+
+    // 'match' followed by a subject and '{' starts a match statement and ends the expression before it
+    if tuple((parse_operator::<E>, comment, tag(L_BRACE)))(rest).is_ok() {
+        return Err(Err::Error(E::from_error_kind(s, ErrorKind::Tag)));
+    }
+
     Ok((rest, Infix::Match))
 }
 
@@ -126,7 +228,8 @@ pub fn greater_than_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (rest, ..) = tag(GREATER_THAN)(s)?;
+    // '>>' is a shift
+    let (rest, ..) = terminated(tag(GREATER_THAN), not(tag(GREATER_THAN)))(s)?;
     Ok((rest, Infix::GreaterThan))
 }
 
@@ -134,7 +237,8 @@ pub fn less_than_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (rest, ..) = tag(LESS_THAN)(s)?;
+    // '<<' is a shift
+    let (rest, ..) = terminated(tag(LESS_THAN), not(tag(LESS_THAN)))(s)?;
     Ok((rest, Infix::LessThan))
 }
 
@@ -153,7 +257,19 @@ pub fn parse_term_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    alt((divide_operator, multiply_operator, remainder_operator))(s)
+    alt((
+        divide_operator,
+        multiply_operator,
+        remainder_operator,
+        integer_division_operator,
+    ))(s)
+}
+
+pub fn parse_shift_operator<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    alt((shift_left_operator, shift_right_operator))(s)
 }
 
 pub fn parse_infix_operators<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Infix, E>
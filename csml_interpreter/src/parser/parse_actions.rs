@@ -1,16 +1,22 @@
-use crate::data::{ast::*, csml_logs::LogLvl, tokens::*};
+use crate::data::{ast::*, csml_logs::LogLvl, primitive::PrimitiveNull, tokens::*};
 use crate::error_format::{
-    gen_nom_failure, ERROR_ACTION_ARGUMENT, ERROR_REMEMBER, ERROR_RETURN, ERROR_USE,
+    gen_nom_failure, ERROR_ACTION_ARGUMENT, ERROR_ENV_READ_ONLY, ERROR_HOLD_CONFIDENCE,
+    ERROR_HOLD_CONFIDENCE_ELSE, ERROR_HOLD_EXPECT, ERROR_HOLD_SCHEMA, ERROR_REMEMBER, ERROR_RETRY,
+    ERROR_RETURN, ERROR_RETURN_GUARD, ERROR_USE,
 };
 use crate::parser::{
     operator::parse_operator,
     parse_comments::comment,
+    parse_destructure::parse_destructure,
     parse_foreach::parse_foreach,
     parse_goto::parse_goto,
     parse_idents::{parse_idents_assignation, parse_idents_usage},
     parse_if::parse_if,
+    parse_loop_label::parse_label_usage,
+    parse_match::parse_match,
     parse_path::parse_path,
     parse_previous::parse_previous,
+    parse_try_catch::parse_try_catch,
     parse_var_types::parse_r_bracket,
     parse_while_loop::parse_while,
     tools::{get_interval, get_string, get_tag},
@@ -18,8 +24,8 @@ use crate::parser::{
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
-    combinator::opt,
+    bytes::complete::{tag, take_while, take_while1},
+    combinator::{map, not, opt, peek},
     error::{ContextError, ErrorKind, ParseError},
     multi::separated_list0,
     sequence::{preceded, terminated, tuple},
@@ -119,6 +125,35 @@ where
     ))
 }
 
+// '_env' is set by the bot configuration, assigning it or one of its keys is an error
+fn parse_env_assignation<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (rest, interval) = get_interval(s)?;
+    let (rest, name) = get_string(rest)?;
+    if name != _ENV {
+        return Err(Err::Error(E::from_error_kind(s, ErrorKind::Tag)));
+    }
+
+    let (rest, _) = parse_path(rest, Expr::IdentExpr(Expr::new_idents(name, interval)))?;
+    let (rest, _) = preceded(
+        comment,
+        alt((
+            remainder_assignment,
+            division_assignment,
+            multiplication_assignment,
+            addition_assignment,
+            subtraction_assignment,
+            assignment,
+        )),
+    )(rest)?;
+    // '_env.key == value' is a comparison
+    let (_, _) = not(tag(ASSIGN))(rest)?;
+
+    Err(gen_nom_failure(s, ERROR_ENV_READ_ONLY))
+}
+
 fn parse_remember_as<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Identifier, Box<Expr>), E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -193,7 +228,15 @@ where
     let (s, name) = preceded(comment, get_string)(s)?;
     let (s, ..) = get_tag(name, DO)(s)?;
 
-    let (s, expr) = parse_action_argument(s, alt((parse_assignation_with_path, parse_operator)))?;
+    let (s, expr) = parse_action_argument(
+        s,
+        alt((
+            parse_destructure,
+            parse_env_assignation,
+            parse_assignation_with_path,
+            parse_operator,
+        )),
+    )?;
 
     let (s, do_type) = match expr {
         Expr::ObjectExpr(ObjectType::As(ident, expr)) => (
@@ -213,17 +256,53 @@ where
     Ok((s, Expr::ObjectExpr(ObjectType::Do(do_type))))
 }
 
+// `secret` followed by the name of the memory, `remember secret = value` remembers a
+// memory named secret
+fn parse_secret<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, ..) = get_tag(name, SECRET)(s)?;
+    let (s, ..) = peek(parse_idents_assignation)(s)?;
+
+    Ok((s, ()))
+}
+
 fn parse_remember<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (s, name) = preceded(comment, get_string)(s)?;
     let (s, ..) = get_tag(name, REMEMBER)(s)?;
+    let (s, secret) = opt(parse_secret)(s)?;
+
+    let (s, (idents, expr)) =
+        parse_action_argument(s, alt((parse_assignation, parse_remember_as)))?;
+
+    match secret {
+        Some(_) => Ok((
+            s,
+            Expr::ObjectExpr(ObjectType::RememberSecret(idents, expr)),
+        )),
+        None => Ok((s, Expr::ObjectExpr(ObjectType::Remember(idents, expr)))),
+    }
+}
+
+fn parse_remember_conversation<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, ..) = get_tag(name, REMEMBER_CONVERSATION)(s)?;
 
     let (s, (idents, expr)) =
         parse_action_argument(s, alt((parse_assignation, parse_remember_as)))?;
 
-    Ok((s, Expr::ObjectExpr(ObjectType::Remember(idents, expr))))
+    Ok((
+        s,
+        Expr::ObjectExpr(ObjectType::RememberConversation(idents, expr)),
+    ))
 }
 
 fn parse_forget<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -299,6 +378,31 @@ where
     }
 }
 
+fn parse_assert<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+    let (s, ..) = get_tag(name, ASSERT)(s)?;
+
+    let (s, condition) = parse_action_argument(s, parse_operator)?;
+    let (s, message) = match opt(preceded(comment, tag(COMMA)))(s)? {
+        (s, Some(..)) => {
+            let (s, message) = parse_action_argument(s, parse_operator)?;
+            (s, Some(Box::new(message)))
+        }
+        (s, None) => (s, None),
+    };
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((
+        s,
+        Expr::ObjectExpr(ObjectType::Assert(Box::new(condition), message, interval)),
+    ))
+}
+
 fn parse_log<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -348,6 +452,118 @@ where
     Ok((s, Expr::ObjectExpr(ObjectType::Use(Box::new(expr)))))
 }
 
+fn parse_hold_expect<'a, E>(
+    s: Span<'a>,
+    secure: bool,
+    interval: Interval,
+) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let expected = match preceded(comment, get_string)(s) as IResult<Span<'a>, String, E> {
+        Ok((rest, name)) => match name.as_str() {
+            "number" => Some((rest, ExpectedType::Number)),
+            "string" => Some((rest, ExpectedType::String)),
+            "boolean" => Some((rest, ExpectedType::Boolean)),
+            "email" => Some((rest, ExpectedType::Email)),
+            _ => None,
+        },
+        Err(..) => None,
+    };
+
+    match expected {
+        Some((rest, expected)) => Ok((
+            rest,
+            Expr::ObjectExpr(ObjectType::HoldExpect(expected, secure, interval)),
+        )),
+        None => Err(gen_nom_failure(s, ERROR_HOLD_EXPECT)),
+    }
+}
+
+// the goto of the less confident events: 'else goto clarify' or 'else { goto clarify }'
+fn parse_hold_confidence_else<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Option<Block>, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let s = match preceded(comment, get_string)(s) as IResult<Span<'a>, String, E> {
+        Ok((rest, name)) if name == ELSE => rest,
+        _ => return Ok((s, None)),
+    };
+
+    let braced = tuple((
+        preceded(comment, tag(L_BRACE)),
+        parse_goto,
+        preceded(comment, tag(R_BRACE)),
+    ));
+    let (s, goto) = match alt((map(braced, |(_, goto, _)| goto), parse_goto))(s) {
+        Ok(value) => value,
+        Err(Err::Error(..)) => return Err(gen_nom_failure(s, ERROR_HOLD_CONFIDENCE_ELSE)),
+        Err(err) => return Err(err),
+    };
+
+    Ok((
+        s,
+        Some(Block {
+            commands: vec![(goto, InstructionInfo { index: 0, total: 0 })],
+            commands_count: 1,
+        }),
+    ))
+}
+
+fn parse_hold_confidence<'a, E>(
+    s: Span<'a>,
+    secure: bool,
+    interval: Interval,
+) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (rest, threshold) = match parse_operator(s) {
+        Ok(value) => value,
+        Err(Err::Error(..)) => return Err(gen_nom_failure(s, ERROR_HOLD_CONFIDENCE)),
+        Err(err) => return Err(err),
+    };
+    let (rest, else_goto) = parse_hold_confidence_else(rest)?;
+
+    Ok((
+        rest,
+        Expr::ObjectExpr(ObjectType::HoldConfidence(
+            Box::new(threshold),
+            else_goto,
+            secure,
+            interval,
+        )),
+    ))
+}
+
+fn parse_hold_schema<'a, E>(
+    s: Span<'a>,
+    secure: bool,
+    interval: Interval,
+) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let rest = match preceded(comment, get_string)(s) as IResult<Span<'a>, String, E> {
+        Ok((rest, name)) if name == VALIDATE => rest,
+        Ok((rest, name)) if name == EXPECT => return parse_hold_expect(rest, secure, interval),
+        Ok((rest, name)) if name == CONFIDENCE => {
+            return parse_hold_confidence(rest, secure, interval)
+        }
+        _ if secure => return Ok((s, Expr::ObjectExpr(ObjectType::HoldSecure(interval)))),
+        _ => return Ok((s, Expr::ObjectExpr(ObjectType::Hold(interval)))),
+    };
+
+    match parse_operator(rest) {
+        Ok((rest, schema)) => Ok((
+            rest,
+            Expr::ObjectExpr(ObjectType::HoldSchema(Box::new(schema), secure, interval)),
+        )),
+        Err(Err::Error(..)) => Err(gen_nom_failure(rest, ERROR_HOLD_SCHEMA)),
+        Err(err) => Err(err),
+    }
+}
+
 fn parse_hold<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -357,7 +573,7 @@ where
 
     let (s, ..) = get_tag(name, HOLD)(s)?;
 
-    Ok((s, Expr::ObjectExpr(ObjectType::Hold(inter))))
+    parse_hold_schema(s, false, inter)
 }
 
 fn parse_hold_secure<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -369,7 +585,48 @@ where
 
     let (s, ..) = get_tag(name, HOLD_SECURE)(s)?;
 
-    Ok((s, Expr::ObjectExpr(ObjectType::HoldSecure(inter))))
+    parse_hold_schema(s, true, inter)
+}
+
+fn parse_delay<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, inter) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+
+    let (s, ..) = get_tag(name, DELAY)(s)?;
+    let (s, duration) = parse_action_argument(s, parse_operator)?;
+
+    Ok((
+        s,
+        Expr::ObjectExpr(ObjectType::Delay(Box::new(duration), inter)),
+    ))
+}
+
+// the maximum number of retries is on the line of the retry: 'retry 2'
+fn parse_retry<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, inter) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+
+    let (s, ..) = get_tag(name, RETRY)(s)?;
+    let (rest, max) = opt(preceded(
+        take_while(|c: char| c == ' ' || c == '\t'),
+        take_while1(|c: char| c.is_ascii_digit()),
+    ))(s)?;
+
+    let max = match max {
+        Some(max) => match max.fragment().parse::<usize>() {
+            Ok(max) => Some(max),
+            Err(_) => return Err(gen_nom_failure(s, ERROR_RETRY)),
+        },
+        None => None,
+    };
+
+    Ok((rest, Expr::ObjectExpr(ObjectType::Retry(max, inter))))
 }
 
 fn parse_break<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -380,8 +637,9 @@ where
     let (s, name) = get_string(s)?;
 
     let (s, ..) = get_tag(name, BREAK)(s)?;
+    let (s, label) = opt(parse_label_usage)(s)?;
 
-    Ok((s, Expr::ObjectExpr(ObjectType::Break(inter))))
+    Ok((s, Expr::ObjectExpr(ObjectType::Break(label, inter))))
 }
 
 fn parse_continue<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -392,25 +650,111 @@ where
     let (s, name) = get_string(s)?;
 
     let (s, ..) = get_tag(name, CONTINUE)(s)?;
+    let (s, label) = opt(parse_label_usage)(s)?;
+
+    Ok((s, Expr::ObjectExpr(ObjectType::Continue(label, inter))))
+}
+
+// nothing but a comment, a '}' or the next line follows a bare return
+fn is_bare_return(s: &Span) -> bool {
+    let rest = s.fragment().trim_start_matches(&[' ', '\t', '\r'][..]);
+
+    rest.is_empty()
+        || rest.starts_with('\n')
+        || rest.starts_with(R_BRACE)
+        || rest.starts_with("//")
+        || rest.starts_with("/*")
+}
+
+// the value of the return can be followed by the whitespaces up to the next line
+fn ends_line(start: &Span, rest: &Span) -> bool {
+    let length = rest.location_offset() - start.location_offset();
+    let parsed = start.fragment()[..length].trim_end_matches(&[' ', '\t'][..]);
 
-    Ok((s, Expr::ObjectExpr(ObjectType::Continue(inter))))
+    parsed.ends_with('\n')
+}
+
+// the guard is on the line of the return, an if statement on the next line is not a guard
+fn parse_return_guard<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = take_while(|c: char| c == ' ' || c == '\t')(s)?;
+    let (s, name) = get_string(s)?;
+    let (s, ..) = get_tag(name, IF)(s)?;
+
+    match preceded(comment, parse_operator)(s) {
+        Ok(value) => Ok(value),
+        Err(Err::Error(e)) => Err(Err::Failure(E::add_context(s, ERROR_RETURN_GUARD, e))),
+        Err(err) => Err(err),
+    }
 }
 
 fn parse_return<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, mut range) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
     let (s, ..) = get_tag(name, RETURN)(s)?;
+    let (s, null_interval) = get_interval(s)?;
 
-    let (s, expr) = match preceded(comment, parse_operator)(s) {
-        Ok(value) => value,
-        Err(Err::Error(e)) => return Err(Err::Failure(E::add_context(s, ERROR_RETURN, e))),
-        Err(Err::Failure(e)) => return Err(Err::Failure(e)),
-        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    let null = Expr::LitExpr {
+        literal: PrimitiveNull::get_literal(null_interval),
+        in_in_substring: false,
     };
 
-    Ok((s, Expr::ObjectExpr(ObjectType::Return(Box::new(expr)))))
+    let (s, expr, guard) = match parse_return_guard(s) {
+        Ok((s, guard)) => (s, null, Some(guard)),
+        Err(Err::Error(..)) if is_bare_return(&s) => (s, null, None),
+        Err(Err::Error(..)) => {
+            let (rest, expr) = match preceded(comment, parse_operator)(s) {
+                Ok(value) => value,
+                Err(Err::Error(e)) => return Err(Err::Failure(E::add_context(s, ERROR_RETURN, e))),
+                Err(Err::Failure(e)) => return Err(Err::Failure(e)),
+                Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+            };
+
+            match ends_line(&s, &rest) {
+                true => (rest, expr, None),
+                false => {
+                    let (rest, guard) = opt(parse_return_guard)(rest)?;
+
+                    (rest, expr, guard)
+                }
+            }
+        }
+        Err(err) => return Err(err),
+    };
+
+    let expr = Expr::ObjectExpr(ObjectType::Return(Box::new(expr)));
+
+    // `return value if condition` is an if statement without else around the return
+    match guard {
+        Some(guard) => {
+            let (s, end) = get_interval(s)?;
+            range.add_end(end);
+
+            let mut consequence = Block::default();
+            consequence
+                .commands
+                .push((expr, InstructionInfo { index: 0, total: 0 }));
+
+            Ok((
+                s,
+                Expr::IfExpr {
+                    branches: vec![IfBranch {
+                        cond: Box::new(guard),
+                        consequence,
+                        last_action_index: 0, // this wil be update in parse_root
+                    }],
+                    else_body: None,
+                    range,
+                },
+            ))
+        }
+        None => Ok((s, expr)),
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -426,9 +770,12 @@ where
         parse_do,
         parse_debug,
         parse_log,
+        parse_assert,
         parse_if,
         parse_foreach,
         parse_while,
+        parse_match,
+        parse_try_catch,
         // only accessible inside foreach or if scopes
         parse_break,
         parse_continue,
@@ -437,9 +784,9 @@ where
         parse_previous,
         parse_say,
         parse_remember,
+        parse_remember_conversation,
         parse_forget,
-        parse_hold,
-        parse_hold_secure,
+        alt((parse_hold, parse_hold_secure, parse_delay, parse_retry)),
         // only accessible in functions scopes
         parse_return,
         // soon to be deprecated
@@ -1,6 +1,7 @@
 use crate::data::{ast::*, primitive::closure::PrimitiveClosure, tokens::*};
 use crate::parser::{
-    parse_braces::parse_r_brace, parse_comments::comment, parse_scope::parse_root, tools::*,
+    parse_braces::parse_r_brace, parse_comments::comment, parse_loop_label::check_loop_labels,
+    parse_scope::parse_root, tools::*,
 };
 use nom::{
     bytes::complete::tag,
@@ -38,8 +39,9 @@ where
 
     let (s, _) = preceded(comment, tag(L_BRACE))(s)?;
 
-    let result = preceded(comment, parse_root)(s);
-    let (s, func) = result?;
+    let (start, _) = comment(s)?;
+    let (s, func) = parse_root(start)?;
+    check_loop_labels(start, &func)?;
 
     let (s, _) = preceded(comment, parse_r_brace)(s)?;
 
@@ -1,13 +1,36 @@
+use crate::data::ast::{
+    AttachedComment, Block, Comment, CommentPlacement, Expr, Flow, InstructionScope, Interval,
+};
 use crate::data::tokens::*;
+use crate::error_format::{gen_nom_failure_in_place, ERROR_UNTERMINATED_COMMENT};
+use crate::interpreter::variable_handler::interval::interval_from_expr;
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_till, take_until, take_while},
+    bytes::complete::{tag, take_till, take_while},
     character::complete::multispace0,
-    error::ParseError,
+    combinator::recognize,
+    error::{ContextError, ErrorKind, ParseError},
     multi::many0,
     sequence::delimited,
     IResult, *,
 };
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+thread_local! {
+    // comments read by `comment` while they are kept, by offset: the parsers backtrack
+    // and read the same comment more than once
+    static KEPT_COMMENTS: RefCell<Option<BTreeMap<usize, Comment>>> = const { RefCell::new(None) };
+}
+
+// the comments are no longer kept once the parsing ends, even on a panic
+struct KeepComments;
+
+impl Drop for KeepComments {
+    fn drop(&mut self) {
+        KEPT_COMMENTS.with(|kept| *kept.borrow_mut() = None);
+    }
+}
 
 fn comment_single_line<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E> {
     let (s, _) = tag("//")(s)?;
@@ -16,32 +39,185 @@ fn comment_single_line<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span
 }
 
 fn comment_delimited<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E> {
-    let (s, _) = tag(START_COMMENT)(s)?;
-    let val: IResult<Span<'a>, Span<'a>, E> = take_until(END_COMMENT)(s);
-    match val {
-        Ok((s, _)) => tag(END_COMMENT)(s),
-        // Error in comment_delimited is if '*/' is not found so the rest of the file is commented
-        Err(Err::Error(_e)) | Err(Err::Failure(_e)) => Ok((Span::new(""), Span::new(""))),
-        Err(Err::Incomplete(_)) => Ok((Span::new(""), Span::new(""))),
+    let (rest, _) = tag(START_COMMENT)(s)?;
+    let fragment = rest.fragment();
+    let mut depth = 1;
+    let mut index = 0;
+
+    // block comments can be nested, each '/*' must be closed by its own '*/'
+    while depth > 0 {
+        let remaining = &fragment[index..];
+
+        match (remaining.find(START_COMMENT), remaining.find(END_COMMENT)) {
+            (Some(start), Some(end)) if start < end => {
+                depth += 1;
+                index += start + START_COMMENT.len();
+            }
+            (_, Some(end)) => {
+                depth -= 1;
+                index += end + END_COMMENT.len();
+            }
+            // '*/' is not found, the error points to the opening '/*'
+            (_, None) => return Err(Err::Failure(E::from_error_kind(s, ErrorKind::TakeUntil))),
+        }
     }
+
+    // 'index' is a byte offset, 'take' would count it in chars
+    Ok(rest.take_split(index))
 }
 
+// the whole comment, with its delimiters
 fn all_comments<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E> {
-    alt((comment_single_line, comment_delimited))(s)
+    recognize(alt((comment_single_line, comment_delimited)))(s)
 }
 
-pub fn comment<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E> {
+pub fn comment<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
     let (s, _) = sp(s)?;
 
-    let (s, _) = match many0(ws(all_comments))(s) {
+    let (s, comments) = match many0(ws(all_comments))(s) {
         Ok(val) => val,
-        Err(Err::Error((s, _val))) | Err(Err::Failure((s, _val))) => return Ok((s, s)),
+        Err(Err::Failure((s, _val))) => return Err(gen_nom_failure_in_place(s, ERROR_UNTERMINATED_COMMENT)),
+        Err(Err::Error((s, _val))) => return Ok((s, s)),
         Err(Err::Incomplete(i)) => return Err(Err::Incomplete(i)),
     };
 
+    keep_comments(&comments);
+
     Ok((s, s))
 }
 
+// run 'parse' keeping the comments read by `comment`, in the order of the source
+pub fn with_comments<T>(parse: impl FnOnce() -> T) -> (T, Vec<Comment>) {
+    let guard = KeepComments;
+    KEPT_COMMENTS.with(|kept| *kept.borrow_mut() = Some(BTreeMap::new()));
+
+    let result = parse();
+    let comments = KEPT_COMMENTS.with(|kept| kept.borrow_mut().take());
+    drop(guard);
+
+    (result, comments.unwrap_or_default().into_values().collect())
+}
+
+// attach each comment to the node the nearest to it: the last node starting before it
+// on the same line, else the first node after it, else the last node of the flow
+pub fn attach_comments(flow: &Flow, comments: Vec<Comment>) -> Vec<AttachedComment> {
+    let mut nodes = vec![];
+    for (instruction, expr) in flow.flow_instructions.iter() {
+        match instruction {
+            InstructionScope::ImportScope(import) => nodes.push(import.interval),
+            InstructionScope::InsertStep(insert) => nodes.push(insert.interval),
+            InstructionScope::DuplicateInstruction(..) => {}
+            _ => collect_nodes(expr, &mut nodes),
+        }
+    }
+    nodes.sort_by_key(|node| node.offset);
+    nodes.dedup_by_key(|node| node.offset);
+
+    comments
+        .into_iter()
+        .filter_map(|comment| {
+            let offset = comment.interval.offset;
+            let next = nodes.partition_point(|node| node.offset < offset);
+
+            let (placement, node) = match (
+                next.checked_sub(1).map(|index| nodes[index]),
+                nodes.get(next),
+            ) {
+                (Some(previous), _) if previous.start_line == comment.interval.start_line => {
+                    (CommentPlacement::Trailing, previous)
+                }
+                (_, Some(next)) => (CommentPlacement::Leading, *next),
+                (Some(previous), None) => (CommentPlacement::Trailing, previous),
+                (None, None) => return None,
+            };
+
+            Some(AttachedComment {
+                comment,
+                placement,
+                node,
+            })
+        })
+        .collect()
+}
+
+fn keep_comments(comments: &[Span]) {
+    KEPT_COMMENTS.with(|kept| {
+        if let Some(kept) = kept.borrow_mut().as_mut() {
+            for comment in comments.iter() {
+                kept.entry(comment.location_offset())
+                    .or_insert_with(|| Comment {
+                        text: comment.fragment().to_string(),
+                        interval: comment_interval(comment),
+                    });
+            }
+        }
+    });
+}
+
+// the end of the interval is the position after the comment
+fn comment_interval(comment: &Span) -> Interval {
+    let mut interval = Interval::new_as_span(*comment);
+    let text = comment.fragment();
+
+    let (end_line, end_column) = match text.rfind('\n') {
+        Some(index) => (
+            interval.start_line + text.matches('\n').count() as u32,
+            (text.len() - index) as u32,
+        ),
+        None => (
+            interval.start_line,
+            interval.start_column + text.len() as u32,
+        ),
+    };
+    interval.end_line = Some(end_line);
+    interval.end_column = Some(end_column);
+
+    interval
+}
+
+// the steps and functions, their commands and the commands of the nested blocks
+fn collect_nodes(expr: &Expr, nodes: &mut Vec<Interval>) {
+    nodes.push(interval_from_expr(expr));
+
+    match expr {
+        Expr::Scope { scope, .. } => collect_block_nodes(scope, nodes),
+        Expr::IfExpr {
+            branches,
+            else_body,
+            ..
+        } => {
+            for branch in branches.iter() {
+                collect_block_nodes(&branch.consequence, nodes);
+            }
+            if let Some(else_body) = else_body {
+                collect_block_nodes(else_body, nodes);
+            }
+        }
+        Expr::ForEachExpr(_, _, _, block, _, _) | Expr::WhileExpr(_, block, _) => {
+            collect_block_nodes(block, nodes)
+        }
+        Expr::MatchExpr(_, arms, _) => {
+            for (_pattern, block) in arms.iter() {
+                collect_block_nodes(block, nodes);
+            }
+        }
+        Expr::TryCatchExpr(try_block, _, catch_block, _) => {
+            collect_block_nodes(try_block, nodes);
+            collect_block_nodes(catch_block, nodes);
+        }
+        _ => {}
+    }
+}
+
+fn collect_block_nodes(block: &Block, nodes: &mut Vec<Interval>) {
+    for (command, _info) in block.commands.iter() {
+        collect_nodes(command, nodes);
+    }
+}
+
 fn sp<'a, E: ParseError<Span<'a>>>(s: Span<'a>) -> IResult<Span<'a>, Span<'a>, E> {
     // nom combinators like `take_while` return a function. That function is the
     // parser,to which we can pass the input
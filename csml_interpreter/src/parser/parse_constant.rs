@@ -1,13 +1,20 @@
-use crate::data::primitive::{PrimitiveArray, PrimitiveBoolean, PrimitiveObject};
+use crate::data::primitive::{PrimitiveArray, PrimitiveBoolean, PrimitiveObject, PrimitiveType};
 use crate::data::{ast::*, position::Position, tokens::*, Literal};
 use crate::error_format::*;
 use crate::parser::{
-    operator::parse_operator, parse_comments::comment, parse_idents::parse_idents_assignation,
-    tools::*,
+    operator::parse_operator, parse_braces::parse_r_brace, parse_comments::comment,
+    parse_idents::parse_idents_assignation, tools::*,
 };
 
 use nom::error::{ContextError, ParseError};
-use nom::{bytes::complete::tag, sequence::preceded, IResult};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{cut, map, opt},
+    multi::separated_list0,
+    sequence::{preceded, terminated},
+    IResult,
+};
 use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -26,22 +33,23 @@ fn interval_from_expr(expr: &Expr) -> Interval {
             ..
         } => *range_interval,
         Expr::VecExpr(_e, range_interval) => *range_interval,
+        Expr::SpreadExpr(_e, range_interval) => *range_interval,
+        Expr::SpreadMapExpr(_e, range_interval) => *range_interval,
+        Expr::TernaryExpr(_, _, _, range_interval) => *range_interval,
         Expr::ObjectExpr(fnexpr) => interval_from_reserved_fn(fnexpr),
         Expr::InfixExpr(_i, expr, _e) => interval_from_expr(expr), // RangeInterval ?
         Expr::PostfixExpr(_p, expr) => interval_from_expr(expr),   // RangeInterval ?
         Expr::PathExpr { literal, .. } => interval_from_expr(literal),
-        Expr::ForEachExpr(_, _, _, _, range_interval) => *range_interval,
+        Expr::ForEachExpr(_, _, _, _, _, range_interval) => *range_interval,
         Expr::WhileExpr(_, _, range_interval) => *range_interval,
+        Expr::MatchExpr(_, _, range_interval) => *range_interval,
+        Expr::RegexExpr(_, range_interval) => *range_interval,
+        Expr::TryCatchExpr(_, _, _, range_interval) => *range_interval,
+        Expr::RangeExpr(_, _, _, range_interval) => *range_interval,
         Expr::IdentExpr(ident) => ident.interval.to_owned(),
         Expr::LitExpr { literal, .. } => literal.interval.to_owned(),
-        Expr::IfExpr(ifstmt) => interval_from_if_stmt(ifstmt),
-    }
-}
-
-pub fn interval_from_if_stmt(ifstmt: &IfStatement) -> Interval {
-    match ifstmt {
-        IfStatement::IfStmt { ref cond, .. } => interval_from_expr(cond),
-        IfStatement::ElseStmt(_e, range_interval) => *range_interval,
+        Expr::IfExpr { range, .. } => *range,
+        Expr::Destructure(_, _, range_interval) => *range_interval,
     }
 }
 
@@ -56,15 +64,25 @@ pub fn interval_from_reserved_fn(reserved_fn: &ObjectType) -> Interval {
         ObjectType::Debug(_expr, interval) => interval.to_owned(),
         ObjectType::Log { interval, .. } => interval.to_owned(),
         ObjectType::Return(expr) => interval_from_expr(expr),
-        ObjectType::Remember(ident, ..) => ident.interval.to_owned(),
+        ObjectType::Remember(ident, ..)
+        | ObjectType::RememberConversation(ident, ..)
+        | ObjectType::RememberSecret(ident, ..) => ident.interval.to_owned(),
         ObjectType::Forget(_, interval) => interval.to_owned(),
         ObjectType::Assign(_assign, ident, ..) => interval_from_expr(ident),
         ObjectType::As(ident, ..) => ident.interval.to_owned(),
         ObjectType::BuiltIn(Function { interval, .. }) => interval.to_owned(),
         ObjectType::Hold(interval) => interval.to_owned(),
         ObjectType::HoldSecure(interval) => interval.to_owned(),
-        ObjectType::Break(interval) => interval.to_owned(),
-        ObjectType::Continue(interval) => interval.to_owned(),
+        ObjectType::HoldSchema(_schema, _secure, interval) => interval.to_owned(),
+        ObjectType::HoldExpect(_expected, _secure, interval) => interval.to_owned(),
+        ObjectType::HoldConfidence(_threshold, _else_goto, _secure, interval) => {
+            interval.to_owned()
+        }
+        ObjectType::Delay(_duration, interval) => interval.to_owned(),
+        ObjectType::Retry(_max, interval) => interval.to_owned(),
+        ObjectType::Assert(_condition, _message, interval) => interval.to_owned(),
+        ObjectType::Break(_, interval) => interval.to_owned(),
+        ObjectType::Continue(_, interval) => interval.to_owned(),
     }
 }
 
@@ -156,7 +174,7 @@ fn evaluate_infix(
                 Err(err) => Err(gen_error_info(Position::new(lhs.interval, flow_name), err)),
             }
         }
-        (Infix::Remainder, Ok(lhs), Ok(rhs)) => {
+        (Infix::Remainder(interval), Ok(lhs), Ok(rhs)) => {
             let primitive = lhs.primitive % rhs.primitive;
 
             match primitive {
@@ -167,7 +185,39 @@ fn evaluate_infix(
                     interval: lhs.interval,
                     secure_variable: false,
                 }),
-                Err(err) => Err(gen_error_info(Position::new(lhs.interval, flow_name), err)),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
+            }
+        }
+        (Infix::IntegerDivision(interval), Ok(lhs), Ok(rhs)) => {
+            let primitive = lhs.primitive.int_div(&*rhs.primitive);
+
+            match primitive {
+                Ok(primitive) => Ok(Literal {
+                    content_type: primitive.get_type().to_string(),
+                    primitive,
+                    additional_info: None,
+                    interval: lhs.interval,
+                    secure_variable: false,
+                }),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
+            }
+        }
+        (Infix::BitwiseAnd(interval), Ok(lhs), Ok(rhs))
+        | (Infix::BitwiseOr(interval), Ok(lhs), Ok(rhs))
+        | (Infix::BitwiseXor(interval), Ok(lhs), Ok(rhs))
+        | (Infix::ShiftLeft(interval), Ok(lhs), Ok(rhs))
+        | (Infix::ShiftRight(interval), Ok(lhs), Ok(rhs)) => {
+            let primitive = lhs.primitive.bitwise(infix, &*rhs.primitive);
+
+            match primitive {
+                Ok(primitive) => Ok(Literal {
+                    content_type: primitive.get_type().to_string(),
+                    primitive,
+                    additional_info: None,
+                    interval: lhs.interval,
+                    secure_variable: false,
+                }),
+                Err(err) => Err(gen_error_info(Position::new(*interval, flow_name), err)),
             }
         }
 
@@ -180,6 +230,11 @@ fn evaluate_infix(
             lhs.interval,
         )),
 
+        (Infix::NullCoalescing, Ok(lhs), Ok(rhs)) => match lhs.primitive.get_type() {
+            PrimitiveType::PrimitiveNull => Ok(rhs),
+            _ => Ok(lhs),
+        },
+
         (Infix::Match, Ok(lhs), Ok(_)) | (Infix::NotMatch, Ok(lhs), Ok(_)) => Err(gen_error_info(
             Position::new(lhs.interval, "flow"),
             "invalid operation in constant declaration".to_owned(),
@@ -223,6 +278,40 @@ fn evaluate_condition(
     }
 }
 
+// NAME = expr
+fn parse_constant_declaration<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Instruction, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, name) = parse_idents_assignation(s)?;
+    let (s, _) = preceded(comment, tag(ASSIGN))(s)?;
+    let (s, expr) = preceded(comment, parse_operator)(s)?;
+
+    Ok((
+        s,
+        Instruction {
+            instruction_type: InstructionScope::Constant(name.ident),
+            actions: expr,
+        },
+    ))
+}
+
+// const { MAX = 5, GREETING = "hi" } declares several constants at once
+fn parse_constant_block<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Vec<Instruction>, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = preceded(comment, tag(L_BRACE))(s)?;
+
+    cut(terminated(
+        separated_list0(preceded(comment, tag(COMMA)), parse_constant_declaration),
+        preceded(
+            opt(preceded(comment, tag(COMMA))),
+            preceded(comment, parse_r_brace),
+        ),
+    ))(s)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
@@ -237,17 +326,10 @@ where
 
     let (s, ..) = get_tag(name, CONST)(s)?;
 
-    let (s, name) = parse_idents_assignation(s)?;
-    let (s, _) = preceded(comment, tag(ASSIGN))(s)?;
-    let (s, expr) = preceded(comment, parse_operator)(s)?;
-
-    Ok((
-        s,
-        vec![Instruction {
-            instruction_type: InstructionScope::Constant(name.ident),
-            actions: expr,
-        }],
-    ))
+    alt((
+        parse_constant_block,
+        map(parse_constant_declaration, |constant| vec![constant]),
+    ))(s)
 }
 
 pub fn constant_expr_to_lit(expr: &Expr, flow_name: &str) -> Result<Literal, ErrorInfo> {
@@ -295,6 +377,12 @@ pub fn constant_expr_to_lit(expr: &Expr, flow_name: &str) -> Result<Literal, Err
         Expr::InfixExpr(infix, exp_1, exp_2) => {
             Ok(evaluate_condition(infix, exp_1, exp_2, flow_name)?)
         }
+        Expr::TernaryExpr(cond, then, or_else, _) => {
+            match constant_expr_to_lit(cond, flow_name)?.primitive.as_bool() {
+                true => constant_expr_to_lit(then, flow_name),
+                false => constant_expr_to_lit(or_else, flow_name),
+            }
+        }
         Expr::LitExpr { literal, .. } => Ok(literal.clone()),
 
         Expr::ComplexLiteral(vec, interval) => {
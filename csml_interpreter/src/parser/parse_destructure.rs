@@ -0,0 +1,218 @@
+use crate::data::{
+    ast::{Expr, Identifier, Pattern},
+    tokens::{Span, ASSIGN, COLON, COMMA, L_BRACE, L_BRACKET, R_BRACE, R_BRACKET},
+};
+use crate::parser::{
+    operator::parse_operator,
+    parse_comments::comment,
+    parse_idents::parse_idents_assignation,
+    tools::{get_interval, get_string},
+};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{not, opt},
+    error::{ContextError, ParseError},
+    multi::separated_list1,
+    sequence::{preceded, terminated, tuple},
+    *,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_ident_pattern<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Pattern, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, ident) = parse_idents_assignation(s)?;
+
+    Ok((s, Pattern::Ident(ident)))
+}
+
+fn parse_renamed_field<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Identifier, Pattern), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, interval) = preceded(comment, get_interval)(s)?;
+    let (s, ident) = get_string(s)?;
+    let (s, _) = preceded(comment, tag(COLON))(s)?;
+    let (s, pattern) = parse_pattern(s)?;
+
+    Ok((s, (Identifier { ident, interval }, pattern)))
+}
+
+fn parse_short_field<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Identifier, Pattern), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, ident) = parse_idents_assignation(s)?;
+
+    Ok((s, (ident.clone(), Pattern::Ident(ident))))
+}
+
+fn parse_object_pattern<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Pattern, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, (fields, _)) = preceded(
+        tag(L_BRACE),
+        terminated(
+            tuple((
+                separated_list1(
+                    preceded(comment, tag(COMMA)),
+                    alt((parse_renamed_field, parse_short_field)),
+                ),
+                opt(preceded(comment, tag(COMMA))),
+            )),
+            preceded(comment, tag(R_BRACE)),
+        ),
+    )(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Pattern::Object(fields, interval)))
+}
+
+fn parse_array_pattern<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Pattern, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, (items, _)) = preceded(
+        tag(L_BRACKET),
+        terminated(
+            tuple((
+                separated_list1(preceded(comment, tag(COMMA)), parse_pattern),
+                opt(preceded(comment, tag(COMMA))),
+            )),
+            preceded(comment, tag(R_BRACKET)),
+        ),
+    )(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Pattern::Array(items, interval)))
+}
+
+fn parse_pattern<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Pattern, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    alt((
+        parse_object_pattern,
+        parse_array_pattern,
+        parse_ident_pattern,
+    ))(s)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+// {name, email} = user or [first, second] = list, only errors are returned in
+// order to fall back on objects and arrays literals
+pub fn parse_destructure<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, pattern) = alt((parse_object_pattern, parse_array_pattern))(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    let (s, _) = preceded(comment, terminated(tag(ASSIGN), not(tag(ASSIGN))))(s)?;
+    let (s, expr) = preceded(comment, parse_operator)(s)?;
+
+    Ok((s, Expr::Destructure(pattern, Box::new(expr), interval)))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_destructure(s: Span) -> IResult<Span, Expr> {
+        preceded(comment, parse_destructure)(s)
+    }
+
+    fn idents(pattern: &Pattern) -> Vec<String> {
+        pattern
+            .idents()
+            .iter()
+            .map(|ident| ident.ident.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn ok_object_pattern() {
+        let string = Span::new("{name, email} = user");
+        match test_destructure(string) {
+            Ok((_, Expr::Destructure(pattern @ Pattern::Object(..), expr, _))) => {
+                assert_eq!(idents(&pattern), vec!["name", "email"]);
+                assert!(matches!(*expr, Expr::IdentExpr(..)));
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_array_pattern() {
+        let string = Span::new("[first, second,] = list");
+        match test_destructure(string) {
+            Ok((_, Expr::Destructure(pattern @ Pattern::Array(..), ..))) => {
+                assert_eq!(idents(&pattern), vec!["first", "second"])
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_nested_pattern() {
+        let string = Span::new("{user: {name, address: [street]}, id: user_id} = resp");
+        match test_destructure(string) {
+            Ok((_, Expr::Destructure(Pattern::Object(fields, _), ..))) => {
+                assert_eq!(fields[0].0.ident, "user");
+                assert_eq!(fields[1].0.ident, "id");
+                assert_eq!(idents(&fields[0].1), vec!["name", "street"]);
+                assert_eq!(idents(&fields[1].1), vec!["user_id"]);
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_object_literal() {
+        let string = Span::new("{name: \"csml\"}");
+        match test_destructure(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_comparison() {
+        let string = Span::new("[a] == list");
+        match test_destructure(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_empty_pattern() {
+        let string = Span::new("{} = user");
+        match test_destructure(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
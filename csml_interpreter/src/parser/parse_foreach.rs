@@ -1,17 +1,19 @@
 use crate::data::{
     ast::{Expr, Identifier},
-    tokens::{Span, COMMA, FOREACH, IN, L_PAREN, R_PAREN},
+    tokens::{Span, COMMA, FOREACH, IN, L_PAREN, RANGE, RANGE_INCLUSIVE, R_PAREN},
 };
 use crate::parser::operator::parse_operator;
 use crate::parser::parse_idents::parse_idents_assignation;
+use crate::parser::parse_loop_label::parse_loop_label;
 use crate::parser::{
     parse_comments::comment,
     parse_scope::parse_scope,
     tools::{get_interval, get_string, get_tag},
 };
 use nom::{
+    branch::alt,
     bytes::complete::tag,
-    combinator::{cut, opt},
+    combinator::{cut, map, opt},
     error::{ContextError, ParseError},
     sequence::preceded,
     *,
@@ -21,6 +23,31 @@ use nom::{
 // PRIVATE FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+fn parse_range<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, start) = parse_operator(s)?;
+
+    let (s, inclusive) = preceded(
+        comment,
+        alt((
+            map(tag(RANGE_INCLUSIVE), |_| true),
+            map(tag(RANGE), |_| false),
+        )),
+    )(s)?;
+
+    let (s, end) = cut(parse_operator)(s)?;
+    let (s, end_interval) = get_interval(s)?;
+    interval.add_end(end_interval);
+
+    Ok((
+        s,
+        Expr::RangeExpr(Box::new(start), Box::new(end), inclusive, interval),
+    ))
+}
+
 fn pars_args<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Identifier, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -39,6 +66,7 @@ pub fn parse_foreach<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
+    let (s, label) = opt(parse_loop_label)(s)?;
     let (s, _) = preceded(comment, tag(FOREACH))(s)?;
     let (s, mut interval) = get_interval(s)?;
 
@@ -50,7 +78,7 @@ where
     let (s, value) = cut(preceded(comment, get_string))(s)?;
     let (s, ..) = cut(get_tag(value, IN))(s)?;
 
-    let (s, expr) = cut(parse_operator)(s)?;
+    let (s, expr) = cut(alt((parse_range, parse_operator)))(s)?;
 
     let (s, block) = parse_scope(s)?;
     let (s, end) = get_interval(s)?;
@@ -58,6 +86,48 @@ where
 
     Ok((
         s,
-        Expr::ForEachExpr(idents, opt, Box::new(expr), block, interval),
+        Expr::ForEachExpr(idents, opt, Box::new(expr), block, label, interval),
     ))
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::ast::Block;
+    use crate::parser::parse_scope::parse_root;
+
+    pub fn test_foreach(s: Span) -> IResult<Span, Block> {
+        preceded(comment, parse_root)(s)
+    }
+
+    #[test]
+    fn ok_foreach_object_entries() {
+        let string = Span::new("foreach (value, key) in {\"a\": 1} { say key }");
+        match test_foreach(string) {
+            Ok((_, block)) => match &block.commands[0].0 {
+                Expr::ForEachExpr(value, Some(key), expr, _, None, _) => {
+                    assert_eq!(value.ident, "value");
+                    assert_eq!(key.ident, "key");
+                    match expr.as_ref() {
+                        Expr::MapExpr { .. } => {}
+                        expr => panic!("{:?}", expr),
+                    }
+                }
+                expr => panic!("{:?}", expr),
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_foreach_three_idents() {
+        let string = Span::new("foreach (value, key, other) in {\"a\": 1} { say key }");
+        if test_foreach(string).is_ok() {
+            panic!("foreach only takes a value and an index or a key")
+        }
+    }
+}
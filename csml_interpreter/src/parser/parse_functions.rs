@@ -3,8 +3,8 @@ use crate::parser::parse_idents::parse_idents_assignation;
 use crate::data::{ast::*, tokens::*};
 use crate::error_format::*;
 use crate::parser::{
-    parse_braces::parse_r_brace, parse_comments::comment, parse_scope::parse_root,
-    parse_var_types::parse_fn_args, tools::*,
+    parse_braces::parse_r_brace, parse_comments::comment, parse_loop_label::check_loop_labels,
+    parse_scope::parse_root, parse_var_types::parse_fn_args, tools::*,
 };
 
 use nom::error::{ContextError, ParseError};
@@ -63,9 +63,10 @@ where
 
     let (s, _) = preceded(comment, tag("fn"))(s)?;
     let (s, ident) = preceded(comment, parse_idents_assignation)(s)?;
-    let (s, args) = parse_fn_args(s)?;
+    let (block_start, (args, defaults)) = parse_fn_args(s)?;
 
-    let (s, scope) = alt((parse_function_scope_colon, parse_function_scope))(s)?;
+    let (s, scope) = alt((parse_function_scope_colon, parse_function_scope))(block_start)?;
+    check_loop_labels(block_start, &scope)?;
 
     let (s, end) = get_interval(s)?;
 
@@ -77,6 +78,7 @@ where
             instruction_type: InstructionScope::FunctionScope {
                 name: ident.ident,
                 args,
+                defaults,
             },
             actions: Expr::Scope {
                 block_type: BlockType::Function,
@@ -86,3 +88,135 @@ where
         }],
     ))
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::primitive::PrimitiveType;
+
+    pub fn test_function(s: Span) -> IResult<Span, Vec<Instruction>> {
+        preceded(comment, parse_function)(s)
+    }
+
+    #[test]
+    fn ok_function_default_args() {
+        let string =
+            Span::new("fn greet(name, greeting = \"Hello\", punct = \"!\"):\n return name");
+        match test_function(string) {
+            Ok((_, instructions)) => match &instructions[0].instruction_type {
+                InstructionScope::FunctionScope { args, defaults, .. } => {
+                    assert_eq!(args, &vec!["name", "greeting", "punct"]);
+                    assert_eq!(defaults.len(), 2);
+                    assert!(!defaults.contains_key("name"));
+                    assert!(defaults.contains_key("greeting"));
+                    assert!(defaults.contains_key("punct"));
+                }
+                instruction => panic!("{:?}", instruction),
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_function_without_default_args() {
+        let string = Span::new("fn add(a, b) { return a + b }");
+        match test_function(string) {
+            Ok((_, instructions)) => match &instructions[0].instruction_type {
+                InstructionScope::FunctionScope { args, defaults, .. } => {
+                    assert_eq!(args, &vec!["a", "b"]);
+                    assert!(defaults.is_empty());
+                }
+                instruction => panic!("{:?}", instruction),
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    fn function_commands(string: Span) -> Vec<Expr> {
+        match test_function(string) {
+            Ok((_, instructions)) => match &instructions[0].actions {
+                Expr::Scope { scope, .. } => scope
+                    .commands
+                    .iter()
+                    .map(|(expr, _)| expr.to_owned())
+                    .collect(),
+                actions => panic!("{:?}", actions),
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_function_return_guard() {
+        let commands = function_commands(Span::new(
+            "fn check(name):\n return \"error\" if name == null\n return name",
+        ));
+
+        assert_eq!(commands.len(), 2);
+        match &commands[0] {
+            Expr::IfExpr {
+                branches,
+                else_body: None,
+                ..
+            } => {
+                assert_eq!(branches.len(), 1);
+                assert!(matches!(&*branches[0].cond, Expr::InfixExpr(..)));
+                assert!(matches!(
+                    branches[0].consequence.commands[0].0,
+                    Expr::ObjectExpr(ObjectType::Return(..))
+                ));
+            }
+            expr => panic!("{:?}", expr),
+        }
+    }
+
+    #[test]
+    fn ok_function_bare_return() {
+        let commands = function_commands(Span::new("fn stop() {\n do x = 1\n return\n}"));
+
+        match &commands[1] {
+            Expr::ObjectExpr(ObjectType::Return(value)) => match &**value {
+                Expr::LitExpr { literal, .. } => {
+                    assert_eq!(literal.primitive.get_type(), PrimitiveType::PrimitiveNull)
+                }
+                expr => panic!("{:?}", expr),
+            },
+            expr => panic!("{:?}", expr),
+        }
+    }
+
+    #[test]
+    fn ok_function_if_after_return() {
+        // an if statement on the next line is not a guard
+        let commands =
+            function_commands(Span::new("fn stop(a) {\n return a\n if (a) { say a }\n}"));
+
+        assert!(matches!(
+            commands[0],
+            Expr::ObjectExpr(ObjectType::Return(..))
+        ));
+        assert!(matches!(commands[1], Expr::IfExpr { .. }));
+    }
+
+    #[test]
+    fn err_function_return_guard_without_condition() {
+        let string = Span::new("fn check(name):\n return name if");
+        match test_function(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_function_required_arg_after_default() {
+        let string = Span::new("fn greet(greeting = \"Hello\", name):\n return name");
+        match test_function(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
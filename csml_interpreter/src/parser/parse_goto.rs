@@ -112,3 +112,51 @@ where
 
     Ok((s, Expr::ObjectExpr(ObjectType::Goto(goto_type, interval))))
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_goto(s: &str) -> GotoType {
+        match parse_goto::<nom::error::Error<Span>>(Span::new(s)) {
+            Ok((_, Expr::ObjectExpr(ObjectType::Goto(goto_type, _)))) => goto_type,
+            result => panic!("{:?}", result),
+        }
+    }
+
+    fn get_ident(value: Option<GotoValueType>) -> String {
+        match value {
+            Some(GotoValueType::Name(ident)) => ident.ident,
+            value => panic!("{:?}", value),
+        }
+    }
+
+    #[test]
+    fn ok_step_in_other_flow() {
+        match test_goto("goto checkout@sales") {
+            GotoType::StepFlow { step, flow, bot } => {
+                assert_eq!(get_ident(step), "checkout");
+                assert_eq!(get_ident(flow), "sales");
+                assert!(bot.is_none());
+            }
+            goto_type => panic!("{:?}", goto_type),
+        }
+    }
+
+    #[test]
+    fn ok_in_targets_a_bot() {
+        // 'in' switches to another bot, the flow is always given with '@'
+        match test_goto("goto checkout@sales in store") {
+            GotoType::StepFlow { step, flow, bot } => {
+                assert_eq!(get_ident(step), "checkout");
+                assert_eq!(get_ident(flow), "sales");
+                assert_eq!(get_ident(bot), "store");
+            }
+            goto_type => panic!("{:?}", goto_type),
+        }
+    }
+}
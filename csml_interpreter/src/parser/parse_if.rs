@@ -12,6 +12,7 @@ use nom::{
     bytes::complete::tag,
     combinator::opt,
     error::{ContextError, ParseError},
+    multi::many0,
     sequence::delimited,
     sequence::preceded,
     *,
@@ -28,68 +29,71 @@ where
     delimited(parse_l_parentheses, parse_operator, parse_r_parentheses)(s)
 }
 
-fn parse_else_if<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Box<IfStatement>, E>
+fn parse_branch<'a, E>(s: Span<'a>) -> IResult<Span<'a>, IfBranch, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, _) = preceded(comment, tag(ELSE))(s)?;
-    let (s, _) = preceded(comment, tag(IF))(s)?;
-
     let (s, condition) = parse_strict_condition_group(s)?;
 
     let (s, block) = alt((parse_scope, parse_implicit_scope))(s)?;
 
-    let (s, opt) = opt(alt((parse_else_if, parse_else)))(s)?;
-
     Ok((
         s,
-        Box::new(IfStatement::IfStmt {
+        IfBranch {
             cond: Box::new(condition),
             consequence: block,
-            then_branch: opt,
             last_action_index: 0, // this wil be update in parse_root
-        }),
+        },
     ))
 }
 
-fn parse_else<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Box<IfStatement>, E>
+fn parse_else_if<'a, E>(s: Span<'a>) -> IResult<Span<'a>, IfBranch, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (s, _) = preceded(comment, tag(ELSE))(s)?;
-    let (s, mut interval) = get_interval(s)?;
+    let (s, _) = preceded(comment, tag(IF))(s)?;
 
-    let (s, block) = alt((parse_scope, parse_implicit_scope))(s)?;
+    parse_branch(s)
+}
 
-    let (s, end) = get_interval(s)?;
-    interval.add_end(end);
+fn parse_else<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Block, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = preceded(comment, tag(ELSE))(s)?;
 
-    Ok((s, Box::new(IfStatement::ElseStmt(block, interval))))
+    alt((parse_scope, parse_implicit_scope))(s)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+// the else if are flattened in the branches of a single IfExpr
 pub fn parse_if<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, _) = preceded(comment, tag(IF))(s)?;
-    let (s, condition) = parse_strict_condition_group(s)?;
+    let (s, mut range) = preceded(comment, get_interval)(s)?;
+    let (s, _) = tag(IF)(s)?;
 
-    let (s, block) = alt((parse_scope, parse_implicit_scope))(s)?;
+    let (s, branch) = parse_branch(s)?;
+    let (s, mut branches) = many0(parse_else_if)(s)?;
+    branches.insert(0, branch);
+
+    let (s, else_body) = opt(parse_else)(s)?;
 
-    let (s, opt) = opt(alt((parse_else_if, parse_else)))(s)?;
+    let (s, end) = get_interval(s)?;
+    range.add_end(end);
 
     Ok((
         s,
-        Expr::IfExpr(IfStatement::IfStmt {
-            cond: Box::new(condition),
-            consequence: block,
-            then_branch: opt,
-            last_action_index: 0, // this wil be update in parse_root
-        }),
+        Expr::IfExpr {
+            branches,
+            else_body,
+            range,
+        },
     ))
 }
 
@@ -133,6 +137,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ok_flattened_else_if() {
+        let string = Span::new(
+            "if (a) { say 1 } else if (b) { say 2 } else if (c) { say 3 } else if (d) { say 4 } else { say 5 }",
+        );
+        match test_if(string) {
+            Ok((
+                _,
+                Expr::IfExpr {
+                    branches,
+                    else_body: Some(..),
+                    ..
+                },
+            )) => {
+                assert_eq!(branches.len(), 4);
+                for (branch, ident) in branches.iter().zip(["a", "b", "c", "d"]) {
+                    match &*branch.cond {
+                        Expr::IdentExpr(cond) => assert_eq!(cond.ident, ident),
+                        expr => panic!("{:?}", expr),
+                    }
+                    assert!(branch
+                        .consequence
+                        .commands
+                        .iter()
+                        .all(|(expr, _)| !matches!(expr, Expr::IfExpr { .. })));
+                }
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_if_without_else() {
+        let string = Span::new("if (a) { say 1 } else if (b) { say 2 }");
+        match test_if(string) {
+            Ok((
+                _,
+                Expr::IfExpr {
+                    branches,
+                    else_body: None,
+                    ..
+                },
+            )) => assert_eq!(branches.len(), 2),
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
     #[test]
     fn err_normal_if1() {
         let string = Span::new("if ");
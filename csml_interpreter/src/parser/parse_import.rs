@@ -1,5 +1,7 @@
-use crate::data::{ast::*, primitive::PrimitiveNull, tokens::*};
-use crate::error_format::ERROR_IMPORT_ARGUMENT;
+use crate::data::{
+    ast::*, error_info::ErrorInfo, position::Position, primitive::PrimitiveNull, tokens::*,
+};
+use crate::error_format::{gen_error_info, ERROR_IMPORT_ARGUMENT, ERROR_IMPORT_CONDITION};
 use crate::parser::{
     get_interval, get_string, get_tag,
     parse_comments::comment,
@@ -7,11 +9,11 @@ use crate::parser::{
 };
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_until},
     combinator::{map, opt},
     error::{ContextError, ErrorKind, ParseError},
     multi::separated_list0,
-    sequence::{preceded, terminated, tuple},
+    sequence::{delimited, preceded, terminated, tuple},
     Err, IResult,
 };
 
@@ -72,15 +74,93 @@ where
     }
 }
 
+fn parse_flow_name<'a, E>(s: Span<'a>) -> IResult<Span<'a>, String, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    // the flow name can be quoted: from "sales_flow"
+    preceded(
+        comment,
+        alt((
+            delimited(tag(DOUBLE_QUOTE), get_string, tag(DOUBLE_QUOTE)),
+            get_string,
+        )),
+    )(s)
+}
+
+fn parse_condition_value<'a, E>(s: Span<'a>) -> IResult<Span<'a>, String, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    preceded(
+        preceded(comment, tag(EQUAL)),
+        preceded(
+            comment,
+            delimited(
+                tag(DOUBLE_QUOTE),
+                map(take_until(DOUBLE_QUOTE), |value: Span| {
+                    (*value.fragment()).to_owned()
+                }),
+                tag(DOUBLE_QUOTE),
+            ),
+        ),
+    )(s)
+}
+
+// _env.key == "value" else flow, after the 'if' of the conditional import
+fn parse_condition<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (String, Option<String>, String), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, ..) = preceded(comment, tag(_ENV))(s)?;
+    let (s, key) = preceded(tag(DOT), get_string)(s)?;
+    let (s, value) = opt(parse_condition_value)(s)?;
+
+    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, ..) = get_tag(name, ELSE)(s)?;
+    let (s, else_flow) = parse_flow_name(s)?;
+
+    Ok((s, (key, value, else_flow)))
+}
+
+fn parse_import_condition<'a, E>(
+    s: Span<'a>,
+) -> IResult<Span<'a>, (String, Option<String>, String), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, ..) = get_tag(name, IF)(s)?;
+
+    match parse_condition(s) {
+        Ok(value) => Ok(value),
+        Err(Err::Error(e)) | Err(Err::Failure(e)) => {
+            Err(Err::Failure(E::add_context(s, ERROR_IMPORT_CONDITION, e)))
+        }
+        Err(Err::Incomplete(needed)) => Err(Err::Incomplete(needed)),
+    }
+}
+
 fn parse_from<'a, E>(s: Span<'a>) -> IResult<Span<'a>, FromFlow, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     let (s, name) = preceded(comment, get_string)(s)?;
     let (s, ..) = get_tag(name, FROM)(s)?;
-    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, name) = parse_flow_name(s)?;
 
-    Ok((s, FromFlow::Normal(name)))
+    match opt(parse_import_condition)(s)? {
+        (s, Some((key, value, else_flow))) => Ok((
+            s,
+            FromFlow::Conditional(ImportCondition {
+                key,
+                value,
+                then_flow: name,
+                else_flow,
+            }),
+        )),
+        (s, None) => Ok((s, FromFlow::Normal(name))),
+    }
 }
 
 fn parse_from_extern_module<'a, E>(s: Span<'a>) -> IResult<Span<'a>, FromFlow, E>
@@ -95,10 +175,98 @@ where
     Ok((s, FromFlow::Extern(name)))
 }
 
+fn get_condition_flow<'a>(
+    condition: &'a ImportCondition,
+    env: &Option<serde_json::Value>,
+) -> Result<&'a String, String> {
+    let name = format!("{}.{}", _ENV, condition.key);
+
+    let is_true = match (
+        env.as_ref().and_then(|env| env.get(&condition.key)),
+        &condition.value,
+    ) {
+        (Some(serde_json::Value::String(string)), Some(value)) => string == value,
+        (Some(env_value), Some(value)) => env_value.to_string() == *value,
+        (Some(serde_json::Value::Bool(boolean)), None) => *boolean,
+        (Some(_), None) => {
+            return Err(format!(
+                "import condition '{}' can not be resolved, expecting a boolean",
+                name
+            ))
+        }
+        (None, _) => {
+            return Err(format!(
+                "import condition '{}' can not be resolved, the key is missing from the env",
+                name
+            ))
+        }
+    };
+
+    match is_true {
+        true => Ok(&condition.then_flow),
+        false => Ok(&condition.else_flow),
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 //// PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+// choose the flow of the conditional imports with the env of the bot, before the bot runs.
+// The chosen flow must be one of the flows of the bot
+pub fn resolve_conditional_imports(
+    flow: &mut Flow,
+    flow_name: &str,
+    env: &Option<serde_json::Value>,
+    flow_names: &[&str],
+) -> Result<(), ErrorInfo> {
+    let conditional_imports: Vec<ImportScope> = flow
+        .flow_instructions
+        .keys()
+        .filter_map(|scope| match scope {
+            InstructionScope::ImportScope(
+                import @ ImportScope {
+                    from_flow: FromFlow::Conditional(_),
+                    ..
+                },
+            ) => Some(import.clone()),
+            _ => None,
+        })
+        .collect();
+
+    for import in conditional_imports {
+        let position = Position::new(import.interval, flow_name);
+
+        let target = match &import.from_flow {
+            FromFlow::Conditional(condition) => get_condition_flow(condition, env)
+                .map_err(|message| gen_error_info(position.clone(), message))?
+                .to_owned(),
+            _ => continue,
+        };
+
+        if !flow_names.contains(&target.as_str()) {
+            return Err(gen_error_info(
+                position,
+                format!(
+                    "import of '{}' failed, the flow '{}' does not exist",
+                    import.name, target
+                ),
+            ));
+        }
+
+        let scope = InstructionScope::ImportScope(import);
+        if let Some((InstructionScope::ImportScope(mut import), expr)) =
+            flow.flow_instructions.remove_entry(&scope)
+        {
+            import.from_flow = FromFlow::Normal(target);
+            flow.flow_instructions
+                .insert(InstructionScope::ImportScope(import), expr);
+        }
+    }
+
+    Ok(())
+}
+
 pub fn parse_import_prototype<'a, E>(
     s: Span<'a>,
 ) -> IResult<Span<'a>, (Interval, Vec<Expr>, FromFlow), E>
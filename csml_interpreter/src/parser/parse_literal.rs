@@ -4,14 +4,18 @@ use crate::parser::tools::get_tag;
 use crate::parser::{parse_comments::comment, tools::get_interval};
 
 use crate::data::primitive::{
-    boolean::PrimitiveBoolean, float::PrimitiveFloat, int::PrimitiveInt, null::PrimitiveNull,
+    boolean::PrimitiveBoolean,
+    float::PrimitiveFloat,
+    int::PrimitiveInt,
+    null::PrimitiveNull,
+    tools_time::{get_duration_literal, get_duration_unit},
 };
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{char, one_of},
     combinator::{opt, recognize},
-    error::{ContextError, ParseError},
+    error::{ContextError, ErrorKind, ParseError},
     multi::{many0, many1},
     sequence::{preceded, terminated, tuple},
     IResult,
@@ -80,6 +84,33 @@ where
     Ok((s, expression))
 }
 
+fn parse_duration<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (rest, interval) = get_interval(s)?;
+    let (rest, raw_digits) = decimal(rest)?;
+    let (rest, unit) = get_string(rest)?;
+
+    // the unit follows the digits without space: 30m, 3days, 1_500ms
+    let count = raw_digits.fragment().replace('_', "").parse::<i64>().ok();
+    let milliseconds = match (count, get_duration_unit(&unit)) {
+        (Some(count), Some(unit)) => count.checked_mul(unit),
+        _ => None,
+    };
+
+    match milliseconds {
+        Some(milliseconds) => Ok((
+            rest,
+            Expr::LitExpr {
+                literal: get_duration_literal(milliseconds, interval),
+                in_in_substring: false,
+            },
+        )),
+        None => Err(nom::Err::Error(E::from_error_kind(s, ErrorKind::Tag))),
+    }
+}
+
 fn parse_number<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -163,7 +194,10 @@ where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
     // TODO: span: preceded( comment ,  position!() ?
-    preceded(comment, alt((parse_number, parse_boolean, parse_null)))(s)
+    preceded(
+        comment,
+        alt((parse_duration, parse_number, parse_boolean, parse_null)),
+    )(s)
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -215,6 +249,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ok_duration() {
+        let string = Span::new(" 1_500ms");
+        match test_literal(string) {
+            Ok((_, Expr::LitExpr { literal, .. })) => {
+                assert_eq!(literal.content_type, "duration")
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_duration_unit() {
+        let string = Span::new(" 3months");
+        match test_literal(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
     #[test]
     fn err_sign() {
         let string = Span::new(" +++++4");
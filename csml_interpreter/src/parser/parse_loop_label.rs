@@ -0,0 +1,200 @@
+use crate::data::{
+    ast::{Block, Expr, Identifier, ObjectType},
+    tokens::{Span, COLON, SINGLE_QUOTE},
+};
+use crate::error_format::{gen_nom_failure, ERROR_LOOP_LABEL, ERROR_LOOP_LABEL_NAME};
+use crate::parser::{
+    parse_comments::comment,
+    tools::{get_interval, get_string},
+};
+use nom::{
+    bytes::complete::tag,
+    error::{ContextError, ParseError},
+    sequence::preceded,
+    *,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn check_label<'a, E>(s: Span<'a>, label: &Identifier, labels: &[String]) -> Result<(), Err<E>>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    if labels.contains(&label.ident) {
+        return Ok(());
+    }
+
+    // move the error on the label token
+    let label_span = s.slice(label.interval.offset - s.location_offset()..);
+    Err(gen_nom_failure(label_span, ERROR_LOOP_LABEL))
+}
+
+fn check_block_labels<'a, E>(
+    s: Span<'a>,
+    block: &Block,
+    labels: &mut Vec<String>,
+) -> Result<(), Err<E>>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    for (expr, _) in block.commands.iter() {
+        match expr {
+            Expr::ObjectExpr(ObjectType::Break(Some(label), _))
+            | Expr::ObjectExpr(ObjectType::Continue(Some(label), _)) => {
+                check_label(s, label, labels)?
+            }
+            Expr::ForEachExpr(_ident, _index, _expr, block, label, _range) => {
+                if let Some(label) = label {
+                    labels.push(label.ident.to_owned());
+                }
+                check_block_labels(s, block, labels)?;
+                if label.is_some() {
+                    labels.pop();
+                }
+            }
+            Expr::WhileExpr(_expr, block, _range) => check_block_labels(s, block, labels)?,
+            Expr::IfExpr {
+                branches,
+                else_body,
+                ..
+            } => {
+                for branch in branches.iter() {
+                    check_block_labels(s, &branch.consequence, labels)?;
+                }
+                if let Some(else_body) = else_body {
+                    check_block_labels(s, else_body, labels)?;
+                }
+            }
+            Expr::MatchExpr(_subject, arms, _range) => {
+                for (_pattern, block) in arms.iter() {
+                    check_block_labels(s, block, labels)?;
+                }
+            }
+            Expr::TryCatchExpr(try_block, _ident, catch_block, _range) => {
+                check_block_labels(s, try_block, labels)?;
+                check_block_labels(s, catch_block, labels)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn parse_label_usage<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Identifier, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, interval) = preceded(comment, get_interval)(s)?;
+    let (s, _) = tag(SINGLE_QUOTE)(s)?;
+
+    match get_string::<E>(s) {
+        Ok((s, ident)) => Ok((s, Identifier { ident, interval })),
+        Err(_) => Err(gen_nom_failure(s, ERROR_LOOP_LABEL_NAME)),
+    }
+}
+
+pub fn parse_loop_label<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Identifier, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, label) = parse_label_usage(s)?;
+    let (s, _) = preceded(comment, tag(COLON))(s)?;
+
+    Ok((s, label))
+}
+
+// labels are only known once the whole step or function is parsed, s is the
+// start of the block and is used to locate the error on the unknown label
+pub fn check_loop_labels<'a, E>(s: Span<'a>, block: &Block) -> Result<(), Err<E>>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    check_block_labels(s, block, &mut vec![])
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_scope::parse_root;
+
+    pub fn test_labels(s: Span) -> IResult<Span, Block> {
+        let (rest, block) = preceded(comment, parse_root)(s)?;
+        check_loop_labels(s, &block)?;
+
+        Ok((rest, block))
+    }
+
+    #[test]
+    fn ok_break_label() {
+        let string =
+            Span::new("'outer: foreach (x) in [1, 2] { foreach (y) in [3, 4] { break 'outer } }");
+        match test_labels(string) {
+            Ok((_, block)) => match &block.commands[0].0 {
+                Expr::ForEachExpr(_, _, _, inner, Some(label), _) => {
+                    assert_eq!(label.ident, "outer");
+                    match &inner.commands[0].0 {
+                        Expr::ForEachExpr(_, _, _, inner, None, _) => match &inner.commands[0].0 {
+                            Expr::ObjectExpr(ObjectType::Break(Some(label), _)) => {
+                                assert_eq!(label.ident, "outer")
+                            }
+                            expr => panic!("{:?}", expr),
+                        },
+                        expr => panic!("{:?}", expr),
+                    }
+                }
+                expr => panic!("{:?}", expr),
+            },
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_continue_label_in_if() {
+        let string = Span::new(
+            "'outer: foreach (x) in [1, 2] { while (true) { if (x == 1) { continue 'outer } } }",
+        );
+        match test_labels(string) {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_unknown_label() {
+        let string = Span::new("'outer: foreach (x) in [1, 2] { break 'inner }");
+        match test_labels(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(Err::Failure(err)) => assert_eq!(err.input.get_column(), 39),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_label_out_of_loop() {
+        let string = Span::new("'outer: foreach (x) in [1, 2] { say x } break 'outer");
+        match test_labels(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_label_without_name() {
+        let string = Span::new("foreach (x) in [1, 2] { break ' }");
+        match test_labels(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::data::{
+    ast::{Block, Expr},
+    primitive::tools::get_regex,
+    tokens::{Span, COMMA, DIVIDE, FATARROW, L_BRACE, MATCH, R_BRACE},
+};
+use crate::error_format::{gen_nom_failure, ERROR_MATCH_ARM, ERROR_MATCH_REGEX};
+use crate::parser::operator::parse_operator;
+use crate::parser::{
+    parse_comments::comment,
+    parse_literal::parse_literal_expr,
+    parse_scope::{parse_implicit_scope, parse_scope},
+    parse_string::parse_string,
+    tools::{get_interval, get_string, get_tag},
+};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while},
+    character::complete::{anychar, char, none_of},
+    combinator::{cut, opt, recognize},
+    error::{ContextError, ParseError},
+    multi::{many0, many1},
+    sequence::{pair, preceded},
+    *,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_wildcard<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, interval) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+    let (s, ..) = get_tag(name, "_")(s)?;
+
+    Ok((
+        s,
+        Expr::IdentExpr(Expr::new_idents("_".to_owned(), interval)),
+    ))
+}
+
+// /hello.*world/i, the regex is compiled when the flow is parsed so that invalid
+// patterns are reported before the bot runs
+fn parse_regex<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let start = s;
+    let (s, _) = tag(DIVIDE)(s)?;
+
+    let (s, pattern) = recognize(many1(alt((
+        recognize(pair(char('\\'), anychar)),
+        recognize(none_of("\\/\n")),
+    ))))(s)?;
+    let (s, _) = match tag(DIVIDE)(s) {
+        Ok(value) => value,
+        Err(Err::Error((_input, _err))) | Err(Err::Failure((_input, _err))) => {
+            return Err(gen_nom_failure(start, ERROR_MATCH_REGEX))
+        }
+        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    };
+    let (s, flags) = take_while(|c: char| c.is_alphabetic())(s)?;
+
+    if !flags.fragment().chars().all(|flag| "imsxU".contains(flag)) {
+        return Err(gen_nom_failure(start, ERROR_MATCH_REGEX));
+    }
+
+    let pattern = pattern.fragment().replace("\\/", "/");
+    let regex = match flags.fragment().is_empty() {
+        true => pattern,
+        false => format!("(?{}){}", flags.fragment(), pattern),
+    };
+
+    if get_regex(&regex).is_err() {
+        return Err(gen_nom_failure(start, ERROR_MATCH_REGEX));
+    }
+
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Expr::RegexExpr(regex, interval)))
+}
+
+fn parse_arm<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Expr, Block), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, pattern) = alt((
+        parse_wildcard,
+        parse_regex,
+        preceded(comment, parse_string),
+        parse_literal_expr,
+    ))(s)?;
+
+    let (s, _) = match preceded(comment, tag(FATARROW))(s) {
+        Ok(value) => value,
+        Err(Err::Error((_input, _err))) | Err(Err::Failure((_input, _err))) => {
+            return Err(gen_nom_failure(s, ERROR_MATCH_ARM))
+        }
+        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    };
+
+    let (s, block) = alt((parse_scope, parse_implicit_scope))(s)?;
+    let (s, _) = opt(preceded(comment, tag(COMMA)))(s)?;
+
+    Ok((s, (pattern, block)))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn parse_match<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+    let (s, ..) = get_tag(name, MATCH)(s)?;
+
+    let (s, subject) = cut(parse_operator)(s)?;
+    let (s, _) = cut(preceded(comment, tag(L_BRACE)))(s)?;
+
+    let (s, arms) = many0(parse_arm)(s)?;
+
+    let (s, _) = match preceded(comment, tag(R_BRACE))(s) {
+        Ok(value) => value,
+        Err(Err::Error((input, _err))) | Err(Err::Failure((input, _err))) => {
+            return Err(gen_nom_failure(input, ERROR_MATCH_ARM))
+        }
+        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    };
+
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Expr::MatchExpr(Box::new(subject), arms, interval)))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_match(s: Span) -> IResult<Span, Expr> {
+        preceded(comment, parse_match)(s)
+    }
+
+    #[test]
+    fn ok_match_implicit_arms() {
+        let string = Span::new(
+            "match intent { \"greeting\" => goto greet, \"bye\" => goto end, _ => goto fallback }",
+        );
+        match test_match(string) {
+            Ok((_, Expr::MatchExpr(_, arms, _))) => assert_eq!(arms.len(), 3),
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_match_scope_arms() {
+        let string =
+            Span::new("match value { 1 => { say \"one\" say \"un\" } 2.5 => say \"two\" }");
+        match test_match(string) {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_match_empty() {
+        let string = Span::new("match value {}");
+        match test_match(string) {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_match_regex_arm() {
+        let string =
+            Span::new("match text { /hello.*world/i => say \"hi\", /a\\/b/ => say \"path\" }");
+        match test_match(string) {
+            Ok((_, Expr::MatchExpr(_, arms, _))) => match (&arms[0].0, &arms[1].0) {
+                (Expr::RegexExpr(first, _), Expr::RegexExpr(second, _)) => {
+                    assert_eq!(first, "(?i)hello.*world");
+                    assert_eq!(second, "a/b");
+                }
+                (first, second) => panic!("{:?} {:?}", first, second),
+            },
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_match_invalid_regex() {
+        let string = Span::new("match text { /hello(/ => say \"hi\" }");
+        match test_match(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_match_regex_invalid_flag() {
+        let string = Span::new("match text { /hello/g => say \"hi\" }");
+        match test_match(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_match_missing_arrow() {
+        let string = Span::new("match value { \"a\" say \"a\" }");
+        match test_match(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_match_invalid_pattern() {
+        let string = Span::new("match value { var => say \"a\" }");
+        match test_match(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_match_missing_brace() {
+        let string = Span::new("match value \"a\" => say \"a\"");
+        match test_match(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
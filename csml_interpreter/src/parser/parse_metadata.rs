@@ -0,0 +1,184 @@
+use crate::data::{
+    ast::{Expr, Interval},
+    tokens::{Span, COLON, COMMA, DOUBLE_QUOTE, L_BRACE, METADATA, R_BRACE},
+};
+use crate::error_format::{gen_nom_failure, ERROR_METADATA, ERROR_METADATA_DUPLICATE};
+use crate::parser::{
+    operator::parse_operator,
+    parse_comments::comment,
+    tools::{get_interval, get_string},
+};
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_till1},
+    combinator::{not, opt},
+    error::{ContextError, ParseError},
+    multi::separated_list0,
+    sequence::{delimited, preceded, terminated, tuple},
+    Err, IResult,
+};
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// keys are identifiers or strings: version: "1.2" or "version": "1.2"
+fn parse_key<'a, E>(s: Span<'a>) -> IResult<Span<'a>, String, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, key) = preceded(
+        comment,
+        alt((
+            delimited(
+                tag(DOUBLE_QUOTE),
+                take_till1(|c: char| c == '"'),
+                tag(DOUBLE_QUOTE),
+            ),
+            take_till1(|c: char| c != '-' && c != '_' && !c.is_alphanumeric()),
+        )),
+    )(s)?;
+
+    Ok((s, key.fragment().to_string()))
+}
+
+fn parse_field<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (String, Expr), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, key) = parse_key(s)?;
+    let (s, _) = preceded(comment, tag(COLON))(s)?;
+    let (s, value) = preceded(comment, parse_operator)(s)?;
+
+    Ok((s, (key, value)))
+}
+
+fn parse_fields<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Vec<(String, Expr)>, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, (fields, _)) = preceded(
+        preceded(comment, tag(L_BRACE)),
+        terminated(
+            tuple((
+                separated_list0(preceded(comment, tag(COMMA)), parse_field),
+                opt(preceded(comment, tag(COMMA))),
+            )),
+            preceded(comment, tag(R_BRACE)),
+        ),
+    )(s)?;
+
+    Ok((s, fields))
+}
+
+fn parse_metadata_tag<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Interval, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, interval) = preceded(comment, get_interval)(s)?;
+    // @metadata_other is not a metadata block
+    let (s, _) = terminated(tag(METADATA), not(get_string))(s)?;
+
+    Ok((s, interval))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// @metadata { version: "1.2", tags: ["sales"] }, the values are checked as constants
+// when the flow is created
+pub fn parse_metadata<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = parse_metadata_tag(s)?;
+
+    let (s, fields) = match parse_fields::<E>(s) {
+        Ok(value) => value,
+        Err(Err::Error(_)) | Err(Err::Failure(_)) => {
+            let (position, _) = comment(s)?;
+            return Err(gen_nom_failure(position, ERROR_METADATA));
+        }
+        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    };
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    let object: HashMap<String, Expr> = fields.into_iter().collect();
+
+    Ok((
+        s,
+        Expr::MapExpr {
+            object,
+            is_in_sub_string: false,
+            interval,
+        },
+    ))
+}
+
+// a metadata block after the steps or after another metadata block
+pub fn parse_misplaced_metadata<'a, E, O>(s: Span<'a>) -> IResult<Span<'a>, O, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (position, _) = comment(s)?;
+    parse_metadata_tag::<E>(position)?;
+
+    Err(gen_nom_failure(position, ERROR_METADATA_DUPLICATE))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_metadata(s: Span) -> IResult<Span, Expr> {
+        parse_metadata(s)
+    }
+
+    #[test]
+    fn ok_metadata() {
+        let string = Span::new("@metadata { version: \"1.2\", \"tags\": [\"sales\"], }");
+        match test_metadata(string) {
+            Ok((_, Expr::MapExpr { object, .. })) => {
+                assert!(object.contains_key("version"));
+                assert!(object.contains_key("tags"));
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_metadata_empty() {
+        let string = Span::new("@metadata {}");
+        match test_metadata(string) {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_metadata_not_an_object() {
+        let string = Span::new("@metadata [\"sales\"]");
+        match test_metadata(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(Err::Failure(..)) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_metadata_missing_colon() {
+        let string = Span::new("@metadata { version \"1.2\" }");
+        match test_metadata(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
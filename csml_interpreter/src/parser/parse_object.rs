@@ -1,8 +1,9 @@
 use crate::data::{ast::*, tokens::*};
-use crate::parser::{parse_comments::comment, tools::get_interval};
+use crate::parser::{parse_comments::comment, parse_var_types::parse_spread, tools::get_interval};
 
 use crate::parser::operator::parse_operator;
 use nom::{
+    branch::alt,
     bytes::complete::tag,
     bytes::complete::take_till1,
     combinator::{cut, map, opt},
@@ -50,45 +51,105 @@ where
     Ok((s, (key, is_sub_string)))
 }
 
+fn parse_key_value<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Option<(Span<'a>, bool)>, Expr), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, (key, value)) = separated_pair(
+        preceded(comment, string),
+        cut(preceded(comment, tag(COLON))),
+        parse_operator,
+    )(s)?;
+
+    Ok((s, (Some(key), value)))
+}
+
+// spread elements have no key
 fn parse_arguments<'a, E>(
     s: Span<'a>,
-) -> IResult<Span<'a>, (Vec<((Span<'a>, bool), Expr)>, bool), E>
+) -> IResult<Span<'a>, Vec<(Option<(Span<'a>, bool)>, Expr)>, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let (s, result) = separated_list0(
+    separated_list0(
         preceded(comment, tag(COMMA)),
-        separated_pair(
-            preceded(comment, string),
-            cut(preceded(comment, tag(COLON))),
-            parse_operator,
-        ),
-    )(s)?;
-
-    Ok((s, (result, false)))
+        alt((map(parse_spread, |expr| (None, expr)), parse_key_value)),
+    )(s)
 }
 
-fn key_value<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (HashMap<String, Expr>, bool), E>
+fn key_value<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Vec<(Option<String>, Expr)>, bool), E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    map(parse_arguments, |(tuple_vec, mut is_in_sub_string)| {
-        let args_map = tuple_vec
+    map(parse_arguments, |tuple_vec| {
+        let mut is_in_sub_string = false;
+        let args = tuple_vec
             .into_iter()
-            .map(|((key, token_type), value)| {
-                match token_type {
-                    true => is_in_sub_string = true,
-                    false => (),
-                };
+            .map(|(key, value)| match key {
+                Some((key, token_type)) => {
+                    match token_type {
+                        true => is_in_sub_string = true,
+                        false => (),
+                    };
 
-                (String::from(*key.fragment()), value)
+                    (Some(String::from(*key.fragment())), value)
+                }
+                None => (None, value),
             })
             .collect();
 
-        (args_map, is_in_sub_string)
+        (args, is_in_sub_string)
     })(s)
 }
 
+// without spread the object is a MapExpr, otherwise the consecutive keys are grouped
+// in MapExpr parts around the spread elements
+fn object_expr(
+    args: Vec<(Option<String>, Expr)>,
+    is_in_sub_string: bool,
+    interval: Interval,
+) -> Expr {
+    let mut parts = vec![];
+    let mut object = HashMap::new();
+
+    for (key, value) in args {
+        match key {
+            Some(key) => {
+                object.insert(key, value);
+            }
+            None => {
+                if !object.is_empty() {
+                    parts.push(Expr::MapExpr {
+                        object: std::mem::take(&mut object),
+                        is_in_sub_string,
+                        interval,
+                    });
+                }
+
+                parts.push(value);
+            }
+        }
+    }
+
+    if parts.is_empty() {
+        return Expr::MapExpr {
+            object,
+            is_in_sub_string,
+            interval,
+        };
+    }
+
+    if !object.is_empty() {
+        parts.push(Expr::MapExpr {
+            object,
+            is_in_sub_string,
+            interval,
+        });
+    }
+
+    Expr::SpreadMapExpr(parts, interval)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
@@ -99,7 +160,7 @@ where
 {
     let (s, mut interval) = preceded(comment, get_interval)(s)?;
     // the 'is_in_sub_string' param is use to determine if this object was declare inside a string or not
-    let (s, ((object, is_in_sub_string), _trailing_comma)) = preceded(
+    let (s, ((args, is_in_sub_string), _trailing_comma)) = preceded(
         tag(L_BRACE),
         terminated(
             tuple((key_value, opt(preceded(comment, tag(COMMA))))),
@@ -110,12 +171,5 @@ where
     let (s, end) = preceded(comment, get_interval)(s)?;
     interval.add_end(end);
 
-    Ok((
-        s,
-        Expr::MapExpr {
-            object,
-            is_in_sub_string,
-            interval,
-        },
-    ))
+    Ok((s, object_expr(args, is_in_sub_string, interval)))
 }
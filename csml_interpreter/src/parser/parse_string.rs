@@ -15,31 +15,76 @@ use nom::{
     sequence::{delimited, preceded},
     *,
 };
+use std::iter::Peekable;
+use std::str::CharIndices;
 use std::sync::mpsc;
 
-use nom::branch::alt;
-use nom::bytes::complete::escaped_transform;
-use nom::character::complete::{anychar, satisfy};
-use nom::combinator::value;
-
 ////////////////////////////////////////////////////////////////////////////////
 // TOOL FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////};
 
-fn parser(input: &str) -> IResult<&str, String> {
-    escaped_transform(
-        satisfy(|c| c != '\\'),
-        '\\',
-        alt((
-            value('\n', tag("n")),
-            value('\t', tag("t")),
-            value('\r', tag("r")),
-            value('\'', tag("\'")),
-            value('\"', tag("\"")),
-            value('\\', tag("\\")),
-            anychar,
-        )),
-    )(input)
+// \uXXXX with exactly 4 hex digits, or \u{X} with 1 to 6 hex digits
+fn unicode_escape(chars: &mut Peekable<CharIndices>) -> Option<char> {
+    let mut hex = String::new();
+
+    match chars.peek() {
+        Some((_, '{')) => {
+            chars.next();
+            loop {
+                match chars.next()? {
+                    (_, '}') => break,
+                    (_, c) if c.is_ascii_hexdigit() && hex.len() < 6 => hex.push(c),
+                    _ => return None,
+                }
+            }
+            if hex.is_empty() {
+                return None;
+            }
+        }
+        _ => {
+            for _ in 0..4 {
+                match chars.next()? {
+                    (_, c) if c.is_ascii_hexdigit() => hex.push(c),
+                    _ => return None,
+                }
+            }
+        }
+    }
+
+    std::char::from_u32(u32::from_str_radix(&hex, 16).ok()?)
+}
+
+// decode the escape sequences of a string, on error returns the byte offset of the
+// backslash starting the invalid sequence
+fn unescape(string: &str) -> Result<String, (usize, &'static str)> {
+    let mut result = String::with_capacity(string.len());
+    let mut chars = string.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        let escaped = match chars.next() {
+            Some((_, 'n')) => '\n',
+            Some((_, 't')) => '\t',
+            Some((_, 'r')) => '\r',
+            Some((_, '0')) => '\0',
+            Some((_, '\\')) => '\\',
+            Some((_, '"')) => '"',
+            Some((_, '\'')) => '\'',
+            Some((_, '{')) => '{',
+            Some((_, '}')) => '}',
+            Some((_, 'u')) => {
+                unicode_escape(&mut chars).ok_or((index, ERROR_STRING_UNICODE_ESCAPE))?
+            }
+            _ => return Err((index, ERROR_STRING_ESCAPE)),
+        };
+        result.push(escaped);
+    }
+
+    Ok(result)
 }
 
 fn add_to_vector<'a, E>(
@@ -54,7 +99,13 @@ where
     let (rest, value) = s.take_split(length);
     let (value, interval) = get_interval(value)?;
 
-    let (_, string) = parser(&value.fragment()).unwrap_or(("", value.fragment().to_string()));
+    let string = match unescape(value.fragment()) {
+        Ok(string) => string,
+        Err((offset, error)) => {
+            let (backslash, _) = value.take_split(offset);
+            return Err(gen_nom_failure_in_place(backslash, error));
+        }
+    };
 
     expr_vector.push(Expr::LitExpr {
         literal: PrimitiveString::get_literal(&string, interval),
@@ -134,10 +185,73 @@ where
     Ok((s, None))
 }
 
+// a heredoc opening with a line break is dedented to its least indented line, the line
+// breaks after the opening and before the closing quotes are not part of the string
+fn format_heredoc(content: &str) -> String {
+    let content = match content
+        .strip_prefix("\r\n")
+        .or_else(|| content.strip_prefix('\n'))
+    {
+        Some(content) => content,
+        None => return content.to_owned(),
+    };
+
+    let mut lines: Vec<&str> = content.split('\n').collect();
+    if let Some(last) = lines.last() {
+        if last.trim().is_empty() {
+            lines.pop();
+        }
+    }
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(|c| c == ' ' || c == '\t').len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
+// raw string between triple quotes, escapes and '{{ }}' are kept as they are
+fn parse_heredoc<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (rest, _) = tag(TRIPLE_DOUBLE_QUOTE)(s)?;
+
+    match rest.find_substring(TRIPLE_DOUBLE_QUOTE) {
+        Some(distance) => {
+            let (rest, content) = rest.take_split(distance);
+            let (_, mut interval) = get_interval(s)?;
+            let (rest, _) = tag(TRIPLE_DOUBLE_QUOTE)(rest)?;
+            let (rest, end) = get_interval(rest)?;
+            interval.add_end(end);
+
+            Ok((
+                rest,
+                Expr::LitExpr {
+                    literal: PrimitiveString::get_literal(
+                        &format_heredoc(content.fragment()),
+                        interval,
+                    ),
+                    in_in_substring: false,
+                },
+            ))
+        }
+        // the rest of the flow is inside the heredoc, report its opening quotes
+        None => Err(gen_nom_failure(s, ERROR_UNTERMINATED_HEREDOC)),
+    }
+}
+
 fn parse_complex_string<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
@@ -172,8 +286,7 @@ fn check_escaped_right_brace<'a, E>(
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let mut ref_srt = string.fragment().chars();
-    if len > 1 && ref_srt.nth(len - 1) == Some('\\') {
+    if string.fragment()[..len].ends_with('\\') {
         let (split_rest, split_string) = string.take_split(len + 2);
 
         add_to_vector(
@@ -186,7 +299,9 @@ where
 
         Ok((s, ()))
     } else {
-        Err(gen_nom_failure(s, ERROR_DOUBLE_CLOSE_BRACE))
+        let (braces, _) = string.take_split(len);
+
+        Err(gen_nom_failure_in_place(braces, ERROR_DOUBLE_OPEN_BRACE))
     }
 }
 
@@ -200,14 +315,15 @@ fn check_escaped_left_brace<'a, E>(
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
-    let mut ref_srt = string.fragment().chars();
-    if len > 1 && ref_srt.nth(len - 1) == Some('\\') {
+    if string.fragment()[..len].ends_with('\\') {
         let (res, _) = add_to_vector(*string, string.fragment().len(), vector, interval)?;
         *string = res;
 
         Ok((s, ()))
     } else {
-        return Err(gen_nom_failure(s, ERROR_DOUBLE_CLOSE_BRACE));
+        let (braces, _) = string.take_split(len);
+
+        Err(gen_nom_failure_in_place(braces, ERROR_DOUBLE_CLOSE_BRACE))
     }
 }
 
@@ -310,6 +426,10 @@ where
 {
     let (start, _) = get_interval(s)?;
 
+    if let Ok(..) = tag(TRIPLE_DOUBLE_QUOTE)(s) as IResult<Span<'a>, Span<'a>, E> {
+        return parse_heredoc(s);
+    }
+
     let toto = match (
         tag(DOUBLE_QUOTE)(s) as IResult<Span<'a>, Span<'a>, E>,
         tag(BACKSLASH_DOUBLE_QUOTE)(s) as IResult<Span<'a>, Span<'a>, E>,
@@ -700,4 +820,144 @@ mod tests {
             Err(_) => {}
         }
     }
+
+    //////////////////////////////////////////////////////////////////////////
+    /// ESCAPE SEQUENCES
+    //////////////////////////////////////////////////////////////////////////
+
+    fn escaped_value(string: &str) -> String {
+        match test_string(Span::new(string)) {
+            Ok((_, Expr::ComplexLiteral(vector, ..))) => vector
+                .iter()
+                .map(|expr| match expr {
+                    Expr::LitExpr { literal, .. } => literal.primitive.to_string(),
+                    expr => panic!("expecting a literal, got {:?}", expr),
+                })
+                .collect(),
+            Ok((_, expr)) => panic!("expecting a string, got {:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    fn escape_error(flow: &str) -> crate::data::error_info::ErrorInfo {
+        match crate::parser::parse_flow(flow, "flow") {
+            Ok(..) => panic!("need to fail"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn ok_escape_control_characters() {
+        assert_eq!(escaped_value(r#""a\nb\tc\rd\0""#), "a\nb\tc\rd\0");
+    }
+
+    #[test]
+    fn ok_escape_quotes_and_backslash() {
+        assert_eq!(
+            escaped_value(r#""\"a\" \'b\' c:\\new""#),
+            r#""a" 'b' c:\new"#
+        );
+    }
+
+    #[test]
+    fn ok_escape_unicode_4_digits() {
+        assert_eq!(escaped_value(r#""caf\u00e9 \u00E9""#), "café é");
+    }
+
+    #[test]
+    fn ok_escape_unicode_braces() {
+        assert_eq!(escaped_value(r#""\u{1F600} \u{41}""#), "😀 A");
+    }
+
+    #[test]
+    fn ok_escape_braces_stay_literal() {
+        assert_eq!(escaped_value(r#""\{{ name }}""#), "{{ name }}");
+        assert_eq!(escaped_value(r#""a \}} b""#), "a }} b");
+    }
+
+    #[test]
+    fn ok_escape_with_interpolation() {
+        let string = r#""\t{{ name }}\u{1F600}""#;
+
+        match test_string(Span::new(string)) {
+            Ok((_, Expr::ComplexLiteral(vector, ..))) => assert_eq!(vector.len(), 3),
+            Ok((_, expr)) => panic!("expecting a string, got {:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_escape_invalid() {
+        let e = escape_error("start:\n    say \"hello \\q\"\n    goto end\n");
+
+        assert!(e.message.contains(ERROR_STRING_ESCAPE));
+        assert_eq!(e.position.interval.start_line, 2);
+        assert_eq!(e.position.interval.start_column, 16);
+    }
+
+    #[test]
+    fn err_escape_unicode_malformed() {
+        for escape in ["\\u12", "\\u{}", "\\u{1234567}", "\\u{D800}", "\\u{1F600"].iter() {
+            let flow = format!("start:\n    say \"a{}\"\n    goto end\n", escape);
+            let e = escape_error(&flow);
+
+            assert!(
+                e.message.contains(ERROR_STRING_UNICODE_ESCAPE),
+                "{}",
+                escape
+            );
+            assert_eq!(e.position.interval.start_line, 2);
+            assert_eq!(e.position.interval.start_column, 11);
+        }
+    }
+
+    //////////////////////////////////////////////////////////////////////////
+    /// HEREDOC STRINGS
+    //////////////////////////////////////////////////////////////////////////
+
+    fn heredoc_value(string: &str) -> String {
+        match test_string(Span::new(string)) {
+            Ok((_, Expr::LitExpr { literal, .. })) => literal.primitive.to_string(),
+            Ok((_, expr)) => panic!("expecting a literal, got {:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_heredoc_inline() {
+        let string = r#"""" {"text": "{{ name }}\n"} """"#;
+
+        assert_eq!(heredoc_value(string), r#" {"text": "{{ name }}\n"} "#);
+    }
+
+    #[test]
+    fn ok_heredoc_dedent() {
+        let string = "\"\"\"\n        first\n\n          second\n    \"\"\"";
+
+        assert_eq!(heredoc_value(string), "first\n\n  second");
+    }
+
+    #[test]
+    fn ok_heredoc_say() {
+        let flow = "start:\n    say \"\"\"\n        Dear {{ name }},\n        \"quoted\" \\n\n    \"\"\"\n    goto end\n";
+
+        match crate::parser::parse_flow(flow, "flow") {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_heredoc_unterminated() {
+        let flow = "start:\n    say \"\"\"\n        Hello\n    goto end\n";
+
+        match crate::parser::parse_flow(flow, "flow") {
+            Ok(..) => panic!("need to fail"),
+            Err(e) => {
+                assert!(e.message.contains(ERROR_UNTERMINATED_HEREDOC));
+                assert_eq!(e.position.interval.start_line, 2);
+                assert_eq!(e.position.interval.start_column, 9);
+            }
+        }
+    }
 }
@@ -0,0 +1,144 @@
+use crate::data::{
+    ast::{Expr, Identifier},
+    tokens::{Span, CATCH, L_PAREN, R_PAREN, TRY},
+};
+use crate::error_format::{gen_nom_failure, ERROR_TRY_CATCH};
+use crate::parser::parse_idents::parse_idents_assignation;
+use crate::parser::{
+    parse_comments::comment,
+    parse_scope::parse_scope,
+    tools::{get_interval, get_string, get_tag},
+};
+use nom::{
+    bytes::complete::tag,
+    combinator::cut,
+    error::{ContextError, ParseError},
+    sequence::preceded,
+    *,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse_catch_keyword<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, name) = preceded(comment, get_string)(s)?;
+    let (s, ..) = get_tag(name, CATCH)(s)?;
+    let (s, _) = preceded(comment, tag(L_PAREN))(s)?;
+
+    Ok((s, ()))
+}
+
+fn parse_catch<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Identifier, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, _) = match parse_catch_keyword(s) {
+        Ok(value) => value,
+        Err(Err::Error((_input, _err))) | Err(Err::Failure((_input, _err))) => {
+            return Err(gen_nom_failure(s, ERROR_TRY_CATCH))
+        }
+        Err(Err::Incomplete(needed)) => return Err(Err::Incomplete(needed)),
+    };
+
+    let (s, ident) = cut(parse_idents_assignation)(s)?;
+
+    match preceded(comment, tag(R_PAREN))(s) {
+        Ok((s, _)) => Ok((s, ident)),
+        Err(Err::Error((_input, _err))) | Err(Err::Failure((_input, _err))) => {
+            Err(gen_nom_failure(s, ERROR_TRY_CATCH))
+        }
+        Err(Err::Incomplete(needed)) => Err(Err::Incomplete(needed)),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTION
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn parse_try_catch<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, name) = get_string(s)?;
+    let (s, ..) = get_tag(name, TRY)(s)?;
+
+    let (s, try_block) = cut(parse_scope)(s)?;
+    let (s, ident) = parse_catch(s)?;
+    let (s, catch_block) = cut(parse_scope)(s)?;
+
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((
+        s,
+        Expr::TryCatchExpr(try_block, ident, catch_block, interval),
+    ))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_try_catch(s: Span) -> IResult<Span, Expr> {
+        preceded(comment, parse_try_catch)(s)
+    }
+
+    #[test]
+    fn ok_try_catch() {
+        let string = Span::new("try { say \"hello\" } catch (err) { say err.message }");
+        match test_try_catch(string) {
+            Ok((_, Expr::TryCatchExpr(try_block, ident, catch_block, _))) => {
+                assert_eq!(try_block.commands.len(), 1);
+                assert_eq!(ident.ident, "err");
+                assert_eq!(catch_block.commands.len(), 1);
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_try_catch_empty() {
+        let string = Span::new("try {} catch(err) {}");
+        match test_try_catch(string) {
+            Ok(..) => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_try_identifier() {
+        let string = Span::new("try_count");
+        match test_try_catch(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_try_missing_catch() {
+        let string = Span::new("try { say \"hello\" } say \"bye\"");
+        match test_try_catch(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_catch_missing_ident() {
+        let string = Span::new("try { say \"hello\" } catch { say \"bye\" }");
+        match test_try_catch(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::data::{ast::*, primitive::PrimitiveInt, tokens::*};
-use crate::error_format::{gen_nom_failure, ERROR_RIGHT_BRACKET};
+use crate::error_format::{
+    gen_nom_failure, ERROR_FN_DEFAULT_ARGS, ERROR_FN_DUPLICATE_ARG, ERROR_FN_NAMED_ARGS,
+    ERROR_RIGHT_BRACKET,
+};
 use crate::parser::{
     operator::{parse_operator, tools::parse_item_operator},
     parse_built_in::parse_built_in,
@@ -23,6 +26,7 @@ use nom::{
     sequence::{delimited, preceded, terminated, tuple},
     Err, IResult,
 };
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -71,6 +75,30 @@ where
     ))
 }
 
+fn parse_fn_arg<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Span<'a>, String, Option<Expr>), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (position, _) = comment(s)?;
+    let (s, name) = get_string(position)?;
+    let (s, default) = opt(preceded(
+        preceded(comment, tag(ASSIGN)),
+        cut(parse_operator),
+    ))(s)?;
+
+    Ok((s, (position, name, default)))
+}
+
+fn parse_call_arg<'a, E>(s: Span<'a>) -> IResult<Span<'a>, (Span<'a>, Expr), E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (position, _) = comment(s)?;
+    let (s, expr) = alt((parse_assignation_without_path, parse_operator))(position)?;
+
+    Ok((s, (position, expr)))
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
@@ -97,7 +125,9 @@ where
     Ok((s, Expr::IdentExpr(idents)))
 }
 
-pub fn parse_fn_args<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Vec<String>, E>
+pub fn parse_fn_args<'a, E>(
+    s: Span<'a>,
+) -> IResult<Span<'a>, (Vec<String>, HashMap<String, Expr>), E>
 where
     E: ParseError<Span<'a>> + ContextError<Span<'a>>,
 {
@@ -109,7 +139,7 @@ where
             tag(L_PAREN),
             terminated(
                 tuple((
-                    separated_list0(preceded(comment, tag(COMMA)), preceded(comment, get_string)),
+                    separated_list0(preceded(comment, tag(COMMA)), parse_fn_arg),
                     opt(preceded(comment, tag(COMMA))),
                 )),
                 cut(parse_r_parentheses),
@@ -117,7 +147,26 @@ where
         ),
     )?;
 
-    Ok((s, vec))
+    let mut args = Vec::with_capacity(vec.len());
+    let mut defaults = HashMap::new();
+
+    for (position, name, default) in vec {
+        match default {
+            Some(expr) => {
+                defaults.insert(name.to_owned(), expr);
+            }
+            // missing arguments are filled from left to right, a required argument
+            // can't follow an argument with a default value
+            None if !defaults.is_empty() => {
+                return Err(gen_nom_failure(position, ERROR_FN_DEFAULT_ARGS))
+            }
+            None => {}
+        }
+
+        args.push(name);
+    }
+
+    Ok((s, (args, defaults)))
 }
 
 pub fn parse_expr_list<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -132,10 +181,7 @@ where
             tag(L_PAREN),
             terminated(
                 tuple((
-                    separated_list0(
-                        preceded(comment, tag(COMMA)),
-                        alt((parse_assignation_without_path, parse_operator)),
-                    ),
+                    separated_list0(preceded(comment, tag(COMMA)), parse_call_arg),
                     opt(preceded(comment, tag(COMMA))),
                 )),
                 cut(parse_r_parentheses),
@@ -145,7 +191,41 @@ where
     let (s, end) = get_interval(s)?;
     interval.add_end(end);
 
-    Ok((s, Expr::VecExpr(vec, interval)))
+    // name = value arguments are bound by name, they come after the positional ones
+    let mut names = vec![];
+    let mut args = Vec::with_capacity(vec.len());
+
+    for (position, expr) in vec {
+        match &expr {
+            Expr::ObjectExpr(ObjectType::Assign(_, name, _)) => {
+                if let Expr::IdentExpr(Identifier { ident, .. }) = &**name {
+                    if names.contains(ident) {
+                        return Err(gen_nom_failure(position, ERROR_FN_DUPLICATE_ARG));
+                    }
+                    names.push(ident.to_owned());
+                }
+            }
+            _ if !names.is_empty() => return Err(gen_nom_failure(position, ERROR_FN_NAMED_ARGS)),
+            _ => {}
+        }
+
+        args.push(expr);
+    }
+
+    Ok((s, Expr::VecExpr(args, interval)))
+}
+
+// ...expr, spreads an array or an object inside a literal of the same type
+pub fn parse_spread<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
+where
+    E: ParseError<Span<'a>> + ContextError<Span<'a>>,
+{
+    let (s, mut interval) = preceded(comment, get_interval)(s)?;
+    let (s, expr) = preceded(tag(SPREAD), cut(parse_operator))(s)?;
+    let (s, end) = get_interval(s)?;
+    interval.add_end(end);
+
+    Ok((s, Expr::SpreadExpr(Box::new(expr), interval)))
 }
 
 pub fn parse_expr_array<'a, E>(s: Span<'a>) -> IResult<Span<'a>, Expr, E>
@@ -161,7 +241,10 @@ where
             tag(L_BRACKET),
             terminated(
                 tuple((
-                    separated_list0(preceded(comment, tag(COMMA)), parse_operator), //parse_basic_expr
+                    separated_list0(
+                        preceded(comment, tag(COMMA)),
+                        alt((parse_spread, parse_operator)),
+                    ), //parse_basic_expr
                     opt(preceded(comment, tag(COMMA))),
                 )),
                 preceded(comment, parse_r_bracket),
@@ -198,3 +281,125 @@ where
     let (s, _) = comment(s)?;
     Ok((s, expr))
 }
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub fn test_basic_expr(s: Span) -> IResult<Span, Expr> {
+        parse_basic_expr(s)
+    }
+
+    #[test]
+    fn ok_array_spread() {
+        let string = Span::new("[...first, 42, ...[1, 2]]");
+        match test_basic_expr(string) {
+            Ok((_, Expr::VecExpr(vec, _))) => {
+                assert_eq!(vec.len(), 3);
+                assert!(matches!(vec[0], Expr::SpreadExpr(..)));
+                assert!(matches!(vec[1], Expr::LitExpr { .. }));
+                assert!(
+                    matches!(&vec[2], Expr::SpreadExpr(expr, _) if matches!(**expr, Expr::VecExpr(..)))
+                );
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_object_spread() {
+        let string = Span::new("{...base, \"key\": 42, \"other\": 0, ...overrides}");
+        match test_basic_expr(string) {
+            Ok((_, Expr::SpreadMapExpr(parts, _))) => {
+                assert_eq!(parts.len(), 3);
+                assert!(matches!(parts[0], Expr::SpreadExpr(..)));
+                assert!(matches!(&parts[1], Expr::MapExpr { object, .. } if object.len() == 2));
+                assert!(matches!(parts[2], Expr::SpreadExpr(..)));
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_object_without_spread() {
+        let string = Span::new("{\"key\": 42}");
+        match test_basic_expr(string) {
+            Ok((_, Expr::MapExpr { .. })) => {}
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    pub fn test_expr_list(s: Span) -> IResult<Span, Expr> {
+        parse_expr_list(s)
+    }
+
+    fn arg_name(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::ObjectExpr(ObjectType::Assign(_, name, _)) => match &**name {
+                Expr::IdentExpr(Identifier { ident, .. }) => Some(ident),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn ok_named_args_reordered() {
+        let string = Span::new("(greeting = \"Hi\", name = \"Bob\")");
+        match test_expr_list(string) {
+            Ok((_, Expr::VecExpr(vec, _))) => {
+                let names: Vec<Option<&str>> = vec.iter().map(arg_name).collect();
+                assert_eq!(names, vec![Some("greeting"), Some("name")]);
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn ok_positional_then_named_args() {
+        let string = Span::new("(\"Bob\", greeting = \"Hi\")");
+        match test_expr_list(string) {
+            Ok((_, Expr::VecExpr(vec, _))) => {
+                let names: Vec<Option<&str>> = vec.iter().map(arg_name).collect();
+                assert_eq!(names, vec![None, Some("greeting")]);
+            }
+            Ok((_, expr)) => panic!("{:?}", expr),
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    #[test]
+    fn err_named_args_duplicate() {
+        let string = Span::new("(name = \"Bob\", name = \"Alice\")");
+        match test_expr_list(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_positional_after_named_args() {
+        let string = Span::new("(name = \"Bob\", \"Hi\")");
+        match test_expr_list(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+
+    #[test]
+    fn err_spread_without_expr() {
+        let string = Span::new("[1, ...]");
+        match test_basic_expr(string) {
+            Ok(..) => panic!("need to fail"),
+            Err(..) => {}
+        }
+    }
+}
@@ -9,8 +9,8 @@ pub enum ExitCondition {
     Goto,
     End,
     Error,
-    Break,
-    Continue,
+    Break(Option<String>), // label of the targeted loop
+    Continue(Option<String>),
     Hold,
     Return(Literal),
 }
@@ -19,24 +19,29 @@ pub enum ExitCondition {
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-fn count_if_commands(if_statement: &mut IfStatement, index: &mut usize) {
-    match if_statement {
-        IfStatement::IfStmt {
-            consequence: scope,
-            then_branch,
-            last_action_index,
-            ..
-        } => {
-            count_scope_commands(scope, index);
-            if *index >= 1 {
-                *last_action_index = *index - 1;
-            }
+pub fn is_loop_target(target: &Option<String>, label: &Option<Identifier>) -> bool {
+    // a break or continue without label targets the innermost loop
+    match (target, label) {
+        (None, _) => true,
+        (Some(target), Some(label)) => *target == label.ident,
+        (Some(_), None) => false,
+    }
+}
 
-            if let Some(else_scope) = then_branch {
-                count_if_commands(else_scope, index)
-            }
+fn count_if_commands(
+    branches: &mut Vec<IfBranch>,
+    else_body: &mut Option<Block>,
+    index: &mut usize,
+) {
+    for branch in branches.iter_mut() {
+        count_scope_commands(&mut branch.consequence, index);
+        if *index >= 1 {
+            branch.last_action_index = *index - 1;
         }
-        IfStatement::ElseStmt(scope, ..) => count_scope_commands(scope, index),
+    }
+
+    if let Some(else_body) = else_body {
+        count_scope_commands(else_body, index)
     }
 }
 
@@ -55,11 +60,15 @@ pub fn count_commands(command: &mut Expr, index: &mut usize, info: &mut Instruct
             *index = *index + 1
         }
 
-        Expr::IfExpr(if_statement) => {
+        Expr::IfExpr {
+            branches,
+            else_body,
+            ..
+        } => {
             info.index = *index;
-            count_if_commands(if_statement, index)
+            count_if_commands(branches, else_body, index)
         }
-        Expr::ForEachExpr(_ident, _index, _expr, block, _range) => {
+        Expr::ForEachExpr(_ident, _index, _expr, block, _label, _range) => {
             info.index = *index;
             count_scope_commands(block, index)
         }
@@ -67,6 +76,17 @@ pub fn count_commands(command: &mut Expr, index: &mut usize, info: &mut Instruct
             info.index = *index;
             count_scope_commands(block, index)
         }
+        Expr::MatchExpr(_subject, arms, _range) => {
+            info.index = *index;
+            for (_pattern, block) in arms.iter_mut() {
+                count_scope_commands(block, index)
+            }
+        }
+        Expr::TryCatchExpr(try_block, _ident, catch_block, _range) => {
+            info.index = *index;
+            count_scope_commands(try_block, index);
+            count_scope_commands(catch_block, index)
+        }
         _ => {}
     }
 
@@ -0,0 +1,458 @@
+use crate::data::ast::{
+    AssignType, Block, DoType, Expr, Flow, Function, Infix, InstructionScope, ObjectType, PathState,
+};
+use crate::data::position::Position;
+use crate::data::primitive::{
+    PrimitiveArray, PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveNull, PrimitiveString,
+    PrimitiveType,
+};
+use crate::error_format::*;
+
+use std::collections::HashMap;
+
+// type of each variable of the walked scope, None when it can't be known without running
+// the flow: memories, event, results of functions or values set differently by two branches
+type Types = HashMap<String, Option<PrimitiveType>>;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn check_method(
+    primitive_type: &PrimitiveType,
+    function: &Function,
+    flow_name: &str,
+    errors: &mut Vec<ErrorInfo>,
+) {
+    let (has_method, message) = match primitive_type {
+        PrimitiveType::PrimitiveString => (
+            PrimitiveString::has_method(&function.name),
+            ERROR_STRING_UNKNOWN_METHOD,
+        ),
+        PrimitiveType::PrimitiveInt => (
+            PrimitiveInt::has_method(&function.name),
+            ERROR_INT_UNKNOWN_METHOD,
+        ),
+        PrimitiveType::PrimitiveFloat => (
+            PrimitiveFloat::has_method(&function.name),
+            ERROR_FLOAT_UNKNOWN_METHOD,
+        ),
+        PrimitiveType::PrimitiveBoolean => (
+            PrimitiveBoolean::has_method(&function.name),
+            ERROR_BOOLEAN_UNKNOWN_METHOD,
+        ),
+        PrimitiveType::PrimitiveArray => (
+            PrimitiveArray::has_method(&function.name),
+            ERROR_ARRAY_UNKNOWN_METHOD,
+        ),
+        PrimitiveType::PrimitiveNull => (
+            PrimitiveNull::has_method(&function.name),
+            ERROR_NULL_UNKNOWN_METHOD,
+        ),
+        // the methods of an object depend on its content type (http, time, ...)
+        PrimitiveType::PrimitiveObject | PrimitiveType::PrimitiveClosure => (true, ""),
+    };
+
+    if !has_method {
+        errors.push(gen_error_info(
+            Position::new(function.interval, flow_name),
+            format!("[{}] {}", function.name, message),
+        ));
+    }
+}
+
+// the types of the variables after one of several branches was executed, a variable
+// keeps its type only if it has it at the end of every branch
+fn merge_types(branches: Vec<Types>) -> Types {
+    let mut merged = Types::new();
+
+    for branch in branches.iter() {
+        for (name, primitive_type) in branch.iter() {
+            let is_same = branches
+                .iter()
+                .all(|other| other.get(name) == Some(primitive_type));
+
+            match is_same {
+                true => merged.insert(name.to_owned(), primitive_type.to_owned()),
+                false => merged.insert(name.to_owned(), None),
+            };
+        }
+    }
+
+    merged
+}
+
+fn check_expr(
+    expr: &Expr,
+    types: &mut Types,
+    flow_name: &str,
+    errors: &mut Vec<ErrorInfo>,
+) -> Option<PrimitiveType> {
+    match expr {
+        Expr::LitExpr { literal, .. } => Some(literal.primitive.get_type()),
+        Expr::ComplexLiteral(exprs, _) => {
+            for expr in exprs.iter() {
+                check_expr(expr, types, flow_name, errors);
+            }
+            Some(PrimitiveType::PrimitiveString)
+        }
+        Expr::VecExpr(exprs, _) => {
+            for expr in exprs.iter() {
+                check_expr(expr, types, flow_name, errors);
+            }
+            Some(PrimitiveType::PrimitiveArray)
+        }
+        Expr::MapExpr { object, .. } => {
+            for expr in object.values() {
+                check_expr(expr, types, flow_name, errors);
+            }
+            Some(PrimitiveType::PrimitiveObject)
+        }
+        Expr::SpreadMapExpr(exprs, _) => {
+            for expr in exprs.iter() {
+                check_expr(expr, types, flow_name, errors);
+            }
+            Some(PrimitiveType::PrimitiveObject)
+        }
+        Expr::SpreadExpr(expr, _) => check_expr(expr, types, flow_name, errors),
+        Expr::IdentExpr(ident) => types.get(&ident.ident).cloned().flatten(),
+        Expr::PathExpr { literal, path } => {
+            let mut primitive_type = check_expr(literal, types, flow_name, errors);
+
+            for (_interval, state) in path.iter() {
+                primitive_type = match state {
+                    PathState::Func(function) => {
+                        if let Some(primitive_type) = &primitive_type {
+                            check_method(primitive_type, function, flow_name, errors);
+                        }
+                        check_expr(&function.args, types, flow_name, errors);
+                        None
+                    }
+                    PathState::ExprIndex(expr) => {
+                        check_expr(expr, types, flow_name, errors);
+                        None
+                    }
+                    PathState::StringIndex(_) => None,
+                };
+            }
+
+            primitive_type
+        }
+        Expr::InfixExpr(infix, lhs, rhs) => {
+            check_expr(lhs, types, flow_name, errors);
+            check_expr(rhs, types, flow_name, errors);
+
+            match infix {
+                Infix::Match
+                | Infix::NotMatch
+                | Infix::Equal
+                | Infix::NotEqual
+                | Infix::GreaterThanEqual
+                | Infix::LessThanEqual
+                | Infix::GreaterThan
+                | Infix::LessThan => Some(PrimitiveType::PrimitiveBoolean),
+                Infix::BitwiseAnd(_)
+                | Infix::BitwiseOr(_)
+                | Infix::BitwiseXor(_)
+                | Infix::ShiftLeft(_)
+                | Infix::ShiftRight(_) => Some(PrimitiveType::PrimitiveInt),
+                _ => None,
+            }
+        }
+        Expr::PostfixExpr(_, expr) => {
+            check_expr(expr, types, flow_name, errors);
+            Some(PrimitiveType::PrimitiveBoolean)
+        }
+        Expr::TernaryExpr(cond, then, otherwise, _) => {
+            check_expr(cond, types, flow_name, errors);
+            let then = check_expr(then, types, flow_name, errors);
+            let otherwise = check_expr(otherwise, types, flow_name, errors);
+
+            if then == otherwise {
+                then
+            } else {
+                None
+            }
+        }
+        Expr::RangeExpr(start, end, ..) => {
+            check_expr(start, types, flow_name, errors);
+            check_expr(end, types, flow_name, errors);
+            Some(PrimitiveType::PrimitiveArray)
+        }
+        Expr::ObjectExpr(object) => check_object(object, types, flow_name, errors),
+        Expr::ForEachExpr(ident, index, expr, block, ..) => {
+            let iterated = check_expr(expr, types, flow_name, errors);
+
+            let mut loop_types = types.clone();
+            loop_types.insert(ident.ident.to_owned(), None);
+            if let Some(index) = index {
+                // the position of the elements, or the key of the entries of an object
+                let index_type = match iterated {
+                    Some(PrimitiveType::PrimitiveObject) => Some(PrimitiveType::PrimitiveString),
+                    Some(_) => Some(PrimitiveType::PrimitiveInt),
+                    None => None,
+                };
+                loop_types.insert(index.ident.to_owned(), index_type);
+            }
+            check_block(block, &mut loop_types, flow_name, errors);
+
+            // the loop may run zero or several times
+            *types = merge_types(vec![types.clone(), loop_types]);
+            None
+        }
+        Expr::WhileExpr(cond, block, _) => {
+            check_expr(cond, types, flow_name, errors);
+
+            let mut loop_types = types.clone();
+            check_block(block, &mut loop_types, flow_name, errors);
+
+            *types = merge_types(vec![types.clone(), loop_types]);
+            None
+        }
+        // every branch is checked whatever its condition, the types set by the
+        // branches are only kept after the if when they all agree
+        Expr::IfExpr {
+            branches,
+            else_body,
+            ..
+        } => {
+            let mut branches_types = vec![];
+
+            for branch in branches.iter() {
+                check_expr(&branch.cond, types, flow_name, errors);
+
+                let mut branch_types = types.clone();
+                check_block(&branch.consequence, &mut branch_types, flow_name, errors);
+                branches_types.push(branch_types);
+            }
+
+            let mut else_types = types.clone();
+            if let Some(else_body) = else_body {
+                check_block(else_body, &mut else_types, flow_name, errors);
+            }
+            branches_types.push(else_types);
+
+            *types = merge_types(branches_types);
+            None
+        }
+        Expr::MatchExpr(subject, arms, _) => {
+            check_expr(subject, types, flow_name, errors);
+
+            let mut arms_types = vec![types.clone()];
+            for (pattern, block) in arms.iter() {
+                check_expr(pattern, types, flow_name, errors);
+
+                let mut arm_types = types.clone();
+                check_block(block, &mut arm_types, flow_name, errors);
+                arms_types.push(arm_types);
+            }
+
+            *types = merge_types(arms_types);
+            None
+        }
+        Expr::TryCatchExpr(try_block, ident, catch_block, _) => {
+            let mut try_types = types.clone();
+            check_block(try_block, &mut try_types, flow_name, errors);
+
+            let mut catch_types = types.clone();
+            catch_types.insert(ident.ident.to_owned(), None);
+            check_block(catch_block, &mut catch_types, flow_name, errors);
+
+            *types = merge_types(vec![try_types, catch_types]);
+            None
+        }
+        Expr::Scope { scope, .. } => {
+            check_block(scope, types, flow_name, errors);
+            None
+        }
+        Expr::Destructure(pattern, expr, _) => {
+            check_expr(expr, types, flow_name, errors);
+            for ident in pattern.idents() {
+                types.insert(ident.ident.to_owned(), None);
+            }
+            None
+        }
+        Expr::RegexExpr(..) => None,
+    }
+}
+
+fn check_object(
+    object: &ObjectType,
+    types: &mut Types,
+    flow_name: &str,
+    errors: &mut Vec<ErrorInfo>,
+) -> Option<PrimitiveType> {
+    match object {
+        ObjectType::Do(DoType::Update(assign_type, target, expr))
+        | ObjectType::Assign(assign_type, target, expr) => {
+            let primitive_type = check_expr(expr, types, flow_name, errors);
+
+            match (&**target, assign_type) {
+                (Expr::IdentExpr(ident), AssignType::Assignment) => {
+                    types.insert(ident.ident.to_owned(), primitive_type)
+                }
+                (Expr::IdentExpr(ident), _) => types.insert(ident.ident.to_owned(), None),
+                (Expr::PathExpr { literal, .. }, _) => match &**literal {
+                    Expr::IdentExpr(ident) => types.insert(ident.ident.to_owned(), None),
+                    _ => None,
+                },
+                _ => None,
+            };
+            None
+        }
+        ObjectType::Remember(ident, expr)
+        | ObjectType::RememberConversation(ident, expr)
+        | ObjectType::RememberSecret(ident, expr)
+        | ObjectType::As(ident, expr) => {
+            let primitive_type = check_expr(expr, types, flow_name, errors);
+
+            types.insert(ident.ident.to_owned(), primitive_type);
+            None
+        }
+        ObjectType::Do(DoType::Exec(expr))
+        | ObjectType::Say(expr)
+        | ObjectType::Return(expr)
+        | ObjectType::Use(expr)
+        | ObjectType::Debug(expr, _)
+        | ObjectType::HoldSchema(expr, ..)
+        | ObjectType::HoldConfidence(expr, ..)
+        | ObjectType::Delay(expr, _)
+        | ObjectType::Log { expr, .. } => {
+            check_expr(expr, types, flow_name, errors);
+            None
+        }
+        ObjectType::Assert(condition, message, _) => {
+            check_expr(condition, types, flow_name, errors);
+            if let Some(message) = message {
+                check_expr(message, types, flow_name, errors);
+            }
+            None
+        }
+        ObjectType::BuiltIn(function) => {
+            check_expr(&function.args, types, flow_name, errors);
+            None
+        }
+        ObjectType::Goto(..)
+        | ObjectType::Previous(..)
+        | ObjectType::Hold(_)
+        | ObjectType::HoldSecure(_)
+        | ObjectType::HoldExpect(..)
+        | ObjectType::Forget(..)
+        | ObjectType::Retry(..)
+        | ObjectType::Break(..)
+        | ObjectType::Continue(..) => None,
+    }
+}
+
+fn check_block(block: &Block, types: &mut Types, flow_name: &str, errors: &mut Vec<ErrorInfo>) {
+    for (expr, _) in block.commands.iter() {
+        check_expr(expr, types, flow_name, errors);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// check the steps and functions of a flow for the type errors that can be found without
+// running it, like an unknown method of a string. Nothing is executed: every branch of
+// the ifs and matches is checked and the values of memories, event and function calls
+// are unknown, so only the errors that would happen whenever the code is reached are reported
+pub fn type_check_flow(flow: &Flow, flow_name: &str) -> Vec<ErrorInfo> {
+    let mut errors = vec![];
+
+    for (instruction_type, expr) in flow.flow_instructions.iter() {
+        match instruction_type {
+            InstructionScope::StepScope(_) | InstructionScope::FunctionScope { .. } => {
+                check_expr(expr, &mut Types::new(), flow_name, &mut errors);
+            }
+            _ => {}
+        }
+    }
+
+    errors.sort_by_key(|error| {
+        let interval = error.position.interval;
+        (interval.start_line, interval.start_column)
+    });
+
+    errors
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// TEST FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_flow;
+
+    fn type_check(content: &str) -> Vec<ErrorInfo> {
+        type_check_flow(&parse_flow(content, "flow").unwrap(), "flow")
+    }
+
+    #[test]
+    fn ok_no_type_error() {
+        let errors = type_check(
+            "start:\n    do name = \"csml\"\n    say name.to_uppercase()\n\
+             \x20   do count = 4\n    say count.pow(2)\n    say event.length()\n    goto end\n",
+        );
+
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn err_method_in_else_branch() {
+        let errors = type_check(
+            "start:\n    do count = 4\n    if (event == \"yes\") {\n        say count\n\
+             \x20   } else {\n        say count.to_uppercase()\n    }\n    goto end\n",
+        );
+
+        // the else branch is checked whatever the event
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .message
+            .contains("[to_uppercase] is not a method of Int"));
+        assert_eq!(errors[0].position.interval.start_line, 6);
+        assert_eq!(errors[0].position.flow, "flow");
+    }
+
+    #[test]
+    fn ok_branches_with_different_types() {
+        let errors = type_check(
+            "start:\n    do value = 4\n    if (event) {\n        do value = \"four\"\n    }\n\
+             \x20   say value.length()\n    goto end\n",
+        );
+
+        // value is either an int or a string after the if
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn err_functions_and_loops() {
+        let errors = type_check(
+            "start:\n    foreach (item, index) in [1, 2] {\n        say index.trim()\n    }\n\
+             \x20   goto end\n\n\
+             fn total(list):\n    do sum = [1, 2]\n    return sum.to_lowercase()\n",
+        );
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].message.contains("[trim] is not a method of Int"));
+        assert!(errors[1]
+            .message
+            .contains("[to_lowercase] is not a method of Array"));
+    }
+
+    #[test]
+    fn err_object_loop_key() {
+        let errors = type_check(
+            "start:\n    foreach (value, key) in {\"a\": 1} {\n        say key.push(1)\n    }\n\
+             \x20   goto end\n",
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .message
+            .contains("[push] is not a method of String"));
+    }
+}
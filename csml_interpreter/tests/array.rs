@@ -560,3 +560,152 @@ fn array_reduce_index() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn array_slice_negative() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":[4, 5], "content_type":"array"},
+        {"content":[2, 3, 4], "content_type":"array"},
+        {"content":[1, 2, 3, 4, 5], "content_type":"array"},
+        {"content":[], "content_type":"array"}
+    ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_slice_negative",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // out of range indexes are clamped to the array
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn array_contains_deep() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"true"}, "content_type":"text"},
+        {"content":{"text":"true"}, "content_type":"text"},
+        {"content":{"text":"false"}, "content_type":"text"},
+        {"content":{"text":"2"}, "content_type":"text"}
+    ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_contains_deep",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn array_contains_not_an_array() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_contains_not_an_array",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    assert_eq!(msg.messages[0].content_type, "error");
+    assert!(msg.messages[0].content["error"]
+        .as_str()
+        .unwrap()
+        .contains("[contains] is not a method of Int at line 198, column 15"));
+}
+
+#[test]
+fn array_reduce_without_initial_value() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"6"}, "content_type":"text"}, {"content":{"text":"a"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_reduce_without_initial_value",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn array_reduce_empty() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_reduce_empty",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    assert_eq!(msg.messages[0].content_type, "error");
+    assert!(msg.messages[0].content["error"]
+        .as_str()
+        .unwrap()
+        .contains("[reduce] Cannot reduce an empty array without an initial value"));
+}
+
+#[test]
+fn array_callback_error_index() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"}, "content_type":"text"}, {"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"2"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "array_callback_error_index",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/array.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,114 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::error_format::ErrorCode;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::support::tools::{format_message, message_to_json_value, run_step, step_context};
+
+use serde_json::{json, Value};
+
+const FLOW: &str = "CSML/basic_test/assert.csml";
+
+// the assertions are enabled from the env, the tests changing it run one after the other
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+fn set_assertions(enabled: bool) -> MutexGuard<'static, ()> {
+    let lock = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    std::env::set_var("CSML_ASSERTIONS", enabled.to_string());
+
+    lock
+}
+
+fn payload_event() -> Event {
+    Event::new("payload", "", json!({}))
+}
+
+fn texts(value: Value) -> Vec<Value> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].clone())
+        .collect()
+}
+
+#[test]
+fn assert_passing() {
+    let _lock = set_assertions(true);
+
+    assert_eq!(
+        texts(run_step(FLOW, "start")),
+        vec![json!({"text": "passed"})]
+    );
+}
+
+#[test]
+fn assert_failing() {
+    let _lock = set_assertions(true);
+    let msg = format_message(payload_event(), step_context("failing", None), FLOW);
+    let error = msg.error.clone().unwrap();
+
+    assert_eq!(error.code, ErrorCode::AssertionFailed);
+    assert_eq!(error.message, "assertion failed: count is 3");
+    assert_eq!(error.position.interval.start_line, 10);
+    assert_eq!(error.position.interval.start_column, 5);
+    assert_eq!(
+        texts(message_to_json_value(msg)),
+        vec![
+            json!({"text": "before"}),
+            json!({"error": "assertion failed: count is 3 at line 10, column 5 at flow [flow]"}),
+        ]
+    );
+}
+
+#[test]
+fn assert_without_message() {
+    let _lock = set_assertions(true);
+    let msg = format_message(payload_event(), step_context("without_message", None), FLOW);
+
+    assert_eq!(msg.error.unwrap().message, "assertion failed");
+}
+
+#[test]
+fn assert_caught() {
+    let _lock = set_assertions(true);
+
+    assert_eq!(
+        texts(run_step(FLOW, "caught")),
+        vec![
+            json!({"text": "assertion failed: 1 is not greater than 2"}),
+            json!({"text": "20"}),
+        ]
+    );
+}
+
+#[test]
+fn assert_disabled() {
+    let _lock = set_assertions(false);
+
+    assert_eq!(
+        texts(run_step(FLOW, "failing")),
+        vec![json!({"text": "before"}), json!({"text": "not reached"})]
+    );
+    assert!(
+        format_message(payload_event(), step_context("without_message", None), FLOW)
+            .error
+            .is_none()
+    );
+}
+
+#[test]
+fn assert_in_function() {
+    let _lock = set_assertions(true);
+    let msg = format_message(payload_event(), step_context("in_function", None), FLOW);
+
+    assert_eq!(
+        msg.error.clone().unwrap().message,
+        "assertion failed: count is 3"
+    );
+    assert_eq!(
+        texts(message_to_json_value(msg)),
+        vec![json!({"error": "assertion failed: count is 3 at line 34, column 5 at flow [flow]"})]
+    );
+}
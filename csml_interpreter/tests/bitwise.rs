@@ -0,0 +1,171 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use std::collections::HashMap;
+
+use crate::support::tools::format_message;
+use crate::support::tools::message_to_json_value;
+
+use serde_json::Value;
+
+#[test]
+fn ok_bitwise_operators() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"8"},"content_type":"text"}, {"content":{"text":"14"},"content_type":"text"}, {"content":{"text":"6"},"content_type":"text"}, {"content":{"text":"16"},"content_type":"text"}, {"content":{"text":"-4"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn bitwise_precedence() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"8"},"content_type":"text"}, {"content":{"text":"3"},"content_type":"text"}, {"content":{"text":"true"},"content_type":"text"}, {"content":{"text":"true"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "precedence",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn bitwise_flags() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"5"},"content_type":"text"}, {"content":{"text":"true"},"content_type":"text"}, {"content":{"text":"false"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "flags",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn bitwise_float_operand() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Ops: bitwise operators only take int operands, got float and int at line 25, column 13 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "float_operand",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn bitwise_string_operand() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Ops: bitwise operators only take int operands, got string and int at line 29, column 13 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "string_operand",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn shift_count_overflow() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Ops: shift count must be between 0 and 63, got 64 at line 33, column 11 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "shift_count_overflow",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn shift_count_negative() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Ops: shift count must be between 0 and 63, got -1 at line 38, column 11 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "shift_count_negative",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/bitwise.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
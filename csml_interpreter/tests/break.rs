@@ -125,3 +125,26 @@ fn break_test_4() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn break_outside_loop() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"Hello"}, "content_type":"text"}, {"content":{"error":"break can only be used inside loops at line 57, column 5 at flow [flow]"}, "content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "break_outside_loop",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/break.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
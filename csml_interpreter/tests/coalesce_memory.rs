@@ -0,0 +1,111 @@
+mod support;
+
+use csml_interpreter::data::context::{get_hashmap_from_json, Context};
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MessageData;
+
+use crate::support::tools::{format_message, message_to_json_value, step_context};
+
+use serde_json::{json, Value};
+
+// the user scope is in the current memories of the context, the conversation scope apart
+fn scoped_context(step: &str, user: Value, conversation: Value) -> Context {
+    Context {
+        current: get_hashmap_from_json(&user, "flow"),
+        conversation: get_hashmap_from_json(&conversation, "flow"),
+        ..step_context(step, None)
+    }
+}
+
+fn texts(msg: MessageData) -> Vec<String> {
+    message_to_json_value(msg)["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"]["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn coalesce_memory_in_both_scopes() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context(
+            "start",
+            json!({"cart": "user cart"}),
+            json!({"cart": "conversation cart"}),
+        ),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    // the default read stays on the current memories
+    assert_eq!(texts, vec!["conversation cart", "user cart"]);
+}
+
+#[test]
+fn coalesce_memory_only_user_scope() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context("start", json!({"cart": "user cart"}), json!({})),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    assert_eq!(texts, vec!["user cart", "user cart"]);
+}
+
+#[test]
+fn coalesce_memory_only_conversation_scope() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context(
+            "coalesce_null",
+            json!({}),
+            json!({"cart": "conversation cart"}),
+        ),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    assert_eq!(texts, vec!["false"]);
+}
+
+#[test]
+fn coalesce_memory_in_no_scope() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context("coalesce_null", json!({}), json!({})),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    assert_eq!(texts, vec!["true"]);
+}
+
+#[test]
+fn coalesce_memory_after_remember_conversation() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context(
+            "remember_in_conversation",
+            json!({"cart": "user cart"}),
+            json!({}),
+        ),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    assert_eq!(texts, vec!["new conversation cart"]);
+}
+
+#[test]
+fn coalesce_memory_after_remember_of_conversation_key() {
+    let texts = texts(format_message(
+        Event::new("payload", "", json!({})),
+        scoped_context(
+            "remember_conversation_key",
+            json!({"cart": "user cart"}),
+            json!({"cart": "conversation cart"}),
+        ),
+        "CSML/basic_test/built-in/coalesce_memory.csml",
+    ));
+
+    // remember does not change the memory of the conversation
+    assert_eq!(texts, vec!["conversation cart"]);
+}
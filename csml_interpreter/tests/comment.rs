@@ -0,0 +1,62 @@
+mod support;
+
+use csml_interpreter::data::ast::Flow;
+use csml_interpreter::error_format::ErrorInfo;
+use csml_interpreter::parser::parse_flow;
+
+use support::tools::read_file;
+
+fn format_message(filepath: String) -> Result<Flow, ErrorInfo> {
+    let text = read_file(filepath).unwrap();
+
+    parse_flow(&text, "Test")
+}
+
+#[test]
+fn comment_nested() {
+    let result = match format_message("CSML/basic_test/syntax/comment/comment_0.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn comment_unterminated() {
+    let err =
+        format_message("CSML/basic_test/syntax/comment/comment_1.csml".to_owned()).unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 4);
+    assert_eq!(err.position.interval.start_column, 5);
+    assert!(err.message.contains("expecting '*/' to end the comment"));
+}
+
+#[test]
+fn comment_unterminated_in_scope() {
+    let err =
+        format_message("CSML/basic_test/syntax/comment/comment_3.csml".to_owned()).unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 5);
+    assert_eq!(err.position.interval.start_column, 13);
+    assert!(err.message.contains("expecting '*/' to end the comment"));
+}
+
+#[test]
+fn comment_keep_error_position() {
+    let err =
+        format_message("CSML/basic_test/syntax/comment/comment_2.csml".to_owned()).unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 10);
+    assert_eq!(err.position.interval.start_column, 11);
+}
+
+#[test]
+fn comment_multibyte_chars() {
+    let result = match format_message("CSML/basic_test/syntax/comment/comment_4.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
@@ -0,0 +1,68 @@
+mod support;
+
+use csml_interpreter::data::{CompiledFlow, Event, RunOptions};
+use csml_interpreter::parser::parse_flow;
+use csml_interpreter::{compile_flow, run_compiled_flow, run_flow};
+
+use crate::support::tools::{read_file, step_context};
+
+fn get_event() -> Event {
+    Event::new("payload", "", serde_json::json!({}))
+}
+
+#[test]
+fn compiled_flow_same_output_as_source() {
+    let source = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let compiled = compile_flow(&parse_flow(&source, "flow").unwrap());
+
+    // cached by the host, then loaded on a warm path
+    let bytes = compiled.to_bytes();
+    let loaded = CompiledFlow::from_bytes(&bytes, "flow").unwrap();
+
+    for step in ["start", "hold_while_ok", "hold_range_ok"] {
+        let from_source = run_flow(
+            &source,
+            get_event(),
+            step_context(step, None),
+            RunOptions::default(),
+        )
+        .unwrap();
+        let from_compiled = run_compiled_flow(
+            &loaded,
+            get_event(),
+            step_context(step, None),
+            RunOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(from_compiled, from_source, "step {}", step);
+    }
+}
+
+#[test]
+fn compiled_flow_version_mismatch() {
+    let source = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let mut compiled = compile_flow(&parse_flow(&source, "flow").unwrap());
+    compiled.version = 0;
+
+    let err = CompiledFlow::from_bytes(&compiled.to_bytes(), "flow").unwrap_err();
+    assert!(err.message.starts_with(
+        "the compiled flow was compiled with another version of the ast: compiled with 0"
+    ));
+
+    let err = run_compiled_flow(
+        &compiled,
+        get_event(),
+        step_context("start", None),
+        RunOptions::default(),
+    )
+    .unwrap_err();
+    assert!(err.message.contains("compiled with 0,"));
+}
+
+#[test]
+fn compiled_flow_invalid_bytes() {
+    let err = CompiledFlow::from_bytes(&[1, 2, 3], "flow").unwrap_err();
+
+    assert!(err.message.starts_with("invalid compiled flow"));
+}
@@ -0,0 +1,79 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::parser::parse_flow;
+use csml_interpreter::validate_bot;
+use std::collections::HashMap;
+
+use crate::support::tools::{format_message, init_bot, message_to_json_value, read_file};
+
+use serde_json::{json, Value};
+
+#[test]
+fn constant_block_declaration() {
+    let content = read_file("CSML/basic_test/constant.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    let constants: HashMap<&str, Value> = flow
+        .constants
+        .iter()
+        .map(|(name, lit)| (name.as_str(), lit.primitive.to_json()))
+        .collect();
+
+    assert_eq!(constants.len(), 4);
+    assert_eq!(constants["MAX"], json!(5));
+    assert_eq!(constants["GREETING"], json!("hi"));
+    assert_eq!(constants["LIMITS"], json!({"min": 1, "max": 10}));
+    assert_eq!(constants["NAME"], json!("csml"));
+}
+
+#[test]
+fn constant_block_reference() {
+    let data = r#"{
+        "memories":[
+        ],
+        "messages":[
+            {"content":{"text": "hi csml"}, "content_type":"text"},
+            {"content":{"text": "6"}, "content_type":"text"}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/constant.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn constant_block_reassign() {
+    let content = "const { MAX = 5, GREETING = \"hi\" }\n\nstart:\n    do MAX = 6\n    goto end";
+    let errors = validate_bot(&init_bot(content)).errors.unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .message
+        .contains("constant 'MAX' is immutable and can not be changed"));
+    assert_eq!(errors[0].position.interval.start_line, 4);
+    assert_eq!(errors[0].position.interval.start_column, 8);
+}
+
+#[test]
+fn constant_block_not_closed() {
+    let content = "const {\n    MAX = 5\n\nstart:\n    goto end";
+    let err = parse_flow(content, "flow").unwrap_err();
+
+    assert!(err.message.contains("expecting '}'"));
+}
@@ -0,0 +1,135 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::Hold;
+use chrono::{DateTime, Utc};
+
+use crate::support::tools::{
+    init_bot, interpret_with_hold, message_to_json_value, read_file, run_step_with_hold,
+    step_context,
+};
+
+use serde_json::{json, Value};
+
+fn payload_event() -> Event {
+    Event::new("payload", "", json!({ "payload": "" }))
+}
+
+fn get_wake_at(hold: &Hold) -> chrono::DateTime<chrono::FixedOffset> {
+    let wake_at = hold.wake_at.as_ref().unwrap();
+
+    chrono::DateTime::parse_from_rfc3339(wake_at).unwrap()
+}
+
+#[test]
+fn delay_emits_wake_at() {
+    let before = chrono::Utc::now();
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "start", None, payload_event());
+    let after = chrono::Utc::now();
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"see you in 30 minutes"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+
+    let hold = hold.unwrap();
+    let wake_at = get_wake_at(&hold);
+    let delay = chrono::Duration::minutes(30);
+    // the wake date is rounded to the millisecond
+    assert!(wake_at >= before + delay - chrono::Duration::milliseconds(1));
+    assert!(wake_at <= after + delay);
+    assert!(hold.wake_at.unwrap().ends_with('Z'));
+}
+
+#[test]
+fn delay_resumes_after_wake_at() {
+    let (_, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "start", None, payload_event());
+    let mut hold = hold.unwrap();
+    assert_eq!(hold.index.command_index, 1);
+
+    hold.wake_at = Some("2000-01-01T00:00:00.000Z".to_owned());
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "start", Some(hold), payload_event());
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"welcome back"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn delay_holds_before_wake_at() {
+    let (_, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "short", None, payload_event());
+    let hold = hold.unwrap();
+    let wake_at = hold.wake_at.clone();
+    assert_eq!(hold.index.command_index, 0);
+
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "short", Some(hold), payload_event());
+
+    assert!(msg.messages.is_empty());
+    let hold = hold.unwrap();
+    assert_eq!(hold.index.command_index, 0);
+    assert_eq!(hold.wake_at, wake_at);
+}
+
+fn date(date: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(date)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+#[test]
+fn delay_wake_at_from_context_date() {
+    let content = read_file("CSML/basic_test/delay.csml".to_owned()).unwrap();
+    let context_at = |hold: Option<Hold>, now: &str| Context {
+        now: Some(date(now)),
+        ..step_context("start", hold)
+    };
+
+    let (_, hold) = interpret_with_hold(
+        init_bot(&content),
+        context_at(None, "2024-01-02T03:04:05.678Z"),
+        payload_event(),
+    );
+
+    let hold = hold.unwrap();
+    assert_eq!(hold.wake_at.as_deref(), Some("2024-01-02T03:34:05.678Z"));
+
+    // resumed one minute before the wake date, the conversation stays on hold
+    let (msg, still_held) = interpret_with_hold(
+        init_bot(&content),
+        context_at(Some(hold.clone()), "2024-01-02T03:33:05.678Z"),
+        payload_event(),
+    );
+    assert!(msg.messages.is_empty());
+    assert_eq!(still_held.unwrap().wake_at, hold.wake_at);
+
+    let (msg, hold) = interpret_with_hold(
+        init_bot(&content),
+        context_at(Some(hold), "2024-01-02T03:34:05.678Z"),
+        payload_event(),
+    );
+    assert_eq!(
+        message_to_json_value(msg)["messages"][0]["content"]["text"],
+        "welcome back"
+    );
+    assert!(hold.is_none());
+}
+
+#[test]
+fn delay_without_duration() {
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/delay.csml", "invalid", None, payload_event());
+    let value = message_to_json_value(msg);
+
+    assert!(hold.is_none());
+    assert!(value["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("delay expects a positive duration"));
+    assert_eq!(value["messages"][1]["content"]["text"], "not delayed");
+}
@@ -0,0 +1,98 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn destructure_object() {
+    let data = r#"{"messages":[ {"content":{ "text": "csml contact@csml.dev" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_array() {
+    let data = r#"{"messages":[ {"content":{ "text": "3" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "array");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_nested() {
+    let data = r#"{"messages":[ {"content":{ "text": "csml bot" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "nested");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_renamed() {
+    let data = r#"{"messages":[ {"content":{ "text": "csml" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "renamed");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_missing_values() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": null },"content_type":"text"},
+                {"content":{ "text": null },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "missing");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_remembered() {
+    let data = r#"
+        {
+            "messages":[ {"content":{ "text": "new" },"content_type":"text"} ],
+            "memories":[ {"key": "name", "value": "old"}, {"key": "name", "value": "new"} ]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "remembered");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn destructure_not_object() {
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "not_object");
+
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("only accepts objects, got string at line 37, column 8"));
+}
+
+#[test]
+fn destructure_not_array() {
+    let v1: Value = run_step("CSML/basic_test/destructure.csml", "not_array");
+
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("only accepts arrays, got object at line 42, column 8"));
+}
@@ -0,0 +1,55 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn duration_addition() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"2020-08-16T10:00:00.000Z"}, "content_type":"text"},
+        {"content":{"text":"2020-08-13T08:30:00.000Z"}, "content_type":"text"},
+        {"content":{"text":"2020-08-13T12:00:00.000Z"}, "content_type":"text"}
+    ]}"#;
+    let v1: Value = run_step("CSML/basic_test/duration.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // the times are formatted like get_date_time
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn duration_comparison() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"true"}, "content_type":"text"},
+        {"content":{"text":"true"}, "content_type":"text"},
+        {"content":{"text":"true"}, "content_type":"text"}
+    ]}"#;
+    let v1: Value = run_step("CSML/basic_test/duration.csml", "compare");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn duration_between_times() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"86400000"}, "content_type":"text"},
+        {"content":{"text":"43200000"}, "content_type":"text"}
+    ]}"#;
+    let v1: Value = run_step("CSML/basic_test/duration.csml", "difference");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn duration_type_error() {
+    let v1: Value = run_step("CSML/basic_test/duration.csml", "type_error");
+
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("illegal operation: time + string"));
+}
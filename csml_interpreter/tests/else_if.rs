@@ -0,0 +1,65 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+
+use crate::support::tools::{message_to_json_value, run_step, run_step_with_hold};
+
+use serde_json::Value;
+
+#[test]
+fn else_if_short_circuit() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"else if"}, "content_type":"text"},
+        {"content":["if", "else if"], "content_type":"array"}
+    ]}"#;
+    let v1: Value = run_step("CSML/basic_test/else_if.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // the conditions after the first true branch are not evaluated
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn else_if_else_branch() {
+    let data =
+        r#"{"memories":[], "messages":[{"content":{"text":"else"}, "content_type":"text"}]}"#;
+    let v1: Value = run_step("CSML/basic_test/else_if.csml", "else_branch");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn else_if_hold_in_branch() {
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/else_if.csml",
+        "hold_in_branch",
+        None,
+        Event::new("payload", "", serde_json::json!({})),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"before hold"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+
+    // the conversation resumes in the third branch after the hold
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/else_if.csml",
+        "hold_in_branch",
+        hold,
+        Event::new("payload", "", serde_json::json!({})),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[
+            {"content":{"text":"after hold"}, "content_type":"text"},
+            {"content":{"text":"end"}, "content_type":"text"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2)
+}
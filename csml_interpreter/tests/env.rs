@@ -0,0 +1,95 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::CsmlBot;
+use csml_interpreter::{interpret, validate_bot};
+
+use crate::support::tools::{init_bot, message_to_json_value, read_file, step_context};
+
+use serde_json::Value;
+
+fn get_bot(content: &str) -> CsmlBot {
+    CsmlBot {
+        env: Some(serde_json::json!({"API_KEY": "secret", "region": "eu"})),
+        ..init_bot(content)
+    }
+}
+
+#[test]
+fn env_present_key() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"key secret"}, "content_type":"text"},
+        {"content":{"text":"eu"}, "content_type":"text"}
+    ]}"#;
+    let content = read_file("CSML/basic_test/env.csml".to_owned()).unwrap();
+    let msg = interpret(
+        get_bot(&content),
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn env_missing_key() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":null}, "content_type":"text"},
+        {"content":{"text":"default"}, "content_type":"text"}
+    ]}"#;
+    let content = read_file("CSML/basic_test/env.csml".to_owned()).unwrap();
+    let msg = interpret(
+        get_bot(&content),
+        step_context("missing_key", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // a missing key is null, not an error
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn env_comparison_is_not_an_assignment() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"true"}, "content_type":"text"}
+    ]}"#;
+    let content = read_file("CSML/basic_test/env.csml".to_owned()).unwrap();
+    let msg = interpret(
+        get_bot(&content),
+        step_context("compare", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn env_write_attempt() {
+    let writes = [
+        "do _env = {}",
+        "do _env.API_KEY = \"hacked\"",
+        "do _env.count += 1",
+    ];
+
+    for write in writes.iter() {
+        let content = format!("start:\n    {}\n    goto end\n", write);
+        let errors = validate_bot(&get_bot(&content)).errors.unwrap_or_default();
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .message
+            .contains("_env is read-only, its values are set by the bot configuration"));
+    }
+}
@@ -0,0 +1,143 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::error_format::ErrorCode;
+
+use crate::support::tools::{message_to_json_value, run_step_with_hold};
+
+use serde_json::Value;
+
+fn budget_event(instruction_limit: Option<usize>, time_limit: Option<u64>) -> Event {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.instruction_limit = instruction_limit;
+    event.time_limit = time_limit;
+
+    event
+}
+
+fn get_texts(value: &Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|message| message["content"]["text"].as_str())
+        .map(|text| text.to_owned())
+        .collect()
+}
+
+#[test]
+fn execution_budget_instruction_limit() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "start",
+        None,
+        budget_event(Some(10), None),
+    );
+
+    let error = msg.error.clone().unwrap();
+    assert_eq!(error.code, ErrorCode::ExecutionBudgetExceeded);
+    // the say inside the loop trips the budget
+    assert_eq!(error.position.interval.start_line, 4);
+
+    let v1 = message_to_json_value(msg);
+    let messages = v1["messages"].as_array().unwrap();
+
+    // the messages sent before the abort are kept
+    assert_eq!(
+        get_texts(&v1),
+        vec!["before", "0", "1", "2", "3", "4", "5", "6", "7"]
+    );
+    assert_eq!(messages.last().unwrap()["content_type"], "error");
+}
+
+#[test]
+fn execution_budget_time_limit() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "time_limit",
+        None,
+        budget_event(None, Some(50)),
+    );
+
+    let error = msg.error.clone().unwrap();
+    assert_eq!(error.code, ErrorCode::ExecutionBudgetExceeded);
+    assert_eq!(error.position.interval.start_line, 12);
+
+    let v1 = message_to_json_value(msg);
+    assert_eq!(get_texts(&v1), vec!["before"]);
+}
+
+#[test]
+fn execution_budget_in_function() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "in_function",
+        None,
+        budget_event(Some(100), None),
+    );
+
+    assert_eq!(
+        msg.error.clone().unwrap().code,
+        ErrorCode::ExecutionBudgetExceeded
+    );
+
+    let v1 = message_to_json_value(msg);
+    assert_eq!(get_texts(&v1), vec!["before"]);
+}
+
+#[test]
+fn execution_budget_not_caught() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "in_try_block",
+        None,
+        budget_event(Some(100), None),
+    );
+
+    assert_eq!(
+        msg.error.clone().unwrap().code,
+        ErrorCode::ExecutionBudgetExceeded
+    );
+
+    let v1 = message_to_json_value(msg);
+    assert!(get_texts(&v1).is_empty());
+}
+
+#[test]
+fn execution_budget_hold_replay() {
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "hold_budget",
+        None,
+        budget_event(Some(8), None),
+    );
+    assert!(msg.error.is_none());
+    assert!(hold.is_some());
+
+    let v1 = message_to_json_value(msg);
+    assert_eq!(get_texts(&v1), vec!["before", "0", "1", "2", "3", "4"]);
+
+    // the commands replayed to reach the hold do not use the budget
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "hold_budget",
+        hold,
+        budget_event(Some(8), None),
+    );
+    assert!(msg.error.is_none());
+
+    let v1 = message_to_json_value(msg);
+    assert_eq!(get_texts(&v1), vec!["after 0", "after 1", "after 2"]);
+}
+
+#[test]
+fn execution_budget_unlimited() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/execution_budget.csml",
+        "hold_budget",
+        None,
+        budget_event(None, None),
+    );
+
+    assert!(msg.error.is_none());
+}
@@ -0,0 +1,74 @@
+use csml_interpreter::data::ast::{AttachedComment, CommentPlacement, Interval};
+use csml_interpreter::parser::{parse_flow, parse_flow_with_comments};
+
+mod support;
+
+use crate::support::tools::read_file;
+
+fn flow_comments() -> Vec<AttachedComment> {
+    let content = read_file("CSML/basic_test/flow_comments.csml".to_owned()).unwrap();
+
+    parse_flow_with_comments(&content, "flow").unwrap().comments
+}
+
+// text, placement and start line of the node of each comment
+fn summary(comments: &[AttachedComment]) -> Vec<(&str, CommentPlacement, u32)> {
+    comments
+        .iter()
+        .map(|attached| {
+            (
+                attached.comment.text.as_str(),
+                attached.placement,
+                attached.node.start_line,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn comments_attached_to_nodes() {
+    let comments = flow_comments();
+
+    assert_eq!(
+        summary(&comments),
+        vec![
+            ("// about the flow", CommentPlacement::Leading, 2),
+            ("// first step", CommentPlacement::Trailing, 2),
+            ("// greeting", CommentPlacement::Trailing, 3),
+            ("/* block\n       comment */", CommentPlacement::Leading, 6),
+            ("// inside the if", CommentPlacement::Leading, 8),
+            ("// doubles x", CommentPlacement::Trailing, 12),
+            ("// before the second step", CommentPlacement::Leading, 16),
+            ("// end of the flow", CommentPlacement::Trailing, 18),
+        ]
+    );
+}
+
+#[test]
+fn comments_positions() {
+    let comments = flow_comments();
+
+    assert_eq!(
+        comments[0].comment.interval,
+        Interval::new_as_u32(1, 1, 0, Some(1), Some(18))
+    );
+    // the end of a block comment is on its last line
+    assert_eq!(
+        comments[3].comment.interval,
+        Interval::new_as_u32(4, 5, 71, Some(5), Some(18))
+    );
+    // the step node starts at its name
+    assert_eq!(comments[1].node.start_line, 2);
+    assert_eq!(comments[1].node.start_column, 1);
+}
+
+#[test]
+fn comments_dropped_by_default() {
+    let content = read_file("CSML/basic_test/flow_comments.csml".to_owned()).unwrap();
+
+    // the comments are not kept once parse_flow_with_comments returns
+    parse_flow_with_comments(&content, "flow").unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    assert!(flow.comments.is_empty());
+}
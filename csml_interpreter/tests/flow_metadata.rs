@@ -0,0 +1,42 @@
+mod support;
+
+use csml_interpreter::parser::parse_flow;
+
+use crate::support::tools::{read_file, run_step};
+
+use serde_json::{json, Value};
+
+#[test]
+fn flow_metadata() {
+    let content = read_file("CSML/basic_test/flow_metadata.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    assert_eq!(
+        flow.metadata,
+        Some(json!({
+            "version": "1.2",
+            "tags": ["sales", "support"],
+            "owner": {"name": "csml", "active": true},
+        }))
+    );
+    assert!(flow.constants.contains_key("GREETING"));
+}
+
+#[test]
+fn flow_without_metadata() {
+    let content = read_file("CSML/basic_test/else_if.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    assert_eq!(flow.metadata, None);
+}
+
+#[test]
+fn flow_metadata_interpret() {
+    let v1: Value = run_step("CSML/basic_test/flow_metadata.csml", "start");
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"hello"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+
+    assert_eq!(v1, v2)
+}
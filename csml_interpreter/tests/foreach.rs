@@ -145,3 +145,63 @@ fn foreach_12() {
 
     assert!(result);
 }
+
+#[test]
+fn foreach_13() {
+    let result = match format_message("CSML/basic_test/syntax/foreach/foreach_13.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn foreach_14() {
+    let result = match format_message("CSML/basic_test/syntax/foreach/foreach_14.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn foreach_15() {
+    let result = match format_message("CSML/basic_test/syntax/foreach/foreach_15.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn foreach_16() {
+    let result = match format_message("CSML/basic_test/syntax/foreach/foreach_16.csml".to_owned()) {
+        Ok(_) => false,
+        Err(_) => true,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn foreach_label() {
+    let result = match format_message("CSML/basic_test/loop_label.csml".to_owned()) {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+
+    assert!(result);
+}
+
+#[test]
+fn foreach_unknown_label() {
+    let err =
+        format_message("CSML/basic_test/syntax/foreach/foreach_17.csml".to_owned()).unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 4);
+    assert_eq!(err.position.interval.start_column, 19);
+    assert!(err.message.contains("undeclared loop label"));
+}
@@ -0,0 +1,39 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn foreach_nested_error_position() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"error":"illegal operation: PrimitiveInt / PrimitiveString at line 5, column 22 at flow [flow]"}, "content_type":"error"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_errors.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn foreach_nested_method_error_position() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"error":"illegal operation: PrimitiveInt + PrimitiveArray at line 14, column 22 at flow [flow]"}, "content_type":"error"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_errors.csml", "method");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,93 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn foreach_object_entries_by_key() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"text":"alice: 1"}, "content_type":"text"},
+            {"content":{"text":"bob: 2"}, "content_type":"text"},
+            {"content":{"text":"carol: 3"}, "content_type":"text"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_object.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn foreach_object_values() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"nested":true}, "content_type":"object"},
+            {"content":[1, 2], "content_type":"array"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_object.csml", "value_only");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn foreach_empty_object() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"text":"done"}, "content_type":"text"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_object.csml", "empty");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn foreach_scalar_error() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"error":"foreach only accepts iterable elements like arrays, strings and objects. Example: foreach(elem) in [1, 2, 3] (got int) at line 23, column 29 at flow [flow]"}, "content_type":"error"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_object.csml", "scalar");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn foreach_null_error() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"error":"foreach only accepts iterable elements like arrays, strings and objects. Example: foreach(elem) in [1, 2, 3] (got null) at line 29, column 24 at flow [flow]"}, "content_type":"error"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/foreach_object.csml", "null_value");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,148 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use std::collections::HashMap;
+
+use crate::support::tools::format_message;
+use crate::support::tools::message_to_json_value;
+
+use serde_json::Value;
+
+#[test]
+fn ok_range_exclusive() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"0"}, "content_type":"text"}, {"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"2"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_range_inclusive() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"0:1"}, "content_type":"text"}, {"content":{"text":"1:2"}, "content_type":"text"}, {"content":{"text":"2:3"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "inclusive",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_range_bounds_expr() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"2"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "bounds_expr",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_range_reversed_is_empty() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "reversed",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_range_large() {
+    let data =
+        r#"{"memories":[], "messages":[{"content":{"text":"100000"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "large",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn err_range_float_bound() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "float_bound",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/foreach_range.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+
+    assert_eq!(v1["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+}
@@ -4,9 +4,11 @@ use csml_interpreter::data::ast::Flow;
 use csml_interpreter::error_format::ErrorInfo;
 use csml_interpreter::parser::parse_flow;
 
-use support::tools::read_file;
+use support::tools::{read_file, run_step};
 
-fn format_message(filepath: String) -> Result<Flow, ErrorInfo> {
+use serde_json::Value;
+
+fn parse_message(filepath: String) -> Result<Flow, ErrorInfo> {
     let text = read_file(filepath).unwrap();
 
     parse_flow(&text, "Test")
@@ -14,10 +16,170 @@ fn format_message(filepath: String) -> Result<Flow, ErrorInfo> {
 
 #[test]
 fn functions_syntax() {
-    let result = match format_message("CSML/basic_test/syntax/functions.csml".to_owned()) {
+    let result = match parse_message("CSML/basic_test/syntax/functions.csml".to_owned()) {
         Ok(_) => true,
         Err(_) => false,
     };
 
     assert!(result);
 }
+
+#[test]
+fn functions_default_args() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "Hello Alice" },"content_type":"text"},
+                {"content":{ "text": "Hi Bob" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_default_args.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_default_args_use_previous_arg() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "abab" },"content_type":"text"},
+                {"content":{ "text": "ababab" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step(
+        "CSML/basic_test/functions_default_args.csml",
+        "default_uses_arg",
+    );
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_default_args_missing_required() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "function arguments are not valid" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step(
+        "CSML/basic_test/functions_default_args.csml",
+        "missing_required_arg",
+    );
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_named_args() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "Hi Bob" },"content_type":"text"},
+                {"content":{ "text": "Hey Alice" },"content_type":"text"},
+                {"content":{ "text": "Hello Carol" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_named_args.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_positional_then_named_args() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "a b c" },"content_type":"text"},
+                {"content":{ "text": "a b c" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_named_args.csml", "three_args");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_unknown_named_arg() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "function has no argument named 'salutation'" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_named_args.csml", "unknown_arg");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_return_guard() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "hello csml" },"content_type":"text"},
+                {"content":{ "text": "missing name" },"content_type":"text"},
+                {"content":{ "text": "empty name" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_return_guard.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_bare_return() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "null" },"content_type":"text"},
+                {"content":{ "text": "null" },"content_type":"text"},
+                {"content":{ "text": "2" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_return_guard.csml", "bare");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn functions_return_guard_in_loop() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "20" },"content_type":"text"},
+                {"content":{ "text": "null" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/functions_return_guard.csml", "loop");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
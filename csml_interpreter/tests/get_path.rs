@@ -0,0 +1,64 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+fn texts(value: &Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"]["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn get_path_deep_hit() {
+    assert_eq!(
+        texts(&run_step(
+            "CSML/basic_test/built-in/get_path.csml",
+            "deep_hit"
+        )),
+        vec!["Ada"]
+    );
+}
+
+#[test]
+fn get_path_missing_intermediate() {
+    assert_eq!(
+        texts(&run_step(
+            "CSML/basic_test/built-in/get_path.csml",
+            "missing_intermediate"
+        )),
+        vec!["null", "null", "null"]
+    );
+}
+
+#[test]
+fn get_path_array_index() {
+    assert_eq!(
+        texts(&run_step(
+            "CSML/basic_test/built-in/get_path.csml",
+            "array_index"
+        )),
+        vec!["2", "b", "3", "null"]
+    );
+}
+
+#[test]
+fn get_path_malformed_path() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"error":"get_path builtin got a malformed path \"data.items[0.id\": missing ']' in 'items[0' at line 26, column 13 at flow [flow]"}, "content_type":"error"}
+        ]
+    }
+    "#;
+
+    let v1: Value = run_step("CSML/basic_test/built-in/get_path.csml", "malformed_path");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,57 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+
+use crate::support::tools::{format_message, message_to_json_value, step_context};
+
+use serde_json::Value;
+
+#[test]
+fn goto_end_in_nested_foreach() {
+    let data = r#"
+    {
+        "memories":[],
+        "messages":[
+            {"content":{"text":"item 1"}, "content_type":"text"},
+            {"content":{"text":"item 2"}, "content_type":"text"}
+        ]
+    }
+    "#;
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        "CSML/basic_test/goto_end.csml",
+    );
+    assert!(msg.ended());
+    assert!(msg.hold.is_none());
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn goto_end_hold_not_ended() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("wait", None),
+        "CSML/basic_test/goto_end.csml",
+    );
+
+    assert!(!msg.ended());
+    assert!(msg.hold.is_some());
+}
+
+#[test]
+fn goto_end_last_step() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("last", None),
+        "CSML/basic_test/goto_end.csml",
+    );
+
+    assert!(msg.ended());
+    assert_eq!(msg.messages[0].content["text"], "last");
+}
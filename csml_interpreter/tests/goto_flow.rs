@@ -0,0 +1,116 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{CsmlBot, CsmlFlow, MessageData};
+
+use crate::support::tools::{
+    init_bot_with_flows, interpret_with_hold, message_to_json_value, read_file, step_context,
+};
+
+use serde_json::Value;
+
+fn init_bot() -> CsmlBot {
+    let main = read_file("CSML/basic_test/goto_flow/main.csml".to_owned()).unwrap();
+    let sales = read_file("CSML/basic_test/goto_flow/sales.csml".to_owned()).unwrap();
+
+    init_bot_with_flows(vec![
+        CsmlFlow::new("flow", "flow", &main, Vec::default()),
+        CsmlFlow::new("sales", "sales", &sales, Vec::default()),
+    ])
+}
+
+fn get_error(msg_data: MessageData) -> String {
+    let value = message_to_json_value(msg_data);
+
+    value["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn goto_flow_keeps_memories() {
+    let data = r#"{"memories":[
+        {"key":"cart", "value":["shoes"]},
+        {"key":"total", "value":1}
+    ], "messages":[
+        {"content":["shoes"], "content_type":"array"}
+    ]}"#;
+    let (msg, _) = interpret_with_hold(
+        init_bot(),
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // the memories of main are read and completed in sales
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn goto_flow_hold() {
+    let (msg, hold) = interpret_with_hold(
+        init_bot(),
+        step_context("to_hold", None),
+        Event::new("payload", "", serde_json::json!({})),
+    );
+    let hold = hold.unwrap();
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[{"key":"cart", "value":["hat"]}], "messages":[
+            {"content":{"text":"which size for the hat?"}, "content_type":"text"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+
+    // the hold is in the target flow
+    assert_eq!(hold.flow_name, "sales");
+    assert_eq!(hold.step_name, "ask");
+
+    let context = Context {
+        flow: "sales".to_owned(),
+        ..step_context("ask", Some(hold))
+    };
+    let (msg, _) = interpret_with_hold(
+        init_bot(),
+        context,
+        Event::new("payload", "M", serde_json::json!({})),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[
+            {"content":{"text":"size M"}, "content_type":"text"}
+        ]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn goto_flow_unknown_step() {
+    let (msg, _) = interpret_with_hold(
+        init_bot(),
+        step_context("missing_step", None),
+        Event::new("payload", "", serde_json::json!({})),
+    );
+
+    assert!(get_error(msg).contains("[unknown] step does not exist in flow: [sales]"));
+}
+
+#[test]
+fn goto_flow_unknown_flow() {
+    let (msg, _) = interpret_with_hold(
+        init_bot(),
+        step_context("missing_flow", None),
+        Event::new("payload", "", serde_json::json!({})),
+    );
+
+    assert!(get_error(msg)
+        .contains("flow: [unknown] does not exist in bot: [id], can not go to step: [checkout]"));
+}
@@ -0,0 +1,104 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::error_format::ErrorCode;
+
+use crate::support::tools::{message_to_json_value, run_step_with_hold};
+
+use serde_json::Value;
+
+fn limit_event(step_limit: Option<usize>, goto_loop_limit: Option<usize>) -> Event {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.step_limit = step_limit;
+    event.goto_loop_limit = goto_loop_limit;
+
+    event
+}
+
+fn get_texts(value: &Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|message| message["content"]["text"].as_str())
+        .map(|text| text.to_owned())
+        .collect()
+}
+
+#[test]
+fn goto_loop_two_steps() {
+    // above the default goto loop limit of 100 entries per step
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/goto_loop.csml",
+        "start",
+        None,
+        limit_event(Some(1000), None),
+    );
+
+    let error = msg.error.clone().unwrap();
+    assert_eq!(error.code, ErrorCode::GotoLoop);
+    assert!(error.message.contains(
+        "step [step_1] was entered more than 100 times without waiting for an event, cycle: step_1 -> step_2 -> step_1"
+    ));
+
+    let cycle = error.additional_info.unwrap()["cycle"].primitive.to_json();
+    assert_eq!(
+        cycle,
+        serde_json::json!([
+            {"flow": "flow", "step": "step_1"},
+            {"flow": "flow", "step": "step_2"},
+            {"flow": "flow", "step": "step_1"},
+        ])
+    );
+
+    let v1 = message_to_json_value(msg);
+    let messages = v1["messages"].as_array().unwrap();
+    assert_eq!(get_texts(&v1), vec!["start"]);
+    assert_eq!(messages.last().unwrap()["content_type"], "error");
+}
+
+#[test]
+fn goto_loop_custom_limit() {
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/goto_loop.csml",
+        "self_loop",
+        None,
+        limit_event(None, Some(3)),
+    );
+
+    let error = msg.error.unwrap();
+    assert_eq!(error.code, ErrorCode::GotoLoop);
+    assert!(error
+        .message
+        .contains("step [self_loop] was entered more than 3 times"));
+    assert!(error.message.contains("cycle: self_loop -> self_loop"));
+}
+
+#[test]
+fn goto_loop_with_hold() {
+    // each run stops at the hold, the loop never enters a step more than twice in a run
+    let (msg, mut hold) = run_step_with_hold(
+        "CSML/basic_test/goto_loop.csml",
+        "ask_loop",
+        None,
+        limit_event(None, Some(2)),
+    );
+    assert!(msg.error.is_none());
+
+    for _ in 0..5 {
+        assert!(hold.is_some());
+
+        let (msg, next_hold) = run_step_with_hold(
+            "CSML/basic_test/goto_loop.csml",
+            "ask_loop",
+            hold,
+            limit_event(None, Some(2)),
+        );
+        assert!(msg.error.is_none());
+
+        let v1 = message_to_json_value(msg);
+        assert_eq!(get_texts(&v1), vec!["answer", "question"]);
+
+        hold = next_hold;
+    }
+}
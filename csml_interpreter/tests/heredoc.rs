@@ -0,0 +1,29 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn heredoc_multi_line() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"{\"name\": \"{{ name }}\",\n  \"path\": \"C:\\new\"}"}, "content_type":"text"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/heredoc.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn heredoc_inline() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"say \"hello\" "}, "content_type":"text"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/heredoc.csml", "inline");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -2,11 +2,14 @@ mod support;
 
 use csml_interpreter::data::event::Event;
 use csml_interpreter::data::hold::{Hold, IndexInfo};
-use csml_interpreter::data::Context;
+use csml_interpreter::data::primitive::{PrimitiveArray, PrimitiveInt, PrimitiveString};
+use csml_interpreter::data::{Context, Interval};
+use csml_interpreter::parser::parse_flow;
 use std::collections::HashMap;
 
 use crate::support::tools::format_message;
 use crate::support::tools::message_to_json_value;
+use crate::support::tools::read_file;
 
 use serde_json::Value;
 
@@ -348,3 +351,421 @@ fn hold_test_step_4_ok() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn hold_test_while_resume_last_iteration() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"3"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}] }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 2,
+                    loop_index: vec![2],
+                },
+                serde_json::json!({"i": 3}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_while_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_while_resume_mid_loop() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"}, "content_type":"text"}] }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 2,
+                    loop_index: vec![1],
+                },
+                serde_json::json!({"i": 2}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_while_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_while_first_command() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}] }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 1,
+                    loop_index: vec![1],
+                },
+                serde_json::json!({"i": 1}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_while_first",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_break_ok() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![0],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_break_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_continue_ok() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"3"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![0],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_continue_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_break_after_resume() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![1],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_break_after_resume",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_resume_before_break() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"1"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![0],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_break_after_resume",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_range_ok() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"1"}, "content_type":"text"}, {"content":{"text":"2"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![1],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_range_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_range_inclusive_ok() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"}, "content_type":"text"}, {"content":{"text":"3"}, "content_type":"text"}, {"content":{"text":"OK"}, "content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::new(
+                IndexInfo {
+                    command_index: 0,
+                    loop_index: vec![1],
+                },
+                serde_json::json!({}),
+                "".to_owned(),
+                "".to_owned(),
+                None,
+                false,
+            )),
+            "hold_range_inclusive_ok",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_resume_at() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"4"}, "content_type":"text"}]}"#;
+    let content = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            Some(Hold::resume_at(&flow, "flow", "start", 3, vec![]).unwrap()),
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/hold.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn hold_test_resume_at_out_of_range() {
+    let content = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    let err = Hold::resume_at(&flow, "flow", "start", 13, vec![]).unwrap_err();
+
+    assert_eq!(
+        err.message,
+        "hold command_index is out of the step instructions range: 13 is not lower than the 13 instructions of step [start]"
+    );
+    assert!(Hold::resume_at(&flow, "flow", "start", 12, vec![]).is_ok());
+}
+
+#[test]
+fn hold_test_resume_at_unknown_step() {
+    let content = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let flow = parse_flow(&content, "flow").unwrap();
+
+    let err = Hold::resume_at(&flow, "flow", "unknown", 0, vec![]).unwrap_err();
+
+    assert_eq!(err.message, "[unknown] step does not exist");
+}
+
+#[test]
+fn hold_test_context_round_trip() {
+    let mut current = HashMap::new();
+    current.insert(
+        "name".to_owned(),
+        PrimitiveString::get_literal("Jo", Interval::default()),
+    );
+    current.insert(
+        "cart".to_owned(),
+        PrimitiveArray::get_literal(
+            &[
+                PrimitiveInt::get_literal(4, Interval::default()),
+                PrimitiveString::get_literal("shoes", Interval::default()),
+            ],
+            Interval::default(),
+        ),
+    );
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "firstname".to_owned(),
+        PrimitiveString::get_literal("Jo", Interval::default()),
+    );
+
+    let hold = Hold::new(
+        IndexInfo {
+            command_index: 7,
+            loop_index: vec![1, 3],
+        },
+        serde_json::json!({"item": "shoes", "index": 3}),
+        "checkout".to_owned(),
+        "flow".to_owned(),
+        None,
+        true,
+    );
+    let context = Context::new(
+        current,
+        metadata,
+        None,
+        Some(hold),
+        "checkout",
+        "flow",
+        None,
+    );
+
+    let restored = Context::from_bytes(&context.to_bytes()).unwrap();
+
+    assert_eq!(restored, context);
+    let hold = restored.hold.unwrap();
+    assert_eq!(hold.index.command_index, 7);
+    assert_eq!(hold.index.loop_index, vec![1, 3]);
+}
+
+#[test]
+fn hold_test_context_unknown_version() {
+    let context = Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        "start",
+        "flow",
+        None,
+    );
+    let mut value: Value = serde_json::from_slice(&context.to_bytes()).unwrap();
+    value["version"] = serde_json::json!(99);
+
+    let err = Context::from_bytes(value.to_string().as_bytes()).unwrap_err();
+
+    assert_eq!(err, "unsupported serialized context version: 99");
+}
@@ -0,0 +1,220 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MessageData;
+
+use crate::support::tools::{message_to_json_value, run_step_with_hold};
+
+use serde_json::{json, Value};
+
+fn intent_event(text: &str, confidence: Option<f64>) -> Event {
+    let content = match confidence {
+        Some(confidence) => json!({ "text": text, "confidence": confidence }),
+        None => json!({ "text": text }),
+    };
+
+    Event::new("text", text, content)
+}
+
+fn contents(msg: MessageData) -> Vec<Value> {
+    message_to_json_value(msg)["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].clone())
+        .collect()
+}
+
+#[test]
+fn hold_confidence_above_threshold() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "start",
+        None,
+        intent_event("start", None),
+    );
+    assert!(hold.is_some());
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "start",
+        hold,
+        intent_event("bye", Some(0.9)),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "intent bye"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_below_threshold() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "start",
+        None,
+        intent_event("start", None),
+    );
+
+    // the conversation stays on hold until the event is confident enough
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "start",
+        hold,
+        intent_event("bye", Some(0.42)),
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the confidence of the event is below the hold threshold: 0.42 < 0.8 at line 3, column 5 at flow [flow]"
+        })]
+    );
+    assert!(hold.is_some());
+
+    // the threshold is reached
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "start",
+        hold,
+        intent_event("bye", Some(0.8)),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "intent bye"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_missing() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "strict",
+        None,
+        intent_event("start", None),
+    );
+
+    // the events without confidence are not confident by default
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "strict",
+        hold,
+        intent_event("bye", None),
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the confidence of the event is below the hold threshold: 0 < 1 at line 8, column 5 at flow [flow]"
+        })]
+    );
+    assert!(hold.is_some());
+
+    let mut event = intent_event("bye", None);
+    event.hold_confidence_default = Some(1.0);
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "strict",
+        hold,
+        event,
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "confident"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_invalid_default() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "strict",
+        None,
+        intent_event("start", None),
+    );
+
+    let mut event = intent_event("bye", None);
+    event.hold_confidence_default = Some(2.0);
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "strict",
+        hold,
+        event,
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the confidence of the event must be a number between 0 and 1 at line 8, column 5 at flow [flow]"
+        })]
+    );
+}
+
+#[test]
+fn hold_confidence_else_below_threshold() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed",
+        None,
+        intent_event("start", None),
+    );
+    assert!(hold.is_some());
+
+    // the less confident event goes to the else goto instead of staying on hold
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed",
+        hold,
+        intent_event("bye", Some(0.42)),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "did you mean bye?"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_else_above_threshold() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed",
+        None,
+        intent_event("start", None),
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed",
+        hold,
+        intent_event("bye", Some(0.9)),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "intent bye"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_else_block() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed_block",
+        None,
+        intent_event("start", None),
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_confidence.csml",
+        "routed_block",
+        hold,
+        intent_event("bye", None),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "did you mean bye?"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_confidence_else_without_goto() {
+    let flow = "start:\n    hold confidence 0.8 else say \"clarify\"\n    goto end\n";
+    let err = csml_interpreter::parser::parse_flow(flow, "flow").unwrap_err();
+
+    assert!(err
+        .message
+        .contains("hold confidence expects a goto after else"));
+}
@@ -0,0 +1,165 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MessageData;
+
+use crate::support::tools::{init_bot, message_to_json_value, run_step_with_hold};
+
+use serde_json::{json, Value};
+
+fn text_event(text: &str) -> Event {
+    Event::new("text", text, json!({ "text": text }))
+}
+
+fn contents(msg: MessageData) -> Vec<Value> {
+    message_to_json_value(msg)["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].clone())
+        .collect()
+}
+
+#[test]
+fn hold_expect_number() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "start",
+        None,
+        text_event("start"),
+    );
+    assert!(hold.is_some());
+
+    // the event is a number in the rest of the run, even after a goto
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "start",
+        hold,
+        text_event(" 41 "),
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![
+            json!({"text": "42"}),
+            json!({"text": "number"}),
+            json!({"text": "next 41"}),
+        ]
+    );
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_expect_number_mismatch() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "start",
+        None,
+        text_event("start"),
+    );
+
+    // the conversation stays on hold until the event can be coerced
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "start",
+        hold,
+        text_event("forty"),
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the event does not match the hold expected type: expected a number, got \"forty\" at line 3, column 5 at flow [flow]"
+        })]
+    );
+    assert!(hold.is_some());
+
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "start",
+        hold,
+        text_event("4.5"),
+    );
+
+    assert_eq!(contents(msg)[0], json!({"text": "5.5"}));
+}
+
+#[test]
+fn hold_expect_email() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "email",
+        None,
+        text_event("start"),
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "email",
+        hold,
+        text_event("jo@example"),
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the event does not match the hold expected type: expected an email, got \"jo@example\" at line 14, column 5 at flow [flow]"
+        })]
+    );
+    assert!(hold.is_some());
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "email",
+        hold,
+        text_event("jo@example.com"),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "saved jo@example.com"})]);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_expect_secure_boolean() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "confirm",
+        None,
+        text_event("start"),
+    );
+    assert!(hold.as_ref().unwrap().secure);
+
+    let mut event = text_event("my password");
+    event.secure = true;
+    let (msg, hold) =
+        run_step_with_hold("CSML/basic_test/hold_expect.csml", "confirm", hold, event);
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({
+            "error": "the event does not match the hold expected type: expected a boolean, got a secure value at line 19, column 5 at flow [flow]"
+        })]
+    );
+
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/hold_expect.csml",
+        "confirm",
+        hold,
+        text_event("True"),
+    );
+
+    assert_eq!(contents(msg), vec![json!({"text": "confirmed"})]);
+}
+
+#[test]
+fn hold_expect_unknown_type() {
+    let content = "start:\n    hold expect date\n    goto end";
+    let bot = init_bot(content);
+
+    let result = csml_interpreter::validate_bot(&bot);
+    let errors = result.errors.unwrap();
+
+    assert!(errors[0]
+        .message
+        .contains("hold expect expects one of the types"));
+}
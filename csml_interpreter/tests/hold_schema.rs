@@ -0,0 +1,122 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MessageData;
+
+use crate::support::tools::{message_to_json_value, run_step_with_hold};
+
+use serde_json::{json, Value};
+
+fn payload_event(payload: &str) -> Event {
+    Event::new("payload", payload, json!({ "payload": payload }))
+}
+
+fn get_error(msg: &MessageData) -> String {
+    let value = message_to_json_value(msg.to_owned());
+
+    value["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn hold_schema_valid_event() {
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "start",
+        None,
+        payload_event("start"),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"confirm?"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+    assert!(hold.is_some());
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "start",
+        hold,
+        payload_event("yes"),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"answer yes"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+    assert!(hold.is_none());
+}
+
+#[test]
+fn hold_schema_invalid_event() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "start",
+        None,
+        payload_event("start"),
+    );
+
+    // the conversation stays on hold with the step variables until the event matches
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "start",
+        hold,
+        payload_event("maybe"),
+    );
+
+    assert_eq!(msg.messages.len(), 1);
+    assert!(get_error(&msg).contains("the event does not match the hold schema"));
+    assert!(get_error(&msg).contains("$.payload: \"maybe\" is not one of [\"yes\",\"no\"]"));
+    assert!(hold.is_some());
+
+    let (msg, _) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "start",
+        hold,
+        payload_event("no"),
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"answer no"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+}
+
+#[test]
+fn hold_schema_nested_fields() {
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/hold_schema.csml",
+        "nested",
+        None,
+        payload_event("start"),
+    );
+
+    let event = Event::new("object", "", json!({"address": {"zip": "750012"}}));
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/hold_schema.csml", "nested", hold, event);
+
+    assert!(get_error(&msg).contains("$.address.zip: 6 is greater than maxLength 5"));
+    assert!(hold.is_some());
+
+    let event = Event::new("object", "", json!({"address": {}}));
+    let (msg, hold) = run_step_with_hold("CSML/basic_test/hold_schema.csml", "nested", hold, event);
+
+    assert!(get_error(&msg).contains("$.address.zip: missing required property"));
+
+    let event = Event::new("object", "", json!({"address": {"zip": "75001"}}));
+    let (msg, _) = run_step_with_hold("CSML/basic_test/hold_schema.csml", "nested", hold, event);
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(
+        r#"{"memories":[], "messages":[{"content":{"text":"75001"}, "content_type":"text"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(v1, v2);
+}
@@ -0,0 +1,175 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::{event::Event, primitive::PrimitiveString, Interval};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::thread;
+use std::time::Duration;
+
+use crate::support::tools::{format_message, message_to_json_value, step_context};
+
+use serde_json::Value;
+
+/// Mock HTTP server answering each request with the next (delay, status, body) of
+/// `responses`, returns its url and the number of requests received
+fn mock_server(responses: Vec<(u64, u16, &'static str)>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    thread::spawn(move || {
+        for ((delay, status, body), stream) in responses.into_iter().zip(listener.incoming()) {
+            let mut stream = stream.unwrap();
+            let counter = counter.clone();
+
+            // a delayed response must not hold back the retried requests
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                // read the request headers and body before answering
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                thread::sleep(Duration::from_millis(delay));
+
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nX-Mock: csml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                // the client may have given up on a delayed response
+                let _ = stream.write_all(response.as_bytes());
+            });
+        }
+    });
+
+    (url, requests)
+}
+
+/// Context of `step` with the url of the mock server in the metadata
+fn url_context(step: &str, url: &str) -> Context {
+    let mut context = step_context(step, None);
+    context.metadata.insert(
+        "url".to_owned(),
+        PrimitiveString::get_literal(url, Interval::default()),
+    );
+
+    context
+}
+
+#[test]
+fn http_fetch_success() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"200"}, "content_type":"text"},
+        {"content":{"ok":true}, "content_type":"object"},
+        {"content":{"text":"csml"}, "content_type":"text"}
+    ]}"#;
+    let (url, requests) = mock_server(vec![(0, 200, r#"{"ok": true}"#)]);
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        url_context("start", &url),
+        "CSML/basic_test/stdlib/http_retry.csml",
+    );
+    let v1 = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn http_fetch_retry_then_success() {
+    let (url, requests) = mock_server(vec![
+        (0, 503, r#"{"error": "unavailable"}"#),
+        (0, 500, r#"{"error": "internal"}"#),
+        (0, 200, r#"{"ok": true}"#),
+    ]);
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        url_context("start", &url),
+        "CSML/basic_test/stdlib/http_retry.csml",
+    );
+    let v1 = message_to_json_value(msg);
+
+    assert_eq!(v1["messages"][0]["content"]["text"], "200");
+    assert_eq!(
+        v1["messages"][1]["content"],
+        serde_json::json!({"ok": true})
+    );
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn http_fetch_retry_on_timeout() {
+    let (url, requests) = mock_server(vec![(1000, 200, "{}"), (0, 201, "{}")]);
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        url_context("timeout", &url),
+        "CSML/basic_test/stdlib/http_retry.csml",
+    );
+    let v1 = message_to_json_value(msg);
+
+    assert_eq!(v1["messages"][0]["content"]["text"], "201");
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn http_fetch_permanent_failure() {
+    let (url, requests) = mock_server(vec![(0, 500, "{}"), (0, 502, "{}"), (0, 200, "{}")]);
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        url_context("permanent_failure", &url),
+        "CSML/basic_test/stdlib/http_retry.csml",
+    );
+    let v1 = message_to_json_value(msg);
+    let messages = v1["messages"].as_array().unwrap();
+
+    // the error after the last retry is caught by the try block
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0]["content"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("status code 502"));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn http_fetch_client_error_not_retried() {
+    let (url, requests) = mock_server(vec![(0, 404, "{}"), (0, 200, "{}")]);
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        url_context("permanent_failure", &url),
+        "CSML/basic_test/stdlib/http_retry.csml",
+    );
+    let v1 = message_to_json_value(msg);
+
+    assert!(v1["messages"][0]["content"]["text"]
+        .as_str()
+        .unwrap()
+        .contains("status code 404"));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
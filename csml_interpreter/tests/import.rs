@@ -0,0 +1,175 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{CsmlBot, CsmlFlow};
+use csml_interpreter::{interpret, validate_bot};
+
+use crate::support::tools::{init_bot_with_flows, message_to_json_value, read_file, step_context};
+
+use serde_json::Value;
+
+fn init_bot(main: &str) -> CsmlBot {
+    init_bot_with_env(main, None)
+}
+
+fn init_bot_with_env(main: &str, env: Option<Value>) -> CsmlBot {
+    let main = read_file(format!("CSML/basic_test/import/{}.csml", main)).unwrap();
+    let sales_flow = read_file("CSML/basic_test/import/sales_flow.csml".to_owned()).unwrap();
+    let sales_flow_b = read_file("CSML/basic_test/import/sales_flow_b.csml".to_owned()).unwrap();
+
+    CsmlBot {
+        env,
+        ..init_bot_with_flows(vec![
+            CsmlFlow::new("flow", "flow", &main, Vec::default()),
+            CsmlFlow::new("sales_flow", "sales_flow", &sales_flow, Vec::default()),
+            CsmlFlow::new(
+                "sales_flow_b",
+                "sales_flow_b",
+                &sales_flow_b,
+                Vec::default(),
+            ),
+        ])
+    }
+}
+
+#[test]
+fn import_selected_functions() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"hello csml"}, "content_type":"text"},
+        {"content":{"text":"total 42"}, "content_type":"text"}
+    ]}"#;
+    let msg = interpret(
+        init_bot("main"),
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn import_alias() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"hello alias"}, "content_type":"text"}
+    ]}"#;
+    let msg = interpret(
+        init_bot("main"),
+        step_context("alias", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn import_missing_symbol() {
+    let errors = validate_bot(&init_bot("missing_symbol"))
+        .errors
+        .unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("'refund'"));
+    assert!(errors[0].message.contains("sales_flow"));
+}
+
+#[test]
+fn import_condition_else_flow() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"hello csml"}, "content_type":"text"}
+    ]}"#;
+    let bot = init_bot_with_env("conditional", Some(serde_json::json!({"variant": "a"})));
+    let msg = interpret(
+        bot,
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn import_condition_then_flow() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"hi csml"}, "content_type":"text"}
+    ]}"#;
+    let bot = init_bot_with_env("conditional", Some(serde_json::json!({"variant": "b"})));
+    let msg = interpret(
+        bot,
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn import_condition_flag() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"hi csml"}, "content_type":"text"}
+    ]}"#;
+    let bot = init_bot_with_env("conditional_flag", Some(serde_json::json!({"use_b": true})));
+    let msg = interpret(
+        bot,
+        step_context("start", None),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn import_condition_unresolved() {
+    let errors = validate_bot(&init_bot_with_env(
+        "conditional",
+        Some(serde_json::json!({})),
+    ))
+    .errors
+    .unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("'_env.variant'"));
+}
+
+#[test]
+fn import_condition_flag_not_boolean() {
+    let bot = init_bot_with_env(
+        "conditional_flag",
+        Some(serde_json::json!({"use_b": "yes"})),
+    );
+    let errors = validate_bot(&bot).errors.unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("'_env.use_b'"));
+}
+
+#[test]
+fn import_condition_missing_flow() {
+    let bot = init_bot_with_env(
+        "conditional_missing_flow",
+        Some(serde_json::json!({"variant": "c"})),
+    );
+    let errors = validate_bot(&bot).errors.unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message.contains("'sales_flow_c'"));
+}
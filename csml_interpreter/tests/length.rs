@@ -4,8 +4,7 @@ use csml_interpreter::data::context::Context;
 use csml_interpreter::data::event::Event;
 use std::collections::HashMap;
 
-use crate::support::tools::format_message;
-use crate::support::tools::message_to_json_value;
+use crate::support::tools::{format_message, message_to_json_value, run_step};
 
 use serde_json::Value;
 
@@ -73,5 +72,46 @@ fn ok_length_2() {
         "CSML/basic_test/built-in/length.csml",
     );
 
-    assert_eq!(msg.messages[0].content_type, "error")
+    assert_eq!(msg.messages[0].content_type, "error");
+    // the error names the type of the value
+    assert!(msg.messages[0].content["error"]
+        .as_str()
+        .unwrap()
+        .contains("(got int)"))
+}
+
+#[test]
+fn ok_length_unicode() {
+    // the emoji and its skin tone modifier are two characters, but 8 bytes
+    let data = r#"{"messages":[
+        {"content":{ "text": "8"  },"content_type":"text"},
+        {"content":{ "text": "8"  },"content_type":"text"}
+    ],"memories":[]}"#;
+    let v1: Value = run_step("CSML/basic_test/built-in/length.csml", "step_2");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_length_object_and_null() {
+    let data = r#"{"messages":[
+        {"content":{ "text": "2"  },"content_type":"text"},
+        {"content":{ "text": "0"  },"content_type":"text"}
+    ],"memories":[]}"#;
+    let v1: Value = run_step("CSML/basic_test/built-in/length.csml", "step_3");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_length_boolean() {
+    let v1: Value = run_step("CSML/basic_test/built-in/length.csml", "step_4");
+
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("(got boolean)"))
 }
@@ -0,0 +1,67 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::Hold;
+
+use crate::support::tools::{message_to_json_value, run_step, run_step_with_hold};
+
+use serde_json::Value;
+
+fn get_texts(value: &Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"]["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn loop_label_break() {
+    let v1 = run_step("CSML/basic_test/loop_label.csml", "start");
+
+    assert_eq!(
+        get_texts(&v1),
+        vec!["1-1", "1-2", "1-3", "2-1", "2-2", "done"]
+    );
+}
+
+#[test]
+fn loop_label_continue() {
+    let v1 = run_step("CSML/basic_test/loop_label.csml", "continue_label");
+
+    assert_eq!(get_texts(&v1), vec!["1-1", "2-1", "3-1", "done"]);
+}
+
+#[test]
+fn loop_label_break_from_while() {
+    let v1 = run_step("CSML/basic_test/loop_label.csml", "break_from_while");
+
+    assert_eq!(get_texts(&v1), vec!["0-2", "done"]);
+}
+
+#[test]
+fn loop_label_hold() {
+    let run = |hold: Option<Hold>| {
+        let (msg_data, hold) = run_step_with_hold(
+            "CSML/basic_test/loop_label.csml",
+            "hold_in_label",
+            hold,
+            Event::new("payload", "", serde_json::json!({})),
+        );
+
+        (message_to_json_value(msg_data), hold)
+    };
+
+    let (v1, hold) = run(None);
+    assert_eq!(get_texts(&v1), vec!["1-1", "1-2"]);
+
+    // resuming inside the nested loop, the labeled continue goes to the next outer value
+    let (v1, hold) = run(hold);
+    assert_eq!(get_texts(&v1), vec!["2-1", "2-2"]);
+
+    // the labeled break leaves both loops
+    let (v1, hold) = run(hold);
+    assert_eq!(get_texts(&v1), vec!["done"]);
+    assert!(hold.is_none());
+}
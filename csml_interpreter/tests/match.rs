@@ -0,0 +1,169 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::hold::{Hold, IndexInfo};
+
+use crate::support::tools::{format_message, message_to_json_value, run_step, step_context};
+
+use serde_json::Value;
+
+#[test]
+fn match_string() {
+    let data =
+        r#"{"messages":[ {"content":{ "text": "bye" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_wildcard() {
+    let data = r#"{"messages":[ {"content":{ "text": "fallback" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_wildcard");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_number() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "two" },"content_type":"text"},
+                {"content":{ "text": "deux" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_number");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_first_wins() {
+    let data =
+        r#"{"messages":[ {"content":{ "text": "first" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_first_wins");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_no_arm() {
+    let data =
+        r#"{"messages":[ {"content":{ "text": "after" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_no_arm");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_hold() {
+    let data =
+        r#"{"messages":[ {"content":{ "text": "before" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_hold");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1["messages"], v2["messages"])
+}
+
+#[test]
+fn match_hold_resume() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "after" },"content_type":"text"},
+                {"content":{ "text": "end" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let hold = Hold::new(
+        IndexInfo {
+            command_index: 2,
+            loop_index: vec![],
+        },
+        serde_json::json!({}),
+        "match_hold".to_owned(),
+        "flow".to_owned(),
+        None,
+        false,
+    );
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("match_hold", Some(hold)),
+        "CSML/basic_test/match.csml",
+    );
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_regex() {
+    let data =
+        r#"{"messages":[ {"content":{ "text": "regex" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_regex");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_regex_fallback() {
+    let data = r#"{"messages":[ {"content":{ "text": "fallback" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_regex_fallback");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_regex_not_string() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "fallback" },"content_type":"text"},
+                {"content":{ "text": "true" },"content_type":"text"},
+                {"content":{ "text": "false" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_regex_not_string");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn match_infix_multiline() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "infix" },"content_type":"text"},
+                {"content":{ "text": "statement" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/match.csml", "match_infix_multiline");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,200 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::primitive::{PrimitiveInt, PrimitiveString};
+use csml_interpreter::data::{Interval, Literal, Memory, MemoryDiff, MessageData};
+use csml_interpreter::interpreter::memory_to_literal;
+use std::collections::HashMap;
+
+use crate::support::tools::{format_message, step_context};
+
+use serde_json::json;
+
+/// Context of `step` with the memories saved before the turn
+fn memory_context(step: &str, current: HashMap<String, Literal>) -> Context {
+    Context {
+        current,
+        ..step_context(step, None)
+    }
+}
+
+fn init_memories() -> HashMap<String, Literal> {
+    let mut current = HashMap::new();
+    current.insert(
+        "counter".to_owned(),
+        PrimitiveInt::get_literal(1, Interval::default()),
+    );
+    current.insert(
+        "name".to_owned(),
+        PrimitiveString::get_literal("Alice", Interval::default()),
+    );
+
+    current
+}
+
+#[test]
+fn memory_diff_added_key() {
+    let diff = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("start", HashMap::new()),
+        "CSML/basic_test/memory_diff.csml",
+    )
+    .memory_diff;
+
+    assert_eq!(
+        diff.to_json(),
+        json!({
+            "added": [{"key": "name", "before": null, "after": "Alice"}],
+            "modified": [],
+            "removed": [],
+        })
+    );
+}
+
+#[test]
+fn memory_diff_overwritten_key() {
+    let diff = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("overwrite", init_memories()),
+        "CSML/basic_test/memory_diff.csml",
+    )
+    .memory_diff;
+
+    // name is remembered with the same value so it is not reported
+    assert_eq!(
+        diff.to_json(),
+        json!({
+            "added": [],
+            "modified": [{"key": "counter", "before": 1, "after": 2}],
+            "removed": [],
+        })
+    );
+}
+
+#[test]
+fn memory_diff_removed_key() {
+    let diff = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("forget_key", init_memories()),
+        "CSML/basic_test/memory_diff.csml",
+    )
+    .memory_diff;
+
+    assert_eq!(
+        diff.to_json(),
+        json!({
+            "added": [],
+            "modified": [],
+            "removed": [{"key": "counter", "before": 1, "after": null}],
+        })
+    );
+}
+
+#[test]
+fn memory_diff_no_change() {
+    let diff = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("no_change", init_memories()),
+        "CSML/basic_test/memory_diff.csml",
+    )
+    .memory_diff;
+
+    assert!(diff.is_empty());
+    assert_eq!(diff, MemoryDiff::default());
+}
+
+fn get_memory(msg: &MessageData, key: &str) -> Memory {
+    msg.memories
+        .as_ref()
+        .unwrap()
+        .iter()
+        .find(|memory| memory.key == key)
+        .unwrap()
+        .to_owned()
+}
+
+#[test]
+fn memory_diff_sensitive_key() {
+    let msg = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("secret_key", HashMap::new()),
+        "CSML/basic_test/memory_diff.csml",
+    );
+
+    assert_eq!(
+        msg.memory_diff.to_json(),
+        json!({
+            "added": [
+                {"key": "name", "before": null, "after": "Alice"},
+                {"key": "ssn", "before": null, "after": "***"},
+            ],
+            "modified": [],
+            "removed": [],
+        })
+    );
+
+    // the real value is saved, tagged as sensitive, but never displayed
+    let ssn = get_memory(&msg, "ssn");
+    assert!(ssn.is_sensitive());
+    assert_eq!(ssn.value["value"], "123-45-6789");
+    assert_eq!(Memory::redact_value(&ssn.value), json!("***"));
+
+    let debug = format!("{:?}", ssn);
+    assert!(!debug.contains("123-45-6789"));
+    assert!(debug.contains("***"));
+
+    let name = get_memory(&msg, "name");
+    assert!(!name.is_sensitive());
+    assert!(format!("{:?}", name).contains("Alice"));
+}
+
+#[test]
+fn memory_diff_sensitive_key_next_turn() {
+    // the memory saved in a previous turn, as read from the database
+    let saved = json!({"_additional_info": {"sensitive": true}, "value": "123-45-6789"});
+    let mut current = HashMap::new();
+    current.insert(
+        "ssn".to_owned(),
+        memory_to_literal(&saved, Interval::default(), "flow").unwrap(),
+    );
+
+    let msg = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("secret_overwrite", current),
+        "CSML/basic_test/memory_diff.csml",
+    );
+
+    assert_eq!(
+        msg.memory_diff.to_json(),
+        json!({
+            "added": [],
+            "modified": [{"key": "ssn", "before": "***", "after": "***"}],
+            "removed": [],
+        })
+    );
+
+    // remembered without secret, the memory is still sensitive
+    let ssn = get_memory(&msg, "ssn");
+    assert!(ssn.is_sensitive());
+    assert_eq!(ssn.value["value"], "987-65-4321");
+}
+
+#[test]
+fn memory_diff_memory_named_secret() {
+    let diff = format_message(
+        Event::new("payload", "", json!({})),
+        memory_context("secret_name", HashMap::new()),
+        "CSML/basic_test/memory_diff.csml",
+    )
+    .memory_diff;
+
+    assert_eq!(
+        diff.to_json(),
+        json!({
+            "added": [{"key": "secret", "before": null, "after": "not sensitive"}],
+            "modified": [],
+            "removed": [],
+        })
+    );
+}
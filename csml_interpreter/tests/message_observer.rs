@@ -0,0 +1,119 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{ApiInfo, Client, Interval, Message, MessageObserver};
+use csml_interpreter::interpret_with_observer;
+use std::cell::RefCell;
+
+use crate::support::tools::{init_bot, message_to_json_value, read_file, step_context};
+
+use serde_json::Value;
+
+/// Observer keeping the line and the content of the messages it sees
+#[derive(Default)]
+struct CollectObserver {
+    messages: RefCell<Vec<(Option<Client>, u32, Value)>>,
+}
+
+impl MessageObserver for CollectObserver {
+    fn on_message(
+        &self,
+        client: Option<&Client>,
+        interval: &Interval,
+        message: &Message,
+    ) -> Result<(), String> {
+        self.messages.borrow_mut().push((
+            client.cloned(),
+            interval.start_line,
+            message.content.to_owned(),
+        ));
+
+        Ok(())
+    }
+}
+
+struct FailingObserver;
+
+impl MessageObserver for FailingObserver {
+    fn on_message(&self, _: Option<&Client>, _: &Interval, _: &Message) -> Result<(), String> {
+        Err("audit store unavailable".to_owned())
+    }
+}
+
+fn get_client() -> Client {
+    Client::new("bot".to_owned(), "channel".to_owned(), "user".to_owned())
+}
+
+/// Context of `step` for the client of get_client
+fn client_context(step: &str) -> Context {
+    Context {
+        api_info: Some(ApiInfo {
+            client: get_client(),
+            apps_endpoint: "http://localhost".to_owned(),
+        }),
+        ..step_context(step, None)
+    }
+}
+
+#[test]
+fn message_observer_emission_order() {
+    let observer = CollectObserver::default();
+    let content = read_file("CSML/basic_test/message_observer.csml".to_owned()).unwrap();
+    let msg = interpret_with_observer(
+        init_bot(&content),
+        client_context("start"),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+        &observer,
+    );
+
+    let messages = observer.messages.borrow();
+    let lines: Vec<u32> = messages.iter().map(|(_, line, _)| *line).collect();
+    let contents: Vec<Value> = messages
+        .iter()
+        .map(|(_, _, content)| content.to_owned())
+        .collect();
+
+    assert_eq!(lines, vec![2, 3, 8, 9]);
+    assert_eq!(
+        contents,
+        vec![
+            serde_json::json!({"text": "first"}),
+            serde_json::json!({"text": "second"}),
+            serde_json::json!({"text": "3"}),
+            serde_json::json!({"args": ["fourth"]}),
+        ]
+    );
+    assert!(messages
+        .iter()
+        .all(|(client, _, _)| *client == Some(get_client())));
+
+    // the observer sees the messages of the run, as they are returned
+    let value = message_to_json_value(msg);
+    let returned: Vec<Value> = value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].to_owned())
+        .collect();
+    assert_eq!(returned, contents);
+}
+
+#[test]
+fn message_observer_error_does_not_abort() {
+    let content = read_file("CSML/basic_test/message_observer.csml".to_owned()).unwrap();
+    let msg = interpret_with_observer(
+        init_bot(&content),
+        client_context("start"),
+        Event::new("payload", "", serde_json::json!({})),
+        None,
+        &FailingObserver,
+    );
+
+    assert!(msg.error.is_none());
+
+    let value = message_to_json_value(msg);
+    assert_eq!(value["messages"].as_array().unwrap().len(), 4);
+    assert_eq!(value["messages"][3]["content"]["args"][0], "fourth");
+}
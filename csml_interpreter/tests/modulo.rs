@@ -0,0 +1,202 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use std::collections::HashMap;
+
+use crate::support::tools::format_message;
+use crate::support::tools::message_to_json_value;
+
+use serde_json::Value;
+
+#[test]
+fn ok_negative_operands() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"},"content_type":"text"}, {"content":{"text":"-2"},"content_type":"text"}, {"content":{"text":"-4"},"content_type":"text"}, {"content":{"text":"3"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+/// MODULO
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn modulo_cycle() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"blue"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "modulo_cycle",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn modulo_by_zero() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Int: Division by zero at line 19, column 12 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "modulo_by_zero",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn modulo_float() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"0.5"},"content_type":"text"}, {"content":{"text":"0"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "modulo_float",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn modulo_float_by_zero() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Float: Division by zero at line 28, column 12 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "modulo_float_by_zero",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+/// INTEGER DIVISION
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn div_promotion() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"3"},"content_type":"text"}, {"content":{"text":"3"},"content_type":"text"}, {"content":{"text":"3"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "div_promotion",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn div_by_zero() {
+    let data = r#"{"memories":[], "messages":[{"content":{"error":"[!] Int: Division by zero at line 42, column 12 at flow [flow]"},"content_type":"error"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "div_by_zero",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn div_identifier() {
+    let data = r#"{"memories":[], "messages":[{"content":{"text":"2"},"content_type":"text"}]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "div_identifier",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/numerical_operation/modulo.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,85 @@
+mod support;
+
+use csml_interpreter::data::NativeFunction;
+use csml_interpreter::{register_native_function, validate_bot};
+
+use crate::support::tools::{init_bot, read_file, run_step};
+
+use serde_json::Value;
+
+const FLOW: &str = "CSML/basic_test/native_function/native_function.csml";
+
+/// Native function reversing its text argument
+struct Reverse;
+
+impl NativeFunction for Reverse {
+    fn call(&self, args: Vec<Value>) -> Result<Value, String> {
+        match args.first() {
+            Some(Value::String(text)) => Ok(Value::String(text.chars().rev().collect())),
+            _ => Err("reverse expects one value of type String".to_owned()),
+        }
+    }
+}
+
+fn texts(value: Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"]["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+fn read_flow(flow: &str) -> String {
+    read_file(format!("CSML/basic_test/native_function/{}.csml", flow)).unwrap()
+}
+
+#[test]
+fn native_function_call() {
+    register_native_function("reverse", Box::new(Reverse));
+
+    assert_eq!(texts(run_step(FLOW, "start")), vec!["lmsc"]);
+}
+
+#[test]
+fn native_function_in_expression() {
+    register_native_function("reverse", Box::new(Reverse));
+
+    assert_eq!(
+        texts(run_step(FLOW, "native_function_in_expression")),
+        vec!["csml hello"]
+    );
+}
+
+#[test]
+fn native_function_error_is_caught() {
+    register_native_function("reverse", Box::new(Reverse));
+    let texts = texts(run_step(FLOW, "native_function_error"));
+
+    assert_eq!(texts.len(), 2);
+    assert!(texts[0].contains("reverse expects one value of type String"));
+    assert_eq!(texts[1], "after");
+}
+
+#[test]
+fn native_function_is_a_known_function() {
+    register_native_function("reverse", Box::new(Reverse));
+
+    let errors = validate_bot(&init_bot(&read_flow("native_function"))).errors;
+
+    assert!(errors.is_none());
+}
+
+#[test]
+fn native_function_collision() {
+    register_native_function("reverse", Box::new(Reverse));
+
+    let errors = validate_bot(&init_bot(&read_flow("collision")))
+        .errors
+        .unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .message
+        .contains("function collides with the native function 'reverse'"));
+}
@@ -0,0 +1,70 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::json;
+
+#[test]
+fn nested_object_write() {
+    let v1 = run_step("CSML/basic_test/nested_assignment.csml", "start");
+
+    assert_eq!(
+        v1["messages"],
+        json!([{
+            "content": {"address": {"city": "Paris", "zip": "69000"}},
+            "content_type": "object"
+        }])
+    );
+}
+
+#[test]
+fn array_index_write() {
+    let v1 = run_step("CSML/basic_test/nested_assignment.csml", "array_index");
+
+    assert_eq!(
+        v1["messages"],
+        json!([{"content": ["a", "b", "z"], "content_type": "array"}])
+    );
+}
+
+#[test]
+fn compound_write_at_path() {
+    let v1 = run_step("CSML/basic_test/nested_assignment.csml", "compound");
+    let user = json!({"stats": {"visits": 2}, "scores": [10, 40]});
+
+    assert_eq!(
+        v1["messages"],
+        json!([{"content": user, "content_type": "object"}])
+    );
+    // the remembered variable is saved with each update
+    assert_eq!(
+        v1["memories"].as_array().unwrap().last().unwrap()["value"]["_content"],
+        user
+    );
+}
+
+#[test]
+fn missing_intermediate_error() {
+    let v1 = run_step(
+        "CSML/basic_test/nested_assignment.csml",
+        "missing_intermediate",
+    );
+
+    assert_eq!(
+        v1["messages"][0]["content"]["error"],
+        "[address] key does not exist at line 22, column 13 at flow [flow]"
+    );
+    // the variable is left unchanged
+    assert_eq!(v1["messages"][1]["content"], json!({"name": "Jo"}));
+}
+
+#[test]
+fn member_write_on_non_object_error() {
+    let v1 = run_step("CSML/basic_test/nested_assignment.csml", "not_an_object");
+
+    assert_eq!(
+        v1["messages"][0]["content"]["error"],
+        "[label] can't be set on a value that is not an Object at line 28, column 21 at flow [flow]"
+    );
+    assert_eq!(v1["messages"][1]["content"], json!({"tags": ["a"]}));
+}
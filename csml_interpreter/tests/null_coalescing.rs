@@ -0,0 +1,94 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn null_coalescing() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "default" },"content_type":"text"},
+                {"content":{ "text": "unset" },"content_type":"text"},
+                {"content":{ "text": "text" },"content_type":"text"},
+                {"content":{ "text": "false" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/null_coalescing.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn null_coalescing_chained() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "third" },"content_type":"text"},
+                {"content":{ "text": "0" },"content_type":"text"},
+                {"content":{ "text": null },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/null_coalescing.csml", "chained");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn null_coalescing_member() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "Ada" },"content_type":"text"},
+                {"content":{ "text": "36" },"content_type":"text"},
+                {"content":{ "text": "unknown" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/null_coalescing.csml", "member");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn null_equality() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "value is null" },"content_type":"text"},
+                {"content":{ "text": "missing is null" },"content_type":"text"},
+                {"content":{ "text": "text is not null" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/null_coalescing.csml", "equality");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn null_member_access() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "[name] can't be accessed on a Null value" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/null_coalescing.csml", "member_of_null");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
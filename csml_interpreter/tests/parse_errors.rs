@@ -0,0 +1,201 @@
+mod support;
+
+use csml_interpreter::data::ast::Flow;
+use csml_interpreter::error_format::{ErrorCode, ErrorInfo};
+use csml_interpreter::parser::{parse_flow, parse_flow_collect_errors};
+use csml_interpreter::validate_bot;
+
+use support::tools::{init_bot, read_file};
+
+fn format_message(filepath: String) -> Result<Flow, ErrorInfo> {
+    let text = read_file(filepath).unwrap();
+
+    parse_flow(&text, "Test")
+}
+
+fn collect_errors(filepath: String) -> (Option<Flow>, Vec<ErrorInfo>) {
+    let text = read_file(filepath).unwrap();
+
+    parse_flow_collect_errors(&text, "Test")
+}
+
+fn validate_flow(filepath: String) -> Vec<ErrorInfo> {
+    let content = read_file(filepath).unwrap();
+
+    validate_content(&content)
+}
+
+fn validate_content(content: &str) -> Vec<ErrorInfo> {
+    let bot = init_bot(content);
+
+    validate_bot(&bot).errors.unwrap_or_default()
+}
+
+#[test]
+fn error_code_unexpected_token() {
+    let err = format_message("CSML/basic_test/syntax/errors/unexpected_token.csml".to_owned())
+        .unwrap_err();
+
+    assert_eq!(err.code, ErrorCode::UnexpectedToken);
+}
+
+#[test]
+fn error_code_incomplete_input() {
+    let err = format_message("CSML/basic_test/syntax/errors/incomplete_input.csml".to_owned())
+        .unwrap_err();
+
+    assert_eq!(err.code, ErrorCode::IncompleteInput);
+    assert!(err.message.contains("expecting '}'"));
+}
+
+#[test]
+fn error_code_unterminated_comment() {
+    let err =
+        format_message("CSML/basic_test/syntax/comment/comment_1.csml".to_owned()).unwrap_err();
+
+    assert_eq!(err.code, ErrorCode::IncompleteInput);
+}
+
+#[test]
+fn error_code_duplicate_step() {
+    let errors = validate_flow("CSML/basic_test/linter/duplicate_step.csml".to_owned());
+
+    assert!(errors
+        .iter()
+        .any(|err| err.code == ErrorCode::DuplicateInstruction
+            && err.message.contains("duplicate step start")));
+}
+
+#[test]
+fn error_duplicate_step_interval() {
+    let errors = validate_flow("CSML/basic_test/linter/duplicate_step.csml".to_owned());
+    let err = errors
+        .iter()
+        .find(|err| err.code == ErrorCode::DuplicateInstruction)
+        .unwrap();
+
+    // the error is on the redefinition of the step
+    assert_eq!(err.position.interval.start_line, 5);
+    assert_eq!(err.position.interval.start_column, 1);
+    assert!(err.message.contains("duplicate step start"));
+}
+
+#[test]
+fn error_duplicate_step_large_flow() {
+    let mut content = String::from("start:\n    goto step_0\n\n");
+    for index in 0..500 {
+        content.push_str(&format!(
+            "step_{}:\n    say \"{}\"\n    goto step_{}\n\n",
+            index,
+            index,
+            index + 1
+        ));
+    }
+    // line of the redefinition, after the 3 lines of start and the 4 lines of each step
+    let duplicate_line = 3 + 500 * 4 + 1;
+    content.push_str("step_250:\n    goto end\n");
+
+    let flow = parse_flow(&content, "Test").unwrap();
+    // start, the 500 steps and the duplicated step_250
+    assert_eq!(flow.flow_instructions.len(), 502);
+
+    let errors: Vec<ErrorInfo> = validate_content(&content)
+        .into_iter()
+        .filter(|err| err.code == ErrorCode::DuplicateInstruction)
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].position.interval.start_line, duplicate_line);
+    assert!(errors[0].message.contains("duplicate step step_250"));
+}
+
+#[test]
+fn error_code_serialization() {
+    let err = format_message("CSML/basic_test/syntax/errors/unexpected_token.csml".to_owned())
+        .unwrap_err();
+    let value = serde_json::json!(err);
+
+    assert_eq!(value["code"], "unexpected_token");
+    assert_eq!(value["message"], err.message);
+}
+
+#[test]
+fn collect_multiple_errors() {
+    let (flow, errors) =
+        collect_errors("CSML/basic_test/syntax/errors/multiple_errors.csml".to_owned());
+    let positions: Vec<(u32, u32)> = errors
+        .iter()
+        .map(|err| {
+            (
+                err.position.interval.start_line,
+                err.position.interval.start_column,
+            )
+        })
+        .collect();
+
+    assert!(flow.is_none());
+    assert_eq!(positions, vec![(3, 11), (11, 12), (15, 15)]);
+    assert_eq!(errors[0].code, ErrorCode::UnexpectedToken);
+    assert_eq!(errors[1].code, ErrorCode::IncompleteInput);
+    assert_eq!(errors[2].code, ErrorCode::UnexpectedToken);
+}
+
+#[test]
+fn collect_errors_first_error_matches_parse_flow() {
+    let filepath = "CSML/basic_test/syntax/errors/multiple_errors.csml";
+    let (_, errors) = collect_errors(filepath.to_owned());
+    let err = format_message(filepath.to_owned()).unwrap_err();
+
+    assert_eq!(errors[0].position.interval, err.position.interval);
+    assert_eq!(errors[0].message, err.message);
+}
+
+#[test]
+fn collect_errors_valid_flow() {
+    let (flow, errors) = collect_errors("CSML/basic_test/hold.csml".to_owned());
+    let expected = format_message("CSML/basic_test/hold.csml".to_owned()).unwrap();
+
+    assert!(errors.is_empty());
+    assert_eq!(
+        flow.unwrap().flow_instructions.len(),
+        expected.flow_instructions.len()
+    );
+}
+
+#[test]
+fn error_ternary_missing_else() {
+    let err = format_message("CSML/basic_test/syntax/errors/ternary_missing_else.csml".to_owned())
+        .unwrap_err();
+
+    assert!(err.message.contains("ternary expressions expect ':'"));
+}
+
+#[test]
+fn error_match_invalid_regex() {
+    let err = format_message("CSML/basic_test/syntax/errors/match_invalid_regex.csml".to_owned())
+        .unwrap_err();
+
+    assert!(err.message.contains("invalid regex in match arm"));
+    // the error points to the start of the pattern
+    assert_eq!(err.position.interval.start_line, 3);
+    assert_eq!(err.position.interval.start_column, 9);
+}
+
+#[test]
+fn error_metadata_duplicate() {
+    let err = format_message("CSML/basic_test/syntax/errors/metadata_duplicate.csml".to_owned())
+        .unwrap_err();
+
+    assert!(err.message.contains("a single @metadata block"));
+    assert_eq!(err.position.interval.start_line, 7);
+}
+
+#[test]
+fn error_import_collision() {
+    let err = format_message("CSML/basic_test/syntax/errors/import_collision.csml".to_owned())
+        .unwrap_err();
+
+    assert!(err
+        .message
+        .contains("import collides with the local function 'greet'"));
+    assert_eq!(err.position.interval.start_line, 1);
+}
@@ -0,0 +1,80 @@
+use csml_interpreter::data::ast::{Expr, InstructionScope};
+use csml_interpreter::parser::{parse_flow, parse_step_in_range};
+
+const FLOW: &str = "start:\n    say \"hello\"\n    goto second\n\n\
+                    second:\n    say \"second\"\n    if (event) {\n        goto end\n    }\n\n\
+                    third:\n    say \"third\"\n    goto end\n";
+
+fn get_range(step: &str, next_step: Option<&str>) -> std::ops::Range<usize> {
+    let start = FLOW.find(step).unwrap();
+    let end = match next_step {
+        Some(next_step) => FLOW.find(next_step).unwrap(),
+        None => FLOW.len(),
+    };
+
+    start..end
+}
+
+#[test]
+fn parse_step_in_range_ok() {
+    let range = get_range("second:", Some("third:"));
+    let instruction = parse_step_in_range(FLOW, range, "flow").unwrap();
+
+    assert_eq!(
+        instruction.instruction_type,
+        InstructionScope::StepScope("second".to_owned())
+    );
+    match instruction.actions {
+        Expr::Scope { scope, range, .. } => {
+            assert_eq!(scope.commands.len(), 2);
+            // the interval is the one of the step in the whole flow
+            assert_eq!(range.start_line, 5);
+            assert_eq!(range.start_column, 1);
+            assert_eq!(range.offset, FLOW.find("second:").unwrap());
+        }
+        expr => panic!("{:?}", expr),
+    }
+}
+
+#[test]
+fn parse_step_in_range_error_position() {
+    let flow = FLOW.replace("say \"third\"", "say \"third\" +");
+    let start = flow.find("third:").unwrap();
+
+    let err = parse_step_in_range(&flow, start..flow.len(), "flow").unwrap_err();
+    let flow_err = parse_flow(&flow, "flow").unwrap_err();
+
+    // lines and columns start at the origin of the range in the flow
+    assert_eq!(err.position.interval.start_line, 12);
+    assert!(err.position.interval.offset > start);
+    assert_eq!(err.position.interval, flow_err.position.interval);
+    assert_eq!(err.message, flow_err.message);
+}
+
+#[test]
+fn parse_step_in_range_several_steps() {
+    let range = get_range("second:", None);
+    let err = parse_step_in_range(FLOW, range, "flow").unwrap_err();
+
+    assert!(err.message.contains("expecting a single step"));
+    assert_eq!(err.position.interval.start_line, 11);
+    assert_eq!(err.position.interval.start_column, 1);
+}
+
+#[test]
+fn parse_step_in_range_not_a_step() {
+    let flow = "start:\n    goto end\n\nconst NAME = \"csml\"\n";
+    let start = flow.find("const").unwrap();
+
+    let err = parse_step_in_range(flow, start..flow.len(), "flow").unwrap_err();
+
+    assert!(err.message.contains("expecting a single step"));
+    assert_eq!(err.position.interval.start_line, 4);
+}
+
+#[test]
+fn parse_step_in_range_invalid_range() {
+    let err = parse_step_in_range(FLOW, 10..FLOW.len() + 1, "flow").unwrap_err();
+
+    assert!(err.message.contains("not a valid byte range"));
+}
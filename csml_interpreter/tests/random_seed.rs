@@ -0,0 +1,116 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MessageData;
+
+use crate::support::tools::{format_message, message_to_json_value, step_context};
+
+use serde_json::{json, Value};
+
+fn seed_event(seed: u64) -> Event {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.seed = Some(seed);
+
+    event
+}
+
+fn contents(msg: MessageData) -> Vec<Value> {
+    message_to_json_value(msg)["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"].clone())
+        .collect()
+}
+
+#[test]
+fn seeded_random_builtins() {
+    let msg = format_message(
+        seed_event(42),
+        step_context("start", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![
+            json!({"text": "53"}),
+            json!({"text": "55"}),
+            json!({"text": "1.136465099143895"}),
+            json!([4, 5, 2, 1, 3]),
+            json!({"text": "d"}),
+        ]
+    );
+}
+
+#[test]
+fn same_seed_same_values() {
+    let first = format_message(
+        seed_event(7),
+        step_context("start", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+    let second = format_message(
+        seed_event(7),
+        step_context("start", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+
+    assert_eq!(message_to_json_value(first), message_to_json_value(second));
+}
+
+#[test]
+fn random_same_bounds() {
+    let msg = format_message(
+        seed_event(42),
+        step_context("same_bounds", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+
+    assert_eq!(
+        contents(msg),
+        vec![json!({"text": "7"}), json!({"text": "2.5"})]
+    );
+}
+
+#[test]
+fn sample_empty_array() {
+    let msg = format_message(
+        seed_event(42),
+        step_context("empty_sample", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+
+    assert_eq!(contents(msg), vec![json!({ "text": null })]);
+}
+
+#[test]
+fn random_invalid_range() {
+    let msg = format_message(
+        seed_event(42),
+        step_context("invalid_range", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+    let contents = contents(msg);
+
+    assert_eq!(
+        contents[0]["error"],
+        "random builtin expects min to be lower than or equal to max (got 10 and 1) at line 19, column 9 at flow [flow]"
+    );
+    assert_eq!(contents.last().unwrap(), &json!({"text": "after"}));
+}
+
+#[test]
+fn random_invalid_arguments() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("invalid_arguments", None),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+    let errors: Vec<Value> = contents(msg)
+        .into_iter()
+        .filter(|content| content.get("error").is_some())
+        .collect();
+
+    assert_eq!(errors.len(), 3);
+}
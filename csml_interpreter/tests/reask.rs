@@ -0,0 +1,203 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::validate_bot;
+
+use crate::support::tools::{init_bot, message_to_json_value, run_step_with_hold};
+
+use serde_json::Value;
+
+fn text_event(text: &str) -> Event {
+    Event::new("text", text, serde_json::json!({ "text": text }))
+}
+
+fn text_messages(texts: &[&str]) -> Value {
+    let messages: Vec<Value> = texts
+        .iter()
+        .map(|text| serde_json::json!({"content": {"text": text}, "content_type": "text"}))
+        .collect();
+
+    serde_json::json!({"memories": [], "messages": messages})
+}
+
+#[test]
+fn retry_twice_then_fall_through() {
+    let (msg, hold) =
+        run_step_with_hold("CSML/basic_test/reask.csml", "start", None, text_event(""));
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["What is your email?"])
+    );
+    let hold = hold.unwrap();
+    assert_eq!(hold.retries, 0);
+
+    // each retry holds the conversation again on the same hold
+    let (msg, retry) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        Some(hold.clone()),
+        text_event("nope"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["This is not an email"])
+    );
+    let retry = retry.unwrap();
+    assert_eq!(retry.index, hold.index);
+    assert_eq!(retry.retries, 1);
+
+    let (msg, retry) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        Some(retry),
+        text_event("still nope"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["This is not an email"])
+    );
+    assert_eq!(retry.as_ref().unwrap().retries, 2);
+
+    // the retries are used up, the step goes on after the retry
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        retry,
+        text_event("no"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["This is not an email", "Let's go on without your email"])
+    );
+    assert!(hold.is_none());
+}
+
+#[test]
+fn retry_keeps_the_step_variables() {
+    let (_, hold) = run_step_with_hold("CSML/basic_test/reask.csml", "start", None, text_event(""));
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        hold,
+        text_event("nope"),
+    );
+
+    // the variables updated after the hold are kept by the retry
+    let hold = hold.unwrap();
+    assert_eq!(hold.step_vars["attempts"], serde_json::json!(1));
+
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        Some(hold),
+        text_event("nope"),
+    );
+    assert_eq!(hold.unwrap().step_vars["attempts"], serde_json::json!(2));
+}
+
+#[test]
+fn retry_valid_answer() {
+    let (_, hold) = run_step_with_hold("CSML/basic_test/reask.csml", "start", None, text_event(""));
+    let (_, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        hold,
+        text_event("nope"),
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "start",
+        hold,
+        text_event("jo@csml.dev"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["Thanks jo@csml.dev"])
+    );
+    assert!(hold.is_none());
+}
+
+#[test]
+fn retry_in_loop() {
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "retry_in_loop",
+        None,
+        text_event(""),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["What is your name?"])
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "retry_in_loop",
+        hold,
+        text_event("skip"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["Please answer"])
+    );
+    assert_eq!(hold.as_ref().unwrap().index.loop_index, vec![0]);
+
+    // the next hold of the loop starts without retries
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "retry_in_loop",
+        hold,
+        text_event("jo"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["name: jo", "What is your email?"])
+    );
+    let hold = hold.unwrap();
+    assert_eq!(hold.index.loop_index, vec![1]);
+    assert_eq!(hold.retries, 0);
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "retry_in_loop",
+        Some(hold),
+        text_event("skip"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["Please answer"])
+    );
+
+    let (msg, hold) = run_step_with_hold(
+        "CSML/basic_test/reask.csml",
+        "retry_in_loop",
+        hold,
+        text_event("skip"),
+    );
+    assert_eq!(
+        message_to_json_value(msg),
+        text_messages(&["Please answer", "email: skip"])
+    );
+    assert!(hold.is_none());
+}
+
+#[test]
+fn retry_without_hold() {
+    let bot = init_bot("start:\n    say \"Hello\"\n    retry\n    goto end");
+
+    let errors = validate_bot(&bot).errors.unwrap();
+
+    assert!(errors[0]
+        .message
+        .contains("'retry' action is only allowed after a 'hold' of the step"));
+}
+
+#[test]
+fn retry_in_function() {
+    let bot = init_bot("start:\n    hold\n    goto end\n\nfn ask():\n    retry\n    return 1");
+
+    let errors = validate_bot(&bot).errors.unwrap();
+
+    assert!(errors[0].message.contains("'retry' action is only allowed"));
+}
@@ -0,0 +1,101 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MSG;
+use csml_interpreter::{interpret, validate_bot};
+use std::sync::mpsc;
+
+use crate::support::tools::{init_bot, read_file, run_step, step_context};
+
+use serde_json::{json, Value};
+
+// (scope, key, value) of the memories sent by the interpreter
+fn get_remembered(step: &str) -> Vec<(&'static str, String, Value)> {
+    let content = read_file("CSML/basic_test/remember_conversation.csml".to_owned()).unwrap();
+    let event = Event::new("payload", "", json!({}));
+
+    let (sender, receiver) = mpsc::channel();
+    interpret(
+        init_bot(&content),
+        step_context(step, None),
+        event,
+        Some(sender),
+    );
+
+    receiver
+        .try_iter()
+        .filter_map(|msg| match msg {
+            MSG::Remember(memory) => Some(("user", memory.key, memory.value)),
+            MSG::RememberConversation(memory) => Some(("conversation", memory.key, memory.value)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn remember_conversation_is_readable() {
+    let data = r#"{
+        "memories":[
+            {"key":"name", "value":"Ada"}
+        ],
+        "messages":[
+            {"content":{"text": "apple"}, "content_type":"text"},
+            {"content":{"text": "Ada"}, "content_type":"text"}
+        ]}"#;
+    let v1: Value = run_step("CSML/basic_test/remember_conversation.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn remember_conversation_scopes() {
+    assert_eq!(
+        get_remembered("start"),
+        vec![
+            ("conversation", "cart".to_owned(), json!(["apple"])),
+            ("user", "name".to_owned(), json!("Ada")),
+        ]
+    );
+}
+
+#[test]
+fn remember_conversation_update() {
+    // the updates of a memory of the conversation stay in the conversation
+    assert_eq!(
+        get_remembered("update_conversation_memory"),
+        vec![
+            ("conversation", "cart".to_owned(), json!([])),
+            ("conversation", "cart".to_owned(), json!(["pear"])),
+        ]
+    );
+}
+
+#[test]
+fn remember_conversation_user_memory_first() {
+    // remember saves the memory of the user, which is read before the one of the conversation
+    assert_eq!(
+        get_remembered("remember_user_memory"),
+        vec![
+            ("conversation", "cart".to_owned(), json!(["apple"])),
+            ("user", "cart".to_owned(), json!(["pear"])),
+        ]
+    );
+
+    let v1: Value = run_step(
+        "CSML/basic_test/remember_conversation.csml",
+        "remember_user_memory",
+    );
+    assert_eq!(v1["messages"][0]["content"]["text"], "pear");
+}
+
+#[test]
+fn remember_conversation_in_function() {
+    let content = "start:\n    goto end\n\nfn save(value):\n    remember_conversation saved = value\n    return value";
+    let errors = validate_bot(&init_bot(content)).errors.unwrap_or_default();
+
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .message
+        .contains("'remember' action is not allowed in function scope"));
+}
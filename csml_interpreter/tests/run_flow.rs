@@ -0,0 +1,172 @@
+mod support;
+
+use csml_interpreter::data::{Event, RunOptions};
+use csml_interpreter::run_flow;
+
+use crate::support::tools::{format_message, message_to_json_value, read_file, step_context};
+
+use serde_json::Value;
+
+#[test]
+fn run_flow_hold_parity() {
+    let data = r#"
+    {"memories":[],
+    "messages":[
+        {"content":{"error":"< this_hold > is used before it was saved in memory at line 2, column 5 at flow [flow]"}, "content_type":"error"},
+        {"content":{"text":"1"}, "content_type":"text"},
+        {"content":{"text":"2"}, "content_type":"text"},
+        {"content":{"error": "< this_hold > is used before it was saved in memory at line 8, column 6 at flow [flow]"}, "content_type":"error"},
+        {"content":{"text":"4"}, "content_type":"text"}]
+    }
+    "#;
+
+    let source = read_file("CSML/basic_test/hold.csml".to_owned()).unwrap();
+    let v1: Value = run_flow(
+        &source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        RunOptions::default(),
+    )
+    .unwrap();
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2);
+
+    // same output as the test helper
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        "CSML/basic_test/hold.csml",
+    );
+    assert_eq!(v1, message_to_json_value(msg));
+}
+
+#[test]
+fn run_flow_options() {
+    let source = "start:\n    say _env.greeting\n    remember name = \"csml\"\n    goto next\n\n\
+                  next:\n    say \"next\"\n    goto end\n";
+    let options = RunOptions {
+        step_limit: Some(1),
+        env: Some(serde_json::json!({"greeting": "hello"})),
+        ..Default::default()
+    };
+
+    let value = run_flow(
+        source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value["memories"],
+        serde_json::json!([{"key": "name", "value": "csml"}])
+    );
+    assert_eq!(value["messages"][0]["content"]["text"], "hello");
+    // the step limit stops the run before the next step
+    assert!(value["messages"][1]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("stop at step next"));
+    assert_eq!(value["messages"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn run_flow_seed() {
+    let source =
+        "start:\n    say random(1, 1000)\n    say shuffle([1, 2, 3, 4, 5])\n    goto end\n";
+    let run = |seed| {
+        let options = RunOptions {
+            seed: Some(seed),
+            ..Default::default()
+        };
+
+        run_flow(
+            source,
+            Event::new("payload", "", serde_json::json!({})),
+            step_context("start", None),
+            options,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(run(42), run(42));
+    assert_ne!(run(42), run(43));
+}
+
+#[test]
+fn run_flow_entry_step() {
+    let source = "welcome:\n    say \"welcome\"\n    goto next\n\n\
+                  next:\n    say \"next\"\n    goto end\n";
+    let options = RunOptions {
+        entry_step: Some("welcome".to_owned()),
+        ..Default::default()
+    };
+
+    let value = run_flow(
+        source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        options.clone(),
+    )
+    .unwrap();
+    assert_eq!(value["messages"][0]["content"]["text"], "welcome");
+    assert_eq!(value["messages"][1]["content"]["text"], "next");
+
+    // a context targeting another step keeps it
+    let value = run_flow(
+        source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("next", None),
+        options,
+    )
+    .unwrap();
+    assert_eq!(value["messages"][0]["content"]["text"], "next");
+}
+
+#[test]
+fn run_flow_missing_entry_step() {
+    let source = "start:\n    say \"start\"\n    goto end\n";
+    let options = RunOptions {
+        entry_step: Some("welcome".to_owned()),
+        ..Default::default()
+    };
+
+    let err = run_flow(
+        source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        options,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        err.message,
+        "[welcome] entry step of the run options does not exist in the flow"
+    );
+    assert_eq!(err.position.flow, "flow");
+}
+
+#[test]
+fn run_flow_now() {
+    let source = "start:\n    say Time().format()\n    goto end\n";
+    let now = chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+    let options = RunOptions {
+        now: Some(now.with_timezone(&chrono::Utc)),
+        ..Default::default()
+    };
+
+    let value = run_flow(
+        source,
+        Event::new("payload", "", serde_json::json!({})),
+        step_context("start", None),
+        options,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value["messages"][0]["content"]["text"],
+        "2024-01-02T03:04:05.678Z"
+    );
+}
@@ -0,0 +1,54 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn spread_array() {
+    let data = r#"{"messages":[ {"content":[1, 2, 3, 4],"content_type":"array"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/spread.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn spread_object_override() {
+    let data = r#"{"messages":[
+        {"content":{"name": "csml", "lang": "en", "version": 1},"content_type":"object"},
+        {"content":{"name": "csml", "lang": "fr", "version": 1},"content_type":"object"},
+        {"content":{"name": "csml", "lang": "fr", "version": 2},"content_type":"object"}
+    ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/spread.csml", "object_override");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // later keys override earlier ones
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn spread_array_not_iterable() {
+    let v1: Value = run_step("CSML/basic_test/spread.csml", "array_not_iterable");
+
+    assert_eq!(v1["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("only accepts arrays in array literals, got int at line 16, column 13"));
+}
+
+#[test]
+fn spread_object_not_object() {
+    let v1: Value = run_step("CSML/basic_test/spread.csml", "object_not_object");
+
+    assert_eq!(v1["messages"].as_array().unwrap().len(), 1);
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(v1["messages"][0]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("only accepts objects in object literals, got array at line 21, column 26"));
+}
@@ -558,3 +558,119 @@ fn string_step_18_html_escape() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn string_step_19_trim_case() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content": {"text": "héllo wörld"}},
+            {"content_type":"text", "content": {"text": "STRASSE ÜNÏ"}},
+            {"content_type":"text", "content": {"text": "àéî σ"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_19_trim_case",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_20_split() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"array", "content": ["a", "b", "", "c"]},
+            {"content_type":"array", "content": ["h", "é", "l", "l", "o"]}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_20_split",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_21_replace() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content": {"text": "hello csml"}},
+            {"content_type":"text", "content": {"text": "hello"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_21_replace",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_22_not_a_string() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {
+                "content_type":"error",
+                "content": {"error": "[trim] is not a method of Int at line 144, column 15 at flow [flow]"}
+            },
+            {"content_type":"text", "content": {"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_22_not_a_string",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
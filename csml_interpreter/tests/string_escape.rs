@@ -0,0 +1,27 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::{json, Value};
+
+fn said_text(step: &str) -> Value {
+    run_step("CSML/basic_test/string_escape.csml", step)["messages"][0]["content"]["text"].clone()
+}
+
+#[test]
+fn string_escape_say() {
+    assert_eq!(
+        said_text("start"),
+        json!("line\n\tindented \"quoted\" c:\\new")
+    );
+}
+
+#[test]
+fn string_escape_unicode() {
+    assert_eq!(said_text("unicode"), json!("café 😀"));
+}
+
+#[test]
+fn string_escape_interpolation() {
+    assert_eq!(said_text("interpolation"), json!("{{ name }} is Jo!"));
+}
@@ -0,0 +1,89 @@
+mod support;
+
+use csml_interpreter::data::ast::Flow;
+use csml_interpreter::error_format::ErrorInfo;
+use csml_interpreter::parser::parse_flow;
+
+use crate::support::tools::{read_file, run_step};
+
+use serde_json::Value;
+
+fn parse_file(filepath: &str) -> Result<Flow, ErrorInfo> {
+    let text = read_file(filepath.to_owned()).unwrap();
+
+    parse_flow(&text, "Test")
+}
+
+#[test]
+fn string_interpolation_member() {
+    let data = r#"{"messages":[ {"content":{ "text": "Hello Ada!" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/string_interpolation.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_interpolation_nested_member() {
+    let data = r#"{"messages":[ {"content":{ "text": "Ada lives in London" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/string_interpolation.csml", "nested_member");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_interpolation_escaped_braces() {
+    let data = r#"{"messages":[ {"content":{ "text": "{{user.first_name}} is Ada" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step(
+        "CSML/basic_test/string_interpolation.csml",
+        "escaped_braces",
+    );
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_interpolation_error_position() {
+    let v1: Value = run_step("CSML/basic_test/string_interpolation.csml", "type_error");
+    let error = v1["messages"][0]["content"]["error"].as_str().unwrap();
+
+    assert_eq!(v1["messages"][0]["content_type"], "error");
+    assert!(error.contains("at line 18, column 27"));
+}
+
+#[test]
+fn string_interpolation_syntax() {
+    assert!(
+        parse_file("CSML/basic_test/syntax/string_interpolation/string_interpolation_0.csml")
+            .is_ok()
+    );
+}
+
+#[test]
+fn string_interpolation_unterminated() {
+    let err = parse_file("CSML/basic_test/syntax/string_interpolation/string_interpolation_1.csml")
+        .unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 3);
+    assert_eq!(err.position.interval.start_column, 16);
+    assert!(err
+        .message
+        .contains("expecting '}}' to end expandable string"));
+}
+
+#[test]
+fn string_interpolation_unopened() {
+    let err = parse_file("CSML/basic_test/syntax/string_interpolation/string_interpolation_2.csml")
+        .unwrap_err();
+
+    assert_eq!(err.position.interval.start_line, 2);
+    assert_eq!(err.position.interval.start_column, 31);
+    assert!(err
+        .message
+        .contains("expecting '{{' to begin expandable string"));
+}
@@ -2,12 +2,14 @@ use csml_interpreter::data::csml_bot::CsmlBot;
 use csml_interpreter::data::csml_flow::CsmlFlow;
 use csml_interpreter::data::event::Event;
 use csml_interpreter::data::message_data::MessageData;
-use csml_interpreter::data::Context;
+use csml_interpreter::data::{Context, Hold, MSG};
 use csml_interpreter::{interpret, load_components};
-use serde_json::{json, map::Map, Value};
+use serde_json::{json, Value};
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::prelude::*;
+use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 /// PUBLIC FUNCTIONS
@@ -26,48 +28,93 @@ pub fn read_file(file_path: String) -> Result<String, ::std::io::Error> {
 pub fn format_message(event: Event, context: Context, filepath: &str) -> MessageData {
     let content = read_file(filepath.to_string()).unwrap();
 
-    let flow = CsmlFlow::new("id", "flow", &content, Vec::default());
-    let native_component = load_components().unwrap();
+    interpret(init_bot(&content), context, event, None)
+}
+
+/// Context of `step` in the flow named `flow`
+#[allow(dead_code)]
+pub fn step_context(step: &str, hold: Option<Hold>) -> Context {
+    Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        hold,
+        step,
+        "flow",
+        None,
+    )
+}
+
+/// Runs `step` of the flow file with an empty payload event
+#[allow(dead_code)]
+pub fn run_step(filepath: &str, step: &str) -> Value {
+    let msg = format_message(
+        Event::new("payload", "", json!({})),
+        step_context(step, None),
+        filepath,
+    );
+
+    message_to_json_value(msg)
+}
+
+/// Runs `step` of the flow file after `hold`, returns the hold saved by the step
+#[allow(dead_code)]
+pub fn run_step_with_hold(
+    filepath: &str,
+    step: &str,
+    hold: Option<Hold>,
+    event: Event,
+) -> (MessageData, Option<Hold>) {
+    let content = read_file(filepath.to_string()).unwrap();
 
-    let bot = CsmlBot::new(
+    interpret_with_hold(init_bot(&content), step_context(step, hold), event)
+}
+
+#[allow(dead_code)]
+pub fn interpret_with_hold(
+    bot: CsmlBot,
+    context: Context,
+    event: Event,
+) -> (MessageData, Option<Hold>) {
+    let (sender, receiver) = mpsc::channel();
+    let msg_data = interpret(bot, context, event, Some(sender));
+
+    let hold = receiver.try_iter().find_map(|msg| match msg {
+        MSG::Hold(value) => Some(value),
+        _ => None,
+    });
+
+    (msg_data, hold)
+}
+
+/// Bot with the native components and a single flow named `flow`
+#[allow(dead_code)]
+pub fn init_bot(content: &str) -> CsmlBot {
+    init_bot_with_flows(vec![CsmlFlow::new("id", "flow", content, Vec::default())])
+}
+
+/// Bot with the native components, the first flow is the default flow
+#[allow(dead_code)]
+pub fn init_bot_with_flows(flows: Vec<CsmlFlow>) -> CsmlBot {
+    let default_flow = flows[0].name.to_owned();
+
+    CsmlBot::new(
         "id",
         "bot",
         None,
-        vec![flow],
-        Some(native_component),
+        flows,
+        Some(load_components().unwrap()),
         None,
-        "flow",
+        &default_flow,
         None,
         None,
         None,
         None,
         None,
-    );
-
-    interpret(bot, context, event, None)
+    )
 }
 
 #[allow(dead_code)]
 pub fn message_to_json_value(result: MessageData) -> Value {
-    let mut message: Map<String, Value> = Map::new();
-    let mut vec = vec![];
-    let mut memories = vec![];
-
-    for msg in result.messages.iter() {
-        vec.push(msg.to_owned().message_to_json());
-    }
-
-    if let Some(mem) = result.memories {
-        for elem in mem.iter() {
-            let mut map = Map::new();
-            map.insert("key".to_owned(), json!(elem.key.to_owned()));
-            map.insert("value".to_owned(), elem.value.to_owned());
-            memories.push(json!(map));
-        }
-    }
-
-    message.insert("memories".to_owned(), Value::Array(memories));
-    message.insert("messages".to_owned(), Value::Array(vec));
-
-    Value::Object(message)
+    result.to_json_value()
 }
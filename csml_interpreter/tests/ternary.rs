@@ -0,0 +1,58 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+#[test]
+fn ternary_precedence() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"items"}, "content_type":"text"},
+        {"content":{"text":"6"}, "content_type":"text"},
+        {"content":{"text":"2"}, "content_type":"text"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/ternary.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ternary_nested() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"medium"}, "content_type":"text"},
+        {"content":{"text":"low"}, "content_type":"text"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/ternary.csml", "nested");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ternary_member_access() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":{"text":"guest"}, "content_type":"text"},
+        {"content":{"text":"dsl"}, "content_type":"text"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/ternary.csml", "member_access");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ternary_untaken_branch() {
+    let data = r#"{"memories":[], "messages":[
+        {"content":["then", "else"], "content_type":"array"}
+    ]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/ternary.csml", "untaken_branch");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    // the branch that is not taken is never evaluated
+    assert_eq!(v1, v2)
+}
@@ -151,3 +151,35 @@ fn ok_with_timezone() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn ok_time_context_date() {
+    let data = r#"
+        {"messages":[
+            {"content":{"text": "2024-01-02T03:04:05.678Z"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let mut context = Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        "context_date",
+        "flow",
+        None,
+    );
+    let now = chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05.678Z").unwrap();
+    context.now = Some(now.with_timezone(&chrono::Utc));
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        context,
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -0,0 +1,126 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::support::tools::{format_message, step_context};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Metadata, Subscriber};
+
+type Fields = HashMap<String, String>;
+
+struct FieldVisitor<'a>(&'a mut Fields);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+}
+
+// keeps the name and fields of every span and event
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<(String, Fields)>>>,
+    events: Arc<Mutex<Vec<Fields>>>,
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut fields = Fields::new();
+        span.record(&mut FieldVisitor(&mut fields));
+
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((span.metadata().name().to_owned(), fields));
+
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+
+        values.record(&mut FieldVisitor(fields));
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut fields = Fields::new();
+        event.record(&mut FieldVisitor(&mut fields));
+
+        self.events.lock().unwrap().push(fields);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn trace_spans_steps_and_gotos() {
+    let recorder = Recorder::default();
+    let event = Event::new("payload", "secret payload", serde_json::json!({}));
+    tracing::subscriber::with_default(recorder.clone(), || {
+        format_message(
+            event,
+            step_context("start", None),
+            "CSML/basic_test/trace_spans.csml",
+        )
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    let names: Vec<&str> = spans.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["csml_interpret", "csml_step", "csml_step"]);
+
+    assert_eq!(spans[0].1["bot_id"], "id");
+    assert_eq!(spans[0].1["flow"], "flow");
+    assert_eq!(spans[1].1["step"], "start");
+    assert_eq!(spans[2].1["step"], "next");
+
+    let events = recorder.events.lock().unwrap();
+    let gotos: Vec<&Fields> = events
+        .iter()
+        .filter(|fields| fields.get("message").map(String::as_str) == Some("goto"))
+        .collect();
+    assert_eq!(gotos.len(), 1);
+    assert_eq!(gotos[0]["flow"], "flow");
+    assert_eq!(gotos[0]["step"], "next");
+}
+
+#[test]
+fn trace_spans_never_record_the_event() {
+    let recorder = Recorder::default();
+    let event = Event::new("payload", "secret payload", serde_json::json!({}));
+    tracing::subscriber::with_default(recorder.clone(), || {
+        format_message(
+            event,
+            step_context("start", None),
+            "CSML/basic_test/trace_spans.csml",
+        )
+    });
+
+    let spans = recorder.spans.lock().unwrap();
+    let events = recorder.events.lock().unwrap();
+    let values = spans
+        .iter()
+        .map(|(_, fields)| fields)
+        .chain(events.iter())
+        .flat_map(|fields| fields.values());
+
+    for value in values {
+        assert!(!value.contains("secret payload"));
+    }
+}
@@ -0,0 +1,120 @@
+mod support;
+
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{Hold, MSG};
+use csml_interpreter::interpret;
+use std::sync::mpsc;
+
+use crate::support::tools::{init_bot, message_to_json_value, read_file, run_step, step_context};
+
+use serde_json::Value;
+
+#[test]
+fn try_without_error() {
+    let data = r#"{"messages":[ {"content":{ "text": "no error" },"content_type":"text"} ],"memories":[]}"#;
+
+    let v1: Value = run_step("CSML/basic_test/try_catch.csml", "start");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn try_catch_error() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "before" },"content_type":"text"},
+                {"content":{ "text": "< missing > is used before it was saved in memory" },"content_type":"text"},
+                {"content":{ "text": "12" },"content_type":"text"},
+                {"content":{ "text": "13" },"content_type":"text"},
+                {"content":{ "text": "done" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/try_catch.csml", "try_catch");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn try_catch_nested() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "inner 25" },"content_type":"text"},
+                {"content":{ "text": "between" },"content_type":"text"},
+                {"content":{ "text": "outer 30" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/try_catch.csml", "try_nested");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn try_catch_rethrow() {
+    let data = r#"
+        {
+            "messages":[
+                {"content":{ "text": "inner" },"content_type":"text"},
+                {"content":{ "text": "outer 42" },"content_type":"text"}
+            ],
+            "memories":[]
+        }"#;
+
+    let v1: Value = run_step("CSML/basic_test/try_catch.csml", "try_rethrow");
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn try_catch_error_in_catch() {
+    let v1: Value = run_step("CSML/basic_test/try_catch.csml", "catch_error");
+    let messages = v1["messages"].as_array().unwrap();
+
+    // without an enclosing try the error is sent as usual and the catch block goes on
+    assert_eq!(messages[0]["content_type"], "error");
+    assert_eq!(messages.last().unwrap()["content"]["text"], "still running");
+}
+
+#[test]
+fn try_catch_hold() {
+    let run = |hold: Option<Hold>| {
+        let content = read_file("CSML/basic_test/try_catch.csml".to_owned()).unwrap();
+        let bot = init_bot(&content);
+        let context = step_context("try_hold", hold);
+
+        let (sender, receiver) = mpsc::channel();
+        let msg_data = interpret(
+            bot,
+            context,
+            Event::new("payload", "", serde_json::json!({})),
+            Some(sender),
+        );
+
+        let mut hold = None;
+        for msg in receiver.try_iter() {
+            match msg {
+                MSG::Hold(value) => hold = Some(value),
+                MSG::Message(message) => assert_ne!(message.content_type, "error"),
+                _ => {}
+            }
+        }
+
+        (message_to_json_value(msg_data), hold)
+    };
+
+    let (v1, hold) = run(None);
+    assert_eq!(v1["messages"], serde_json::json!([]));
+
+    // the caught error is not sent, the error binding is saved with the hold and still available when resuming
+    let (v1, _) = run(hold);
+    assert_eq!(v1["messages"][0]["content"]["text"], "resumed 61");
+}
@@ -0,0 +1,51 @@
+mod support;
+
+use crate::support::tools::run_step;
+
+use serde_json::Value;
+
+fn texts(value: &Value) -> Vec<String> {
+    value["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["content"]["text"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn typeof_values() {
+    let value = run_step("CSML/basic_test/built-in/typeof.csml", "typeof_values");
+
+    assert_eq!(
+        texts(&value),
+        vec!["string", "number", "number", "boolean", "array", "object", "null", "object"]
+    );
+}
+
+#[test]
+fn type_predicates() {
+    let value = run_step("CSML/basic_test/built-in/typeof.csml", "type_predicates");
+
+    assert_eq!(
+        texts(&value),
+        vec![
+            "true", "true", "false", "true", "false", "true", "true", "false", "true", "true",
+            "false"
+        ]
+    );
+}
+
+#[test]
+fn typeof_if() {
+    let value = run_step("CSML/basic_test/built-in/typeof.csml", "typeof_if");
+
+    assert_eq!(texts(&value), vec!["array of 2"]);
+}
+
+#[test]
+fn typeof_match() {
+    let value = run_step("CSML/basic_test/built-in/typeof.csml", "typeof_match");
+
+    assert_eq!(texts(&value), vec!["string", "number", "other", "other"]);
+}
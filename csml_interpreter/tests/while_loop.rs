@@ -41,3 +41,25 @@ fn ok_while_loop() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn while_loop_limit() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "infinite_while",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/while_loops.csml",
+    );
+
+    let v: Value = message_to_json_value(msg);
+
+    assert_eq!(v["messages"].as_array().unwrap().len(), 1);
+    assert_eq!("error", v["messages"][0]["content_type"])
+}
@@ -1,5 +1,5 @@
 use actix_web::{post, web, HttpResponse};
-use csml_engine::{validate_bot, CsmlResult};
+use csml_engine::{validate_bot, CsmlResult, ErrorCode};
 use csml_interpreter::data::csml_bot::CsmlBot;
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +26,7 @@ struct ValidationError {
   end_line: Option<u32>,
   end_column: Option<u32>,
   message: String,
+  code: ErrorCode,
 }
 
 #[post("/validate")]
@@ -56,6 +57,7 @@ pub async fn handler(body: web::Json<CsmlBot>) -> HttpResponse {
           end_line: error_info.position.interval.end_line,
           end_column: error_info.position.interval.end_column,
           message: error_info.message.clone(),
+          code: error_info.code,
         })
       }
       ValidateBotResponse {